@@ -0,0 +1,104 @@
+//! A2A delivery into agents' message histories
+//!
+//! Messages sent through the API go over an [`A2aBus`] with at-least-once
+//! delivery. Each available agent is registered on the bus; whatever reaches
+//! its channel is handed to the caller's store and then acked. Messages for
+//! agents that are unavailable stay queued on the bus until a later sweep
+//! finds them registered again, and messages for unknown agents are
+//! dead-lettered, where `GET /api/protocols/a2a/dead-letters` lists them.
+
+use agentic_core::{AgentId, Result};
+use agentic_protocols::{A2aBus, A2aMessage};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// The bus plus the receiving end of every registered agent's channel
+#[derive(Clone)]
+pub struct A2aInboxes {
+    bus: Arc<A2aBus>,
+    receivers: Arc<Mutex<HashMap<AgentId, mpsc::UnboundedReceiver<A2aMessage>>>>,
+}
+
+impl A2aInboxes {
+    pub fn new(bus: Arc<A2aBus>) -> Self {
+        Self { bus, receivers: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    pub fn bus(&self) -> &Arc<A2aBus> {
+        &self.bus
+    }
+
+    /// Register `agent_id` on the bus while it is available, so sends reach
+    /// it at once, and unregister it otherwise, so they wait in the queue
+    pub async fn set_available(&self, agent_id: &AgentId, available: bool) {
+        let registered = self.receivers.lock().unwrap().contains_key(agent_id);
+        if available && !registered {
+            let rx = self.bus.register_agent(*agent_id).await;
+            self.receivers.lock().unwrap().insert(*agent_id, rx);
+        } else if !available && registered {
+            self.receivers.lock().unwrap().remove(agent_id);
+            self.bus.unregister_agent(agent_id).await;
+        }
+    }
+
+    /// Run a delivery sweep, then pass every message waiting for `agent_id`
+    /// to `store` and ack it; returns how many were stored
+    ///
+    /// Messages whose ack timed out are delivered again, so `store` may see
+    /// a message twice.
+    pub async fn deliver(&self, agent_id: &AgentId, mut store: impl FnMut(&A2aMessage)) -> Result<usize> {
+        self.bus.redeliver_unacked().await?;
+
+        let received: Vec<A2aMessage> = {
+            let mut receivers = self.receivers.lock().unwrap();
+            let Some(rx) = receivers.get_mut(agent_id) else {
+                return Ok(0);
+            };
+            std::iter::from_fn(|| rx.try_recv().ok()).collect()
+        };
+
+        for message in &received {
+            store(message);
+            // Already acked when it arrives twice
+            let _ = self.bus.ack(&message.envelope.message_id).await;
+        }
+        Ok(received.len())
+    }
+}
+
+impl Default for A2aInboxes {
+    fn default() -> Self {
+        Self::new(Arc::new(A2aBus::new()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentic_protocols::A2aMessageBuilder;
+
+    fn message(to: AgentId) -> A2aMessage {
+        A2aMessageBuilder::new(AgentId::generate(), "planner".to_string())
+            .to(to, "worker".to_string())
+            .build_task_assignment("start".to_string(), serde_json::json!({}))
+    }
+
+    #[tokio::test]
+    async fn test_message_for_unavailable_agent_waits_until_it_is_back() {
+        let inboxes = A2aInboxes::default();
+        let agent = AgentId::generate();
+        let mut stored = Vec::new();
+
+        inboxes.set_available(&agent, false).await;
+        inboxes.bus().send_reliable(message(agent)).await.unwrap();
+        assert_eq!(inboxes.deliver(&agent, |m| stored.push(m.clone())).await.unwrap(), 0);
+        assert_eq!(inboxes.bus().pending_count().await, 1);
+
+        inboxes.set_available(&agent, true).await;
+        assert_eq!(inboxes.deliver(&agent, |m| stored.push(m.clone())).await.unwrap(), 1);
+        assert_eq!(stored.len(), 1);
+        assert_eq!(inboxes.bus().pending_count().await, 0);
+        assert_eq!(inboxes.bus().unacked_count().await, 0);
+    }
+}
@@ -1,41 +1,139 @@
 //! Business API endpoints - Opportunity discovery, validation, and revenue generation
 
-use crate::{DashboardState, DashboardEvent};
+use crate::{error_response, DashboardState, DashboardEvent};
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
     Json,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tracing::{info, error};
+use tracing::{info, error, warn};
 
 use agentic_business::{
-    opportunity::OpportunityDiscoveryManager,
-    models::{Opportunity, UserPreferences, OpportunityId},
+    opportunity::{DiscoverySession, DiscoverySessionStore, OpportunityDiscoveryManager},
+    validation::{BusinessValidationManager, ComprehensiveValidationReport, RecommendationThresholds},
+    revenue::RevenueGenerationManager,
+    models::{Opportunity, UserPreferences, OpportunityId, ScoreExplanation, DEFAULT_OPPORTUNITY_TTL_DAYS},
+    pipeline::{run_pipeline_with, PipelineReport},
 };
-use agentic_runtime::llm::LlmClient;
+use agentic_core::AgentId;
+use agentic_meta::{DecisionLog, DecisionRecord, MetaMetricsRegistry};
+use agentic_runtime::llm::{LlmClient, SwappableLlmClient};
 
 /// Shared state for business operations
 pub struct BusinessState {
+    /// Client of the business agents; follows [`BusinessState::set_llm_client`]
     pub llm_client: Arc<dyn LlmClient>,
+    swappable_client: Arc<SwappableLlmClient>,
     pub discovery_manager: Arc<Mutex<OpportunityDiscoveryManager>>,
     pub discovered_opportunities: Arc<Mutex<Vec<Opportunity>>>,
+    /// Discovery sessions, shared with the discovery manager
+    pub discovery_sessions: DiscoverySessionStore,
+    /// Score cut-offs for validation recommendations and score bands
+    pub validation_thresholds: RecommendationThresholds,
+    /// Ids the per-request validation manager and monetization agent record
+    /// decisions under, so decisions from separate requests share them
+    pub validation_agent_id: AgentId,
+    pub monetization_agent_id: AgentId,
+    /// Latest validation report per opportunity
+    pub validation_reports: Arc<Mutex<HashMap<OpportunityId, ComprehensiveValidationReport>>>,
+    /// Completed pipeline runs by run id
+    pub pipeline_runs: Arc<Mutex<HashMap<uuid::Uuid, PipelineReport>>>,
     pub dashboard_state: DashboardState,
+    /// Meta-agent metrics, shared with `AppState`
+    pub meta_metrics: MetaMetricsRegistry,
+    /// Audit trail of validation and monetization decisions
+    pub decision_log: DecisionLog,
+    /// Age after which listed opportunities are flagged stale (`OPPORTUNITY_TTL_DAYS`)
+    pub opportunity_ttl: chrono::Duration,
 }
 
 impl BusinessState {
     pub fn new(llm_client: Arc<dyn LlmClient>, dashboard_state: DashboardState) -> Self {
-        let discovery_manager = OpportunityDiscoveryManager::new(llm_client.clone());
+        let swappable_client = Arc::new(SwappableLlmClient::new(llm_client));
+        let llm_client: Arc<dyn LlmClient> = swappable_client.clone();
+        let discovery_sessions = discovery_sessions_from_env();
+        let discovery_manager =
+            OpportunityDiscoveryManager::new(llm_client.clone()).with_session_store(discovery_sessions.clone());
 
         Self {
             llm_client,
+            swappable_client,
             discovery_manager: Arc::new(Mutex::new(discovery_manager)),
             discovered_opportunities: Arc::new(Mutex::new(Vec::new())),
+            discovery_sessions,
+            validation_thresholds: RecommendationThresholds::default(),
+            validation_agent_id: AgentId::generate(),
+            monetization_agent_id: AgentId::generate(),
+            validation_reports: Arc::new(Mutex::new(HashMap::new())),
+            pipeline_runs: Arc::new(Mutex::new(HashMap::new())),
             dashboard_state,
+            meta_metrics: MetaMetricsRegistry::new(),
+            decision_log: decision_log_from_env(),
+            opportunity_ttl: opportunity_ttl_from_env(),
         }
     }
+
+    /// Send LLM calls of the business agents, including the discovery
+    /// manager's, to `client` from now on
+    pub fn set_llm_client(&self, client: Arc<dyn LlmClient>) {
+        self.swappable_client.set(client);
+    }
+
+    /// Record meta-agent runs into an existing registry
+    pub fn with_meta_metrics(mut self, registry: MetaMetricsRegistry) -> Self {
+        self.meta_metrics = registry;
+        self
+    }
+
+    /// Validation manager for one request, recording under the shared id
+    pub fn validation_manager(&self) -> agentic_core::Result<BusinessValidationManager> {
+        BusinessValidationManager::for_agent(self.llm_client.clone(), self.validation_agent_id)
+            .with_metrics_registry(self.meta_metrics.clone())
+            .with_decision_log(self.decision_log.clone())
+            .with_thresholds(self.validation_thresholds)
+    }
+
+    /// Revenue manager for one pipeline run, recording under the shared id
+    pub fn revenue_manager(&self) -> RevenueGenerationManager {
+        RevenueGenerationManager::new(self.llm_client.clone())
+            .with_decision_log(self.decision_log.clone())
+            .with_monetization_agent_id(self.monetization_agent_id)
+    }
+}
+
+fn opportunity_ttl_from_env() -> chrono::Duration {
+    let days = std::env::var("OPPORTUNITY_TTL_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_OPPORTUNITY_TTL_DAYS);
+    chrono::Duration::days(days)
+}
+
+/// Decision log persisted to `DECISION_LOG_PATH`, or in-memory when unset
+fn decision_log_from_env() -> DecisionLog {
+    match std::env::var("DECISION_LOG_PATH") {
+        Ok(path) => DecisionLog::open(&path).unwrap_or_else(|e| {
+            error!("Failed to open decision log {}: {}; keeping decisions in memory", path, e);
+            DecisionLog::new()
+        }),
+        Err(_) => DecisionLog::new(),
+    }
+}
+
+/// Discovery sessions saved under `DISCOVERY_SESSION_DIR`, or in-memory when unset
+fn discovery_sessions_from_env() -> DiscoverySessionStore {
+    match std::env::var("DISCOVERY_SESSION_DIR") {
+        Ok(dir) => DiscoverySessionStore::open(&dir).unwrap_or_else(|e| {
+            error!("Failed to open discovery session dir {}: {}; keeping sessions in memory", dir, e);
+            DiscoverySessionStore::new()
+        }),
+        Err(_) => DiscoverySessionStore::new(),
+    }
 }
 
 // ============================================================================
@@ -52,6 +150,8 @@ pub struct DiscoverOpportunitiesResponse {
     pub opportunities: Vec<Opportunity>,
     pub count: usize,
     pub workflow_id: String,
+    /// Discovery session of this run, see `GET /api/discovery/sessions/:id`
+    pub session_id: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -63,6 +163,15 @@ pub struct OpportunityDetailsResponse {
 pub struct OpportunityListResponse {
     pub opportunities: Vec<Opportunity>,
     pub total: usize,
+    /// Listed opportunities older than the configured TTL
+    #[serde(default)]
+    pub stale: Vec<OpportunityId>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct OpportunityListQuery {
+    /// Leave out opportunities discovered (or refreshed) more than this many days ago
+    pub max_age_days: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -77,6 +186,22 @@ pub struct StartDevelopmentResponse {
     pub message: String,
 }
 
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RunPipelineRequest {
+    /// Budget handed to the revenue stage's marketing campaigns (USD)
+    #[serde(default)]
+    pub marketing_budget: Option<f64>,
+}
+
+/// Marketing budget used when a pipeline run doesn't specify one
+const DEFAULT_MARKETING_BUDGET: f64 = 1000.0;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DecisionQuery {
+    /// Only decisions made by this agent id
+    pub agent: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BusinessMetricsResponse {
     pub total_opportunities_discovered: usize,
@@ -99,8 +224,12 @@ pub async fn api_discover_opportunities(
 
     let mut manager = state.discovery_manager.lock().await;
 
-    match manager.discover(req.preferences).await {
-        Ok(opportunities) => {
+    let result = match manager.create_session(req.preferences) {
+        Ok(session_id) => manager.resume_session(session_id).await.map(|found| (session_id, found)),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok((session_id, opportunities)) => {
             let count = opportunities.len();
             let workflow_id = manager.workflow_id().to_string();
 
@@ -129,8 +258,10 @@ pub async fn api_discover_opportunities(
                 opportunities,
                 count,
                 workflow_id,
+                session_id: session_id.to_string(),
             }))
         }
+        Err(agentic_core::Error::InvalidArgument(msg)) => Err((StatusCode::BAD_REQUEST, msg)),
         Err(e) => {
             error!("Failed to discover opportunities: {}", e);
             Err((
@@ -141,19 +272,75 @@ pub async fn api_discover_opportunities(
     }
 }
 
-/// GET /api/business/opportunities
-/// List all discovered opportunities
+/// GET /api/business/opportunities?max_age_days=
+/// List discovered opportunities, flagging those older than the TTL
 pub async fn api_list_opportunities(
     State(state): State<Arc<BusinessState>>,
+    Query(query): Query<OpportunityListQuery>,
 ) -> Json<OpportunityListResponse> {
-    let opportunities = state.discovered_opportunities.lock().await;
+    let now = chrono::Utc::now();
+    let opportunities: Vec<Opportunity> = state
+        .discovered_opportunities
+        .lock()
+        .await
+        .iter()
+        .filter(|opp| {
+            query
+                .max_age_days
+                .is_none_or(|days| !opp.is_stale_at(chrono::Duration::days(days), now))
+        })
+        .cloned()
+        .collect();
+    let stale = opportunities
+        .iter()
+        .filter(|opp| opp.is_stale_at(state.opportunity_ttl, now))
+        .map(|opp| opp.id)
+        .collect();
 
     Json(OpportunityListResponse {
         total: opportunities.len(),
-        opportunities: opportunities.clone(),
+        opportunities,
+        stale,
     })
 }
 
+/// POST /api/business/opportunities/:id/refresh
+/// Re-run enrichment and scoring, making the opportunity fresh again if enrichment succeeds
+pub async fn api_refresh_opportunity(
+    State(state): State<Arc<BusinessState>>,
+    Path(id): Path<String>,
+) -> Result<Json<OpportunityDetailsResponse>, (StatusCode, String)> {
+    let opportunity_id = id.parse::<OpportunityId>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid opportunity ID".to_string()))?;
+
+    let mut opportunity = state.discovered_opportunities.lock().await
+        .iter()
+        .find(|opp| opp.id == opportunity_id)
+        .cloned()
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Opportunity not found".to_string()))?;
+
+    let enriched = state.discovery_manager.lock().await
+        .refresh(&mut opportunity)
+        .await
+        .map_err(|e| {
+            error!("Failed to refresh opportunity {}: {}", opportunity_id, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Refresh failed: {}", e))
+        })?;
+    if !enriched {
+        warn!("Enrichment of opportunity {} failed; it stays stale", opportunity_id);
+    }
+
+    // Write back unless the opportunity was deleted meanwhile
+    let mut opportunities = state.discovered_opportunities.lock().await;
+    let stored = opportunities
+        .iter_mut()
+        .find(|opp| opp.id == opportunity_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Opportunity not found".to_string()))?;
+    *stored = opportunity.clone();
+
+    Ok(Json(OpportunityDetailsResponse { opportunity }))
+}
+
 /// GET /api/business/opportunities/:id
 /// Get details for a specific opportunity
 pub async fn api_get_opportunity(
@@ -177,6 +364,24 @@ pub async fn api_get_opportunity(
     }))
 }
 
+/// GET /api/business/opportunities/:id/score
+/// Explain an opportunity's score: per-dimension value, weight and contribution
+pub async fn api_get_opportunity_score(
+    State(state): State<Arc<BusinessState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ScoreExplanation>, (StatusCode, String)> {
+    let opportunity_id = id.parse::<OpportunityId>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid opportunity ID".to_string()))?;
+
+    let opportunities = state.discovered_opportunities.lock().await;
+    let opportunity = opportunities
+        .iter()
+        .find(|opp| opp.id == opportunity_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Opportunity not found".to_string()))?;
+
+    Ok(Json(opportunity.scores.explain_with_thresholds(&state.validation_thresholds)))
+}
+
 /// POST /api/business/opportunities/:id/develop
 /// Start development workflow for an opportunity
 pub async fn api_start_development(
@@ -205,6 +410,135 @@ pub async fn api_start_development(
     }))
 }
 
+/// POST /api/business/opportunities/:id/validate
+/// Run the full business validation for a discovered opportunity, reusing
+/// the latest report while the opportunity hasn't been refreshed since
+pub async fn api_validate_opportunity(
+    State(state): State<Arc<BusinessState>>,
+    Path(id): Path<String>,
+) -> Result<Json<ComprehensiveValidationReport>, (StatusCode, String)> {
+    let opportunity_id = id.parse::<OpportunityId>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid opportunity ID".to_string()))?;
+
+    let opportunity = state.discovered_opportunities.lock().await
+        .iter()
+        .find(|opp| opp.id == opportunity_id)
+        .cloned()
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Opportunity not found".to_string()))?;
+
+    let mut manager = state.validation_manager().map_err(error_response)?;
+    let previous = state.validation_reports.lock().await.get(&opportunity_id).cloned();
+    let report = manager.validate_or_reuse(&opportunity, previous.as_ref()).await.map_err(|e| {
+        error!("Validation failed for {}: {}", id, e);
+        (StatusCode::INTERNAL_SERVER_ERROR, format!("Validation failed: {}", e))
+    })?;
+
+    state.validation_reports.lock().await.insert(opportunity_id, report.clone());
+    Ok(Json(report))
+}
+
+/// GET /api/validation/:id.md (or :id.html)
+/// Export the latest validation report as Markdown or print-ready HTML
+pub async fn api_export_validation_report(
+    State(state): State<Arc<BusinessState>>,
+    Path(file): Path<String>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let (id, extension) = file
+        .rsplit_once('.')
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Expected <id>.md or <id>.html".to_string()))?;
+
+    let opportunity_id = id.parse::<OpportunityId>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid opportunity ID".to_string()))?;
+
+    let reports = state.validation_reports.lock().await;
+    let report = reports
+        .get(&opportunity_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "No validation report for this opportunity".to_string()))?;
+
+    match extension {
+        "md" => Ok(([(header::CONTENT_TYPE, "text/markdown; charset=utf-8")], report.to_markdown())),
+        "html" => Ok(([(header::CONTENT_TYPE, "text/html; charset=utf-8")], report.to_html())),
+        other => Err((StatusCode::NOT_FOUND, format!("Unsupported export format: {}", other))),
+    }
+}
+
+/// POST /api/business/opportunities/:id/pipeline
+/// Run validation, development and revenue generation and store the report
+pub async fn api_run_pipeline(
+    State(state): State<Arc<BusinessState>>,
+    Path(id): Path<String>,
+    body: Option<Json<RunPipelineRequest>>,
+) -> Result<Json<PipelineReport>, (StatusCode, String)> {
+    let opportunity_id = id.parse::<OpportunityId>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid opportunity ID".to_string()))?;
+
+    let opportunity = state.discovered_opportunities.lock().await
+        .iter()
+        .find(|opp| opp.id == opportunity_id)
+        .cloned()
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Opportunity not found".to_string()))?;
+
+    let budget = body
+        .and_then(|Json(req)| req.marketing_budget)
+        .unwrap_or(DEFAULT_MARKETING_BUDGET);
+
+    let mut validation_manager = state.validation_manager().map_err(error_response)?;
+    let mut revenue_manager = state.revenue_manager();
+    let report = run_pipeline_with(
+        &mut validation_manager,
+        &mut revenue_manager,
+        state.llm_client.clone(),
+        &opportunity,
+        budget,
+    )
+    .await
+    .map_err(|e| {
+        error!("Pipeline failed for {}: {}", id, e);
+        (StatusCode::INTERNAL_SERVER_ERROR, format!("Pipeline failed: {}", e))
+    })?;
+
+    info!("Pipeline run {} finished for {}", report.id, opportunity.title);
+    state.pipeline_runs.lock().await.insert(report.id, report.clone());
+    Ok(Json(report))
+}
+
+/// GET /api/pipeline/:id (or :id.md)
+/// Fetch a stored pipeline report as JSON, or as Markdown with a `.md` suffix
+pub async fn api_get_pipeline_report(
+    State(state): State<Arc<BusinessState>>,
+    Path(id): Path<String>,
+) -> Result<axum::response::Response, (StatusCode, String)> {
+    let (id, markdown) = match id.strip_suffix(".md") {
+        Some(id) => (id, true),
+        None => (id.as_str(), false),
+    };
+    let run_id = id.parse::<uuid::Uuid>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid pipeline run ID".to_string()))?;
+
+    let runs = state.pipeline_runs.lock().await;
+    let report = runs
+        .get(&run_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, "Pipeline run not found".to_string()))?;
+
+    if markdown {
+        Ok(([(header::CONTENT_TYPE, "text/markdown; charset=utf-8")], report.to_markdown()).into_response())
+    } else {
+        Ok(Json(report.clone()).into_response())
+    }
+}
+
+/// GET /api/decisions?agent=<agent id>
+/// Recorded agent decisions, oldest first, optionally for a single agent
+pub async fn api_list_decisions(
+    State(state): State<Arc<BusinessState>>,
+    Query(query): Query<DecisionQuery>,
+) -> Json<Vec<DecisionRecord>> {
+    Json(match query.agent {
+        Some(agent) => state.decision_log.for_agent(&agent),
+        None => state.decision_log.all(),
+    })
+}
+
 /// DELETE /api/business/opportunities/:id
 /// Remove an opportunity from the list
 pub async fn api_delete_opportunity(
@@ -241,6 +575,25 @@ pub async fn api_business_metrics(
     })
 }
 
+/// GET /api/discovery/sessions/:id
+/// Progress of a discovery session: finished sources, failures and results
+pub async fn api_get_discovery_session(
+    State(state): State<Arc<BusinessState>>,
+    Path(id): Path<String>,
+) -> Result<Json<DiscoverySession>, (StatusCode, String)> {
+    let session_id = id.parse::<uuid::Uuid>()
+        .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid session ID".to_string()))?;
+
+    match state.discovery_sessions.get(session_id) {
+        Ok(Some(session)) => Ok(Json(session)),
+        Ok(None) => Err((StatusCode::NOT_FOUND, "Discovery session not found".to_string())),
+        Err(e) => {
+            error!("Failed to load discovery session {}: {}", session_id, e);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load session: {}", e)))
+        }
+    }
+}
+
 /// GET /api/business/discovery/status
 /// Get status of the discovery manager
 pub async fn api_discovery_status(
@@ -272,11 +625,19 @@ pub fn create_business_routes(state: Arc<BusinessState>) -> Router {
         .route("/business/opportunities", get(api_list_opportunities))
         .route("/business/opportunities/:id", get(api_get_opportunity))
         .route("/business/opportunities/:id", delete(api_delete_opportunity))
+        .route("/business/opportunities/:id/score", get(api_get_opportunity_score))
+        .route("/business/opportunities/:id/refresh", post(api_refresh_opportunity))
         .route("/business/opportunities/:id/develop", post(api_start_development))
+        .route("/business/opportunities/:id/validate", post(api_validate_opportunity))
+        .route("/validation/:file", get(api_export_validation_report))
+        .route("/business/opportunities/:id/pipeline", post(api_run_pipeline))
+        .route("/pipeline/:id", get(api_get_pipeline_report))
+        .route("/decisions", get(api_list_decisions))
 
         // Metrics and status
         .route("/business/metrics", get(api_business_metrics))
         .route("/business/discovery/status", get(api_discovery_status))
+        .route("/discovery/sessions/:id", get(api_get_discovery_session))
 
         .with_state(state)
 }
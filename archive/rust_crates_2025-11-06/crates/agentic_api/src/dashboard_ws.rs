@@ -5,6 +5,15 @@
 //! - Business opportunity pipeline
 //! - Revenue metrics
 //! - System health
+//!
+//! With a batch window set (`DASHBOARD_BATCH_MS`), each client receives the
+//! events of a window as one JSON array instead of one message per event,
+//! and status events for the same agent are collapsed to the latest one.
+//!
+//! Events go through a bounded broadcast channel (`DASHBOARD_EVENT_CAPACITY`,
+//! default [`DEFAULT_EVENT_CAPACITY`]). A client that falls further behind
+//! than that loses the oldest events; it is sent a
+//! [`DashboardEvent::Resync`] in their place so it knows to refetch state.
 
 use axum::{
     extract::{
@@ -16,14 +25,18 @@ use axum::{
     routing::get,
     Router,
 };
-use futures::{sink::SinkExt, stream::StreamExt};
+use futures::{sink::{Sink, SinkExt}, stream::StreamExt};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{broadcast, RwLock};
 use std::collections::HashMap;
 use uuid::Uuid;
 use tracing::{info, warn, error};
 
+/// Events buffered per client before a slow client starts missing events
+pub const DEFAULT_EVENT_CAPACITY: usize = 1000;
+
 /// Dashboard event types that are broadcast to connected clients
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -121,9 +134,40 @@ pub enum DashboardEvent {
         to_phase: String,
         timestamp: String,
     },
+
+    /// Estimated LLM spend crossed an alert threshold
+    CostAlert {
+        /// `None` when the global threshold was crossed
+        agent_id: Option<String>,
+        window_cost_usd: f64,
+        threshold_usd: f64,
+        window_seconds: u64,
+        timestamp: String,
+    },
+
+    /// The client fell behind and `skipped` events were dropped for it;
+    /// its view is stale until it refetches (e.g. `GET /api/dashboard/stats`)
+    Resync {
+        skipped: u64,
+        timestamp: String,
+    },
 }
 
 impl DashboardEvent {
+    /// Events sharing a key replace each other within a batch, as only the
+    /// latest state matters; `None` for events that are always delivered
+    ///
+    /// Keys include the event kind, so an agent's completion doesn't swallow
+    /// the start that preceded it.
+    fn coalesce_key(&self) -> Option<String> {
+        match self {
+            Self::AgentExecutionStarted { agent_id, .. } => Some(format!("agent_execution_started:{}", agent_id)),
+            Self::AgentExecutionCompleted { agent_id, .. } => Some(format!("agent_execution_completed:{}", agent_id)),
+            Self::SystemHealth { .. } => Some("system_health".to_string()),
+            _ => None,
+        }
+    }
+
     /// Create a new agent execution started event
     pub fn agent_started(agent_id: impl Into<String>, agent_name: impl Into<String>, task: impl Into<String>) -> Self {
         Self::AgentExecutionStarted {
@@ -166,6 +210,25 @@ impl DashboardEvent {
         }
     }
 
+    /// Create a cost alert event
+    pub fn cost_alert(alert: &agentic_runtime::CostAlert) -> Self {
+        Self::CostAlert {
+            agent_id: alert.agent_id.clone(),
+            window_cost_usd: alert.window_cost_usd,
+            threshold_usd: alert.threshold_usd,
+            window_seconds: alert.window_seconds,
+            timestamp: alert.timestamp.to_rfc3339(),
+        }
+    }
+
+    /// Create a resync hint for a client that missed `skipped` events
+    pub fn resync(skipped: u64) -> Self {
+        Self::Resync {
+            skipped,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
     /// Create a new system health event
     pub fn system_health(agents_active: usize, agents_total: usize, opportunities_active: usize, cpu_usage: f64, memory_usage: f64) -> Self {
         Self::SystemHealth {
@@ -190,6 +253,9 @@ pub struct DashboardState {
 
     /// Event history (last 100 events)
     history: Arc<RwLock<Vec<DashboardEvent>>>,
+
+    /// Collect events for this long and send them as one batch; `None` sends each at once
+    batch_window: Option<Duration>,
 }
 
 #[derive(Debug, Clone)]
@@ -201,15 +267,44 @@ struct ClientInfo {
 impl DashboardState {
     /// Create a new dashboard state
     pub fn new() -> Self {
-        let (event_tx, _) = broadcast::channel(1000);
+        let (event_tx, _) = broadcast::channel(DEFAULT_EVENT_CAPACITY);
 
         Self {
             event_tx,
             clients: Arc::new(RwLock::new(HashMap::new())),
             history: Arc::new(RwLock::new(Vec::new())),
+            batch_window: None,
         }
     }
 
+    /// Batch events per client over `window` (`None` or zero disables batching)
+    pub fn with_batch_window(mut self, window: Option<Duration>) -> Self {
+        self.batch_window = window.filter(|w| !w.is_zero());
+        self
+    }
+
+    /// Buffer up to `capacity` undelivered events per client (at least 1)
+    ///
+    /// Replaces the event channel, so call it before clients subscribe.
+    pub fn with_event_capacity(mut self, capacity: usize) -> Self {
+        self.event_tx = broadcast::channel(capacity.max(1)).0;
+        self
+    }
+
+    /// Read the batch window in milliseconds from `DASHBOARD_BATCH_MS` and
+    /// the channel capacity from `DASHBOARD_EVENT_CAPACITY`
+    pub fn from_env() -> Self {
+        let window = std::env::var("DASHBOARD_BATCH_MS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_millis);
+        let capacity = std::env::var("DASHBOARD_EVENT_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_EVENT_CAPACITY);
+        Self::new().with_batch_window(window).with_event_capacity(capacity)
+    }
+
     /// Broadcast an event to all connected clients
     pub async fn broadcast(&self, event: DashboardEvent) {
         // Add to history
@@ -295,14 +390,10 @@ async fn handle_socket(socket: WebSocket, state: DashboardState) {
     }
 
     // Spawn task to forward broadcast events to this client
+    let batch_window = state.batch_window;
     let mut send_task = tokio::spawn(async move {
-        while let Ok(event) = event_rx.recv().await {
-            if let Ok(json) = serde_json::to_string(&event) {
-                if sender.send(Message::Text(json)).await.is_err() {
-                    break;
-                }
-            }
-        }
+        let sink = sender.with(|json: String| futures::future::ready(Ok::<_, axum::Error>(Message::Text(json))));
+        forward_events(event_rx, batch_window, sink).await;
     });
 
     // Spawn task to handle incoming messages from client (heartbeat, etc.)
@@ -331,6 +422,89 @@ async fn handle_socket(socket: WebSocket, state: DashboardState) {
     state.unregister_client(client_id).await;
 }
 
+/// Next event for a client, or a [`DashboardEvent::Resync`] if it lagged;
+/// `None` once the channel is closed
+async fn next_event(event_rx: &mut broadcast::Receiver<DashboardEvent>) -> Option<DashboardEvent> {
+    match event_rx.recv().await {
+        Ok(event) => Some(event),
+        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+            warn!("Dashboard client lagged, {} events skipped; sending resync", skipped);
+            Some(DashboardEvent::resync(skipped))
+        }
+        Err(broadcast::error::RecvError::Closed) => None,
+    }
+}
+
+/// Send events from `event_rx` to `sink` as JSON until either side closes
+///
+/// Without a batch window every event is its own message. With one, the
+/// first event opens a window; everything received until it ends goes out
+/// as a single JSON array, coalesced by [`DashboardEvent::coalesce_key`].
+async fn forward_events<S>(mut event_rx: broadcast::Receiver<DashboardEvent>, batch_window: Option<Duration>, mut sink: S)
+where
+    S: Sink<String> + Unpin,
+{
+    let Some(window) = batch_window else {
+        while let Some(event) = next_event(&mut event_rx).await {
+            if let Ok(json) = serde_json::to_string(&event) {
+                if sink.send(json).await.is_err() {
+                    break;
+                }
+            }
+        }
+        return;
+    };
+
+    loop {
+        let Some(first) = next_event(&mut event_rx).await else {
+            return;
+        };
+
+        let mut batch = EventBatch::default();
+        batch.push(first);
+        let mut closed = false;
+        let deadline = tokio::time::sleep(window);
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                _ = &mut deadline => break,
+                next = next_event(&mut event_rx) => match next {
+                    Some(event) => batch.push(event),
+                    None => {
+                        closed = true;
+                        break;
+                    }
+                },
+            }
+        }
+
+        if let Ok(json) = serde_json::to_string(&batch.events) {
+            if sink.send(json).await.is_err() {
+                return;
+            }
+        }
+        if closed {
+            return;
+        }
+    }
+}
+
+/// Events collected during one batch window, in arrival order
+#[derive(Debug, Default)]
+struct EventBatch {
+    events: Vec<DashboardEvent>,
+}
+
+impl EventBatch {
+    /// Add `event`, dropping an earlier event it supersedes
+    fn push(&mut self, event: DashboardEvent) {
+        if let Some(key) = event.coalesce_key() {
+            self.events.retain(|e| e.coalesce_key().as_deref() != Some(key.as_str()));
+        }
+        self.events.push(event);
+    }
+}
+
 /// Get dashboard statistics
 #[derive(Serialize)]
 pub struct DashboardStats {
@@ -429,4 +603,53 @@ mod tests {
         let history = state.get_history().await;
         assert_eq!(history.len(), 100);
     }
+
+    #[tokio::test]
+    async fn test_rapid_status_events_arrive_as_one_batch() {
+        let state = DashboardState::new().with_batch_window(Some(Duration::from_millis(50)));
+        let event_rx = state.event_tx.subscribe();
+        let (sink, client) = futures::channel::mpsc::unbounded::<String>();
+
+        for i in 0..50 {
+            state.broadcast(DashboardEvent::agent_started("agent-1", "Worker", format!("step {}", i))).await;
+        }
+        state.broadcast(DashboardEvent::agent_started("agent-2", "Other", "only step")).await;
+        state.broadcast(DashboardEvent::agent_completed("agent-1", "Worker", "step 49", 12, true)).await;
+        let batch_window = state.batch_window;
+        drop(state);
+
+        forward_events(event_rx, batch_window, sink).await;
+
+        let messages: Vec<String> = client.collect().await;
+        assert_eq!(messages.len(), 1);
+        let batch: Vec<serde_json::Value> = serde_json::from_str(&messages[0]).unwrap();
+        assert_eq!(batch.len(), 3);
+        assert_eq!(batch[0]["task"], "step 49");
+        assert_eq!(batch[1]["agent_id"], "agent-2");
+        // A completion doesn't replace the start it follows
+        assert_eq!(batch[2]["type"], "agent_execution_completed");
+    }
+
+    #[tokio::test]
+    async fn test_slow_subscriber_gets_resync_hint() {
+        let state = DashboardState::new().with_event_capacity(4);
+        let event_rx = state.event_tx.subscribe();
+        let (sink, client) = futures::channel::mpsc::unbounded::<String>();
+
+        // Nothing is read while ten events go out, so the oldest six are lost
+        for i in 0..10 {
+            state.broadcast(DashboardEvent::agent_started(format!("agent-{}", i), "Worker", "step")).await;
+        }
+        drop(state);
+
+        forward_events(event_rx, None, sink).await;
+
+        let messages: Vec<serde_json::Value> =
+            client.map(|m| serde_json::from_str(&m).unwrap()).collect().await;
+        assert_eq!(messages.len(), 5);
+        assert_eq!(messages[0]["type"], "resync");
+        assert_eq!(messages[0]["skipped"], 6);
+        let agents: Vec<&str> = messages[1..].iter().map(|m| m["agent_id"].as_str().unwrap()).collect();
+        assert_eq!(agents, ["agent-6", "agent-7", "agent-8", "agent-9"]);
+    }
 }
@@ -1,13 +1,20 @@
 //! Agent execution endpoints
 
-use crate::{AppState, DashboardEvent};
-use axum::{extract::{Path, State}, Json};
+use crate::{error_response, AppState, DashboardEvent};
+use axum::{extract::{Path, State}, http::StatusCode, response::{IntoResponse, Response}, Json};
 use serde::{Deserialize, Serialize};
-use tracing::{info, error};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{info, warn, error, Instrument};
+use agentic_core::{AgentId, RequiredCapability};
 use agentic_runtime::{
     executor::AgentExecutor,
-    context::ExecutionContext,
-    scheduler::{Task, TaskPriority},
+    context::{ExecutionContext, ModelOverrides},
+    scheduler::{DrainReport, SchedulerStatus, TaskPriority},
+    PromptTrace,
 };
 
 #[derive(Deserialize)]
@@ -15,9 +22,27 @@ pub struct ExecuteAgentReq {
     pub input: String,
     #[serde(default)]
     pub with_learning: bool,
+    /// Per-call model settings; the stored agent is not modified
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<usize>,
+    #[serde(default)]
+    pub stop_sequences: Vec<String>,
+    /// Keep the full prompts sent, retrievable via the returned `execution_id`
+    #[serde(default)]
+    pub trace: bool,
+    /// Return `202 Accepted` with an execution id right away and run in the
+    /// background; poll or cancel it under `/api/executions/:id`
+    #[serde(default, rename = "async")]
+    pub run_async: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ExecuteAgentRes {
     pub success: bool,
     pub output: String,
@@ -25,14 +50,48 @@ pub struct ExecuteAgentRes {
     pub tokens_used: usize,
     pub execution_time_ms: u64,
     pub learning_events_count: usize,
+    pub model: Option<String>,
+    /// Set when the request asked for a prompt trace
+    pub execution_id: Option<String>,
+}
+
+impl ExecuteAgentRes {
+    pub(crate) fn failed(error: String) -> Self {
+        Self {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+            tokens_used: 0,
+            execution_time_ms: 0,
+            learning_events_count: 0,
+            model: None,
+            execution_id: None,
+        }
+    }
 }
 
-/// Execute an agent directly
+/// Execute an agent directly, or in the background with `async: true`
+///
+/// Background executions answer 503 once the server is draining for shutdown.
 pub async fn api_agent_execute(
     State(state): State<AppState>,
     Path(id): Path<String>,
     Json(req): Json<ExecuteAgentReq>,
-) -> Json<ExecuteAgentRes> {
+) -> Response {
+    if !req.run_async {
+        return Json(run_execution(state, id, req).await).into_response();
+    }
+    if state.scheduler.is_draining() {
+        let shutting_down = agentic_core::Error::ShuttingDown("Server is not accepting background executions".to_string());
+        return error_response(shutting_down).into_response();
+    }
+
+    let execution_id = state.executions.spawn(id.clone(), run_execution(state.clone(), id, req));
+    let job = state.executions.get(&execution_id);
+    (StatusCode::ACCEPTED, Json(job)).into_response()
+}
+
+async fn run_execution(state: AppState, id: String, req: ExecuteAgentReq) -> ExecuteAgentRes {
     info!("Executing agent {} with input: {}", id, req.input);
 
     // Get agent from registry
@@ -40,14 +99,7 @@ pub async fn api_agent_execute(
 
     let Some(mut agent) = agent_opt else {
         error!("Agent {} not found", id);
-        return Json(ExecuteAgentRes {
-            success: false,
-            output: String::new(),
-            error: Some(format!("Agent {} not found", id)),
-            tokens_used: 0,
-            execution_time_ms: 0,
-            learning_events_count: 0,
-        });
+        return ExecuteAgentRes::failed(format!("Agent {} not found", id));
     };
 
     // Broadcast execution started event
@@ -61,11 +113,18 @@ pub async fn api_agent_execute(
     ).await;
 
     // Create execution context
-    let context = ExecutionContext::new(agent.id);
+    let context = ExecutionContext::new(agent.id).with_overrides(ModelOverrides {
+        model: req.model.clone(),
+        provider: req.provider.clone(),
+        temperature: req.temperature,
+        max_tokens: req.max_tokens,
+        stop_sequences: req.stop_sequences.clone(),
+    });
+    let context = if req.trace { context.with_prompt_trace() } else { context };
 
     // Execute agent
     let result = if req.with_learning {
-        let mut learning_engine = state.learning_engine.lock().unwrap();
+        let mut learning_engine = state.learning_engine.lock().await;
         state.executor
             .execute_with_learning(&mut agent, &req.input, &context, &mut learning_engine)
             .await
@@ -90,19 +149,31 @@ pub async fn api_agent_execute(
                 )
             ).await;
 
-            // Update agent in registry
-            state.registry.lock().unwrap().register(agent,
-                state.registry.lock().unwrap().get_genome(&id).unwrap().clone()
-            );
+            // Update agent in registry, unless it was deleted while running
+            let Some(genome) = state.registry.lock().unwrap().get_genome(&id).cloned() else {
+                error!("Agent {} was deleted during execution", id);
+                return ExecuteAgentRes::failed(format!("Agent {} not found", id));
+            };
+            if let Err(e) = state.registry.lock().unwrap().register(agent, genome) {
+                error!("Failed to update agent {} in registry: {}", id, e);
+            }
 
-            Json(ExecuteAgentRes {
+            let execution_id = exec_result.prompt_trace.clone().map(|trace| {
+                let execution_id = trace.execution_id.to_string();
+                state.prompt_traces.insert(trace);
+                execution_id
+            });
+
+            ExecuteAgentRes {
                 success: exec_result.success,
                 output: exec_result.output,
                 error: exec_result.error,
                 tokens_used: exec_result.tokens_used,
                 execution_time_ms: exec_result.execution_time_ms,
                 learning_events_count: exec_result.learning_events.len(),
-            })
+                model: exec_result.model,
+                execution_id,
+            }
         }
         Err(e) => {
             // Broadcast execution failed event
@@ -117,40 +188,266 @@ pub async fn api_agent_execute(
             ).await;
 
             error!("Execution error: {}", e);
-            Json(ExecuteAgentRes {
-                success: false,
-                output: String::new(),
-                error: Some(e.to_string()),
-                tokens_used: 0,
-                execution_time_ms: 0,
-                learning_events_count: 0,
-            })
+            ExecuteAgentRes::failed(e.to_string())
         }
     }
 }
 
+/// Prompts sent by a traced execution of agent `id`
+pub async fn api_execution_trace(
+    State(state): State<AppState>,
+    Path((id, exec_id)): Path<(String, String)>,
+) -> Result<Json<PromptTrace>, (StatusCode, String)> {
+    let agent_id = AgentId::from_string(&id).map_err(error_response)?;
+    let execution_id = parse_execution_id(&exec_id)?;
+
+    state
+        .prompt_traces
+        .get(&agent_id, &execution_id)
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("no trace for execution {} of agent {}", exec_id, id)))
+}
+
+/// Finished background executions kept for polling; older ones are dropped
+pub const MAX_FINISHED_EXECUTIONS: usize = 1000;
+
+/// Where a background execution stands
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ExecutionStatus {
+    Running,
+    Completed { result: ExecuteAgentRes },
+    Cancelled,
+    /// The execution panicked before producing a result
+    Failed { error: String },
+}
+
+/// An execution started with `async: true`
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionJob {
+    pub execution_id: uuid::Uuid,
+    pub agent_id: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    #[serde(flatten)]
+    pub status: ExecutionStatus,
+    #[serde(skip)]
+    abort: Option<tokio::task::AbortHandle>,
+}
+
+/// Background executions by id, each cancellable until it finishes
+#[derive(Clone, Default)]
+pub struct ExecutionJobs {
+    jobs: Arc<Mutex<HashMap<uuid::Uuid, ExecutionJob>>>,
+    /// Signalled whenever a running job ends
+    job_finished: Arc<Notify>,
+}
+
+impl ExecutionJobs {
+    /// Run `execution` on the runtime and track it under a new id
+    pub fn spawn<F>(&self, agent_id: String, execution: F) -> uuid::Uuid
+    where
+        F: Future<Output = ExecuteAgentRes> + Send + 'static,
+    {
+        let execution_id = uuid::Uuid::new_v4();
+        let jobs = self.clone();
+        // Hold the lock until the job is recorded, so a quick execution
+        // can't finish before its entry exists
+        let mut tracked = self.jobs.lock().unwrap();
+        // Keep the caller's span as parent, so the execution's spans stay
+        // under the request that started it
+        let handle = tokio::spawn(execution.in_current_span());
+        let abort = handle.abort_handle();
+        // A panicking execution still ends its job instead of leaving it running
+        tokio::spawn(async move {
+            let status = match handle.await {
+                Ok(result) => ExecutionStatus::Completed { result },
+                Err(e) if e.is_cancelled() => ExecutionStatus::Cancelled,
+                Err(e) => {
+                    error!("Execution {} failed: {}", execution_id, e);
+                    ExecutionStatus::Failed { error: e.to_string() }
+                }
+            };
+            jobs.finish(execution_id, status);
+        });
+        tracked.insert(execution_id, ExecutionJob {
+            execution_id,
+            agent_id,
+            started_at: chrono::Utc::now(),
+            status: ExecutionStatus::Running,
+            abort: Some(abort),
+        });
+        execution_id
+    }
+
+    pub fn get(&self, execution_id: &uuid::Uuid) -> Option<ExecutionJob> {
+        self.jobs.lock().unwrap().get(execution_id).cloned()
+    }
+
+    /// Abort execution `execution_id` if it is still running
+    ///
+    /// Returns the job as it stands afterwards, so a job that had already
+    /// finished comes back `Completed` or `Failed`; `None` if the id is unknown.
+    pub fn cancel(&self, execution_id: &uuid::Uuid) -> Option<ExecutionJob> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let job = jobs.get_mut(execution_id)?;
+        if let (ExecutionStatus::Running, Some(abort)) = (&job.status, job.abort.take()) {
+            abort.abort();
+            job.status = ExecutionStatus::Cancelled;
+        }
+        Some(job.clone())
+    }
+
+    /// Let running executions finish for up to `grace`, then cancel the rest
+    ///
+    /// Background executions are never queued, so `cancelled_queued` is
+    /// always 0.
+    pub async fn drain(&self, grace: Duration) -> DrainReport {
+        let running_at_start = self.running_ids().len();
+        let deadline = tokio::time::Instant::now() + grace;
+        loop {
+            let finished = self.job_finished.notified();
+            if self.running_ids().is_empty() || tokio::time::timeout_at(deadline, finished).await.is_err() {
+                break;
+            }
+        }
+
+        let stragglers = self.running_ids();
+        for execution_id in &stragglers {
+            self.cancel(execution_id);
+        }
+        if !stragglers.is_empty() {
+            warn!("Cancelled {} background executions at shutdown", stragglers.len());
+        }
+        DrainReport {
+            finished: running_at_start.saturating_sub(stragglers.len()),
+            cancelled_running: stragglers.len(),
+            cancelled_queued: 0,
+        }
+    }
+
+    fn running_ids(&self) -> Vec<uuid::Uuid> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|job| matches!(job.status, ExecutionStatus::Running))
+            .map(|job| job.execution_id)
+            .collect()
+    }
+
+    fn finish(&self, execution_id: uuid::Uuid, status: ExecutionStatus) {
+        let mut jobs = self.jobs.lock().unwrap();
+        if let Some(job) = jobs.get_mut(&execution_id) {
+            if matches!(job.status, ExecutionStatus::Running) {
+                job.status = status;
+                job.abort = None;
+            }
+        }
+        self.job_finished.notify_waiters();
+
+        let mut finished: Vec<(chrono::DateTime<chrono::Utc>, uuid::Uuid)> = jobs
+            .values()
+            .filter(|job| !matches!(job.status, ExecutionStatus::Running))
+            .map(|job| (job.started_at, job.execution_id))
+            .collect();
+        if finished.len() > MAX_FINISHED_EXECUTIONS {
+            finished.sort();
+            for (_, id) in &finished[..finished.len() - MAX_FINISHED_EXECUTIONS] {
+                jobs.remove(id);
+            }
+        }
+    }
+}
+
+/// GET /api/executions/:id
+pub async fn api_execution_get(
+    State(state): State<AppState>,
+    Path(exec_id): Path<String>,
+) -> Result<Json<ExecutionJob>, (StatusCode, String)> {
+    let execution_id = parse_execution_id(&exec_id)?;
+    state
+        .executions
+        .get(&execution_id)
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("no execution {}", exec_id)))
+}
+
+/// DELETE /api/executions/:id
+///
+/// Cancels a running background execution; `409 Conflict` if it already
+/// finished.
+pub async fn api_execution_cancel(
+    State(state): State<AppState>,
+    Path(exec_id): Path<String>,
+) -> Result<Json<ExecutionJob>, (StatusCode, String)> {
+    let execution_id = parse_execution_id(&exec_id)?;
+    let job = state
+        .executions
+        .cancel(&execution_id)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("no execution {}", exec_id)))?;
+    match job.status {
+        ExecutionStatus::Completed { .. } | ExecutionStatus::Failed { .. } => {
+            Err((StatusCode::CONFLICT, format!("execution {} already finished", exec_id)))
+        }
+        _ => {
+            info!("Cancelled execution {} of agent {}", exec_id, job.agent_id);
+            state
+                .dashboard_state
+                .broadcast(DashboardEvent::agent_completed(job.agent_id.clone(), "", "cancelled", 0, false))
+                .await;
+            Ok(Json(job))
+        }
+    }
+}
+
+fn parse_execution_id(exec_id: &str) -> Result<uuid::Uuid, (StatusCode, String)> {
+    exec_id
+        .parse()
+        .map_err(|_| (StatusCode::BAD_REQUEST, format!("invalid execution id: {}", exec_id)))
+}
+
 #[derive(Deserialize)]
 pub struct CreateTaskReq {
-    pub agent_id: String,
+    /// Agent to run the task; when omitted, the best idle agent with
+    /// `required_capabilities` is picked
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    #[serde(default)]
+    pub required_capabilities: Vec<RequiredCapability>,
     pub input: String,
     #[serde(default)]
     pub priority: String, // "low", "normal", "high", "critical"
     pub workflow_id: Option<String>,
+    #[serde(default)]
+    pub deadline: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[derive(Serialize)]
 pub struct CreateTaskRes {
     pub task_id: String,
+    pub agent_id: String,
 }
 
 /// Create a new task
+///
+/// Answers 503 once the scheduler is draining for shutdown.
 pub async fn api_tasks_create(
     State(state): State<AppState>,
     Json(req): Json<CreateTaskReq>,
-) -> Json<Result<CreateTaskRes, String>> {
-    let agent_id = match req.agent_id.parse() {
-        Ok(id) => id,
-        Err(_) => return Json(Err("Invalid agent ID".to_string())),
+) -> Result<Json<CreateTaskRes>, (StatusCode, String)> {
+    let agent_id = match &req.agent_id {
+        Some(id) => AgentId::from_string(id).map_err(error_response)?,
+        None => {
+            let registry = state.registry.lock().unwrap();
+            match registry.best_idle_match(&req.required_capabilities) {
+                Some(agent) => agent.id,
+                None => {
+                    return Err(error_response(agentic_core::Error::AgentNotFound(
+                        "No idle agent has the required capabilities".to_string(),
+                    )))
+                }
+            }
+        }
     };
 
     let priority = match req.priority.as_str() {
@@ -161,7 +458,11 @@ pub async fn api_tasks_create(
         _ => TaskPriority::Normal,
     };
 
-    let mut task = Task::new(agent_id, req.input).with_priority(priority);
+    let mut task = state.scheduler.new_task(agent_id, req.input).with_priority(priority);
+
+    if let Some(deadline) = req.deadline {
+        task = task.with_deadline(deadline);
+    }
 
     if let Some(wf_id) = req.workflow_id {
         if let Ok(workflow_id) = wf_id.parse() {
@@ -169,16 +470,9 @@ pub async fn api_tasks_create(
         }
     }
 
-    match state.scheduler.submit(task) {
-        Ok(task_id) => {
-            info!("Task {} created for agent {}", task_id, req.agent_id);
-            Json(Ok(CreateTaskRes { task_id }))
-        }
-        Err(e) => {
-            error!("Failed to create task: {}", e);
-            Json(Err(e))
-        }
-    }
+    let task_id = state.scheduler.submit(task).map_err(error_response)?;
+    info!("Task {} created for agent {}", task_id, agent_id);
+    Ok(Json(CreateTaskRes { task_id, agent_id: agent_id.to_string() }))
 }
 
 /// List all tasks
@@ -192,9 +486,17 @@ pub async fn api_tasks_list(
         "running": stats.running,
         "completed": stats.completed,
         "failed": stats.failed,
+        "missed": stats.missed,
     })])
 }
 
+/// Running and queued work, per-priority queue depths and LLM concurrency
+pub async fn api_scheduler_status(
+    State(state): State<AppState>,
+) -> Json<SchedulerStatus> {
+    Json(state.scheduler.status())
+}
+
 /// Get task by ID
 pub async fn api_task_get(
     State(state): State<AppState>,
@@ -210,6 +512,7 @@ pub async fn api_task_get(
             "created_at": task.created_at,
             "started_at": task.started_at,
             "completed_at": task.completed_at,
+            "deadline": task.deadline,
             "result": task.result,
             "error": task.error,
         })))
@@ -218,19 +521,35 @@ pub async fn api_task_get(
     }
 }
 
+#[derive(Serialize)]
+pub struct TaskStatusRes {
+    pub status: String,
+    pub deadline: Option<chrono::DateTime<chrono::Utc>>,
+    /// Milliseconds until the deadline (negative once passed)
+    pub time_to_deadline_ms: Option<i64>,
+}
+
 /// Get task status
 pub async fn api_task_status(
     State(state): State<AppState>,
     Path(id): Path<String>,
-) -> Json<Option<String>> {
-    state.scheduler.get_task(&id).map(|task| format!("{:?}", task.status)).into()
+) -> Json<Option<TaskStatusRes>> {
+    state
+        .scheduler
+        .get_task(&id)
+        .map(|task| TaskStatusRes {
+            status: format!("{:?}", task.status),
+            deadline: task.deadline,
+            time_to_deadline_ms: state.scheduler.time_to_deadline(&task).map(|d| d.num_milliseconds()),
+        })
+        .into()
 }
 
 /// Get learning statistics
 pub async fn api_learning_stats(
     State(state): State<AppState>,
 ) -> Json<serde_json::Value> {
-    let engine = state.learning_engine.lock().unwrap();
+    let engine = state.learning_engine.lock().await;
     Json(serde_json::json!({
         "total_events": engine.total_events_processed,
         "success_rate": engine.success_rate,
@@ -243,7 +562,7 @@ pub async fn api_learning_events(
     State(state): State<AppState>,
     Path(agent_id): Path<String>,
 ) -> Json<Vec<serde_json::Value>> {
-    let engine = state.learning_engine.lock().unwrap();
+    let engine = state.learning_engine.lock().await;
 
     if let Ok(agent_id_parsed) = agent_id.parse() {
         if let Some(events) = engine.learning_by_agent.get(&agent_id_parsed) {
@@ -1,31 +1,61 @@
 //! Minimal Axum API server: templates, agents, and a simple HTML UI
 
-use axum::{routing::{get, post, delete}, Router, extract::Path, Json, response::Html};
+use axum::{routing::{get, post, put, delete}, Router, extract::Path, Json, response::Html, http::StatusCode};
+use axum::response::sse::{Event, KeepAlive, Sse};
 use serde::{Deserialize, Serialize};
 use tracing::instrument;
 use std::sync::{Arc, Mutex};
 use agentic_factory::{AgentFactory, AgentRegistry};
 use agentic_standards::{StandardsAgent};
-use agentic_protocols::{MockMcpAdapter, MockA2aAdapter};
+use agentic_protocols::{message_types, A2aDelivery, A2aMessage, A2aSendResult, CachedMcpAdapter, DeadLetter, McpAdapter, MockA2aAdapter, TimedMcpAdapter, ToolCacheStats};
+use agentic_meta::{MetaAgent, MetaMetricsRegistry, MetaMetricsSnapshot, SDLCManager};
 use agentic_runtime::{
     executor::{AgentExecutor, DefaultExecutor, ExecutionResult},
     context::ExecutionContext,
-    scheduler::{TaskScheduler, Task, TaskPriority, TaskStatus},
-    llm::{MockLlmClient, LlmClient},
+    scheduler::{DrainReport, TaskScheduler, Task, TaskPriority, TaskStatus},
+    llm::{MockLlmClient, LlmClient, LlmProvider, Message, ModelInfo, ToolCall},
+    model_alias::{AliasedLlmClient, ModelAliasMap},
+    AggregationStrategy, ExecutionCache, ExecutionCacheStats, HttpClientBuilder, RateLimiter, RetryPolicy, RetryingLlmClient, RouteHealth, RoutingConfig, RoutingLlmClient, CostAlertConfig, CostTotals, CostTracker, LlmConfig, PartialRuntimeConfig,
+    PromptTraceStore, RedactionPolicy, RuntimeConfig, TaskKind,
 };
 use std::fs;
 use std::path::PathBuf;
 use std::collections::HashMap;
+use std::convert::Infallible;
 
 mod execution;
 use execution::*;
 
+pub mod a2a_inbox;
+use a2a_inbox::A2aInboxes;
+
+pub mod mcp_http;
+use mcp_http::HttpMcpAdapter;
+
 mod business;
 use business::BusinessState;
 
 mod dashboard_ws;
 pub use dashboard_ws::{DashboardState, DashboardEvent, broadcast_event};
 
+pub mod negotiate;
+use negotiate::{Negotiated, ResponseFormat};
+
+pub mod projection;
+use projection::Fields;
+
+#[cfg(feature = "loadgen")]
+pub mod loadgen;
+
+#[cfg(test)]
+mod test_app;
+
+/// Default cap on workers created by a single workflow request
+pub const DEFAULT_MAX_WORKFLOW_WORKERS: usize = 50;
+
+/// How long `GET /api/models` reuses the providers' model listings
+pub const MODEL_LIST_CACHE_SECONDS: i64 = 60;
+
 #[derive(Clone)]
 pub struct AppState {
     pub standards: StandardsAgent,
@@ -36,37 +66,155 @@ pub struct AppState {
     pub workflows: Arc<Mutex<HashMap<String, Workflow>>>,
     pub executor: Arc<DefaultExecutor>,
     pub scheduler: Arc<TaskScheduler>,
-    pub learning_engine: Arc<Mutex<agentic_learning::LearningEngine>>,
+    pub learning_engine: Arc<tokio::sync::Mutex<agentic_learning::LearningEngine>>,
     pub business_state: Arc<BusinessState>,
     pub dashboard_state: DashboardState,
+    /// Upper bound on workers per workflow (`MAX_WORKFLOW_WORKERS`, default 50)
+    pub max_workflow_workers: usize,
+    /// Metrics recorded by meta-agents run through the API
+    pub meta_metrics: MetaMetricsRegistry,
+    /// MCP tools, with results of deterministic tools cached
+    pub mcp: Arc<CachedMcpAdapter>,
+    /// Runs MCP invocations with a deadline (`MCP_INVOKE_TIMEOUT`, default 30s)
+    /// against the server at `MCP_SERVER_URL`, or against `mcp` when unset
+    pub mcp_invoker: Arc<TimedMcpAdapter>,
+    /// Prompts of executions run with `trace: true`, most recent kept
+    pub prompt_traces: PromptTraceStore,
+    /// Estimated LLM spend of executor calls; alerts go to the dashboard
+    pub costs: CostTracker,
+    /// Last model listing served by `GET /api/models`, with when it was fetched
+    pub model_list: Arc<Mutex<Option<(chrono::DateTime<chrono::Utc>, Vec<ModelInfo>)>>>,
+    /// Executions started with `async: true`, by execution id
+    pub executions: ExecutionJobs,
+    /// Applied to messages before they are stored (`MESSAGE_REDACTION`); off by default
+    pub redaction: RedactionPolicy,
+    /// Configuration the server runs with; changed by [`AppState::reload_config`]
+    pub runtime_config: Arc<Mutex<RuntimeConfig>>,
+    /// Router behind the executor's client when routing is on
+    /// (`LLM_PROVIDER_PRIORITY`); its health is served at `/api/llm/health`
+    pub llm_router: Arc<Mutex<Option<Arc<RoutingLlmClient>>>>,
+    /// A2A messages in flight between agents, with their dead letters
+    pub a2a: A2aInboxes,
+    /// Outbound HTTP client shared by the LLM providers and the MCP server,
+    /// built from the `http` section of the runtime config
+    pub http: reqwest::Client,
+    /// Bearer token required by the `/api/admin` routes (`ADMIN_API_TOKEN`);
+    /// they are refused while it is unset
+    pub admin_token: Option<String>,
 }
 
 impl AppState {
     pub fn new() -> Self {
-        let standards = StandardsAgent::new();
-        let factory = AgentFactory::from_registry(standards.registry().clone());
-        let registry = Arc::new(Mutex::new(AgentRegistry::new()));
+        Self::with_runtime_config(runtime_config_from_env())
+    }
+
+    /// State running with `runtime_config`
+    ///
+    /// The LLM concurrency and provider rate limits are process-wide, so
+    /// they are set from `runtime_config` here as well.
+    pub fn with_runtime_config(runtime_config: RuntimeConfig) -> Self {
+        // Standards every registered template must include (BASELINE_STANDARDS)
+        let standards = StandardsAgent::new().with_baseline_standards(agentic_standards::baseline_standards_from_env());
+        let strict_models = std::env::var("STRICT_MODEL_PROVIDER")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(false);
+        let factory = AgentFactory::from_registry(standards.registry().clone()).with_strict_models(strict_models);
+        let name_uniqueness = std::env::var("AGENT_NAME_UNIQUENESS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default();
+        let registry = Arc::new(Mutex::new(AgentRegistry::new().with_name_uniqueness(name_uniqueness)));
         let storage = Arc::new(Mutex::new(PersistedStore::load_default()));
         let messages = Arc::new(Mutex::new(HashMap::new()));
         let workflows = Arc::new(Mutex::new(HashMap::new()));
 
-        // Create executor with mock LLM (can be configured with real LLM via env)
-        let llm_client: Arc<dyn LlmClient> = Arc::new(MockLlmClient::default());
-        let executor = Arc::new(DefaultExecutor::new(llm_client));
+        // DEFAULT_LLM_PROVIDER or LLM_PROVIDER_PRIORITY picks the client (mock
+        // unless set); aliases and fallbacks come from LLM_MODEL_ALIASES / LLM_FALLBACK_MODELS,
+        // transient provider failures are retried as LLM_RETRY_* says
+        let http = HttpClientBuilder::new(runtime_config.http.clone()).build().unwrap_or_else(|e| {
+            tracing::warn!("Invalid HTTP configuration, using defaults: {}", e);
+            reqwest::Client::new()
+        });
+        let mock = || -> Arc<dyn LlmClient> {
+            Arc::new(AliasedLlmClient::new(Arc::new(MockLlmClient::default()), ModelAliasMap::from_env()))
+        };
+        let (llm_client, llm_router) = match runtime_config.llm.provider() {
+            Ok(LlmProvider::Mock) if !runtime_config.llm.routing.is_enabled() => (mock(), None),
+            _ => match llm_client_from_config(&runtime_config.llm, &runtime_config.performance.llm_retry, &http) {
+                Ok(built) => (built.client, built.router),
+                Err(e) => {
+                    tracing::warn!("Falling back to the mock LLM client: {}", e);
+                    (mock(), None)
+                }
+            },
+        };
+        let health_check_interval = runtime_config.llm.routing.health_check_interval();
+
+        // Create dashboard state; DASHBOARD_BATCH_MS turns on per-client event batching
+        let dashboard_state = DashboardState::from_env();
+
+        // Track executor spend; thresholds come from COST_ALERT_THRESHOLD_USD / COST_ALERT_AGENT_THRESHOLD_USD
+        let costs = {
+            let dashboard = dashboard_state.clone();
+            CostTracker::new(CostAlertConfig::from_env()).with_alert_handler(move |alert| {
+                let dashboard = dashboard.clone();
+                let event = DashboardEvent::cost_alert(alert);
+                tokio::spawn(async move { dashboard.broadcast(event).await });
+            })
+        };
+        // Workflows persisted before a restart keep their spend attribution
+        for workflow in storage.lock().unwrap().list_workflows() {
+            assign_workflow_costs(&costs, &workflow);
+        }
+        // Sandbox limits and execution retries come from the execution config;
+        // retry waits follow LLM_BACKOFF (default exponential from 200ms).
+        // Temperature-0 executions are answered from the result cache
+        let executor = Arc::new(
+            DefaultExecutor::new(llm_client.clone())
+                .with_result_cache(Arc::new(ExecutionCache::default()))
+                .with_middleware(Arc::new(costs.clone()))
+                .with_execution_config(&runtime_config.execution)
+                .with_backoff(runtime_config.llm.backoff.build()),
+        );
 
         // Create task scheduler
         let scheduler = Arc::new(TaskScheduler::new());
+        scheduler.set_max_concurrency(runtime_config.performance.max_concurrent_llm_calls);
+        RateLimiter::global().set_config(&runtime_config.performance);
 
         // Create learning engine
-        let learning_engine = Arc::new(Mutex::new(agentic_learning::LearningEngine::new()));
+        let learning_engine = Arc::new(tokio::sync::Mutex::new(agentic_learning::LearningEngine::new()));
+
+        let meta_metrics = MetaMetricsRegistry::new();
 
-        // Create dashboard state
-        let dashboard_state = DashboardState::new();
+        let mcp = Arc::new(CachedMcpAdapter::default());
+        let mcp_adapter: Arc<dyn McpAdapter> = match HttpMcpAdapter::from_env(&http) {
+            Some(remote) => {
+                let remote = Arc::new(remote);
+                if tokio::runtime::Handle::try_current().is_ok() {
+                    let remote = remote.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = remote.refresh_tools().await {
+                            tracing::warn!("Listing tools of MCP server {} failed: {}", remote.endpoint(), e);
+                        }
+                    });
+                }
+                remote
+            }
+            None => mcp.clone(),
+        };
+        let mcp_invoker = Arc::new(TimedMcpAdapter::new(mcp_adapter).with_timeout(std::time::Duration::from_secs(
+            runtime_config.execution.mcp_invoke_timeout_seconds,
+        )));
 
         // Create business state (with dashboard state for event broadcasting)
-        let business_state = Arc::new(BusinessState::new(llm_client.clone(), dashboard_state.clone()));
+        let business_state = Arc::new(
+            BusinessState::new(llm_client.clone(), dashboard_state.clone())
+                .with_meta_metrics(meta_metrics.clone()),
+        );
 
-        Self {
+        let state = Self {
             standards,
             factory,
             registry,
@@ -78,7 +226,186 @@ impl AppState {
             learning_engine,
             business_state,
             dashboard_state,
+            max_workflow_workers: std::env::var("MAX_WORKFLOW_WORKERS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_MAX_WORKFLOW_WORKERS),
+            meta_metrics,
+            mcp,
+            mcp_invoker,
+            prompt_traces: PromptTraceStore::default(),
+            costs,
+            model_list: Arc::new(Mutex::new(None)),
+            executions: ExecutionJobs::default(),
+            redaction: RedactionPolicy::from_env(),
+            runtime_config: Arc::new(Mutex::new(runtime_config)),
+            llm_router: Arc::new(Mutex::new(None)),
+            a2a: A2aInboxes::default(),
+            http,
+            admin_token: std::env::var("ADMIN_API_TOKEN").ok().filter(|token| !token.is_empty()),
+        };
+        state.set_llm_router(llm_router, health_check_interval);
+        state
+    }
+
+    /// Make `router` the one `/api/llm/health` reports on, probing its
+    /// providers every `interval`
+    ///
+    /// The probes stop by themselves once the executor has moved on to
+    /// another client and the router is dropped.
+    fn set_llm_router(&self, router: Option<Arc<RoutingLlmClient>>, interval: Option<std::time::Duration>) {
+        if let (Some(router), Some(interval)) = (&router, interval) {
+            if tokio::runtime::Handle::try_current().is_ok() {
+                router.spawn_health_checks(interval);
+            } else {
+                tracing::warn!("No async runtime to run LLM provider health checks on");
+            }
         }
+        *self.llm_router.lock().unwrap() = router;
+    }
+
+    /// Apply `new` without restarting
+    ///
+    /// A changed `llm` section or `performance.llm_retry` rebuilds the LLM
+    /// client and swaps it into the executor; the scheduler's concurrency
+    /// limit follows `performance.max_concurrent_llm_calls` and the provider
+    /// rate limits follow `performance.rate_limit_per_minute` and
+    /// `performance.provider_rate_limits`. If `new` changes settings the
+    /// running components were built with, nothing is applied and the error
+    /// names them.
+    pub fn reload_config(&self, new: RuntimeConfig) -> agentic_core::Result<()> {
+        let mut current = self.runtime_config.lock().unwrap();
+        let fixed = restart_required_changes(&current, &new);
+        if !fixed.is_empty() {
+            return Err(agentic_core::Error::InvalidArgument(format!(
+                "Settings that need a restart can't be reloaded: {}",
+                fixed.join(", ")
+            )));
+        }
+
+        if new.llm != current.llm || new.performance.llm_retry != current.performance.llm_retry {
+            let built = llm_client_from_config(&new.llm, &new.performance.llm_retry, &self.http)?;
+            self.executor.set_client(built.client.clone());
+            self.business_state.set_llm_client(built.client);
+            self.set_llm_router(built.router, new.llm.routing.health_check_interval());
+        }
+        self.scheduler.set_max_concurrency(new.performance.max_concurrent_llm_calls);
+        RateLimiter::global().set_config(&new.performance);
+        tracing::info!("Runtime configuration reloaded");
+        *current = new;
+        Ok(())
+    }
+
+    /// Refuse new tasks and background executions, give the running ones up
+    /// to `grace` to finish, then cancel the rest
+    pub async fn drain(&self, grace: std::time::Duration) -> ShutdownReport {
+        let (tasks, executions) = tokio::join!(self.scheduler.drain(grace), self.executions.drain(grace));
+        ShutdownReport { tasks, executions }
+    }
+}
+
+/// Outcome of [`AppState::drain`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ShutdownReport {
+    /// Scheduled tasks
+    pub tasks: DrainReport,
+    /// Executions started with `async: true`
+    pub executions: DrainReport,
+}
+
+/// Runtime config from the JSON file at `AGENTIC_CONFIG`, if set, with the
+/// environment variables layered over it
+///
+/// A file that can't be read or parsed is logged and skipped.
+fn runtime_config_from_env() -> RuntimeConfig {
+    runtime_config_from_file(std::env::var("AGENTIC_CONFIG").ok().as_deref())
+}
+
+/// Runtime config from the JSON file at `path`, if any, with the environment
+/// variables layered over it
+fn runtime_config_from_file(path: Option<&str>) -> RuntimeConfig {
+    let file = path.and_then(|path| {
+        PartialRuntimeConfig::from_file(path)
+            .map_err(|e| tracing::warn!("Ignoring config file {}: {}", path, e))
+            .ok()
+    });
+    RuntimeConfig::layered(file.into_iter().chain([PartialRuntimeConfig::from_env()]))
+}
+
+/// Settings [`AppState::reload_config`] applies to the running server
+const LIVE_RELOADABLE_SETTINGS: &[&str] = &[
+    "llm.anthropic_api_key",
+    "llm.openai_api_key",
+    "llm.openai_base_url",
+    "llm.ollama_base_url",
+    "llm.default_provider",
+    "llm.default_model",
+    "llm.max_tokens",
+    "llm.temperature",
+    "llm.extra_headers",
+    "llm.routing",
+    "performance.max_concurrent_llm_calls",
+    "performance.rate_limit_per_minute",
+    "performance.provider_rate_limits",
+    "performance.llm_retry",
+];
+
+/// Settings, as `section.field`, that differ between `current` and `new` and
+/// only take effect after a restart
+fn restart_required_changes(current: &RuntimeConfig, new: &RuntimeConfig) -> Vec<String> {
+    let (Ok(serde_json::Value::Object(current)), Ok(serde_json::Value::Object(new))) =
+        (serde_json::to_value(current), serde_json::to_value(new))
+    else {
+        return Vec::new();
+    };
+    let mut changed = Vec::new();
+    for (section, new_fields) in &new {
+        let (Some(serde_json::Value::Object(current_fields)), serde_json::Value::Object(new_fields)) =
+            (current.get(section), new_fields)
+        else {
+            continue;
+        };
+        for (field, value) in new_fields {
+            let name = format!("{}.{}", section, field);
+            if current_fields.get(field) != Some(value) && !LIVE_RELOADABLE_SETTINGS.contains(&name.as_str()) {
+                changed.push(name);
+            }
+        }
+    }
+    changed
+}
+
+/// Status and client-facing message for a core error
+///
+/// The whole source chain is logged. Server errors get a generic message so
+/// internal details (paths, upstream responses) don't reach the client.
+pub fn error_response(err: agentic_core::Error) -> (StatusCode, String) {
+    use agentic_core::Error;
+
+    let status = match &err {
+        Error::InvalidArgument(_)
+        | Error::InvalidAgentId(_)
+        | Error::InvalidWorkflowId(_)
+        | Error::InvalidTaskId(_)
+        | Error::PolicyViolation(_) => StatusCode::BAD_REQUEST,
+        Error::AgentNotFound(_)
+        | Error::WorkflowNotFound(_)
+        | Error::TaskNotFound(_)
+        | Error::ToolNotFound(_) => StatusCode::NOT_FOUND,
+        Error::Conflict(_) => StatusCode::CONFLICT,
+        Error::SandboxLimitExceeded { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+        Error::AuthorizationFailed(_) => StatusCode::FORBIDDEN,
+        Error::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+        Error::ShuttingDown(_) => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+
+    if status.is_server_error() {
+        tracing::error!(error = %err.chain_message(), "request failed");
+        (status, "Internal server error".to_string())
+    } else {
+        tracing::warn!(error = %err.chain_message(), "request rejected");
+        (status, err.to_string())
     }
 }
 
@@ -87,6 +414,12 @@ pub struct CreateAgentReq {
     pub template_id: String,
     pub name: String,
     pub description: String,
+    /// Provider replacing the template's default
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Model replacing the template's default; checked against the provider
+    #[serde(default)]
+    pub model: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -108,25 +441,74 @@ pub fn router(state: AppState) -> Router {
         .route("/api/templates/:id", get(api_template_show))
         .route("/api/agents", get(api_agents).post(api_agents_create))
         .route("/api/agents/:id/compliance", get(api_agent_compliance))
+        .route("/api/agents/:id/remediate", post(api_agent_remediate))
+        .route("/api/compliance/recheck", post(api_compliance_recheck))
         .route("/api/agents/:id", delete(api_agents_delete))
         .route("/api/agents/:id/detail", get(api_agent_detail))
+        .route("/api/agents/:id/snapshot", get(api_agent_snapshot))
+        .route("/api/agents/:id/secrets", put(api_agent_secrets_set))
         .route("/api/agents/:id/messages", get(api_agent_messages).post(api_agent_send_message))
+        .route("/api/agents/:id/chat", post(api_agent_chat))
+        .route("/api/protocols/mcp/cache/stats", get(api_mcp_cache_stats))
         .route("/api/protocols/mcp/:id/tools", get(api_mcp_tools))
         .route("/api/protocols/mcp/:id/invoke", post(api_mcp_invoke))
         .route("/api/protocols/a2a/send", post(api_a2a_send))
+        .route("/api/protocols/a2a/dead-letters", get(api_a2a_dead_letters))
         .route("/api/workflows", get(api_workflows_list).post(api_workflows_create))
         .route("/api/workflows/:id", get(api_workflows_get))
+        .route("/api/workflows/:id/run", post(api_workflows_run))
         .route("/api/agents/:id/execute", post(api_agent_execute))
+        .route("/api/agents/:id/executions/:exec_id/trace", get(api_execution_trace))
+        .route("/api/executions/cache/stats", get(api_execution_cache_stats))
+        .route("/api/executions/:id", get(api_execution_get).delete(api_execution_cancel))
         .route("/api/tasks", get(api_tasks_list).post(api_tasks_create))
         .route("/api/tasks/:id", get(api_task_get))
         .route("/api/tasks/:id/status", get(api_task_status))
+        .route("/api/scheduler/status", get(api_scheduler_status))
         .route("/api/learning/stats", get(api_learning_stats))
         .route("/api/learning/events/:agent_id", get(api_learning_events))
+        .route("/api/meta/metrics", get(api_meta_metrics))
+        .route("/api/meta/sdlc/develop", post(api_meta_sdlc_develop))
+        .route("/api/costs", get(api_costs))
+        .route("/api/models", get(api_models))
+        .route("/api/llm/health", get(api_llm_health))
+        .merge(admin_routes(state.clone()))
         .with_state(state)
         // Merge business routes under /api/
         .merge(Router::new().nest("/api", business_routes))
         // Merge dashboard routes under /api/dashboard/
         .merge(Router::new().nest("/api/dashboard", dashboard_routes))
+        // One span per request, parent of the handler's spans
+        .layer(tower_http::trace::TraceLayer::new_for_http())
+}
+
+/// Routes that change the running server, behind [`require_admin`]
+fn admin_routes(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/api/admin/llm-client", post(api_admin_set_llm_client))
+        .route("/api/admin/reload", post(api_admin_reload))
+        .route_layer(axum::middleware::from_fn_with_state(state, require_admin))
+}
+
+/// Let a request through only with `Authorization: Bearer <ADMIN_API_TOKEN>`
+async fn require_admin(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Result<axum::response::Response, (StatusCode, String)> {
+    let Some(expected) = state.admin_token.as_deref() else {
+        return Err((StatusCode::FORBIDDEN, "Admin API is disabled; set ADMIN_API_TOKEN to enable it".to_string()));
+    };
+    let presented = request
+        .headers()
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if presented != Some(expected) {
+        tracing::warn!(path = %request.uri().path(), "admin request without a valid token");
+        return Err((StatusCode::UNAUTHORIZED, "Missing or invalid admin token".to_string()));
+    }
+    Ok(next.run(request).await)
 }
 
 async fn ui_dashboard() -> Html<String> {
@@ -246,7 +628,24 @@ async fn ui_index() -> Html<String> {
         btn.addEventListener('click', async ()=>{
           const id = btn.getAttribute('data-id');
           const msg = document.getElementById(`m-${id}`).value;
-          await fetch(`/api/agents/${id}/messages`, { method:'POST', headers:{'Content-Type':'application/json'}, body: JSON.stringify({ content: msg }) });
+          const out = document.getElementById(`h-${id}`);
+          out.textContent = '';
+          const res = await fetch(`/api/agents/${id}/chat`, { method:'POST', headers:{'Content-Type':'application/json'}, body: JSON.stringify({ message: msg }) });
+          const reader = res.body.getReader();
+          const decoder = new TextDecoder();
+          let buf = '';
+          for (;;) {
+            const { done, value } = await reader.read();
+            if (done) break;
+            buf += decoder.decode(value, { stream: true });
+            const events = buf.split('\n\n');
+            buf = events.pop();
+            for (const ev of events) {
+              const lines = ev.split('\n');
+              if (lines[0] !== 'event: token') continue;
+              out.textContent += lines.filter(l => l.startsWith('data: ')).map(l => l.slice(6)).join('\n');
+            }
+          }
           const h = await fetch(`/api/agents/${id}/messages`);
           const hist = await h.json();
           document.getElementById(`h-${id}`).textContent = JSON.stringify(hist, null, 2);
@@ -270,7 +669,7 @@ async fn ui_index() -> Html<String> {
       const tool = document.getElementById('mcp-tool').value;
       const input = document.getElementById('mcp-input').value;
       const r = await fetch(`/api/protocols/mcp/${id}/invoke`, { method:'POST', headers:{'Content-Type':'application/json'}, body: JSON.stringify({ tool, input })});
-      document.getElementById('mcp-out').textContent = JSON.stringify(await r.json(), null, 2);
+      document.getElementById('mcp-out').textContent = r.ok ? JSON.stringify(await r.json(), null, 2) : `${r.status}: ${await r.text()}`;
     });
     document.getElementById('a2a-send').addEventListener('click', async ()=>{
       const from = document.getElementById('a2a-from').value;
@@ -299,16 +698,18 @@ async fn ui_index() -> Html<String> {
     Html(html.to_string())
 }
 
-async fn api_templates(axum::extract::State(state): axum::extract::State<AppState>) -> Json<Vec<(String, String)>> {
-    // MVP: only known template
-    let id = "tmpl.standard.worker".to_string();
-    let name = state
+async fn api_templates(
+    format: ResponseFormat,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Negotiated<Vec<(String, String)>> {
+    let templates = state
         .standards
         .registry()
-        .get_template(&id)
-        .map(|t| t.display_name.clone())
-        .unwrap_or_else(|| "Unknown".into());
-    Json(vec![(id, name)])
+        .templates()
+        .into_iter()
+        .map(|t| (t.template_id.clone(), t.display_name.clone()))
+        .collect();
+    format.respond(templates)
 }
 
 async fn api_template_show(
@@ -325,16 +726,19 @@ async fn api_template_show(
 
 #[instrument(skip(state))]
 #[instrument(skip(state))]
-async fn api_agents(axum::extract::State(state): axum::extract::State<AppState>) -> Json<Vec<(String, String)>> {
+async fn api_agents(
+    format: ResponseFormat,
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Negotiated<Vec<(String, String)>> {
     let reg = state.registry.lock().unwrap();
     let list: Vec<(String,String)> = reg.list_agents().into_iter().map(|a| (a.id.to_string(), a.name.clone())).collect();
     drop(reg);
     if list.is_empty() {
         let store = state.storage.lock().unwrap();
         let fallback: Vec<(String,String)> = store.list().into_iter().map(|x| (x.id, x.name)).collect();
-        return Json(fallback);
+        return format.respond(fallback);
     }
-    Json(list)
+    format.respond(list)
 }
 
 #[instrument(skip(state, req))]
@@ -342,44 +746,181 @@ async fn api_agents(axum::extract::State(state): axum::extract::State<AppState>)
 async fn api_agents_create(
     axum::extract::State(state): axum::extract::State<AppState>,
     Json(req): Json<CreateAgentReq>,
-) -> Json<CreateAgentRes> {
-    let (agent, genome) = state
+) -> Result<Json<CreateAgentRes>, (StatusCode, String)> {
+    let (mut agent, genome) = state
         .factory
         .create_from_template(&req.template_id, &req.name, &req.description)
-        .expect("create");
+        .map_err(error_response)?;
+    if req.provider.is_some() || req.model.is_some() {
+        if let Some(provider) = req.provider {
+            agent.provider = provider;
+        }
+        if let Some(model) = req.model {
+            agent.model = model;
+        }
+        state.factory.check_model(&agent.provider, &agent.model).map_err(error_response)?;
+    }
     let id = agent.id.to_string();
-    state.registry.lock().unwrap().register(agent, genome);
+    let name = state.registry.lock().unwrap().register(agent, genome).map_err(error_response)?;
     // persist lightweight record
-    state.storage.lock().unwrap().add(StoredAgent { id: id.clone(), template_id: req.template_id, name: req.name, description: req.description });
-    Json(CreateAgentRes { id })
+    state.storage.lock().unwrap().add(StoredAgent { id: id.clone(), template_id: req.template_id, name, description: req.description });
+    Ok(Json(CreateAgentRes { id }))
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 struct StoredAgent { id: String, template_id: String, name: String, description: String }
 
 #[derive(Default)]
-pub struct PersistedStore { path: PathBuf, items: Vec<StoredAgent> }
+pub struct PersistedStore { path: PathBuf, items: Vec<StoredAgent>, config: StoreConfig }
 
-#[derive(Serialize, Deserialize, Default)]
+/// How the agent store is written; any format is accepted on load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreConfig {
+    /// Indented JSON (`STORE_PRETTY_JSON`, default true); false writes compact JSON
+    pub pretty: bool,
+    /// Gzip the file (`STORE_GZIP`, default false), for large stores
+    pub gzip: bool,
+}
+
+impl Default for StoreConfig {
+    fn default() -> Self { Self { pretty: true, gzip: false } }
+}
+
+impl StoreConfig {
+    pub fn from_env() -> Self {
+        let flag = |name: &str, default: bool| {
+            std::env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+        };
+        let defaults = Self::default();
+        Self { pretty: flag("STORE_PRETTY_JSON", defaults.pretty), gzip: flag("STORE_GZIP", defaults.gzip) }
+    }
+
+    fn encode(&self, data: &PersistedData) -> std::io::Result<Vec<u8>> {
+        let json = if self.pretty { serde_json::to_vec_pretty(data) } else { serde_json::to_vec(data) }?;
+        if !self.gzip { return Ok(json); }
+
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&json)?;
+        encoder.finish()
+    }
+}
+
+/// Undo gzip if the file starts with the gzip magic bytes
+fn decode_store(bytes: Vec<u8>) -> std::io::Result<Vec<u8>> {
+    if !bytes.starts_with(&[0x1f, 0x8b]) { return Ok(bytes); }
+
+    use std::io::Read;
+    let mut json = Vec::new();
+    flate2::read::GzDecoder::new(bytes.as_slice()).read_to_end(&mut json)?;
+    Ok(json)
+}
+
+/// Current on-disk schema version of the agent store.
+///
+/// v1: `{agents, workflows}` without a version field (or a bare agents array)
+/// v2: adds the explicit `version` field
+const STORE_VERSION: u32 = 2;
+
+#[derive(Serialize, Deserialize)]
 struct PersistedData {
+    version: u32,
     agents: Vec<StoredAgent>,
     workflows: Vec<Workflow>,
 }
 
+impl Default for PersistedData {
+    fn default() -> Self { Self { version: STORE_VERSION, agents: vec![], workflows: vec![] } }
+}
+
+impl PersistedData {
+    /// Parse any known store version, migrating it to the current schema.
+    /// Also returns the version found on disk.
+    fn from_slice(bytes: &[u8]) -> std::io::Result<(Self, u32)> {
+        let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+        let mut value: serde_json::Value = serde_json::from_slice(bytes).map_err(|e| invalid(e.to_string()))?;
+
+        // oldest format: bare agents array
+        if value.is_array() {
+            value = serde_json::json!({ "agents": value, "workflows": [] });
+        }
+
+        let mut version = match value.get("version") {
+            None => 1,
+            Some(v) => v.as_u64().map(|v| v as u32).ok_or_else(|| invalid(format!("invalid store version: {}", v)))?,
+        };
+        if version > STORE_VERSION {
+            return Err(invalid(format!(
+                "store version {} is newer than supported version {}; refusing to load to avoid data loss",
+                version, STORE_VERSION
+            )));
+        }
+
+        let found = version;
+        while version < STORE_VERSION {
+            value = match version {
+                1 => migrate_v1_to_v2(value),
+                v => return Err(invalid(format!("no migration from store version {}", v))),
+            };
+            version += 1;
+        }
+
+        let data = serde_json::from_value(value).map_err(|e| invalid(e.to_string()))?;
+        Ok((data, found))
+    }
+}
+
+fn migrate_v1_to_v2(mut value: serde_json::Value) -> serde_json::Value {
+    if value.get("workflows").is_none() { value["workflows"] = serde_json::json!([]); }
+    if value.get("agents").is_none() { value["agents"] = serde_json::json!([]); }
+    value["version"] = serde_json::json!(2);
+    value
+}
+
 impl PersistedStore {
     pub fn load_default() -> Self {
-        let path = Self::default_path();
-        if let Ok(bytes) = fs::read(&path) {
-            // try new format
-            if let Ok(pd) = serde_json::from_slice::<PersistedData>(&bytes) {
-                return Self { path, items: pd.agents };
-            }
-            // fallback old format (agents array)
-            if let Ok(items) = serde_json::from_slice::<Vec<StoredAgent>>(&bytes) {
-                return Self { path, items };
+        Self::load_or_reset(Self::default_path(), StoreConfig::from_env())
+    }
+
+    /// Like [`PersistedStore::load_with`], starting empty when the file
+    /// can't be loaded
+    ///
+    /// The unreadable file is moved to `<path>.corrupt` first, so the next
+    /// save doesn't overwrite what may still be recovered from it.
+    pub fn load_or_reset(path: PathBuf, config: StoreConfig) -> Self {
+        Self::load_with(path.clone(), config).unwrap_or_else(|e| {
+            let mut aside = path.clone().into_os_string();
+            aside.push(".corrupt");
+            let aside = PathBuf::from(aside);
+            tracing::error!(
+                "Failed to load agent store {}: {}; moving it to {} and starting empty",
+                path.display(),
+                e,
+                aside.display()
+            );
+            if let Err(e) = fs::rename(&path, &aside) {
+                tracing::error!("Could not move {} aside: {}", path.display(), e);
             }
-        }
-        Self { path, items: vec![] }
+            Self { path, items: vec![], config }
+        })
+    }
+
+    /// Load a store, migrating older schema versions in place
+    pub fn load(path: PathBuf) -> std::io::Result<Self> {
+        Self::load_with(path, StoreConfig::default())
+    }
+
+    /// Like [`PersistedStore::load`], writing future saves as `config` says
+    pub fn load_with(path: PathBuf, config: StoreConfig) -> std::io::Result<Self> {
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => decode_store(bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self { path, items: vec![], config }),
+            Err(e) => return Err(e),
+        };
+        let (data, found) = PersistedData::from_slice(&bytes)?;
+        let store = Self { path, items: data.agents.clone(), config };
+        if found < STORE_VERSION { store.write_all(&data)?; }
+        Ok(store)
     }
 
     fn default_path() -> PathBuf {
@@ -393,53 +934,315 @@ impl PersistedStore {
     pub fn get(&self, id: &str) -> Option<StoredAgent> { self.items.iter().find(|x| x.id == id).cloned() }
     pub fn list(&self) -> Vec<StoredAgent> { self.items.clone() }
 
-    pub fn add_workflow(&mut self, wf: Workflow) { let mut data = self.read_all(); data.workflows.push(wf); let _ = self.write_all(&data); }
-    pub fn list_workflows(&self) -> Vec<Workflow> { self.read_all().workflows }
+    pub fn add_workflow(&mut self, wf: Workflow) {
+        if let Ok(mut data) = self.read_all() { data.workflows.push(wf); let _ = self.write_all(&data); }
+    }
+    pub fn list_workflows(&self) -> Vec<Workflow> { self.read_all().map(|d| d.workflows).unwrap_or_default() }
 
     fn save(&self) -> std::io::Result<()> {
-        let mut data = self.read_all();
+        let mut data = self.read_all()?;
         data.agents = self.items.clone();
         self.write_all(&data)
     }
 
-    fn read_all(&self) -> PersistedData {
-        if let Ok(bytes) = fs::read(&self.path) {
-            if let Ok(pd) = serde_json::from_slice::<PersistedData>(&bytes) { return pd; }
+    fn read_all(&self) -> std::io::Result<PersistedData> {
+        match fs::read(&self.path) {
+            Ok(bytes) => PersistedData::from_slice(&decode_store(bytes)?).map(|(data, _)| data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(PersistedData::default()),
+            Err(e) => Err(e),
         }
-        PersistedData::default()
     }
 
     fn write_all(&self, data: &PersistedData) -> std::io::Result<()> {
-        let bytes = serde_json::to_vec_pretty(data).unwrap_or_default();
-        fs::write(&self.path, bytes)
+        fs::write(&self.path, self.config.encode(data)?)
     }
 }
 
 #[instrument(skip(state))]
 #[instrument(skip(state))]
 async fn api_agent_compliance(
+    fields: Fields,
     axum::extract::State(state): axum::extract::State<AppState>,
     Path(id): Path<String>,
-) -> Json<Option<serde_json::Value>> {
+) -> Json<serde_json::Value> {
     let store = state.storage.lock().unwrap();
     if let Some(sa) = store.get(&id) {
         let reg = state.registry.lock().unwrap();
         if let Some(agent) = reg.get_agent(&id) {
             if let Some(report) = state.standards.compliance_for_template(&sa.template_id, agent) {
-                return Json(Some(serde_json::json!({
+                return Json(fields.project(&serde_json::json!({
                     "standard": report.standard.0,
                     "compliant": report.compliant,
                     "missing_protocols": report.missing_protocols,
                     "missing_capabilities": report.missing_capabilities,
                     "notes": report.notes,
+                    "suggested_fixes": report.suggested_fixes,
                 })));
             }
         }
     }
-    Json(None)
+    Json(serde_json::Value::Null)
 }
 
+/// Apply the suggested fixes of agent `id`'s compliance report, returning
+/// the report after remediation
+#[instrument(skip(state))]
+async fn api_agent_remediate(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<agentic_standards::ComplianceReport>, (StatusCode, String)> {
+    let stored = state.storage.lock().unwrap().get(&id);
+    let mut reg = state.registry.lock().unwrap();
+    let (Some(stored), Some(agent), Some(genome)) = (stored, reg.get_agent(&id), reg.get_genome(&id)) else {
+        return Err(error_response(agentic_core::Error::AgentNotFound(id)));
+    };
+    let (mut agent, genome) = (agent.clone(), genome.clone());
+    let template = state.standards.registry().get_template(&stored.template_id).ok_or_else(|| {
+        error_response(agentic_core::Error::InvalidState(format!("unknown template: {}", stored.template_id)))
+    })?;
+
+    let report = template.compliance_for(&agent);
+    if report.compliant {
+        return Ok(Json(report));
+    }
+    for fix in &report.suggested_fixes {
+        agent.apply_patch(fix);
+    }
+    let report = template.compliance_for(&agent);
+    reg.register(agent, genome).map_err(error_response)?;
+    Ok(Json(report))
+}
+
+#[derive(Serialize)]
+struct AgentSecretsRes { keys: Vec<String> }
+
+/// Set the secrets of agent `id` its tools get; `null` removes one
+///
+/// Write-only: the response names the secrets the agent holds, never their
+/// values, and no other endpoint returns them either.
+#[instrument(skip(state, secrets))]
+async fn api_agent_secrets_set(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path(id): Path<String>,
+    Json(secrets): Json<HashMap<String, Option<String>>>,
+) -> Result<Json<AgentSecretsRes>, (StatusCode, String)> {
+    let mut reg = state.registry.lock().unwrap();
+    let (Some(agent), Some(genome)) = (reg.get_agent(&id), reg.get_genome(&id)) else {
+        return Err(error_response(agentic_core::Error::AgentNotFound(id)));
+    };
+    let (mut agent, genome) = (agent.clone(), genome.clone());
+    for (key, value) in secrets {
+        match value {
+            Some(value) => agent.set_secret(key, value),
+            None => {
+                agent.remove_secret(&key);
+            }
+        }
+    }
+    let keys = agent.env.keys().into_iter().map(str::to_string).collect();
+    reg.register(agent, genome).map_err(error_response)?;
+    Ok(Json(AgentSecretsRes { keys }))
+}
+
+#[derive(Serialize)]
+struct NonCompliantAgent { id: String, name: String, template_id: String, reasons: Vec<String> }
+
+#[derive(Serialize)]
+struct ComplianceRecheckRes { checked: usize, compliant: Vec<String>, non_compliant: Vec<NonCompliantAgent> }
+
+/// Re-run compliance for every stored agent against its template
 #[instrument(skip(state))]
+async fn api_compliance_recheck(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Json<ComplianceRecheckRes> {
+    let stored = state.storage.lock().unwrap().list();
+    let reg = state.registry.lock().unwrap();
+    let mut res = ComplianceRecheckRes { checked: stored.len(), compliant: vec![], non_compliant: vec![] };
+
+    for sa in stored {
+        let reasons = match reg.get_agent(&sa.id) {
+            None => vec!["agent not loaded in registry".to_string()],
+            Some(agent) => match state.standards.compliance_for_template(&sa.template_id, agent) {
+                None => vec![format!("unknown template: {}", sa.template_id)],
+                Some(report) if report.compliant => {
+                    res.compliant.push(sa.id);
+                    continue;
+                }
+                Some(report) => report.reasons(),
+            },
+        };
+        res.non_compliant.push(NonCompliantAgent { id: sa.id, name: sa.name, template_id: sa.template_id, reasons });
+    }
+
+    Json(res)
+}
+
+#[derive(Serialize)]
+struct MetaMetricsRes { meta_agents: Vec<MetaMetricsSnapshot> }
+
+/// Executions, failures, cache hits and latency for each meta-agent
+async fn api_meta_metrics(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Json<MetaMetricsRes> {
+    Json(MetaMetricsRes { meta_agents: state.meta_metrics.snapshot() })
+}
+
+/// Run the SDLC workflow for `{"feature_request": ...}`, recording the run
+/// in the meta-agent metrics
+#[instrument(skip(state, params))]
+async fn api_meta_sdlc_develop(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Json(params): Json<HashMap<String, serde_json::Value>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let mut manager = SDLCManager::new(state.executor.client()).with_metrics_registry(state.meta_metrics.clone());
+    let result = manager.execute_meta_task("develop_feature", params).await.map_err(error_response)?;
+    Ok(Json(result))
+}
+
+/// Rolling estimated LLM spend and token usage, overall and per agent,
+/// workflow and provider
+async fn api_costs(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Json<CostTotals> {
+    Json(state.costs.totals())
+}
+
+#[derive(Deserialize)]
+pub struct SetLlmClientReq {
+    pub provider: String,
+    /// Model requests fall back to when the agent's own isn't served; defaults per provider
+    pub model: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SetLlmClientRes {
+    pub provider: String,
+    pub model: String,
+    pub is_mock: bool,
+}
+
+/// Build a client for `provider` alone, reading API keys from the environment
+///
+/// Routing settings are ignored: the client talks to `provider` even when
+/// `LLM_PROVIDER_PRIORITY` is set.
+fn llm_client_for(state: &AppState, provider: LlmProvider, model: &str) -> agentic_core::Result<Arc<dyn LlmClient>> {
+    let config = LlmConfig {
+        default_provider: provider.as_str().to_string(),
+        default_model: model.to_string(),
+        routing: RoutingConfig::default(),
+        ..LlmConfig::from_env()
+    };
+    let retry = state.runtime_config.lock().unwrap().performance.llm_retry.clone();
+    Ok(llm_client_from_config(&config, &retry, &state.http)?.client)
+}
+
+/// An LLM client, and the router behind it when routing is on
+struct BuiltLlmClient {
+    client: Arc<dyn LlmClient>,
+    router: Option<Arc<RoutingLlmClient>>,
+}
+
+/// Build a client for `config.default_provider`; `config.default_model` is
+/// where requests for models the provider doesn't serve fall back to
+///
+/// With `config.routing` enabled the client spans the routed providers
+/// instead, and unknown models go to the first of them unchanged. Transient
+/// failures are retried as `retry` says. Every provider sends over `http`.
+fn llm_client_from_config(
+    config: &LlmConfig,
+    retry: &RetryPolicy,
+    http: &reqwest::Client,
+) -> agentic_core::Result<BuiltLlmClient> {
+    if config.routing.is_enabled() {
+        let router = Arc::new(RoutingLlmClient::from_config(config, http)?);
+        let client = Arc::new(AliasedLlmClient::new(router.clone(), ModelAliasMap::from_env()));
+        return Ok(BuiltLlmClient { client: RetryingLlmClient::wrap(client, retry), router: Some(router) });
+    }
+    let provider = config.provider()?;
+    let inner = agentic_runtime::llm::client_from_config(provider, config, http)?;
+    let aliases = ModelAliasMap::from_env().with_fallback(provider, &config.default_model);
+    let client = Arc::new(AliasedLlmClient::new(inner, aliases));
+    Ok(BuiltLlmClient { client: RetryingLlmClient::wrap(client, retry), router: None })
+}
+
+/// Health of each routed LLM provider, in priority order; empty unless
+/// routing is on
+async fn api_llm_health(axum::extract::State(state): axum::extract::State<AppState>) -> Json<Vec<RouteHealth>> {
+    let router = state.llm_router.lock().unwrap().clone();
+    Json(router.map(|router| router.health()).unwrap_or_default())
+}
+
+/// Models of every configured provider, with their capability flags
+///
+/// The mock provider is always listed; Anthropic and OpenAI are listed when
+/// their API key is set, OpenAI also when `OPENAI_BASE_URL` points at a
+/// compatible server. Ollama lists the models pulled to the server at
+/// `OLLAMA_BASE_URL`, and nothing when no server answers there. The listing
+/// is reused for [`MODEL_LIST_CACHE_SECONDS`].
+async fn api_models(axum::extract::State(state): axum::extract::State<AppState>) -> Json<Vec<ModelInfo>> {
+    let now = chrono::Utc::now();
+    if let Some((listed_at, models)) = state.model_list.lock().unwrap().as_ref() {
+        if now - *listed_at < chrono::Duration::seconds(MODEL_LIST_CACHE_SECONDS) {
+            return Json(models.clone());
+        }
+    }
+
+    let clients: Vec<Arc<dyn LlmClient>> = LlmProvider::all()
+        .iter()
+        .filter_map(|&provider| llm_client_for(&state, provider, provider.default_model()).ok())
+        .collect();
+    let models = list_models(&clients).await;
+    *state.model_list.lock().unwrap() = Some((now, models.clone()));
+    Json(models)
+}
+
+/// Every model `clients` list; a provider that fails is logged and skipped
+async fn list_models(clients: &[Arc<dyn LlmClient>]) -> Vec<ModelInfo> {
+    let listings = futures::future::join_all(clients.iter().map(|client| client.list_models())).await;
+    clients
+        .iter()
+        .zip(listings)
+        .flat_map(|(client, listing)| {
+            listing.unwrap_or_else(|e| {
+                tracing::warn!(provider = %client.provider(), "Listing models failed: {}", e);
+                Vec::new()
+            })
+        })
+        .collect()
+}
+
+/// Replace the LLM client of the executor and the business agents without
+/// restarting the server
+async fn api_admin_set_llm_client(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Json(req): Json<SetLlmClientReq>,
+) -> Result<Json<SetLlmClientRes>, (StatusCode, String)> {
+    let provider: LlmProvider = req.provider.parse().map_err(error_response)?;
+    let model = req.model.unwrap_or_else(|| provider.default_model().to_string());
+    let client = llm_client_for(&state, provider, &model).map_err(error_response)?;
+
+    state.executor.set_client(client.clone());
+    state.business_state.set_llm_client(client);
+    state.set_llm_router(None, None);
+    Ok(Json(SetLlmClientRes {
+        provider: state.executor.provider().to_string(),
+        model,
+        is_mock: state.executor.is_mock(),
+    }))
+}
+
+/// Merge the posted settings over the running configuration and apply them
+#[instrument(skip(state, req))]
+async fn api_admin_reload(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Json(req): Json<PartialRuntimeConfig>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    let mut config = state.runtime_config.lock().unwrap().clone();
+    config.merge(req);
+    state.reload_config(config).map_err(error_response)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
 #[instrument(skip(state))]
 async fn api_agents_delete(
     axum::extract::State(state): axum::extract::State<AppState>,
@@ -449,6 +1252,7 @@ async fn api_agents_delete(
     state.registry.lock().unwrap().remove(&id);
     state.storage.lock().unwrap().remove(&id);
     state.messages.lock().unwrap().remove(&id);
+    state.costs.unassign_agent(&id);
     Json(true)
 }
 
@@ -456,20 +1260,25 @@ async fn api_health() -> Json<serde_json::Value> {
     Json(serde_json::json!({"status":"ok"}))
 }
 
-async fn api_version() -> Json<serde_json::Value> {
-    Json(serde_json::json!({"version":"0.1.0-alpha"}))
+async fn api_version(axum::extract::State(state): axum::extract::State<AppState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "version": "0.1.0-alpha",
+        "llm_provider": state.executor.provider().as_str(),
+        "llm_is_mock": state.executor.is_mock(),
+    }))
 }
 
 #[instrument(skip(state))]
 #[instrument(skip(state))]
 async fn api_agent_detail(
+    fields: Fields,
     axum::extract::State(state): axum::extract::State<AppState>,
     Path(id): Path<String>,
-) -> Json<Option<serde_json::Value>> {
+) -> Json<serde_json::Value> {
     let reg = state.registry.lock().unwrap();
     if let Some(agent) = reg.get_agent(&id) {
         let cfg: Vec<(String, String)> = agent.config.iter().map(|(k,v)| (k.clone(), v.clone())).collect();
-        return Json(Some(serde_json::json!({
+        return Json(fields.project(&serde_json::json!({
             "id": agent.id.to_string(),
             "name": agent.name,
             "description": agent.description,
@@ -481,11 +1290,45 @@ async fn api_agent_detail(
             "config": cfg,
         })));
     }
-    Json(None)
+    Json(serde_json::Value::Null)
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-struct AgentMessage { ts: String, from: String, to: String, content: String }
+#[instrument(skip(state))]
+async fn api_agent_snapshot(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path(id): Path<String>,
+) -> Json<Option<agentic_core::AgentSnapshot>> {
+    let reg = state.registry.lock().unwrap();
+    Json(reg.get_agent(&id).map(|agent| agent.snapshot()))
+}
+
+/// One entry of an agent's message history
+///
+/// Besides plain text, an entry is either an agent turn that called tools
+/// (`tool_calls`) or a tool's output (`tool_call_id`), so tool rounds replay
+/// into the next LLM request.
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct AgentMessage {
+    ts: String,
+    from: String,
+    to: String,
+    content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<ToolCall>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<String>,
+}
+
+impl AgentMessage {
+    fn to_llm_message(&self) -> Message {
+        match &self.tool_call_id {
+            Some(call_id) => Message::tool_result(call_id, &self.content),
+            None if !self.tool_calls.is_empty() => Message::tool_calls(&self.content, self.tool_calls.clone()),
+            None if self.from == "user" => Message::user(&self.content),
+            None => Message::assistant(&self.content),
+        }
+    }
+}
 
 #[derive(Deserialize)]
 struct SendMessageReq { content: String }
@@ -494,10 +1337,15 @@ struct SendMessageReq { content: String }
 async fn api_agent_messages(
     axum::extract::State(state): axum::extract::State<AppState>,
     Path(id): Path<String>,
-) -> Json<Vec<AgentMessage>> {
+) -> Result<Json<Vec<AgentMessage>>, (StatusCode, String)> {
+    let recipient = state.registry.lock().unwrap().get_agent(&id).map(|agent| (agent.id, agent.is_available));
+    if let Some((agent_id, available)) = recipient {
+        state.a2a.set_available(&agent_id, available).await;
+        deliver_a2a_messages(&state, &agent_id).await?;
+    }
     let map = state.messages.lock().unwrap();
     let v = map.get(&id).cloned().unwrap_or_default();
-    Json(v)
+    Ok(Json(v))
 }
 
 #[instrument(skip(state, req))]
@@ -505,14 +1353,105 @@ async fn api_agent_send_message(
     axum::extract::State(state): axum::extract::State<AppState>,
     Path(id): Path<String>,
     Json(req): Json<SendMessageReq>,
-) -> Json<bool> {
+) -> Result<Json<bool>, (StatusCode, String)> {
+    let agent = chat_agent(&state, &id)?;
+    let mut tokens = state
+        .executor
+        .stream_chat(&agent, chat_history(&state, &id), &req.content)
+        .await
+        .map_err(error_response)?;
+
+    let mut reply = String::new();
+    while let Some(delta) = tokens.recv().await {
+        reply.push_str(&delta.map_err(|e| error_response(e.into()))?);
+    }
+    record_exchange(&state, &id, &req.content, reply);
+    Ok(Json(true))
+}
+
+#[derive(Deserialize)]
+struct ChatReq { message: String }
+
+/// Stream the agent's reply as SSE `token` events followed by `done`
+///
+/// The exchange is added to the agent's message history once the reply is
+/// complete; `done` carries the stored reply. A client that disconnects
+/// early drops the stream, which cancels the LLM call, and nothing is stored.
+#[instrument(skip(state, req))]
+async fn api_agent_chat(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<ChatReq>,
+) -> Result<Sse<impl futures::Stream<Item = Result<Event, Infallible>>>, (StatusCode, String)> {
+    let agent = chat_agent(&state, &id)?;
+    let tokens = state
+        .executor
+        .stream_chat(&agent, chat_history(&state, &id), &req.message)
+        .await
+        .map_err(error_response)?;
+
+    let events = futures::stream::unfold(Some((tokens, String::new())), move |turn| {
+        let (state, id, message) = (state.clone(), id.clone(), req.message.clone());
+        async move {
+            let (mut tokens, mut reply) = turn?;
+            let event = match tokens.recv().await {
+                Some(Ok(delta)) => {
+                    reply.push_str(&delta);
+                    let event = Event::default().event("token").data(delta);
+                    return Some((Ok(event), Some((tokens, reply))));
+                }
+                Some(Err(e)) => Event::default().event("error").data(error_response(e.into()).1),
+                None => {
+                    let stored = record_exchange(&state, &id, &message, reply);
+                    Event::default().event("done").json_data(stored).unwrap_or_default()
+                }
+            };
+            Some((Ok(event), None))
+        }
+    });
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+fn chat_agent(state: &AppState, id: &str) -> Result<agentic_core::Agent, (StatusCode, String)> {
+    state
+        .registry
+        .lock()
+        .unwrap()
+        .get_agent(id)
+        .cloned()
+        .ok_or_else(|| error_response(agentic_core::Error::AgentNotFound(id.to_string())))
+}
+
+/// Stored messages for `id` as LLM turns
+fn chat_history(state: &AppState, id: &str) -> Vec<Message> {
+    let map = state.messages.lock().unwrap();
+    map.get(id)
+        .map(|messages| {
+            messages.iter().map(AgentMessage::to_llm_message).collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Append a user message and the agent's reply; returns the reply
+///
+/// The stored copies go through the redaction policy; the returned reply
+/// is the one the agent gave.
+fn record_exchange(state: &AppState, id: &str, content: &str, reply: String) -> AgentMessage {
     let now = chrono::Utc::now().to_rfc3339();
+    let answer = AgentMessage { ts: now.clone(), from: id.to_string(), to: "user".into(), content: reply, ..Default::default() };
+    let stored_answer = AgentMessage { content: state.redaction.redact(&answer.content), ..answer.clone() };
     let mut map = state.messages.lock().unwrap();
-    let entry = map.entry(id.clone()).or_insert_with(Vec::new);
-    entry.push(AgentMessage { ts: now.clone(), from: "user".into(), to: id.clone(), content: req.content.clone() });
-    // Mock agent response: uppercase echo
-    entry.push(AgentMessage { ts: now, from: id.clone(), to: "user".into(), content: format!("{}", req.content.to_uppercase()) });
-    Json(true)
+    let entry = map.entry(id.to_string()).or_insert_with(Vec::new);
+    entry.push(AgentMessage {
+        ts: now,
+        from: "user".into(),
+        to: id.to_string(),
+        content: state.redaction.redact(content),
+        ..Default::default()
+    });
+    entry.push(stored_answer);
+    answer
 }
 
 #[derive(Serialize)]
@@ -521,33 +1460,113 @@ struct McpInvokeRes { tool: String, input: String, output: String }
 #[derive(Deserialize)]
 struct McpInvokeReq { tool: String, input: String }
 
-#[instrument]
+#[instrument(skip(state))]
 async fn api_mcp_tools(
+    axum::extract::State(state): axum::extract::State<AppState>,
     Path(_id): Path<String>,
 ) -> Json<Vec<agentic_protocols::McpTool>> {
-    let mcp = MockMcpAdapter;
-    Json(mcp.list_tools())
+    Json(state.mcp_invoker.list_tools())
 }
 
-#[instrument]
+#[instrument(skip(state, req))]
 async fn api_mcp_invoke(
-    Path(_id): Path<String>,
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path(id): Path<String>,
     Json(req): Json<McpInvokeReq>,
-) -> Json<McpInvokeRes> {
-    let mcp = MockMcpAdapter;
-    let out = mcp.invoke(&req.tool, &req.input);
-    Json(McpInvokeRes { tool: req.tool, input: req.input, output: out })
+) -> Result<Json<McpInvokeRes>, (StatusCode, String)> {
+    // Tools of a registered agent get its secrets; they never appear in the response
+    let agent = state.registry.lock().unwrap().get_agent(&id).cloned();
+    // Times out with 504; dropping the future (client gone) abandons the call too.
+    // The executor's sandbox applies MAX_TOOL_DEPTH and the wall-time limit.
+    let (invoker, tool, input) = (&state.mcp_invoker, req.tool.as_str(), req.input.as_str());
+    let out = state
+        .executor
+        .run_tool(agent.as_ref(), tool, |env| async move { invoker.invoke_with_env(tool, input, &env).await })
+        .await
+        .map_err(error_response)?;
+    Ok(Json(McpInvokeRes { tool: req.tool, input: req.input, output: out }))
+}
+
+/// Hits, misses and entries of the MCP tool result cache
+async fn api_mcp_cache_stats(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Json<ToolCacheStats> {
+    Json(state.mcp.cache_stats())
+}
+
+/// Hits, misses and size of the executor's result cache
+async fn api_execution_cache_stats(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Json<ExecutionCacheStats> {
+    Json(state.executor.result_cache().map(|cache| cache.stats()).unwrap_or_default())
 }
 
 #[derive(Serialize, Deserialize)]
 struct A2aSendReq { from: String, to: String, content: String }
 
-#[instrument]
+/// Route a message into the recipient's message history over the A2A bus
+///
+/// Registered agents receive it at once, or once they are available again
+/// (`queued`); messages to unknown ids are dead-lettered and can be inspected
+/// at `/api/protocols/a2a/dead-letters`.
+#[instrument(skip(state, req))]
 async fn api_a2a_send(
+    axum::extract::State(state): axum::extract::State<AppState>,
     Json(req): Json<A2aSendReq>,
-) -> Json<agentic_protocols::A2aEnvelope> {
-    let a2a = MockA2aAdapter;
-    Json(a2a.envelope(&req.from, &req.to, &req.content))
+) -> Result<Json<A2aSendResult>, (StatusCode, String)> {
+    let envelope = MockA2aAdapter.envelope(&req.from, &req.to, &req.content);
+    let recipient = state.registry.lock().unwrap().get_agent(&req.to).map(|agent| (agent.id, agent.is_available));
+    let message = A2aMessage::new(
+        agentic_core::AgentId::from_string(&req.from).unwrap_or_default(),
+        req.from,
+        recipient.map(|(agent_id, _)| agent_id).unwrap_or_default(),
+        req.to,
+        message_types::REQUEST.to_string(),
+        serde_json::json!({ "content": state.redaction.redact(&req.content) }),
+    )
+    .stamped(state.a2a.bus().clock().as_ref());
+
+    let delivery = match recipient {
+        Some((agent_id, available)) => {
+            state.a2a.set_available(&agent_id, available).await;
+            state.a2a.bus().send_reliable(message).await.map_err(error_response)?;
+            deliver_a2a_messages(&state, &agent_id).await?;
+            if available { A2aDelivery::Delivered } else { A2aDelivery::Queued }
+        }
+        None => {
+            state.a2a.bus().dead_letter(message).await.map_err(error_response)?;
+            A2aDelivery::DeadLettered
+        }
+    };
+
+    Ok(Json(A2aSendResult { envelope, delivery, recipient_known: recipient.is_some() }))
+}
+
+/// Append the A2A messages that reached `agent_id` to its message history
+async fn deliver_a2a_messages(state: &AppState, agent_id: &agentic_core::AgentId) -> Result<(), (StatusCode, String)> {
+    let to = agent_id.to_string();
+    state
+        .a2a
+        .deliver(agent_id, |message| {
+            let entry = AgentMessage {
+                ts: message.envelope.timestamp.to_rfc3339(),
+                from: message.envelope.from.agent_name.clone(),
+                to: to.clone(),
+                content: message.payload.data["content"].as_str().unwrap_or_default().to_string(),
+                ..Default::default()
+            };
+            state.messages.lock().unwrap().entry(to.clone()).or_default().push(entry);
+        })
+        .await
+        .map_err(error_response)?;
+    Ok(())
+}
+
+/// A2A messages that were given up on, oldest first
+async fn api_a2a_dead_letters(
+    axum::extract::State(state): axum::extract::State<AppState>,
+) -> Json<Vec<DeadLetter>> {
+    Json(state.a2a.bus().dead_letters().await)
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -555,10 +1574,30 @@ struct Workflow {
     id: String,
     supervisor_id: String,
     worker_ids: Vec<String>,
+    /// Config applied to every member when the workflow was created
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    workflow_defaults: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Deserialize)]
-struct WorkflowCreateReq { supervisor: String, n: usize, template_id: String }
+struct WorkflowCreateReq {
+    supervisor: String,
+    n: usize,
+    template_id: String,
+    /// Worker names become `<prefix>-1..N` (default "Worker")
+    #[serde(default)]
+    worker_name_prefix: Option<String>,
+    /// Template for workers when it differs from the supervisor's
+    #[serde(default)]
+    worker_template_id: Option<String>,
+    /// Config shared by every member (e.g. `mcp:endpoint`), over the
+    /// template's config
+    #[serde(default)]
+    workflow_defaults: HashMap<String, serde_json::Value>,
+    /// Config for single members by name, over `workflow_defaults`
+    #[serde(default)]
+    member_config: HashMap<String, HashMap<String, serde_json::Value>>,
+}
 
 #[derive(Serialize)]
 struct WorkflowCreateRes { id: String, supervisor_id: String, worker_ids: Vec<String> }
@@ -567,41 +1606,97 @@ struct WorkflowCreateRes { id: String, supervisor_id: String, worker_ids: Vec<St
 async fn api_workflows_create(
     axum::extract::State(state): axum::extract::State<AppState>,
     Json(req): Json<WorkflowCreateReq>,
-) -> Json<WorkflowCreateRes> {
+) -> Result<Json<WorkflowCreateRes>, (StatusCode, String)> {
+    let n = req.n.max(1);
+    if n > state.max_workflow_workers {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("requested {} workers, maximum is {}", n, state.max_workflow_workers),
+        ));
+    }
+
+    // Validate every referenced template before creating anything
+    let worker_template_id = req.worker_template_id.clone().unwrap_or_else(|| req.template_id.clone());
+    for tid in [&req.template_id, &worker_template_id] {
+        if state.standards.registry().get_template(tid).is_none() {
+            return Err((StatusCode::BAD_REQUEST, format!("unknown template: {}", tid)));
+        }
+    }
+
     // create supervisor
-    let sup_name = req.supervisor;
-    let (mut sup_agent, sup_genome) = state.factory.create_from_template(&req.template_id, &sup_name, "Supervisor agent").unwrap();
+    let (mut sup_agent, sup_genome) = state.factory.create_from_template(&req.template_id, &req.supervisor, "Supervisor agent").map_err(error_response)?;
+    apply_member_config(&mut sup_agent, &req);
     sup_agent.set_status(agentic_core::agent::AgentStatus::Running);
     let sup_id = sup_agent.id.to_string();
-    state.registry.lock().unwrap().register(sup_agent, sup_genome);
 
     // create workers
-    let mut workers = Vec::new();
-    for i in 0..req.n.max(1) {
-        let name = format!("Worker-{}", i + 1);
-        let (mut w_agent, w_genome) = state.factory.create_from_template(&req.template_id, &name, "Worker agent").unwrap();
+    let prefix = req.worker_name_prefix.as_deref().unwrap_or("Worker");
+    let mut created = Vec::with_capacity(n);
+    for i in 0..n {
+        let name = format!("{}-{}", prefix, i + 1);
+        let (mut w_agent, w_genome) = state.factory.create_from_template(&worker_template_id, &name, "Worker agent").map_err(error_response)?;
+        apply_member_config(&mut w_agent, &req);
         w_agent.set_status(agentic_core::agent::AgentStatus::Running);
-        let wid = w_agent.id.to_string();
-        state.registry.lock().unwrap().register(w_agent, w_genome);
-        workers.push(wid);
+        created.push((w_agent, w_genome));
+    }
+
+    // register only once every agent was created successfully
+    let workers: Vec<String> = created.iter().map(|(a, _)| a.id.to_string()).collect();
+    {
+        let mut reg = state.registry.lock().unwrap();
+        let mut registered: Vec<String> = Vec::with_capacity(n + 1);
+        for (agent, genome) in std::iter::once((sup_agent, sup_genome)).chain(created) {
+            let id = agent.id.to_string();
+            if let Err(e) = reg.register(agent, genome) {
+                // all or nothing: drop the agents registered so far
+                for id in &registered {
+                    reg.remove(id);
+                }
+                return Err(error_response(e));
+            }
+            registered.push(id);
+        }
     }
 
     let wf_id = format!("wf-{}", chrono::Utc::now().timestamp_millis());
-    state.workflows.lock().unwrap().insert(wf_id.clone(), Workflow { id: wf_id.clone(), supervisor_id: sup_id.clone(), worker_ids: workers.clone() });
-    state.storage.lock().unwrap().add_workflow(Workflow { id: wf_id.clone(), supervisor_id: sup_id.clone(), worker_ids: workers.clone() });
-    Json(WorkflowCreateRes { id: wf_id, supervisor_id: sup_id, worker_ids: workers })
+    let workflow = Workflow {
+        id: wf_id.clone(),
+        supervisor_id: sup_id.clone(),
+        worker_ids: workers.clone(),
+        workflow_defaults: req.workflow_defaults,
+    };
+    assign_workflow_costs(&state.costs, &workflow);
+    state.workflows.lock().unwrap().insert(wf_id.clone(), workflow.clone());
+    state.storage.lock().unwrap().add_workflow(workflow);
+    Ok(Json(WorkflowCreateRes { id: wf_id, supervisor_id: sup_id, worker_ids: workers }))
+}
+
+/// Attribute the LLM spend of every member of `workflow` to it
+fn assign_workflow_costs(costs: &CostTracker, workflow: &Workflow) {
+    for agent_id in std::iter::once(&workflow.supervisor_id).chain(&workflow.worker_ids) {
+        costs.assign_workflow(agent_id.clone(), workflow.id.clone());
+    }
+}
+
+/// Layer the workflow's shared config, then the member's own, over `agent`'s
+fn apply_member_config(agent: &mut agentic_core::Agent, req: &WorkflowCreateReq) {
+    let own = req.member_config.get(&agent.name).into_iter().flatten();
+    for (key, value) in req.workflow_defaults.iter().chain(own) {
+        agent.config.insert(key.clone(), value.clone());
+    }
 }
 
 #[instrument(skip(state))]
 async fn api_workflows_list(
+    format: ResponseFormat,
     axum::extract::State(state): axum::extract::State<AppState>,
-) -> Json<Vec<Workflow>> {
+) -> Negotiated<Vec<Workflow>> {
     let mem: Vec<Workflow> = state.workflows.lock().unwrap().values().cloned().collect();
     if mem.is_empty() {
         let persisted = state.storage.lock().unwrap().list_workflows();
-        return Json(persisted);
+        return format.respond(persisted);
     }
-    Json(mem)
+    format.respond(mem)
 }
 
 #[instrument(skip(state))]
@@ -612,3 +1707,1013 @@ async fn api_workflows_get(
     let wf = state.workflows.lock().unwrap().get(&id).cloned();
     Json(wf)
 }
+
+#[derive(Deserialize)]
+struct WorkflowRunReq {
+    input: String,
+    /// How the supervisor combines worker outputs; defaults by `task_kind`
+    #[serde(default)]
+    strategy: Option<AggregationStrategy>,
+    /// What the workers are doing (default `analysis`)
+    #[serde(default)]
+    task_kind: Option<TaskKind>,
+}
+
+#[derive(Serialize)]
+struct WorkerRunRes { agent_id: String, success: bool, output: String, error: Option<String> }
+
+#[derive(Serialize)]
+struct WorkflowRunRes { id: String, strategy: AggregationStrategy, workers: Vec<WorkerRunRes>, output: String }
+
+/// Run every worker on the input, then combine their results in the supervisor step
+#[instrument(skip(state, req))]
+async fn api_workflows_run(
+    axum::extract::State(state): axum::extract::State<AppState>,
+    Path(id): Path<String>,
+    Json(req): Json<WorkflowRunReq>,
+) -> Result<Json<WorkflowRunRes>, (StatusCode, String)> {
+    use agentic_core::Error;
+
+    let wf = state
+        .workflows
+        .lock()
+        .unwrap()
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| error_response(Error::WorkflowNotFound(id.clone())))?;
+    let (mut supervisor, workers) = {
+        let reg = state.registry.lock().unwrap();
+        let lookup = |agent_id: &String| {
+            reg.get_agent(agent_id)
+                .cloned()
+                .ok_or_else(|| error_response(Error::AgentNotFound(agent_id.clone())))
+        };
+        let workers = wf.worker_ids.iter().map(lookup).collect::<Result<Vec<_>, _>>()?;
+        (lookup(&wf.supervisor_id)?, workers)
+    };
+
+    let runs = workers.into_iter().map(|mut agent| {
+        let executor = state.executor.clone();
+        let input = req.input.clone();
+        async move {
+            let context = ExecutionContext::new(agent.id);
+            let result = executor
+                .execute(&mut agent, &input, &context)
+                .await
+                .unwrap_or_else(|e| ExecutionResult::failure(e.to_string(), 0));
+            (agent.id.to_string(), result)
+        }
+    });
+    let (agent_ids, results): (Vec<String>, Vec<ExecutionResult>) =
+        futures::future::join_all(runs).await.into_iter().unzip();
+
+    let strategy = req
+        .strategy
+        .unwrap_or_else(|| AggregationStrategy::for_task(req.task_kind.unwrap_or(TaskKind::Analysis)));
+    let output = strategy
+        .aggregate(&req.input, &results, &mut supervisor, state.executor.as_ref())
+        .await
+        .map_err(error_response)?;
+
+    let workers = agent_ids
+        .into_iter()
+        .zip(results)
+        .map(|(agent_id, r)| WorkerRunRes { agent_id, success: r.success, output: r.output, error: r.error })
+        .collect();
+    Ok(Json(WorkflowRunRes { id, strategy, workers, output }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentic_protocols::test_support::SlowAdapter;
+
+    fn temp_store_path(name: &str) -> PathBuf {
+        let mut p = std::env::temp_dir();
+        p.push(format!("agentic_store_{}_{}.json", name, uuid::Uuid::new_v4()));
+        p
+    }
+
+    #[test]
+    fn test_v1_store_migrates_to_current_version() {
+        let path = temp_store_path("v1");
+        let v1 = serde_json::json!({
+            "agents": [{"id": "a1", "template_id": "standard_worker", "name": "w", "description": "d"}],
+            "workflows": [{"id": "wf-1", "supervisor_id": "a1", "worker_ids": []}],
+        });
+        fs::write(&path, serde_json::to_vec(&v1).unwrap()).unwrap();
+
+        let store = PersistedStore::load(path.clone()).unwrap();
+        assert_eq!(store.list().len(), 1);
+        assert_eq!(store.list_workflows().len(), 1);
+
+        let on_disk: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(on_disk["version"], STORE_VERSION);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_legacy_agents_array_is_migrated() {
+        let (data, found) = PersistedData::from_slice(
+            br#"[{"id": "a1", "template_id": "t", "name": "n", "description": "d"}]"#,
+        )
+        .unwrap();
+        assert_eq!(found, 1);
+        assert_eq!(data.version, STORE_VERSION);
+        assert_eq!(data.agents.len(), 1);
+        assert!(data.workflows.is_empty());
+    }
+
+    #[test]
+    fn test_future_version_is_rejected() {
+        let path = temp_store_path("future");
+        fs::write(&path, br#"{"version": 99, "agents": [], "workflows": []}"#).unwrap();
+
+        let err = PersistedStore::load(path.clone()).err().expect("future version must fail");
+        assert!(err.to_string().contains("newer than supported"));
+
+        // the file must be left untouched
+        assert!(fs::read_to_string(&path).unwrap().contains("99"));
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_corrupt_store_is_moved_aside() {
+        let path = temp_store_path("corrupt");
+        fs::write(&path, b"{not json").unwrap();
+
+        let store = PersistedStore::load_or_reset(path.clone(), StoreConfig::default());
+        assert!(store.list().is_empty());
+
+        let mut aside = path.clone().into_os_string();
+        aside.push(".corrupt");
+        assert_eq!(fs::read(&aside).unwrap(), b"{not json");
+        assert!(!path.exists());
+        let _ = fs::remove_file(aside);
+    }
+
+    #[test]
+    fn test_compact_pretty_and_gzip_stores_round_trip() {
+        let agent = StoredAgent { id: "a1".into(), template_id: "t".into(), name: "n".into(), description: "d".into() };
+        let configs = [
+            StoreConfig { pretty: true, gzip: false },
+            StoreConfig { pretty: false, gzip: false },
+            StoreConfig { pretty: false, gzip: true },
+        ];
+        for config in configs {
+            let path = temp_store_path("format");
+            let mut store = PersistedStore::load_with(path.clone(), config).unwrap();
+            store.add(agent.clone());
+            store.add_workflow(Workflow { id: "wf-1".into(), supervisor_id: "a1".into(), worker_ids: vec![], workflow_defaults: HashMap::new() });
+
+            let raw = fs::read(&path).unwrap();
+            assert_eq!(raw.starts_with(&[0x1f, 0x8b]), config.gzip);
+            if !config.gzip {
+                assert_eq!(raw.contains(&b'\n'), config.pretty);
+            }
+
+            // Loading doesn't depend on the format the file was written in
+            let reloaded = PersistedStore::load(path.clone()).unwrap();
+            assert_eq!(reloaded.get("a1").unwrap().name, "n");
+            assert_eq!(reloaded.list_workflows().len(), 1);
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    fn test_state(name: &str) -> (AppState, PathBuf) {
+        let path = temp_store_path(name);
+        let mut state = AppState::new();
+        state.storage = Arc::new(Mutex::new(PersistedStore::load(path.clone()).unwrap()));
+        (state, path)
+    }
+
+    fn workflow_req(n: usize, worker_template_id: Option<&str>) -> WorkflowCreateReq {
+        WorkflowCreateReq {
+            supervisor: "Lead".into(),
+            n,
+            template_id: "tmpl.standard.worker".into(),
+            worker_name_prefix: Some("Crawler".into()),
+            worker_template_id: worker_template_id.map(String::from),
+            workflow_defaults: HashMap::new(),
+            member_config: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compliance_recheck_splits_agents() {
+        let (state, path) = test_state("recheck");
+        let req = CreateAgentReq { template_id: "tmpl.standard.worker".into(), name: "good".into(), description: "d".into(), provider: None, model: None };
+        let Json(good) = api_agents_create(axum::extract::State(state.clone()), Json(req)).await.unwrap();
+
+        // strip a required capability so the template's MCP standard fails
+        let (mut agent, genome) = state.factory.create_from_template("tmpl.standard.worker", "bad", "d").unwrap();
+        agent.config.remove("cap:mcp.tools");
+        let bad_id = agent.id.to_string();
+        state.registry.lock().unwrap().register(agent, genome).unwrap();
+        state.storage.lock().unwrap().add(StoredAgent { id: bad_id.clone(), template_id: "tmpl.standard.worker".into(), name: "bad".into(), description: "d".into() });
+
+        let Json(res) = api_compliance_recheck(axum::extract::State(state)).await;
+        assert_eq!(res.checked, 2);
+        assert_eq!(res.compliant, vec![good.id]);
+        assert_eq!(res.non_compliant.len(), 1);
+        assert_eq!(res.non_compliant[0].id, bad_id);
+        assert_eq!(res.non_compliant[0].reasons, vec!["missing capability mcp.tools".to_string()]);
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_remediate_makes_agent_compliant() {
+        let (state, path) = test_state("remediate");
+        let (mut agent, genome) = state.factory.create_from_template("tmpl.standard.supervisor", "lead", "d").unwrap();
+        agent.config.remove("cap:mcp.tools");
+        agent.config.remove("protocol:a2a");
+        let id = agent.id.to_string();
+        state.registry.lock().unwrap().register(agent, genome).unwrap();
+        state.storage.lock().unwrap().add(StoredAgent { id: id.clone(), template_id: "tmpl.standard.supervisor".into(), name: "lead".into(), description: "d".into() });
+
+        let Json(before) = api_agent_compliance(Fields::default(), axum::extract::State(state.clone()), Path(id.clone())).await;
+        assert_eq!(before["compliant"], false);
+        assert!(before["suggested_fixes"].as_array().unwrap().contains(&serde_json::json!({"protocol:a2a": "1.0"})));
+
+        let Json(after) = api_agent_remediate(axum::extract::State(state.clone()), Path(id.clone())).await.unwrap();
+        assert!(after.compliant, "{:?}", after.reasons());
+        let agent = state.registry.lock().unwrap().get_agent(&id).unwrap().clone();
+        assert_eq!(agent.config["cap:mcp.tools"], "1.0.0");
+
+        let missing = api_agent_remediate(axum::extract::State(state), Path("missing".into())).await;
+        assert_eq!(missing.err().unwrap().0, StatusCode::NOT_FOUND);
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_meta_metrics_reflect_validation_run() {
+        use agentic_business::models::{Opportunity, ProductType};
+
+        let state = AppState::new();
+        let Json(before) = api_meta_metrics(axum::extract::State(state.clone())).await;
+        assert!(before.meta_agents.is_empty());
+
+        let opportunity = Opportunity::new(
+            "Test SaaS".to_string(),
+            "A test product".to_string(),
+            "SaaS".to_string(),
+            ProductType::SaaS,
+        );
+        let id = opportunity.id.to_string();
+        state.business_state.discovered_opportunities.lock().await.push(opportunity);
+
+        let _ = business::api_validate_opportunity(
+            axum::extract::State(state.business_state.clone()),
+            Path(id),
+        )
+        .await;
+
+        let Json(after) = api_meta_metrics(axum::extract::State(state)).await;
+        assert_eq!(after.meta_agents.len(), 1);
+        assert_eq!(after.meta_agents[0].name, "BusinessValidationManager");
+        assert_eq!(after.meta_agents[0].executions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_decisions_from_separate_requests_share_one_agent() {
+        use agentic_business::models::{Opportunity, ProductType};
+        use axum::extract::Query;
+
+        let state = AppState::new();
+        for title in ["First SaaS", "Second SaaS"] {
+            let opportunity = Opportunity::new(title.to_string(), String::new(), "SaaS".to_string(), ProductType::SaaS);
+            let id = opportunity.id.to_string();
+            state.business_state.discovered_opportunities.lock().await.push(opportunity);
+            business::api_validate_opportunity(axum::extract::State(state.business_state.clone()), Path(id))
+                .await
+                .unwrap();
+        }
+
+        let agent_id = state.business_state.validation_agent_id.to_string();
+        let Json(decisions) = business::api_list_decisions(
+            axum::extract::State(state.business_state.clone()),
+            Query(business::DecisionQuery { agent: Some(agent_id) }),
+        )
+        .await;
+        assert_eq!(decisions.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_stale_opportunities_are_flagged_and_filtered() {
+        use agentic_business::models::{Opportunity, ProductType};
+        use axum::extract::Query;
+
+        let state = AppState::new();
+        let fresh = Opportunity::new("Fresh".into(), String::new(), "SaaS".into(), ProductType::SaaS);
+        let mut old = Opportunity::new("Old".into(), String::new(), "SaaS".into(), ProductType::SaaS);
+        old.discovered_at -= state.business_state.opportunity_ttl + chrono::Duration::days(1);
+        let old_id = old.id;
+        state.business_state.discovered_opportunities.lock().await.extend([fresh, old]);
+
+        let Json(all) = business::api_list_opportunities(
+            axum::extract::State(state.business_state.clone()),
+            Query(business::OpportunityListQuery::default()),
+        )
+        .await;
+        assert_eq!(all.total, 2);
+        assert_eq!(all.stale, vec![old_id]);
+
+        let Json(recent) = business::api_list_opportunities(
+            axum::extract::State(state.business_state.clone()),
+            Query(business::OpportunityListQuery { max_age_days: Some(7) }),
+        )
+        .await;
+        assert_eq!(recent.total, 1);
+        assert_eq!(recent.opportunities[0].title, "Fresh");
+        assert!(recent.stale.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_slow_mcp_invoke_returns_gateway_timeout() {
+        let mut state = AppState::new();
+        state.mcp_invoker = Arc::new(
+            TimedMcpAdapter::new(Arc::new(SlowAdapter::new(std::time::Duration::from_secs(5))))
+                .with_timeout(std::time::Duration::from_millis(20)),
+        );
+
+        let req = McpInvokeReq { tool: "slow".into(), input: "x".into() };
+        let err = api_mcp_invoke(axum::extract::State(state.clone()), Path("a1".into()), Json(req))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.0, StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(state.mcp_invoker.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_agent_secrets_reach_tools_but_not_detail() {
+        /// Answers with the secret its tool needs
+        struct KeyedAdapter;
+
+        #[async_trait::async_trait]
+        impl McpAdapter for KeyedAdapter {
+            fn list_tools(&self) -> Vec<agentic_protocols::McpTool> {
+                Vec::new()
+            }
+
+            async fn invoke(&self, _tool: &str, _input: &str) -> String {
+                "no key".to_string()
+            }
+
+            async fn invoke_with_env(&self, _tool: &str, _input: &str, env: &agentic_core::AgentEnv) -> String {
+                env.get("SEARCH_API_KEY").unwrap_or("no key").to_string()
+            }
+        }
+
+        let (mut state, path) = test_state("secrets");
+        state.mcp_invoker = Arc::new(TimedMcpAdapter::new(Arc::new(KeyedAdapter)));
+        let req = CreateAgentReq { template_id: "tmpl.standard.worker".into(), name: "w1".into(), description: "d".into(), provider: None, model: None };
+        let Json(created) = api_agents_create(axum::extract::State(state.clone()), Json(req)).await.unwrap();
+        let secrets = HashMap::from([("SEARCH_API_KEY".to_string(), Some("sk-search-secret".to_string()))]);
+        let Json(set) = api_agent_secrets_set(axum::extract::State(state.clone()), Path(created.id.clone()), Json(secrets))
+            .await
+            .unwrap();
+        assert_eq!(set.keys, vec!["SEARCH_API_KEY"]);
+        assert!(!serde_json::to_string(&set).unwrap().contains("sk-search-secret"));
+
+        let req = McpInvokeReq { tool: "search".into(), input: "rust".into() };
+        let Json(res) = api_mcp_invoke(axum::extract::State(state.clone()), Path(created.id.clone()), Json(req))
+            .await
+            .unwrap();
+        assert_eq!(res.output, "sk-search-secret");
+
+        let Json(detail) = api_agent_detail(Fields::default(), axum::extract::State(state.clone()), Path(created.id.clone())).await;
+        let detail = serde_json::to_string(&detail).unwrap();
+        assert!(detail.contains("w1"));
+        assert!(!detail.contains("sk-search-secret"));
+        assert!(!detail.contains("SEARCH_API_KEY"));
+
+        let secrets = HashMap::from([("SEARCH_API_KEY".to_string(), None)]);
+        let Json(set) = api_agent_secrets_set(axum::extract::State(state.clone()), Path(created.id.clone()), Json(secrets))
+            .await
+            .unwrap();
+        assert!(set.keys.is_empty());
+        let err = api_agent_secrets_set(axum::extract::State(state.clone()), Path("missing".into()), Json(HashMap::new()))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.0, StatusCode::NOT_FOUND);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_chat_history_replays_tool_round() {
+        let (state, path) = test_state("tool-history");
+        let call = ToolCall { id: "call_1".into(), name: "search".into(), input: serde_json::json!({"q": "rust"}) };
+        let entry = |from: &str, content: &str| AgentMessage { from: from.into(), content: content.into(), ..Default::default() };
+        state.messages.lock().unwrap().insert(
+            "a1".into(),
+            vec![
+                entry("user", "Find Rust news"),
+                AgentMessage { tool_calls: vec![call.clone()], ..entry("a1", "") },
+                AgentMessage { tool_call_id: Some("call_1".into()), ..entry("search", "Rust 1.90 released") },
+                entry("a1", "Rust 1.90 is out."),
+            ],
+        );
+
+        assert_eq!(
+            chat_history(&state, "a1"),
+            vec![
+                Message::user("Find Rust news"),
+                Message::tool_calls("", vec![call]),
+                Message::tool_result("call_1", "Rust 1.90 released"),
+                Message::assistant("Rust 1.90 is out."),
+            ]
+        );
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_a2a_send_reports_delivery() {
+        let (state, path) = test_state("a2a");
+        let req = CreateAgentReq { template_id: "tmpl.standard.worker".into(), name: "w1".into(), description: "d".into(), provider: None, model: None };
+        let Json(created) = api_agents_create(axum::extract::State(state.clone()), Json(req)).await.unwrap();
+
+        let send = |to: &str| A2aSendReq { from: "planner".into(), to: to.into(), content: "start".into() };
+        let Json(sent) = api_a2a_send(axum::extract::State(state.clone()), Json(send(&created.id))).await.unwrap();
+        assert_eq!(sent.delivery, A2aDelivery::Delivered);
+        assert!(sent.recipient_known);
+        let Json(inbox) = api_agent_messages(axum::extract::State(state.clone()), Path(created.id.clone())).await.unwrap();
+        assert_eq!((inbox[0].from.as_str(), inbox[0].content.as_str()), ("planner", "start"));
+        assert_eq!(state.a2a.bus().unacked_count().await, 0);
+
+        let Json(lost) = api_a2a_send(axum::extract::State(state.clone()), Json(send("no-such-agent"))).await.unwrap();
+        assert_eq!(lost.delivery, A2aDelivery::DeadLettered);
+        assert!(!lost.recipient_known);
+        assert!(state.messages.lock().unwrap().get("no-such-agent").is_none());
+        let Json(dead) = api_a2a_dead_letters(axum::extract::State(state.clone())).await;
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].message.envelope.to.agent_name, "no-such-agent");
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_agents_list_negotiates_json_and_msgpack() {
+        use tower::ServiceExt;
+
+        let (state, path) = test_state("negotiate");
+        let req = CreateAgentReq { template_id: "tmpl.standard.worker".into(), name: "w1".into(), description: "d".into(), provider: None, model: None };
+        let Json(created) = api_agents_create(axum::extract::State(state.clone()), Json(req)).await.unwrap();
+        let app = router(state);
+
+        let get_agents = |accept: &str| {
+            axum::http::Request::get("/api/agents")
+                .header(axum::http::header::ACCEPT, accept)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let res = app.clone().oneshot(get_agents("application/json")).await.unwrap();
+        assert_eq!(res.headers()[axum::http::header::CONTENT_TYPE], "application/json");
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let agents: Vec<(String, String)> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(agents, vec![(created.id.clone(), "w1".to_string())]);
+
+        let res = app.oneshot(get_agents(negotiate::MSGPACK_CONTENT_TYPE)).await.unwrap();
+        assert_eq!(res.headers()[axum::http::header::CONTENT_TYPE], negotiate::MSGPACK_CONTENT_TYPE);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let agents: Vec<(String, String)> = rmp_serde::from_slice(&body).unwrap();
+        assert_eq!(agents, vec![(created.id, "w1".to_string())]);
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_app_creates_and_lists_agents() {
+        let app = test_app::TestApp::new();
+        let id = app.create_agent("w1").await;
+
+        let agents: Vec<(String, String)> = app.get("/api/agents").await;
+        assert_eq!(agents, vec![(id.clone(), "w1".to_string())]);
+
+        let (status, _) = app.request(axum::http::Method::DELETE, &format!("/api/agents/{}", id), None).await;
+        assert!(status.is_success());
+        let agents: Vec<(String, String)> = app.get("/api/agents").await;
+        assert!(agents.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_models_endpoint_aggregates_providers() {
+        let mock_models = LlmProvider::Mock.models().len();
+        let clients: Vec<Arc<dyn LlmClient>> = vec![Arc::new(MockLlmClient::default()), Arc::new(MockLlmClient::new("b"))];
+        assert_eq!(list_models(&clients).await.len(), 2 * mock_models);
+
+        let app = test_app::TestApp::new();
+        let models: Vec<ModelInfo> = app.get("/api/models").await;
+        let mock: Vec<&str> = models.iter().filter(|m| m.provider == LlmProvider::Mock).map(|m| m.id.as_str()).collect();
+        assert_eq!(mock, LlmProvider::Mock.models());
+        assert!(app.state.model_list.lock().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_llm_health_reports_routed_providers() {
+        let app = test_app::TestApp::new();
+        let health: Vec<serde_json::Value> = app.get("/api/llm/health").await;
+        assert!(health.is_empty());
+
+        let router = Arc::new(RoutingLlmClient::new().with_provider(Arc::new(MockLlmClient::default())));
+        app.state.set_llm_router(Some(router), None);
+        let health: Vec<serde_json::Value> = app.get("/api/llm/health").await;
+        assert_eq!(health.len(), 1);
+        assert_eq!(health[0]["provider"], "Mock");
+        assert_eq!(health[0]["healthy"], true);
+
+        // A client for one provider replaces the router
+        let _: SetLlmClientRes = app.admin_post("/api/admin/llm-client", &serde_json::json!({"provider": "mock"})).await;
+        assert!(app.state.llm_router.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sdlc_runs_are_recorded_in_meta_metrics() {
+        let app = test_app::TestApp::new();
+
+        let (status, _) = app.request(axum::http::Method::POST, "/api/meta/sdlc/develop", Some(serde_json::json!({}))).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let body = serde_json::json!({"feature_request": {
+            "description": "Export reports as CSV",
+            "priority": "Medium",
+            "deadline": null,
+            "acceptance_criteria": [],
+            "dependencies": [],
+            "target_users": [],
+            "context": {}
+        }});
+        app.request(axum::http::Method::POST, "/api/meta/sdlc/develop", Some(body)).await;
+        let metrics = app.state.meta_metrics.get("SDLCManager").unwrap();
+        assert_eq!(metrics.executions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_malformed_feature_request_is_a_bad_request() {
+        let app = test_app::TestApp::new();
+        let body = serde_json::json!({"feature_request": {"description": 42}});
+
+        let (status, message) = app.request(axum::http::Method::POST, "/api/meta/sdlc/develop", Some(body)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(String::from_utf8(message).unwrap().contains("Invalid feature_request"));
+    }
+
+    #[tokio::test]
+    async fn test_task_creation_errors_have_status_codes() {
+        let app = test_app::TestApp::new();
+        let id = app.create_agent("w1").await;
+        let post = |agent_id: &str| {
+            let body = serde_json::json!({"agent_id": agent_id, "input": "work", "priority": "normal"});
+            app.request(axum::http::Method::POST, "/api/tasks", Some(body))
+        };
+
+        assert_eq!(post("not-an-id").await.0, StatusCode::BAD_REQUEST);
+        assert_eq!(post(&id).await.0, StatusCode::OK);
+
+        app.state.scheduler.drain(std::time::Duration::ZERO).await;
+        assert_eq!(post(&id).await.0, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_admin_routes_need_the_token() {
+        let app = test_app::TestApp::scripted(["scripted reply"]);
+        let body = serde_json::json!({"llm": {"temperature": 0.3}});
+
+        let (status, _) = app.request(axum::http::Method::POST, "/api/admin/reload", Some(body.clone())).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = app.admin_request(axum::http::Method::POST, "/api/admin/reload", Some(body)).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        // The business agents were moved to the reloaded client too
+        let request = agentic_runtime::LlmRequest::new("mock-model").add_message(Message::user("hi"));
+        let reply = app.state.business_state.llm_client.complete(request).await.unwrap();
+        assert_eq!(reply.content, MockLlmClient::default().response);
+    }
+
+    #[tokio::test]
+    async fn test_app_reports_compliance() {
+        let app = test_app::TestApp::new();
+        let id = app.create_agent("w1").await;
+
+        let report: Option<serde_json::Value> = app.get(&format!("/api/agents/{}/compliance", id)).await;
+        let report = report.unwrap();
+        assert!(report["standard"].is_string());
+        assert!(report["compliant"].is_boolean());
+
+        let unknown: Option<serde_json::Value> = app.get("/api/agents/no-such-agent/compliance").await;
+        assert!(unknown.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_app_projects_requested_fields() {
+        let app = test_app::TestApp::new();
+        let id = app.create_agent("w1").await;
+
+        let detail: serde_json::Value = app.get(&format!("/api/agents/{}/detail?fields=id,name", id)).await;
+        assert_eq!(detail, serde_json::json!({"id": id, "name": "w1"}));
+
+        let full: serde_json::Value = app.get(&format!("/api/agents/{}/detail", id)).await;
+        assert!(full.get("config").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_app_reports_discovery_session() {
+        let app = test_app::TestApp::new();
+        let discovered: serde_json::Value =
+            app.post("/api/business/discover", &serde_json::json!({"preferences": {}})).await;
+        let session_id = discovered["session_id"].as_str().unwrap();
+
+        let session: serde_json::Value = app.get(&format!("/api/discovery/sessions/{}", session_id)).await;
+        assert_eq!(session["id"], session_id);
+        assert!(session["results"].is_array());
+
+        let (status, _) = app
+            .request(axum::http::Method::GET, &format!("/api/discovery/sessions/{}", uuid::Uuid::new_v4()), None)
+            .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_execution_over_cost_budget_is_aborted() {
+        // One mock gpt-4o call costs more than a millionth of a dollar
+        let app = test_app::TestApp::with_execution_config(MockLlmClient::default(), |config| {
+            config.max_cost_usd_per_execution = Some(0.000_001);
+        });
+        let id = app.create_agent("w1").await;
+
+        let res: serde_json::Value = app
+            .post(&format!("/api/agents/{}/execute", id), &serde_json::json!({"input": "hi", "model": "gpt-4o"}))
+            .await;
+
+        assert_eq!(res["success"], false);
+        assert!(res["error"].as_str().unwrap().contains("cost"), "{}", res);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_background_execution_ends_cancelled() {
+        let slow = MockLlmClient::default().with_latency(std::time::Duration::from_secs(30));
+        let app = test_app::TestApp::with_llm(slow);
+        let id = app.create_agent("w1").await;
+
+        let (status, body) = app
+            .request(
+                axum::http::Method::POST,
+                &format!("/api/agents/{}/execute", id),
+                Some(serde_json::json!({"input": "take your time", "async": true})),
+            )
+            .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let started: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(started["status"], "running");
+        let uri = format!("/api/executions/{}", started["execution_id"].as_str().unwrap());
+
+        let (status, body) = app.request(axum::http::Method::DELETE, &uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&body).unwrap()["status"], "cancelled");
+
+        let job: serde_json::Value = app.get(&uri).await;
+        assert_eq!(job["status"], "cancelled");
+        assert!(job.get("result").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_agent_deleted_during_background_execution_fails_it() {
+        let slow = MockLlmClient::default().with_latency(std::time::Duration::from_millis(200));
+        let app = test_app::TestApp::with_llm(slow);
+        let id = app.create_agent("w1").await;
+
+        let (_, body) = app
+            .request(
+                axum::http::Method::POST,
+                &format!("/api/agents/{}/execute", id),
+                Some(serde_json::json!({"input": "take your time", "async": true})),
+            )
+            .await;
+        let started: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let uri = format!("/api/executions/{}", started["execution_id"].as_str().unwrap());
+        let (status, _) = app.request(axum::http::Method::DELETE, &format!("/api/agents/{}", id), None).await;
+        assert!(status.is_success());
+
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        let job: serde_json::Value = app.get(&uri).await;
+        assert_eq!(job["status"], "completed");
+        assert_eq!(job["result"]["success"], false);
+        assert!(job["result"]["error"].as_str().unwrap().contains("not found"), "{}", job);
+    }
+
+    #[tokio::test]
+    async fn test_panicking_background_execution_ends_failed() {
+        let jobs = ExecutionJobs::default();
+        let execution_id = jobs.spawn("a1".to_string(), async { panic!("executor bug") });
+
+        for _ in 0..50 {
+            if !matches!(jobs.get(&execution_id).unwrap().status, ExecutionStatus::Running) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert!(matches!(jobs.get(&execution_id).unwrap().status, ExecutionStatus::Failed { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_drain_reports_and_cancels_background_executions() {
+        let app = test_app::TestApp::new();
+        let quick = app.state.executions.spawn("a1".to_string(), async {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            ExecuteAgentRes::failed("done".to_string())
+        });
+        let stuck = app.state.executions.spawn("a2".to_string(), std::future::pending());
+
+        let report = app.state.drain(std::time::Duration::from_millis(200)).await;
+
+        assert_eq!(report.executions, DrainReport { finished: 1, cancelled_running: 1, cancelled_queued: 0 });
+        assert!(matches!(app.state.executions.get(&quick).unwrap().status, ExecutionStatus::Completed { .. }));
+        assert!(matches!(app.state.executions.get(&stuck).unwrap().status, ExecutionStatus::Cancelled));
+
+        let id = app.create_agent("w1").await;
+        let body = serde_json::json!({"input": "work", "async": true});
+        let (status, _) = app.request(axum::http::Method::POST, &format!("/api/agents/{}/execute", id), Some(body)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_stored_messages_are_redacted_but_reply_is_not() {
+        let mut state = AppState::new();
+        state.redaction = RedactionPolicy::all();
+        let reply = "Noted, I'll write to jane@example.com".to_string();
+
+        let returned = record_exchange(&state, "agent-1", "My email is jane@example.com", reply.clone());
+
+        assert_eq!(returned.content, reply);
+        let stored = state.messages.lock().unwrap().get("agent-1").cloned().unwrap();
+        assert_eq!(stored[0].content, "My email is [REDACTED EMAIL]");
+        assert_eq!(stored[1].content, "Noted, I'll write to [REDACTED EMAIL]");
+    }
+
+    #[tokio::test]
+    async fn test_app_replays_scripted_replies() {
+        let app = test_app::TestApp::scripted(["First answer", "Second answer"]);
+        let id = app.create_agent("w1").await;
+        let uri = format!("/api/agents/{}/messages", id);
+
+        for content in ["one", "two"] {
+            let sent: bool = app.post(&uri, &serde_json::json!({"content": content})).await;
+            assert!(sent);
+        }
+
+        let history: Vec<serde_json::Value> = app.get(&uri).await;
+        let replies: Vec<&str> = history.iter().skip(1).step_by(2).map(|m| m["content"].as_str().unwrap()).collect();
+        assert_eq!(replies, vec!["First answer", "Second answer"]);
+    }
+
+    #[tokio::test]
+    async fn test_chat_streams_tokens_and_persists_reply() {
+        use tower::ServiceExt;
+
+        let (mut state, path) = test_state("chat");
+        state.executor = Arc::new(DefaultExecutor::new(Arc::new(MockLlmClient::new("Hello from the agent"))));
+        let req = CreateAgentReq { template_id: "tmpl.standard.worker".into(), name: "w1".into(), description: "d".into(), provider: None, model: None };
+        let Json(created) = api_agents_create(axum::extract::State(state.clone()), Json(req)).await.unwrap();
+        let app = router(state.clone());
+
+        let chat = axum::http::Request::post(format!("/api/agents/{}/chat", created.id))
+            .header(axum::http::header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(r#"{"message": "Hi"}"#))
+            .unwrap();
+        let res = app.oneshot(chat).await.unwrap();
+        assert_eq!(res.headers()[axum::http::header::CONTENT_TYPE], "text/event-stream");
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        let tokens: Vec<&str> = body
+            .split("\n\n")
+            .filter_map(|event| event.strip_prefix("event: token\ndata: "))
+            .collect();
+        assert_eq!(tokens, vec!["Hello ", "from ", "the ", "agent"]);
+        assert!(body.contains("event: done"));
+
+        let history = state.messages.lock().unwrap().get(&created.id).cloned().unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].content, "Hi");
+        assert_eq!(history[1].from, created.id);
+        assert_eq!(history[1].content, "Hello from the agent");
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_admin_swaps_executor_client() {
+        let (mut state, path) = test_state("swap");
+        state.executor = Arc::new(DefaultExecutor::new(Arc::new(MockLlmClient::new("old client"))));
+        state.business_state =
+            Arc::new(BusinessState::new(Arc::new(MockLlmClient::new("old client")), state.dashboard_state.clone()));
+        let req = CreateAgentReq { template_id: "tmpl.standard.worker".into(), name: "w1".into(), description: "d".into(), provider: None, model: None };
+        let Json(created) = api_agents_create(axum::extract::State(state.clone()), Json(req)).await.unwrap();
+
+        let swap = SetLlmClientReq { provider: "mock".into(), model: Some("mock-model".into()) };
+        let Json(res) = api_admin_set_llm_client(axum::extract::State(state.clone()), Json(swap)).await.unwrap();
+        assert_eq!(res.provider, "mock");
+        assert!(res.is_mock);
+
+        let mut agent = state.registry.lock().unwrap().get_agent(&created.id).unwrap().clone();
+        let context = ExecutionContext::new(agent.id);
+        let result = state.executor.execute(&mut agent, "hi", &context).await.unwrap();
+        assert_eq!(result.output, MockLlmClient::default().response);
+        let request = agentic_runtime::LlmRequest::new("mock-model").add_message(Message::user("hi"));
+        let reply = state.business_state.llm_client.complete(request).await.unwrap();
+        assert_eq!(reply.content, MockLlmClient::default().response);
+
+        let bad = SetLlmClientReq { provider: "nope".into(), model: None };
+        let err = api_admin_set_llm_client(axum::extract::State(state), Json(bad)).await.unwrap_err();
+        assert_eq!(err.0, StatusCode::BAD_REQUEST);
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_config_file_limits_apply_at_startup() {
+        let path = std::env::temp_dir().join(format!("agentic_config_{}.json", uuid::Uuid::new_v4()));
+        let config = serde_json::json!({"performance": {
+            "max_concurrent_llm_calls": 5,
+            "provider_rate_limits": {"ollama": {"requests_per_minute": 7}},
+        }});
+        fs::write(&path, config.to_string()).unwrap();
+
+        let state = AppState::with_runtime_config(runtime_config_from_file(path.to_str()));
+
+        assert_eq!(state.scheduler.status().max_concurrency, 5);
+        let ollama = RateLimiter::global().limits(agentic_runtime::LlmProvider::Ollama);
+        assert_eq!(ollama.requests_per_minute, Some(7));
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_reload_updates_scheduler_concurrency() {
+        let mut state = AppState::new();
+        state.scheduler = Arc::new(TaskScheduler::new().with_concurrency_limiter(agentic_runtime::LlmConcurrencyLimiter::new(2)));
+
+        let raise: PartialRuntimeConfig =
+            serde_json::from_value(serde_json::json!({"performance": {"max_concurrent_llm_calls": 7}})).unwrap();
+        let status = api_admin_reload(axum::extract::State(state.clone()), Json(raise)).await.unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert_eq!(state.scheduler.status().max_concurrency, 7);
+        assert_eq!(state.runtime_config.lock().unwrap().performance.max_concurrent_llm_calls, 7);
+
+        // Settings fixed at startup are named, and nothing is applied
+        let mut config = state.runtime_config.lock().unwrap().clone();
+        config.performance.max_concurrent_llm_calls = 3;
+        config.execution.agent_timeout_seconds += 1;
+        config.http.request_timeout_seconds += 1;
+        let err = state.reload_config(config).unwrap_err().to_string();
+        assert!(err.contains("execution.agent_timeout_seconds"), "{}", err);
+        assert!(err.contains("http.request_timeout_seconds"), "{}", err);
+        assert!(!err.contains("max_concurrent_llm_calls"), "{}", err);
+        assert_eq!(state.scheduler.status().max_concurrency, 7);
+    }
+
+    #[tokio::test]
+    async fn test_chat_with_unknown_agent_is_not_found() {
+        let state = AppState::new();
+        let res = api_agent_chat(
+            axum::extract::State(state),
+            Path("missing".to_string()),
+            Json(ChatReq { message: "Hi".into() }),
+        )
+        .await;
+        assert_eq!(res.err().unwrap().0, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn test_error_response_hides_internal_sources() {
+        use agentic_core::ResultExt;
+
+        let err = serde_json::from_str::<serde_json::Value>("{")
+            .context("Failed to load workflow")
+            .unwrap_err();
+        let (status, message) = error_response(err);
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(message, "Internal server error");
+
+        let (status, message) = error_response(agentic_core::Error::Conflict("name taken".into()));
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(message, "Conflict: name taken");
+    }
+
+    #[tokio::test]
+    async fn test_workflow_worker_cap_is_enforced() {
+        let (mut state, path) = test_state("cap");
+        state.max_workflow_workers = 3;
+
+        let res = api_workflows_create(axum::extract::State(state.clone()), Json(workflow_req(4, None))).await;
+        let (status, _) = res.err().expect("over the cap must fail");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(state.registry.lock().unwrap().list_agents().is_empty());
+
+        let Json(wf) = api_workflows_create(axum::extract::State(state.clone()), Json(workflow_req(3, None)))
+            .await
+            .unwrap();
+        assert_eq!(wf.worker_ids.len(), 3);
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_workflow_run_concats_worker_outputs() {
+        let (state, path) = test_state("run");
+        let Json(wf) = api_workflows_create(axum::extract::State(state.clone()), Json(workflow_req(2, None)))
+            .await
+            .unwrap();
+
+        let req = WorkflowRunReq { input: "summarize".into(), strategy: Some(AggregationStrategy::Concat), task_kind: None };
+        let Json(run) = api_workflows_run(axum::extract::State(state.clone()), Path(wf.id.clone()), Json(req))
+            .await
+            .unwrap();
+        assert_eq!(run.workers.len(), 2);
+        assert_eq!(run.output, format!("{}\n\n{}", run.workers[0].output, run.workers[1].output));
+
+        let missing = WorkflowRunReq { input: "x".into(), strategy: None, task_kind: None };
+        let (status, _) = api_workflows_run(axum::extract::State(state), Path("wf-missing".into()), Json(missing))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_create_rejects_model_from_other_provider() {
+        let (mut state, path) = test_state("model-mismatch");
+        state.factory = AgentFactory::from_registry(state.standards.registry().clone()).with_strict_models(true);
+        let req = |provider: &str, model: &str| CreateAgentReq {
+            template_id: "tmpl.standard.worker".into(),
+            name: format!("{}-{}", provider, model),
+            description: "d".into(),
+            provider: Some(provider.into()),
+            model: Some(model.into()),
+        };
+
+        assert!(api_agents_create(axum::extract::State(state.clone()), Json(req("openai", "gpt-4o"))).await.is_ok());
+        let (status, message) = api_agents_create(axum::extract::State(state), Json(req("anthropic", "gpt-4o")))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(message.contains("use provider openai"));
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_workflow_members_inherit_shared_config() {
+        let (state, path) = test_state("defaults");
+        let endpoint = serde_json::json!("http://mcp.internal:7000");
+        let mut req = workflow_req(3, None);
+        req.workflow_defaults.insert("mcp:endpoint".into(), endpoint.clone());
+        req.workflow_defaults.insert("budget_usd".into(), serde_json::json!(5));
+        req.member_config.insert("Crawler-2".into(), HashMap::from([("budget_usd".into(), serde_json::json!(20))]));
+
+        let Json(wf) = api_workflows_create(axum::extract::State(state.clone()), Json(req)).await.unwrap();
+
+        let reg = state.registry.lock().unwrap();
+        for id in std::iter::once(&wf.supervisor_id).chain(&wf.worker_ids) {
+            let agent = reg.get_agent(id).unwrap();
+            assert_eq!(agent.config["mcp:endpoint"], endpoint, "{} lacks the shared endpoint", agent.name);
+            let budget = if agent.name == "Crawler-2" { 20 } else { 5 };
+            assert_eq!(agent.config["budget_usd"], serde_json::json!(budget));
+        }
+        drop(reg);
+
+        let persisted = state.storage.lock().unwrap().list_workflows();
+        assert_eq!(persisted[0].workflow_defaults["mcp:endpoint"], endpoint);
+        let _ = fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_workflow_with_distinct_worker_template() {
+        let (mut state, path) = test_state("mixed");
+        let mut crawler = agentic_standards::template_standard_worker();
+        crawler.template_id = "tmpl.crawler".into();
+        crawler.default_tags = vec!["crawler".into()];
+        state.standards.register_template(crawler);
+        state.factory = AgentFactory::from_registry(state.standards.registry().clone());
+
+        // an unknown worker template fails before anything is created
+        let res = api_workflows_create(axum::extract::State(state.clone()), Json(workflow_req(2, Some("tmpl.missing")))).await;
+        assert_eq!(res.err().unwrap().0, StatusCode::BAD_REQUEST);
+        assert!(state.registry.lock().unwrap().list_agents().is_empty());
+
+        let Json(wf) = api_workflows_create(axum::extract::State(state.clone()), Json(workflow_req(2, Some("tmpl.crawler"))))
+            .await
+            .unwrap();
+
+        let reg = state.registry.lock().unwrap();
+        let sup = reg.get_agent(&wf.supervisor_id).unwrap();
+        assert!(!sup.tags.contains(&"crawler".to_string()));
+        for (i, id) in wf.worker_ids.iter().enumerate() {
+            let worker = reg.get_agent(id).unwrap();
+            assert_eq!(worker.name, format!("Crawler-{}", i + 1));
+            assert!(worker.tags.contains(&"crawler".to_string()));
+        }
+        let _ = fs::remove_file(path);
+    }
+}
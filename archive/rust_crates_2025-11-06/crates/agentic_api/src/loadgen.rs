@@ -0,0 +1,220 @@
+//! Synthetic load against the in-process router
+//!
+//! [`run`] boots the full [`router`](crate::router) over a mock LLM that
+//! waits [`LoadConfig::mock_latency`] per call, creates one agent and fires
+//! [`LoadConfig::requests`] executions at it from
+//! [`LoadConfig::concurrency`] workers. The [`LoadReport`] gives throughput
+//! and latency percentiles, so executor, scheduler and lock changes can be
+//! compared without a provider or a socket. Enabled by the `loadgen` feature.
+
+use crate::{router, AppState, PersistedStore};
+use agentic_core::{Error, Result};
+use agentic_runtime::executor::DefaultExecutor;
+use agentic_runtime::llm::MockLlmClient;
+use axum::body::Body;
+use axum::http::{header, Method, Request};
+use axum::Router;
+use serde::Serialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tower::ServiceExt;
+
+/// Shape of a load run
+#[derive(Debug, Clone)]
+pub struct LoadConfig {
+    /// Executions sent in total
+    pub requests: usize,
+    /// Executions in flight at once
+    pub concurrency: usize,
+    /// How long each mock LLM call takes
+    pub mock_latency: Duration,
+    /// Input of every execution
+    pub input: String,
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self {
+            requests: 1000,
+            concurrency: 50,
+            mock_latency: Duration::from_millis(20),
+            input: "Summarize the quarterly report".to_string(),
+        }
+    }
+}
+
+/// Request latency percentiles, in milliseconds
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub min_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    fn from_samples(mut samples: Vec<Duration>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort();
+        // Nearest-rank percentile
+        let at = |p: f64| {
+            let rank = ((p / 100.0) * samples.len() as f64).ceil() as usize;
+            samples[rank.clamp(1, samples.len()) - 1].as_secs_f64() * 1000.0
+        };
+        Self {
+            min_ms: samples[0].as_secs_f64() * 1000.0,
+            p50_ms: at(50.0),
+            p90_ms: at(90.0),
+            p99_ms: at(99.0),
+            max_ms: samples[samples.len() - 1].as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+/// Outcome of a load run
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadReport {
+    pub requests: usize,
+    /// Executions that returned an error status or `success: false`
+    pub failures: usize,
+    pub elapsed_ms: u64,
+    pub throughput_per_sec: f64,
+    pub latency: LatencySummary,
+}
+
+/// Boot a router over a mock LLM and drive `config` against it
+pub async fn run(config: &LoadConfig) -> Result<LoadReport> {
+    let store_path = std::env::temp_dir().join(format!("agentic_loadgen_{}.json", uuid::Uuid::new_v4()));
+    let mut state = AppState::new();
+    state.storage = Arc::new(Mutex::new(
+        PersistedStore::load(store_path.clone()).map_err(|e| Error::InternalError(e.to_string()))?,
+    ));
+    let llm = Arc::new(MockLlmClient::default().with_latency(config.mock_latency));
+    state.executor = Arc::new(DefaultExecutor::new(llm).with_middleware(Arc::new(state.costs.clone())));
+
+    let router = router(state);
+    let report = async {
+        let agent_id = create_agent(&router).await?;
+        Ok(drive(&router, &agent_id, config).await)
+    }
+    .await;
+    let _ = std::fs::remove_file(&store_path);
+    report
+}
+
+/// Fire `config.requests` executions of `agent_id` at `router`
+///
+/// For routers built over a custom [`AppState`]; `config.mock_latency` is
+/// not applied here.
+pub async fn drive(router: &Router, agent_id: &str, config: &LoadConfig) -> LoadReport {
+    let uri = format!("/api/agents/{}/execute", agent_id);
+    let body = serde_json::json!({ "input": config.input }).to_string();
+    let next = Arc::new(AtomicUsize::new(0));
+
+    let started = Instant::now();
+    let mut workers = tokio::task::JoinSet::new();
+    for _ in 0..config.concurrency.clamp(1, config.requests.max(1)) {
+        let (router, uri, body, next) = (router.clone(), uri.clone(), body.clone(), next.clone());
+        let total = config.requests;
+        workers.spawn(async move {
+            let mut samples = Vec::new();
+            let mut failures = 0;
+            while next.fetch_add(1, Ordering::Relaxed) < total {
+                let request_started = Instant::now();
+                if !execute(&router, &uri, &body).await {
+                    failures += 1;
+                }
+                samples.push(request_started.elapsed());
+            }
+            (samples, failures)
+        });
+    }
+
+    let mut samples = Vec::with_capacity(config.requests);
+    let mut failures = 0;
+    while let Some(joined) = workers.join_next().await {
+        match joined {
+            Ok((worker_samples, worker_failures)) => {
+                samples.extend(worker_samples);
+                failures += worker_failures;
+            }
+            Err(e) => tracing::warn!("Load worker panicked: {}", e),
+        }
+    }
+    let elapsed = started.elapsed();
+
+    LoadReport {
+        requests: samples.len(),
+        failures,
+        elapsed_ms: elapsed.as_millis() as u64,
+        throughput_per_sec: samples.len() as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        latency: LatencySummary::from_samples(samples),
+    }
+}
+
+async fn create_agent(router: &Router) -> Result<String> {
+    let body = serde_json::json!({
+        "template_id": "tmpl.standard.worker",
+        "name": "loadgen-worker",
+        "description": "Synthetic load target",
+    });
+    let (ok, json) = post(router, "/api/agents", body.to_string()).await;
+    match json.as_ref().and_then(|json| json["id"].as_str()) {
+        Some(id) if ok => Ok(id.to_string()),
+        _ => Err(Error::InternalError(format!("Failed to create load agent: {:?}", json))),
+    }
+}
+
+/// One execution; `true` when it succeeded
+async fn execute(router: &Router, uri: &str, body: &str) -> bool {
+    let (ok, json) = post(router, uri, body.to_string()).await;
+    ok && json.is_some_and(|json| json["success"].as_bool() == Some(true))
+}
+
+async fn post(router: &Router, uri: &str, body: String) -> (bool, Option<serde_json::Value>) {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri(uri)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("valid request");
+    let response = router.clone().oneshot(request).await.unwrap_or_else(|never| match never {});
+    let ok = response.status().is_success();
+    let json = match axum::body::to_bytes(response.into_body(), usize::MAX).await {
+        Ok(bytes) => serde_json::from_slice(&bytes).ok(),
+        Err(_) => None,
+    };
+    (ok, json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_small_load_completes_with_sane_numbers() {
+        let config = LoadConfig { requests: 20, concurrency: 4, mock_latency: Duration::from_millis(5), ..LoadConfig::default() };
+
+        let report = run(&config).await.unwrap();
+
+        assert_eq!(report.requests, 20);
+        assert_eq!(report.failures, 0);
+        assert!(report.throughput_per_sec > 0.0);
+        let latency = &report.latency;
+        assert!(latency.min_ms >= 5.0, "{:?}", latency);
+        assert!(latency.min_ms <= latency.p50_ms && latency.p50_ms <= latency.p90_ms);
+        assert!(latency.p90_ms <= latency.p99_ms && latency.p99_ms <= latency.max_ms);
+        assert!(latency.max_ms <= report.elapsed_ms as f64 + 1.0);
+    }
+
+    #[test]
+    fn test_percentiles_use_nearest_rank() {
+        let samples = (1..=100).map(Duration::from_millis).collect();
+        let summary = LatencySummary::from_samples(samples);
+        assert_eq!((summary.p50_ms, summary.p90_ms, summary.p99_ms, summary.max_ms), (50.0, 90.0, 99.0, 100.0));
+    }
+}
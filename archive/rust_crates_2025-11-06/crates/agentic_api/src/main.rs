@@ -3,17 +3,25 @@
 use agentic_api::{AppState, router};
 use tower_http::cors::{Any, CorsLayer};
 use std::net::SocketAddr;
+use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
+    // Initialize tracing; with the `otel` feature, spans are also exported
+    // when OTEL_EXPORTER_OTLP_ENDPOINT is set
+    #[cfg(feature = "otel")]
+    let (otel_layer, otel_provider) = agentic_observability::otel::layer_from_env().unzip();
+    #[cfg(not(feature = "otel"))]
+    let otel_layer: Option<tracing_subscriber::layer::Identity> = None;
+
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "agentic_api=info,tower_http=debug".into()),
+                .unwrap_or_else(|_| "agentic_api=info,agentic_runtime=info,tower_http=debug".into()),
         )
         .with(tracing_subscriber::fmt::layer())
+        .with(otel_layer)
         .init();
 
     // Create application state
@@ -26,7 +34,7 @@ async fn main() {
         .allow_headers(Any);
 
     // Build router with middleware
-    let app = router(state).layer(cors);
+    let app = router(state.clone()).layer(cors);
 
     // Start server
     let addr: SocketAddr = "127.0.0.1:8080".parse().unwrap();
@@ -42,9 +50,71 @@ async fn main() {
         .await
         .expect("Failed to bind to address");
 
+    // Give running tasks and background executions SHUTDOWN_GRACE_SECONDS
+    // (default 30) to finish. Draining starts with the signal, so work
+    // submitted while open connections finish is refused, not cut off later.
+    let grace = std::env::var("SHUTDOWN_GRACE_SECONDS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(Duration::from_secs(30));
+    let (signalled_tx, signalled_rx) = tokio::sync::oneshot::channel();
+    let drain = tokio::spawn(async move {
+        shutdown_signal().await;
+        let _ = signalled_tx.send(());
+        state.drain(grace).await
+    });
+
     axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            let _ = signalled_rx.await;
+        })
         .await
         .expect("Server error");
+
+    let report = drain.await.expect("Shutdown drain panicked");
+    tracing::info!(
+        finished = report.tasks.finished,
+        cancelled_running = report.tasks.cancelled_running,
+        cancelled_queued = report.tasks.cancelled_queued,
+        "Scheduler drained"
+    );
+    tracing::info!(
+        finished = report.executions.finished,
+        cancelled_running = report.executions.cancelled_running,
+        "Background executions drained"
+    );
+
+    // Flush the spans still waiting in the export batch
+    #[cfg(feature = "otel")]
+    if let Some(provider) = otel_provider {
+        if let Err(e) = provider.shutdown() {
+            tracing::warn!("Failed to flush OpenTelemetry spans: {}", e);
+        }
+    }
+}
+
+/// Resolves on Ctrl+C (or SIGTERM on Unix)
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.expect("Failed to listen for Ctrl+C");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to listen for SIGTERM")
+            .recv()
+            .await;
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    tracing::info!("Shutdown signal received, no longer accepting connections");
 }
 
 
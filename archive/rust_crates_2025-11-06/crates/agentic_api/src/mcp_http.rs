@@ -0,0 +1,181 @@
+//! MCP tools served by a remote server
+//!
+//! With `MCP_SERVER_URL` set, tool calls go to that server as JSON-RPC
+//! `tools/call` requests over the server's shared HTTP client, so the proxy,
+//! timeout and certificate settings of `HTTP_*` apply to them as they do to
+//! the LLM providers. The tool list is fetched with `tools/list` at startup
+//! and again by [`HttpMcpAdapter::refresh_tools`]. The calling agent's
+//! secrets travel in the call's `_meta.env`, so the server's tools can use
+//! them; they are not part of the tool arguments.
+
+use agentic_core::{AgentEnv, Error, Result};
+use agentic_protocols::{McpAdapter, McpTool};
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+/// [`McpAdapter`] for an MCP server reachable over HTTP
+pub struct HttpMcpAdapter {
+    endpoint: String,
+    client: reqwest::Client,
+    tools: Mutex<Vec<McpTool>>,
+}
+
+impl HttpMcpAdapter {
+    pub fn new(endpoint: impl Into<String>, client: reqwest::Client) -> Self {
+        Self { endpoint: endpoint.into(), client, tools: Mutex::new(Vec::new()) }
+    }
+
+    /// Adapter for `MCP_SERVER_URL`, or `None` when it is unset
+    pub fn from_env(client: &reqwest::Client) -> Option<Self> {
+        std::env::var("MCP_SERVER_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty())
+            .map(|url| Self::new(url, client.clone()))
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Fetch the server's tools; [`McpAdapter::list_tools`] serves them
+    /// from then on
+    pub async fn refresh_tools(&self) -> Result<Vec<McpTool>> {
+        let result = self.call("tools/list", serde_json::json!({})).await?;
+        let tools: Vec<McpTool> = result["tools"]
+            .as_array()
+            .map(|tools| {
+                tools
+                    .iter()
+                    .filter_map(|tool| {
+                        let name = tool["name"].as_str()?;
+                        Some(McpTool::new(name, tool["description"].as_str().unwrap_or_default()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        *self.tools.lock().unwrap() = tools.clone();
+        Ok(tools)
+    }
+
+    async fn call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": uuid::Uuid::new_v4().to_string(),
+            "method": method,
+            "params": params,
+        });
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| Error::ToolExecutionFailed(format!("MCP server {} unreachable: {}", self.endpoint, e)))?;
+        if !response.status().is_success() {
+            return Err(Error::ToolExecutionFailed(format!(
+                "MCP server {} answered HTTP {}",
+                self.endpoint,
+                response.status()
+            )));
+        }
+        let mut body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| Error::ToolExecutionFailed(format!("Invalid MCP response: {}", e)))?;
+        if let Some(error) = body.get("error") {
+            return Err(Error::ToolExecutionFailed(format!(
+                "MCP {} failed: {}",
+                method,
+                error["message"].as_str().unwrap_or("unknown error")
+            )));
+        }
+        Ok(body["result"].take())
+    }
+}
+
+#[async_trait]
+impl McpAdapter for HttpMcpAdapter {
+    fn list_tools(&self) -> Vec<McpTool> {
+        self.tools.lock().unwrap().clone()
+    }
+
+    /// `input` is passed as the tool's arguments when it is a JSON object,
+    /// and as `{"input": input}` otherwise
+    async fn invoke(&self, tool: &str, input: &str) -> String {
+        self.invoke_with_env(tool, input, &AgentEnv::default()).await
+    }
+
+    async fn invoke_with_env(&self, tool: &str, input: &str, env: &AgentEnv) -> String {
+        let arguments = match serde_json::from_str::<serde_json::Value>(input) {
+            Ok(object @ serde_json::Value::Object(_)) => object,
+            _ => serde_json::json!({ "input": input }),
+        };
+        let mut params = serde_json::json!({ "name": tool, "arguments": arguments });
+        if !env.is_empty() {
+            let vars: serde_json::Map<String, serde_json::Value> =
+                env.keys().into_iter().filter_map(|key| Some((key.to_string(), env.get(key)?.into()))).collect();
+            params["_meta"] = serde_json::json!({ "env": vars });
+        }
+        match self.call("tools/call", params).await {
+            Ok(result) => result["content"]
+                .as_array()
+                .map(|content| {
+                    content
+                        .iter()
+                        .filter_map(|part| part["text"].as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                })
+                .unwrap_or_default(),
+            Err(e) => e.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Json, Router};
+
+    async fn serve() -> String {
+        async fn rpc(Json(request): Json<serde_json::Value>) -> Json<serde_json::Value> {
+            let result = match request["method"].as_str() {
+                Some("tools/list") => serde_json::json!({"tools": [{"name": "upper", "description": "Uppercase"}]}),
+                _ => {
+                    let params = &request["params"];
+                    let text = match params["name"].as_str() {
+                        Some("whoami") => params["_meta"]["env"]["USER_TOKEN"].as_str().unwrap_or("anonymous").to_string(),
+                        _ => params["arguments"]["input"].as_str().unwrap_or_default().to_uppercase(),
+                    };
+                    serde_json::json!({"content": [{"type": "text", "text": text}]})
+                }
+            };
+            Json(serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": result}))
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, Router::new().route("/mcp", post(rpc))).await });
+        format!("http://{}/mcp", addr)
+    }
+
+    #[tokio::test]
+    async fn test_tools_are_listed_and_called_over_http() {
+        let adapter = HttpMcpAdapter::new(serve().await, reqwest::Client::new());
+        assert!(adapter.list_tools().is_empty());
+
+        adapter.refresh_tools().await.unwrap();
+        assert_eq!(adapter.list_tools()[0].name, "upper");
+        assert_eq!(adapter.invoke("upper", "hello").await, "HELLO");
+    }
+
+    #[tokio::test]
+    async fn test_agent_secrets_are_sent_as_call_meta() {
+        let adapter = HttpMcpAdapter::new(serve().await, reqwest::Client::new());
+        let mut env = AgentEnv::new();
+        env.set("USER_TOKEN", "tok-123");
+
+        assert_eq!(adapter.invoke_with_env("whoami", "", &env).await, "tok-123");
+        assert_eq!(adapter.invoke("whoami", "").await, "anonymous");
+    }
+}
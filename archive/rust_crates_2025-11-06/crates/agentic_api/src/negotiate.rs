@@ -0,0 +1,100 @@
+//! Response content negotiation (JSON or MessagePack)
+//!
+//! Handlers take a [`ResponseFormat`] extractor and return
+//! `format.respond(value)`; the same serde types are encoded as JSON by
+//! default or as MessagePack when the client sends
+//! `Accept: application/msgpack`.
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header, request::Parts, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::convert::Infallible;
+
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Encoding chosen from the request's `Accept` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    MsgPack,
+}
+
+impl ResponseFormat {
+    /// Pick the first supported media type listed in `Accept`, defaulting to JSON
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let Some(accept) = headers.get(header::ACCEPT).and_then(|v| v.to_str().ok()) else {
+            return Self::Json;
+        };
+
+        for media_type in accept.split(',') {
+            let media_type = media_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+            match media_type.as_str() {
+                "application/msgpack" | "application/x-msgpack" => return Self::MsgPack,
+                "application/json" | "application/*" | "*/*" => return Self::Json,
+                _ => {}
+            }
+        }
+        Self::Json
+    }
+
+    pub fn respond<T: Serialize>(self, value: T) -> Negotiated<T> {
+        Negotiated { format: self, value }
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ResponseFormat {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// A response body encoded in the negotiated format
+#[derive(Debug, Clone)]
+pub struct Negotiated<T> {
+    pub format: ResponseFormat,
+    pub value: T,
+}
+
+impl<T: Serialize> IntoResponse for Negotiated<T> {
+    fn into_response(self) -> Response {
+        match self.format {
+            ResponseFormat::Json => axum::Json(self.value).into_response(),
+            ResponseFormat::MsgPack => match rmp_serde::to_vec_named(&self.value) {
+                Ok(bytes) => (
+                    [(header::CONTENT_TYPE, HeaderValue::from_static(MSGPACK_CONTENT_TYPE))],
+                    bytes,
+                )
+                    .into_response(),
+                Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("MessagePack encoding failed: {}", e)).into_response(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_format_from_accept_header() {
+        assert_eq!(ResponseFormat::from_headers(&HeaderMap::new()), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_headers(&accept("application/msgpack")), ResponseFormat::MsgPack);
+        assert_eq!(ResponseFormat::from_headers(&accept("text/html, application/x-msgpack;q=0.9")), ResponseFormat::MsgPack);
+        assert_eq!(ResponseFormat::from_headers(&accept("application/json, application/msgpack")), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_headers(&accept("text/plain")), ResponseFormat::Json);
+    }
+}
@@ -0,0 +1,75 @@
+//! Response field projection (`?fields=id,name`)
+//!
+//! Handlers take a [`Fields`] extractor and return `fields.project(&value)`.
+//! The value is serialized and only the listed top-level keys of each
+//! object are kept (of every element, for arrays), so large responses can
+//! be trimmed without per-endpoint summary types. Without `fields` the
+//! value is returned whole.
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Default, Deserialize)]
+struct FieldsQuery {
+    fields: Option<String>,
+}
+
+/// Allowlist of response fields; `None` keeps everything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Fields(pub Option<Vec<String>>);
+
+impl Fields {
+    /// Parse a comma-separated list; blank entries are ignored
+    pub fn parse(list: &str) -> Self {
+        Self(Some(list.split(',').map(str::trim).filter(|f| !f.is_empty()).map(String::from).collect()))
+    }
+
+    /// `value` as JSON, keeping only the allowed keys
+    pub fn project<T: Serialize>(&self, value: &T) -> Value {
+        let value = serde_json::to_value(value).unwrap_or(Value::Null);
+        match &self.0 {
+            Some(fields) => project_value(value, fields),
+            None => value,
+        }
+    }
+}
+
+fn project_value(value: Value, fields: &[String]) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(map.into_iter().filter(|(key, _)| fields.contains(key)).collect()),
+        Value::Array(items) => Value::Array(items.into_iter().map(|item| project_value(item, fields)).collect()),
+        other => other,
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Fields {
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<FieldsQuery>::try_from_uri(&parts.uri)
+            .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid query: {}", e)))?;
+        Ok(query.fields.as_deref().map(Fields::parse).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_projection_keeps_listed_keys() {
+        let value = serde_json::json!([{"id": "a1", "name": "w1", "config": {"k": 1}}, {"id": "a2", "tags": []}]);
+        assert_eq!(
+            Fields::parse("id, name,").project(&value),
+            serde_json::json!([{"id": "a1", "name": "w1"}, {"id": "a2"}])
+        );
+        assert_eq!(Fields::default().project(&value), value);
+        assert_eq!(Fields::parse("id").project(&Option::<Value>::None), Value::Null);
+    }
+}
@@ -0,0 +1,143 @@
+//! In-process harness for route-level tests
+//!
+//! [`TestApp`] boots the full [`router`](crate::router) over an [`AppState`]
+//! whose agent store lives in a throwaway temp file and whose executor and
+//! business routes share one scripted [`MockLlmClient`]. Requests go through
+//! `tower::ServiceExt::oneshot`, so no socket is bound. The admin routes
+//! accept [`TEST_ADMIN_TOKEN`], which [`TestApp::admin_post`] sends.
+
+/// Admin token of every [`TestApp`]
+pub(crate) const TEST_ADMIN_TOKEN: &str = "test-admin-token";
+
+use crate::{router, AppState, BusinessState, PersistedStore};
+use agentic_runtime::config::ExecutionConfig;
+use agentic_runtime::executor::DefaultExecutor;
+use agentic_runtime::llm::MockLlmClient;
+use axum::body::Body;
+use axum::http::{header, Method, Request, StatusCode};
+use axum::Router;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tower::ServiceExt;
+
+pub(crate) struct TestApp {
+    pub state: AppState,
+    pub router: Router,
+    store_path: PathBuf,
+}
+
+impl TestApp {
+    pub fn new() -> Self {
+        Self::with_llm(MockLlmClient::default())
+    }
+
+    /// LLM calls answer with `responses` in order, then with the mock default
+    pub fn scripted<S: Into<String>>(responses: impl IntoIterator<Item = S>) -> Self {
+        Self::with_llm(MockLlmClient::default().with_script(responses))
+    }
+
+    pub fn with_llm(llm: MockLlmClient) -> Self {
+        Self::with_execution_config(llm, |_| {})
+    }
+
+    /// Like [`TestApp::with_llm`], with the executor's sandbox limits and
+    /// retries taken from the execution config after `configure` ran on it
+    pub fn with_execution_config(llm: MockLlmClient, configure: impl FnOnce(&mut ExecutionConfig)) -> Self {
+        let store_path = std::env::temp_dir().join(format!("agentic_test_app_{}.json", uuid::Uuid::new_v4()));
+        let llm = Arc::new(llm);
+
+        let mut state = AppState::new();
+        let execution = {
+            let mut config = state.runtime_config.lock().unwrap();
+            configure(&mut config.execution);
+            config.execution.clone()
+        };
+        state.storage = Arc::new(Mutex::new(PersistedStore::load(store_path.clone()).unwrap()));
+        state.executor = Arc::new(
+            DefaultExecutor::new(llm.clone())
+                .with_middleware(Arc::new(state.costs.clone()))
+                .with_execution_config(&execution),
+        );
+        state.business_state = Arc::new(
+            BusinessState::new(llm, state.dashboard_state.clone()).with_meta_metrics(state.meta_metrics.clone()),
+        );
+        state.admin_token = Some(TEST_ADMIN_TOKEN.to_string());
+
+        Self { router: router(state.clone()), state, store_path }
+    }
+
+    /// Send a request, with `body` as JSON if given; returns status and raw body
+    pub async fn request(&self, method: Method, uri: &str, body: Option<serde_json::Value>) -> (StatusCode, Vec<u8>) {
+        self.send(Request::builder().method(method).uri(uri), body).await
+    }
+
+    /// Like [`TestApp::request`], authenticated with [`TEST_ADMIN_TOKEN`]
+    pub async fn admin_request(&self, method: Method, uri: &str, body: Option<serde_json::Value>) -> (StatusCode, Vec<u8>) {
+        let builder = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", TEST_ADMIN_TOKEN));
+        self.send(builder, body).await
+    }
+
+    async fn send(&self, builder: axum::http::request::Builder, body: Option<serde_json::Value>) -> (StatusCode, Vec<u8>) {
+        let request = match body {
+            Some(json) => builder
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json.to_string())),
+            None => builder.body(Body::empty()),
+        }
+        .unwrap();
+
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, bytes.to_vec())
+    }
+
+    /// GET `uri`, asserting success, and decode the JSON response
+    pub async fn get<T: DeserializeOwned>(&self, uri: &str) -> T {
+        self.json(Method::GET, uri, None).await
+    }
+
+    /// POST `body` to `uri`, asserting success, and decode the JSON response
+    pub async fn post<T: DeserializeOwned>(&self, uri: &str, body: &impl Serialize) -> T {
+        self.json(Method::POST, uri, Some(serde_json::to_value(body).unwrap())).await
+    }
+
+    /// POST `body` to an admin route with the admin token, asserting
+    /// success, and decode the JSON response
+    pub async fn admin_post<T: DeserializeOwned>(&self, uri: &str, body: &impl Serialize) -> T {
+        let (status, bytes) = self.admin_request(Method::POST, uri, Some(serde_json::to_value(body).unwrap())).await;
+        assert!(status.is_success(), "POST {} returned {}: {}", uri, status, String::from_utf8_lossy(&bytes));
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    /// Create an agent from the standard worker template; returns its id
+    pub async fn create_agent(&self, name: &str) -> String {
+        let body = serde_json::json!({"template_id": "tmpl.standard.worker", "name": name, "description": "test agent"});
+        let created: serde_json::Value = self.post("/api/agents", &body).await;
+        created["id"].as_str().unwrap().to_string()
+    }
+
+    async fn json<T: DeserializeOwned>(&self, method: Method, uri: &str, body: Option<serde_json::Value>) -> T {
+        let (status, bytes) = self.request(method.clone(), uri, body).await;
+        assert!(
+            status.is_success(),
+            "{} {} returned {}: {}",
+            method,
+            uri,
+            status,
+            String::from_utf8_lossy(&bytes)
+        );
+        serde_json::from_slice(&bytes).unwrap()
+    }
+}
+
+impl Drop for TestApp {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.store_path);
+    }
+}
@@ -4,7 +4,7 @@ use super::models::*;
 use crate::models::Opportunity;
 use crate::validation::TechnicalFeasibilityReport;
 use agentic_core::{Agent, AgentRole, Result};
-use agentic_runtime::llm::{LlmClient, LlmRequest, LlmMessage, MessageRole};
+use agentic_runtime::llm::LlmClient;
 use std::sync::Arc;
 use tracing::{info, debug};
 
@@ -4,10 +4,13 @@ use super::models::*;
 use super::{UIUXDesignAgent, InfrastructureAgent};
 use crate::models::Opportunity;
 use crate::validation::ComprehensiveValidationReport;
-use agentic_core::{Agent, AgentRole, Result, WorkflowId};
+use agentic_core::{Agent, AgentRole, Error, Result, WorkflowId};
 use agentic_meta::{MetaAgent, MetaAgentMetrics};
 use agentic_runtime::llm::LlmClient;
+use agentic_runtime::CallerLlmClient;
+use futures::stream::{self, StreamExt};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{info, debug};
 
 /// Product Development Manager - Meta-agent for complete product development
@@ -45,6 +48,8 @@ impl ProductDevelopmentManager {
         // Configure agent to be standards-compliant (A2A, MCP protocols)
         crate::configure_standards_compliant_agent(&mut agent);
 
+        let llm_client = CallerLlmClient::for_manager(llm_client, &agent);
+
         Self {
             agent,
             workflow_id: WorkflowId::generate(),
@@ -75,9 +80,66 @@ impl ProductDevelopmentManager {
         &mut self,
         opportunity: &Opportunity,
         validation_report: &ComprehensiveValidationReport,
+    ) -> Result<ProductDevelopmentResult> {
+        let start_time = Instant::now();
+        let result = self.run_development(opportunity, validation_report).await?;
+        self.record_execution(start_time.elapsed());
+        Ok(result)
+    }
+
+    /// Develop several opportunities, at most `concurrency` at a time
+    ///
+    /// `validation_reports[i]` must belong to `opportunities[i]`. Results come
+    /// back in input order, one per opportunity, so a failed product doesn't
+    /// discard the others. LLM calls from all pipelines still queue on the
+    /// clients' shared concurrency limiter, so raising `concurrency` past the
+    /// provider limit only adds waiting. A `concurrency` of 0 is treated as 1.
+    pub async fn develop_many(
+        &mut self,
+        opportunities: &[Opportunity],
+        validation_reports: &[ComprehensiveValidationReport],
+        concurrency: usize,
+    ) -> Result<Vec<Result<ProductDevelopmentResult>>> {
+        if opportunities.len() != validation_reports.len() {
+            return Err(Error::InvalidArgument(format!(
+                "Got {} opportunities but {} validation reports",
+                opportunities.len(),
+                validation_reports.len()
+            )));
+        }
+
+        info!(
+            "🚀 Developing {} products, {} at a time",
+            opportunities.len(),
+            concurrency.max(1)
+        );
+        let this = &*self;
+        let runs: Vec<(Result<ProductDevelopmentResult>, Duration)> = stream::iter(opportunities.iter().zip(validation_reports))
+            .map(|(opportunity, report)| async move {
+                let start_time = Instant::now();
+                let result = this.run_development(opportunity, report).await;
+                (result, start_time.elapsed())
+            })
+            .buffered(concurrency.max(1))
+            .collect()
+            .await;
+
+        let mut results = Vec::with_capacity(runs.len());
+        for (result, elapsed) in runs {
+            if result.is_ok() {
+                self.record_execution(elapsed);
+            }
+            results.push(result);
+        }
+        Ok(results)
+    }
+
+    async fn run_development(
+        &self,
+        opportunity: &Opportunity,
+        validation_report: &ComprehensiveValidationReport,
     ) -> Result<ProductDevelopmentResult> {
         info!("🚀 Starting product development for: {}", opportunity.title);
-        let start_time = std::time::Instant::now();
 
         // Phase 1: UI/UX Design
         info!("📐 Phase 1: Generating UI/UX design...");
@@ -106,13 +168,6 @@ impl ProductDevelopmentManager {
         let quality_gates_passed = self.check_quality_gates(&development_spec);
         info!("✅ Quality gates: {}", if quality_gates_passed { "PASSED" } else { "WARNINGS" });
 
-        // Update metrics
-        let elapsed = start_time.elapsed();
-        self.metrics.tasks_executed += 1;
-        self.metrics.avg_execution_time_ms =
-            (self.metrics.avg_execution_time_ms * (self.metrics.tasks_executed - 1) as f64
-                + elapsed.as_millis() as f64) / self.metrics.tasks_executed as f64;
-
         let result = ProductDevelopmentResult {
             opportunity_id: opportunity.id,
             status: if quality_gates_passed {
@@ -136,6 +191,13 @@ impl ProductDevelopmentManager {
         Ok(result)
     }
 
+    fn record_execution(&mut self, elapsed: Duration) {
+        self.metrics.tasks_executed += 1;
+        self.metrics.avg_execution_time_ms =
+            (self.metrics.avg_execution_time_ms * (self.metrics.tasks_executed - 1) as f64
+                + elapsed.as_millis() as f64) / self.metrics.tasks_executed as f64;
+    }
+
     /// Create comprehensive development specification
     async fn create_development_spec(
         &self,
@@ -407,4 +469,29 @@ mod tests {
         assert!(!result.specification.infrastructure.database.schema.is_empty());
         assert!(result.completion_percentage > 0.0);
     }
+
+    #[tokio::test]
+    async fn test_develop_many_keeps_input_order() {
+        let llm = Arc::new(MockLlmClient::new());
+        let mut manager = ProductDevelopmentManager::new(llm.clone());
+        let mut validation_manager = BusinessValidationManager::new(llm);
+
+        let opportunities: Vec<Opportunity> = ["Invoicing", "Scheduling", "Analytics"]
+            .iter()
+            .map(|title| Opportunity::new(title.to_string(), format!("{} SaaS", title), "SaaS".to_string(), ProductType::SaaS))
+            .collect();
+        let mut reports = Vec::new();
+        for opp in &opportunities {
+            reports.push(validation_manager.validate(opp).await.unwrap());
+        }
+
+        let results = manager.develop_many(&opportunities, &reports, 2).await.unwrap();
+
+        assert_eq!(results.len(), 3);
+        for (opp, result) in opportunities.iter().zip(&results) {
+            assert_eq!(result.as_ref().unwrap().opportunity_id, opp.id);
+        }
+        assert_eq!(manager.metrics().tasks_executed, 3);
+        assert!(manager.develop_many(&opportunities, &reports[..2], 2).await.is_err());
+    }
 }
@@ -3,7 +3,8 @@
 use super::models::*;
 use crate::models::Opportunity;
 use agentic_core::{Agent, AgentRole, Result};
-use agentic_runtime::llm::{LlmClient, LlmRequest, LlmMessage, MessageRole};
+use agentic_runtime::llm::{LlmClient, LlmRequest, Message};
+use agentic_runtime::TaskKind;
 use std::sync::Arc;
 use tracing::{info, debug};
 
@@ -152,22 +153,11 @@ impl UIUXDesignAgent {
             opportunity.domain, opportunity.title, opportunity.description
         );
 
-        let _llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: "You are a UI/UX design expert specializing in color theory and accessibility.".to_string(),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: prompt,
-                },
-            ],
-            temperature: Some(0.7),
-            max_tokens: Some(512),
-            tools: None,
-        };
+        let _llm_request = LlmRequest::new(self.agent.model.clone())
+            .with_system("You are a UI/UX design expert specializing in color theory and accessibility.")
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Creative)
+            .with_max_tokens(512);
 
         // For demo, provide a professional default palette
         Ok(ColorPalette {
@@ -73,16 +73,19 @@ pub mod opportunity;
 pub mod validation;
 pub mod development;
 pub mod revenue;
+pub mod pipeline;
 
 // Re-export main types
 pub use models::{
     Opportunity, MultiDimensionalScore, UserPreferences,
     FinancialProjection, CompetitiveAnalysis,
+    ScoreBand, ScoreContribution, ScoreExplanation,
 };
 pub use opportunity::{
     OpportunityDiscoveryManager,
     MarketResearchAgent,
     TrendAnalysisAgent,
+    OpportunitySource,
 };
 pub use revenue::{
     RevenueGenerationManager,
@@ -92,6 +95,7 @@ pub use revenue::{
     AnalyticsAgent,
     OptimizationAgent,
 };
+pub use pipeline::{PipelineReport, run_pipeline, run_pipeline_with};
 
 /// Configure an agent to be standards-compliant according to agentic_standards
 ///
@@ -1,5 +1,7 @@
 //! Data models for the business-to-revenue system
 
+use crate::validation::RecommendationThresholds;
+use agentic_core::{Error, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
@@ -7,6 +9,23 @@ use uuid::Uuid;
 /// Unique identifier for an opportunity
 pub type OpportunityId = Uuid;
 
+/// UUIDv5 namespace for [`Opportunity::deterministic_id`]
+pub const OPPORTUNITY_ID_NAMESPACE: Uuid = Uuid::from_u128(0x6f1c2b7e_9a4d_4c1e_8f3a_5d2e7b9c0a41);
+
+/// Days after which an opportunity counts as stale when no TTL is configured
+pub const DEFAULT_OPPORTUNITY_TTL_DAYS: i64 = 30;
+
+/// Opportunities one discovery call asks each LLM source for by default
+pub const DEFAULT_TARGET_OPPORTUNITY_COUNT: usize = 10;
+
+/// Largest accepted `UserPreferences::target_opportunity_count`; beyond this
+/// the answer no longer fits a single response
+pub const MAX_TARGET_OPPORTUNITY_COUNT: usize = 50;
+
+fn default_target_opportunity_count() -> usize {
+    DEFAULT_TARGET_OPPORTUNITY_COUNT
+}
+
 /// User preferences for opportunity discovery
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserPreferences {
@@ -39,6 +58,19 @@ pub struct UserPreferences {
 
     /// Custom criteria
     pub custom_criteria: HashMap<String, serde_json::Value>,
+
+    /// Opportunities shown to the LLM as examples of what good results look like
+    #[serde(default)]
+    pub examples: Vec<Opportunity>,
+
+    /// Free-form constraints appended to the discovery prompt
+    #[serde(default)]
+    pub extra_instructions: Option<String>,
+
+    /// Opportunities requested from each LLM source; parsed results are
+    /// capped to this too
+    #[serde(default = "default_target_opportunity_count")]
+    pub target_opportunity_count: usize,
 }
 
 impl Default for UserPreferences {
@@ -54,7 +86,56 @@ impl Default for UserPreferences {
             focus_passive_revenue: false,
             focus_quick_wins: false,
             custom_criteria: HashMap::new(),
+            examples: vec![],
+            extra_instructions: None,
+            target_opportunity_count: DEFAULT_TARGET_OPPORTUNITY_COUNT,
+        }
+    }
+}
+
+impl UserPreferences {
+    /// Reject values that would silently skew filtering
+    ///
+    /// Investments must be finite and non-negative with `min_investment <=
+    /// max_investment`, `max_time_to_market_days` must be at least 1, and
+    /// `target_opportunity_count` must be between 1 and
+    /// [`MAX_TARGET_OPPORTUNITY_COUNT`].
+    /// The error names the offending field.
+    pub fn validate(&self) -> Result<()> {
+        for (field, value) in [("min_investment", self.min_investment), ("max_investment", self.max_investment)] {
+            if let Some(value) = value {
+                if !value.is_finite() || value < 0.0 {
+                    return Err(Error::InvalidArgument(format!(
+                        "{} must be a non-negative amount, got {}",
+                        field, value
+                    )));
+                }
+            }
+        }
+
+        if let (Some(min), Some(max)) = (self.min_investment, self.max_investment) {
+            if min > max {
+                return Err(Error::InvalidArgument(format!(
+                    "min_investment ({}) is greater than max_investment ({})",
+                    min, max
+                )));
+            }
+        }
+
+        if self.max_time_to_market_days == Some(0) {
+            return Err(Error::InvalidArgument(
+                "max_time_to_market_days must be at least 1".to_string(),
+            ));
+        }
+
+        if !(1..=MAX_TARGET_OPPORTUNITY_COUNT).contains(&self.target_opportunity_count) {
+            return Err(Error::InvalidArgument(format!(
+                "target_opportunity_count must be between 1 and {}, got {}",
+                MAX_TARGET_OPPORTUNITY_COUNT, self.target_opportunity_count
+            )));
         }
+
+        Ok(())
     }
 }
 
@@ -86,8 +167,12 @@ pub struct Opportunity {
     pub competitive_analysis: CompetitiveAnalysis,
     pub implementation_estimate: ImplementationEstimate,
     pub sources: Vec<DataSource>,
+    /// When the opportunity was discovered or last refreshed
     pub discovered_at: chrono::DateTime<chrono::Utc>,
     pub validation_status: Option<ValidationStatus>,
+    /// Whether `MarketResearchAgent::enrich_opportunity` populated the details
+    #[serde(default)]
+    pub enriched: bool,
 }
 
 impl Opportunity {
@@ -105,9 +190,41 @@ impl Opportunity {
             sources: Vec::new(),
             discovered_at: chrono::Utc::now(),
             validation_status: None,
+            enriched: false,
         }
     }
 
+    /// Id derived from title and domain, ignoring case and extra whitespace
+    ///
+    /// The same idea gets the same id on every run and from every source,
+    /// so references survive when duplicates are merged.
+    pub fn deterministic_id(title: &str, domain: &str) -> OpportunityId {
+        let normalize = |s: &str| s.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        let key = format!("{}\n{}", normalize(domain), normalize(title));
+        Uuid::new_v5(&OPPORTUNITY_ID_NAMESPACE, key.as_bytes())
+    }
+
+    /// Replace the random id with [`Opportunity::deterministic_id`]
+    pub fn with_deterministic_id(mut self) -> Self {
+        self.id = Self::deterministic_id(&self.title, &self.domain);
+        self
+    }
+
+    /// Time since discovery (or the last refresh) as of `now`
+    pub fn age_at(&self, now: chrono::DateTime<chrono::Utc>) -> chrono::Duration {
+        now - self.discovered_at
+    }
+
+    /// Whether the market data is older than `ttl` as of `now`
+    pub fn is_stale_at(&self, ttl: chrono::Duration, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.age_at(now) > ttl
+    }
+
+    /// Whether the market data is older than `ttl`
+    pub fn is_stale(&self, ttl: chrono::Duration) -> bool {
+        self.is_stale_at(ttl, chrono::Utc::now())
+    }
+
     /// Check if opportunity matches user preferences
     pub fn matches_preferences(&self, prefs: &UserPreferences) -> bool {
         // Domain match
@@ -155,6 +272,32 @@ impl Opportunity {
     pub fn attractiveness_score(&self) -> f64 {
         self.scores.overall
     }
+
+    /// Combined confidence of all sources that surfaced this opportunity
+    ///
+    /// Uses noisy-OR (`1 - Π(1 - c)`), treating sources as independent
+    /// evidence: several weak sources agreeing can outweigh one strong one.
+    /// Returns 0.0 when there are no sources.
+    pub fn aggregate_confidence(&self) -> f64 {
+        self.weighted_confidence(|_| 1.0)
+    }
+
+    /// [`aggregate_confidence`](Self::aggregate_confidence) with each
+    /// source's confidence scaled by `weight(source name)` first
+    ///
+    /// The sources themselves are left as reported.
+    pub fn weighted_confidence(&self, weight: impl Fn(&str) -> f64) -> f64 {
+        if self.sources.is_empty() {
+            return 0.0;
+        }
+
+        let miss: f64 = self
+            .sources
+            .iter()
+            .map(|s| 1.0 - (s.confidence * weight(&s.name)).clamp(0.0, 1.0))
+            .product();
+        1.0 - miss
+    }
 }
 
 /// Multi-dimensional opportunity scoring
@@ -183,6 +326,10 @@ pub struct MultiDimensionalScore {
 
     /// Overall weighted score (0-10)
     pub overall: f64,
+
+    /// Aggregate source confidence (0-1), see `Opportunity::aggregate_confidence`
+    #[serde(default)]
+    pub confidence: f64,
 }
 
 impl Default for MultiDimensionalScore {
@@ -196,26 +343,121 @@ impl Default for MultiDimensionalScore {
             investment_required: 5.0,
             passive_income: 5.0,
             overall: 5.0,
+            confidence: 0.0,
         }
     }
 }
 
+/// Recommendation band for an overall opportunity score
+///
+/// Bands follow the overall-score cut-offs of [`RecommendationThresholds`],
+/// so they line up with the recommendation business validation would give.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreBand {
+    /// `strong_go_score` and above
+    Strong,
+    /// `go_score` to `strong_go_score`
+    Promising,
+    /// `conditional_score` to `go_score`
+    Marginal,
+    /// Below `conditional_score`
+    Weak,
+}
+
+impl ScoreBand {
+    pub fn for_score(overall: f64, thresholds: &RecommendationThresholds) -> Self {
+        if overall >= thresholds.strong_go_score {
+            ScoreBand::Strong
+        } else if overall >= thresholds.go_score {
+            ScoreBand::Promising
+        } else if overall >= thresholds.conditional_score {
+            ScoreBand::Marginal
+        } else {
+            ScoreBand::Weak
+        }
+    }
+}
+
+/// How one dimension feeds the overall score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreContribution {
+    pub dimension: String,
+    /// Value as stored on the score (0-10)
+    pub raw: f64,
+    /// Value after inverting "lower is better" dimensions
+    pub adjusted: f64,
+    /// Share of the overall score (weights sum to 1)
+    pub weight: f64,
+    /// `adjusted * weight`
+    pub contribution: f64,
+}
+
+/// Breakdown of a `MultiDimensionalScore` into per-dimension contributions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreExplanation {
+    pub dimensions: Vec<ScoreContribution>,
+    /// Sum of the contributions
+    pub overall: f64,
+    pub band: ScoreBand,
+    pub confidence: f64,
+}
+
 impl MultiDimensionalScore {
+    /// (dimension, raw, adjusted, weight) for each scored dimension
+    fn weighted_dimensions(&self) -> [(&'static str, f64, f64, f64); 7] {
+        // Weights can be customized based on user preferences
+        [
+            ("market_size", self.market_size, self.market_size, 0.2),
+            ("competition", self.competition, 10.0 - self.competition, 0.15), // Invert competition (lower is better)
+            ("complexity", self.complexity, 10.0 - self.complexity, 0.10),   // Invert complexity
+            ("revenue_potential", self.revenue_potential, self.revenue_potential, 0.25),
+            ("time_to_market", self.time_to_market, self.time_to_market, 0.10),
+            ("investment_required", self.investment_required, 10.0 - self.investment_required, 0.10), // Invert investment
+            ("passive_income", self.passive_income, self.passive_income, 0.10),
+        ]
+    }
+
     /// Calculate weighted overall score
     pub fn calculate_overall(&mut self) {
-        // Weights can be customized based on user preferences
-        let weights = [
-            (self.market_size, 0.2),
-            (10.0 - self.competition, 0.15), // Invert competition (lower is better)
-            (10.0 - self.complexity, 0.10),   // Invert complexity
-            (self.revenue_potential, 0.25),
-            (self.time_to_market, 0.10),
-            (10.0 - self.investment_required, 0.10), // Invert investment
-            (self.passive_income, 0.10),
-        ];
-
-        let total_weight: f64 = weights.iter().map(|(_, w)| w).sum();
-        self.overall = weights.iter().map(|(score, weight)| score * weight).sum::<f64>() / total_weight;
+        self.overall = self.explain().overall;
+    }
+
+    /// Explain the overall score dimension by dimension, banded by the
+    /// default [`RecommendationThresholds`]
+    ///
+    /// `overall` is recomputed from the dimensions, so it equals what
+    /// `calculate_overall` would store.
+    pub fn explain(&self) -> ScoreExplanation {
+        self.explain_with_thresholds(&RecommendationThresholds::default())
+    }
+
+    /// [`explain`](Self::explain) with the band taken from `thresholds`
+    pub fn explain_with_thresholds(&self, thresholds: &RecommendationThresholds) -> ScoreExplanation {
+        let weights = self.weighted_dimensions();
+        let total_weight: f64 = weights.iter().map(|(_, _, _, w)| w).sum();
+
+        let dimensions: Vec<ScoreContribution> = weights
+            .iter()
+            .map(|&(dimension, raw, adjusted, weight)| {
+                let weight = weight / total_weight;
+                ScoreContribution {
+                    dimension: dimension.to_string(),
+                    raw,
+                    adjusted,
+                    weight,
+                    contribution: adjusted * weight,
+                }
+            })
+            .collect();
+        let overall = dimensions.iter().map(|d| d.contribution).sum();
+
+        ScoreExplanation {
+            dimensions,
+            overall,
+            band: ScoreBand::for_score(overall, thresholds),
+            confidence: self.confidence,
+        }
     }
 }
 
@@ -354,6 +596,7 @@ pub struct TechStack {
     pub backend: Option<String>,
     pub database: Option<String>,
     pub hosting: Option<String>,
+    #[serde(default)]
     pub additional: Vec<String>,
 }
 
@@ -455,3 +698,105 @@ pub enum TrendDirection {
     Stable,
     Declining,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negative_investment_is_rejected() {
+        let prefs = UserPreferences { max_investment: Some(-500.0), ..Default::default() };
+        let err = prefs.validate().unwrap_err();
+        assert!(matches!(&err, Error::InvalidArgument(msg) if msg.starts_with("max_investment")));
+
+        let prefs = UserPreferences { min_investment: Some(f64::NAN), ..Default::default() };
+        assert!(prefs.validate().is_err());
+        assert!(UserPreferences::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_inverted_investment_range_is_rejected() {
+        let prefs = UserPreferences { min_investment: Some(10_000.0), max_investment: Some(1_000.0), ..Default::default() };
+        let err = prefs.validate().unwrap_err();
+        assert!(matches!(&err, Error::InvalidArgument(msg) if msg.contains("min_investment")));
+
+        let prefs = UserPreferences { max_time_to_market_days: Some(0), ..Default::default() };
+        assert!(prefs.validate().is_err());
+
+        for count in [0, MAX_TARGET_OPPORTUNITY_COUNT + 1] {
+            let prefs = UserPreferences { target_opportunity_count: count, ..Default::default() };
+            assert!(matches!(prefs.validate(), Err(Error::InvalidArgument(msg)) if msg.contains("target_opportunity_count")));
+        }
+    }
+
+    #[test]
+    fn test_score_explanation_contributions_sum_to_overall() {
+        let mut score = MultiDimensionalScore {
+            market_size: 8.0,
+            competition: 3.0,
+            complexity: 6.0,
+            revenue_potential: 9.0,
+            time_to_market: 7.0,
+            investment_required: 2.0,
+            passive_income: 4.0,
+            ..Default::default()
+        };
+        score.calculate_overall();
+
+        let explanation = score.explain();
+        let sum: f64 = explanation.dimensions.iter().map(|d| d.contribution).sum();
+        let weights: f64 = explanation.dimensions.iter().map(|d| d.weight).sum();
+
+        assert_eq!(explanation.dimensions.len(), 7);
+        assert!((sum - score.overall).abs() < 1e-9);
+        assert!((weights - 1.0).abs() < 1e-9);
+        assert_eq!(explanation.band, ScoreBand::for_score(score.overall, &RecommendationThresholds::default()));
+
+        let competition = explanation.dimensions.iter().find(|d| d.dimension == "competition").unwrap();
+        assert_eq!(competition.raw, 3.0);
+        assert_eq!(competition.adjusted, 7.0);
+    }
+
+    #[test]
+    fn test_score_band_thresholds() {
+        let defaults = RecommendationThresholds::default();
+        assert_eq!(ScoreBand::for_score(8.0, &defaults), ScoreBand::Strong);
+        assert_eq!(ScoreBand::for_score(7.9, &defaults), ScoreBand::Promising);
+        assert_eq!(ScoreBand::for_score(5.0, &defaults), ScoreBand::Marginal);
+        assert_eq!(ScoreBand::for_score(4.99, &defaults), ScoreBand::Weak);
+
+        let conservative = RecommendationThresholds { strong_go_score: 9.0, go_score: 7.5, ..Default::default() };
+        assert_eq!(ScoreBand::for_score(8.0, &conservative), ScoreBand::Promising);
+        assert_eq!(ScoreBand::for_score(7.0, &conservative), ScoreBand::Marginal);
+    }
+
+    #[test]
+    fn test_deterministic_id_depends_only_on_title_and_domain() {
+        let a = Opportunity::new("AI Invoice Parser".into(), "From Reddit".into(), "FinTech".into(), ProductType::SaaS)
+            .with_deterministic_id();
+        let b = Opportunity::new("  ai invoice   parser".into(), "From GitHub".into(), "fintech".into(), ProductType::API)
+            .with_deterministic_id();
+        let other_domain = Opportunity::new("AI Invoice Parser".into(), String::new(), "Legal".into(), ProductType::SaaS)
+            .with_deterministic_id();
+
+        assert_eq!(a.id, b.id);
+        assert_eq!(a.id.get_version_num(), 5);
+        assert_ne!(a.id, other_domain.id);
+
+        // Random ids remain the default
+        let random = Opportunity::new("AI Invoice Parser".into(), String::new(), "FinTech".into(), ProductType::SaaS);
+        assert_ne!(random.id, a.id);
+    }
+
+    #[test]
+    fn test_opportunity_older_than_ttl_is_stale() {
+        let mut opportunity = Opportunity::new("Niche CRM".into(), String::new(), "SaaS".into(), ProductType::SaaS);
+        let ttl = chrono::Duration::days(DEFAULT_OPPORTUNITY_TTL_DAYS);
+        let now = chrono::Utc::now();
+        assert!(!opportunity.is_stale_at(ttl, now));
+
+        opportunity.discovered_at = now - chrono::Duration::days(DEFAULT_OPPORTUNITY_TTL_DAYS + 1);
+        assert!(opportunity.is_stale_at(ttl, now));
+        assert_eq!(opportunity.age_at(now).num_days(), DEFAULT_OPPORTUNITY_TTL_DAYS + 1);
+    }
+}
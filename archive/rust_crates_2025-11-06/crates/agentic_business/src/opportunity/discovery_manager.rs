@@ -3,13 +3,17 @@
 use super::{
     MarketResearchAgent, TrendAnalysisAgent,
     CompetitorAnalysisAgent, OpportunityEvaluationAgent,
+    OpportunitySource,
+    DiscoverySession, DiscoverySessionId, DiscoverySessionStore,
 };
-use crate::models::{Opportunity, UserPreferences};
-use agentic_core::{Agent, AgentRole, Result, WorkflowId};
+use crate::models::{Opportunity, OpportunityId, UserPreferences};
+use agentic_core::{Agent, AgentRole, Error, Result, WorkflowId};
 use agentic_meta::meta_agent::{MetaAgent, MetaAgentType, MetaAgentCapability, MetaAgentMetrics};
 use agentic_runtime::llm::LlmClient;
+use agentic_runtime::CallerLlmClient;
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::collections::hash_map::Entry;
 use std::sync::Arc;
 use tracing::{info, debug};
 
@@ -22,6 +26,9 @@ pub struct OpportunityDiscoveryManager {
     competitor_analysis: CompetitorAnalysisAgent,
     evaluation: OpportunityEvaluationAgent,
     metrics: MetaAgentMetrics,
+    deterministic_ids: bool,
+    source_weights: HashMap<String, f64>,
+    sessions: DiscoverySessionStore,
 }
 
 impl OpportunityDiscoveryManager {
@@ -42,6 +49,8 @@ impl OpportunityDiscoveryManager {
         // Configure agent to be standards-compliant (A2A, MCP protocols)
         crate::configure_standards_compliant_agent(&mut agent);
 
+        let llm_client = CallerLlmClient::for_manager(llm_client, &agent);
+
         Self {
             agent,
             workflow_id: WorkflowId::generate(),
@@ -50,22 +59,113 @@ impl OpportunityDiscoveryManager {
             competitor_analysis: CompetitorAnalysisAgent::new(llm_client.clone()),
             evaluation: OpportunityEvaluationAgent::new(llm_client),
             metrics: MetaAgentMetrics::default(),
+            deterministic_ids: false,
+            source_weights: HashMap::new(),
+            sessions: DiscoverySessionStore::new(),
         }
     }
 
+    /// Give discovered opportunities ids derived from title and domain
+    ///
+    /// See [`Opportunity::deterministic_id`]. Off by default.
+    pub fn with_deterministic_ids(mut self, enabled: bool) -> Self {
+        self.deterministic_ids = enabled;
+        self
+    }
+
+    /// Scale the confidence of everything the source named `source` reports
+    /// when ranking and merging duplicates
+    ///
+    /// `source` is the name in the opportunities' `DataSource` attribution
+    /// (e.g. "LLM Analysis"). Sources without a weight keep 1.0; a weight
+    /// above 1.0 lets a curated feed outrank LLM brainstorming with the same
+    /// scores, and decides which copy survives when both report the same idea.
+    /// The opportunities keep the confidences their sources reported.
+    pub fn with_source_weight(mut self, source: impl Into<String>, weight: f64) -> Self {
+        self.source_weights.insert(source.into(), weight.max(0.0));
+        self
+    }
+
+    /// Trust weight of `source`
+    pub fn source_weight(&self, source: &str) -> f64 {
+        self.source_weights.get(source).copied().unwrap_or(1.0)
+    }
+
+    /// Checkpoint discovery sessions to `store` instead of a private
+    /// in-memory one
+    pub fn with_session_store(mut self, store: DiscoverySessionStore) -> Self {
+        self.sessions = store;
+        self
+    }
+
+    /// Store holding this manager's discovery sessions
+    pub fn session_store(&self) -> &DiscoverySessionStore {
+        &self.sessions
+    }
+
+    /// Register an additional opportunity source with the market researcher
+    pub fn register_source(&mut self, source: Box<dyn OpportunitySource>) {
+        self.market_research.register_source(source);
+    }
+
     /// Discover and rank opportunities based on user preferences
+    ///
+    /// Fails with `Error::InvalidArgument` if the preferences don't pass
+    /// [`UserPreferences::validate`]. The run is recorded as a
+    /// [`DiscoverySession`], so it can be picked up with
+    /// [`resume_session`](Self::resume_session) if it is interrupted.
     pub async fn discover(&mut self, preferences: UserPreferences) -> Result<Vec<Opportunity>> {
-        info!("Starting opportunity discovery workflow");
+        let session_id = self.create_session(preferences)?;
+        self.resume_session(session_id).await
+    }
+
+    /// Record a new discovery session over the registered sources without
+    /// running it
+    pub fn create_session(&self, preferences: UserPreferences) -> Result<DiscoverySessionId> {
+        preferences.validate()?;
+        let sources = self.market_research.sources().iter().map(|s| s.name().to_string()).collect();
+        let session = DiscoverySession::new(preferences, sources);
+        self.sessions.save(&session)?;
+        Ok(session.id)
+    }
+
+    /// Run the sources of session `id` that haven't answered yet, then rank
+    /// everything the session has collected
+    ///
+    /// Sources are queried by [`MarketResearchAgent::run_pending`], and the
+    /// session is saved as soon as each answers. A failing source is left
+    /// pending for the next resume; one that is no longer registered is
+    /// dropped. Resuming a finished session returns its results without
+    /// querying anything.
+    pub async fn resume_session(&mut self, id: DiscoverySessionId) -> Result<Vec<Opportunity>> {
+        let mut session = self
+            .sessions
+            .get(id)?
+            .ok_or_else(|| Error::WorkflowNotFound(format!("discovery session {}", id)))?;
+        if session.is_complete() {
+            return Ok(session.results.unwrap_or_default());
+        }
+
+        info!("Starting opportunity discovery workflow (session {})", id);
         let start = std::time::Instant::now();
 
         // Step 1: Market Research - Discover raw opportunities
-        debug!("Step 1: Market Research");
-        let mut opportunities = self.market_research
-            .discover_opportunities(&preferences)
-            .await?;
+        debug!("Step 1: Market Research ({} sources pending)", session.pending_sources().len());
+        let sessions = &self.sessions;
+        self.market_research.run_pending(&mut session, |s| sessions.save(s)).await?;
 
+        let opportunities = session.matching();
         info!("Discovered {} raw opportunities", opportunities.len());
 
+        let mut opportunities = merge_duplicates(opportunities, |source| self.source_weight(source));
+        debug!("{} opportunities after merging duplicates", opportunities.len());
+
+        if self.deterministic_ids {
+            for opportunity in &mut opportunities {
+                opportunity.id = Opportunity::deterministic_id(&opportunity.title, &opportunity.domain);
+            }
+        }
+
         // Step 2: Trend Analysis - Analyze growth patterns
         debug!("Step 2: Trend Analysis");
         for opportunity in &mut opportunities {
@@ -90,7 +190,11 @@ impl OpportunityDiscoveryManager {
 
         // Step 5: Ranking
         debug!("Step 5: Ranking opportunities");
-        self.evaluation.rank_opportunities(&mut opportunities);
+        self.evaluation.rank_opportunities_weighted(&mut opportunities, |source| self.source_weight(source));
+
+        session.results = Some(opportunities.clone());
+        session.updated_at = chrono::Utc::now();
+        self.sessions.save(&session)?;
 
         // Update metrics
         let elapsed = start.elapsed();
@@ -106,6 +210,29 @@ impl OpportunityDiscoveryManager {
         Ok(opportunities)
     }
 
+    /// Re-run enrichment, analysis and scoring on a stale opportunity
+    ///
+    /// Resets `discovered_at`, so the opportunity counts as fresh again, only
+    /// when enrichment succeeded; returns whether it did.
+    pub async fn refresh(&mut self, opportunity: &mut Opportunity) -> Result<bool> {
+        info!("Refreshing opportunity: {}", opportunity.title);
+
+        let enriched = self.market_research.enrich_opportunity(opportunity).await;
+        if let Ok(trends) = self.trend_analysis.analyze_trends(opportunity).await {
+            debug!("Analyzed {} trends for {}", trends.len(), opportunity.title);
+        }
+        if let Ok(analysis) = self.competitor_analysis.analyze_competitors(opportunity).await {
+            opportunity.competitive_analysis = analysis;
+        }
+        self.evaluation.evaluate_opportunity(opportunity).await?;
+        if enriched {
+            opportunity.discovered_at = chrono::Utc::now();
+        }
+
+        self.metrics.tasks_executed += 1;
+        Ok(enriched)
+    }
+
     /// Get workflow ID
     pub fn workflow_id(&self) -> WorkflowId {
         self.workflow_id
@@ -117,6 +244,33 @@ impl OpportunityDiscoveryManager {
     }
 }
 
+/// Collapse opportunities with the same title and domain into one
+///
+/// The copy with the highest confidence after `weight` survives, keeping its
+/// position, and gains the attribution of the others.
+fn merge_duplicates(opportunities: Vec<Opportunity>, weight: impl Fn(&str) -> f64) -> Vec<Opportunity> {
+    let mut merged: Vec<Opportunity> = Vec::with_capacity(opportunities.len());
+    let mut index: HashMap<OpportunityId, usize> = HashMap::new();
+
+    for opportunity in opportunities {
+        match index.entry(Opportunity::deterministic_id(&opportunity.title, &opportunity.domain)) {
+            Entry::Vacant(slot) => {
+                slot.insert(merged.len());
+                merged.push(opportunity);
+            }
+            Entry::Occupied(slot) => {
+                let existing = &mut merged[*slot.get()];
+                let mut duplicate = opportunity;
+                if duplicate.weighted_confidence(&weight) > existing.weighted_confidence(&weight) {
+                    std::mem::swap(existing, &mut duplicate);
+                }
+                existing.sources.append(&mut duplicate.sources);
+            }
+        }
+    }
+    merged
+}
+
 #[async_trait]
 impl MetaAgent for OpportunityDiscoveryManager {
     fn meta_type(&self) -> MetaAgentType {
@@ -205,6 +359,7 @@ impl MetaAgent for OpportunityDiscoveryManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{DataSource, ProductType, SourceType};
     use agentic_runtime::llm::MockLlmClient;
 
     #[tokio::test]
@@ -228,4 +383,175 @@ mod tests {
         let result = manager.discover(preferences).await;
         assert!(result.is_ok());
     }
+
+    /// Reports fixed opportunities under the name "Curated Feed"
+    struct CuratedSource(Vec<&'static str>);
+
+    #[async_trait]
+    impl OpportunitySource for CuratedSource {
+        fn name(&self) -> &str {
+            "Curated Feed"
+        }
+
+        fn source_type(&self) -> SourceType {
+            SourceType::UserInput
+        }
+
+        async fn discover(&self, _preferences: &UserPreferences) -> Result<Vec<Opportunity>> {
+            Ok(self
+                .0
+                .iter()
+                .map(|title| {
+                    let mut opp = Opportunity::new(title.to_string(), "Curated".into(), "General".into(), ProductType::SaaS);
+                    opp.sources.push(DataSource {
+                        name: "Curated Feed".into(),
+                        source_type: SourceType::UserInput,
+                        url: None,
+                        confidence: 0.5,
+                    });
+                    opp
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_trusted_source_outranks_llm_opportunities() {
+        let llm = Arc::new(MockLlmClient::new(
+            r#"[{"title": "Invoice Chaser", "description": "Chases unpaid invoices"},
+                {"title": "Churn Radar", "description": "Predicts churn"}]"#,
+        ));
+        let discover = |weight: Option<f64>| {
+            let mut manager = OpportunityDiscoveryManager::new(llm.clone());
+            if let Some(weight) = weight {
+                manager = manager.with_source_weight("Curated Feed", weight);
+            }
+            manager.register_source(Box::new(CuratedSource(vec!["Curated Niche", "churn radar"])));
+            async move { manager.discover(UserPreferences::default()).await.unwrap() }
+        };
+
+        // Untrusted, the curated feed's 0.5 confidence loses to the LLM's 0.8
+        let plain = discover(None).await;
+        assert_eq!(plain.last().unwrap().title, "Curated Niche");
+
+        let weighted = discover(Some(2.0)).await;
+        assert_eq!(weighted.len(), 3);
+        let titles: Vec<&str> = weighted.iter().map(|o| o.title.as_str()).collect();
+        assert_eq!(titles, ["churn radar", "Curated Niche", "Invoice Chaser"]);
+        // The curated copy of the duplicate survives and keeps the LLM attribution
+        assert_eq!(weighted[0].sources.len(), 2);
+        assert_eq!(weighted[0].attractiveness_score(), weighted[2].attractiveness_score());
+        // The weight only counts when ranking; the reported confidence is kept
+        let curated = weighted[1].sources.iter().find(|s| s.name == "Curated Feed").unwrap();
+        assert_eq!(curated.confidence, 0.5);
+    }
+
+    /// Counts its calls and hangs while `blocked` is set
+    struct GatedSource {
+        name: &'static str,
+        calls: Arc<std::sync::atomic::AtomicUsize>,
+        blocked: Arc<std::sync::atomic::AtomicBool>,
+    }
+
+    #[async_trait]
+    impl OpportunitySource for GatedSource {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn source_type(&self) -> SourceType {
+            SourceType::UserInput
+        }
+
+        async fn discover(&self, _preferences: &UserPreferences) -> Result<Vec<Opportunity>> {
+            use std::sync::atomic::Ordering;
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.blocked.load(Ordering::SeqCst) {
+                std::future::pending::<()>().await;
+            }
+            let title = format!("{} Pick", self.name);
+            Ok(vec![Opportunity::new(title, "Gated".into(), "General".into(), ProductType::SaaS)])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_runs_only_unfinished_sources() {
+        use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+        let store = DiscoverySessionStore::new();
+        let mut manager = OpportunityDiscoveryManager::new(Arc::new(MockLlmClient::new()))
+            .with_session_store(store.clone());
+        let names = ["Feed A", "Feed B", "Feed C", "Feed D"];
+        let calls: Vec<Arc<AtomicUsize>> = names.iter().map(|_| Arc::default()).collect();
+        let gate = Arc::new(AtomicBool::new(true));
+        for (i, name) in names.iter().enumerate() {
+            manager.register_source(Box::new(GatedSource {
+                name,
+                calls: calls[i].clone(),
+                // The third and fourth sources hang until the gate opens
+                blocked: if i < 2 { Arc::new(AtomicBool::new(false)) } else { gate.clone() },
+            }));
+        }
+        let counts = || calls.iter().map(|c| c.load(Ordering::SeqCst)).collect::<Vec<_>>();
+
+        // Interrupt the run while the third source is hanging
+        let id = manager.create_session(UserPreferences::default()).unwrap();
+        let interrupted = tokio::time::timeout(std::time::Duration::from_millis(100), manager.resume_session(id)).await;
+        assert!(interrupted.is_err());
+        let session = store.get(id).unwrap().unwrap();
+        assert_eq!(session.pending_sources(), ["Feed C", "Feed D"]);
+        assert!(session.results.is_none());
+        let before = counts();
+        assert_eq!(before, [1, 1, 1, 0]);
+
+        gate.store(false, Ordering::SeqCst);
+        let opportunities = manager.resume_session(id).await.unwrap();
+        let ran: Vec<usize> = counts().iter().zip(&before).map(|(after, before)| after - before).collect();
+        assert_eq!(ran, [0, 0, 1, 1]);
+        for name in names {
+            assert!(opportunities.iter().any(|o| o.title == format!("{} Pick", name)));
+        }
+
+        let session = store.get(id).unwrap().unwrap();
+        assert!(session.is_complete());
+        // A finished session answers from its checkpoint
+        assert_eq!(manager.resume_session(id).await.unwrap().len(), opportunities.len());
+        assert_eq!(counts().iter().sum::<usize>(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_keeps_the_timestamp_when_enrichment_fails() {
+        let mut manager = OpportunityDiscoveryManager::new(Arc::new(MockLlmClient::new("not json")));
+        let mut opportunity = Opportunity::new("Stale".into(), "Old pick".into(), "General".into(), ProductType::SaaS);
+        opportunity.discovered_at -= chrono::Duration::days(30);
+        let discovered_at = opportunity.discovered_at;
+
+        assert!(!manager.refresh(&mut opportunity).await.unwrap());
+        assert_eq!(opportunity.discovered_at, discovered_at);
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_source_does_not_block_the_session() {
+        use std::sync::atomic::AtomicBool;
+
+        let store = DiscoverySessionStore::new();
+        let mut manager = OpportunityDiscoveryManager::new(Arc::new(MockLlmClient::new()))
+            .with_session_store(store.clone());
+        manager.register_source(Box::new(GatedSource {
+            name: "Feed A",
+            calls: Arc::default(),
+            blocked: Arc::new(AtomicBool::new(false)),
+        }));
+        let session = DiscoverySession::new(
+            UserPreferences::default(),
+            vec!["Feed A".to_string(), "Retired Feed".to_string()],
+        );
+        store.save(&session).unwrap();
+
+        manager.resume_session(session.id).await.unwrap();
+
+        let session = store.get(session.id).unwrap().unwrap();
+        assert!(session.is_complete());
+        assert!(session.failed.contains_key("Retired Feed"));
+    }
 }
@@ -0,0 +1,372 @@
+//! GitHub Trending Source - Turns fast-growing repositories into opportunity signals
+//!
+//! Queries the GitHub search API for recently created repositories gaining stars
+//! quickly, then asks the LLM what commercial gaps those projects expose. The
+//! source is not registered by default; add it with
+//! `MarketResearchAgent::register_source`.
+
+use super::sources::OpportunitySource;
+use crate::models::{Opportunity, UserPreferences, ProductType, DataSource, SourceType};
+use agentic_core::Result;
+use agentic_runtime::llm::{LlmClient, LlmRequest, Message, DEFAULT_MAX_CONTINUATIONS};
+use agentic_runtime::TaskKind;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+const DEFAULT_ENDPOINT: &str = "https://api.github.com/search/repositories";
+
+/// GitHub trending source configuration
+#[derive(Debug, Clone)]
+pub struct GitHubTrendingConfig {
+    /// Search endpoint (overridable for GitHub Enterprise or tests)
+    pub endpoint: String,
+    /// Restrict to a language (e.g. "rust")
+    pub language: Option<String>,
+    /// Restrict to a topic (e.g. "ai")
+    pub topic: Option<String>,
+    /// Only consider repositories created within this many days
+    pub lookback_days: i64,
+    /// Minimum stars gained per day since creation to count as high-momentum
+    pub min_stars_per_day: f64,
+    /// Maximum repositories to analyze
+    pub max_repos: usize,
+    /// Per-request timeout
+    pub timeout: Duration,
+    /// Minimum interval between API calls (unauthenticated search allows ~10/min)
+    pub min_interval: Duration,
+    /// Optional token for higher rate limits
+    pub api_token: Option<String>,
+}
+
+impl Default for GitHubTrendingConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            language: None,
+            topic: None,
+            lookback_days: 30,
+            min_stars_per_day: 10.0,
+            max_repos: 10,
+            timeout: Duration::from_secs(5),
+            min_interval: Duration::from_secs(6),
+            api_token: None,
+        }
+    }
+}
+
+/// Opportunity source backed by GitHub repository momentum
+pub struct GitHubTrendingSource {
+    config: GitHubTrendingConfig,
+    http_client: reqwest::Client,
+    llm_client: Arc<dyn LlmClient>,
+    model: String,
+    last_request: Mutex<Option<Instant>>,
+}
+
+impl GitHubTrendingSource {
+    /// Create a new GitHub trending source
+    pub fn new(
+        config: GitHubTrendingConfig,
+        http_client: reqwest::Client,
+        llm_client: Arc<dyn LlmClient>,
+        model: impl Into<String>,
+    ) -> Self {
+        Self {
+            config,
+            http_client,
+            llm_client,
+            model: model.into(),
+            last_request: Mutex::new(None),
+        }
+    }
+
+    /// Reserve a request slot, returning false if we're calling too often
+    fn try_acquire_slot(&self) -> bool {
+        let mut last = self.last_request.lock().unwrap();
+        if let Some(at) = *last {
+            if at.elapsed() < self.config.min_interval {
+                return false;
+            }
+        }
+        *last = Some(Instant::now());
+        true
+    }
+
+    /// Build the search query string
+    fn build_query(&self) -> String {
+        let since = Utc::now() - chrono::Duration::days(self.config.lookback_days);
+        let mut query = format!("created:>{}", since.format("%Y-%m-%d"));
+
+        if let Some(language) = &self.config.language {
+            query.push_str(&format!(" language:{}", language));
+        }
+
+        if let Some(topic) = &self.config.topic {
+            query.push_str(&format!(" topic:{}", topic));
+        }
+
+        query
+    }
+
+    /// Fetch recently created repositories sorted by stars
+    async fn fetch_repos(&self) -> std::result::Result<Vec<Repository>, String> {
+        let per_page = self.config.max_repos.max(1).to_string();
+        let mut request = self
+            .http_client
+            .get(&self.config.endpoint)
+            .query(&[
+                ("q", self.build_query().as_str()),
+                ("sort", "stars"),
+                ("order", "desc"),
+                ("per_page", per_page.as_str()),
+            ])
+            .header("Accept", "application/vnd.github+json")
+            .timeout(self.config.timeout);
+
+        if let Some(token) = &self.config.api_token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await.map_err(|e| e.to_string())?;
+
+        let remaining = response
+            .headers()
+            .get("x-ratelimit-remaining")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u32>().ok());
+
+        if remaining == Some(0) {
+            return Err("rate limit exhausted".to_string());
+        }
+
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+
+        let parsed: SearchResponse = response.json().await.map_err(|e| e.to_string())?;
+        Ok(parsed.items)
+    }
+
+    /// Keep only repositories gaining stars quickly
+    fn high_momentum(&self, repos: Vec<Repository>) -> Vec<Repository> {
+        let now = Utc::now();
+        repos
+            .into_iter()
+            .filter(|repo| repo.stars_per_day(now) >= self.config.min_stars_per_day)
+            .take(self.config.max_repos)
+            .collect()
+    }
+
+    /// Ask the LLM which product gaps the trending repositories reveal
+    async fn analyze_gaps(&self, repos: &[Repository]) -> Vec<Opportunity> {
+        let listing: Vec<String> = repos
+            .iter()
+            .map(|r| {
+                format!(
+                    "- {} ({} stars): {}",
+                    r.full_name,
+                    r.stargazers_count,
+                    r.description.as_deref().unwrap_or("no description")
+                )
+            })
+            .collect();
+
+        let prompt = format!(
+            "These open-source repositories are gaining stars rapidly:\n\n{}\n\n\
+            For each, identify a commercial product gap (hosting, tooling, support, \
+            a vertical SaaS built on top). Respond with a JSON array of objects with \
+            fields: repo, title, description.",
+            listing.join("\n")
+        );
+
+        let llm_request = LlmRequest::new(self.model.clone())
+            .with_system("You are a developer-tools analyst who spots business opportunities in open-source trends.")
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Discovery)
+            .with_max_tokens(2048);
+
+        let gaps = match self.llm_client.complete_with_continuation(llm_request, DEFAULT_MAX_CONTINUATIONS).await {
+            Ok(response) => parse_gap_analysis(&response.content),
+            Err(e) => {
+                warn!("GitHub trending gap analysis failed, using repository summaries: {}", e);
+                Vec::new()
+            }
+        };
+
+        repos
+            .iter()
+            .map(|repo| {
+                let gap = gaps.iter().find(|g| g.repo.eq_ignore_ascii_case(&repo.full_name));
+                repo_to_opportunity(repo, gap)
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl OpportunitySource for GitHubTrendingSource {
+    fn name(&self) -> &str {
+        "GitHub Trending"
+    }
+
+    fn source_type(&self) -> SourceType {
+        SourceType::WebScraping
+    }
+
+    async fn discover(&self, _preferences: &UserPreferences) -> Result<Vec<Opportunity>> {
+        if !self.try_acquire_slot() {
+            debug!("GitHub trending queried too recently, skipping");
+            return Ok(Vec::new());
+        }
+
+        let repos = match self.fetch_repos().await {
+            Ok(repos) => self.high_momentum(repos),
+            Err(e) => {
+                warn!("GitHub trending unavailable, skipping: {}", e);
+                return Ok(Vec::new());
+            }
+        };
+
+        debug!("Found {} high-momentum repositories", repos.len());
+
+        if repos.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(self.analyze_gaps(&repos).await)
+    }
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    items: Vec<Repository>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Repository {
+    full_name: String,
+    html_url: String,
+    description: Option<String>,
+    stargazers_count: u64,
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    topics: Vec<String>,
+}
+
+impl Repository {
+    fn stars_per_day(&self, now: DateTime<Utc>) -> f64 {
+        let age_days = (now - self.created_at).num_days().max(1);
+        self.stargazers_count as f64 / age_days as f64
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GapAnalysis {
+    repo: String,
+    title: String,
+    description: String,
+}
+
+fn parse_gap_analysis(content: &str) -> Vec<GapAnalysis> {
+    match (content.find('['), content.rfind(']')) {
+        (Some(start), Some(end)) if start < end => {
+            serde_json::from_str(&content[start..=end]).unwrap_or_default()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Map a repository (and its LLM gap analysis, if any) into an opportunity
+fn repo_to_opportunity(repo: &Repository, gap: Option<&GapAnalysis>) -> Opportunity {
+    let (title, description) = match gap {
+        Some(gap) => (gap.title.clone(), gap.description.clone()),
+        None => (
+            format!("Commercial offering around {}", repo.full_name),
+            repo.description
+                .clone()
+                .unwrap_or_else(|| format!("Fast-growing open-source project {}", repo.full_name)),
+        ),
+    };
+
+    let domain = repo
+        .topics
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "Developer Tools".to_string());
+
+    let mut opp = Opportunity::new(title, description, domain, ProductType::SaaS);
+
+    opp.sources.push(DataSource {
+        name: "GitHub Trending".to_string(),
+        source_type: SourceType::WebScraping,
+        url: Some(repo.html_url.clone()),
+        confidence: if gap.is_some() { 0.7 } else { 0.5 },
+    });
+
+    opp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opportunity::sources::serve_once;
+    use agentic_runtime::llm::MockLlmClient;
+
+    fn fixture() -> String {
+        let created = (Utc::now() - chrono::Duration::days(5)).to_rfc3339();
+        format!(
+            r#"{{"total_count":2,"items":[
+                {{"full_name":"acme/fastdb","html_url":"https://github.com/acme/fastdb",
+                  "description":"Embedded vector database","stargazers_count":4000,
+                  "created_at":"{created}","topics":["database"]}},
+                {{"full_name":"acme/sleepy","html_url":"https://github.com/acme/sleepy",
+                  "description":"Barely used","stargazers_count":3,
+                  "created_at":"{created}","topics":[]}}]}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn test_trending_repo_yields_tagged_opportunity() {
+        let endpoint = serve_once(fixture()).await;
+        let llm = Arc::new(MockLlmClient::new(
+            r#"[{"repo":"acme/fastdb","title":"Managed FastDB Cloud","description":"Hosted fastdb with backups"}]"#,
+        ));
+
+        let config = GitHubTrendingConfig {
+            endpoint,
+            ..Default::default()
+        };
+        let source = GitHubTrendingSource::new(config, reqwest::Client::new(), llm, "mock-model");
+
+        let opps = source.discover(&UserPreferences::default()).await.unwrap();
+        assert_eq!(opps.len(), 1);
+        assert_eq!(opps[0].title, "Managed FastDB Cloud");
+        assert_eq!(opps[0].domain, "database");
+        assert_eq!(opps[0].sources[0].name, "GitHub Trending");
+        assert_eq!(
+            opps[0].sources[0].url.as_deref(),
+            Some("https://github.com/acme/fastdb")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_api_degrades_to_empty() {
+        let config = GitHubTrendingConfig {
+            endpoint: "http://127.0.0.1:9/search".to_string(),
+            timeout: Duration::from_millis(200),
+            ..Default::default()
+        };
+        let llm = Arc::new(MockLlmClient::default());
+        let source = GitHubTrendingSource::new(config, reqwest::Client::new(), llm, "mock-model");
+
+        let opps = source.discover(&UserPreferences::default()).await.unwrap();
+        assert!(opps.is_empty());
+
+        // Second call within the interval is skipped without a request
+        assert!(!source.try_acquire_slot());
+    }
+}
@@ -1,9 +1,13 @@
 //! Market Research Agent - Discovers opportunities from multiple sources
 
-use crate::models::{Opportunity, UserPreferences, ProductType, DataSource, SourceType};
-use agentic_core::{Agent, AgentRole, Result, Error};
-use agentic_runtime::llm::{LlmClient, LlmRequest, LlmMessage, MessageRole};
-use serde::{Deserialize, Serialize};
+use super::session::DiscoverySession;
+use super::sources::{LlmOpportunitySource, OpportunitySource, TrendOpportunitySource};
+use crate::models::{Opportunity, UserPreferences, Feature, FeaturePriority};
+use agentic_core::{Agent, AgentRole, Error, Result};
+use agentic_runtime::llm::{LlmClient, LlmRequest, Message, DEFAULT_MAX_CONTINUATIONS};
+use agentic_runtime::TaskKind;
+use agentic_runtime::HttpClientBuilder;
+use serde::Deserialize;
 use std::sync::Arc;
 use tracing::{info, debug, warn};
 
@@ -12,11 +16,27 @@ pub struct MarketResearchAgent {
     agent: Agent,
     llm_client: Arc<dyn LlmClient>,
     http_client: reqwest::Client,
+    sources: Vec<Box<dyn OpportunitySource>>,
 }
 
 impl MarketResearchAgent {
     /// Create a new market research agent
+    ///
+    /// Outbound HTTP uses a client configured from the environment
+    /// (proxy, timeouts, custom CA); see `HttpClientBuilder`.
     pub fn new(llm_client: Arc<dyn LlmClient>) -> Self {
+        let http_client = HttpClientBuilder::from_env()
+            .build()
+            .unwrap_or_else(|e| {
+                warn!("Invalid HTTP configuration, using defaults: {}", e);
+                reqwest::Client::new()
+            });
+
+        Self::with_http_client(llm_client, http_client)
+    }
+
+    /// Create a new market research agent sharing an existing HTTP client
+    pub fn with_http_client(llm_client: Arc<dyn LlmClient>, http_client: reqwest::Client) -> Self {
         let mut agent = Agent::new(
             "MarketResearcher",
             "Discovers market opportunities from APIs, web scraping, and trend analysis",
@@ -32,16 +52,23 @@ impl MarketResearchAgent {
         // Configure agent to be standards-compliant (A2A, MCP protocols)
         crate::configure_standards_compliant_agent(&mut agent);
 
-        let http_client = reqwest::Client::builder()
-            .user_agent("AgenticForge/1.0")
-            .timeout(std::time::Duration::from_secs(30))
-            .build()
-            .unwrap();
+        #[allow(unused_mut)]
+        let mut sources: Vec<Box<dyn OpportunitySource>> = vec![
+            Box::new(LlmOpportunitySource::new(llm_client.clone(), agent.model.clone())),
+            Box::new(TrendOpportunitySource::new(llm_client.clone(), agent.model.clone())),
+        ];
+
+        #[cfg(feature = "product-hunt")]
+        sources.push(Box::new(super::product_hunt::ProductHuntSource::new(
+            super::product_hunt::ProductHuntConfig::from_env(),
+            http_client.clone(),
+        )));
 
         Self {
             agent,
             llm_client,
             http_client,
+            sources,
         }
     }
 
@@ -50,319 +77,85 @@ impl MarketResearchAgent {
         &self.agent
     }
 
+    /// Register an additional opportunity source
+    pub fn register_source(&mut self, source: Box<dyn OpportunitySource>) {
+        debug!("Registering opportunity source: {}", source.name());
+        self.sources.push(source);
+    }
+
+    /// Get the registered opportunity sources
+    pub fn sources(&self) -> &[Box<dyn OpportunitySource>] {
+        &self.sources
+    }
+
     /// Discover opportunities based on user preferences
+    ///
+    /// Every registered source is queried; a failing source is logged and
+    /// skipped. An error is only returned when all sources fail.
     pub async fn discover_opportunities(
         &self,
         preferences: &UserPreferences,
     ) -> Result<Vec<Opportunity>> {
         info!("Starting opportunity discovery with preferences: {:?}", preferences);
 
-        let mut opportunities = Vec::new();
-
-        // Source 1: LLM-based analysis (always available)
-        debug!("Discovering opportunities via LLM analysis");
-        let llm_opportunities = self.discover_via_llm(preferences).await?;
-        opportunities.extend(llm_opportunities);
-
-        // Source 2: Product Hunt API (if accessible)
-        debug!("Discovering opportunities via Product Hunt");
-        if let Ok(ph_opportunities) = self.discover_via_product_hunt(preferences).await {
-            opportunities.extend(ph_opportunities);
-        } else {
-            warn!("Product Hunt API unavailable, skipping");
-        }
-
-        // Source 3: Trend analysis
-        debug!("Discovering opportunities via trend analysis");
-        if let Ok(trend_opportunities) = self.discover_via_trends(preferences).await {
-            opportunities.extend(trend_opportunities);
-        }
-
-        // Source 4: Web scraping (GitHub trending, Reddit, etc.)
-        debug!("Discovering opportunities via web scraping");
-        if let Ok(web_opportunities) = self.discover_via_web_scraping(preferences).await {
-            opportunities.extend(web_opportunities);
-        }
-
-        // Filter by preferences
-        let filtered: Vec<Opportunity> = opportunities
-            .into_iter()
-            .filter(|opp| opp.matches_preferences(preferences))
-            .collect();
+        let names = self.sources.iter().map(|s| s.name().to_string()).collect();
+        let mut session = DiscoverySession::new(preferences.clone(), names);
+        self.run_pending(&mut session, |_| Ok(())).await?;
+        let filtered = session.matching();
 
         info!("Discovered {} opportunities matching preferences", filtered.len());
 
         Ok(filtered)
     }
 
-    /// Discover opportunities using LLM analysis
-    async fn discover_via_llm(&self, preferences: &UserPreferences) -> Result<Vec<Opportunity>> {
-        let prompt = self.build_llm_discovery_prompt(preferences);
-
-        let llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: "You are a market research expert and business analyst. \
-                    Generate innovative, viable business opportunities based on current market trends, \
-                    gaps, and user preferences. Be creative but realistic.".to_string(),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: prompt,
-                },
-            ],
-            temperature: Some(0.7), // Higher creativity
-            max_tokens: Some(4096),
-            tools: None,
-        };
-
-        let response = self.llm_client.complete(llm_request).await?;
-
-        // Parse opportunities from LLM response
-        let opportunities = self.parse_llm_opportunities(&response.content, preferences)?;
-
-        Ok(opportunities)
-    }
-
-    /// Build prompt for LLM-based opportunity discovery
-    fn build_llm_discovery_prompt(&self, preferences: &UserPreferences) -> String {
-        let mut prompt = String::from("Generate 5-10 innovative business opportunities based on the following preferences:\n\n");
-
-        if let Some(domain) = &preferences.domain {
-            prompt.push_str(&format!("Domain: {}\n", domain));
-        }
-
-        if let Some(product_type) = preferences.product_type {
-            prompt.push_str(&format!("Product Type: {:?}\n", product_type));
-        }
-
-        if preferences.focus_minimal_investment {
-            prompt.push_str("Focus: Minimal investment required\n");
-        }
-
-        if preferences.focus_passive_revenue {
-            prompt.push_str("Focus: Passive revenue streams\n");
-        }
-
-        if preferences.focus_quick_wins {
-            prompt.push_str("Focus: Quick time to market\n");
-        }
-
-        if !preferences.revenue_type.is_empty() {
-            prompt.push_str(&format!("Revenue Types: {}\n", preferences.revenue_type.join(", ")));
-        }
-
-        prompt.push_str("\nFor each opportunity, provide:\n");
-        prompt.push_str("1. Title: A concise, catchy name\n");
-        prompt.push_str("2. Description: 2-3 sentences explaining the concept\n");
-        prompt.push_str("3. Target Market: Who would use this?\n");
-        prompt.push_str("4. Revenue Model: How would it make money?\n");
-        prompt.push_str("5. Competitive Advantage: Why would this succeed?\n");
-        prompt.push_str("6. Initial Investment: Estimated startup cost\n");
-        prompt.push_str("7. Time to Market: Estimated development time\n");
-        prompt.push_str("\nFormat as a JSON array of opportunities with these fields: title, description, domain, revenue_model, initial_investment, time_to_market_days\n");
-
-        prompt
-    }
-
-    /// Parse LLM response into opportunities
-    fn parse_llm_opportunities(
+    /// Query the sources `session` is still waiting for, recording each
+    /// answer and calling `checkpoint` after every source
+    ///
+    /// A failing source stays pending; a source that is no longer
+    /// registered is dropped from the session so it can still finish. An
+    /// error is only returned when no source of the session has answered.
+    pub async fn run_pending(
         &self,
-        content: &str,
-        _preferences: &UserPreferences,
-    ) -> Result<Vec<Opportunity>> {
-        // Try to extract JSON from the response
-        let json_str = if let Some(start) = content.find('[') {
-            if let Some(end) = content.rfind(']') {
-                &content[start..=end]
-            } else {
-                content
-            }
-        } else {
-            // Fallback: create synthetic opportunities from text
-            return self.create_synthetic_opportunities_from_text(content);
-        };
-
-        #[derive(Deserialize)]
-        struct LLMOpportunity {
-            title: String,
-            description: String,
-            domain: Option<String>,
-            revenue_model: Option<String>,
-            initial_investment: Option<f64>,
-            time_to_market_days: Option<u32>,
-        }
-
-        match serde_json::from_str::<Vec<LLMOpportunity>>(json_str) {
-            Ok(llm_opps) => {
-                let opportunities: Vec<Opportunity> = llm_opps
-                    .into_iter()
-                    .map(|llm_opp| {
-                        let mut opp = Opportunity::new(
-                            llm_opp.title,
-                            llm_opp.description,
-                            llm_opp.domain.unwrap_or_else(|| "General".to_string()),
-                            ProductType::SaaS, // Default
-                        );
-
-                        if let Some(investment) = llm_opp.initial_investment {
-                            opp.financial_projection.initial_investment = investment;
-                        }
-
-                        if let Some(days) = llm_opp.time_to_market_days {
-                            opp.implementation_estimate.estimated_days = days;
-                        }
-
-                        if let Some(model) = llm_opp.revenue_model {
-                            opp.financial_projection.revenue_model = model;
-                        }
-
-                        opp.sources.push(DataSource {
-                            name: "LLM Analysis".to_string(),
-                            source_type: SourceType::LLMAnalysis,
-                            url: None,
-                            confidence: 0.8,
-                        });
-
-                        opp
-                    })
-                    .collect();
-
-                Ok(opportunities)
-            }
-            Err(_) => {
-                // Fallback to text parsing
-                self.create_synthetic_opportunities_from_text(content)
-            }
-        }
-    }
-
-    /// Create synthetic opportunities from unstructured text
-    fn create_synthetic_opportunities_from_text(&self, text: &str) -> Result<Vec<Opportunity>> {
-        // Extract title-like patterns
-        let mut opportunities = Vec::new();
-
-        // Simple heuristic: look for numbered items or bullet points
-        for line in text.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with(|c: char| c.is_numeric())
-                || trimmed.starts_with('-')
-                || trimmed.starts_with('*')
-            {
-                // Extract the opportunity title
-                let title = trimmed
-                    .trim_start_matches(|c: char| c.is_numeric() || c == '.' || c == '-' || c == '*')
-                    .trim()
-                    .to_string();
-
-                if !title.is_empty() && title.len() > 10 {
-                    let mut opp = Opportunity::new(
-                        title.clone(),
-                        format!("Market opportunity: {}", title),
-                        "General".to_string(),
-                        ProductType::SaaS,
-                    );
-
-                    opp.sources.push(DataSource {
-                        name: "LLM Analysis (parsed)".to_string(),
-                        source_type: SourceType::LLMAnalysis,
-                        url: None,
-                        confidence: 0.6,
-                    });
-
-                    opportunities.push(opp);
-
-                    if opportunities.len() >= 10 {
-                        break;
-                    }
+        session: &mut DiscoverySession,
+        mut checkpoint: impl FnMut(&DiscoverySession) -> Result<()>,
+    ) -> Result<()> {
+        let mut last_error = None;
+        let pending: Vec<String> = session.pending_sources().into_iter().map(String::from).collect();
+        for name in pending {
+            let Some(source) = self.sources.iter().find(|s| s.name() == name) else {
+                let e = Error::InvalidState(format!("Opportunity source {} is no longer registered", name));
+                warn!("Discovery session {}: {}, dropping it", session.id, e);
+                session.drop_source(&name, &e);
+                last_error = Some(e);
+                checkpoint(session)?;
+                continue;
+            };
+            debug!("Discovering opportunities via {}", name);
+            match source.discover(&session.preferences).await {
+                Ok(found) => session.record_success(&name, found),
+                Err(e) => {
+                    warn!("Opportunity source {} failed, leaving it pending: {}", name, e);
+                    session.record_failure(&name, &e);
+                    last_error = Some(e);
                 }
             }
+            checkpoint(session)?;
         }
 
-        Ok(opportunities)
-    }
-
-    /// Discover opportunities via Product Hunt API
-    async fn discover_via_product_hunt(
-        &self,
-        _preferences: &UserPreferences,
-    ) -> Result<Vec<Opportunity>> {
-        // Note: Product Hunt API requires authentication
-        // For now, return empty - can be implemented with proper API key
-
-        debug!("Product Hunt integration not yet configured");
-        Ok(Vec::new())
-    }
-
-    /// Discover opportunities via trend analysis
-    async fn discover_via_trends(&self, preferences: &UserPreferences) -> Result<Vec<Opportunity>> {
-        // Use LLM to analyze current trends and generate opportunities
-        let prompt = format!(
-            "Analyze current market trends in {} and identify 3-5 emerging opportunities. \
-            Focus on: {}\n\n\
-            For each opportunity, explain why it's trending, the market gap it fills, \
-            and how it could be monetized.",
-            preferences.domain.as_ref().unwrap_or(&"technology".to_string()),
-            if preferences.focus_passive_revenue {
-                "passive income opportunities"
-            } else {
-                "scalable business models"
+        if session.completed.is_empty() {
+            if let Some(e) = last_error {
+                return Err(e);
             }
-        );
-
-        let llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: "You are a trend analyst specializing in identifying emerging market opportunities.".to_string(),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: prompt,
-                },
-            ],
-            temperature: Some(0.6),
-            max_tokens: Some(2048),
-            tools: None,
-        };
-
-        let response = self.llm_client.complete(llm_request).await?;
-
-        // Create opportunities from trend analysis
-        let opportunities = self.create_synthetic_opportunities_from_text(&response.content)?;
-
-        // Tag as trend-based
-        let mut tagged_opportunities = Vec::new();
-        for mut opp in opportunities {
-            opp.sources.push(DataSource {
-                name: "Trend Analysis".to_string(),
-                source_type: SourceType::TrendAnalysis,
-                url: None,
-                confidence: 0.75,
-            });
-            tagged_opportunities.push(opp);
         }
-
-        Ok(tagged_opportunities)
-    }
-
-    /// Discover opportunities via web scraping
-    async fn discover_via_web_scraping(
-        &self,
-        _preferences: &UserPreferences,
-    ) -> Result<Vec<Opportunity>> {
-        // Scrape GitHub trending, Reddit, etc.
-        // For safety and simplicity, we'll use LLM to generate realistic mock data
-
-        debug!("Web scraping integration: using mock data for demo");
-        Ok(Vec::new())
+        Ok(())
     }
 
     /// Enrich an opportunity with additional research
-    pub async fn enrich_opportunity(&self, opportunity: &mut Opportunity) -> Result<()> {
+    ///
+    /// Best-effort: if the LLM call fails or its answer can't be parsed, the
+    /// opportunity is left as-is with `enriched == false`. Returns whether
+    /// enrichment was applied.
+    pub async fn enrich_opportunity(&self, opportunity: &mut Opportunity) -> bool {
         info!("Enriching opportunity: {}", opportunity.title);
 
         // Use LLM to add more details
@@ -371,49 +164,138 @@ impl MarketResearchAgent {
             Title: {}\n\
             Description: {}\n\
             Domain: {}\n\n\
-            Please provide:\n\
-            1. Estimated market size\n\
-            2. Initial investment required (USD)\n\
-            3. Monthly operating costs\n\
-            4. Realistic monthly revenue projections (low, mid, high)\n\
-            5. Time to break even\n\
-            6. Recommended tech stack\n\
-            7. Core features needed\n\n\
-            Format as JSON with these fields.",
+            Respond with a single JSON object with these fields:\n\
+            - initial_investment: initial investment required (USD)\n\
+            - monthly_costs: monthly operating costs (USD)\n\
+            - monthly_revenue_low, monthly_revenue_mid, monthly_revenue_high: monthly revenue projections (USD)\n\
+            - break_even_months: time to break even\n\
+            - tech_stack: object with frontend, backend, database, hosting\n\
+            - core_features: array of objects with name, description, estimated_hours",
             opportunity.title, opportunity.description, opportunity.domain
         );
 
-        let llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: "You are a business analyst providing detailed market analysis.".to_string(),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: prompt,
-                },
-            ],
-            temperature: Some(0.4),
-            max_tokens: Some(2048),
-            tools: None,
+        let llm_request = LlmRequest::new(self.agent.model.clone())
+            .with_system("You are a business analyst providing detailed market analysis.")
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(2048);
+
+        let response = match self.llm_client.complete_with_continuation(llm_request, DEFAULT_MAX_CONTINUATIONS).await {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("Enrichment failed for {}, leaving un-enriched: {}", opportunity.title, e);
+                opportunity.enriched = false;
+                return false;
+            }
         };
 
-        let response = self.llm_client.complete(llm_request).await?;
+        match parse_enrichment(&response.content) {
+            Some(enrichment) => {
+                enrichment.apply_to(opportunity);
+                opportunity.enriched = true;
+                true
+            }
+            None => {
+                warn!("Could not parse enrichment for {}, leaving un-enriched", opportunity.title);
+                debug!("Enrichment analysis: {}", response.content.chars().take(100).collect::<String>());
+                opportunity.enriched = false;
+                false
+            }
+        }
+    }
+}
+
+/// Structured enrichment returned by the LLM
+#[derive(Debug, Deserialize)]
+struct Enrichment {
+    initial_investment: Option<f64>,
+    monthly_costs: Option<f64>,
+    monthly_revenue_low: Option<f64>,
+    monthly_revenue_mid: Option<f64>,
+    monthly_revenue_high: Option<f64>,
+    break_even_months: Option<f64>,
+    tech_stack: Option<EnrichmentTechStack>,
+    #[serde(default)]
+    core_features: Vec<EnrichmentFeature>,
+}
 
-        // Parse and update opportunity
-        // (Simplified - in production would parse JSON)
-        debug!("Enrichment analysis: {}", &response.content[..100.min(response.content.len())]);
+#[derive(Debug, Deserialize)]
+struct EnrichmentTechStack {
+    frontend: Option<String>,
+    backend: Option<String>,
+    database: Option<String>,
+    hosting: Option<String>,
+}
 
-        Ok(())
+#[derive(Debug, Deserialize)]
+struct EnrichmentFeature {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    estimated_hours: u32,
+}
+
+impl Enrichment {
+    fn apply_to(self, opportunity: &mut Opportunity) {
+        let financials = &mut opportunity.financial_projection;
+        if let Some(v) = self.initial_investment {
+            financials.initial_investment = v;
+        }
+        if let Some(v) = self.monthly_costs {
+            financials.monthly_costs = v;
+        }
+        if let Some(v) = self.monthly_revenue_low {
+            financials.monthly_revenue_low = v;
+        }
+        if let Some(v) = self.monthly_revenue_mid {
+            financials.monthly_revenue_mid = v;
+        }
+        if let Some(v) = self.monthly_revenue_high {
+            financials.monthly_revenue_high = v;
+        }
+        if let Some(v) = self.break_even_months {
+            financials.break_even_months = v;
+        }
+
+        let estimate = &mut opportunity.implementation_estimate;
+        if let Some(stack) = self.tech_stack {
+            estimate.tech_stack.frontend = stack.frontend.or(estimate.tech_stack.frontend.take());
+            estimate.tech_stack.backend = stack.backend.or(estimate.tech_stack.backend.take());
+            estimate.tech_stack.database = stack.database.or(estimate.tech_stack.database.take());
+            estimate.tech_stack.hosting = stack.hosting.or(estimate.tech_stack.hosting.take());
+        }
+        if !self.core_features.is_empty() {
+            estimate.core_features = self
+                .core_features
+                .into_iter()
+                .map(|f| Feature {
+                    name: f.name,
+                    description: f.description,
+                    priority: FeaturePriority::Critical,
+                    estimated_hours: f.estimated_hours,
+                })
+                .collect();
+        }
+    }
+}
+
+/// Extract the enrichment JSON object from an LLM response
+fn parse_enrichment(content: &str) -> Option<Enrichment> {
+    let start = content.find('{')?;
+    let end = content.rfind('}')?;
+    if end < start {
+        return None;
     }
+    serde_json::from_str(&content[start..=end]).ok()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use agentic_runtime::llm::MockLlmClient;
+    use crate::models::{ProductType, SourceType};
+    use agentic_runtime::llm::{LlmError, MockLlmClient};
+    use agentic_runtime::test_support::FailingLlmClient;
 
     #[tokio::test]
     async fn test_market_research_agent_creation() {
@@ -436,4 +318,83 @@ mod tests {
         let result = agent.discover_opportunities(&preferences).await;
         assert!(result.is_ok());
     }
+
+    struct StaticSource;
+
+    #[async_trait::async_trait]
+    impl OpportunitySource for StaticSource {
+        fn name(&self) -> &str {
+            "Static Feed"
+        }
+
+        fn source_type(&self) -> SourceType {
+            SourceType::UserInput
+        }
+
+        async fn discover(&self, _preferences: &UserPreferences) -> Result<Vec<Opportunity>> {
+            Ok(vec![Opportunity::new(
+                "Custom Feed Opportunity".to_string(),
+                "Registered by the caller".to_string(),
+                "SaaS".to_string(),
+                ProductType::SaaS,
+            )])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_custom_source_is_merged() {
+        let llm = Arc::new(MockLlmClient::new("Nothing to report"));
+        let mut agent = MarketResearchAgent::new(llm);
+        agent.register_source(Box::new(StaticSource));
+        assert_eq!(agent.sources().last().unwrap().name(), "Static Feed");
+
+        let opportunities = agent
+            .discover_opportunities(&UserPreferences::default())
+            .await
+            .unwrap();
+
+        assert!(opportunities
+            .iter()
+            .any(|opp| opp.title == "Custom Feed Opportunity"));
+    }
+
+    #[tokio::test]
+    async fn test_enrich_opportunity_populates_fields() {
+        let llm = Arc::new(MockLlmClient::new(
+            r#"Here is the analysis: {"initial_investment": 2500, "monthly_costs": 300,
+            "monthly_revenue_low": 1000, "monthly_revenue_mid": 4000, "monthly_revenue_high": 9000,
+            "break_even_months": 4, "tech_stack": {"frontend": "React", "backend": "Rust", "database": "PostgreSQL"},
+            "core_features": [{"name": "Dashboard", "description": "Usage overview", "estimated_hours": 40}]}"#,
+        ));
+        let agent = MarketResearchAgent::new(llm);
+        let mut opp = Opportunity::new(
+            "Churn Radar".to_string(),
+            "Predict SaaS churn".to_string(),
+            "SaaS".to_string(),
+            ProductType::SaaS,
+        );
+
+        assert!(agent.enrich_opportunity(&mut opp).await);
+        assert!(opp.enriched);
+        assert_eq!(opp.financial_projection.initial_investment, 2500.0);
+        assert_eq!(opp.financial_projection.monthly_revenue_mid, 4000.0);
+        assert_eq!(opp.implementation_estimate.tech_stack.backend.as_deref(), Some("Rust"));
+        assert_eq!(opp.implementation_estimate.core_features[0].name, "Dashboard");
+    }
+
+    #[tokio::test]
+    async fn test_enrich_opportunity_failure_is_non_fatal() {
+        let refused = || LlmError::NetworkError("connection refused".to_string());
+        let agent = MarketResearchAgent::new(Arc::new(FailingLlmClient::new(refused)));
+        let mut opp = Opportunity::new(
+            "Churn Radar".to_string(),
+            "Predict SaaS churn".to_string(),
+            "SaaS".to_string(),
+            ProductType::SaaS,
+        );
+
+        assert!(!agent.enrich_opportunity(&mut opp).await);
+        assert!(!opp.enriched);
+        assert_eq!(opp.financial_projection.initial_investment, 0.0);
+    }
 }
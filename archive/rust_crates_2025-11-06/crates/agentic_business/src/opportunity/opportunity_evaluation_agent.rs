@@ -39,16 +39,72 @@ impl OpportunityEvaluationAgent {
         // TODO: Implement evaluation logic
         let mut score = MultiDimensionalScore::default();
         score.calculate_overall();
+        score.confidence = opportunity.aggregate_confidence();
         opportunity.scores = score.clone();
         Ok(score)
     }
 
-    /// Rank multiple opportunities
+    /// Rank multiple opportunities (ties broken by source confidence)
     pub fn rank_opportunities(&self, opportunities: &mut [Opportunity]) {
+        self.rank_opportunities_weighted(opportunities, |_| 1.0);
+    }
+
+    /// Rank with ties broken by source confidence scaled by `weight(source name)`
+    pub fn rank_opportunities_weighted(&self, opportunities: &mut [Opportunity], weight: impl Fn(&str) -> f64) {
         opportunities.sort_by(|a, b| {
             b.attractiveness_score()
                 .partial_cmp(&a.attractiveness_score())
                 .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| {
+                    b.weighted_confidence(&weight)
+                        .partial_cmp(&a.weighted_confidence(&weight))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DataSource, ProductType, SourceType};
+    use agentic_runtime::llm::MockLlmClient;
+
+    fn with_sources(title: &str, confidences: &[f64]) -> Opportunity {
+        let mut opp = Opportunity::new(title.into(), "d".into(), "General".into(), ProductType::SaaS);
+        for &confidence in confidences {
+            opp.sources.push(DataSource {
+                name: "test".into(),
+                source_type: SourceType::LLMAnalysis,
+                url: None,
+                confidence,
+            });
+        }
+        opp
+    }
+
+    #[test]
+    fn test_weak_sources_combine_above_strong_source() {
+        let strong = with_sources("strong", &[0.7]);
+        let weak_pair = with_sources("weak pair", &[0.5, 0.5]);
+
+        assert!((strong.aggregate_confidence() - 0.7).abs() < 1e-9);
+        // 1 - 0.5 * 0.5
+        assert!((weak_pair.aggregate_confidence() - 0.75).abs() < 1e-9);
+        assert_eq!(with_sources("none", &[]).aggregate_confidence(), 0.0);
+
+        let agent = OpportunityEvaluationAgent::new(Arc::new(MockLlmClient::default()));
+        let mut ranked = vec![strong, weak_pair];
+        agent.rank_opportunities(&mut ranked);
+        assert_eq!(ranked[0].title, "weak pair");
+    }
+
+    #[tokio::test]
+    async fn test_evaluation_reports_confidence() {
+        let agent = OpportunityEvaluationAgent::new(Arc::new(MockLlmClient::default()));
+        let mut opp = with_sources("single", &[0.6]);
+
+        let score = agent.evaluate_opportunity(&mut opp).await.unwrap();
+        assert!((score.confidence - 0.6).abs() < 1e-9);
+    }
+}
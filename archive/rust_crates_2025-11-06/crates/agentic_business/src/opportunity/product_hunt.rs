@@ -0,0 +1,238 @@
+//! Product Hunt Source - Discovers opportunities from top Product Hunt launches
+//!
+//! Uses the Product Hunt GraphQL API (v2). Requires a developer token; when the
+//! token is missing or the API is unreachable the source logs and returns no
+//! opportunities, so discovery keeps working without it.
+
+use super::sources::OpportunitySource;
+use crate::models::{Opportunity, UserPreferences, ProductType, DataSource, SourceType};
+use agentic_core::Result;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::env;
+use tracing::{debug, warn};
+
+const DEFAULT_ENDPOINT: &str = "https://api.producthunt.com/v2/api/graphql";
+
+const POSTS_QUERY: &str = "query TopPosts($first: Int!) { \
+    posts(order: VOTES, first: $first) { edges { node { \
+    name tagline description url votesCount \
+    topics(first: 3) { edges { node { name } } } } } } }";
+
+/// Product Hunt API configuration
+#[derive(Debug, Clone)]
+pub struct ProductHuntConfig {
+    pub api_token: Option<String>,
+    pub endpoint: String,
+    pub max_posts: u32,
+}
+
+impl ProductHuntConfig {
+    /// Load configuration from environment variables
+    pub fn from_env() -> Self {
+        Self {
+            api_token: env::var("PRODUCT_HUNT_TOKEN").ok(),
+            endpoint: env::var("PRODUCT_HUNT_ENDPOINT")
+                .unwrap_or_else(|_| DEFAULT_ENDPOINT.to_string()),
+            max_posts: env::var("PRODUCT_HUNT_MAX_POSTS")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .unwrap_or(20),
+        }
+    }
+}
+
+impl Default for ProductHuntConfig {
+    fn default() -> Self {
+        Self {
+            api_token: None,
+            endpoint: DEFAULT_ENDPOINT.to_string(),
+            max_posts: 20,
+        }
+    }
+}
+
+/// Opportunity source backed by the Product Hunt GraphQL API
+pub struct ProductHuntSource {
+    config: ProductHuntConfig,
+    http_client: reqwest::Client,
+}
+
+impl ProductHuntSource {
+    /// Create a new Product Hunt source
+    pub fn new(config: ProductHuntConfig, http_client: reqwest::Client) -> Self {
+        Self { config, http_client }
+    }
+
+    /// Fetch top posts, returning the raw GraphQL nodes
+    async fn fetch_posts(&self, token: &str) -> std::result::Result<Vec<PostNode>, String> {
+        let body = serde_json::json!({
+            "query": POSTS_QUERY,
+            "variables": { "first": self.config.max_posts },
+        });
+
+        let response = self
+            .http_client
+            .post(&self.config.endpoint)
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+
+        let parsed: GraphQlResponse = response.json().await.map_err(|e| e.to_string())?;
+
+        if let Some(errors) = parsed.errors.filter(|e| !e.is_empty()) {
+            let messages: Vec<String> = errors.into_iter().map(|e| e.message).collect();
+            return Err(messages.join("; "));
+        }
+
+        Ok(parsed
+            .data
+            .map(|d| d.posts.edges.into_iter().map(|e| e.node).collect())
+            .unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl OpportunitySource for ProductHuntSource {
+    fn name(&self) -> &str {
+        "Product Hunt"
+    }
+
+    fn source_type(&self) -> SourceType {
+        SourceType::API
+    }
+
+    async fn discover(&self, _preferences: &UserPreferences) -> Result<Vec<Opportunity>> {
+        let Some(token) = self.config.api_token.as_deref() else {
+            debug!("PRODUCT_HUNT_TOKEN not set, skipping Product Hunt");
+            return Ok(Vec::new());
+        };
+
+        match self.fetch_posts(token).await {
+            Ok(posts) => {
+                debug!("Fetched {} posts from Product Hunt", posts.len());
+                Ok(posts.into_iter().map(post_to_opportunity).collect())
+            }
+            Err(e) => {
+                warn!("Product Hunt API unavailable, skipping: {}", e);
+                Ok(Vec::new())
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GraphQlResponse {
+    data: Option<PostsData>,
+    errors: Option<Vec<GraphQlError>>,
+}
+
+#[derive(Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct PostsData {
+    posts: Connection<PostNode>,
+}
+
+#[derive(Deserialize)]
+struct Connection<T> {
+    edges: Vec<Edge<T>>,
+}
+
+#[derive(Deserialize)]
+struct Edge<T> {
+    node: T,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PostNode {
+    name: String,
+    tagline: String,
+    description: Option<String>,
+    url: Option<String>,
+    #[serde(default)]
+    votes_count: u32,
+    topics: Option<Connection<TopicNode>>,
+}
+
+#[derive(Deserialize)]
+struct TopicNode {
+    name: String,
+}
+
+/// Map a Product Hunt post into an opportunity signal
+fn post_to_opportunity(post: PostNode) -> Opportunity {
+    let domain = post
+        .topics
+        .and_then(|t| t.edges.into_iter().next())
+        .map(|e| e.node.name)
+        .unwrap_or_else(|| "General".to_string());
+
+    let description = match post.description {
+        Some(desc) if !desc.is_empty() => format!("{} - {}", post.tagline, desc),
+        _ => post.tagline,
+    };
+
+    let mut opp = Opportunity::new(post.name, description, domain, ProductType::SaaS);
+
+    // More upvotes means stronger evidence of demand
+    let confidence = 0.5 + (post.votes_count as f64 / 1000.0).min(0.4);
+
+    opp.sources.push(DataSource {
+        name: "Product Hunt".to_string(),
+        source_type: SourceType::API,
+        url: post.url,
+        confidence,
+    });
+
+    opp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opportunity::sources::serve_once;
+
+    #[tokio::test]
+    async fn test_posts_become_opportunities() {
+        let endpoint = serve_once(
+            r#"{"data":{"posts":{"edges":[{"node":{
+                "name":"FocusFlow","tagline":"Deep work timer for teams",
+                "description":"Blocks distractions","url":"https://www.producthunt.com/posts/focusflow",
+                "votesCount":850,"topics":{"edges":[{"node":{"name":"Productivity"}}]}}}]}}}"#,
+        )
+        .await;
+
+        let config = ProductHuntConfig {
+            api_token: Some("test-token".to_string()),
+            endpoint,
+            max_posts: 5,
+        };
+        let source = ProductHuntSource::new(config, reqwest::Client::new());
+
+        let opps = source.discover(&UserPreferences::default()).await.unwrap();
+        assert_eq!(opps.len(), 1);
+        assert_eq!(opps[0].title, "FocusFlow");
+        assert_eq!(opps[0].domain, "Productivity");
+        assert_eq!(opps[0].sources[0].source_type, SourceType::API);
+        assert!(opps[0].sources[0].confidence > 0.8);
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_returns_empty() {
+        let source = ProductHuntSource::new(ProductHuntConfig::default(), reqwest::Client::new());
+
+        let opps = source.discover(&UserPreferences::default()).await.unwrap();
+        assert!(opps.is_empty());
+    }
+}
@@ -0,0 +1,288 @@
+//! Resumable discovery runs
+//!
+//! A [`DiscoverySession`] records which opportunity sources of a discovery
+//! run have answered and what they returned. The discovery manager saves it
+//! to a [`DiscoverySessionStore`] after every source, so a run cut short
+//! (restart, cancelled request) resumes with only the sources still missing.
+//! The store keeps at most [`DEFAULT_MAX_DISCOVERY_SESSIONS`] sessions and
+//! drops finished ones after [`DEFAULT_FINISHED_SESSION_TTL_HOURS`].
+
+use crate::models::{Opportunity, UserPreferences};
+use agentic_core::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Unique identifier for a discovery session
+pub type DiscoverySessionId = Uuid;
+
+/// Sessions a store keeps unless configured otherwise
+pub const DEFAULT_MAX_DISCOVERY_SESSIONS: usize = 1000;
+
+/// How long a store keeps a finished session unless configured otherwise
+pub const DEFAULT_FINISHED_SESSION_TTL_HOURS: i64 = 24;
+
+/// Progress of one discovery run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoverySession {
+    pub id: DiscoverySessionId,
+    pub preferences: UserPreferences,
+    /// Names of the sources the run queries, in order; a source that is no
+    /// longer registered is dropped, with the reason in `failed`
+    pub sources: Vec<String>,
+    /// Raw opportunities of each source that answered, by source name
+    pub completed: BTreeMap<String, Vec<Opportunity>>,
+    /// Last error of sources that failed; they are retried on resume
+    pub failed: BTreeMap<String, String>,
+    /// Ranked opportunities, once every reachable source has been analyzed
+    pub results: Option<Vec<Opportunity>>,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DiscoverySession {
+    pub fn new(preferences: UserPreferences, sources: Vec<String>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            preferences,
+            sources,
+            completed: BTreeMap::new(),
+            failed: BTreeMap::new(),
+            results: None,
+            started_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Sources that have not answered yet, in query order
+    pub fn pending_sources(&self) -> Vec<&str> {
+        self.sources
+            .iter()
+            .map(String::as_str)
+            .filter(|source| !self.completed.contains_key(*source))
+            .collect()
+    }
+
+    /// Every source answered and the results are ranked
+    pub fn is_complete(&self) -> bool {
+        self.results.is_some() && self.pending_sources().is_empty()
+    }
+
+    pub fn record_success(&mut self, source: &str, opportunities: Vec<Opportunity>) {
+        self.failed.remove(source);
+        self.completed.insert(source.to_string(), opportunities);
+        self.updated_at = Utc::now();
+    }
+
+    pub fn record_failure(&mut self, source: &str, error: &Error) {
+        self.failed.insert(source.to_string(), error.to_string());
+        self.updated_at = Utc::now();
+    }
+
+    /// Stop waiting for `source`, which can't answer any more (e.g. it is
+    /// no longer registered), so the session can still finish
+    pub fn drop_source(&mut self, source: &str, error: &Error) {
+        self.sources.retain(|name| name != source);
+        self.record_failure(source, error);
+    }
+
+    /// Raw opportunities of every completed source, in query order
+    pub fn collected(&self) -> Vec<Opportunity> {
+        self.sources
+            .iter()
+            .filter_map(|source| self.completed.get(source))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Collected opportunities that match the session's preferences
+    pub fn matching(&self) -> Vec<Opportunity> {
+        self.collected()
+            .into_iter()
+            .filter(|opp| opp.matches_preferences(&self.preferences))
+            .collect()
+    }
+}
+
+/// Shared store of [`DiscoverySession`]s, in memory or saved to a directory
+///
+/// Every save first drops finished sessions older than the TTL, then, while
+/// the store is over its bound, the least recently updated session,
+/// finished ones first.
+#[derive(Debug, Clone)]
+pub struct DiscoverySessionStore {
+    sessions: Arc<Mutex<HashMap<DiscoverySessionId, DiscoverySession>>>,
+    dir: Option<Arc<PathBuf>>,
+    max_sessions: usize,
+    finished_ttl: chrono::Duration,
+}
+
+impl Default for DiscoverySessionStore {
+    fn default() -> Self {
+        Self {
+            sessions: Arc::default(),
+            dir: None,
+            max_sessions: DEFAULT_MAX_DISCOVERY_SESSIONS,
+            finished_ttl: chrono::Duration::hours(DEFAULT_FINISHED_SESSION_TTL_HOURS),
+        }
+    }
+}
+
+impl DiscoverySessionStore {
+    /// In-memory store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most `max` sessions (0 is treated as 1)
+    pub fn with_max_sessions(mut self, max: usize) -> Self {
+        self.max_sessions = max.max(1);
+        self
+    }
+
+    /// Drop finished sessions `ttl` after their last update
+    pub fn with_finished_ttl(mut self, ttl: chrono::Duration) -> Self {
+        self.finished_ttl = ttl;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Store that writes each session to `<dir>/<id>.json`, so sessions
+    /// survive a restart
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| Error::InternalError(format!("Failed to create {}: {}", dir.display(), e)))?;
+        Ok(Self { dir: Some(Arc::new(dir)), ..Self::default() })
+    }
+
+    pub fn save(&self, session: &DiscoverySession) -> Result<()> {
+        let evicted = {
+            let mut sessions = self.sessions.lock().unwrap();
+            sessions.insert(session.id, session.clone());
+            self.evict(&mut sessions, session.id)
+        };
+        if let Some(dir) = &self.dir {
+            for id in evicted {
+                // Already gone is fine
+                let _ = std::fs::remove_file(dir.join(format!("{}.json", id)));
+            }
+            let path = dir.join(format!("{}.json", session.id));
+            std::fs::write(&path, serde_json::to_vec_pretty(session)?)
+                .map_err(|e| Error::InternalError(format!("Failed to write {}: {}", path.display(), e)))?;
+        }
+        Ok(())
+    }
+
+    /// Session `id`, read from disk if it isn't in memory yet
+    pub fn get(&self, id: DiscoverySessionId) -> Result<Option<DiscoverySession>> {
+        if let Some(session) = self.sessions.lock().unwrap().get(&id) {
+            return Ok(Some(session.clone()));
+        }
+        let Some(dir) = &self.dir else {
+            return Ok(None);
+        };
+        let path = dir.join(format!("{}.json", id));
+        match std::fs::read(&path) {
+            Ok(bytes) => {
+                let session: DiscoverySession = serde_json::from_slice(&bytes)?;
+                self.sessions.lock().unwrap().insert(id, session.clone());
+                Ok(Some(session))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::InternalError(format!("Failed to read {}: {}", path.display(), e))),
+        }
+    }
+
+    /// Drop expired finished sessions, then the stalest ones over the bound,
+    /// never `keep`; returns the ids dropped
+    fn evict(
+        &self,
+        sessions: &mut HashMap<DiscoverySessionId, DiscoverySession>,
+        keep: DiscoverySessionId,
+    ) -> Vec<DiscoverySessionId> {
+        let cutoff = Utc::now() - self.finished_ttl;
+        let mut evicted: Vec<DiscoverySessionId> = sessions
+            .values()
+            .filter(|s| s.id != keep && s.is_complete() && s.updated_at <= cutoff)
+            .map(|s| s.id)
+            .collect();
+        for id in &evicted {
+            sessions.remove(id);
+        }
+
+        if sessions.len() > self.max_sessions {
+            let mut candidates: Vec<&DiscoverySession> = sessions.values().filter(|s| s.id != keep).collect();
+            candidates.sort_by_key(|s| (!s.is_complete(), s.updated_at));
+            let excess: Vec<DiscoverySessionId> =
+                candidates.iter().take(sessions.len() - self.max_sessions).map(|s| s.id).collect();
+            for id in &excess {
+                sessions.remove(id);
+            }
+            evicted.extend(excess);
+        }
+        evicted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished(store: &DiscoverySessionStore) -> DiscoverySession {
+        let mut session = DiscoverySession::new(UserPreferences::default(), vec!["Feed".to_string()]);
+        session.record_success("Feed", Vec::new());
+        session.results = Some(Vec::new());
+        store.save(&session).unwrap();
+        session
+    }
+
+    #[test]
+    fn test_finished_sessions_expire() {
+        let store = DiscoverySessionStore::new().with_finished_ttl(chrono::Duration::zero());
+        let done = finished(&store);
+        let running = DiscoverySession::new(UserPreferences::default(), vec!["Feed".to_string()]);
+        store.save(&running).unwrap();
+
+        assert!(store.get(done.id).unwrap().is_none());
+        assert!(store.get(running.id).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_store_is_bounded_finished_sessions_first() {
+        let store = DiscoverySessionStore::new().with_max_sessions(2);
+        let running = DiscoverySession::new(UserPreferences::default(), vec!["Feed".to_string()]);
+        store.save(&running).unwrap();
+        let done = finished(&store);
+        let newest = DiscoverySession::new(UserPreferences::default(), vec!["Feed".to_string()]);
+        store.save(&newest).unwrap();
+
+        assert_eq!(store.len(), 2);
+        assert!(store.get(done.id).unwrap().is_none());
+        assert!(store.get(running.id).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_dropped_source_lets_the_session_finish() {
+        let mut session = DiscoverySession::new(UserPreferences::default(), vec!["A".to_string(), "B".to_string()]);
+        session.record_success("A", Vec::new());
+        session.drop_source("B", &Error::InvalidState("B is gone".to_string()));
+        session.results = Some(Vec::new());
+
+        assert!(session.pending_sources().is_empty());
+        assert!(session.is_complete());
+        assert!(session.failed.contains_key("B"));
+    }
+}
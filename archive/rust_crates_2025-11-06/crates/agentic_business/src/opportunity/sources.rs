@@ -0,0 +1,425 @@
+//! Opportunity Sources - Pluggable discovery backends for market research
+//!
+//! Each source turns user preferences into a list of candidate opportunities.
+//! `MarketResearchAgent` queries every registered source and merges the results,
+//! so new feeds (a private API, an RSS feed, a scraper) can be added without
+//! touching the agent itself.
+
+use crate::models::{Opportunity, UserPreferences, ProductType, DataSource, SourceType};
+use agentic_core::Result;
+use agentic_runtime::llm::{LlmClient, LlmRequest, Message, DEFAULT_MAX_CONTINUATIONS};
+use agentic_runtime::TaskKind;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Most `UserPreferences::examples` rendered into a discovery prompt
+pub const MAX_PROMPT_EXAMPLES: usize = 5;
+
+/// A source of opportunity candidates
+#[async_trait]
+pub trait OpportunitySource: Send + Sync {
+    /// Human-readable source name (used for logging and attribution)
+    fn name(&self) -> &str;
+
+    /// Kind of data this source produces
+    fn source_type(&self) -> SourceType;
+
+    /// Discover opportunities matching the given preferences
+    async fn discover(&self, preferences: &UserPreferences) -> Result<Vec<Opportunity>>;
+}
+
+/// Generates opportunities by asking the LLM directly
+pub struct LlmOpportunitySource {
+    llm_client: Arc<dyn LlmClient>,
+    model: String,
+}
+
+impl LlmOpportunitySource {
+    /// Create a new LLM-backed source
+    pub fn new(llm_client: Arc<dyn LlmClient>, model: impl Into<String>) -> Self {
+        Self {
+            llm_client,
+            model: model.into(),
+        }
+    }
+
+    /// Build prompt for LLM-based opportunity discovery
+    fn build_prompt(&self, preferences: &UserPreferences) -> String {
+        let mut prompt = format!(
+            "Generate {} innovative business opportunities based on the following preferences:\n\n",
+            preferences.target_opportunity_count
+        );
+
+        if let Some(domain) = &preferences.domain {
+            prompt.push_str(&format!("Domain: {}\n", domain));
+        }
+
+        if let Some(product_type) = preferences.product_type {
+            prompt.push_str(&format!("Product Type: {:?}\n", product_type));
+        }
+
+        if preferences.focus_minimal_investment {
+            prompt.push_str("Focus: Minimal investment required\n");
+        }
+
+        if preferences.focus_passive_revenue {
+            prompt.push_str("Focus: Passive revenue streams\n");
+        }
+
+        if preferences.focus_quick_wins {
+            prompt.push_str("Focus: Quick time to market\n");
+        }
+
+        if !preferences.revenue_type.is_empty() {
+            prompt.push_str(&format!("Revenue Types: {}\n", preferences.revenue_type.join(", ")));
+        }
+
+        if let Some(instructions) = preferences.extra_instructions.as_deref().map(str::trim).filter(|i| !i.is_empty()) {
+            prompt.push_str(&format!("\nAdditional Instructions:\n{}\n", instructions));
+        }
+
+        prompt.push_str("\nFor each opportunity, provide:\n");
+        prompt.push_str("1. Title: A concise, catchy name\n");
+        prompt.push_str("2. Description: 2-3 sentences explaining the concept\n");
+        prompt.push_str("3. Target Market: Who would use this?\n");
+        prompt.push_str("4. Revenue Model: How would it make money?\n");
+        prompt.push_str("5. Competitive Advantage: Why would this succeed?\n");
+        prompt.push_str("6. Initial Investment: Estimated startup cost\n");
+        prompt.push_str("7. Time to Market: Estimated development time\n");
+        prompt.push_str("\nFormat as a JSON array of opportunities with these fields: title, description, domain, revenue_model, initial_investment, time_to_market_days\n");
+
+        if !preferences.examples.is_empty() {
+            prompt.push_str("\nExamples of the kind of opportunity wanted (match their quality and style, do not repeat them):\n");
+            for example in preferences.examples.iter().take(MAX_PROMPT_EXAMPLES) {
+                prompt.push_str(&format!("{}\n", render_example(example)));
+            }
+        }
+
+        prompt
+    }
+}
+
+/// One example opportunity in the same JSON shape the LLM is asked to return
+fn render_example(opportunity: &Opportunity) -> String {
+    serde_json::json!({
+        "title": opportunity.title,
+        "description": opportunity.description,
+        "domain": opportunity.domain,
+        "revenue_model": opportunity.financial_projection.revenue_model,
+        "initial_investment": opportunity.financial_projection.initial_investment,
+        "time_to_market_days": opportunity.implementation_estimate.estimated_days,
+    })
+    .to_string()
+}
+
+#[async_trait]
+impl OpportunitySource for LlmOpportunitySource {
+    fn name(&self) -> &str {
+        "LLM Analysis"
+    }
+
+    fn source_type(&self) -> SourceType {
+        SourceType::LLMAnalysis
+    }
+
+    async fn discover(&self, preferences: &UserPreferences) -> Result<Vec<Opportunity>> {
+        let llm_request = LlmRequest::new(self.model.clone())
+            .with_system(
+                "You are a market research expert and business analyst. \
+                Generate innovative, viable business opportunities based on current market trends, \
+                gaps, and user preferences. Be creative but realistic.",
+            )
+            .add_message(Message::user(self.build_prompt(preferences)))
+            .with_temperature(0.7) // Higher creativity
+            .with_max_tokens(4096);
+
+        let response = self.llm_client.complete_with_continuation(llm_request, DEFAULT_MAX_CONTINUATIONS).await?;
+
+        Ok(parse_llm_opportunities(&response.content, preferences.target_opportunity_count))
+    }
+}
+
+/// Identifies emerging opportunities from an LLM trend analysis
+pub struct TrendOpportunitySource {
+    llm_client: Arc<dyn LlmClient>,
+    model: String,
+}
+
+impl TrendOpportunitySource {
+    /// Create a new trend-analysis source
+    pub fn new(llm_client: Arc<dyn LlmClient>, model: impl Into<String>) -> Self {
+        Self {
+            llm_client,
+            model: model.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl OpportunitySource for TrendOpportunitySource {
+    fn name(&self) -> &str {
+        "Trend Analysis"
+    }
+
+    fn source_type(&self) -> SourceType {
+        SourceType::TrendAnalysis
+    }
+
+    async fn discover(&self, preferences: &UserPreferences) -> Result<Vec<Opportunity>> {
+        let prompt = format!(
+            "Analyze current market trends in {} and identify 3-5 emerging opportunities. \
+            Focus on: {}\n\n\
+            For each opportunity, explain why it's trending, the market gap it fills, \
+            and how it could be monetized.",
+            preferences.domain.as_deref().unwrap_or("technology"),
+            if preferences.focus_passive_revenue {
+                "passive income opportunities"
+            } else {
+                "scalable business models"
+            }
+        );
+
+        let llm_request = LlmRequest::new(self.model.clone())
+            .with_system("You are a trend analyst specializing in identifying emerging market opportunities.")
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Discovery)
+            .with_max_tokens(2048);
+
+        let response = self.llm_client.complete(llm_request).await?;
+
+        // Tag as trend-based
+        let opportunities = create_synthetic_opportunities_from_text(&response.content, preferences.target_opportunity_count)
+            .into_iter()
+            .map(|mut opp| {
+                opp.sources.push(DataSource {
+                    name: "Trend Analysis".to_string(),
+                    source_type: SourceType::TrendAnalysis,
+                    url: None,
+                    confidence: 0.75,
+                });
+                opp
+            })
+            .collect();
+
+        Ok(opportunities)
+    }
+}
+
+/// Parse at most `limit` opportunities from an LLM response, falling back to
+/// text heuristics
+pub(crate) fn parse_llm_opportunities(content: &str, limit: usize) -> Vec<Opportunity> {
+    // Try to extract JSON from the response
+    let json_str = match (content.find('['), content.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        (Some(_), _) => content,
+        // Fallback: create synthetic opportunities from text
+        (None, _) => return create_synthetic_opportunities_from_text(content, limit),
+    };
+
+    #[derive(Deserialize)]
+    struct LLMOpportunity {
+        title: String,
+        description: String,
+        domain: Option<String>,
+        revenue_model: Option<String>,
+        initial_investment: Option<f64>,
+        time_to_market_days: Option<u32>,
+    }
+
+    match serde_json::from_str::<Vec<LLMOpportunity>>(json_str) {
+        Ok(llm_opps) => llm_opps
+            .into_iter()
+            .take(limit)
+            .map(|llm_opp| {
+                let mut opp = Opportunity::new(
+                    llm_opp.title,
+                    llm_opp.description,
+                    llm_opp.domain.unwrap_or_else(|| "General".to_string()),
+                    ProductType::SaaS, // Default
+                );
+
+                if let Some(investment) = llm_opp.initial_investment {
+                    opp.financial_projection.initial_investment = investment;
+                }
+
+                if let Some(days) = llm_opp.time_to_market_days {
+                    opp.implementation_estimate.estimated_days = days;
+                }
+
+                if let Some(model) = llm_opp.revenue_model {
+                    opp.financial_projection.revenue_model = model;
+                }
+
+                opp.sources.push(DataSource {
+                    name: "LLM Analysis".to_string(),
+                    source_type: SourceType::LLMAnalysis,
+                    url: None,
+                    confidence: 0.8,
+                });
+
+                opp
+            })
+            .collect(),
+        // Fallback to text parsing
+        Err(_) => create_synthetic_opportunities_from_text(content, limit),
+    }
+}
+
+/// Create up to `limit` synthetic opportunities from unstructured text
+pub(crate) fn create_synthetic_opportunities_from_text(text: &str, limit: usize) -> Vec<Opportunity> {
+    let mut opportunities = Vec::new();
+    if limit == 0 {
+        return opportunities;
+    }
+
+    // Simple heuristic: look for numbered items or bullet points
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with(|c: char| c.is_numeric())
+            || trimmed.starts_with('-')
+            || trimmed.starts_with('*')
+        {
+            // Extract the opportunity title
+            let title = trimmed
+                .trim_start_matches(|c: char| c.is_numeric() || c == '.' || c == '-' || c == '*')
+                .trim()
+                .to_string();
+
+            if !title.is_empty() && title.len() > 10 {
+                let mut opp = Opportunity::new(
+                    title.clone(),
+                    format!("Market opportunity: {}", title),
+                    "General".to_string(),
+                    ProductType::SaaS,
+                );
+
+                opp.sources.push(DataSource {
+                    name: "LLM Analysis (parsed)".to_string(),
+                    source_type: SourceType::LLMAnalysis,
+                    url: None,
+                    confidence: 0.6,
+                });
+
+                opportunities.push(opp);
+
+                if opportunities.len() >= limit {
+                    break;
+                }
+            }
+        }
+    }
+
+    opportunities
+}
+
+/// Serve a single canned HTTP response and return its URL
+#[cfg(test)]
+pub(crate) async fn serve_once(body: impl Into<String>) -> String {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let body = body.into();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 8192];
+        let _ = socket.read(&mut buf).await;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+    });
+
+    format!("http://{}/", addr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DEFAULT_TARGET_OPPORTUNITY_COUNT;
+    use agentic_runtime::llm::MockLlmClient;
+    use agentic_runtime::test_support::ScriptedLlmClient;
+
+    #[test]
+    fn test_parse_llm_opportunities_json() {
+        let content = r#"Here you go:
+[{"title": "Invoice Autopilot", "description": "Automated invoicing", "domain": "Finance", "initial_investment": 500.0}]"#;
+
+        let opps = parse_llm_opportunities(content, DEFAULT_TARGET_OPPORTUNITY_COUNT);
+        assert_eq!(opps.len(), 1);
+        assert_eq!(opps[0].domain, "Finance");
+        assert_eq!(opps[0].financial_projection.initial_investment, 500.0);
+        assert_eq!(opps[0].sources[0].source_type, SourceType::LLMAnalysis);
+    }
+
+    #[test]
+    fn test_target_count_sets_prompt_and_parse_cap() {
+        let preferences = UserPreferences { target_opportunity_count: 3, ..Default::default() };
+        let source = LlmOpportunitySource::new(Arc::new(MockLlmClient::default()), "mock-model");
+        assert!(source.build_prompt(&preferences).starts_with("Generate 3 innovative"));
+
+        let json: Vec<serde_json::Value> = (1..=5)
+            .map(|i| serde_json::json!({"title": format!("Idea {}", i), "description": "d"}))
+            .collect();
+        let parsed = parse_llm_opportunities(&serde_json::to_string(&json).unwrap(), 3);
+        assert_eq!(parsed.len(), 3);
+
+        let text = (1..=5).map(|i| format!("{}. Automated bookkeeping idea {}", i, i)).collect::<Vec<_>>().join("\n");
+        assert_eq!(parse_llm_opportunities(&text, 3).len(), 3);
+    }
+
+    #[test]
+    fn test_prompt_includes_examples_and_instructions() {
+        let mut example = Opportunity::new(
+            "Clinic Slot Filler".to_string(),
+            "Fills last-minute cancellations for physiotherapy clinics".to_string(),
+            "HealthTech".to_string(),
+            ProductType::SaaS,
+        );
+        example.financial_projection.revenue_model = "Per-booking fee".to_string();
+
+        let preferences = UserPreferences {
+            domain: Some("HealthTech".to_string()),
+            examples: vec![example],
+            extra_instructions: Some("Only B2B products for small clinics".to_string()),
+            ..Default::default()
+        };
+        let source = LlmOpportunitySource::new(Arc::new(MockLlmClient::default()), "mock-model");
+        let prompt = source.build_prompt(&preferences);
+
+        assert!(prompt.contains("Only B2B products for small clinics"));
+        assert!(prompt.contains(r#""title":"Clinic Slot Filler""#));
+        assert!(prompt.contains("Per-booking fee"));
+        assert!(!source.build_prompt(&UserPreferences::default()).contains("Examples"));
+    }
+
+    #[tokio::test]
+    async fn test_trend_discovery_uses_discovery_temperature() {
+        let llm = Arc::new(ScriptedLlmClient::new(["1. AI-powered meal planning for athletes"]));
+        let source = TrendOpportunitySource::new(llm.clone(), "mock-model");
+
+        source.discover(&UserPreferences::default()).await.unwrap();
+
+        let policy = agentic_runtime::TemperaturePolicy::global();
+        let temperature = llm.last_request().unwrap().temperature.unwrap();
+        assert_eq!(temperature, policy.temperature_for(TaskKind::Discovery));
+        assert!(temperature > policy.temperature_for(TaskKind::Code));
+    }
+
+    #[tokio::test]
+    async fn test_trend_source_tags_opportunities() {
+        let llm = Arc::new(MockLlmClient::new("1. AI-powered meal planning for athletes"));
+        let source = TrendOpportunitySource::new(llm, "mock-model");
+
+        let opps = source.discover(&UserPreferences::default()).await.unwrap();
+        assert_eq!(opps.len(), 1);
+        assert!(opps[0]
+            .sources
+            .iter()
+            .any(|s| s.source_type == SourceType::TrendAnalysis));
+    }
+}
@@ -0,0 +1,306 @@
+//! Consolidated report for one run of the business pipeline
+//!
+//! Discovery, validation, development and revenue generation each produce
+//! their own (large) result. A [`PipelineReport`] keeps the outcome that
+//! matters from each stage so a run can be reviewed in one place.
+
+use crate::development::{DevelopmentStatus, ProductDevelopmentManager, ProductDevelopmentResult};
+use crate::models::{Opportunity, OpportunityId};
+use crate::revenue::{RevenueGenerationManager, RevenueGenerationResult, RevenueGenerationStatus};
+use crate::validation::{BusinessValidationManager, ComprehensiveValidationReport, ValidationRecommendation};
+use agentic_core::Result;
+use agentic_meta::DecisionLog;
+use agentic_runtime::llm::LlmClient;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// The opportunity a pipeline run was started for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpportunityOutcome {
+    pub id: OpportunityId,
+    pub title: String,
+    pub domain: String,
+    pub score: f64,
+}
+
+/// Go/no-go decision from the validation stage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationOutcome {
+    pub recommendation: ValidationRecommendation,
+    pub score: f64,
+    pub confidence: f64,
+    pub rationale: String,
+}
+
+/// How far the development stage got
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevelopmentOutcome {
+    pub status: DevelopmentStatus,
+    pub completion_percentage: f64,
+    pub phases_completed: Vec<String>,
+    pub deployment_url: Option<String>,
+}
+
+/// Projected figures from discovery, plus actuals once revenue generation ran
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevenueOutcome {
+    pub projected_monthly_revenue: f64,
+    pub projected_roi_12_months: f64,
+    pub break_even_months: f64,
+    pub status: Option<RevenueGenerationStatus>,
+    pub revenue_generated: Option<f64>,
+    pub roi: Option<f64>,
+}
+
+/// Key outcome of every stage of one pipeline run
+///
+/// Stages that did not run (e.g. development after a `NoGo`) are `None`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineReport {
+    pub id: Uuid,
+    pub generated_at: DateTime<Utc>,
+    pub opportunity: OpportunityOutcome,
+    pub validation: Option<ValidationOutcome>,
+    pub development: Option<DevelopmentOutcome>,
+    pub revenue: RevenueOutcome,
+}
+
+impl PipelineReport {
+    /// Report for `opportunity` before any later stage has run
+    pub fn new(opportunity: &Opportunity) -> Self {
+        let projection = &opportunity.financial_projection;
+        Self {
+            id: Uuid::new_v4(),
+            generated_at: Utc::now(),
+            opportunity: OpportunityOutcome {
+                id: opportunity.id,
+                title: opportunity.title.clone(),
+                domain: opportunity.domain.clone(),
+                score: opportunity.scores.overall,
+            },
+            validation: None,
+            development: None,
+            revenue: RevenueOutcome {
+                projected_monthly_revenue: projection.monthly_revenue_mid,
+                projected_roi_12_months: projection.roi_12_months,
+                break_even_months: projection.break_even_months,
+                status: None,
+                revenue_generated: None,
+                roi: None,
+            },
+        }
+    }
+
+    pub fn with_validation(mut self, report: &ComprehensiveValidationReport) -> Self {
+        self.validation = Some(ValidationOutcome {
+            recommendation: report.recommendation,
+            score: report.overall_validation_score,
+            confidence: report.confidence_level,
+            rationale: report.decision_rationale.clone(),
+        });
+        self
+    }
+
+    pub fn with_development(mut self, result: &ProductDevelopmentResult) -> Self {
+        self.development = Some(DevelopmentOutcome {
+            status: result.status,
+            completion_percentage: result.completion_percentage,
+            phases_completed: result.phases_completed.clone(),
+            deployment_url: result.deployment_url.clone(),
+        });
+        self
+    }
+
+    pub fn with_revenue(mut self, result: &RevenueGenerationResult) -> Self {
+        self.revenue.status = Some(result.status);
+        self.revenue.revenue_generated = Some(result.total_revenue_generated);
+        self.revenue.roi = Some(result.roi);
+        self
+    }
+
+    /// Render the report as Markdown, one section per stage
+    pub fn to_markdown(&self) -> String {
+        let not_run = "_Stage not run_\n";
+        let mut out = format!(
+            "# Pipeline Report: {}\n\nRun `{}` generated {}\n",
+            self.opportunity.title,
+            self.id,
+            self.generated_at.format("%Y-%m-%d %H:%M UTC")
+        );
+
+        out.push_str("\n## Opportunity\n\n");
+        out.push_str(&format!(
+            "- **ID:** {}\n- **Domain:** {}\n- **Score:** {:.1}/10\n",
+            self.opportunity.id, self.opportunity.domain, self.opportunity.score
+        ));
+
+        out.push_str("\n## Validation\n\n");
+        match &self.validation {
+            Some(v) => out.push_str(&format!(
+                "- **Recommendation:** {:?}\n- **Score:** {:.1}/10 (confidence {:.0}%)\n\n{}\n",
+                v.recommendation,
+                v.score,
+                v.confidence * 100.0,
+                v.rationale
+            )),
+            None => out.push_str(not_run),
+        }
+
+        out.push_str("\n## Development\n\n");
+        match &self.development {
+            Some(d) => {
+                out.push_str(&format!(
+                    "- **Status:** {:?}\n- **Completion:** {:.0}%\n",
+                    d.status, d.completion_percentage
+                ));
+                if !d.phases_completed.is_empty() {
+                    out.push_str(&format!("- **Phases completed:** {}\n", d.phases_completed.join(", ")));
+                }
+                if let Some(url) = &d.deployment_url {
+                    out.push_str(&format!("- **Deployment:** {}\n", url));
+                }
+            }
+            None => out.push_str(not_run),
+        }
+
+        let r = &self.revenue;
+        out.push_str("\n## Revenue\n\n");
+        out.push_str(&format!(
+            "- **Projected monthly revenue:** ${:.2}\n- **Projected 12-month ROI:** {:.1}%\n- **Break-even:** {:.1} months\n",
+            r.projected_monthly_revenue, r.projected_roi_12_months, r.break_even_months
+        ));
+        if let (Some(status), Some(generated), Some(roi)) = (r.status, r.revenue_generated, r.roi) {
+            out.push_str(&format!(
+                "- **Status:** {:?}\n- **Revenue generated:** ${:.2}\n- **ROI:** {:.1}%\n",
+                status, generated, roi
+            ));
+        }
+
+        out
+    }
+}
+
+/// Validate, develop and monetize `opportunity`, stopping after validation on `NoGo`
+///
+/// Validation and monetization decisions are recorded to `decision_log` if given.
+pub async fn run_pipeline(
+    llm_client: Arc<dyn LlmClient>,
+    opportunity: &Opportunity,
+    marketing_budget: f64,
+    decision_log: Option<DecisionLog>,
+) -> Result<PipelineReport> {
+    let mut validation_manager = BusinessValidationManager::new(llm_client.clone());
+    let mut revenue_manager = RevenueGenerationManager::new(llm_client.clone());
+    if let Some(log) = decision_log {
+        validation_manager = validation_manager.with_decision_log(log.clone());
+        revenue_manager = revenue_manager.with_decision_log(log);
+    }
+
+    run_pipeline_with(&mut validation_manager, &mut revenue_manager, llm_client, opportunity, marketing_budget).await
+}
+
+/// [`run_pipeline`] through existing managers, so decisions and metrics
+/// of every run are attributed to the same agents
+pub async fn run_pipeline_with(
+    validation_manager: &mut BusinessValidationManager,
+    revenue_manager: &mut RevenueGenerationManager,
+    llm_client: Arc<dyn LlmClient>,
+    opportunity: &Opportunity,
+    marketing_budget: f64,
+) -> Result<PipelineReport> {
+    info!("Running business pipeline for: {}", opportunity.title);
+
+    let validation = validation_manager.validate(opportunity).await?;
+    let report = PipelineReport::new(opportunity).with_validation(&validation);
+    if validation.recommendation == ValidationRecommendation::NoGo {
+        info!("Pipeline stopped: {} was not recommended", opportunity.title);
+        return Ok(report);
+    }
+
+    let development = ProductDevelopmentManager::new(llm_client)
+        .develop(opportunity, &validation)
+        .await?;
+    let report = report.with_development(&development);
+
+    let revenue = revenue_manager
+        .generate_revenue(opportunity, &validation, &development, marketing_budget)
+        .await?;
+
+    Ok(report.with_revenue(&revenue))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProductType;
+    use agentic_runtime::llm::MockLlmClient;
+
+    fn opportunity() -> Opportunity {
+        Opportunity::new(
+            "Test SaaS".to_string(),
+            "A test product".to_string(),
+            "SaaS".to_string(),
+            ProductType::SaaS,
+        )
+    }
+
+    fn validation(recommendation: ValidationRecommendation) -> ValidationOutcome {
+        ValidationOutcome { recommendation, score: 7.3, confidence: 0.8, rationale: "Solid demand".to_string() }
+    }
+
+    #[test]
+    fn test_markdown_covers_each_stage() {
+        let mut report = PipelineReport::new(&opportunity());
+        report.opportunity.score = 8.4;
+        report.validation = Some(validation(ValidationRecommendation::Go));
+        report.development = Some(DevelopmentOutcome {
+            status: DevelopmentStatus::Complete,
+            completion_percentage: 100.0,
+            phases_completed: vec!["Design".to_string(), "Build".to_string()],
+            deployment_url: Some("https://test-saas.example".to_string()),
+        });
+        report.revenue.status = Some(RevenueGenerationStatus::Active);
+        report.revenue.revenue_generated = Some(1234.5);
+        report.revenue.roi = Some(12.0);
+
+        let markdown = report.to_markdown();
+
+        assert!(markdown.contains("# Pipeline Report: Test SaaS"));
+        assert!(markdown.contains("- **Score:** 8.4/10\n"));
+        assert!(markdown.contains("- **Recommendation:** Go\n- **Score:** 7.3/10 (confidence 80%)"));
+        assert!(markdown.contains("- **Completion:** 100%"));
+        assert!(markdown.contains("- **Phases completed:** Design, Build"));
+        assert!(markdown.contains("- **Deployment:** https://test-saas.example"));
+        assert!(markdown.contains("- **Revenue generated:** $1234.50"));
+        assert!(!markdown.contains("_Stage not run_"));
+    }
+
+    #[test]
+    fn test_markdown_marks_stages_that_did_not_run() {
+        let mut report = PipelineReport::new(&opportunity());
+        report.validation = Some(validation(ValidationRecommendation::NoGo));
+
+        let markdown = report.to_markdown();
+
+        assert!(markdown.contains("- **Recommendation:** NoGo"));
+        assert!(markdown.contains("## Development\n\n_Stage not run_"));
+        assert!(!markdown.contains("Revenue generated"));
+        assert!(markdown.contains(&format!("${:.2}", report.revenue.projected_monthly_revenue)));
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_stops_after_no_go_only() {
+        let opportunity = opportunity();
+
+        let report = run_pipeline(Arc::new(MockLlmClient::default()), &opportunity, 1000.0, None).await.unwrap();
+
+        assert_eq!(report.opportunity.id, opportunity.id);
+        let validation = report.validation.as_ref().unwrap();
+        let stopped = validation.recommendation == ValidationRecommendation::NoGo;
+        assert_eq!(report.development.is_none(), stopped);
+        assert_eq!(report.revenue.revenue_generated.is_none(), stopped);
+    }
+}
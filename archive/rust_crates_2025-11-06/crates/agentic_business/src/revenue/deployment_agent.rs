@@ -3,7 +3,8 @@
 use super::models::*;
 use crate::models::Opportunity;
 use agentic_core::{Agent, AgentRole, Result};
-use agentic_runtime::llm::{LlmClient, LlmRequest};
+use agentic_runtime::llm::{LlmClient, LlmRequest, Message};
+use agentic_runtime::TaskKind;
 use std::sync::Arc;
 use tracing::{info, debug};
 
@@ -68,13 +69,10 @@ impl DeploymentAgent {
             opportunity.product_type
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(50),
-            temperature: Some(0.3),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(50);
 
         let response = self.llm_client.complete(request).await?;
         let provider_name = response.content.trim().to_lowercase();
@@ -3,7 +3,8 @@
 use super::models::*;
 use crate::models::Opportunity;
 use agentic_core::{Agent, AgentRole, Result};
-use agentic_runtime::llm::{LlmClient, LlmRequest};
+use agentic_runtime::llm::{LlmClient, LlmRequest, Message};
+use agentic_runtime::TaskKind;
 use std::sync::Arc;
 use tracing::{info, debug};
 use uuid::Uuid;
@@ -94,13 +95,10 @@ impl MarketingAgent {
             opportunity.product_type
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(100),
-            temperature: Some(0.5),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(100);
 
         let response = self.llm_client.complete(request).await?;
         let channels_str = response.content.to_lowercase();
@@ -183,13 +181,10 @@ impl MarketingAgent {
             opportunity.domain
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(150),
-            temperature: Some(0.7),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Creative)
+            .with_max_tokens(150);
 
         let response = self.llm_client.complete(request).await?;
         Ok(response.content.trim().to_string())
@@ -211,13 +206,10 @@ impl MarketingAgent {
             opportunity.description
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(300),
-            temperature: Some(0.8),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Creative)
+            .with_max_tokens(300);
 
         let response = self.llm_client.complete(request).await?;
 
@@ -255,13 +247,10 @@ impl MarketingAgent {
             opportunity.title
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(50),
-            temperature: Some(0.8),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Creative)
+            .with_max_tokens(50);
 
         let response = self.llm_client.complete(request).await?;
         Ok(response.content.trim().to_string())
@@ -292,13 +281,10 @@ impl MarketingAgent {
             "software"
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(1000),
-            temperature: Some(0.7),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Creative)
+            .with_max_tokens(1000);
 
         let response = self.llm_client.complete(request).await?;
         Ok(response.content)
@@ -323,13 +309,10 @@ impl MarketingAgent {
             count
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(500),
-            temperature: Some(0.8),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Creative)
+            .with_max_tokens(500);
 
         let response = self.llm_client.complete(request).await?;
 
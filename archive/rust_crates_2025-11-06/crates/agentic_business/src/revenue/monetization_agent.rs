@@ -2,17 +2,20 @@
 
 use super::models::*;
 use crate::models::Opportunity;
-use agentic_core::{Agent, AgentRole, Result};
-use agentic_runtime::llm::{LlmClient, LlmRequest};
+use agentic_core::{Agent, AgentId, AgentRole, Result};
+use agentic_meta::DecisionLog;
+use agentic_runtime::llm::{LlmClient, LlmRequest, Message};
+use agentic_runtime::TaskKind;
 use serde_json::json;
 use std::sync::Arc;
-use tracing::{info, debug};
+use tracing::{info, debug, warn};
 use uuid::Uuid;
 
 /// Monetization Agent - Sets up payment infrastructure and pricing
 pub struct MonetizationAgent {
     agent: Agent,
     llm_client: Arc<dyn LlmClient>,
+    decision_log: Option<DecisionLog>,
 }
 
 impl MonetizationAgent {
@@ -34,7 +37,27 @@ impl MonetizationAgent {
         // Configure agent to be standards-compliant
         crate::configure_standards_compliant_agent(&mut agent);
 
-        Self { agent, llm_client }
+        Self { agent, llm_client, decision_log: None }
+    }
+
+    /// Record provider and pricing choices to a shared decision log
+    pub fn with_decision_log(mut self, log: DecisionLog) -> Self {
+        self.decision_log = Some(log);
+        self
+    }
+
+    /// Record decisions under `agent_id` instead of a fresh id
+    pub fn with_agent_id(mut self, agent_id: AgentId) -> Self {
+        self.agent.id = agent_id;
+        self
+    }
+
+    fn log_decision(&self, kind: &str, decision: String, rationale: String, opportunity: &Opportunity) {
+        let Some(log) = &self.decision_log else { return };
+        let inputs = (&opportunity.title, &opportunity.description, opportunity.product_type, &opportunity.domain);
+        if let Err(e) = log.record(self.agent.id.to_string(), kind, decision, rationale, &inputs) {
+            warn!("Failed to persist {} decision: {}", kind, e);
+        }
     }
 
     /// Setup monetization for an opportunity
@@ -107,24 +130,27 @@ impl MonetizationAgent {
             opportunity.domain
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(100),
-            temperature: Some(0.3),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(100);
 
         let response = self.llm_client.complete(request).await?;
         let provider_name = response.content.trim().to_lowercase();
 
-        let provider = match provider_name.as_str() {
-            s if s.contains("stripe") => PaymentProvider::Stripe,
-            s if s.contains("paypal") => PaymentProvider::PayPal,
-            s if s.contains("square") => PaymentProvider::Square,
-            s if s.contains("paddle") => PaymentProvider::Paddle,
-            _ => PaymentProvider::Stripe, // Default to Stripe
+        let (provider, rationale) = match provider_name.as_str() {
+            s if s.contains("stripe") => (PaymentProvider::Stripe, "Recommended by analysis"),
+            s if s.contains("paypal") => (PaymentProvider::PayPal, "Recommended by analysis"),
+            s if s.contains("square") => (PaymentProvider::Square, "Recommended by analysis"),
+            s if s.contains("paddle") => (PaymentProvider::Paddle, "Recommended by analysis"),
+            _ => (PaymentProvider::Stripe, "No provider recognised; defaulted to Stripe"),
         };
+        self.log_decision(
+            "payment_provider",
+            format!("{:?}", provider),
+            format!("{}: {}", rationale, response.content.trim()),
+            opportunity,
+        );
 
         debug!("Selected payment provider: {:?}", provider);
         Ok(provider)
@@ -157,13 +183,10 @@ impl MonetizationAgent {
             opportunity.domain
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(100),
-            temperature: Some(0.3),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(100);
 
         let response = self.llm_client.complete(request).await?;
         let model_name = response.content.trim().to_lowercase();
@@ -176,6 +199,12 @@ impl MonetizationAgent {
             s if s.contains("tiered") => PricingModel::Tiered,
             _ => PricingModel::Subscription, // Default
         };
+        self.log_decision(
+            "pricing_model",
+            format!("{:?}", pricing_model),
+            format!("Pricing analysis answered: {}", response.content.trim()),
+            opportunity,
+        );
 
         debug!("Selected pricing model: {:?}", pricing_model);
         Ok(pricing_model)
@@ -212,13 +241,10 @@ impl MonetizationAgent {
             opportunity.score.profitability * 100.0
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(100),
-            temperature: Some(0.5),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(100);
 
         let response = self.llm_client.complete(request).await?;
 
@@ -253,13 +279,10 @@ impl MonetizationAgent {
             opportunity.domain
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(50),
-            temperature: Some(0.3),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(50);
 
         let response = self.llm_client.complete(request).await?;
         let interval_name = response.content.trim().to_lowercase();
@@ -299,13 +322,10 @@ impl MonetizationAgent {
             opportunity.product_type
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(50),
-            temperature: Some(0.5),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(50);
 
         let response = self.llm_client.complete(request).await?;
         let trial_response = response.content.trim().to_lowercase();
@@ -535,4 +555,26 @@ mod tests {
         assert_eq!(config.opportunity_id, opportunity.id);
         assert!(config.price_point > 0.0);
     }
+
+    #[tokio::test]
+    async fn test_provider_choice_is_logged() {
+        let log = DecisionLog::new();
+        let agent = MonetizationAgent::new(Arc::new(MockLlmClient::new("Paddle")))
+            .with_decision_log(log.clone());
+        let opportunity = Opportunity::new(
+            "Test SaaS".to_string(),
+            "A test product".to_string(),
+            "SaaS".to_string(),
+            ProductType::SaaS,
+        );
+
+        let provider = agent.select_payment_provider(&opportunity).await.unwrap();
+        assert_eq!(provider, PaymentProvider::Paddle);
+
+        let decisions = log.for_agent(&agent.agent().id.to_string());
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].kind, "payment_provider");
+        assert_eq!(decisions[0].decision, "Paddle");
+        assert!(decisions[0].rationale.contains("Paddle"));
+    }
 }
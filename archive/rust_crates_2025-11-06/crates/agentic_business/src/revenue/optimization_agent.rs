@@ -3,7 +3,8 @@
 use super::models::*;
 use crate::models::Opportunity;
 use agentic_core::{Agent, AgentRole, Result};
-use agentic_runtime::llm::{LlmClient, LlmRequest};
+use agentic_runtime::llm::{LlmClient, LlmRequest, Message};
+use agentic_runtime::TaskKind;
 use std::sync::Arc;
 use tracing::{info, debug};
 use uuid::Uuid;
@@ -63,13 +64,10 @@ impl OptimizationAgent {
             analytics.arpu
         );
 
-        let request = LlmRequest {
-            messages: vec![("user".to_string(), prompt)],
-            model: self.agent.model.clone(),
-            max_tokens: Some(600),
-            temperature: Some(0.7),
-            ..Default::default()
-        };
+        let request = LlmRequest::new(self.agent.model.clone())
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Creative)
+            .with_max_tokens(600);
 
         let response = self.llm_client.complete(request).await?;
 
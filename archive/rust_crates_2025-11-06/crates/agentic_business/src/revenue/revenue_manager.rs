@@ -11,9 +11,10 @@ use super::{
 use crate::models::Opportunity;
 use crate::validation::ComprehensiveValidationReport;
 use crate::development::ProductDevelopmentResult;
-use agentic_core::{Agent, AgentRole, Result, WorkflowId};
-use agentic_meta::{MetaAgent, MetaAgentMetrics};
+use agentic_core::{Agent, AgentId, AgentRole, Result, WorkflowId};
+use agentic_meta::{DecisionLog, MetaAgent, MetaAgentMetrics};
 use agentic_runtime::llm::LlmClient;
+use agentic_runtime::CallerLlmClient;
 use std::sync::Arc;
 use tracing::{info, debug};
 use chrono::Utc;
@@ -56,6 +57,8 @@ impl RevenueGenerationManager {
         // Configure agent to be standards-compliant (A2A, MCP protocols)
         crate::configure_standards_compliant_agent(&mut agent);
 
+        let llm_client = CallerLlmClient::for_manager(llm_client, &agent);
+
         Self {
             agent,
             workflow_id: WorkflowId::generate(),
@@ -69,6 +72,19 @@ impl RevenueGenerationManager {
         }
     }
 
+    /// Record monetization choices (provider, pricing model) to a decision log
+    pub fn with_decision_log(mut self, log: DecisionLog) -> Self {
+        self.monetization_agent = self.monetization_agent.with_decision_log(log);
+        self
+    }
+
+    /// Record monetization choices under `agent_id`, so managers built per
+    /// request share one id in the decision log
+    pub fn with_monetization_agent_id(mut self, agent_id: AgentId) -> Self {
+        self.monetization_agent = self.monetization_agent.with_agent_id(agent_id);
+        self
+    }
+
     /// Generate revenue from a validated and developed opportunity
     ///
     /// This orchestrates the complete revenue generation workflow:
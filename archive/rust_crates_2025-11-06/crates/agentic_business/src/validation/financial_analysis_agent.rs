@@ -2,7 +2,8 @@
 
 use crate::models::{Opportunity, FinancialProjection};
 use agentic_core::{Agent, AgentRole, Result};
-use agentic_runtime::llm::{LlmClient, LlmRequest, LlmMessage, MessageRole};
+use agentic_runtime::llm::{LlmClient, LlmRequest, Message};
+use agentic_runtime::TaskKind;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info, debug};
@@ -202,22 +203,11 @@ impl FinancialAnalysisAgent {
             opportunity.financial_projection.monthly_revenue_mid
         );
 
-        let llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: "You are a financial analyst specializing in startup revenue projections. Provide realistic, conservative estimates.".to_string(),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: prompt,
-                },
-            ],
-            temperature: Some(0.3),
-            max_tokens: Some(2048),
-            tools: None,
-        };
+        let llm_request = LlmRequest::new(self.agent.model.clone())
+            .with_system("You are a financial analyst specializing in startup revenue projections. Provide realistic, conservative estimates.")
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(2048);
 
         let _response = self.llm_client.complete(llm_request).await?;
 
@@ -2,7 +2,8 @@
 
 use crate::models::Opportunity;
 use agentic_core::{Agent, AgentRole, Result};
-use agentic_runtime::llm::{LlmClient, LlmRequest, LlmMessage, MessageRole};
+use agentic_runtime::llm::{LlmClient, LlmRequest, Message};
+use agentic_runtime::TaskKind;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info, debug};
@@ -213,22 +214,11 @@ impl MarketDemandAgent {
             opportunity.description
         );
 
-        let _llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: "You are a market research expert. Identify realistic customer segments.".to_string(),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: prompt,
-                },
-            ],
-            temperature: Some(0.4),
-            max_tokens: Some(1024),
-            tools: None,
-        };
+        let _llm_request = LlmRequest::new(self.agent.model.clone())
+            .with_system("You are a market research expert. Identify realistic customer segments.")
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(1024);
 
         // For demo, create example segments
         Ok(vec![
@@ -64,6 +64,7 @@ pub mod technical_feasibility_agent;
 pub mod market_demand_agent;
 pub mod risk_assessment_agent;
 pub mod validation_manager;
+pub mod report_export;
 
 // Re-export main types
 pub use financial_analysis_agent::{
@@ -82,6 +83,7 @@ pub use technical_feasibility_agent::{
     TechnicalFeasibilityAgent,
     TechnicalFeasibilityReport,
     TechnicalRecommendation,
+    TechStackSource,
     ImplementationComplexity,
     TechnicalRisk,
     RiskSeverity,
@@ -108,6 +110,10 @@ pub use risk_assessment_agent::{
     RiskAssessmentAgent,
     RiskAssessmentReport,
     RiskRecommendation,
+    RiskProfile,
+    RiskBaseline,
+    RiskCategoryKind,
+    DomainRiskAdjustment,
     RiskCategory,
     BusinessRisk,
     RiskLevel,
@@ -119,5 +125,7 @@ pub use risk_assessment_agent::{
 pub use validation_manager::{
     BusinessValidationManager,
     ComprehensiveValidationReport,
+    RecommendationThresholds,
     ValidationRecommendation,
+    DEFAULT_MIN_CONFIDENCE,
 };
@@ -0,0 +1,381 @@
+//! Shareable renderings of a `ComprehensiveValidationReport`
+//!
+//! The report is first flattened into titled sections of simple blocks, which
+//! are then written out as Markdown or as a standalone HTML page with print
+//! styles (so "Print to PDF" in a browser gives a clean document).
+
+use super::validation_manager::{ComprehensiveValidationReport, ValidationRecommendation};
+
+enum Block {
+    Paragraph(String),
+    Facts(Vec<(&'static str, String)>),
+    List(Vec<String>),
+    Table {
+        headers: Vec<&'static str>,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+struct Section {
+    title: &'static str,
+    blocks: Vec<Block>,
+}
+
+impl ValidationRecommendation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ValidationRecommendation::StrongGo => "Strong Go",
+            ValidationRecommendation::Go => "Go",
+            ValidationRecommendation::Conditional => "Conditional",
+            ValidationRecommendation::NoGo => "No Go",
+        }
+    }
+}
+
+impl ComprehensiveValidationReport {
+    /// Render the report as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Validation Report\n\n{}\n", self.header_line());
+
+        for section in self.sections() {
+            out.push_str(&format!("\n## {}\n", section.title));
+            for block in section.blocks {
+                out.push('\n');
+                match block {
+                    Block::Paragraph(text) => out.push_str(&format!("{}\n", text)),
+                    Block::Facts(facts) => {
+                        out.push_str("| Metric | Value |\n|---|---|\n");
+                        for (name, value) in facts {
+                            out.push_str(&format!("| {} | {} |\n", name, md_cell(&value)));
+                        }
+                    }
+                    Block::List(items) if items.is_empty() => out.push_str("_None identified_\n"),
+                    Block::List(items) => {
+                        for item in items {
+                            out.push_str(&format!("- {}\n", item));
+                        }
+                    }
+                    Block::Table { headers, rows } => {
+                        out.push_str(&format!("| {} |\n", headers.join(" | ")));
+                        out.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+                        for row in rows {
+                            let cells: Vec<String> = row.iter().map(|c| md_cell(c)).collect();
+                            out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                        }
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Render the report as a self-contained HTML page suitable for print-to-PDF
+    pub fn to_html(&self) -> String {
+        let mut body = format!(
+            "<h1>Validation Report</h1>\n<p class=\"meta\">{}</p>\n",
+            escape_html(&self.header_line())
+        );
+
+        for section in self.sections() {
+            body.push_str(&format!("<section>\n<h2>{}</h2>\n", escape_html(section.title)));
+            for block in section.blocks {
+                match block {
+                    Block::Paragraph(text) => body.push_str(&format!("<p>{}</p>\n", escape_html(&text))),
+                    Block::Facts(facts) => {
+                        body.push_str("<table>\n");
+                        for (name, value) in facts {
+                            body.push_str(&format!(
+                                "<tr><th>{}</th><td>{}</td></tr>\n",
+                                escape_html(name),
+                                escape_html(&value)
+                            ));
+                        }
+                        body.push_str("</table>\n");
+                    }
+                    Block::List(items) if items.is_empty() => body.push_str("<p><em>None identified</em></p>\n"),
+                    Block::List(items) => {
+                        body.push_str("<ul>\n");
+                        for item in items {
+                            body.push_str(&format!("<li>{}</li>\n", escape_html(&item)));
+                        }
+                        body.push_str("</ul>\n");
+                    }
+                    Block::Table { headers, rows } => {
+                        body.push_str("<table>\n<tr>");
+                        for header in headers {
+                            body.push_str(&format!("<th>{}</th>", escape_html(header)));
+                        }
+                        body.push_str("</tr>\n");
+                        for row in rows {
+                            body.push_str("<tr>");
+                            for cell in row {
+                                body.push_str(&format!("<td>{}</td>", escape_html(&cell)));
+                            }
+                            body.push_str("</tr>\n");
+                        }
+                        body.push_str("</table>\n");
+                    }
+                }
+            }
+            body.push_str("</section>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+            <title>Validation Report {}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            self.opportunity_id, PRINT_CSS, body
+        )
+    }
+
+    fn header_line(&self) -> String {
+        format!(
+            "Opportunity {} · Workflow {} · Validated {}",
+            self.opportunity_id,
+            self.workflow_id,
+            self.validation_timestamp.format("%Y-%m-%d %H:%M UTC")
+        )
+    }
+
+    fn sections(&self) -> Vec<Section> {
+        let financial = &self.financial_analysis;
+        let technical = &self.technical_feasibility;
+        let market = &self.market_demand;
+        let risk = &self.risk_assessment;
+        let stack = &technical.recommended_tech_stack;
+
+        vec![
+            Section {
+                title: "Summary",
+                blocks: vec![
+                    Block::Facts(vec![
+                        ("Recommendation", self.recommendation.label().to_string()),
+                        ("Overall score", score(self.overall_validation_score)),
+                        ("Confidence", format!("{:.0}%", self.confidence_level * 100.0)),
+                        ("Financial viability", score(financial.viability_score)),
+                        ("Technical feasibility", score(technical.feasibility_score)),
+                        ("Market demand", score(market.demand_score)),
+                        ("Risk (higher is riskier)", score(risk.overall_risk_score)),
+                    ]),
+                    Block::Paragraph(self.decision_rationale.clone()),
+                ],
+            },
+            Section { title: "Strengths", blocks: vec![Block::List(self.strengths.clone())] },
+            Section { title: "Weaknesses", blocks: vec![Block::List(self.weaknesses.clone())] },
+            Section { title: "Critical Risks", blocks: vec![Block::List(self.critical_risks.clone())] },
+            Section { title: "Success Factors", blocks: vec![Block::List(self.success_factors.clone())] },
+            Section {
+                title: "Financial Analysis",
+                blocks: vec![Block::Facts(vec![
+                    ("Viability score", score(financial.viability_score)),
+                    ("Recommendation", format!("{:?}", financial.recommendation)),
+                    ("Annual recurring revenue", usd(financial.projected_revenue.annual_recurring_revenue)),
+                    ("Month 12 revenue", usd(financial.projected_revenue.month_12)),
+                    ("Initial investment", usd(financial.cost_breakdown.total_initial_investment)),
+                    ("Monthly burn rate", usd(financial.cost_breakdown.monthly_burn_rate)),
+                    ("ROI (12 months)", format!("{:.1}%", financial.roi_analysis.roi_12_months)),
+                    ("Payback period", format!("{:.1} months", financial.roi_analysis.payback_period_months)),
+                    ("Break-even", format!("{:.1} months", financial.break_even_analysis.break_even_months)),
+                    ("Bootstrappable", yes_no(financial.funding_requirements.bootstrappable)),
+                ])],
+            },
+            Section {
+                title: "Technical Feasibility",
+                blocks: vec![
+                    Block::Facts(vec![
+                        ("Feasibility score", score(technical.feasibility_score)),
+                        ("Recommendation", format!("{:?}", technical.recommendation)),
+                        ("Overall complexity", score(technical.implementation_complexity.overall_complexity)),
+                        ("Estimated effort", format!("{:.0} person-hours", technical.implementation_complexity.estimated_person_hours)),
+                        ("Team size", technical.implementation_complexity.estimated_team_size.to_string()),
+                        ("Frontend", stack.frontend.clone().unwrap_or_else(|| "-".to_string())),
+                        ("Backend", stack.backend.clone().unwrap_or_else(|| "-".to_string())),
+                        ("Database", stack.database.clone().unwrap_or_else(|| "-".to_string())),
+                        ("Hosting", stack.hosting.clone().unwrap_or_else(|| "-".to_string())),
+                        ("Stack source", format!("{:?}", technical.tech_stack_source)),
+                    ]),
+                    Block::Table {
+                        headers: vec!["Risk", "Severity", "Probability", "Mitigation"],
+                        rows: technical
+                            .technical_risks
+                            .iter()
+                            .map(|r| {
+                                vec![
+                                    r.risk_type.clone(),
+                                    format!("{:?}", r.severity),
+                                    format!("{:.0}%", r.probability * 100.0),
+                                    r.mitigation.clone(),
+                                ]
+                            })
+                            .collect(),
+                    },
+                ],
+            },
+            Section {
+                title: "Market Demand",
+                blocks: vec![
+                    Block::Facts(vec![
+                        ("Demand score", score(market.demand_score)),
+                        ("Recommendation", format!("{:?}", market.recommendation)),
+                        ("TAM", usd(market.target_market.total_addressable_market)),
+                        ("SAM", usd(market.target_market.serviceable_addressable_market)),
+                        ("SOM", usd(market.target_market.serviceable_obtainable_market)),
+                        ("Trend", format!("{:?}", market.market_trends.overall_trend)),
+                        ("Year 1 users", market.adoption_forecast.projected_year1_users.to_string()),
+                        ("Year 2 users", market.adoption_forecast.projected_year2_users.to_string()),
+                    ]),
+                    Block::Table {
+                        headers: vec!["Segment", "Size", "Willingness to pay", "Priority"],
+                        rows: market
+                            .customer_segments
+                            .iter()
+                            .map(|s| {
+                                vec![
+                                    s.segment_name.clone(),
+                                    s.size.to_string(),
+                                    usd(s.willingness_to_pay),
+                                    format!("{:?}", s.segment_priority),
+                                ]
+                            })
+                            .collect(),
+                    },
+                ],
+            },
+            Section {
+                title: "Risk Assessment",
+                blocks: vec![
+                    Block::Facts(vec![
+                        ("Overall risk score", score(risk.overall_risk_score)),
+                        ("Recommendation", format!("{:?}", risk.recommendation)),
+                    ]),
+                    Block::Table {
+                        headers: vec!["Category", "Level", "Risks"],
+                        rows: risk
+                            .risk_categories
+                            .iter()
+                            .map(|c| {
+                                vec![
+                                    c.category_name.clone(),
+                                    format!("{:?}", c.category_risk_level),
+                                    c.risks.len().to_string(),
+                                ]
+                            })
+                            .collect(),
+                    },
+                    Block::List(
+                        risk.mitigation_strategies
+                            .iter()
+                            .map(|m| format!("{}: {} ({})", m.risk_id, m.strategy, m.timeline))
+                            .collect(),
+                    ),
+                ],
+            },
+        ]
+    }
+}
+
+const PRINT_CSS: &str = "body{font-family:-apple-system,Segoe UI,Helvetica,Arial,sans-serif;max-width:860px;\
+margin:2rem auto;color:#222;line-height:1.45}h1{border-bottom:2px solid #333}h2{margin-top:1.6rem;\
+border-bottom:1px solid #ccc}table{border-collapse:collapse;width:100%;margin:.5rem 0}th,td{border:1px solid #ccc;\
+padding:.3rem .5rem;text-align:left}th{background:#f3f3f3}.meta{color:#666}\
+@media print{body{margin:0;max-width:none}section{page-break-inside:avoid}}";
+
+fn score(value: f64) -> String {
+    format!("{:.1}/10", value)
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "Yes" } else { "No" }.to_string()
+}
+
+/// Whole dollars with thousands separators, e.g. `$1,250,000`
+fn usd(value: f64) -> String {
+    let rounded = value.round().abs() as u64;
+    let digits = rounded.to_string();
+    let mut grouped = String::new();
+    for (i, ch) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(ch);
+    }
+    if value < 0.0 && rounded > 0 {
+        format!("-${}", grouped)
+    } else {
+        format!("${}", grouped)
+    }
+}
+
+fn md_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Opportunity, ProductType};
+    use crate::validation::BusinessValidationManager;
+    use agentic_runtime::llm::MockLlmClient;
+    use std::sync::Arc;
+
+    async fn sample_report() -> ComprehensiveValidationReport {
+        let llm = Arc::new(MockLlmClient::default());
+        let opportunity = Opportunity::new(
+            "Test SaaS".to_string(),
+            "A test product".to_string(),
+            "SaaS".to_string(),
+            ProductType::SaaS,
+        );
+        let mut manager = BusinessValidationManager::new(llm);
+        let mut report = manager.validate(&opportunity).await.unwrap();
+        report.strengths = vec!["Strong margins".to_string()];
+        report.weaknesses = vec![];
+        report
+    }
+
+    #[tokio::test]
+    async fn test_markdown_contains_recommendation_and_scores() {
+        let report = sample_report().await;
+        let md = report.to_markdown();
+
+        assert!(md.starts_with("# Validation Report\n"));
+        assert!(md.contains(&format!("| Recommendation | {} |", report.recommendation.label())));
+        assert!(md.contains(&format!("| Overall score | {:.1}/10 |", report.overall_validation_score)));
+        assert!(md.contains(&format!("| Financial viability | {:.1}/10 |", report.financial_analysis.viability_score)));
+        assert!(md.contains(&format!("| Technical feasibility | {:.1}/10 |", report.technical_feasibility.feasibility_score)));
+        assert!(md.contains(&format!("| Market demand | {:.1}/10 |", report.market_demand.demand_score)));
+        assert!(md.contains(&format!("| Risk (higher is riskier) | {:.1}/10 |", report.risk_assessment.overall_risk_score)));
+        for heading in ["## Strengths", "## Financial Analysis", "## Technical Feasibility", "## Market Demand", "## Risk Assessment"] {
+            assert!(md.contains(heading), "missing {}", heading);
+        }
+        assert!(md.contains("- Strong margins\n"));
+        assert!(md.contains("## Weaknesses\n\n_None identified_\n"));
+    }
+
+    #[tokio::test]
+    async fn test_html_is_standalone_and_escaped() {
+        let mut report = sample_report().await;
+        report.decision_rationale = "Revenue <uncertain> & CAC high".to_string();
+        let html = report.to_html();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("@media print"));
+        assert!(html.contains("Revenue &lt;uncertain&gt; &amp; CAC high"));
+        assert!(html.contains(&format!("<td>{}</td>", report.recommendation.label())));
+    }
+
+    #[test]
+    fn test_usd_formatting() {
+        assert_eq!(usd(1_250_000.4), "$1,250,000");
+        assert_eq!(usd(999.0), "$999");
+        assert_eq!(usd(-1500.0), "-$1,500");
+    }
+}
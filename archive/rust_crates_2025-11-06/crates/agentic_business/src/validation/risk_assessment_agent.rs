@@ -2,10 +2,11 @@
 
 use crate::models::Opportunity;
 use agentic_core::{Agent, AgentRole, Result};
-use agentic_runtime::llm::{LlmClient, LlmRequest, LlmMessage, MessageRole};
+use agentic_runtime::llm::LlmClient;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{info, debug};
+use tracing::info;
 
 /// Risk assessment report
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,6 +48,18 @@ pub enum RiskLevel {
     Critical,
 }
 
+impl RiskLevel {
+    /// Relative weight in the overall risk score
+    fn weight(self) -> f64 {
+        match self {
+            RiskLevel::Low => 1.0,
+            RiskLevel::Medium => 2.0,
+            RiskLevel::High => 4.0,
+            RiskLevel::Critical => 8.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MitigationStrategy {
     pub risk_id: String,
@@ -80,10 +93,117 @@ pub enum RiskRecommendation {
     Unacceptable,
 }
 
+/// The six categories the agent assesses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RiskCategoryKind {
+    Market,
+    Financial,
+    Operational,
+    Technical,
+    Competitive,
+    Regulatory,
+}
+
+/// Caller-supplied probability and impact for every risk in a category
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RiskBaseline {
+    pub probability: f64, // 0-1
+    pub impact: f64,      // 0-10
+}
+
+/// Shift applied to a category when the opportunity's domain matches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DomainRiskAdjustment {
+    /// Case-insensitive substring of `Opportunity::domain`, e.g. "fintech"
+    pub domain: String,
+    pub category: RiskCategoryKind,
+    pub probability_delta: f64,
+    pub impact_delta: f64,
+}
+
+/// Probability and impact inputs for risk assessment
+///
+/// Each risk starts from the value the agent derives from the opportunity
+/// (market size, competition, complexity, ...). When the profile has a
+/// baseline for the risk's category the two are blended, with
+/// `signal_weight` going to the opportunity-derived value. Matching domain
+/// adjustments are added last. The default profile has no baselines or
+/// adjustments, so the built-in constants are used unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskProfile {
+    #[serde(default)]
+    pub baselines: HashMap<RiskCategoryKind, RiskBaseline>,
+    #[serde(default)]
+    pub domain_adjustments: Vec<DomainRiskAdjustment>,
+    /// Share of the blended value taken from opportunity signals (0-1)
+    pub signal_weight: f64,
+}
+
+impl Default for RiskProfile {
+    fn default() -> Self {
+        Self {
+            baselines: HashMap::new(),
+            domain_adjustments: Vec::new(),
+            signal_weight: 0.5,
+        }
+    }
+}
+
+impl RiskProfile {
+    /// Profile for heavily regulated markets (finance, health, insurance, legal)
+    pub fn high_regulation() -> Self {
+        let domain_adjustments = ["fintech", "finance", "banking", "health", "medical", "insurance", "legal"]
+            .into_iter()
+            .map(|domain| DomainRiskAdjustment {
+                domain: domain.to_string(),
+                category: RiskCategoryKind::Regulatory,
+                probability_delta: 0.3,
+                impact_delta: 1.0,
+            })
+            .collect();
+
+        Self {
+            domain_adjustments,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_baseline(mut self, category: RiskCategoryKind, baseline: RiskBaseline) -> Self {
+        self.baselines.insert(category, baseline);
+        self
+    }
+
+    pub fn with_domain_adjustment(mut self, adjustment: DomainRiskAdjustment) -> Self {
+        self.domain_adjustments.push(adjustment);
+        self
+    }
+
+    /// Blend a risk's signal-derived probability and impact with this profile
+    fn apply(&self, category: RiskCategoryKind, opportunity: &Opportunity, risk: &mut BusinessRisk) {
+        if let Some(baseline) = self.baselines.get(&category) {
+            let weight = self.signal_weight.clamp(0.0, 1.0);
+            risk.probability = weight * risk.probability + (1.0 - weight) * baseline.probability;
+            risk.impact = weight * risk.impact + (1.0 - weight) * baseline.impact;
+        }
+
+        let domain = opportunity.domain.to_lowercase();
+        for adjustment in &self.domain_adjustments {
+            if adjustment.category == category && domain.contains(&adjustment.domain.to_lowercase()) {
+                risk.probability += adjustment.probability_delta;
+                risk.impact += adjustment.impact_delta;
+            }
+        }
+
+        risk.probability = risk.probability.clamp(0.0, 1.0);
+        risk.impact = risk.impact.clamp(0.0, 10.0);
+    }
+}
+
 /// Risk Assessment Agent
 pub struct RiskAssessmentAgent {
     agent: Agent,
     llm_client: Arc<dyn LlmClient>,
+    profile: RiskProfile,
 }
 
 impl RiskAssessmentAgent {
@@ -103,7 +223,21 @@ impl RiskAssessmentAgent {
         // Configure agent to be standards-compliant (A2A, MCP protocols)
         crate::configure_standards_compliant_agent(&mut agent);
 
-        Self { agent, llm_client }
+        Self {
+            agent,
+            llm_client,
+            profile: RiskProfile::default(),
+        }
+    }
+
+    /// Use caller-supplied probability/impact inputs instead of the defaults
+    pub fn with_risk_profile(mut self, profile: RiskProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    pub fn risk_profile(&self) -> &RiskProfile {
+        &self.profile
     }
 
     pub fn agent(&self) -> &Agent {
@@ -115,7 +249,7 @@ impl RiskAssessmentAgent {
         info!("Performing risk assessment for: {}", opportunity.title);
 
         // Identify risks across all categories
-        let risk_categories = self.identify_all_risks(opportunity).await?;
+        let risk_categories = self.identify_all_risks(opportunity).await;
 
         // Create risk matrix
         let risk_matrix = self.create_risk_matrix(&risk_categories);
@@ -143,31 +277,21 @@ impl RiskAssessmentAgent {
         })
     }
 
-    async fn identify_all_risks(&self, opportunity: &Opportunity) -> Result<Vec<RiskCategory>> {
-        let mut categories = Vec::new();
-
-        // Market Risks
-        categories.push(self.identify_market_risks(opportunity).await?);
-
-        // Financial Risks
-        categories.push(self.identify_financial_risks(opportunity).await?);
-
-        // Operational Risks
-        categories.push(self.identify_operational_risks(opportunity).await?);
-
-        // Technical Risks
-        categories.push(self.identify_technical_risks(opportunity).await?);
-
-        // Competitive Risks
-        categories.push(self.identify_competitive_risks(opportunity).await?);
-
-        // Regulatory/Legal Risks
-        categories.push(self.identify_regulatory_risks(opportunity).await?);
+    /// Identify risks in all six categories concurrently, in a fixed order
+    async fn identify_all_risks(&self, opportunity: &Opportunity) -> Vec<RiskCategory> {
+        let (market, financial, operational, technical, competitive, regulatory) = tokio::join!(
+            self.identify_market_risks(opportunity),
+            self.identify_financial_risks(opportunity),
+            self.identify_operational_risks(opportunity),
+            self.identify_technical_risks(opportunity),
+            self.identify_competitive_risks(opportunity),
+            self.identify_regulatory_risks(opportunity),
+        );
 
-        Ok(categories)
+        vec![market, financial, operational, technical, competitive, regulatory]
     }
 
-    async fn identify_market_risks(&self, opportunity: &Opportunity) -> Result<RiskCategory> {
+    async fn identify_market_risks(&self, opportunity: &Opportunity) -> RiskCategory {
         let mut risks = Vec::new();
 
         // Market timing risk
@@ -185,20 +309,21 @@ impl RiskAssessmentAgent {
 
         // Calculate risk scores and levels
         for risk in &mut risks {
+            self.profile.apply(RiskCategoryKind::Market, opportunity, risk);
             risk.risk_score = risk.probability * risk.impact;
             risk.risk_level = Self::calculate_risk_level(risk.risk_score);
         }
 
         let category_level = risks.iter().map(|r| r.risk_level).max().unwrap_or(RiskLevel::Low);
 
-        Ok(RiskCategory {
+        RiskCategory {
             category_name: "Market Risks".to_string(),
             risks,
             category_risk_level: category_level,
-        })
+        }
     }
 
-    async fn identify_financial_risks(&self, opportunity: &Opportunity) -> Result<RiskCategory> {
+    async fn identify_financial_risks(&self, opportunity: &Opportunity) -> RiskCategory {
         let mut risks = Vec::new();
 
         risks.push(BusinessRisk {
@@ -226,20 +351,21 @@ impl RiskAssessmentAgent {
         });
 
         for risk in &mut risks {
+            self.profile.apply(RiskCategoryKind::Financial, opportunity, risk);
             risk.risk_score = risk.probability * risk.impact;
             risk.risk_level = Self::calculate_risk_level(risk.risk_score);
         }
 
         let category_level = risks.iter().map(|r| r.risk_level).max().unwrap_or(RiskLevel::Low);
 
-        Ok(RiskCategory {
+        RiskCategory {
             category_name: "Financial Risks".to_string(),
             risks,
             category_risk_level: category_level,
-        })
+        }
     }
 
-    async fn identify_operational_risks(&self, _opportunity: &Opportunity) -> Result<RiskCategory> {
+    async fn identify_operational_risks(&self, opportunity: &Opportunity) -> RiskCategory {
         let mut risks = Vec::new();
 
         risks.push(BusinessRisk {
@@ -255,20 +381,21 @@ impl RiskAssessmentAgent {
         });
 
         for risk in &mut risks {
+            self.profile.apply(RiskCategoryKind::Operational, opportunity, risk);
             risk.risk_score = risk.probability * risk.impact;
             risk.risk_level = Self::calculate_risk_level(risk.risk_score);
         }
 
         let category_level = risks.iter().map(|r| r.risk_level).max().unwrap_or(RiskLevel::Low);
 
-        Ok(RiskCategory {
+        RiskCategory {
             category_name: "Operational Risks".to_string(),
             risks,
             category_risk_level: category_level,
-        })
+        }
     }
 
-    async fn identify_technical_risks(&self, opportunity: &Opportunity) -> Result<RiskCategory> {
+    async fn identify_technical_risks(&self, opportunity: &Opportunity) -> RiskCategory {
         let mut risks = Vec::new();
 
         let complexity = opportunity.implementation_estimate.complexity_score;
@@ -286,20 +413,21 @@ impl RiskAssessmentAgent {
         });
 
         for risk in &mut risks {
+            self.profile.apply(RiskCategoryKind::Technical, opportunity, risk);
             risk.risk_score = risk.probability * risk.impact;
             risk.risk_level = Self::calculate_risk_level(risk.risk_score);
         }
 
         let category_level = risks.iter().map(|r| r.risk_level).max().unwrap_or(RiskLevel::Low);
 
-        Ok(RiskCategory {
+        RiskCategory {
             category_name: "Technical Risks".to_string(),
             risks,
             category_risk_level: category_level,
-        })
+        }
     }
 
-    async fn identify_competitive_risks(&self, opportunity: &Opportunity) -> Result<RiskCategory> {
+    async fn identify_competitive_risks(&self, opportunity: &Opportunity) -> RiskCategory {
         let mut risks = Vec::new();
 
         risks.push(BusinessRisk {
@@ -315,20 +443,21 @@ impl RiskAssessmentAgent {
         });
 
         for risk in &mut risks {
+            self.profile.apply(RiskCategoryKind::Competitive, opportunity, risk);
             risk.risk_score = risk.probability * risk.impact;
             risk.risk_level = Self::calculate_risk_level(risk.risk_score);
         }
 
         let category_level = risks.iter().map(|r| r.risk_level).max().unwrap_or(RiskLevel::Low);
 
-        Ok(RiskCategory {
+        RiskCategory {
             category_name: "Competitive Risks".to_string(),
             risks,
             category_risk_level: category_level,
-        })
+        }
     }
 
-    async fn identify_regulatory_risks(&self, _opportunity: &Opportunity) -> Result<RiskCategory> {
+    async fn identify_regulatory_risks(&self, opportunity: &Opportunity) -> RiskCategory {
         let mut risks = Vec::new();
 
         risks.push(BusinessRisk {
@@ -344,17 +473,18 @@ impl RiskAssessmentAgent {
         });
 
         for risk in &mut risks {
+            self.profile.apply(RiskCategoryKind::Regulatory, opportunity, risk);
             risk.risk_score = risk.probability * risk.impact;
             risk.risk_level = Self::calculate_risk_level(risk.risk_score);
         }
 
         let category_level = risks.iter().map(|r| r.risk_level).max().unwrap_or(RiskLevel::Low);
 
-        Ok(RiskCategory {
+        RiskCategory {
             category_name: "Regulatory Risks".to_string(),
             risks,
             category_risk_level: category_level,
-        })
+        }
     }
 
     fn calculate_risk_level(risk_score: f64) -> RiskLevel {
@@ -437,20 +567,25 @@ impl RiskAssessmentAgent {
         plans
     }
 
+    /// Severity-weighted average of all risk scores (0-10)
+    ///
+    /// Each risk counts in proportion to its level's weight, so one critical
+    /// risk isn't washed out by a handful of low ones. Every critical or high
+    /// risk then adds a flat escalation on top.
     fn calculate_overall_risk_score(&self, categories: &[RiskCategory]) -> f64 {
-        let total_score: f64 = categories
-            .iter()
-            .flat_map(|c| &c.risks)
-            .map(|r| r.risk_score)
-            .sum();
-
-        let risk_count = categories.iter().flat_map(|c| &c.risks).count() as f64;
+        let risks: Vec<&BusinessRisk> = categories.iter().flat_map(|c| &c.risks).collect();
 
-        if risk_count > 0.0 {
-            (total_score / risk_count).min(10.0)
-        } else {
-            0.0
+        let total_weight: f64 = risks.iter().map(|r| r.risk_level.weight()).sum();
+        if total_weight == 0.0 {
+            return 0.0;
         }
+
+        let weighted_score: f64 = risks.iter().map(|r| r.risk_level.weight() * r.risk_score).sum::<f64>() / total_weight;
+
+        let critical = risks.iter().filter(|r| r.risk_level == RiskLevel::Critical).count() as f64;
+        let high = risks.iter().filter(|r| r.risk_level == RiskLevel::High).count() as f64;
+
+        (weighted_score + critical * 0.5 + high * 0.25).clamp(0.0, 10.0)
     }
 
     fn make_recommendation(&self, overall_risk_score: f64, matrix: &RiskMatrix) -> RiskRecommendation {
@@ -467,3 +602,129 @@ impl RiskAssessmentAgent {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ProductType;
+    use agentic_runtime::llm::MockLlmClient;
+
+    fn regulatory_risk(report: &RiskAssessmentReport) -> &BusinessRisk {
+        report
+            .risk_categories
+            .iter()
+            .flat_map(|c| &c.risks)
+            .find(|r| r.risk_id == "REG-001")
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_high_regulation_profile_raises_regulatory_risk() {
+        let opp = Opportunity::new(
+            "Payments Reconciliation".to_string(),
+            "Automated reconciliation for small merchants".to_string(),
+            "Fintech".to_string(),
+            ProductType::SaaS,
+        );
+
+        let baseline = RiskAssessmentAgent::new(Arc::new(MockLlmClient::default()))
+            .analyze(&opp)
+            .await
+            .unwrap();
+        let regulated = RiskAssessmentAgent::new(Arc::new(MockLlmClient::default()))
+            .with_risk_profile(RiskProfile::high_regulation())
+            .analyze(&opp)
+            .await
+            .unwrap();
+
+        assert!((regulatory_risk(&baseline).probability - 0.2).abs() < f64::EPSILON);
+        assert!(regulatory_risk(&regulated).probability > regulatory_risk(&baseline).probability);
+        assert!(regulatory_risk(&regulated).risk_score > regulatory_risk(&baseline).risk_score);
+    }
+
+    #[test]
+    fn test_baseline_blends_with_signal() {
+        let opp = Opportunity::new("A".to_string(), "B".to_string(), "SaaS".to_string(), ProductType::SaaS);
+        let profile = RiskProfile::default().with_baseline(
+            RiskCategoryKind::Operational,
+            RiskBaseline { probability: 0.7, impact: 8.0 },
+        );
+        let mut risk = BusinessRisk {
+            risk_id: "OPS-001".to_string(),
+            risk_name: "Team Capacity Risk".to_string(),
+            description: String::new(),
+            category: "Operational".to_string(),
+            probability: 0.3,
+            impact: 6.0,
+            risk_score: 0.0,
+            risk_level: RiskLevel::Low,
+            indicators: Vec::new(),
+        };
+
+        profile.apply(RiskCategoryKind::Operational, &opp, &mut risk);
+
+        assert!((risk.probability - 0.5).abs() < 1e-9);
+        assert!((risk.impact - 7.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_categories_identified_concurrently() {
+        // Any LLM call in a category check would show in the wall time
+        let latency = std::time::Duration::from_millis(100);
+        let agent = RiskAssessmentAgent::new(Arc::new(MockLlmClient::default().with_latency(latency)));
+        let opp = Opportunity::new("A".to_string(), "B".to_string(), "SaaS".to_string(), ProductType::SaaS);
+
+        let start = std::time::Instant::now();
+        let categories = agent.identify_all_risks(&opp).await;
+
+        assert!(start.elapsed() < latency, "took {:?}", start.elapsed());
+        let names: Vec<_> = categories.iter().map(|c| c.category_name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["Market Risks", "Financial Risks", "Operational Risks", "Technical Risks", "Competitive Risks", "Regulatory Risks"]
+        );
+    }
+
+    fn category_of(scores: &[(f64, f64)]) -> RiskCategory {
+        let risks = scores
+            .iter()
+            .enumerate()
+            .map(|(i, &(probability, impact))| {
+                let risk_score = probability * impact;
+                BusinessRisk {
+                    risk_id: format!("R-{}", i),
+                    risk_name: format!("Risk {}", i),
+                    description: String::new(),
+                    category: "Test".to_string(),
+                    probability,
+                    impact,
+                    risk_score,
+                    risk_level: RiskAssessmentAgent::calculate_risk_level(risk_score),
+                    indicators: Vec::new(),
+                }
+            })
+            .collect();
+
+        RiskCategory {
+            category_name: "Test Risks".to_string(),
+            risks,
+            category_risk_level: RiskLevel::Low,
+        }
+    }
+
+    #[test]
+    fn test_critical_risk_not_diluted_by_lows() {
+        let agent = RiskAssessmentAgent::new(Arc::new(MockLlmClient::default()));
+
+        let mut with_critical = vec![(0.9, 9.0)];
+        with_critical.extend(std::iter::repeat_n((0.2, 5.0), 9));
+        let uniform_mediums = vec![(0.5, 8.0); 10];
+
+        let critical_score = agent.calculate_overall_risk_score(&[category_of(&with_critical)]);
+        let medium_score = agent.calculate_overall_risk_score(&[category_of(&uniform_mediums)]);
+
+        assert!(critical_score > medium_score, "{} <= {}", critical_score, medium_score);
+        assert!(critical_score <= 10.0);
+        assert_eq!(agent.calculate_overall_risk_score(&[]), 0.0);
+    }
+}
@@ -2,10 +2,11 @@
 
 use crate::models::{Opportunity, TechStack};
 use agentic_core::{Agent, AgentRole, Result};
-use agentic_runtime::llm::{LlmClient, LlmRequest, LlmMessage, MessageRole};
+use agentic_runtime::llm::{LlmClient, LlmRequest, Message, DEFAULT_MAX_CONTINUATIONS};
+use agentic_runtime::TaskKind;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::{info, debug};
+use tracing::{info, debug, warn};
 
 /// Technical feasibility report
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +14,9 @@ pub struct TechnicalFeasibilityReport {
     pub opportunity_id: uuid::Uuid,
     pub feasibility_score: f64, // 0-10
     pub recommended_tech_stack: TechStack,
+    /// Whether the stack came from the LLM or the built-in default
+    #[serde(default)]
+    pub tech_stack_source: TechStackSource,
     pub implementation_complexity: ImplementationComplexity,
     pub technical_risks: Vec<TechnicalRisk>,
     pub resource_requirements: ResourceRequirements,
@@ -22,6 +26,16 @@ pub struct TechnicalFeasibilityReport {
     pub recommendation: TechnicalRecommendation,
 }
 
+/// Origin of the recommended tech stack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TechStackSource {
+    /// Parsed from the LLM's structured response
+    Llm,
+    /// The LLM response was missing or unusable
+    #[default]
+    Default,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImplementationComplexity {
     pub overall_complexity: f64, // 0-10
@@ -142,7 +156,7 @@ impl TechnicalFeasibilityAgent {
         info!("Performing technical feasibility analysis for: {}", opportunity.title);
 
         // Step 1: Recommend tech stack
-        let tech_stack = self.recommend_tech_stack(opportunity).await?;
+        let (tech_stack, tech_stack_source) = self.recommend_tech_stack(opportunity).await?;
 
         // Step 2: Assess implementation complexity
         let complexity = self.assess_complexity(opportunity, &tech_stack).await?;
@@ -176,6 +190,7 @@ impl TechnicalFeasibilityAgent {
             opportunity_id: opportunity.id,
             feasibility_score,
             recommended_tech_stack: tech_stack,
+            tech_stack_source,
             implementation_complexity: complexity,
             technical_risks: risks,
             resource_requirements: resources,
@@ -192,7 +207,10 @@ impl TechnicalFeasibilityAgent {
     }
 
     /// Recommend optimal tech stack
-    async fn recommend_tech_stack(&self, opportunity: &Opportunity) -> Result<TechStack> {
+    ///
+    /// Falls back to [`default_tech_stack`] when the LLM reply has no valid
+    /// JSON stack in it.
+    async fn recommend_tech_stack(&self, opportunity: &Opportunity) -> Result<(TechStack, TechStackSource)> {
         debug!("Recommending tech stack");
 
         // Use LLM for smart recommendations
@@ -202,45 +220,31 @@ impl TechnicalFeasibilityAgent {
             Description: {}\n\
             Domain: {}\n\
             Complexity Score: {:.1}/10\n\n\
-            Consider: ease of development, scalability, cost, and time to market.",
+            Consider: ease of development, scalability, cost, and time to market.\n\n\
+            Respond with only a JSON object:\n\
+            {{\"frontend\": \"...\", \"backend\": \"...\", \"database\": \"...\", \"hosting\": \"...\", \"additional\": [\"...\"]}}\n\
+            Use null for frontend if the product has no user interface.",
             opportunity.title,
             opportunity.description,
             opportunity.domain,
             opportunity.implementation_estimate.complexity_score
         );
 
-        let llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: "You are a technical architect. Recommend practical, modern tech stacks.".to_string(),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: prompt,
-                },
-            ],
-            temperature: Some(0.4),
-            max_tokens: Some(1024),
-            tools: None,
-        };
+        let llm_request = LlmRequest::new(self.agent.model.clone())
+            .with_system("You are a technical architect. Recommend practical, modern tech stacks.")
+            .add_message(Message::user(prompt))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(1024);
 
-        let _response = self.llm_client.complete(llm_request).await?;
-
-        // Recommend based on product type and complexity
-        Ok(TechStack {
-            frontend: Some("React + TypeScript + TailwindCSS".to_string()),
-            backend: Some("Rust (Axum) or Node.js (Express)".to_string()),
-            database: Some("PostgreSQL + Redis".to_string()),
-            hosting: Some("Vercel (frontend) + Railway/Fly.io (backend)".to_string()),
-            additional: vec![
-                "Stripe for payments".to_string(),
-                "Resend for emails".to_string(),
-                "Clerk for auth".to_string(),
-                "Plausible for analytics".to_string(),
-            ],
-        })
+        let response = self.llm_client.complete_with_continuation(llm_request, DEFAULT_MAX_CONTINUATIONS).await?;
+
+        match parse_tech_stack(&response.content) {
+            Some(stack) => Ok((stack, TechStackSource::Llm)),
+            None => {
+                warn!("LLM tech stack response was not usable, falling back to default stack");
+                Ok((default_tech_stack(), TechStackSource::Default))
+            }
+        }
     }
 
     /// Assess implementation complexity
@@ -460,10 +464,59 @@ impl TechnicalFeasibilityAgent {
     }
 }
 
+/// Stack used when the LLM doesn't return a usable recommendation
+fn default_tech_stack() -> TechStack {
+    TechStack {
+        frontend: Some("React + TypeScript + TailwindCSS".to_string()),
+        backend: Some("Rust (Axum) or Node.js (Express)".to_string()),
+        database: Some("PostgreSQL + Redis".to_string()),
+        hosting: Some("Vercel (frontend) + Railway/Fly.io (backend)".to_string()),
+        additional: vec![
+            "Stripe for payments".to_string(),
+            "Resend for emails".to_string(),
+            "Clerk for auth".to_string(),
+            "Plausible for analytics".to_string(),
+        ],
+    }
+}
+
+/// Extract and validate the tech stack JSON object from an LLM response
+///
+/// Blank entries are dropped; a stack without both a backend and a database
+/// is rejected.
+fn parse_tech_stack(content: &str) -> Option<TechStack> {
+    let start = content.find('{')?;
+    let end = content.rfind('}')?;
+    if end < start {
+        return None;
+    }
+    let stack: TechStack = serde_json::from_str(&content[start..=end]).ok()?;
+
+    let non_blank = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let stack = TechStack {
+        frontend: non_blank(stack.frontend),
+        backend: non_blank(stack.backend),
+        database: non_blank(stack.database),
+        hosting: non_blank(stack.hosting),
+        additional: stack
+            .additional
+            .into_iter()
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect(),
+    };
+
+    if stack.backend.is_none() || stack.database.is_none() {
+        return None;
+    }
+    Some(stack)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use agentic_runtime::llm::MockLlmClient;
+    use agentic_runtime::test_support::ScriptedLlmClient;
     use crate::models::ProductType;
 
     #[tokio::test]
@@ -484,4 +537,50 @@ mod tests {
         assert!(report.feasibility_score <= 10.0);
         assert!(!report.technical_risks.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_tech_stack_parsed_from_llm_response() {
+        let response = r#"Here is my recommendation:
+{"frontend": "HTMX", "backend": "Go (chi)", "database": "PostgreSQL", "hosting": "Fly.io", "additional": ["NATS"]}"#;
+        let agent = TechnicalFeasibilityAgent::new(Arc::new(MockLlmClient::new(response)));
+
+        let opp = Opportunity::new(
+            "Test App".to_string(),
+            "A test opportunity".to_string(),
+            "SaaS".to_string(),
+            ProductType::SaaS,
+        );
+
+        let report = agent.analyze(&opp).await.unwrap();
+
+        assert_eq!(report.tech_stack_source, TechStackSource::Llm);
+        assert_eq!(report.recommended_tech_stack.backend.as_deref(), Some("Go (chi)"));
+        assert_eq!(report.recommended_tech_stack.database.as_deref(), Some("PostgreSQL"));
+        assert_eq!(report.recommended_tech_stack.additional, vec!["NATS".to_string()]);
+    }
+
+    #[test]
+    fn test_unusable_tech_stack_falls_back() {
+        assert!(parse_tech_stack("Use whatever you like").is_none());
+        assert!(parse_tech_stack(r#"{"frontend": "React", "backend": "Go", "database": " "}"#).is_none());
+        assert!(parse_tech_stack(r#"{"backend": "Go", "database": "PostgreSQL"}"#).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_truncated_tech_stack_is_continued() {
+        // The tech stack JSON in two halves, the first flagged as truncated
+        let llm = Arc::new(ScriptedLlmClient::with_turns([
+            (r#"{"frontend": null, "backend": "Go", "datab"#, "length"),
+            (r#"ase": "PostgreSQL", "hosting": "Fly.io", "additional": []}"#, "stop"),
+        ]));
+        let agent = TechnicalFeasibilityAgent::new(llm.clone());
+        let opp = Opportunity::new("API".to_string(), "Headless API".to_string(), "SaaS".to_string(), ProductType::SaaS);
+
+        let (stack, source) = agent.recommend_tech_stack(&opp).await.unwrap();
+
+        assert_eq!(llm.calls(), 2);
+        assert_eq!(source, TechStackSource::Llm);
+        assert_eq!(stack.frontend, None);
+        assert_eq!(stack.database.as_deref(), Some("PostgreSQL"));
+    }
 }
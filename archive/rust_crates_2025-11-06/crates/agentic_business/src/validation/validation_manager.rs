@@ -10,15 +10,16 @@ use super::{
     financial_analysis_agent::{FinancialAnalysisAgent, FinancialAnalysisReport},
     technical_feasibility_agent::{TechnicalFeasibilityAgent, TechnicalFeasibilityReport},
     market_demand_agent::{MarketDemandAgent, MarketDemandReport},
-    risk_assessment_agent::{RiskAssessmentAgent, RiskAssessmentReport},
+    risk_assessment_agent::{RiskAssessmentAgent, RiskAssessmentReport, RiskProfile},
 };
 use crate::models::Opportunity;
-use agentic_core::{Agent, AgentRole, Result};
-use agentic_meta::{MetaAgent, MetaAgentMetrics, WorkflowId};
+use agentic_core::{Agent, AgentId, AgentRole, Error, Result};
+use agentic_meta::{DecisionLog, MetaAgent, MetaAgentMetrics, MetaMetricsRegistry, WorkflowId};
 use agentic_runtime::llm::LlmClient;
+use agentic_runtime::CallerLlmClient;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::{info, debug};
+use tracing::{info, debug, warn};
 
 /// Comprehensive validation report aggregating all validation dimensions
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,6 +62,80 @@ pub enum ValidationRecommendation {
     NoGo,
 }
 
+impl ValidationRecommendation {
+    /// One step more cautious; `NoGo` stays `NoGo`
+    pub fn downgraded(self) -> Self {
+        match self {
+            ValidationRecommendation::StrongGo => ValidationRecommendation::Go,
+            ValidationRecommendation::Go => ValidationRecommendation::Conditional,
+            ValidationRecommendation::Conditional => ValidationRecommendation::Conditional,
+            ValidationRecommendation::NoGo => ValidationRecommendation::NoGo,
+        }
+    }
+}
+
+/// Confidence below which `StrongGo` and `Go` are downgraded one step
+pub const DEFAULT_MIN_CONFIDENCE: f64 = 0.5;
+
+/// Cut-offs used to turn validation scores into a recommendation
+///
+/// Raise them for a conservative operator, lower them for an aggressive one.
+/// Scores are on the 0-10 scale; ROI is the 12-month ROI in percent.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RecommendationThresholds {
+    /// Minimum overall score for `StrongGo` (also needs highly viable finances and strong demand)
+    pub strong_go_score: f64,
+    /// Minimum overall score for `Go`
+    pub go_score: f64,
+    /// Minimum overall score for `Conditional`; anything lower is `NoGo`
+    pub conditional_score: f64,
+    /// `Go` needs a 12-month ROI above this
+    pub go_min_roi_percent: f64,
+    /// `Go` needs a demand score of at least this
+    pub go_min_demand_score: f64,
+    /// `Go` needs an overall risk score below this
+    pub go_max_risk_score: f64,
+}
+
+impl Default for RecommendationThresholds {
+    fn default() -> Self {
+        Self {
+            strong_go_score: 8.0,
+            go_score: 6.5,
+            conditional_score: 5.0,
+            go_min_roi_percent: 50.0,
+            go_min_demand_score: 6.0,
+            go_max_risk_score: 7.0,
+        }
+    }
+}
+
+impl RecommendationThresholds {
+    /// Check scores are within 0-10 and `strong_go >= go >= conditional`
+    pub fn validate(&self) -> Result<()> {
+        let scores = [
+            ("strong_go_score", self.strong_go_score),
+            ("go_score", self.go_score),
+            ("conditional_score", self.conditional_score),
+            ("go_min_demand_score", self.go_min_demand_score),
+            ("go_max_risk_score", self.go_max_risk_score),
+        ];
+        if let Some((name, value)) = scores.iter().find(|(_, v)| !(0.0..=10.0).contains(v)) {
+            return Err(Error::InvalidArgument(format!("{} must be between 0 and 10, got {}", name, value)));
+        }
+        if !(self.strong_go_score >= self.go_score && self.go_score >= self.conditional_score) {
+            return Err(Error::InvalidArgument(format!(
+                "Thresholds must not increase from StrongGo to Conditional: {} / {} / {}",
+                self.strong_go_score, self.go_score, self.conditional_score
+            )));
+        }
+        if !self.go_min_roi_percent.is_finite() {
+            return Err(Error::InvalidArgument("go_min_roi_percent must be finite".to_string()));
+        }
+        Ok(())
+    }
+}
+
 /// Business Validation Manager - Meta-agent
 pub struct BusinessValidationManager {
     agent: Agent,
@@ -74,6 +149,14 @@ pub struct BusinessValidationManager {
 
     // Metrics tracking
     metrics: MetaAgentMetrics,
+    metrics_registry: Option<MetaMetricsRegistry>,
+    decision_log: Option<DecisionLog>,
+
+    // Recommendations issued below this confidence are downgraded
+    min_confidence: f64,
+
+    // Score cut-offs for each recommendation
+    thresholds: RecommendationThresholds,
 
     // LLM client for synthesis
     llm_client: Arc<dyn LlmClient>,
@@ -82,6 +165,12 @@ pub struct BusinessValidationManager {
 impl BusinessValidationManager {
     /// Create a new BusinessValidationManager
     pub fn new(llm_client: Arc<dyn LlmClient>) -> Self {
+        Self::for_agent(llm_client, AgentId::generate())
+    }
+
+    /// Manager acting as `agent_id`, so managers built per request record
+    /// their decisions under one id
+    pub fn for_agent(llm_client: Arc<dyn LlmClient>, agent_id: AgentId) -> Self {
         let mut agent = Agent::new(
             "BusinessValidationManager",
             "Meta-agent orchestrating comprehensive business validation across financial, technical, market, and risk dimensions",
@@ -89,6 +178,7 @@ impl BusinessValidationManager {
             "claude-3-5-sonnet-20241022",
             "anthropic",
         );
+        agent.id = agent_id;
 
         agent.add_tag("meta-agent");
         agent.add_tag("business");
@@ -98,6 +188,8 @@ impl BusinessValidationManager {
         // Configure agent to be standards-compliant (A2A, MCP protocols)
         crate::configure_standards_compliant_agent(&mut agent);
 
+        let llm_client = CallerLlmClient::for_manager(llm_client, &agent);
+
         Self {
             agent,
             workflow_id: WorkflowId::new(),
@@ -106,10 +198,49 @@ impl BusinessValidationManager {
             market_agent: MarketDemandAgent::new(llm_client.clone()),
             risk_agent: RiskAssessmentAgent::new(llm_client.clone()),
             metrics: MetaAgentMetrics::default(),
+            metrics_registry: None,
+            decision_log: None,
+            min_confidence: DEFAULT_MIN_CONFIDENCE,
+            thresholds: RecommendationThresholds::default(),
             llm_client,
         }
     }
 
+    /// Report each validation run to a shared metrics registry
+    pub fn with_metrics_registry(mut self, registry: MetaMetricsRegistry) -> Self {
+        self.metrics_registry = Some(registry);
+        self
+    }
+
+    /// Record each final recommendation and its rationale to a decision log
+    pub fn with_decision_log(mut self, log: DecisionLog) -> Self {
+        self.decision_log = Some(log);
+        self
+    }
+
+    /// Downgrade positive recommendations whose confidence is below `threshold` (0-1)
+    pub fn with_min_confidence(mut self, threshold: f64) -> Self {
+        self.min_confidence = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Use `thresholds` for Go/No-Go decisions; fails if they aren't monotonic
+    pub fn with_thresholds(mut self, thresholds: RecommendationThresholds) -> Result<Self> {
+        thresholds.validate()?;
+        self.thresholds = thresholds;
+        Ok(self)
+    }
+
+    pub fn thresholds(&self) -> &RecommendationThresholds {
+        &self.thresholds
+    }
+
+    /// Assess risk with caller-supplied probability/impact inputs
+    pub fn with_risk_profile(mut self, profile: RiskProfile) -> Self {
+        self.risk_agent = self.risk_agent.with_risk_profile(profile);
+        self
+    }
+
     /// Perform comprehensive validation of an opportunity
     ///
     /// This orchestrates 4 validation agents in parallel:
@@ -118,6 +249,40 @@ impl BusinessValidationManager {
     /// 3. Market Demand - Target market, customer segments, adoption potential
     /// 4. Risk Assessment - Multi-category risk identification and mitigation
     pub async fn validate(&mut self, opportunity: &Opportunity) -> Result<ComprehensiveValidationReport> {
+        let start_time = std::time::Instant::now();
+        let result = self.run_validation(opportunity).await;
+
+        if let Some(registry) = &self.metrics_registry {
+            registry.record_execution(&self.agent.name, start_time.elapsed(), result.is_ok());
+        }
+
+        result
+    }
+
+    /// Reuse `previous` while it is current for `opportunity`, i.e. it was
+    /// made after the opportunity was last discovered or refreshed, and
+    /// [`validate`](Self::validate) otherwise
+    ///
+    /// A reused report is recorded as a cache hit in the metrics registry.
+    pub async fn validate_or_reuse(
+        &mut self,
+        opportunity: &Opportunity,
+        previous: Option<&ComprehensiveValidationReport>,
+    ) -> Result<ComprehensiveValidationReport> {
+        let current = previous.filter(|report| {
+            report.opportunity_id == opportunity.id && report.validation_timestamp >= opportunity.discovered_at
+        });
+        if let Some(report) = current {
+            debug!("Reusing validation of {} from {}", opportunity.title, report.validation_timestamp);
+            if let Some(registry) = &self.metrics_registry {
+                registry.record_cache_hit(&self.agent.name);
+            }
+            return Ok(report.clone());
+        }
+        self.validate(opportunity).await
+    }
+
+    async fn run_validation(&mut self, opportunity: &Opportunity) -> Result<ComprehensiveValidationReport> {
         info!("🎯 Starting comprehensive validation for: {}", opportunity.title);
         let start_time = std::time::Instant::now();
 
@@ -184,15 +349,19 @@ impl BusinessValidationManager {
             &market_report,
             &risk_report,
         );
+        let (recommendation, confidence_note) = self.apply_confidence_gate(recommendation, confidence);
 
         // Generate decision rationale
-        let decision_rationale = self.generate_decision_rationale(
+        let mut decision_rationale = self.generate_decision_rationale(
             overall_score,
             recommendation,
             &strengths,
             &weaknesses,
             &critical_risks,
         );
+        if let Some(note) = confidence_note {
+            decision_rationale.push_str(&format!("\n{}\n", note));
+        }
 
         // Update metrics
         let elapsed = start_time.elapsed();
@@ -222,6 +391,19 @@ impl BusinessValidationManager {
         info!("🎉 Validation complete - Score: {:.1}/10, Recommendation: {:?}, Confidence: {:.0}%",
             overall_score, recommendation, confidence * 100.0);
 
+        if let Some(log) = &self.decision_log {
+            let inputs = (opportunity.id, &opportunity.title, overall_score, confidence);
+            if let Err(e) = log.record(
+                self.agent.id.to_string(),
+                "validation_recommendation",
+                format!("{:?}", recommendation),
+                report.decision_rationale.clone(),
+                &inputs,
+            ) {
+                warn!("Failed to persist validation decision: {}", e);
+            }
+        }
+
         Ok(report)
     }
 
@@ -442,23 +624,25 @@ impl BusinessValidationManager {
             return ValidationRecommendation::NoGo;
         }
 
+        let thresholds = &self.thresholds;
+
         // Strong Go criteria
-        if overall_score >= 8.0
+        if overall_score >= thresholds.strong_go_score
             && matches!(financial.recommendation, FinancialRecommendation::HighlyViable)
             && matches!(market.recommendation, DemandRecommendation::StrongDemand) {
             return ValidationRecommendation::StrongGo;
         }
 
         // Go criteria
-        if overall_score >= 6.5
-            && financial.roi_analysis.roi_12_months > 50.0
-            && market.demand_score >= 6.0
-            && risk.overall_risk_score < 7.0 {
+        if overall_score >= thresholds.go_score
+            && financial.roi_analysis.roi_12_months > thresholds.go_min_roi_percent
+            && market.demand_score >= thresholds.go_min_demand_score
+            && risk.overall_risk_score < thresholds.go_max_risk_score {
             return ValidationRecommendation::Go;
         }
 
         // Conditional criteria
-        if overall_score >= 5.0 {
+        if overall_score >= thresholds.conditional_score {
             return ValidationRecommendation::Conditional;
         }
 
@@ -466,6 +650,34 @@ impl BusinessValidationManager {
         ValidationRecommendation::NoGo
     }
 
+    /// Cap the recommendation when the dimension scores disagree too much
+    ///
+    /// Returns the possibly downgraded recommendation and, if it changed, a
+    /// note for the rationale.
+    fn apply_confidence_gate(
+        &self,
+        recommendation: ValidationRecommendation,
+        confidence: f64,
+    ) -> (ValidationRecommendation, Option<String>) {
+        if confidence >= self.min_confidence {
+            return (recommendation, None);
+        }
+
+        let downgraded = recommendation.downgraded();
+        if downgraded == recommendation {
+            return (recommendation, None);
+        }
+
+        let note = format!(
+            "Confidence gate: downgraded from {:?} to {:?} because confidence {:.0}% is below the {:.0}% minimum (dimension scores disagree).",
+            recommendation,
+            downgraded,
+            confidence * 100.0,
+            self.min_confidence * 100.0
+        );
+        (downgraded, Some(note))
+    }
+
     /// Generate detailed decision rationale
     fn generate_decision_rationale(
         &self,
@@ -611,6 +823,31 @@ mod tests {
         assert!(report.confidence_level <= 1.0);
     }
 
+    #[tokio::test]
+    async fn test_current_report_is_reused_as_a_cache_hit() {
+        let registry = MetaMetricsRegistry::new();
+        let mut manager = BusinessValidationManager::new(Arc::new(MockLlmClient::new()))
+            .with_metrics_registry(registry.clone());
+        let mut opp = Opportunity::new(
+            "Test SaaS Product".to_string(),
+            "A test opportunity for validation".to_string(),
+            "SaaS".to_string(),
+            ProductType::SaaS,
+        );
+
+        let report = manager.validate(&opp).await.unwrap();
+        let reused = manager.validate_or_reuse(&opp, Some(&report)).await.unwrap();
+        assert_eq!(reused.workflow_id, report.workflow_id);
+
+        // A refreshed opportunity is validated again
+        opp.discovered_at = report.validation_timestamp + chrono::Duration::seconds(1);
+        let revalidated = manager.validate_or_reuse(&opp, Some(&report)).await.unwrap();
+        assert!(revalidated.validation_timestamp > report.validation_timestamp);
+
+        let metrics = registry.get("BusinessValidationManager").unwrap();
+        assert_eq!((metrics.executions, metrics.cache_hits), (2, 1));
+    }
+
     #[tokio::test]
     async fn test_meta_agent_self_analysis() {
         let llm = Arc::new(MockLlmClient::new());
@@ -621,4 +858,57 @@ mod tests {
         assert!(analysis.contains("Financial"));
         assert!(analysis.contains("Technical"));
     }
+
+    #[test]
+    fn test_low_confidence_downgrades_recommendation() {
+        let manager = BusinessValidationManager::new(Arc::new(MockLlmClient::default()));
+
+        let (recommendation, note) = manager.apply_confidence_gate(ValidationRecommendation::StrongGo, 0.3);
+        assert_eq!(recommendation, ValidationRecommendation::Go);
+        assert!(note.unwrap().contains("downgraded from StrongGo to Go"));
+
+        let (recommendation, _) = manager.apply_confidence_gate(ValidationRecommendation::Go, 0.3);
+        assert_eq!(recommendation, ValidationRecommendation::Conditional);
+
+        let (recommendation, note) = manager.apply_confidence_gate(ValidationRecommendation::StrongGo, 0.9);
+        assert_eq!(recommendation, ValidationRecommendation::StrongGo);
+        assert!(note.is_none());
+
+        let strict = BusinessValidationManager::new(Arc::new(MockLlmClient::default())).with_min_confidence(0.95);
+        let (recommendation, _) = strict.apply_confidence_gate(ValidationRecommendation::StrongGo, 0.9);
+        assert_eq!(recommendation, ValidationRecommendation::Go);
+    }
+
+    #[tokio::test]
+    async fn test_stricter_thresholds_turn_go_into_conditional() {
+        use super::super::{DemandRecommendation, FinancialRecommendation, RiskRecommendation, TechnicalRecommendation};
+
+        let opp = Opportunity::new("Niche CRM".into(), "For dentists".into(), "SaaS".into(), ProductType::SaaS);
+        let mut manager = BusinessValidationManager::new(Arc::new(MockLlmClient::default()));
+        let mut report = manager.validate(&opp).await.unwrap();
+        report.financial_analysis.recommendation = FinancialRecommendation::Viable;
+        report.financial_analysis.roi_analysis.roi_12_months = 80.0;
+        report.technical_feasibility.recommendation = TechnicalRecommendation::Feasible;
+        report.market_demand.recommendation = DemandRecommendation::ModerateDemand;
+        report.market_demand.demand_score = 7.0;
+        report.risk_assessment.recommendation = RiskRecommendation::Manageable;
+        report.risk_assessment.overall_risk_score = 4.0;
+        let recommend = |manager: &BusinessValidationManager| {
+            manager.make_recommendation(
+                7.0,
+                &report.financial_analysis,
+                &report.technical_feasibility,
+                &report.market_demand,
+                &report.risk_assessment,
+            )
+        };
+        assert_eq!(recommend(&manager), ValidationRecommendation::Go);
+
+        let conservative = RecommendationThresholds { strong_go_score: 9.0, go_score: 7.5, ..Default::default() };
+        let manager = manager.with_thresholds(conservative).unwrap();
+        assert_eq!(recommend(&manager), ValidationRecommendation::Conditional);
+
+        let inverted = RecommendationThresholds { go_score: 4.0, conditional_score: 5.0, ..Default::default() };
+        assert!(manager.with_thresholds(inverted).is_err());
+    }
 }
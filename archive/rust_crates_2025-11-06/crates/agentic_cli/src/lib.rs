@@ -1,8 +1,9 @@
 //! CLI helpers (library side) for scaffolding standardized agents
 
-use agentic_core::Result;
+use agentic_core::{Agent, Error, Result};
 use agentic_factory::{AgentFactory, AgentRegistry};
-use agentic_standards::{StandardsAgent, StandardsRegistry};
+use agentic_standards::{ComplianceLevel, StandardId, StandardsAgent, StandardsRegistry, TEMPLATE_CONFIG_KEY};
+use std::path::Path;
 
 pub fn scaffold_standardized_agent(template_id: &str, name: &str, description: &str) -> Result<()> {
     let standards_agent = StandardsAgent::new();
@@ -25,11 +26,9 @@ pub fn scaffold_standardized_agent(template_id: &str, name: &str, description: &
 pub fn list_templates() -> Vec<(String, String)> {
     let sa = StandardsAgent::new();
     let reg: &StandardsRegistry = sa.registry();
-    // MVP: we don't have iteration API; list known ids
-    let known = vec!["tmpl.standard.worker".to_string()];
-    known
+    reg.templates()
         .into_iter()
-        .filter_map(|id| reg.get_template(&id).map(|t| (id, t.display_name.clone())))
+        .map(|t| (t.template_id.clone(), t.display_name.clone()))
         .collect()
 }
 
@@ -45,7 +44,7 @@ pub fn create_and_register(template_id: &str, name: &str, description: &str, reg
     let factory = AgentFactory::from_registry(standards_agent.registry().clone());
     let (agent, genome) = factory.create_from_template(template_id, name, description)?;
     let id = agent.id.to_string();
-    registry.register(agent, genome);
+    registry.register(agent, genome)?;
     Ok(id)
 }
 
@@ -56,3 +55,138 @@ pub fn list_registered(registry: &AgentRegistry) -> Vec<String> {
         .map(|a| format!("{} [{}]", a.name, a.id))
         .collect()
 }
+
+/// Which unmet standards make `audit` fail
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum FailOn {
+    /// Only unmet `Required` standards
+    #[default]
+    Required,
+    /// Unmet `Required` or `Recommended` standards
+    Recommended,
+    /// Any unmet standard, drafts included
+    Any,
+}
+
+impl FailOn {
+    pub fn fails(&self, level: ComplianceLevel) -> bool {
+        match self {
+            FailOn::Required => level == ComplianceLevel::Required,
+            FailOn::Recommended => level != ComplianceLevel::Draft,
+            FailOn::Any => true,
+        }
+    }
+}
+
+/// An agent that does not meet one of its template's standards
+#[derive(Clone, Debug)]
+pub struct AuditFinding {
+    pub agent: String,
+    pub template_id: String,
+    pub standard: StandardId,
+    pub level: ComplianceLevel,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct AuditReport {
+    pub agents_audited: usize,
+    /// Agents without a known template; they cannot be audited
+    pub skipped: Vec<String>,
+    pub findings: Vec<AuditFinding>,
+}
+
+impl AuditReport {
+    pub fn failures(&self, fail_on: FailOn) -> Vec<&AuditFinding> {
+        self.findings.iter().filter(|f| fail_on.fails(f.level)).collect()
+    }
+
+    /// Process exit code: 0 when nothing fails under `fail_on`, 1 otherwise
+    pub fn exit_code(&self, fail_on: FailOn) -> i32 {
+        if self.failures(fail_on).is_empty() { 0 } else { 1 }
+    }
+}
+
+/// Check each agent against the standards of the template it was created from
+///
+/// With `template`, only agents of that template are audited.
+pub fn audit_agents(agents: &[Agent], standards: &StandardsRegistry, template: Option<&str>) -> AuditReport {
+    let mut report = AuditReport::default();
+
+    for agent in agents {
+        let label = format!("{} [{}]", agent.name, agent.id);
+        let template_id = agent.config.get(TEMPLATE_CONFIG_KEY).and_then(|v| v.as_str());
+        if template.is_some() && template_id != template {
+            continue;
+        }
+        let Some(tmpl) = template_id.and_then(|id| standards.get_template(id)) else {
+            report.skipped.push(label);
+            continue;
+        };
+
+        report.agents_audited += 1;
+        for (level, compliance) in tmpl.compliance_by_standard(agent) {
+            if !compliance.compliant {
+                report.findings.push(AuditFinding {
+                    agent: label.clone(),
+                    template_id: tmpl.template_id.clone(),
+                    standard: compliance.standard.clone(),
+                    level,
+                    reasons: compliance.reasons(),
+                });
+            }
+        }
+    }
+
+    report
+}
+
+/// Load agents from a JSON array, as written by `serde_json::to_string(&[Agent])`
+pub fn load_agents(path: &Path) -> Result<Vec<Agent>> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| Error::InvalidArgument(format!("cannot read {}: {}", path.display(), e)))?;
+    Ok(serde_json::from_str(&raw)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(name: &str) -> Agent {
+        let sa = StandardsAgent::new();
+        let factory = AgentFactory::from_registry(sa.registry().clone());
+        factory.create_from_template("tmpl.standard.worker", name, "d").unwrap().0
+    }
+
+    #[test]
+    fn test_audit_fail_on_levels() {
+        let sa = StandardsAgent::new();
+        let mut agent = worker("no-a2a");
+        // A2A is only recommended for the standard worker
+        agent.config.remove("protocol:a2a");
+
+        let report = audit_agents(&[agent], sa.registry(), None);
+
+        assert_eq!(report.agents_audited, 1);
+        assert_eq!(report.exit_code(FailOn::Required), 0);
+        assert_eq!(report.exit_code(FailOn::Recommended), 1);
+        assert_eq!(report.exit_code(FailOn::Any), 1);
+        assert_eq!(report.failures(FailOn::Recommended)[0].standard, StandardId("std.a2a.v1".into()));
+    }
+
+    #[test]
+    fn test_audit_filters_by_template() {
+        let sa = StandardsAgent::new();
+        let mut untemplated = worker("custom");
+        untemplated.config.remove(TEMPLATE_CONFIG_KEY);
+        let agents = vec![worker("w"), untemplated];
+
+        let all = audit_agents(&agents, sa.registry(), None);
+        assert_eq!(all.agents_audited, 1);
+        assert_eq!(all.skipped.len(), 1);
+
+        let other = audit_agents(&agents, sa.registry(), Some("tmpl.other"));
+        assert_eq!(other.agents_audited, 0);
+        assert!(other.skipped.is_empty());
+    }
+}
@@ -1,4 +1,6 @@
+use agentic_cli::FailOn;
 use clap::Parser;
+use std::path::PathBuf;
 use tracing_subscriber::{fmt, EnvFilter};
 
 #[derive(Parser, Debug)]
@@ -34,6 +36,20 @@ enum Command {
     },
     /// List registered agents (in-memory, per run)
     AgentsList,
+    /// Audit agents against their templates' standards; exits 1 on failure
+    Audit {
+        /// JSON file with an array of agents
+        #[arg(long)]
+        agents: PathBuf,
+
+        /// Lowest unmet standard level that fails the audit
+        #[arg(long, value_enum, default_value_t = FailOn::Required)]
+        fail_on: FailOn,
+
+        /// Only audit agents created from this template
+        #[arg(long)]
+        template: Option<String>,
+    },
 }
 
 fn main() {
@@ -76,6 +92,35 @@ fn main() {
             let lines = unsafe { agentic_cli::list_registered(REGISTRY.as_ref().unwrap()) };
             if lines.is_empty() { println!("No agents registered yet"); } else { for l in lines { println!("{}", l); } }
         }
+        Command::Audit { agents, fail_on, template } => {
+            let agents = match agentic_cli::load_agents(&agents) {
+                Ok(agents) => agents,
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    std::process::exit(1);
+                }
+            };
+            let sa = agentic_standards::StandardsAgent::new();
+            let report = agentic_cli::audit_agents(&agents, sa.registry(), template.as_deref());
+
+            for finding in &report.findings {
+                let status = if fail_on.fails(finding.level) { "FAIL" } else { "warn" };
+                println!(
+                    "{} {} {} ({:?}): {}",
+                    status,
+                    finding.agent,
+                    finding.standard.0,
+                    finding.level,
+                    finding.reasons.join(", ")
+                );
+            }
+            for agent in &report.skipped {
+                println!("skip {}: no known template", agent);
+            }
+            let failures = report.failures(fail_on).len();
+            println!("Audited {} agent(s), {} failing finding(s)", report.agents_audited, failures);
+            std::process::exit(report.exit_code(fail_on));
+        }
     }
 }
 
@@ -1,9 +1,10 @@
 //! Agent types and traits
 
+use crate::agent_env::AgentEnv;
 use crate::identity::AgentId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Represents the role an agent plays in the system
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
@@ -161,6 +162,10 @@ pub struct Agent {
 
     /// Whether this agent is currently available for use
     pub is_available: bool,
+
+    /// Secrets for the agent's tools; never serialized
+    #[serde(skip)]
+    pub env: AgentEnv,
 }
 
 impl Agent {
@@ -190,6 +195,7 @@ impl Agent {
             updated_at: now,
             fitness_score: 0.5,
             is_available: true,
+            env: AgentEnv::default(),
         }
     }
 
@@ -237,6 +243,135 @@ impl Agent {
             self.updated_at = Utc::now();
         }
     }
+
+    /// Store a secret for the agent's tools, outside the public `config`
+    pub fn set_secret(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.env.set(key, value);
+        self.updated_at = Utc::now();
+    }
+
+    pub fn get_secret(&self, key: &str) -> Option<&str> {
+        self.env.get(key)
+    }
+
+    pub fn remove_secret(&mut self, key: &str) -> Option<String> {
+        let removed = self.env.remove(key);
+        if removed.is_some() {
+            self.updated_at = Utc::now();
+        }
+        removed
+    }
+
+    /// Set every entry of `patch` in the agent's config
+    pub fn apply_patch(&mut self, patch: &ConfigPatch) {
+        if patch.is_empty() {
+            return;
+        }
+        for (key, value) in &patch.set {
+            self.config.insert(key.clone(), value.clone());
+        }
+        self.updated_at = Utc::now();
+    }
+
+    /// Capture the agent's full state for later comparison
+    pub fn snapshot(&self) -> AgentSnapshot {
+        AgentSnapshot {
+            agent: self.clone(),
+            taken_at: Utc::now(),
+        }
+    }
+}
+
+/// Point-in-time copy of an agent's full state
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AgentSnapshot {
+    /// The agent as it was when the snapshot was taken
+    pub agent: Agent,
+
+    /// When the snapshot was taken
+    pub taken_at: DateTime<Utc>,
+}
+
+/// Config entries to set on an agent; serialized as a plain object such as
+/// `{"protocol:mcp": "1.0"}`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ConfigPatch {
+    pub set: BTreeMap<String, serde_json::Value>,
+}
+
+impl ConfigPatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also set `key` to `value`
+    pub fn with(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        self.set.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+}
+
+/// A single field that differs between two snapshots
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    /// Dotted path to the field (e.g. "config.temperature", "metrics.tasks_completed")
+    pub path: String,
+
+    /// Value in the earlier snapshot (`None` if the field was added)
+    pub before: Option<serde_json::Value>,
+
+    /// Value in the later snapshot (`None` if the field was removed)
+    pub after: Option<serde_json::Value>,
+}
+
+impl AgentSnapshot {
+    /// List the fields that changed between `self` and a later snapshot
+    ///
+    /// Nested objects (config, metrics) are compared key by key; arrays and
+    /// scalars are compared as a whole. Results are sorted by path.
+    pub fn diff(&self, other: &AgentSnapshot) -> Vec<FieldChange> {
+        let before = serde_json::to_value(&self.agent).unwrap_or_default();
+        let after = serde_json::to_value(&other.agent).unwrap_or_default();
+
+        let mut changes = Vec::new();
+        diff_values("", Some(&before), Some(&after), &mut changes);
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        changes
+    }
+}
+
+fn diff_values(
+    path: &str,
+    before: Option<&serde_json::Value>,
+    after: Option<&serde_json::Value>,
+    changes: &mut Vec<FieldChange>,
+) {
+    match (before, after) {
+        (Some(serde_json::Value::Object(a)), Some(serde_json::Value::Object(b))) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let child = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                diff_values(&child, a.get(key), b.get(key), changes);
+            }
+        }
+        (a, b) if a != b => changes.push(FieldChange {
+            path: path.to_string(),
+            before: a.cloned(),
+            after: b.cloned(),
+        }),
+        _ => {}
+    }
 }
 
 #[cfg(test)]
@@ -296,4 +431,32 @@ mod tests {
         agent.set_status(AgentStatus::Running);
         assert_eq!(agent.status, AgentStatus::Running);
     }
+
+    #[test]
+    fn test_snapshot_diff_lists_changed_keys() {
+        let mut agent = Agent::new(
+            "Test Agent",
+            "A test agent",
+            AgentRole::Worker,
+            "claude-3-opus",
+            "anthropic",
+        );
+        agent.config.insert("temperature".to_string(), serde_json::json!(0.7));
+        agent.config.insert("max_tokens".to_string(), serde_json::json!(1024));
+        let before = agent.snapshot();
+
+        agent.config.insert("temperature".to_string(), serde_json::json!(0.2));
+        agent.config.insert("top_p".to_string(), serde_json::json!(0.9));
+        let after = agent.snapshot();
+
+        let changes = before.diff(&after);
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["config.temperature", "config.top_p"]);
+
+        assert_eq!(changes[0].before, Some(serde_json::json!(0.7)));
+        assert_eq!(changes[0].after, Some(serde_json::json!(0.2)));
+        assert_eq!(changes[1].before, None);
+
+        assert!(before.diff(&before).is_empty());
+    }
 }
@@ -0,0 +1,70 @@
+//! Per-agent secrets
+//!
+//! `Agent.config` is public: it is serialized, persisted and returned by the
+//! API. Credentials an agent's tools need (API keys, tokens) go in an
+//! [`AgentEnv`] instead, which is skipped by serde and prints only its keys,
+//! so secrets never reach storage, responses or logs.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Secret key/value pairs handed to an agent's tool calls
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct AgentEnv {
+    vars: HashMap<String, String>,
+}
+
+impl AgentEnv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.vars.insert(key.into(), value.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.vars.get(key).map(String::as_str)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.vars.remove(key)
+    }
+
+    /// Names of the secrets held, sorted
+    pub fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.vars.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    pub fn len(&self) -> usize {
+        self.vars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+}
+
+impl fmt::Debug for AgentEnv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.keys().into_iter().map(|key| (key, "[REDACTED]")))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_output_hides_values() {
+        let mut env = AgentEnv::new();
+        env.set("SEARCH_API_KEY", "sk-live-123");
+
+        assert_eq!(env.get("SEARCH_API_KEY"), Some("sk-live-123"));
+        assert_eq!(format!("{:?}", env), r#"{"SEARCH_API_KEY": "[REDACTED]"}"#);
+    }
+}
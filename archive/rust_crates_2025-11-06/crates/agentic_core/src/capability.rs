@@ -86,6 +86,10 @@ pub struct CapabilityCard {
 
     /// Version of the agent
     pub version: String,
+
+    /// Handoff payload types this agent accepts (e.g., "research.findings")
+    #[serde(default)]
+    pub accepts: Vec<String>,
 }
 
 /// API endpoint information
@@ -121,9 +125,21 @@ impl CapabilityCard {
             auth_methods: vec!["oauth2".to_string(), "api_key".to_string()],
             endpoints: Vec::new(),
             version: version.into(),
+            accepts: Vec::new(),
         }
     }
 
+    /// Declare a handoff payload type this agent accepts
+    pub fn with_accepts(mut self, payload_type: impl Into<String>) -> Self {
+        self.accepts.push(payload_type.into());
+        self
+    }
+
+    /// Whether this agent accepts the given payload type
+    pub fn accepts(&self, payload_type: &str) -> bool {
+        self.accepts.iter().any(|t| t == payload_type)
+    }
+
     /// Add a capability
     pub fn with_capability(mut self, capability: Capability) -> Self {
         self.capabilities.push(capability);
@@ -147,10 +163,82 @@ impl CapabilityCard {
     }
 }
 
+/// Prefix of `Agent.config` keys that advertise a capability and its version
+/// (e.g. `cap:mcp.tools = "1.0.0"`)
+pub const CAPABILITY_CONFIG_PREFIX: &str = "cap:";
+
+/// A capability a task needs, optionally at a minimum version
+///
+/// Versions are `major.minor.patch` (missing parts count as 0). An advertised
+/// version satisfies the requirement when it has the same major version and
+/// is not older than `min_version`.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct RequiredCapability {
+    pub name: String,
+    #[serde(default)]
+    pub min_version: Option<String>,
+}
+
+impl RequiredCapability {
+    /// Any version of `name` will do
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            min_version: None,
+        }
+    }
+
+    pub fn at_least(mut self, version: impl Into<String>) -> Self {
+        self.min_version = Some(version.into());
+        self
+    }
+
+    /// Whether `version` of this capability meets the requirement
+    pub fn satisfies(&self, version: &str) -> bool {
+        let Some(min) = &self.min_version else {
+            return true;
+        };
+        match (parse_version(version), parse_version(min)) {
+            (Some(have), Some(want)) => have.0 == want.0 && have >= want,
+            _ => false,
+        }
+    }
+
+    /// Whether `agent` advertises a version of the capability that satisfies it
+    pub fn satisfied_by(&self, agent: &crate::Agent) -> bool {
+        match agent.config.get(&format!("{}{}", CAPABILITY_CONFIG_PREFIX, self.name)) {
+            Some(serde_json::Value::String(version)) => self.satisfies(version),
+            // A flag without a version only meets unversioned requirements
+            Some(_) => self.min_version.is_none(),
+            None => false,
+        }
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let mut next = || parts.next().map(|p| p.parse::<u64>().ok()).unwrap_or(Some(0));
+    let parsed = (next()?, next()?, next()?);
+    parts.next().is_none().then_some(parsed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_required_capability_versions() {
+        let codegen = RequiredCapability::new("codegen.rust").at_least("1.2");
+
+        assert!(codegen.satisfies("1.2.0"));
+        assert!(codegen.satisfies("1.10.3"));
+        assert!(!codegen.satisfies("1.1.9"));
+        // A new major version may have broken compatibility
+        assert!(!codegen.satisfies("2.0.0"));
+        assert!(!codegen.satisfies("latest"));
+        assert!(RequiredCapability::new("codegen.rust").satisfies("latest"));
+    }
+
     #[test]
     fn test_capability_creation() {
         let cap = Capability::new(
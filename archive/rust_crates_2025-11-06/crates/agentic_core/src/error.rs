@@ -53,6 +53,9 @@ pub enum Error {
     #[error("Authorization failed: {0}")]
     AuthorizationFailed(String),
 
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
     #[error("Invalid state: {0}")]
     InvalidState(String),
 
@@ -71,13 +74,94 @@ pub enum Error {
     #[error("Coordination error: {0}")]
     CoordinationError(String),
 
+    #[error("Incompatible: {0}")]
+    Incompatible(String),
+
     #[error("Policy violation: {0}")]
     PolicyViolation(String),
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// The attempt failed in a way a fresh attempt may not (e.g. unparseable
+    /// model output); executors retry these up to their configured limit
+    #[error("Retryable: {0}")]
+    Retryable(String),
+
+    #[error("Shutting down: {0}")]
+    ShuttingDown(String),
+
+    /// An execution hit one of its sandbox limits and was aborted
+    #[error("Sandbox limit exceeded: {limit} ({detail})")]
+    SandboxLimitExceeded {
+        /// Name of the limit that tripped, e.g. `llm_calls`
+        limit: String,
+        detail: String,
+        /// Steps recorded before the abort, as JSON
+        trace: serde_json::Value,
+    },
+
+    /// `source` annotated with what was being attempted when it failed
+    #[error("{context}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
 
+impl Error {
+    /// Wrap `source`, keeping it reachable through `std::error::Error::source`
+    pub fn with_context(
+        source: impl std::error::Error + Send + Sync + 'static,
+        context: impl Into<String>,
+    ) -> Self {
+        Error::Context {
+            context: context.into(),
+            source: Box::new(source),
+        }
+    }
+
+    /// This error followed by each of its sources, outermost first
+    pub fn chain(&self) -> impl Iterator<Item = &(dyn std::error::Error + 'static)> {
+        std::iter::successors(Some(self as &(dyn std::error::Error + 'static)), |e| e.source())
+    }
+
+    /// Every message in the chain joined with `": "`, for logs
+    ///
+    /// A source whose text the previous message already includes is skipped.
+    pub fn chain_message(&self) -> String {
+        let mut message = String::new();
+        for e in self.chain() {
+            let text = e.to_string();
+            if message.is_empty() {
+                message = text;
+            } else if !message.contains(&text) {
+                message.push_str(": ");
+                message.push_str(&text);
+            }
+        }
+        message
+    }
+}
+
+/// Attach context to any error on its way into [`Error`]
+pub trait ResultExt<T> {
+    fn context(self, context: impl Into<String>) -> Result<T>;
+}
+
+impl<T, E> ResultExt<T> for std::result::Result<T, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(|e| Error::with_context(e, context))
+    }
+}
+
 impl From<String> for Error {
     fn from(s: String) -> Self {
         Error::Unknown(s)
@@ -99,4 +183,29 @@ mod tests {
         let err = Error::AgentNotFound("agent-123".to_string());
         assert!(err.to_string().contains("agent-123"));
     }
+
+    #[test]
+    fn test_context_keeps_serialization_source() {
+        use std::error::Error as _;
+
+        let err = serde_json::from_str::<serde_json::Value>("{")
+            .context("Invalid feature_request")
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "Invalid feature_request");
+        let source = err.source().expect("wrapped error should have a source");
+        assert!(source.downcast_ref::<serde_json::Error>().is_some());
+    }
+
+    #[test]
+    fn test_chain_message_walks_nested_context() {
+        let inner = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        let err = Error::with_context(Error::from(inner), "Failed to load agent");
+
+        assert_eq!(err.chain().count(), 3);
+        let message = err.chain_message();
+        assert!(message.starts_with("Failed to load agent: Serialization error: "));
+        // The serde message is already part of SerializationError's text
+        assert_eq!(message.matches("EOF").count(), 1);
+    }
 }
@@ -15,17 +15,22 @@
 //! interface across the ecosystem.
 
 pub mod agent;
+pub mod agent_env;
 pub mod capability;
+pub mod clock;
 pub mod communication;
 pub mod error;
 pub mod identity;
 pub mod message;
+pub mod model_catalog;
 pub mod tool;
 
-pub use agent::{Agent, AgentRole, AgentStatus};
-pub use capability::{Capability, CapabilityCard};
+pub use agent::{Agent, AgentRole, AgentStatus, AgentSnapshot, ConfigPatch, FieldChange};
+pub use agent_env::AgentEnv;
+pub use capability::{Capability, CapabilityCard, RequiredCapability, CAPABILITY_CONFIG_PREFIX};
+pub use clock::{Clock, MockClock, SharedClock, SystemClock};
 pub use communication::{Protocol, ProtocolVersion};
-pub use error::{Error, Result};
+pub use error::{Error, Result, ResultExt};
 pub use identity::{AgentId, WorkflowId};
 pub use message::{Message, MessageContent};
 pub use tool::{Tool, ToolCall, ToolResult};
@@ -1,5 +1,6 @@
 //! Message types for agent communication
 
+use crate::clock::Clock;
 use crate::identity::{AgentId, WorkflowId};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -202,6 +203,12 @@ impl Message {
         self
     }
 
+    /// Re-stamp the creation time from `clock`
+    pub fn stamped(mut self, clock: &dyn Clock) -> Self {
+        self.timestamp = clock.now();
+        self
+    }
+
     /// Mark as requiring acknowledgment
     pub fn requires_acknowledgment(mut self) -> Self {
         self.requires_ack = true;
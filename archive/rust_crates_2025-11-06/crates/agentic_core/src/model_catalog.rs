@@ -0,0 +1,120 @@
+//! Which models each LLM provider serves
+//!
+//! `Agent.provider` and `Agent.model` are free-form strings, so nothing stops
+//! an agent from pairing `anthropic` with `gpt-4o` until the first call
+//! fails. This table lets agent creation catch that early. Models missing
+//! from the list are matched on their family prefix (`claude-`, `gpt-`, ...),
+//! so dated snapshots and new releases are still attributed correctly.
+//! [`capabilities_for_model`] says what each family can do beyond plain text.
+
+use serde::{Deserialize, Serialize};
+
+/// Known models per provider, in the provider's preferred order
+pub const PROVIDER_MODELS: &[(&str, &[&str])] = &[
+    (
+        "anthropic",
+        &[
+            "claude-3-5-sonnet-20241022",
+            "claude-3-5-haiku-20241022",
+            "claude-3-opus-20240229",
+            "claude-3-sonnet-20240229",
+            "claude-3-haiku-20240307",
+        ],
+    ),
+    (
+        "openai",
+        &["gpt-4o", "gpt-4o-mini", "gpt-4-turbo", "gpt-4", "gpt-3.5-turbo", "o1-preview", "o1-mini"],
+    ),
+    ("ollama", &["llama3.2", "llama3.1", "mistral", "qwen2.5", "gemma2", "phi3"]),
+    ("mock", &["mock-model"]),
+];
+
+/// Name prefixes of each provider's model families
+const MODEL_FAMILIES: &[(&str, &[&str])] = &[
+    ("anthropic", &["claude-"]),
+    ("openai", &["gpt-", "o1-", "o3-", "chatgpt-"]),
+    ("ollama", &["llama", "mistral", "mixtral", "qwen", "gemma", "phi", "deepseek"]),
+    ("mock", &["mock"]),
+];
+
+/// What a model supports beyond plain text completion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelCapabilities {
+    /// Accepts tool definitions and returns tool calls
+    pub tool_use: bool,
+    /// Accepts image input
+    pub vision: bool,
+    /// Prompt plus completion tokens, when known
+    pub context_window: Option<usize>,
+}
+
+const fn caps(tool_use: bool, vision: bool, context_window: usize) -> ModelCapabilities {
+    ModelCapabilities { tool_use, vision, context_window: Some(context_window) }
+}
+
+/// Capabilities by model name prefix, most specific prefix first
+const MODEL_CAPABILITIES: &[(&str, ModelCapabilities)] = &[
+    ("claude-3", caps(true, true, 200_000)),
+    ("gpt-4o", caps(true, true, 128_000)),
+    ("gpt-4-turbo", caps(true, true, 128_000)),
+    ("gpt-4", caps(true, false, 8_192)),
+    ("gpt-3.5-turbo", caps(true, false, 16_385)),
+    ("o1-", caps(false, false, 128_000)),
+    ("mock", caps(true, false, 8_192)),
+];
+
+/// Capabilities of `model`; all off for models the table doesn't cover
+pub fn capabilities_for_model(model: &str) -> ModelCapabilities {
+    let model = model.trim();
+    MODEL_CAPABILITIES
+        .iter()
+        .find(|(prefix, _)| model.starts_with(prefix))
+        .map(|(_, capabilities)| *capabilities)
+        .unwrap_or_default()
+}
+
+/// Models listed for `provider` (case-insensitive); empty if unknown
+pub fn models_for_provider(provider: &str) -> &'static [&'static str] {
+    PROVIDER_MODELS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(provider.trim()))
+        .map(|(_, models)| *models)
+        .unwrap_or(&[])
+}
+
+/// Provider serving `model`, or `None` for models no provider claims
+pub fn provider_for_model(model: &str) -> Option<&'static str> {
+    let model = model.trim();
+    PROVIDER_MODELS
+        .iter()
+        .find(|(_, models)| models.contains(&model))
+        .or_else(|| {
+            MODEL_FAMILIES
+                .iter()
+                .find(|(_, prefixes)| prefixes.iter().any(|prefix| model.starts_with(prefix)))
+        })
+        .map(|(provider, _)| *provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_models_resolve_to_their_provider() {
+        assert_eq!(provider_for_model("gpt-4o"), Some("openai"));
+        assert_eq!(provider_for_model("claude-3-opus"), Some("anthropic"));
+        assert_eq!(provider_for_model("llama3:8b"), Some("ollama"));
+        assert_eq!(provider_for_model("command-r"), None);
+        assert!(models_for_provider("OpenAI").contains(&"gpt-4o-mini"));
+        assert!(models_for_provider("unknown").is_empty());
+    }
+
+    #[test]
+    fn test_capabilities_follow_the_most_specific_family() {
+        assert!(capabilities_for_model("gpt-4o-mini").vision);
+        assert!(!capabilities_for_model("gpt-4").vision);
+        assert!(!capabilities_for_model("o1-preview").tool_use);
+        assert_eq!(capabilities_for_model("llama3:8b"), ModelCapabilities::default());
+    }
+}
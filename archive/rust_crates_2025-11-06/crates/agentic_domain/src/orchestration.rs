@@ -7,6 +7,8 @@
 //! - Hybrid patterns
 
 use agentic_core::identity::{AgentId, WorkflowId};
+use agentic_core::{CapabilityCard, Error, Result};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -64,6 +66,14 @@ pub struct Handoff {
 
     /// Conditions for successful completion
     pub completion_criteria: Option<String>,
+
+    /// Typed payload for the receiving agent
+    #[serde(default)]
+    pub payload: Value,
+
+    /// Declared type of `payload` (None for untyped handoffs)
+    #[serde(default)]
+    pub payload_type: Option<String>,
 }
 
 impl Handoff {
@@ -82,6 +92,31 @@ impl Handoff {
             priority: 50,
             required: false,
             completion_criteria: None,
+            payload: Value::Null,
+            payload_type: None,
+        }
+    }
+
+    /// Attach a typed payload
+    pub fn with_payload<T: Serialize>(mut self, payload_type: impl Into<String>, payload: &T) -> Result<Self> {
+        self.payload = serde_json::to_value(payload)?;
+        self.payload_type = Some(payload_type.into());
+        Ok(self)
+    }
+
+    /// Deserialize the payload into the receiver's type
+    pub fn payload_as<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_value(self.payload.clone())?)
+    }
+
+    /// Check the receiving agent declares it accepts this payload type
+    pub fn validate_for(&self, receiver: &CapabilityCard) -> Result<()> {
+        match &self.payload_type {
+            Some(payload_type) if !receiver.accepts(payload_type) => Err(Error::Incompatible(format!(
+                "Agent {} does not accept payload type {}",
+                receiver.name, payload_type
+            ))),
+            _ => Ok(()),
         }
     }
 
@@ -286,6 +321,29 @@ mod tests {
         assert_eq!(handoff.to_agent, to);
     }
 
+    #[test]
+    fn test_typed_handoff_payload() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Findings {
+            topic: String,
+            sources: u32,
+        }
+
+        let findings = Findings { topic: "vector databases".into(), sources: 3 };
+        let handoff = Handoff::new(AgentId::generate(), AgentId::generate(), "Research done", serde_json::json!({}))
+            .with_payload("research.findings", &findings)
+            .unwrap();
+
+        let consumer = CapabilityCard::new("writer-1", "Writer", "Writes reports", "1.0.0")
+            .with_accepts("research.findings");
+        handoff.validate_for(&consumer).unwrap();
+        assert_eq!(handoff.payload_as::<Findings>().unwrap(), findings);
+
+        let other = CapabilityCard::new("coder-1", "Coder", "Writes code", "1.0.0")
+            .with_accepts("code.spec");
+        assert!(matches!(handoff.validate_for(&other), Err(Error::Incompatible(_))));
+    }
+
     #[test]
     fn test_orchestration_configs() {
         let workflow_id = WorkflowId::generate();
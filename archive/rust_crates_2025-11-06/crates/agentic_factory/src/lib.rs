@@ -1,17 +1,77 @@
 //! AgentFactory - creates agents from standardized templates
 
-use agentic_core::{Agent, AgentRole, Result};
+use agentic_core::{
+    model_catalog, Agent, AgentRole, AgentStatus, Error, RequiredCapability, Result, CAPABILITY_CONFIG_PREFIX,
+};
 use agentic_domain::agent_genome::AgentGenome;
-use agentic_standards::{StandardsRegistry, StandardizedAgentTemplate};
+use agentic_standards::{StandardsRegistry, StandardizedAgentTemplate, TEMPLATE_CONFIG_KEY};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+
+pub mod lifecycle;
+
+pub use lifecycle::AgentLifecycleHook;
+
+/// Organization-wide defaults applied to every agent the factory creates
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FactoryDefaults {
+    /// Extra tags added after the template's tags (e.g. "org:acme")
+    pub tags: Vec<String>,
+    /// Provider overriding the template's default
+    pub provider: Option<String>,
+    /// Model overriding the template's default
+    pub model: Option<String>,
+}
 
 pub struct AgentFactory {
     registry: StandardsRegistry,
+    defaults: FactoryDefaults,
+    strict_models: bool,
 }
 
 impl AgentFactory {
     pub fn from_registry(registry: StandardsRegistry) -> Self {
-        Self { registry }
+        Self { registry, defaults: FactoryDefaults::default(), strict_models: false }
+    }
+
+    /// Reject agents whose model belongs to another provider (default: only warn)
+    pub fn with_strict_models(mut self, strict: bool) -> Self {
+        self.strict_models = strict;
+        self
+    }
+
+    /// Check that `model` is served by `provider`
+    ///
+    /// Models and providers missing from the catalog are let through, as are
+    /// all mismatches unless the factory is strict; those are only logged.
+    pub fn check_model(&self, provider: &str, model: &str) -> Result<()> {
+        let Some(expected) = model_catalog::provider_for_model(model) else {
+            return Ok(());
+        };
+        if expected.eq_ignore_ascii_case(provider.trim()) {
+            return Ok(());
+        }
+
+        let message = format!(
+            "Model {} is not served by provider {}; use provider {}",
+            model, provider, expected
+        );
+        if self.strict_models {
+            return Err(Error::InvalidArgument(message));
+        }
+        tracing::warn!("{}", message);
+        Ok(())
+    }
+
+    /// Apply org-wide defaults on top of template defaults
+    pub fn with_defaults(mut self, defaults: FactoryDefaults) -> Self {
+        self.defaults = defaults;
+        self
+    }
+
+    pub fn defaults(&self) -> &FactoryDefaults {
+        &self.defaults
     }
 
     pub fn create_from_template(
@@ -33,11 +93,18 @@ impl AgentFactory {
             tmpl.default_provider.clone(),
         );
 
-        for t in &tmpl.default_tags {
+        for t in tmpl.default_tags.iter().chain(&self.defaults.tags) {
             agent.add_tag(t.clone());
         }
+        if let Some(provider) = &self.defaults.provider {
+            agent.provider = provider.clone();
+        }
+        if let Some(model) = &self.defaults.model {
+            agent.model = model.clone();
+        }
+        self.check_model(&agent.provider, &agent.model)?;
         for cap_name in &tmpl.default_capabilities {
-            agent.config.insert(format!("cap:{}", cap_name), serde_json::json!("1.0.0"));
+            agent.config.insert(format!("{}{}", CAPABILITY_CONFIG_PREFIX, cap_name), serde_json::json!("1.0.0"));
         }
 
         // Set protocol flags to satisfy compliance for required protocols in template
@@ -54,6 +121,7 @@ impl AgentFactory {
                 agent.config.insert(key.to_string(), serde_json::json!(val));
             }
         }
+        agent.config.insert(TEMPLATE_CONFIG_KEY.to_string(), serde_json::json!(template_id));
 
         let genome = AgentGenome::new(agent.id, tmpl.display_name.clone());
 
@@ -61,19 +129,103 @@ impl AgentFactory {
     }
 }
 
+/// How `AgentRegistry::register` treats a name already used by another agent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameUniqueness {
+    /// Duplicate names are allowed
+    #[default]
+    Off,
+    /// Duplicates are renamed with a numeric suffix ("Worker" -> "Worker-2")
+    Warn,
+    /// Duplicates are rejected with `Error::Conflict`
+    Enforce,
+}
+
+impl std::str::FromStr for NameUniqueness {
+    type Err = Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "warn" => Ok(Self::Warn),
+            "enforce" => Ok(Self::Enforce),
+            other => Err(Error::InvalidArgument(format!("Unknown name uniqueness mode: {}", other))),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct AgentRegistry {
     agents: HashMap<String, Agent>,
     genomes: HashMap<String, AgentGenome>,
+    name_uniqueness: NameUniqueness,
+    hooks: Vec<Arc<dyn AgentLifecycleHook>>,
 }
 
 impl AgentRegistry {
-    pub fn new() -> Self { Self { agents: HashMap::new(), genomes: HashMap::new() } }
+    pub fn new() -> Self { Self::default() }
+
+    pub fn with_name_uniqueness(mut self, mode: NameUniqueness) -> Self {
+        self.name_uniqueness = mode;
+        self
+    }
+
+    pub fn name_uniqueness(&self) -> NameUniqueness {
+        self.name_uniqueness
+    }
 
-    pub fn register(&mut self, agent: Agent, genome: AgentGenome) {
+    /// Run `hook` on every later registration, removal and status change
+    pub fn with_hook(mut self, hook: Arc<dyn AgentLifecycleHook>) -> Self {
+        self.add_hook(hook);
+        self
+    }
+
+    pub fn add_hook(&mut self, hook: Arc<dyn AgentLifecycleHook>) {
+        self.hooks.push(hook);
+    }
+
+    /// Register an agent, or replace the entry with the same id
+    ///
+    /// Returns the name the agent was registered under, which differs from
+    /// `agent.name` when `NameUniqueness::Warn` renamed a duplicate.
+    pub fn register(&mut self, mut agent: Agent, genome: AgentGenome) -> Result<String> {
         let id = agent.id.to_string();
+
+        if self.name_taken(&agent.name, &id) {
+            match self.name_uniqueness {
+                NameUniqueness::Off => {}
+                NameUniqueness::Warn => {
+                    let renamed = (2..)
+                        .map(|n| format!("{}-{}", agent.name, n))
+                        .find(|candidate| !self.name_taken(candidate, &id))
+                        .expect("unbounded suffix search");
+                    tracing::warn!("Agent name {} is already registered, renaming to {}", agent.name, renamed);
+                    agent.name = renamed;
+                }
+                NameUniqueness::Enforce => {
+                    return Err(Error::Conflict(format!("An agent named {} is already registered", agent.name)));
+                }
+            }
+        }
+
+        let name = agent.name.clone();
         self.genomes.insert(id.clone(), genome);
-        self.agents.insert(id, agent);
+        let previous = self.agents.insert(id.clone(), agent).map(|old| old.status);
+
+        let agent = &self.agents[&id];
+        match previous {
+            None => lifecycle::notify(&self.hooks, "created", agent, |hook| hook.on_created(agent)),
+            Some(previous) if previous != agent.status => {
+                lifecycle::notify(&self.hooks, "status_changed", agent, |hook| hook.on_status_changed(agent, &previous))
+            }
+            Some(_) => {}
+        }
+        Ok(name)
+    }
+
+    fn name_taken(&self, name: &str, except_id: &str) -> bool {
+        self.agents.iter().any(|(id, agent)| id != except_id && agent.name == name)
     }
 
     pub fn list_agents(&self) -> Vec<&Agent> {
@@ -90,6 +242,208 @@ impl AgentRegistry {
 
     pub fn remove(&mut self, id: &str) -> bool {
         self.genomes.remove(id);
-        self.agents.remove(id).is_some()
+        match self.agents.remove(id) {
+            Some(agent) => {
+                lifecycle::notify(&self.hooks, "deleted", &agent, |hook| hook.on_deleted(&agent));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Agents that advertise every capability in `required`, sorted by name
+    pub fn find_capable(&self, required: &[RequiredCapability]) -> Vec<&Agent> {
+        let mut capable: Vec<&Agent> = self
+            .agents
+            .values()
+            .filter(|agent| required.iter().all(|cap| cap.satisfied_by(agent)))
+            .collect();
+        capable.sort_by(|a, b| a.name.cmp(&b.name));
+        capable
+    }
+
+    /// The capable agent a new task should go to, if any is free
+    ///
+    /// Only available agents that are idle or not yet started qualify. The
+    /// highest success rate wins; ties go to the agent that has completed
+    /// fewer tasks, to spread the load.
+    pub fn best_idle_match(&self, required: &[RequiredCapability]) -> Option<&Agent> {
+        self.find_capable(required)
+            .into_iter()
+            .filter(|agent| agent.is_available && matches!(agent.status, AgentStatus::Idle | AgentStatus::Initialized))
+            .min_by(|a, b| {
+                b.metrics
+                    .success_rate
+                    .total_cmp(&a.metrics.success_rate)
+                    .then(a.metrics.tasks_completed.cmp(&b.metrics.tasks_completed))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentic_standards::StandardsAgent;
+
+    #[test]
+    fn test_factory_defaults_apply_to_created_agents() {
+        let factory = AgentFactory::from_registry(StandardsAgent::new().registry().clone()).with_defaults(FactoryDefaults {
+            tags: vec!["org:acme".into()],
+            provider: Some("openai".into()),
+            model: Some("gpt-4o".into()),
+        });
+
+        let (agent, _) = factory.create_from_template("tmpl.standard.worker", "w", "d").unwrap();
+        // template tags are kept, org tag is added after them
+        assert_eq!(agent.tags, vec!["standard", "worker", "org:acme"]);
+        assert_eq!(agent.provider, "openai");
+        assert_eq!(agent.model, "gpt-4o");
+    }
+
+    #[test]
+    fn test_model_must_match_provider_when_strict() {
+        let mismatched = FactoryDefaults { provider: Some("anthropic".into()), model: Some("gpt-4o".into()), ..Default::default() };
+        let factory = AgentFactory::from_registry(StandardsAgent::new().registry().clone()).with_strict_models(true);
+        assert!(factory.check_model("openai", "gpt-4o").is_ok());
+
+        let err = factory
+            .with_defaults(mismatched.clone())
+            .create_from_template("tmpl.standard.worker", "w", "d")
+            .unwrap_err();
+        assert!(matches!(&err, Error::InvalidArgument(msg) if msg.contains("use provider openai")));
+
+        // Lenient factories only warn
+        let lenient = AgentFactory::from_registry(StandardsAgent::new().registry().clone()).with_defaults(mismatched);
+        assert_eq!(lenient.create_from_template("tmpl.standard.worker", "w", "d").unwrap().0.model, "gpt-4o");
+    }
+
+    fn register_named(registry: &mut AgentRegistry, name: &str) -> Result<String> {
+        let factory = AgentFactory::from_registry(StandardsAgent::new().registry().clone());
+        let (agent, genome) = factory.create_from_template("tmpl.standard.worker", name, "d").unwrap();
+        registry.register(agent, genome)
+    }
+
+    #[test]
+    fn test_find_capable_matches_only_code_generators() {
+        let mut registry = AgentRegistry::new();
+        let factory = AgentFactory::from_registry(StandardsAgent::new().registry().clone());
+        let mut register = |name: &str, caps: &[(&str, &str)]| {
+            let (mut agent, genome) = factory.create_from_template("tmpl.standard.worker", name, "d").unwrap();
+            for (cap, version) in caps {
+                agent.config.insert(format!("{}{}", CAPABILITY_CONFIG_PREFIX, cap), serde_json::json!(version));
+            }
+            registry.register(agent, genome).unwrap();
+        };
+        register("Writer", &[("docs.markdown", "1.0.0")]);
+        register("OldCoder", &[("codegen.rust", "1.0.0")]);
+        register("Coder", &[("codegen.rust", "1.3.0")]);
+
+        let required = [RequiredCapability::new("codegen.rust").at_least("1.2.0")];
+        let names: Vec<&str> = registry.find_capable(&required).iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["Coder"]);
+        assert_eq!(registry.best_idle_match(&required).unwrap().name, "Coder");
+
+        // Busy agents are not handed new work
+        let coder = registry.find_capable(&required)[0].clone();
+        let genome = registry.get_genome(&coder.id.to_string()).unwrap().clone();
+        let mut busy = coder;
+        busy.set_status(AgentStatus::Busy);
+        registry.register(busy, genome).unwrap();
+        assert!(registry.best_idle_match(&required).is_none());
+    }
+
+    /// Counts events per kind; fails every `on_created` when `failing`
+    #[derive(Default)]
+    struct CountingHook {
+        created: std::sync::atomic::AtomicUsize,
+        status_changes: std::sync::atomic::AtomicUsize,
+        deleted: std::sync::atomic::AtomicUsize,
+        failing: bool,
+    }
+
+    impl AgentLifecycleHook for CountingHook {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        fn on_created(&self, _agent: &Agent) -> Result<()> {
+            self.created.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if self.failing {
+                return Err(Error::InternalError("audit service down".into()));
+            }
+            Ok(())
+        }
+
+        fn on_status_changed(&self, _agent: &Agent, _previous: &AgentStatus) -> Result<()> {
+            self.status_changes.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn on_deleted(&self, _agent: &Agent) -> Result<()> {
+            self.deleted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_lifecycle_hooks_fire_once_per_event() {
+        use std::sync::atomic::Ordering;
+
+        let hook = Arc::new(CountingHook::default());
+        let failing = Arc::new(CountingHook { failing: true, ..Default::default() });
+        let mut registry = AgentRegistry::new().with_hook(hook.clone()).with_hook(failing.clone());
+
+        register_named(&mut registry, "Crawler").unwrap();
+        let id = registry.list_agents()[0].id.to_string();
+        register_named(&mut registry, "Indexer").unwrap();
+        assert_eq!(hook.created.load(Ordering::SeqCst), 2);
+        // A failing hook doesn't stop registration
+        assert_eq!(failing.created.load(Ordering::SeqCst), 2);
+        assert_eq!(registry.list_agents().len(), 2);
+
+        let mut agent = registry.get_agent(&id).unwrap().clone();
+        let genome = registry.get_genome(&id).unwrap().clone();
+        registry.register(agent.clone(), genome.clone()).unwrap();
+        agent.set_status(AgentStatus::Busy);
+        registry.register(agent, genome).unwrap();
+        assert_eq!(hook.created.load(Ordering::SeqCst), 2);
+        assert_eq!(hook.status_changes.load(Ordering::SeqCst), 1);
+
+        assert!(registry.remove(&id));
+        assert!(!registry.remove(&id));
+        assert_eq!(hook.deleted.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_duplicate_names_allowed_when_off() {
+        let mut registry = AgentRegistry::new();
+        assert_eq!(register_named(&mut registry, "Crawler").unwrap(), "Crawler");
+        assert_eq!(register_named(&mut registry, "Crawler").unwrap(), "Crawler");
+        assert_eq!(registry.list_agents().len(), 2);
+    }
+
+    #[test]
+    fn test_duplicate_names_renamed_when_warn() {
+        let mut registry = AgentRegistry::new().with_name_uniqueness(NameUniqueness::Warn);
+        assert_eq!(register_named(&mut registry, "Crawler").unwrap(), "Crawler");
+        assert_eq!(register_named(&mut registry, "Crawler").unwrap(), "Crawler-2");
+        assert_eq!(register_named(&mut registry, "Crawler").unwrap(), "Crawler-3");
+
+        let mut names: Vec<&str> = registry.list_agents().iter().map(|a| a.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["Crawler", "Crawler-2", "Crawler-3"]);
+    }
+
+    #[test]
+    fn test_duplicate_names_rejected_when_enforced() {
+        let mut registry = AgentRegistry::new().with_name_uniqueness(NameUniqueness::Enforce);
+        register_named(&mut registry, "Crawler").unwrap();
+        assert!(matches!(register_named(&mut registry, "Crawler"), Err(Error::Conflict(_))));
+        assert_eq!(registry.list_agents().len(), 1);
+
+        // Re-registering the same agent is an update, not a duplicate
+        let agent = registry.list_agents()[0].clone();
+        let genome = registry.get_genome(&agent.id.to_string()).unwrap().clone();
+        assert_eq!(registry.register(agent, genome).unwrap(), "Crawler");
     }
 }
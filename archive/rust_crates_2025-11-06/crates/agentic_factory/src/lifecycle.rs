@@ -0,0 +1,45 @@
+//! Hooks run by [`AgentRegistry`](crate::AgentRegistry) as agents come and go
+//!
+//! Operators attach an [`AgentLifecycleHook`] to mirror the registry
+//! somewhere else: an audit log, an external service directory, a metrics
+//! counter. Hooks are called synchronously after the registry has changed,
+//! and a failing hook is logged and skipped, so it can never undo or block
+//! the change itself.
+
+use agentic_core::{Agent, AgentStatus, Result};
+use tracing::warn;
+
+/// Callbacks for agent registration, removal and status changes
+///
+/// Every method defaults to doing nothing; implement the ones you need.
+pub trait AgentLifecycleHook: Send + Sync {
+    /// Name used when logging the hook's failures
+    fn name(&self) -> &str;
+
+    /// `agent` was registered under an id the registry didn't know yet
+    fn on_created(&self, _agent: &Agent) -> Result<()> {
+        Ok(())
+    }
+
+    /// `agent` was removed from the registry
+    fn on_deleted(&self, _agent: &Agent) -> Result<()> {
+        Ok(())
+    }
+
+    /// `agent` was re-registered with a status other than `previous`
+    fn on_status_changed(&self, _agent: &Agent, _previous: &AgentStatus) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Call `event` on every hook, logging failures instead of returning them
+pub(crate) fn notify<H>(hooks: &[H], event: &str, agent: &Agent, call: impl Fn(&H) -> Result<()>)
+where
+    H: std::ops::Deref<Target = dyn AgentLifecycleHook>,
+{
+    for hook in hooks {
+        if let Err(e) = call(hook) {
+            warn!(hook = hook.name(), event, agent_id = %agent.id, "Agent lifecycle hook failed: {}", e);
+        }
+    }
+}
@@ -1,11 +1,11 @@
 //! Code Generator Agent - Generates code based on specifications
 
 use agentic_core::{Agent, AgentRole, Result, Error};
-use agentic_runtime::{
-    executor::{AgentExecutor, DefaultExecutor, ExecutionContext},
-    llm::{LlmClient, LlmRequest, LlmMessage, MessageRole},
-};
+use agentic_runtime::llm::{LlmClient, LlmRequest};
+use agentic_runtime::TaskKind;
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tracing::{info, debug, warn};
 
@@ -108,6 +108,188 @@ impl GeneratedCode {
         }
         total
     }
+
+    /// File names for the code and tests, based on `language`
+    ///
+    /// Other languages use their name as the extension, keeping only ASCII
+    /// letters and digits so the names can't leave the target directory.
+    pub fn file_names(&self) -> (String, String) {
+        let (code, tests) = match self.language.to_lowercase().as_str() {
+            "rust" | "rs" => ("lib.rs", "tests.rs"),
+            "python" | "py" => ("main.py", "test_main.py"),
+            "typescript" | "ts" => ("index.ts", "index.test.ts"),
+            "javascript" | "js" => ("index.js", "index.test.js"),
+            "go" | "golang" => ("main.go", "main_test.go"),
+            "java" => ("Main.java", "MainTest.java"),
+            other => {
+                let extension: String = other.chars().filter(char::is_ascii_alphanumeric).collect();
+                let extension = if extension.is_empty() { "txt".to_string() } else { extension };
+                return (format!("code.{}", extension), format!("tests.{}", extension));
+            }
+        };
+        (code.to_string(), tests.to_string())
+    }
+
+    /// Write code, tests and docs into `dir`, returning the paths written
+    ///
+    /// Creates `dir` if needed. Tests and docs are only written when present,
+    /// docs as `README.md`. Fails with `Error::Conflict` before writing
+    /// anything if a target file exists and `force` is false.
+    pub fn write_to(&self, dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+        let (code_name, tests_name) = self.file_names();
+        let mut files = vec![(dir.join(code_name), &self.code)];
+        if let Some(tests) = &self.tests {
+            files.push((dir.join(tests_name), tests));
+        }
+        if let Some(docs) = &self.documentation {
+            files.push((dir.join("README.md"), docs));
+        }
+
+        if !force {
+            if let Some((existing, _)) = files.iter().find(|(path, _)| path.exists()) {
+                return Err(Error::Conflict(format!(
+                    "{} already exists; pass force to overwrite",
+                    existing.display()
+                )));
+            }
+        }
+
+        let io_error = |path: &Path, e: std::io::Error| Error::InternalError(format!("Cannot write {}: {}", path.display(), e));
+        fs::create_dir_all(dir).map_err(|e| io_error(dir, e))?;
+        for (path, contents) in &files {
+            fs::write(path, contents).map_err(|e| io_error(path, e))?;
+        }
+
+        debug!("Wrote {} generated file(s) to {}", files.len(), dir.display());
+        Ok(files.into_iter().map(|(path, _)| path).collect())
+    }
+
+    /// `Cargo.toml` declaring the crates the code imports, for Rust code
+    ///
+    /// Crates come from the extracted `use` paths; `std`, `core`, `alloc`
+    /// and crate-relative paths are skipped, as is anything that isn't a
+    /// valid crate name. Well-known crates get a matching version spec,
+    /// others `"*"`.
+    pub fn cargo_manifest(&self) -> Option<String> {
+        if !matches!(self.language.to_lowercase().as_str(), "rust" | "rs") {
+            return None;
+        }
+
+        let mut crates: Vec<&str> = self.dependencies.iter().filter_map(|dep| rust_crate_name(dep)).collect();
+        crates.sort_unstable();
+        crates.dedup();
+
+        let mut manifest = String::from(
+            "[package]\nname = \"generated\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n",
+        );
+        for name in crates {
+            let (package, spec) = KNOWN_CRATES
+                .iter()
+                .find(|(known, _, _)| *known == name)
+                .map(|(_, package, spec)| (*package, *spec))
+                .unwrap_or((name, "\"*\""));
+            manifest.push_str(&format!("{} = {}\n", package, spec));
+        }
+        Some(manifest)
+    }
+
+    /// `package.json` declaring the npm packages the code imports, for
+    /// JavaScript and TypeScript code
+    ///
+    /// Relative imports and Node built-ins are skipped; deep imports count
+    /// as their package (`lodash/merge` is `lodash`).
+    pub fn package_json(&self) -> Option<String> {
+        if !matches!(self.language.to_lowercase().as_str(), "javascript" | "js" | "typescript" | "ts") {
+            return None;
+        }
+
+        let dependencies: serde_json::Map<String, serde_json::Value> = self
+            .dependencies
+            .iter()
+            .filter_map(|dep| npm_package_name(dep))
+            .map(|name| {
+                let version = KNOWN_PACKAGES
+                    .iter()
+                    .find(|(known, _)| *known == name)
+                    .map_or("latest", |(_, version)| *version);
+                (name, serde_json::Value::from(version))
+            })
+            .collect();
+
+        let manifest = serde_json::json!({
+            "name": "generated",
+            "version": "0.1.0",
+            "private": true,
+            "dependencies": dependencies,
+        });
+        serde_json::to_string_pretty(&manifest).ok()
+    }
+}
+
+/// Import path root, package name and version spec of common crates
+const KNOWN_CRATES: &[(&str, &str, &str)] = &[
+    ("anyhow", "anyhow", "\"1\""),
+    ("async_trait", "async-trait", "\"0.1\""),
+    ("axum", "axum", "\"0.7\""),
+    ("chrono", "chrono", "{ version = \"0.4\", features = [\"serde\"] }"),
+    ("clap", "clap", "{ version = \"4\", features = [\"derive\"] }"),
+    ("futures", "futures", "\"0.3\""),
+    ("log", "log", "\"0.4\""),
+    ("rand", "rand", "\"0.8\""),
+    ("regex", "regex", "\"1\""),
+    ("reqwest", "reqwest", "{ version = \"0.12\", features = [\"json\"] }"),
+    ("serde", "serde", "{ version = \"1\", features = [\"derive\"] }"),
+    ("serde_json", "serde_json", "\"1\""),
+    ("thiserror", "thiserror", "\"1\""),
+    ("tokio", "tokio", "{ version = \"1\", features = [\"full\"] }"),
+    ("tracing", "tracing", "\"0.1\""),
+    ("uuid", "uuid", "{ version = \"1\", features = [\"v4\"] }"),
+];
+
+/// Package name and version range of common npm packages
+const KNOWN_PACKAGES: &[(&str, &str)] = &[
+    ("axios", "^1.6.0"),
+    ("express", "^4.18.0"),
+    ("lodash", "^4.17.21"),
+    ("react", "^18.2.0"),
+    ("react-dom", "^18.2.0"),
+    ("zod", "^3.22.0"),
+];
+
+/// Node modules that ship with the runtime
+const NODE_BUILTINS: &[&str] = &[
+    "assert", "buffer", "child_process", "crypto", "events", "fs", "http", "https", "net", "os", "path",
+    "process", "stream", "url", "util", "zlib",
+];
+
+/// Root crate of a `use` path such as `serde::{Deserialize,`, if external
+fn rust_crate_name(path: &str) -> Option<&str> {
+    let root = path.trim_start_matches("::").split([':', ';', ',']).next()?.trim();
+    let valid = root.len() <= 64
+        && root.starts_with(|c: char| c.is_ascii_alphabetic())
+        && root.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    let local = matches!(root, "std" | "core" | "alloc" | "crate" | "self" | "super" | "proc_macro" | "test");
+    (valid && !local).then_some(root)
+}
+
+/// Package imported by an `import`/`require` line, if it is an npm package
+fn npm_package_name(line: &str) -> Option<String> {
+    let quoted = line.split(['\'', '"', '`']).nth(1)?;
+    if quoted.is_empty() || quoted.starts_with('.') || quoted.starts_with('/') || quoted.starts_with("node:") {
+        return None;
+    }
+
+    let mut segments = quoted.split('/');
+    let name = match segments.next()? {
+        scope if scope.starts_with('@') => format!("{}/{}", scope, segments.next()?),
+        name => name.to_string(),
+    };
+    let valid = name.len() <= 214
+        && name
+            .trim_start_matches('@')
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-._~/".contains(c));
+    (valid && !NODE_BUILTINS.contains(&name.as_str())).then_some(name)
 }
 
 /// Code Generator Agent
@@ -146,22 +328,13 @@ impl CodeGeneratorAgent {
         let prompt = self.build_code_prompt(&request);
 
         // Call LLM to generate code
-        let llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: self.get_system_prompt(&request.language),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: prompt,
-                },
-            ],
-            temperature: Some(0.2), // Low temperature for more consistent code
-            max_tokens: Some(4096),
-            tools: None,
-        };
+        let llm_request = LlmRequest::builder()
+            .model(self.agent.model.clone())
+            .system(self.get_system_prompt(&request.language))
+            .user(prompt)
+            .task(TaskKind::Code)
+            .max_tokens(4096)
+            .build()?;
 
         let response = self.llm_client.complete(llm_request).await?;
 
@@ -322,22 +495,13 @@ impl CodeGeneratorAgent {
             request.language, request.language, code, request.language
         );
 
-        let llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: format!("You are an expert in {} testing. Generate thorough, well-structured test code.", request.language),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: prompt,
-                },
-            ],
-            temperature: Some(0.3),
-            max_tokens: Some(2048),
-            tools: None,
-        };
+        let llm_request = LlmRequest::builder()
+            .model(self.agent.model.clone())
+            .system(format!("You are an expert in {} testing. Generate thorough, well-structured test code.", request.language))
+            .user(prompt)
+            .task(TaskKind::Code)
+            .max_tokens(2048)
+            .build()?;
 
         let response = self.llm_client.complete(llm_request).await?;
 
@@ -373,22 +537,13 @@ impl CodeGeneratorAgent {
             request.language, request.language, code
         );
 
-        let llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: "You are a technical documentation expert. Generate clear, comprehensive documentation.".to_string(),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: prompt,
-                },
-            ],
-            temperature: Some(0.4),
-            max_tokens: Some(2048),
-            tools: None,
-        };
+        let llm_request = LlmRequest::builder()
+            .model(self.agent.model.clone())
+            .system("You are a technical documentation expert. Generate clear, comprehensive documentation.")
+            .user(prompt)
+            .task(TaskKind::Documentation)
+            .max_tokens(2048)
+            .build()?;
 
         let response = self.llm_client.complete(llm_request).await?;
         Ok(response.content)
@@ -435,6 +590,7 @@ impl CodeGeneratorAgent {
 mod tests {
     use super::*;
     use agentic_runtime::llm::MockLlmClient;
+    use agentic_runtime::test_support::ScriptedLlmClient;
 
     #[tokio::test]
     async fn test_code_generator_creation() {
@@ -462,6 +618,81 @@ mod tests {
         assert_eq!(generated.language, "rust");
     }
 
+    #[tokio::test]
+    async fn test_code_requests_use_code_temperature() {
+        let llm = Arc::new(ScriptedLlmClient::new(["```rust\npub fn answer() -> u32 { 42 }\n```"]));
+        let generator = CodeGeneratorAgent::new(llm.clone());
+
+        let request = CodeGenRequest::new("rust", "Return the answer");
+        generator.generate(request).await.unwrap();
+
+        let policy = agentic_runtime::TemperaturePolicy::global();
+        let temperatures: Vec<_> = llm.requests().into_iter().map(|request| request.temperature).collect();
+        // Code, tests, then documentation
+        assert_eq!(temperatures[0], Some(policy.temperature_for(TaskKind::Code)));
+        assert_eq!(temperatures[1], Some(policy.temperature_for(TaskKind::Code)));
+        assert_eq!(temperatures[2], Some(policy.temperature_for(TaskKind::Documentation)));
+        assert!(policy.temperature_for(TaskKind::Code) < policy.temperature_for(TaskKind::Discovery));
+    }
+
+    #[test]
+    fn test_write_to_creates_language_files() {
+        let dir = std::env::temp_dir().join(format!("generated_{}", uuid::Uuid::new_v4()));
+        let mut generated = GeneratedCode::new("pub fn answer() -> u32 { 42 }".to_string(), "rust".to_string());
+        generated.tests = Some("#[test]\nfn answers() { assert_eq!(answer(), 42); }".to_string());
+        generated.documentation = Some("# Answer".to_string());
+
+        let written = generated.write_to(&dir, false).unwrap();
+
+        assert_eq!(written, vec![dir.join("lib.rs"), dir.join("tests.rs"), dir.join("README.md")]);
+        assert!(written.iter().all(|path| path.exists()));
+        assert_eq!(fs::read_to_string(dir.join("lib.rs")).unwrap(), generated.code);
+
+        // Existing files are left alone unless forced
+        assert!(matches!(generated.write_to(&dir, false), Err(Error::Conflict(_))));
+        assert!(generated.write_to(&dir, true).is_ok());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_unknown_language_names_stay_in_the_directory() {
+        let names = |language: &str| GeneratedCode::new(String::new(), language.to_string()).file_names();
+        assert_eq!(names("Kotlin"), ("code.kotlin".to_string(), "tests.kotlin".to_string()));
+        assert_eq!(names("/../../etc/passwd"), ("code.etcpasswd".to_string(), "tests.etcpasswd".to_string()));
+        assert_eq!(names("../"), ("code.txt".to_string(), "tests.txt".to_string()));
+    }
+
+    #[test]
+    fn test_manifests_list_imported_packages() {
+        let generator = CodeGeneratorAgent::new(Arc::new(MockLlmClient::default()));
+        let rust = generator
+            .parse_code_response(
+                "```rust\nuse std::collections::HashMap;\nuse serde::{Deserialize, Serialize};\nuse crate::model;\nuse my_crate::Thing;\n```",
+                "rust",
+            )
+            .unwrap();
+        let manifest = rust.cargo_manifest().unwrap();
+        assert!(manifest.contains("serde = { version = \"1\", features = [\"derive\"] }"));
+        assert!(manifest.contains("my_crate = \"*\""));
+        let dependencies: Vec<&str> = manifest.lines().skip_while(|l| *l != "[dependencies]").skip(1).collect();
+        assert_eq!(dependencies.len(), 2, "std and crate-relative paths are skipped: {:?}", dependencies);
+        assert!(rust.package_json().is_none());
+
+        let js = generator
+            .parse_code_response(
+                "```javascript\nimport express from 'express';\nimport merge from \"lodash/merge\";\nimport { z } from '@acme/schema/v2';\nimport fs from 'fs';\nimport util from './util';\n```",
+                "javascript",
+            )
+            .unwrap();
+        let package: serde_json::Value = serde_json::from_str(&js.package_json().unwrap()).unwrap();
+        let dependencies = package["dependencies"].as_object().unwrap();
+        let names: Vec<&str> = dependencies.keys().map(String::as_str).collect();
+        assert_eq!(names, ["@acme/schema", "express", "lodash"]);
+        assert_eq!(dependencies["lodash"], "^4.17.21");
+        assert!(js.cargo_manifest().is_none());
+    }
+
     #[test]
     fn test_code_gen_request_builder() {
         let request = CodeGenRequest::new("python", "Sort a list")
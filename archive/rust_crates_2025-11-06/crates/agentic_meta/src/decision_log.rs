@@ -0,0 +1,147 @@
+//! Audit trail of decisions made by agents
+//!
+//! Meta-agents explain their choices (a validation verdict, a pricing model,
+//! a payment provider) but the explanation used to be returned and then
+//! dropped. A [`DecisionLog`] is a cloneable handle agents record into so
+//! those choices can be reviewed later. Opened with a path, every record is
+//! also appended to a JSON Lines file and reloaded on the next start.
+
+use agentic_core::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// One decision and why it was made
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    pub id: Uuid,
+    pub agent_id: String,
+    pub timestamp: DateTime<Utc>,
+    /// What was being decided, e.g. `payment_provider`
+    pub kind: String,
+    pub decision: String,
+    pub rationale: String,
+    /// Hash of the inputs the decision was based on; equal inputs hash equally
+    pub inputs_hash: String,
+}
+
+/// Stable hash of `inputs` as serialized to JSON
+pub fn hash_inputs<T: Serialize + ?Sized>(inputs: &T) -> String {
+    let bytes = serde_json::to_vec(inputs).unwrap_or_default();
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, &bytes).simple().to_string()
+}
+
+/// Shared, append-only log of [`DecisionRecord`]s
+#[derive(Debug, Clone, Default)]
+pub struct DecisionLog {
+    records: Arc<Mutex<Vec<DecisionRecord>>>,
+    path: Option<Arc<PathBuf>>,
+}
+
+impl DecisionLog {
+    /// In-memory log
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Log persisted to `path`, loading any records already there
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let records = match std::fs::read_to_string(&path) {
+            Ok(text) => text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<std::result::Result<Vec<DecisionRecord>, _>>()
+                .map_err(|e| Error::InternalError(format!("Corrupt decision log {}: {}", path.display(), e)))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(Error::InternalError(format!("Failed to read {}: {}", path.display(), e))),
+        };
+
+        Ok(Self {
+            records: Arc::new(Mutex::new(records)),
+            path: Some(Arc::new(path)),
+        })
+    }
+
+    /// Record a decision by `agent_id`
+    ///
+    /// The record is kept even if appending it to the log file fails.
+    pub fn record<T: Serialize + ?Sized>(
+        &self,
+        agent_id: impl Into<String>,
+        kind: impl Into<String>,
+        decision: impl Into<String>,
+        rationale: impl Into<String>,
+        inputs: &T,
+    ) -> Result<DecisionRecord> {
+        let record = DecisionRecord {
+            id: Uuid::new_v4(),
+            agent_id: agent_id.into(),
+            timestamp: Utc::now(),
+            kind: kind.into(),
+            decision: decision.into(),
+            rationale: rationale.into(),
+            inputs_hash: hash_inputs(inputs),
+        };
+
+        let mut records = self.records.lock().unwrap();
+        records.push(record.clone());
+        if let Some(path) = &self.path {
+            append_line(path, &record)?;
+        }
+        Ok(record)
+    }
+
+    /// Every record, oldest first
+    pub fn all(&self) -> Vec<DecisionRecord> {
+        self.records.lock().unwrap().clone()
+    }
+
+    /// Records made by `agent_id`, oldest first
+    pub fn for_agent(&self, agent_id: &str) -> Vec<DecisionRecord> {
+        self.records
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.agent_id == agent_id)
+            .cloned()
+            .collect()
+    }
+}
+
+fn append_line(path: &Path, record: &DecisionRecord) -> Result<()> {
+    let line = serde_json::to_string(record)?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| writeln!(file, "{}", line))
+        .map_err(|e| Error::InternalError(format!("Failed to append to {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_are_persisted_and_filtered_by_agent() {
+        let path = std::env::temp_dir().join(format!("decisions-{}.jsonl", Uuid::new_v4()));
+        let log = DecisionLog::open(&path).unwrap();
+
+        let first = log.record("agent-a", "pricing_model", "Subscription", "Recurring use", "inputs").unwrap();
+        log.record("agent-b", "payment_provider", "Stripe", "Global coverage", "other inputs").unwrap();
+        assert_eq!(first.inputs_hash, hash_inputs("inputs"));
+        assert_ne!(first.inputs_hash, hash_inputs("other inputs"));
+
+        let reopened = DecisionLog::open(&path).unwrap();
+        assert_eq!(reopened.all().len(), 2);
+        assert_eq!(reopened.for_agent("agent-a"), vec![first]);
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::meta_agent::{MetaAgent, MetaAgentType, MetaAgentCapability, MetaAgentMetrics, MetaAgentResult, MetaAgentConfig};
 use crate::requirements::AgentRequirement;
-use agentic_core::{Agent, AgentRole, AgentId, Result, Error};
+use agentic_core::{Agent, AgentRole, AgentId, Result, ResultExt, Error};
 use agentic_domain::agent_genome::AgentGenome;
 use agentic_factory::AgentFactory;
 use agentic_standards::StandardsRegistry;
@@ -109,7 +109,7 @@ impl FactoryMetaAgent {
         agent.config.insert(
             "quality_requirements".to_string(),
             serde_json::to_value(&requirement.quality_requirements)
-                .map_err(|e| Error::SerializationError(e.to_string()))?,
+                .context("Failed to serialize quality requirements")?,
         );
 
         // Add genome traits based on requirements
@@ -260,7 +260,7 @@ impl MetaAgent for FactoryMetaAgent {
                     params.get("requirement")
                         .ok_or_else(|| Error::InvalidArgument("Missing requirement".to_string()))?
                         .clone()
-                ).map_err(|e| Error::SerializationError(e.to_string()))?;
+                ).context("Invalid requirement")?;
 
                 let (agent, genome) = self.create_from_requirements(&requirement).await?;
 
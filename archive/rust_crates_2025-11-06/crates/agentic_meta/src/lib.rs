@@ -15,6 +15,8 @@ pub mod testing_agent;
 pub mod specialist_agents;
 pub mod requirements;
 pub mod dashboard_coordinator;
+pub mod metrics_registry;
+pub mod decision_log;
 
 pub use meta_agent::{MetaAgent, MetaAgentType, MetaAgentCapability, MetaAgentMetrics};
 pub use factory_agent::FactoryMetaAgent;
@@ -23,3 +25,5 @@ pub use code_generator::{CodeGeneratorAgent, CodeGenRequest, GeneratedCode};
 pub use testing_agent::{TestingAgent, TestGenRequest, GeneratedTests, TestType};
 pub use requirements::{AgentRequirement, FeatureRequest, CapabilitySpec};
 pub use dashboard_coordinator::{DashboardCoordinatorAgent, DashboardRequirements, DashboardBuildResult};
+pub use metrics_registry::{MetaMetricsRegistry, MetaMetricsSnapshot};
+pub use decision_log::{DecisionLog, DecisionRecord};
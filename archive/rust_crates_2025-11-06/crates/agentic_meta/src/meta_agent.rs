@@ -67,6 +67,14 @@ pub struct MetaAgentMetrics {
 
     /// Successful experiment rate
     pub experiment_success_rate: f64,
+
+    /// Number of top-level tasks executed
+    #[serde(default)]
+    pub tasks_executed: u64,
+
+    /// Average task execution time in milliseconds
+    #[serde(default)]
+    pub avg_execution_time_ms: f64,
 }
 
 /// Core meta-agent trait
@@ -0,0 +1,134 @@
+//! Shared metrics registry for meta-agents
+//!
+//! Meta-agents are usually owned by a single workflow, so their
+//! `MetaAgentMetrics` are invisible to anything else in the process. A
+//! [`MetaMetricsRegistry`] is a cheap, cloneable handle that managers record
+//! into and that the API can snapshot.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Latency samples kept per meta-agent for percentile calculation
+const LATENCY_WINDOW: usize = 1000;
+
+/// Point-in-time metrics for one meta-agent
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetaMetricsSnapshot {
+    pub name: String,
+    pub executions: u64,
+    pub failures: u64,
+    pub cache_hits: u64,
+    pub avg_latency_ms: f64,
+    pub p95_latency_ms: f64,
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    executions: u64,
+    failures: u64,
+    cache_hits: u64,
+    total_latency_ms: f64,
+    recent_latencies_ms: VecDeque<f64>,
+}
+
+impl Entry {
+    fn snapshot(&self, name: &str) -> MetaMetricsSnapshot {
+        let avg_latency_ms = if self.executions > 0 {
+            self.total_latency_ms / self.executions as f64
+        } else {
+            0.0
+        };
+
+        let mut sorted: Vec<f64> = self.recent_latencies_ms.iter().copied().collect();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let p95_latency_ms = if sorted.is_empty() {
+            0.0
+        } else {
+            let rank = ((sorted.len() as f64) * 0.95).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+
+        MetaMetricsSnapshot {
+            name: name.to_string(),
+            executions: self.executions,
+            failures: self.failures,
+            cache_hits: self.cache_hits,
+            avg_latency_ms,
+            p95_latency_ms,
+        }
+    }
+}
+
+/// Process-wide collection of meta-agent metrics, keyed by meta-agent name
+#[derive(Debug, Clone, Default)]
+pub struct MetaMetricsRegistry {
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+impl MetaMetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one execution and whether it succeeded
+    pub fn record_execution(&self, name: &str, latency: Duration, success: bool) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.entry(name.to_string()).or_default();
+
+        entry.executions += 1;
+        if !success {
+            entry.failures += 1;
+        }
+        entry.total_latency_ms += latency_ms;
+        if entry.recent_latencies_ms.len() == LATENCY_WINDOW {
+            entry.recent_latencies_ms.pop_front();
+        }
+        entry.recent_latencies_ms.push_back(latency_ms);
+    }
+
+    /// Record a request answered from cache without executing
+    pub fn record_cache_hit(&self, name: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.entry(name.to_string()).or_default().cache_hits += 1;
+    }
+
+    pub fn get(&self, name: &str) -> Option<MetaMetricsSnapshot> {
+        self.entries.lock().unwrap().get(name).map(|entry| entry.snapshot(name))
+    }
+
+    /// Metrics for every meta-agent that has recorded anything, sorted by name
+    pub fn snapshot(&self) -> Vec<MetaMetricsSnapshot> {
+        let entries = self.entries.lock().unwrap();
+        let mut snapshots: Vec<_> = entries.iter().map(|(name, entry)| entry.snapshot(name)).collect();
+        snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+        snapshots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_aggregates_executions() {
+        let registry = MetaMetricsRegistry::new();
+        for ms in 1..=20 {
+            registry.record_execution("SDLCManager", Duration::from_millis(ms), ms != 20);
+        }
+        registry.record_cache_hit("SDLCManager");
+
+        let shared = registry.clone();
+        let stats = shared.get("SDLCManager").unwrap();
+        assert_eq!(stats.executions, 20);
+        assert_eq!(stats.failures, 1);
+        assert_eq!(stats.cache_hits, 1);
+        assert!((stats.avg_latency_ms - 10.5).abs() < 0.01);
+        assert!((stats.p95_latency_ms - 19.0).abs() < 0.01);
+
+        assert!(registry.get("Unknown").is_none());
+        assert_eq!(registry.snapshot().len(), 1);
+    }
+}
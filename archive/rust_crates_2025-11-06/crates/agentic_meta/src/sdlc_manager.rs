@@ -6,13 +6,18 @@ use crate::{
     factory_agent::FactoryMetaAgent,
     code_generator::{CodeGeneratorAgent, CodeGenRequest, GeneratedCode},
     testing_agent::{TestingAgent, TestGenRequest, GeneratedTests, TestType},
+    metrics_registry::MetaMetricsRegistry,
 };
-use agentic_core::{Agent, AgentRole, AgentId, WorkflowId, Result, Error};
+use agentic_core::{Agent, AgentRole, AgentId, WorkflowId, Result, ResultExt, Error};
 use agentic_runtime::llm::LlmClient;
+use agentic_runtime::CallerLlmClient;
+use agentic_runtime::TaskKind;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
 use tracing::{info, debug, warn};
 
 /// SDLC workflow stages
@@ -114,6 +119,21 @@ pub struct DevelopmentResult {
     pub review_notes: Option<String>,
     pub success: bool,
     pub stages_completed: Vec<SDLCStage>,
+    /// Files written when the manager has an output directory
+    #[serde(default)]
+    pub output_files: Vec<PathBuf>,
+}
+
+impl DevelopmentResult {
+    /// Write the code, generated tests and documentation into `dir`
+    ///
+    /// See [`GeneratedCode::write_to`] for file naming and `force`.
+    pub fn write_to(&self, dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+        let mut code = self.code.clone();
+        code.tests = Some(self.tests.test_code.clone());
+        code.documentation = Some(self.documentation.clone());
+        code.write_to(dir, force)
+    }
 }
 
 /// SDLC Manager orchestrates development workflows
@@ -123,6 +143,8 @@ pub struct SDLCManager {
     factory: Option<FactoryMetaAgent>,
     active_workflows: HashMap<WorkflowId, FeatureWorkflow>,
     metrics: MetaAgentMetrics,
+    metrics_registry: Option<MetaMetricsRegistry>,
+    output_dir: Option<PathBuf>,
 }
 
 impl SDLCManager {
@@ -140,12 +162,16 @@ impl SDLCManager {
         agent.add_tag("sdlc");
         agent.add_tag("orchestrator");
 
+        let llm_client = CallerLlmClient::for_manager(llm_client, &agent);
+
         Self {
             agent,
             llm_client,
             factory: None,
             active_workflows: HashMap::new(),
             metrics: MetaAgentMetrics::default(),
+            metrics_registry: None,
+            output_dir: None,
         }
     }
 
@@ -155,6 +181,18 @@ impl SDLCManager {
         self
     }
 
+    /// Report each workflow run to a shared metrics registry
+    pub fn with_metrics_registry(mut self, registry: MetaMetricsRegistry) -> Self {
+        self.metrics_registry = Some(registry);
+        self
+    }
+
+    /// Write each completed feature to `<dir>/<workflow id>/`
+    pub fn with_output_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(dir.into());
+        self
+    }
+
     /// Get the base agent
     pub fn agent(&self) -> &Agent {
         &self.agent
@@ -162,6 +200,17 @@ impl SDLCManager {
 
     /// Execute full SDLC workflow for a feature
     pub async fn develop_feature(&mut self, request: FeatureRequest) -> Result<DevelopmentResult> {
+        let started = Instant::now();
+        let result = self.run_feature_workflow(request).await;
+
+        if let Some(registry) = &self.metrics_registry {
+            registry.record_execution(&self.agent.name, started.elapsed(), result.is_ok());
+        }
+
+        result
+    }
+
+    async fn run_feature_workflow(&mut self, request: FeatureRequest) -> Result<DevelopmentResult> {
         info!("Starting SDLC workflow for feature: {}", request.description);
 
         // Create workflow
@@ -227,7 +276,7 @@ impl SDLCManager {
         self.metrics.tasks_executed += 1;
         self.metrics.avg_execution_time_ms = workflow.duration().num_milliseconds() as f64;
 
-        let result = DevelopmentResult {
+        let mut result = DevelopmentResult {
             workflow_id: workflow.workflow_id,
             feature_name: request.description.clone(),
             code,
@@ -236,8 +285,15 @@ impl SDLCManager {
             review_notes,
             success: true,
             stages_completed,
+            output_files: Vec::new(),
         };
 
+        if let Some(dir) = &self.output_dir {
+            let feature_dir = dir.join(workflow.workflow_id.to_string());
+            result.output_files = result.write_to(&feature_dir, false)?;
+            info!("Generated files written to {}", feature_dir.display());
+        }
+
         info!(
             "SDLC workflow completed for '{}' in {:.2}s",
             request.description,
@@ -275,29 +331,18 @@ impl SDLCManager {
         debug!("Creating design for: {}", request.description);
 
         // Use LLM to create design
-        use agentic_runtime::llm::{LlmRequest, LlmMessage, MessageRole};
-
-        let llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: "You are a software architect. Create a high-level design for the given feature.".to_string(),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: format!(
-                        "Create a design for: {}\n\nPriority: {:?}\nAcceptance Criteria:\n{}",
-                        request.description,
-                        request.priority,
-                        request.acceptance_criteria.join("\n- ")
-                    ),
-                },
-            ],
-            temperature: Some(0.4),
-            max_tokens: Some(2048),
-            tools: None,
-        };
+        use agentic_runtime::llm::{LlmRequest, Message};
+
+        let llm_request = LlmRequest::new(self.agent.model.clone())
+            .with_system("You are a software architect. Create a high-level design for the given feature.")
+            .add_message(Message::user(format!(
+                "Create a design for: {}\n\nPriority: {:?}\nAcceptance Criteria:\n{}",
+                request.description,
+                request.priority,
+                request.acceptance_criteria.join("\n- ")
+            )))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(2048);
 
         let response = self.llm_client.complete(llm_request).await?;
         Ok(response.content)
@@ -341,7 +386,18 @@ impl SDLCManager {
             ])
             .with_coverage_target(80.0);
 
-        let tests = test_agent.generate_tests(test_request).await?;
+        let mut tests = test_agent.generate_tests(test_request).await?;
+
+        // Ask for any requested test types the first pass didn't cover
+        for test_type in tests.missing_test_types() {
+            info!("Requesting missing {} tests", test_type.as_str());
+            let extra = test_agent
+                .generate_specific_tests(&code.code, &code.language, test_type)
+                .await?;
+            tests.test_code.push_str("\n\n");
+            tests.test_code.push_str(&extra);
+        }
+
         Ok(tests)
     }
 
@@ -349,31 +405,20 @@ impl SDLCManager {
     async fn review_code(&self, code: &GeneratedCode, tests: &GeneratedTests) -> Result<Option<String>> {
         debug!("Reviewing generated code");
 
-        use agentic_runtime::llm::{LlmRequest, LlmMessage, MessageRole};
-
-        let llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: "You are an expert code reviewer. Review the code for quality, security, and best practices.".to_string(),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: format!(
-                        "Review this {} code:\n\n```{}\n{}\n```\n\nTests generated: {}\nTest coverage: {:.1}%",
-                        code.language,
-                        code.language,
-                        code.code,
-                        tests.test_count,
-                        tests.estimated_coverage
-                    ),
-                },
-            ],
-            temperature: Some(0.3),
-            max_tokens: Some(2048),
-            tools: None,
-        };
+        use agentic_runtime::llm::{LlmRequest, Message};
+
+        let llm_request = LlmRequest::new(self.agent.model.clone())
+            .with_system("You are an expert code reviewer. Review the code for quality, security, and best practices.")
+            .add_message(Message::user(format!(
+                "Review this {} code:\n\n```{}\n{}\n```\n\nTests generated: {}\nTest coverage: {:.1}%",
+                code.language,
+                code.language,
+                code.code,
+                tests.test_count,
+                tests.estimated_coverage
+            )))
+            .with_task(TaskKind::Analysis)
+            .with_max_tokens(2048);
 
         let response = self.llm_client.complete(llm_request).await?;
         Ok(Some(response.content))
@@ -387,29 +432,18 @@ impl SDLCManager {
             return Ok(docs.clone());
         }
 
-        use agentic_runtime::llm::{LlmRequest, LlmMessage, MessageRole};
-
-        let llm_request = LlmRequest {
-            model: self.agent.model.clone(),
-            messages: vec![
-                LlmMessage {
-                    role: MessageRole::System,
-                    content: "You are a technical documentation expert. Generate clear, comprehensive documentation.".to_string(),
-                },
-                LlmMessage {
-                    role: MessageRole::User,
-                    content: format!(
-                        "Generate documentation for:\n\nFeature: {}\n\nCode:\n```{}\n{}\n```",
-                        request.description,
-                        code.language,
-                        code.code
-                    ),
-                },
-            ],
-            temperature: Some(0.4),
-            max_tokens: Some(2048),
-            tools: None,
-        };
+        use agentic_runtime::llm::{LlmRequest, Message};
+
+        let llm_request = LlmRequest::new(self.agent.model.clone())
+            .with_system("You are a technical documentation expert. Generate clear, comprehensive documentation.")
+            .add_message(Message::user(format!(
+                "Generate documentation for:\n\nFeature: {}\n\nCode:\n```{}\n{}\n```",
+                request.description,
+                code.language,
+                code.code
+            )))
+            .with_task(TaskKind::Documentation)
+            .with_max_tokens(2048);
 
         let response = self.llm_client.complete(llm_request).await?;
         Ok(response.content)
@@ -500,10 +534,10 @@ impl MetaAgent for SDLCManager {
                     params.get("feature_request")
                         .ok_or_else(|| Error::InvalidArgument("Missing feature_request".to_string()))?
                         .clone()
-                ).map_err(|e| Error::SerializationError(e.to_string()))?;
+                ).map_err(|e| Error::InvalidArgument(format!("Invalid feature_request: {}", e)))?;
 
                 let result = self.develop_feature(request).await?;
-                serde_json::to_value(result).map_err(|e| Error::SerializationError(e.to_string()))
+                serde_json::to_value(result).context("Failed to serialize development result")
             }
             _ => Err(Error::InvalidArgument(format!("Unknown task type: {}", task_type))),
         }
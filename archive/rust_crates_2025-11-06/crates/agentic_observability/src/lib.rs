@@ -1 +1,7 @@
-//\! observability implementation
+//! Observability for multi-agent systems
+//!
+//! - [`otel`] (feature `otel`): export `tracing` spans to an OpenTelemetry
+//!   collector over OTLP
+
+#[cfg(feature = "otel")]
+pub mod otel;
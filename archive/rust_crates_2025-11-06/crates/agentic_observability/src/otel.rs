@@ -0,0 +1,243 @@
+//! OpenTelemetry export of `tracing` spans
+//!
+//! [`OtelLayer`] is a `tracing_subscriber` layer that mirrors every span into
+//! an OpenTelemetry span with the same parent, so the spans the crates
+//! already open (the HTTP request, `DefaultExecutor::execute`,
+//! `llm.complete`) reach the collector as one trace. Span fields become
+//! attributes and events inside a span become span events. The layer sits
+//! beside the usual fmt layer, which keeps logging as before.
+
+use opentelemetry::trace::{SpanBuilder, Status, TraceContextExt, TraceError, TracerProvider as _};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{Config, Tracer, TracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context as LayerContext;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Service name reported when `OTEL_SERVICE_NAME` is not set
+pub const DEFAULT_SERVICE_NAME: &str = "agentic";
+
+/// Where to send spans
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtelConfig {
+    /// OTLP gRPC endpoint, e.g. `http://localhost:4317`
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+impl OtelConfig {
+    /// Read `OTEL_EXPORTER_OTLP_ENDPOINT` and `OTEL_SERVICE_NAME`; `None`
+    /// when no endpoint is set, which leaves export off
+    pub fn from_env() -> Option<Self> {
+        let endpoint = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").ok().filter(|e| !e.trim().is_empty())?;
+        let service_name = std::env::var("OTEL_SERVICE_NAME")
+            .ok()
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string());
+        Some(Self { endpoint, service_name })
+    }
+
+    /// Provider batching spans to the endpoint; must be called inside a
+    /// Tokio runtime. Shut it down on exit to flush the last batch.
+    pub fn install(&self) -> Result<TracerProvider, TraceError> {
+        opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(&self.endpoint))
+            .with_trace_config(
+                Config::default().with_resource(Resource::new([KeyValue::new("service.name", self.service_name.clone())])),
+            )
+            .install_batch(opentelemetry_sdk::runtime::Tokio)
+    }
+}
+
+/// Layer exporting to the endpoint configured in the environment, with its
+/// provider; `None` when export is off or the exporter could not be built
+pub fn layer_from_env() -> Option<(OtelLayer, TracerProvider)> {
+    let config = OtelConfig::from_env()?;
+    match config.install() {
+        Ok(provider) => Some((OtelLayer::new(provider.tracer(config.service_name.clone())), provider)),
+        Err(e) => {
+            // No subscriber is installed yet, so the log macros would go nowhere
+            eprintln!("OpenTelemetry export to {} disabled: {}", config.endpoint, e);
+            None
+        }
+    }
+}
+
+/// `tracing_subscriber` layer recording spans with an OpenTelemetry tracer
+pub struct OtelLayer {
+    tracer: Tracer,
+}
+
+impl OtelLayer {
+    pub fn new(tracer: Tracer) -> Self {
+        Self { tracer }
+    }
+}
+
+/// Context holding a span's OpenTelemetry counterpart, kept in the span's
+/// extensions until it closes
+struct OtelSpan(Context);
+
+/// Collects span and event fields as attributes
+#[derive(Default)]
+struct FieldVisitor {
+    attributes: Vec<KeyValue>,
+    message: Option<String>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+        } else {
+            self.attributes.push(KeyValue::new(field.name(), value.to_string()));
+        }
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.attributes.push(KeyValue::new(field.name(), value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.attributes.push(KeyValue::new(field.name(), value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match i64::try_from(value) {
+            Ok(value) => self.attributes.push(KeyValue::new(field.name(), value)),
+            Err(_) => self.attributes.push(KeyValue::new(field.name(), value.to_string())),
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.attributes.push(KeyValue::new(field.name(), value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.record_str(field, &format!("{:?}", value));
+    }
+}
+
+impl<S> Layer<S> for OtelLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let parent = span
+            .parent()
+            .and_then(|parent| parent.extensions().get::<OtelSpan>().map(|otel| otel.0.clone()))
+            .unwrap_or_default();
+
+        let mut fields = FieldVisitor::default();
+        attrs.record(&mut fields);
+        let metadata = attrs.metadata();
+        fields.attributes.push(KeyValue::new("code.namespace", metadata.target()));
+        let otel_span = SpanBuilder::from_name(metadata.name())
+            .with_attributes(fields.attributes)
+            .start_with_context(&self.tracer, &parent);
+        span.extensions_mut().insert(OtelSpan(parent.with_span(otel_span)));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let extensions = span.extensions();
+        if let Some(otel) = extensions.get::<OtelSpan>() {
+            let mut fields = FieldVisitor::default();
+            values.record(&mut fields);
+            otel.0.span().set_attributes(fields.attributes);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let extensions = span.extensions();
+        if let Some(otel) = extensions.get::<OtelSpan>() {
+            let mut fields = FieldVisitor::default();
+            event.record(&mut fields);
+            let name = fields.message.unwrap_or_else(|| event.metadata().name().to_string());
+            let otel_span = otel.0.span();
+            if *event.metadata().level() == Level::ERROR {
+                otel_span.set_status(Status::error(name.clone()));
+            }
+            otel_span.add_event(name, fields.attributes);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let otel = span.extensions_mut().remove::<OtelSpan>();
+        if let Some(otel) = otel {
+            otel.0.span().end();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentic_core::{Agent, AgentRole};
+    use agentic_runtime::executor::DefaultExecutor;
+    use agentic_runtime::llm::MockLlmClient;
+    use agentic_runtime::{AgentExecutor, ExecutionContext};
+    use opentelemetry_sdk::export::trace::{ExportResult, SpanData, SpanExporter};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use tracing::Instrument;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    /// Exporter keeping every finished span in memory
+    #[derive(Debug, Clone, Default)]
+    struct InMemoryExporter(Arc<Mutex<Vec<SpanData>>>);
+
+    impl SpanExporter for InMemoryExporter {
+        fn export(&mut self, batch: Vec<SpanData>) -> Pin<Box<dyn Future<Output = ExportResult> + Send + 'static>> {
+            self.0.lock().unwrap().extend(batch);
+            Box::pin(std::future::ready(Ok(())))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_produces_nested_span_tree() {
+        let exporter = InMemoryExporter::default();
+        let provider = TracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+        let subscriber = tracing_subscriber::registry().with(OtelLayer::new(provider.tracer("test")));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let executor = DefaultExecutor::new(Arc::new(MockLlmClient::new("done")));
+        let mut agent = Agent::new("Traced", "Traced agent", AgentRole::Worker, "mock-model", "mock");
+        let context = ExecutionContext::new(agent.id);
+        executor
+            .execute(&mut agent, "hello", &context)
+            .instrument(tracing::debug_span!("request", method = "POST", uri = "/api/agents/a1/execute"))
+            .await
+            .unwrap();
+
+        let spans = exporter.0.lock().unwrap();
+        let find = |name: &str| spans.iter().find(|s| s.name == name).unwrap_or_else(|| panic!("no {} span", name));
+        let (request, execute, llm) = (find("request"), find("execute"), find("llm.complete"));
+
+        let trace_id = request.span_context.trace_id();
+        assert!([execute, llm].iter().all(|s| s.span_context.trace_id() == trace_id));
+        assert_eq!(request.parent_span_id, opentelemetry::trace::SpanId::INVALID);
+        assert_eq!(execute.parent_span_id, request.span_context.span_id());
+        assert_eq!(llm.parent_span_id, execute.span_context.span_id());
+        assert!(llm.attributes.contains(&KeyValue::new("model", "mock-model")));
+        assert!(request.attributes.contains(&KeyValue::new("uri", "/api/agents/a1/execute")));
+    }
+}
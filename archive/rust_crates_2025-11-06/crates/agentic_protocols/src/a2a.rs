@@ -2,6 +2,7 @@
 //!
 //! Standards-compliant A2A protocol for autonomous agent communication
 
+use agentic_core::clock::Clock;
 use agentic_core::AgentId;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
@@ -97,10 +98,21 @@ impl A2aMessage {
         }
     }
 
+    /// Re-stamp the send time from `clock`
+    pub fn stamped(mut self, clock: &dyn Clock) -> Self {
+        self.envelope.timestamp = clock.now();
+        self
+    }
+
     /// Check if message has expired
     pub fn is_expired(&self) -> bool {
+        self.is_expired_at(Utc::now())
+    }
+
+    /// Check if message has expired as of `now`
+    pub fn is_expired_at(&self, now: DateTime<Utc>) -> bool {
         if let Some(ttl) = self.envelope.ttl {
-            let elapsed = Utc::now().signed_duration_since(self.envelope.timestamp);
+            let elapsed = now.signed_duration_since(self.envelope.timestamp);
             elapsed.num_seconds() as u64 > ttl
         } else {
             false
@@ -4,6 +4,8 @@
 //! enabling agents to communicate, collaborate, and coordinate autonomously.
 
 use crate::a2a::{A2aEnvelope, A2aMessage};
+use crate::a2a_delivery::{DeadLetter, DeliveryQueue};
+use agentic_core::clock::{system_clock, SharedClock};
 use agentic_core::{AgentId, Result, Error};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -27,6 +29,12 @@ pub struct A2aBus {
 
     /// Metrics
     metrics: Arc<RwLock<BusMetrics>>,
+
+    /// Unacked messages sent with `send_reliable`
+    delivery: Arc<RwLock<DeliveryQueue>>,
+
+    /// Time source for delivery bookkeeping
+    clock: SharedClock,
 }
 
 /// Message bus metrics
@@ -39,6 +47,13 @@ pub struct BusMetrics {
     pub broadcast_messages: u64,
 }
 
+/// Outcome of one redelivery sweep
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedeliveryReport {
+    pub redelivered: usize,
+    pub dead_lettered: usize,
+}
+
 impl A2aBus {
     /// Create a new A2A message bus
     pub fn new() -> Self {
@@ -49,9 +64,28 @@ impl A2aBus {
             broadcast: broadcast_tx,
             handlers: Arc::new(RwLock::new(HashMap::new())),
             metrics: Arc::new(RwLock::new(BusMetrics::default())),
+            delivery: Arc::new(RwLock::new(DeliveryQueue::default())),
+            clock: system_clock(),
         }
     }
 
+    /// Take delivery timestamps from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Time source the bus stamps and times deliveries with
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
+    }
+
+    /// Use a specific delivery queue (e.g. a persistent one) for `send_reliable`
+    pub fn with_delivery_queue(mut self, queue: DeliveryQueue) -> Self {
+        self.delivery = Arc::new(RwLock::new(queue));
+        self
+    }
+
     /// Register an agent with the message bus
     pub async fn register_agent(
         &self,
@@ -182,6 +216,102 @@ impl A2aBus {
     pub async fn registered_agents(&self) -> Vec<AgentId> {
         self.agents.read().await.keys().cloned().collect()
     }
+
+    /// Send with at-least-once delivery; returns the message id to `ack`
+    ///
+    /// The message is queued first, so if the recipient isn't registered yet
+    /// it is delivered by a later `redeliver_unacked` sweep.
+    pub async fn send_reliable(&self, message: A2aMessage) -> Result<String> {
+        let message_id = message.envelope.message_id.clone();
+        self.delivery.write().await.enqueue(message.clone())?;
+
+        if self.send(message).await.is_ok() {
+            self.delivery.write().await.mark_delivered(&message_id, self.clock.now())?;
+        } else {
+            debug!("📥 Recipient unavailable, message {} left pending", message_id);
+        }
+
+        Ok(message_id)
+    }
+
+    /// Acknowledge a message received via `send_reliable`
+    pub async fn ack(&self, envelope_id: &str) -> Result<()> {
+        self.delivery.write().await.ack(envelope_id)
+    }
+
+    /// Deliver pending messages and resend any whose ack timed out
+    pub async fn redeliver_unacked(&self) -> Result<RedeliveryReport> {
+        let now = self.clock.now();
+        let (due, dead_lettered) = {
+            let mut queue = self.delivery.write().await;
+            let dead_before = queue.dead_letters().len();
+            let due = queue.take_due(now)?;
+            (due, queue.dead_letters().len() - dead_before)
+        };
+
+        if dead_lettered > 0 {
+            warn!("☠️ {} A2A message(s) dead-lettered after max deliveries", dead_lettered);
+        }
+
+        let mut report = RedeliveryReport { redelivered: 0, dead_lettered };
+        for message in due {
+            let message_id = message.envelope.message_id.clone();
+            let first_attempt = self
+                .delivery
+                .read()
+                .await
+                .queued()
+                .iter()
+                .any(|entry| entry.message.envelope.message_id == message_id && entry.deliveries == 0);
+
+            if self.send(message).await.is_ok() {
+                self.delivery.write().await.mark_delivered(&message_id, now)?;
+                if !first_attempt {
+                    report.redelivered += 1;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Run `redeliver_unacked` every `interval` until the bus is dropped
+    pub fn spawn_redelivery(self: &Arc<Self>, interval: std::time::Duration) -> tokio::task::JoinHandle<()> {
+        let bus = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let Some(bus) = bus.upgrade() else { break };
+                if let Err(e) = bus.redeliver_unacked().await {
+                    warn!("A2A redelivery sweep failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Messages queued but not yet delivered
+    pub async fn pending_count(&self) -> usize {
+        self.delivery.read().await.pending_count()
+    }
+
+    /// Messages delivered but not yet acked
+    pub async fn unacked_count(&self) -> usize {
+        self.delivery.read().await.unacked_count()
+    }
+
+    /// Give up on a message without trying to deliver it
+    pub async fn dead_letter(&self, message: A2aMessage) -> Result<()> {
+        warn!(
+            "☠️ A2A message {} to {} dead-lettered",
+            message.envelope.message_id, message.envelope.to.agent_id
+        );
+        self.delivery.write().await.dead_letter(message, self.clock.now())
+    }
+
+    pub async fn dead_letters(&self) -> Vec<DeadLetter> {
+        self.delivery.read().await.dead_letters().to_vec()
+    }
 }
 
 impl Default for A2aBus {
@@ -196,6 +326,7 @@ pub struct A2aMessageBuilder {
     from_name: String,
     to_id: AgentId,
     to_name: String,
+    clock: SharedClock,
 }
 
 impl A2aMessageBuilder {
@@ -205,9 +336,16 @@ impl A2aMessageBuilder {
             from_name,
             to_id: AgentId::generate(),
             to_name: String::new(),
+            clock: system_clock(),
         }
     }
 
+    /// Stamp messages from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     pub fn to(mut self, agent_id: AgentId, agent_name: String) -> Self {
         self.to_id = agent_id;
         self.to_name = agent_name;
@@ -233,7 +371,7 @@ impl A2aMessageBuilder {
                 },
                 message_id: Uuid::new_v4().to_string(),
                 correlation_id: Some(Uuid::new_v4().to_string()),
-                timestamp: chrono::Utc::now(),
+                timestamp: self.clock.now(),
                 priority: crate::a2a::Priority::Normal,
                 ttl: Some(3600),
             },
@@ -266,7 +404,7 @@ impl A2aMessageBuilder {
                 },
                 message_id: Uuid::new_v4().to_string(),
                 correlation_id: None,
-                timestamp: chrono::Utc::now(),
+                timestamp: self.clock.now(),
                 priority: crate::a2a::Priority::Normal,
                 ttl: Some(3600),
             },
@@ -300,7 +438,7 @@ impl A2aMessageBuilder {
                 },
                 message_id: Uuid::new_v4().to_string(),
                 correlation_id: None,
-                timestamp: chrono::Utc::now(),
+                timestamp: self.clock.now(),
                 priority: crate::a2a::Priority::Normal,
                 ttl: Some(300),
             },
@@ -364,4 +502,19 @@ mod tests {
         let metrics = bus.metrics().await;
         assert_eq!(metrics.successful_deliveries, 1);
     }
+
+    #[test]
+    fn test_builder_stamps_from_its_clock() {
+        use agentic_core::{Clock, MockClock};
+
+        let clock = MockClock::new(chrono::Utc::now() - chrono::Duration::days(1));
+        let message = A2aMessageBuilder::new(AgentId::generate(), "Agent1".to_string())
+            .with_clock(clock.shared())
+            .build_status_update("running".to_string(), 0.5, "halfway".to_string());
+        assert_eq!(message.envelope.timestamp, clock.now());
+        assert!(!message.is_expired_at(clock.now()));
+
+        clock.advance(chrono::Duration::seconds(301));
+        assert!(message.is_expired_at(clock.now()));
+    }
 }
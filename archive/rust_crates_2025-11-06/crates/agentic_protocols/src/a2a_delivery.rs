@@ -0,0 +1,336 @@
+//! At-least-once A2A delivery
+//!
+//! Messages sent with [`A2aBus::send_reliable`](crate::a2a_bus::A2aBus::send_reliable)
+//! are held in a [`DeliveryQueue`] until the recipient acks them. Anything
+//! still unacked after `ack_timeout` is delivered again, and after
+//! `max_deliveries` attempts it moves to the dead-letter list instead.
+//! Recipients must therefore tolerate duplicates.
+
+use crate::a2a::A2aMessage;
+use agentic_core::{Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Redelivery policy
+#[derive(Debug, Clone)]
+pub struct DeliveryConfig {
+    /// How long a delivered message may stay unacked before it is resent
+    pub ack_timeout: Duration,
+    /// Delivery attempts before a message is dead-lettered
+    pub max_deliveries: u32,
+}
+
+impl Default for DeliveryConfig {
+    fn default() -> Self {
+        Self {
+            ack_timeout: Duration::from_secs(30),
+            max_deliveries: 5,
+        }
+    }
+}
+
+/// A message waiting for its ack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedMessage {
+    pub message: A2aMessage,
+    /// Number of times the message has been handed to the recipient
+    pub deliveries: u32,
+    pub last_delivered_at: Option<DateTime<Utc>>,
+}
+
+/// A message that exhausted its delivery attempts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub message: A2aMessage,
+    pub deliveries: u32,
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct QueueState {
+    queued: Vec<QueuedMessage>,
+    dead_letters: Vec<DeadLetter>,
+}
+
+/// Unacked A2A messages, optionally persisted to a JSON file
+#[derive(Debug)]
+pub struct DeliveryQueue {
+    config: DeliveryConfig,
+    state: QueueState,
+    path: Option<PathBuf>,
+}
+
+impl DeliveryQueue {
+    /// In-memory queue; contents are lost on restart
+    pub fn new(config: DeliveryConfig) -> Self {
+        Self {
+            config,
+            state: QueueState::default(),
+            path: None,
+        }
+    }
+
+    /// Queue backed by `path`, reloading whatever was queued there before
+    pub fn persistent(config: DeliveryConfig, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let state = if path.exists() {
+            let bytes = fs::read(&path)
+                .map_err(|e| Error::InitializationFailed(format!("Cannot read {}: {}", path.display(), e)))?;
+            serde_json::from_slice(&bytes)?
+        } else {
+            QueueState::default()
+        };
+
+        Ok(Self {
+            config,
+            state,
+            path: Some(path),
+        })
+    }
+
+    pub fn config(&self) -> &DeliveryConfig {
+        &self.config
+    }
+
+    pub fn enqueue(&mut self, message: A2aMessage) -> Result<()> {
+        self.state.queued.push(QueuedMessage {
+            message,
+            deliveries: 0,
+            last_delivered_at: None,
+        });
+        self.save()
+    }
+
+    /// Record that the message was handed to its recipient
+    pub fn mark_delivered(&mut self, message_id: &str, now: DateTime<Utc>) -> Result<()> {
+        if let Some(entry) = self.find_mut(message_id) {
+            entry.deliveries += 1;
+            entry.last_delivered_at = Some(now);
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Remove an acknowledged message
+    pub fn ack(&mut self, message_id: &str) -> Result<()> {
+        let before = self.state.queued.len();
+        self.state.queued.retain(|entry| entry.message.envelope.message_id != message_id);
+
+        if self.state.queued.len() == before {
+            return Err(Error::InvalidArgument(format!("No unacked message with id {}", message_id)));
+        }
+        self.save()
+    }
+
+    /// Messages that should be delivered now
+    ///
+    /// Never-delivered messages are always due. Delivered ones are due once
+    /// `ack_timeout` has passed, unless they have used up `max_deliveries`,
+    /// in which case they are moved to the dead-letter list.
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Result<Vec<A2aMessage>> {
+        let timeout = chrono::Duration::from_std(self.config.ack_timeout).unwrap_or(chrono::Duration::MAX);
+        let max_deliveries = self.config.max_deliveries;
+
+        let mut due = Vec::new();
+        let mut expired = Vec::new();
+        for (index, entry) in self.state.queued.iter().enumerate() {
+            match entry.last_delivered_at {
+                None => due.push(entry.message.clone()),
+                Some(at) if now - at >= timeout => {
+                    if entry.deliveries >= max_deliveries {
+                        expired.push(index);
+                    } else {
+                        due.push(entry.message.clone());
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        if !expired.is_empty() {
+            for index in expired.into_iter().rev() {
+                let entry = self.state.queued.remove(index);
+                self.state.dead_letters.push(DeadLetter {
+                    message: entry.message,
+                    deliveries: entry.deliveries,
+                    dead_lettered_at: now,
+                });
+            }
+            self.save()?;
+        }
+
+        Ok(due)
+    }
+
+    /// Dead-letter a message that can never be delivered, e.g. one addressed
+    /// to an agent that doesn't exist
+    pub fn dead_letter(&mut self, message: A2aMessage, now: DateTime<Utc>) -> Result<()> {
+        self.state.dead_letters.push(DeadLetter {
+            message,
+            deliveries: 0,
+            dead_lettered_at: now,
+        });
+        self.save()
+    }
+
+    /// Messages not yet delivered (e.g. the recipient isn't registered)
+    pub fn pending_count(&self) -> usize {
+        self.state.queued.iter().filter(|entry| entry.deliveries == 0).count()
+    }
+
+    /// Messages delivered at least once and still awaiting an ack
+    pub fn unacked_count(&self) -> usize {
+        self.state.queued.iter().filter(|entry| entry.deliveries > 0).count()
+    }
+
+    pub fn queued(&self) -> &[QueuedMessage] {
+        &self.state.queued
+    }
+
+    pub fn dead_letters(&self) -> &[DeadLetter] {
+        &self.state.dead_letters
+    }
+
+    fn find_mut(&mut self, message_id: &str) -> Option<&mut QueuedMessage> {
+        self.state
+            .queued
+            .iter_mut()
+            .find(|entry| entry.message.envelope.message_id == message_id)
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let bytes = serde_json::to_vec_pretty(&self.state)?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, bytes)
+            .and_then(|_| fs::rename(&tmp, path))
+            .map_err(|e| Error::InternalError(format!("Cannot write {}: {}", path.display(), e)))
+    }
+}
+
+impl Default for DeliveryQueue {
+    fn default() -> Self {
+        Self::new(DeliveryConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::a2a_bus::{A2aBus, A2aMessageBuilder};
+    use agentic_core::AgentId;
+
+    fn message(to: &AgentId) -> A2aMessage {
+        A2aMessageBuilder::new(AgentId::generate(), "Sender".to_string())
+            .to(*to, "Recipient".to_string())
+            .build_task_assignment("work".to_string(), serde_json::json!({}))
+    }
+
+    #[tokio::test]
+    async fn test_unacked_message_is_redelivered_then_dead_lettered() {
+        let config = DeliveryConfig {
+            ack_timeout: Duration::ZERO,
+            max_deliveries: 3,
+        };
+        let bus = A2aBus::new().with_delivery_queue(DeliveryQueue::new(config));
+        let recipient = AgentId::generate();
+        let mut rx = bus.register_agent(recipient).await;
+
+        let id = bus.send_reliable(message(&recipient)).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap().envelope.message_id, id);
+        assert_eq!(bus.unacked_count().await, 1);
+
+        // Never acked: two redeliveries use up the three attempts
+        for _ in 0..2 {
+            let report = bus.redeliver_unacked().await.unwrap();
+            assert_eq!(report.redelivered, 1);
+            assert_eq!(rx.recv().await.unwrap().envelope.message_id, id);
+        }
+
+        let report = bus.redeliver_unacked().await.unwrap();
+        assert_eq!(report.redelivered, 0);
+        assert_eq!(report.dead_lettered, 1);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(bus.unacked_count().await, 0);
+
+        let dead = bus.dead_letters().await;
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].deliveries, 3);
+    }
+
+    #[tokio::test]
+    async fn test_ack_removes_message_and_pending_waits_for_recipient() {
+        let bus = A2aBus::new();
+        let recipient = AgentId::generate();
+
+        // Recipient not registered yet: the message stays pending
+        let id = bus.send_reliable(message(&recipient)).await.unwrap();
+        assert_eq!(bus.pending_count().await, 1);
+
+        let mut rx = bus.register_agent(recipient).await;
+        bus.redeliver_unacked().await.unwrap();
+        assert_eq!(rx.recv().await.unwrap().envelope.message_id, id);
+        assert_eq!(bus.pending_count().await, 0);
+        assert_eq!(bus.unacked_count().await, 1);
+
+        bus.ack(&id).await.unwrap();
+        assert_eq!(bus.unacked_count().await, 0);
+        assert!(bus.ack(&id).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_undeliverable_message_goes_straight_to_dead_letters() {
+        let bus = A2aBus::new();
+
+        bus.dead_letter(message(&AgentId::generate())).await.unwrap();
+
+        assert_eq!(bus.pending_count().await, 0);
+        let dead = bus.dead_letters().await;
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].deliveries, 0);
+    }
+
+    #[tokio::test]
+    async fn test_redelivery_waits_for_ack_timeout() {
+        let clock = agentic_core::MockClock::default();
+        let bus = A2aBus::new()
+            .with_clock(clock.shared())
+            .with_delivery_queue(DeliveryQueue::new(DeliveryConfig::default()));
+        let recipient = AgentId::generate();
+        let mut rx = bus.register_agent(recipient).await;
+
+        bus.send_reliable(message(&recipient)).await.unwrap();
+        rx.recv().await.unwrap();
+
+        clock.advance(chrono::Duration::seconds(29));
+        assert_eq!(bus.redeliver_unacked().await.unwrap().redelivered, 0);
+
+        clock.advance(chrono::Duration::seconds(1));
+        assert_eq!(bus.redeliver_unacked().await.unwrap().redelivered, 1);
+    }
+
+    #[test]
+    fn test_persistent_queue_survives_reload() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("a2a_queue_{}.json", uuid::Uuid::new_v4()));
+        let recipient = AgentId::generate();
+
+        let mut queue = DeliveryQueue::persistent(DeliveryConfig::default(), &path).unwrap();
+        let msg = message(&recipient);
+        let id = msg.envelope.message_id.clone();
+        queue.enqueue(msg).unwrap();
+        queue.mark_delivered(&id, Utc::now()).unwrap();
+
+        let reloaded = DeliveryQueue::persistent(DeliveryConfig::default(), &path).unwrap();
+        assert_eq!(reloaded.unacked_count(), 1);
+        assert_eq!(reloaded.queued()[0].deliveries, 1);
+
+        let _ = fs::remove_file(path);
+    }
+}
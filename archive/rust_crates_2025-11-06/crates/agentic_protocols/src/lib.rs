@@ -1,12 +1,21 @@
 //! Protocol adapters (A2A, MCP, ANS) - Production implementations
 
-use agentic_core::{Protocol, ProtocolVersion};
+use agentic_core::{AgentEnv, Protocol, ProtocolVersion};
 
 pub mod a2a;
 pub mod a2a_bus;
+pub mod a2a_delivery;
+pub mod mcp_cache;
+pub mod mcp_invoke;
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 
 pub use a2a::*;
 pub use a2a_bus::*;
+pub use a2a_delivery::{DeadLetter, DeliveryConfig, DeliveryQueue, QueuedMessage};
+pub use mcp_cache::{CachedMcpAdapter, ToolCacheStats, ToolResultCache};
+pub use mcp_invoke::{McpAdapter, TimedMcpAdapter};
 
 pub trait ProtocolAdapter {
     fn protocol(&self) -> Protocol;
@@ -14,14 +23,14 @@ pub trait ProtocolAdapter {
     // Extend with encode/decode, handshake, discovery as needed
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub struct MockMcpAdapter;
 
 impl MockMcpAdapter {
     pub fn list_tools(&self) -> Vec<McpTool> {
         vec![
-            McpTool { name: "echo".into(), description: "Echo back input".into() },
-            McpTool { name: "reverse".into(), description: "Reverse input string".into() },
+            McpTool::new("echo", "Echo back input").cacheable(None),
+            McpTool::new("reverse", "Reverse input string").cacheable(None),
         ]
     }
 
@@ -32,6 +41,13 @@ impl MockMcpAdapter {
             _ => format!("unknown tool: {}", tool),
         }
     }
+
+    /// Like [`MockMcpAdapter::invoke`]; the mock tools need no credentials,
+    /// so `env` is unused
+    pub fn invoke_with_env(&self, tool: &str, input: &str, env: &AgentEnv) -> String {
+        let _ = env;
+        self.invoke(tool, input)
+    }
 }
 
 impl ProtocolAdapter for MockMcpAdapter {
@@ -40,7 +56,29 @@ impl ProtocolAdapter for MockMcpAdapter {
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
-pub struct McpTool { pub name: String, pub description: String }
+pub struct McpTool {
+    pub name: String,
+    pub description: String,
+    /// Same input always gives the same output, so results may be cached
+    #[serde(default)]
+    pub cacheable: bool,
+    /// How long a cached result stays valid; `None` uses the cache default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl_secs: Option<u64>,
+}
+
+impl McpTool {
+    pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self { name: name.into(), description: description.into(), cacheable: false, cache_ttl_secs: None }
+    }
+
+    /// Mark the tool deterministic, optionally with its own cache TTL
+    pub fn cacheable(mut self, ttl_secs: Option<u64>) -> Self {
+        self.cacheable = true;
+        self.cache_ttl_secs = ttl_secs;
+        self
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct MockA2aAdapter;
@@ -58,3 +96,23 @@ impl ProtocolAdapter for MockA2aAdapter {
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct A2aEnvelope { pub from: String, pub to: String, pub content: String }
+
+/// What became of a message sent to an agent
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum A2aDelivery {
+    /// In the recipient's inbox
+    Delivered,
+    /// Held for a recipient that is registered but not currently available
+    Queued,
+    /// No such recipient; the message will never be processed
+    DeadLettered,
+}
+
+/// Envelope of a sent A2A message and whether it reached anyone
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct A2aSendResult {
+    pub envelope: A2aEnvelope,
+    pub delivery: A2aDelivery,
+    pub recipient_known: bool,
+}
@@ -0,0 +1,281 @@
+//! Result cache for deterministic MCP tools
+//!
+//! Tools flagged [`McpTool::cacheable`] return the same output for the same
+//! input, so a repeated invocation can be answered from the cache until its
+//! entry is older than the tool's `cache_ttl_secs`. Other tools always run.
+//! Each new entry purges the expired ones and, when the cache is still full,
+//! the oldest entry.
+
+use crate::{McpTool, MockMcpAdapter};
+use agentic_core::clock::{system_clock, SharedClock};
+use agentic_core::AgentEnv;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// TTL used for cacheable tools that don't set one
+pub const DEFAULT_TOOL_CACHE_TTL_SECS: u64 = 300;
+
+/// Outputs a cache holds unless configured otherwise
+pub const DEFAULT_TOOL_CACHE_MAX_ENTRIES: usize = 1000;
+
+/// Hit and miss counters for a [`ToolResultCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries currently held, expired ones included until the next insert
+    pub entries: usize,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    output: String,
+    cached_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<(String, String), CacheEntry>,
+    hits: u64,
+    misses: u64,
+}
+
+/// Tool outputs keyed by (tool, input)
+#[derive(Debug)]
+pub struct ToolResultCache {
+    state: Mutex<CacheState>,
+    max_entries: usize,
+    clock: SharedClock,
+}
+
+impl Default for ToolResultCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ToolResultCache {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(CacheState::default()),
+            max_entries: DEFAULT_TOOL_CACHE_MAX_ENTRIES,
+            clock: system_clock(),
+        }
+    }
+
+    /// Hold at most `max` outputs (0 is treated as 1)
+    pub fn with_max_entries(mut self, max: usize) -> Self {
+        self.max_entries = max.max(1);
+        self
+    }
+
+    /// Use a different time source for expiry (e.g. a `MockClock` in tests)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Return the cached output for `tool` and `input`, or run `invoke` and cache it
+    ///
+    /// Non-cacheable tools always run `invoke` and are not counted.
+    pub fn get_or_invoke(&self, tool: &McpTool, input: &str, invoke: impl FnOnce() -> String) -> String {
+        if !tool.cacheable {
+            return invoke();
+        }
+
+        let key = (tool.name.clone(), input.to_string());
+        let ttl = chrono::Duration::seconds(tool.cache_ttl_secs.unwrap_or(DEFAULT_TOOL_CACHE_TTL_SECS) as i64);
+        let now = self.clock.now();
+        {
+            let mut state = self.state.lock().unwrap();
+            let fresh = state
+                .entries
+                .get(&key)
+                .filter(|entry| now < entry.expires_at)
+                .map(|entry| entry.output.clone());
+            if let Some(output) = fresh {
+                state.hits += 1;
+                return output;
+            }
+            state.misses += 1;
+        }
+
+        // Run the tool without holding the lock
+        let output = invoke();
+        let entry = CacheEntry { output: output.clone(), cached_at: now, expires_at: now + ttl };
+        let mut state = self.state.lock().unwrap();
+        state.entries.retain(|_, entry| now < entry.expires_at);
+        if state.entries.len() >= self.max_entries && !state.entries.contains_key(&key) {
+            let oldest = state.entries.iter().min_by_key(|(_, entry)| entry.cached_at).map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+        state.entries.insert(key, entry);
+        output
+    }
+
+    pub fn stats(&self) -> ToolCacheStats {
+        let state = self.state.lock().unwrap();
+        ToolCacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            entries: state.entries.len(),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+}
+
+/// [`MockMcpAdapter`] with results of cacheable tools cached
+#[derive(Debug, Default)]
+pub struct CachedMcpAdapter {
+    adapter: MockMcpAdapter,
+    cache: ToolResultCache,
+}
+
+impl CachedMcpAdapter {
+    pub fn new(adapter: MockMcpAdapter, cache: ToolResultCache) -> Self {
+        Self { adapter, cache }
+    }
+
+    pub fn list_tools(&self) -> Vec<McpTool> {
+        self.adapter.list_tools()
+    }
+
+    pub fn invoke(&self, tool: &str, input: &str) -> String {
+        self.invoke_with_env(tool, input, &AgentEnv::default())
+    }
+
+    /// Like [`CachedMcpAdapter::invoke`], passing the calling agent's secrets on
+    ///
+    /// The secrets are not part of the cache key: a cacheable tool's output
+    /// depends on its input alone.
+    pub fn invoke_with_env(&self, tool: &str, input: &str, env: &AgentEnv) -> String {
+        match self.adapter.list_tools().into_iter().find(|t| t.name == tool) {
+            Some(spec) => self.cache.get_or_invoke(&spec, input, || self.adapter.invoke_with_env(tool, input, env)),
+            None => self.adapter.invoke_with_env(tool, input, env),
+        }
+    }
+
+    pub fn cache_stats(&self) -> ToolCacheStats {
+        self.cache.stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentic_core::MockClock;
+    use std::cell::Cell;
+
+    fn tool(name: &str, cacheable: bool) -> McpTool {
+        McpTool {
+            name: name.into(),
+            description: String::new(),
+            cacheable,
+            cache_ttl_secs: Some(60),
+        }
+    }
+
+    #[test]
+    fn test_cacheable_tool_served_from_cache() {
+        let cache = ToolResultCache::new();
+        let reverse = tool("reverse", true);
+        let calls = Cell::new(0);
+        let invoke = || {
+            calls.set(calls.get() + 1);
+            "olleh".to_string()
+        };
+
+        assert_eq!(cache.get_or_invoke(&reverse, "hello", invoke), "olleh");
+        assert_eq!(cache.get_or_invoke(&reverse, "hello", invoke), "olleh");
+
+        assert_eq!(calls.get(), 1);
+        assert_eq!(cache.stats(), ToolCacheStats { hits: 1, misses: 1, entries: 1 });
+    }
+
+    #[test]
+    fn test_non_cacheable_tool_always_runs() {
+        let cache = ToolResultCache::new();
+        let now = tool("now", false);
+        let calls = Cell::new(0);
+
+        for _ in 0..2 {
+            cache.get_or_invoke(&now, "", || {
+                calls.set(calls.get() + 1);
+                String::new()
+            });
+        }
+
+        assert_eq!(calls.get(), 2);
+        assert_eq!(cache.stats(), ToolCacheStats::default());
+    }
+
+    #[test]
+    fn test_entries_expire_after_ttl() {
+        let clock = MockClock::default();
+        let cache = ToolResultCache::new().with_clock(clock.shared());
+        let reverse = tool("reverse", true);
+
+        cache.get_or_invoke(&reverse, "ab", || "ba".to_string());
+        clock.advance(chrono::Duration::seconds(61));
+        cache.get_or_invoke(&reverse, "ab", || "ba".to_string());
+
+        assert_eq!(cache.stats().misses, 2);
+        assert_eq!(cache.stats().hits, 0);
+    }
+
+    #[test]
+    fn test_inserts_purge_expired_and_oldest_entries() {
+        let clock = MockClock::default();
+        let cache = ToolResultCache::new().with_max_entries(2).with_clock(clock.shared());
+        let reverse = tool("reverse", true);
+
+        cache.get_or_invoke(&reverse, "stale", || "elats".to_string());
+        clock.advance(chrono::Duration::seconds(61));
+        cache.get_or_invoke(&reverse, "a", || "a".to_string());
+        assert_eq!(cache.stats().entries, 1);
+
+        clock.advance(chrono::Duration::seconds(1));
+        cache.get_or_invoke(&reverse, "b", || "b".to_string());
+        clock.advance(chrono::Duration::seconds(1));
+        cache.get_or_invoke(&reverse, "c", || "c".to_string());
+
+        assert_eq!(cache.stats().entries, 2);
+        let misses = cache.stats().misses;
+        cache.get_or_invoke(&reverse, "c", || "c".to_string());
+        assert_eq!(cache.stats().misses, misses);
+        cache.get_or_invoke(&reverse, "a", || "a".to_string());
+        assert_eq!(cache.stats().misses, misses + 1);
+    }
+
+    #[test]
+    fn test_cached_adapter_reuses_reverse() {
+        let mcp = CachedMcpAdapter::default();
+
+        assert_eq!(mcp.invoke("reverse", "abc"), "cba");
+        assert_eq!(mcp.invoke("reverse", "abc"), "cba");
+        assert_eq!(mcp.invoke("unknown", "abc"), "unknown tool: unknown");
+
+        assert_eq!(mcp.cache_stats().hits, 1);
+    }
+
+    #[test]
+    fn test_secrets_stay_out_of_the_cache_key() {
+        let mcp = CachedMcpAdapter::default();
+        let mut env = AgentEnv::new();
+        env.set("SEARCH_API_KEY", "sk-one");
+
+        assert_eq!(mcp.invoke_with_env("reverse", "abc", &env), "cba");
+        env.set("SEARCH_API_KEY", "sk-two");
+        assert_eq!(mcp.invoke_with_env("reverse", "abc", &env), "cba");
+
+        assert_eq!(mcp.cache_stats(), ToolCacheStats { hits: 1, misses: 1, entries: 1 });
+    }
+}
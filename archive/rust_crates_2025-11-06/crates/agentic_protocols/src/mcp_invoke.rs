@@ -0,0 +1,171 @@
+//! Bounded MCP tool invocation
+//!
+//! The mock adapters answer instantly, but a remote MCP server can hang.
+//! [`TimedMcpAdapter`] gives every invocation a deadline and a slot in a
+//! bounded pool. When the deadline passes, or the caller drops the future
+//! (e.g. the HTTP client disconnected), the invocation is abandoned and its
+//! slot is released.
+
+use crate::{CachedMcpAdapter, McpTool, MockMcpAdapter};
+use agentic_core::{AgentEnv, Error, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+
+/// Timeout used when none is configured
+pub const DEFAULT_MCP_INVOKE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Invocations allowed in flight at once by default
+pub const DEFAULT_MAX_CONCURRENT_INVOKES: usize = 16;
+
+/// Something that can list and run MCP tools
+#[async_trait]
+pub trait McpAdapter: Send + Sync {
+    fn list_tools(&self) -> Vec<McpTool>;
+
+    async fn invoke(&self, tool: &str, input: &str) -> String;
+
+    /// Run `tool` on behalf of an agent whose secrets are in `env`
+    ///
+    /// Adapters whose tools need credentials override this; by default the
+    /// secrets are ignored.
+    async fn invoke_with_env(&self, tool: &str, input: &str, env: &AgentEnv) -> String {
+        let _ = env;
+        self.invoke(tool, input).await
+    }
+}
+
+#[async_trait]
+impl McpAdapter for MockMcpAdapter {
+    fn list_tools(&self) -> Vec<McpTool> {
+        MockMcpAdapter::list_tools(self)
+    }
+
+    async fn invoke(&self, tool: &str, input: &str) -> String {
+        MockMcpAdapter::invoke(self, tool, input)
+    }
+
+    async fn invoke_with_env(&self, tool: &str, input: &str, env: &AgentEnv) -> String {
+        MockMcpAdapter::invoke_with_env(self, tool, input, env)
+    }
+}
+
+#[async_trait]
+impl McpAdapter for CachedMcpAdapter {
+    fn list_tools(&self) -> Vec<McpTool> {
+        CachedMcpAdapter::list_tools(self)
+    }
+
+    async fn invoke(&self, tool: &str, input: &str) -> String {
+        CachedMcpAdapter::invoke(self, tool, input)
+    }
+
+    async fn invoke_with_env(&self, tool: &str, input: &str, env: &AgentEnv) -> String {
+        CachedMcpAdapter::invoke_with_env(self, tool, input, env)
+    }
+}
+
+/// Wraps an [`McpAdapter`] with a per-invocation timeout and a concurrency cap
+pub struct TimedMcpAdapter {
+    adapter: Arc<dyn McpAdapter>,
+    timeout: Duration,
+    permits: Arc<Semaphore>,
+    max_concurrent: usize,
+}
+
+impl TimedMcpAdapter {
+    pub fn new(adapter: Arc<dyn McpAdapter>) -> Self {
+        Self {
+            adapter,
+            timeout: DEFAULT_MCP_INVOKE_TIMEOUT,
+            permits: Arc::new(Semaphore::new(DEFAULT_MAX_CONCURRENT_INVOKES)),
+            max_concurrent: DEFAULT_MAX_CONCURRENT_INVOKES,
+        }
+    }
+
+    /// Abandon invocations that take longer than `timeout`
+    ///
+    /// Waiting for a free slot counts towards the timeout.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Allow at most `max` invocations in flight (0 is treated as 1)
+    pub fn with_max_concurrent(mut self, max: usize) -> Self {
+        self.max_concurrent = max.max(1);
+        self.permits = Arc::new(Semaphore::new(self.max_concurrent));
+        self
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Invocations currently holding a slot
+    pub fn in_flight(&self) -> usize {
+        self.max_concurrent - self.permits.available_permits()
+    }
+
+    pub fn list_tools(&self) -> Vec<McpTool> {
+        self.adapter.list_tools()
+    }
+
+    /// Run `tool`, failing with `Error::Timeout` once the deadline passes
+    pub async fn invoke(&self, tool: &str, input: &str) -> Result<String> {
+        self.invoke_with_env(tool, input, &AgentEnv::default()).await
+    }
+
+    /// Like [`TimedMcpAdapter::invoke`], passing the calling agent's secrets on
+    pub async fn invoke_with_env(&self, tool: &str, input: &str, env: &AgentEnv) -> Result<String> {
+        let run = async {
+            let _permit = self
+                .permits
+                .acquire()
+                .await
+                .map_err(|_| Error::InternalError("MCP invoke pool closed".to_string()))?;
+            Ok(self.adapter.invoke_with_env(tool, input, env).await)
+        };
+
+        match tokio::time::timeout(self.timeout, run).await {
+            Ok(result) => result,
+            Err(_) => {
+                tracing::warn!(tool, timeout_ms = self.timeout.as_millis() as u64, "MCP invoke timed out");
+                Err(Error::Timeout(format!(
+                    "MCP tool {} did not finish within {:?}",
+                    tool, self.timeout
+                )))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::SlowAdapter;
+
+    #[tokio::test]
+    async fn test_timeout_fires_and_releases_slot() {
+        let mcp = TimedMcpAdapter::new(Arc::new(SlowAdapter::new(Duration::from_secs(5))))
+            .with_timeout(Duration::from_millis(20))
+            .with_max_concurrent(1);
+
+        let err = mcp.invoke("slow", "x").await.unwrap_err();
+        assert!(matches!(err, Error::Timeout(_)));
+        assert_eq!(mcp.in_flight(), 0);
+
+        // The only slot was given back, so the next call isn't stuck behind the abandoned one
+        assert_eq!(mcp.invoke("fast", "y").await.unwrap(), "y");
+    }
+
+    #[tokio::test]
+    async fn test_fast_tools_are_unaffected() {
+        let mcp = TimedMcpAdapter::new(Arc::new(CachedMcpAdapter::default()))
+            .with_timeout(Duration::from_millis(200));
+
+        assert_eq!(mcp.invoke("reverse", "abc").await.unwrap(), "cba");
+        assert_eq!(mcp.list_tools().len(), 2);
+    }
+}
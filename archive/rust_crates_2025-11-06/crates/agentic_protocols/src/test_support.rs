@@ -0,0 +1,33 @@
+//! MCP adapters for tests
+//!
+//! Built for this crate's unit tests and, with the `test-support` feature,
+//! for other crates' tests.
+
+use crate::{McpAdapter, McpTool};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Takes `delay` to answer the `slow` tool; `fast` echoes at once
+pub struct SlowAdapter {
+    delay: Duration,
+}
+
+impl SlowAdapter {
+    pub fn new(delay: Duration) -> Self {
+        Self { delay }
+    }
+}
+
+#[async_trait]
+impl McpAdapter for SlowAdapter {
+    fn list_tools(&self) -> Vec<McpTool> {
+        vec![McpTool::new("slow", "Sleeps before answering"), McpTool::new("fast", "Echoes")]
+    }
+
+    async fn invoke(&self, tool: &str, input: &str) -> String {
+        if tool == "slow" {
+            tokio::time::sleep(self.delay).await;
+        }
+        input.to_string()
+    }
+}
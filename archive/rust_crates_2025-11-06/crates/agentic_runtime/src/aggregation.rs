@@ -0,0 +1,193 @@
+//! Combining worker results in a supervisor step
+//!
+//! When a supervisor fans a task out to several workers it gets back one
+//! [`ExecutionResult`] per worker. An [`AggregationStrategy`] decides how
+//! those become the single answer of the step. Failed workers are ignored by
+//! every strategy; the step only fails when no worker succeeded.
+
+use crate::context::ExecutionContext;
+use crate::executor::{AgentExecutor, ExecutionResult};
+use crate::temperature::TaskKind;
+use agentic_core::{Agent, Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// How a supervisor combines the outputs of its workers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationStrategy {
+    /// Every successful output, in worker order, separated by blank lines
+    Concat,
+    /// The output given by the most workers; ties go to the earliest worker
+    MajorityVote,
+    /// The supervisor's LLM merges the outputs into one answer
+    LlmSynthesize,
+    /// The first successful output
+    FirstSuccess,
+}
+
+impl AggregationStrategy {
+    /// Strategy to use when a request doesn't name one
+    pub fn for_task(kind: TaskKind) -> Self {
+        match kind {
+            TaskKind::Analysis => AggregationStrategy::LlmSynthesize,
+            TaskKind::Code => AggregationStrategy::FirstSuccess,
+            TaskKind::Documentation | TaskKind::Discovery | TaskKind::Creative => AggregationStrategy::Concat,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AggregationStrategy::Concat => "concat",
+            AggregationStrategy::MajorityVote => "majority_vote",
+            AggregationStrategy::LlmSynthesize => "llm_synthesize",
+            AggregationStrategy::FirstSuccess => "first_success",
+        }
+    }
+
+    /// Combine `results` into one output
+    ///
+    /// `supervisor` and `executor` are only used by `LlmSynthesize`, which
+    /// runs the supervisor on a prompt listing `task` and each worker output.
+    pub async fn aggregate(
+        &self,
+        task: &str,
+        results: &[ExecutionResult],
+        supervisor: &mut Agent,
+        executor: &dyn AgentExecutor,
+    ) -> Result<String> {
+        let outputs: Vec<&str> = results.iter().filter(|r| r.success).map(|r| r.output.as_str()).collect();
+        if outputs.is_empty() {
+            return Err(Error::InvalidState(format!(
+                "No successful worker results to aggregate ({} failed)",
+                results.len()
+            )));
+        }
+
+        match self {
+            AggregationStrategy::Concat => Ok(outputs.join("\n\n")),
+            AggregationStrategy::FirstSuccess => Ok(outputs[0].to_string()),
+            AggregationStrategy::MajorityVote => Ok(majority(&outputs).to_string()),
+            AggregationStrategy::LlmSynthesize => {
+                let prompt = synthesis_prompt(task, &outputs);
+                let context = ExecutionContext::new(supervisor.id);
+                let result = executor.execute(supervisor, &prompt, &context).await?;
+                if result.success {
+                    Ok(result.output)
+                } else {
+                    Err(Error::InternalError(format!(
+                        "Supervisor failed to synthesize worker results: {}",
+                        result.error.unwrap_or_default()
+                    )))
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for AggregationStrategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        [
+            AggregationStrategy::Concat,
+            AggregationStrategy::MajorityVote,
+            AggregationStrategy::LlmSynthesize,
+            AggregationStrategy::FirstSuccess,
+        ]
+        .into_iter()
+        .find(|strategy| strategy.as_str().eq_ignore_ascii_case(s.trim()))
+        .ok_or_else(|| Error::InvalidArgument(format!("Unknown aggregation strategy: {}", s)))
+    }
+}
+
+/// Most common output, compared ignoring surrounding whitespace
+fn majority<'a>(outputs: &[&'a str]) -> &'a str {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for output in outputs {
+        *counts.entry(output.trim()).or_default() += 1;
+    }
+    let best = outputs.iter().map(|o| counts[o.trim()]).max().unwrap_or(0);
+    outputs.iter().find(|o| counts[o.trim()] == best).copied().unwrap_or_default()
+}
+
+fn synthesis_prompt(task: &str, outputs: &[&str]) -> String {
+    let mut prompt = format!(
+        "You are supervising {} workers who were each given this task:\n\n{}\n\n",
+        outputs.len(),
+        task
+    );
+    for (i, output) in outputs.iter().enumerate() {
+        prompt.push_str(&format!("--- Worker {} ---\n{}\n\n", i + 1, output.trim()));
+    }
+    prompt.push_str(
+        "Merge these answers into a single, consistent answer. Resolve disagreements, \
+         drop duplicates and keep every point that is supported by at least one worker.",
+    );
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::DefaultExecutor;
+    use crate::llm::MockLlmClient;
+    use agentic_core::AgentRole;
+    use std::sync::Arc;
+
+    fn fixture() -> (Agent, DefaultExecutor) {
+        let supervisor = Agent::new("Lead", "Supervisor", AgentRole::Supervisor, "mock-model", "mock");
+        (supervisor, DefaultExecutor::new(Arc::new(MockLlmClient::new("merged"))))
+    }
+
+    fn results(outputs: &[&str]) -> Vec<ExecutionResult> {
+        outputs.iter().map(|o| ExecutionResult::success(o.to_string(), 0, 0)).collect()
+    }
+
+    #[tokio::test]
+    async fn test_concat_joins_successes_in_order() {
+        let (mut supervisor, executor) = fixture();
+        let mut worker_results = results(&["first", "second"]);
+        worker_results.insert(1, ExecutionResult::failure("boom".into(), 0));
+
+        let out = AggregationStrategy::Concat
+            .aggregate("task", &worker_results, &mut supervisor, &executor)
+            .await
+            .unwrap();
+        assert_eq!(out, "first\n\nsecond");
+    }
+
+    #[tokio::test]
+    async fn test_majority_vote_picks_plurality() {
+        let (mut supervisor, executor) = fixture();
+        let worker_results = results(&["red", "blue", "blue ", "green", "red", "blue"]);
+
+        let out = AggregationStrategy::MajorityVote
+            .aggregate("task", &worker_results, &mut supervisor, &executor)
+            .await
+            .unwrap();
+        assert_eq!(out.trim(), "blue");
+
+        // tie between "a" and "b": the earliest worker's answer wins
+        let tied = results(&["b", "a", "a", "b"]);
+        let out = AggregationStrategy::MajorityVote
+            .aggregate("task", &tied, &mut supervisor, &executor)
+            .await
+            .unwrap();
+        assert_eq!(out, "b");
+    }
+
+    #[tokio::test]
+    async fn test_no_successful_workers_is_an_error() {
+        let (mut supervisor, executor) = fixture();
+        let failed = vec![ExecutionResult::failure("boom".into(), 0)];
+
+        let err = AggregationStrategy::LlmSynthesize
+            .aggregate("task", &failed, &mut supervisor, &executor)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidState(_)));
+        assert_eq!(AggregationStrategy::for_task(TaskKind::Analysis), AggregationStrategy::LlmSynthesize);
+    }
+}
@@ -0,0 +1,218 @@
+//! Delays between retries
+//!
+//! A [`BackoffStrategy`] maps the number of the retry about to happen to how
+//! long to wait first, or to `None` when no more retries should be made.
+//! [`DefaultExecutor`](crate::executor::DefaultExecutor) consults it before
+//! re-running a retryable execution. Strategies are chosen in configuration
+//! through [`BackoffConfig`] (`LlmConfig::backoff`, `LLM_BACKOFF`).
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Retry delay before the first retry when none is configured
+pub const DEFAULT_BACKOFF_BASE: Duration = Duration::from_millis(200);
+
+/// Decides how long to wait before each retry
+pub trait BackoffStrategy: Send + Sync + std::fmt::Debug {
+    /// Delay before retry number `attempt` (1 for the first retry), or
+    /// `None` to give up
+    fn next_delay(&self, attempt: u32) -> Option<Duration>;
+}
+
+/// `base`, `2 * base`, `4 * base`, ... capped at `max_delay`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exponential {
+    pub base: Duration,
+    pub max_delay: Option<Duration>,
+    pub max_attempts: Option<u32>,
+}
+
+/// The same `delay` before every retry
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fixed {
+    pub delay: Duration,
+    pub max_attempts: Option<u32>,
+}
+
+/// `base` times 1, 1, 2, 3, 5, 8, ... capped at `max_delay`; grows more
+/// gently than [`Exponential`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fibonacci {
+    pub base: Duration,
+    pub max_delay: Option<Duration>,
+    pub max_attempts: Option<u32>,
+}
+
+impl Exponential {
+    pub fn new(base: Duration) -> Self {
+        Self { base, max_delay: None, max_attempts: None }
+    }
+}
+
+impl Fixed {
+    pub fn new(delay: Duration) -> Self {
+        Self { delay, max_attempts: None }
+    }
+}
+
+impl Fibonacci {
+    pub fn new(base: Duration) -> Self {
+        Self { base, max_delay: None, max_attempts: None }
+    }
+}
+
+/// `None` once `attempt` is past `max_attempts`
+fn within(attempt: u32, max_attempts: Option<u32>) -> Option<()> {
+    match max_attempts {
+        Some(max) if attempt > max => None,
+        _ => Some(()),
+    }
+}
+
+fn capped(delay: Duration, max_delay: Option<Duration>) -> Duration {
+    max_delay.map_or(delay, |max| delay.min(max))
+}
+
+impl BackoffStrategy for Exponential {
+    fn next_delay(&self, attempt: u32) -> Option<Duration> {
+        within(attempt, self.max_attempts)?;
+        let delay = self.base.saturating_mul(1 << attempt.saturating_sub(1).min(16));
+        Some(capped(delay, self.max_delay))
+    }
+}
+
+impl BackoffStrategy for Fixed {
+    fn next_delay(&self, attempt: u32) -> Option<Duration> {
+        within(attempt, self.max_attempts)?;
+        Some(self.delay)
+    }
+}
+
+impl BackoffStrategy for Fibonacci {
+    fn next_delay(&self, attempt: u32) -> Option<Duration> {
+        within(attempt, self.max_attempts)?;
+        let (mut previous, mut current) = (0u32, 1u32);
+        for _ in 1..attempt.min(40) {
+            (previous, current) = (current, previous.saturating_add(current));
+        }
+        Some(capped(self.base.saturating_mul(current), self.max_delay))
+    }
+}
+
+/// Serializable choice of [`BackoffStrategy`]
+///
+/// In JSON: `{"strategy": "fixed", "delay_ms": 1000}`. From the environment:
+/// `LLM_BACKOFF=<strategy>[:<milliseconds>]`, e.g. `fibonacci:250`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum BackoffConfig {
+    Exponential {
+        base_ms: u64,
+        #[serde(default)]
+        max_delay_ms: Option<u64>,
+        #[serde(default)]
+        max_attempts: Option<u32>,
+    },
+    Fixed {
+        delay_ms: u64,
+        #[serde(default)]
+        max_attempts: Option<u32>,
+    },
+    Fibonacci {
+        base_ms: u64,
+        #[serde(default)]
+        max_delay_ms: Option<u64>,
+        #[serde(default)]
+        max_attempts: Option<u32>,
+    },
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        BackoffConfig::Exponential {
+            base_ms: DEFAULT_BACKOFF_BASE.as_millis() as u64,
+            max_delay_ms: None,
+            max_attempts: None,
+        }
+    }
+}
+
+impl BackoffConfig {
+    /// The configured strategy
+    pub fn build(&self) -> Arc<dyn BackoffStrategy> {
+        let ms = Duration::from_millis;
+        match *self {
+            BackoffConfig::Exponential { base_ms, max_delay_ms, max_attempts } => Arc::new(Exponential {
+                base: ms(base_ms),
+                max_delay: max_delay_ms.map(ms),
+                max_attempts,
+            }),
+            BackoffConfig::Fixed { delay_ms, max_attempts } => Arc::new(Fixed { delay: ms(delay_ms), max_attempts }),
+            BackoffConfig::Fibonacci { base_ms, max_delay_ms, max_attempts } => Arc::new(Fibonacci {
+                base: ms(base_ms),
+                max_delay: max_delay_ms.map(ms),
+                max_attempts,
+            }),
+        }
+    }
+}
+
+impl FromStr for BackoffConfig {
+    type Err = agentic_core::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || agentic_core::Error::InvalidArgument(format!("Invalid backoff: {}", s));
+        let (strategy, ms) = match s.trim().split_once(':') {
+            Some((strategy, ms)) => (strategy, Some(ms.trim().parse::<u64>().map_err(|_| invalid())?)),
+            None => (s.trim(), None),
+        };
+        let ms = ms.unwrap_or(DEFAULT_BACKOFF_BASE.as_millis() as u64);
+        match strategy.to_ascii_lowercase().as_str() {
+            "exponential" => Ok(BackoffConfig::Exponential { base_ms: ms, max_delay_ms: None, max_attempts: None }),
+            "fixed" => Ok(BackoffConfig::Fixed { delay_ms: ms, max_attempts: None }),
+            "fibonacci" => Ok(BackoffConfig::Fibonacci { base_ms: ms, max_delay_ms: None, max_attempts: None }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delays(strategy: &dyn BackoffStrategy, attempts: u32) -> Vec<Option<u64>> {
+        (1..=attempts).map(|a| strategy.next_delay(a).map(|d| d.as_millis() as u64)).collect()
+    }
+
+    #[test]
+    fn test_strategies_yield_expected_sequences() {
+        let exponential = Exponential { max_delay: Some(Duration::from_millis(500)), ..Exponential::new(Duration::from_millis(100)) };
+        assert_eq!(delays(&exponential, 5), vec![Some(100), Some(200), Some(400), Some(500), Some(500)]);
+
+        let fixed = Fixed::new(Duration::from_millis(250));
+        assert_eq!(delays(&fixed, 3), vec![Some(250); 3]);
+
+        let fibonacci = Fibonacci::new(Duration::from_millis(100));
+        assert_eq!(delays(&fibonacci, 6), vec![Some(100), Some(100), Some(200), Some(300), Some(500), Some(800)]);
+    }
+
+    #[test]
+    fn test_max_attempts_stops_retrying() {
+        let fixed = Fixed { max_attempts: Some(2), ..Fixed::new(Duration::from_millis(10)) };
+        assert_eq!(delays(&fixed, 3), vec![Some(10), Some(10), None]);
+
+        let config: BackoffConfig =
+            serde_json::from_value(serde_json::json!({"strategy": "fibonacci", "base_ms": 50, "max_attempts": 1})).unwrap();
+        assert_eq!(delays(config.build().as_ref(), 2), vec![Some(50), None]);
+    }
+
+    #[test]
+    fn test_parse_from_env_syntax() {
+        assert_eq!("fixed:1000".parse::<BackoffConfig>().unwrap(), BackoffConfig::Fixed { delay_ms: 1000, max_attempts: None });
+        assert_eq!("exponential".parse::<BackoffConfig>().unwrap(), BackoffConfig::default());
+        assert!("linear:5".parse::<BackoffConfig>().is_err());
+        assert!("fixed:soon".parse::<BackoffConfig>().is_err());
+    }
+}
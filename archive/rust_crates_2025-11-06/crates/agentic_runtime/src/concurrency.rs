@@ -0,0 +1,181 @@
+//! Process-wide cap on in-flight LLM requests
+//!
+//! Parallel discovery and validation can fan out far more `complete` calls
+//! than a provider account tolerates. Every client acquires a permit from an
+//! [`LlmConcurrencyLimiter`] before sending, so the total number of requests
+//! in flight stays bounded no matter how many call sites run at once. This is
+//! separate from per-minute rate limiting.
+
+use crate::config::PerformanceConfig;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+static GLOBAL: OnceLock<LlmConcurrencyLimiter> = OnceLock::new();
+
+/// Semaphore bounding concurrent LLM calls; clones share the same permits
+#[derive(Debug, Clone)]
+pub struct LlmConcurrencyLimiter {
+    semaphore: Arc<Semaphore>,
+    limit: Arc<AtomicUsize>,
+}
+
+impl LlmConcurrencyLimiter {
+    /// Allow at most `limit` calls in flight (a limit of 0 is treated as 1)
+    pub fn new(limit: usize) -> Self {
+        let limit = limit.max(1);
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            limit: Arc::new(AtomicUsize::new(limit)),
+        }
+    }
+
+    pub fn from_config(config: &PerformanceConfig) -> Self {
+        Self::new(config.max_concurrent_llm_calls)
+    }
+
+    /// Shared limiter used by clients that aren't given one explicitly
+    ///
+    /// Sized from [`PerformanceConfig::from_env`] on first use, or by an
+    /// earlier call to [`LlmConcurrencyLimiter::install_global`].
+    pub fn global() -> &'static LlmConcurrencyLimiter {
+        GLOBAL.get_or_init(|| Self::from_config(&PerformanceConfig::from_env()))
+    }
+
+    /// Set the global limiter; returns false if it was already initialised
+    pub fn install_global(limiter: LlmConcurrencyLimiter) -> bool {
+        GLOBAL.set(limiter).is_ok()
+    }
+
+    /// Wait for a slot; the call stays counted until the permit is dropped
+    pub async fn acquire(&self) -> OwnedSemaphorePermit {
+        self.semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("LLM concurrency semaphore is never closed")
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit.load(Ordering::SeqCst)
+    }
+
+    /// Change the limit for every clone (0 is treated as 1)
+    ///
+    /// Calls already in flight keep their permits; when lowering the limit
+    /// below them, the excess permits are retired as those calls finish,
+    /// which needs a Tokio runtime.
+    pub fn set_limit(&self, limit: usize) {
+        let limit = limit.max(1);
+        let previous = self.limit.swap(limit, Ordering::SeqCst);
+        if limit > previous {
+            self.semaphore.add_permits(limit - previous);
+        } else if limit < previous {
+            let excess = (previous - limit) as u32;
+            let retired = self.semaphore.forget_permits(excess as usize) as u32;
+            if retired < excess {
+                let semaphore = self.semaphore.clone();
+                tokio::spawn(async move {
+                    if let Ok(permits) = semaphore.acquire_many_owned(excess - retired).await {
+                        permits.forget();
+                    }
+                });
+            }
+        }
+    }
+
+    /// Number of calls currently holding a permit
+    pub fn in_flight(&self) -> usize {
+        self.limit().saturating_sub(self.semaphore.available_permits())
+    }
+}
+
+impl Default for LlmConcurrencyLimiter {
+    fn default() -> Self {
+        Self::from_config(&PerformanceConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{LlmClient, LlmRequest, Message, MockLlmClient};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_in_flight_calls_never_exceed_limit() {
+        let limiter = LlmConcurrencyLimiter::new(2);
+        let client = Arc::new(
+            MockLlmClient::default()
+                .with_latency(Duration::from_millis(50))
+                .with_concurrency_limiter(limiter.clone()),
+        );
+
+        let done = Arc::new(AtomicBool::new(false));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let sampler = {
+            let (limiter, done, peak) = (limiter.clone(), done.clone(), peak.clone());
+            tokio::spawn(async move {
+                while !done.load(Ordering::SeqCst) {
+                    peak.fetch_max(limiter.in_flight(), Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+            })
+        };
+
+        let started = Instant::now();
+        let calls: Vec<_> = (0..6)
+            .map(|i| {
+                let client = client.clone();
+                tokio::spawn(async move {
+                    let request = LlmRequest::new("mock-model").add_message(Message::user(format!("call {}", i)));
+                    client.complete(request).await
+                })
+            })
+            .collect();
+        for call in calls {
+            call.await.unwrap().unwrap();
+        }
+        done.store(true, Ordering::SeqCst);
+        sampler.await.unwrap();
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        // Six 50ms calls two at a time take at least three rounds
+        assert!(started.elapsed() >= Duration::from_millis(150));
+        assert_eq!(limiter.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_set_limit_resizes_every_clone() {
+        let limiter = LlmConcurrencyLimiter::new(2);
+        let shared = limiter.clone();
+        let first = limiter.acquire().await;
+        let second = limiter.acquire().await;
+
+        shared.set_limit(4);
+        assert_eq!(limiter.limit(), 4);
+        assert_eq!(limiter.in_flight(), 2);
+
+        // Shrinking below the calls in flight retires permits as they finish
+        shared.set_limit(1);
+        assert_eq!(limiter.limit(), 1);
+        drop(first);
+        tokio::task::yield_now().await;
+        assert_eq!(limiter.in_flight(), 1);
+        drop(second);
+        assert_eq!(limiter.in_flight(), 0);
+        let _only = limiter.acquire().await;
+        assert!(limiter.semaphore.try_acquire().is_err());
+    }
+
+    #[test]
+    fn test_limit_from_config() {
+        let config = PerformanceConfig {
+            max_concurrent_llm_calls: 3,
+            ..Default::default()
+        };
+        assert_eq!(LlmConcurrencyLimiter::from_config(&config).limit(), 3);
+        assert_eq!(LlmConcurrencyLimiter::new(0).limit(), 1);
+    }
+}
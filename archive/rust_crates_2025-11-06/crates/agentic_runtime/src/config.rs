@@ -1,23 +1,28 @@
 //! Configuration management for the runtime
 
+use crate::backoff::BackoffConfig;
+use crate::rate_limit::ProviderLimits;
+use crate::retry::RetryPolicy;
+use crate::llm::LlmProvider;
+use crate::routing::RoutingConfig;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
+use std::path::Path;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RuntimeConfig {
     pub llm: LlmConfig,
     pub execution: ExecutionConfig,
     pub performance: PerformanceConfig,
+    pub http: HttpConfig,
 }
 
 impl RuntimeConfig {
     /// Load configuration from environment variables
     pub fn from_env() -> Self {
-        Self {
-            llm: LlmConfig::from_env(),
-            execution: ExecutionConfig::from_env(),
-            performance: PerformanceConfig::from_env(),
-        }
+        Self::layered([PartialRuntimeConfig::from_env()])
     }
 
     /// Load with defaults
@@ -26,37 +31,286 @@ impl RuntimeConfig {
             llm: LlmConfig::default(),
             execution: ExecutionConfig::default(),
             performance: PerformanceConfig::default(),
+            http: HttpConfig::default(),
+        }
+    }
+
+    /// Build from defaults, applying layers in increasing precedence
+    /// (e.g. `[file, env, cli]`)
+    pub fn layered(layers: impl IntoIterator<Item = PartialRuntimeConfig>) -> Self {
+        layers.into_iter().fold(Self::default(), |mut config, layer| {
+            config.merge(layer);
+            config
+        })
+    }
+
+    /// Override only the fields set in `other`
+    pub fn merge(&mut self, other: PartialRuntimeConfig) {
+        self.llm.merge(other.llm);
+        self.execution.merge(other.execution);
+        self.performance.merge(other.performance);
+        self.http.merge(other.http);
+    }
+}
+
+/// A single configuration layer where every field is optional
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PartialRuntimeConfig {
+    pub llm: PartialLlmConfig,
+    pub execution: PartialExecutionConfig,
+    pub performance: PartialPerformanceConfig,
+    pub http: PartialHttpConfig,
+}
+
+impl PartialRuntimeConfig {
+    /// Layer containing only the environment variables that are set
+    pub fn from_env() -> Self {
+        Self {
+            llm: PartialLlmConfig::from_env(),
+            execution: PartialExecutionConfig::from_env(),
+            performance: PartialPerformanceConfig::from_env(),
+            http: PartialHttpConfig::from_env(),
         }
     }
+
+    /// Layer read from a JSON config file
+    pub fn from_file(path: impl AsRef<Path>) -> agentic_core::Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            agentic_core::Error::InitializationFailed(format!("Cannot read config {}: {}", path.display(), e))
+        })?;
+        Ok(serde_json::from_str(&contents)?)
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Parse an environment variable, ignoring it when unset or malformed
+fn env_parse<T: FromStr>(key: &str) -> Option<T> {
+    env::var(key).ok().and_then(|v| v.parse().ok())
+}
+
+/// Parse `Name=value,Other=value` header lists; entries without `=` are skipped
+fn parse_headers(list: &str) -> HashMap<String, String> {
+    list.split(',')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+/// Parse `provider=requests/tokens` lists such as `anthropic=50/40000,openai=500`;
+/// malformed entries are logged and skipped
+fn parse_rate_limits(list: &str) -> HashMap<String, ProviderLimits> {
+    parse_headers(list)
+        .into_iter()
+        .filter_map(|(provider, limits)| match limits.parse() {
+            Ok(limits) => Some((provider.to_ascii_lowercase(), limits)),
+            Err(e) => {
+                tracing::warn!("Ignoring LLM_RATE_LIMITS entry for {}: {}", provider, e);
+                None
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PartialLlmConfig {
+    pub anthropic_api_key: Option<String>,
+    pub openai_api_key: Option<String>,
+    pub openai_base_url: Option<String>,
+    pub ollama_base_url: Option<String>,
+    pub default_provider: Option<String>,
+    pub default_model: Option<String>,
+    pub max_tokens: Option<usize>,
+    pub temperature: Option<f32>,
+    pub extra_headers: Option<HashMap<String, String>>,
+    pub backoff: Option<BackoffConfig>,
+    pub routing: Option<RoutingConfig>,
+}
+
+impl PartialLlmConfig {
+    pub fn from_env() -> Self {
+        Self {
+            anthropic_api_key: env::var("ANTHROPIC_API_KEY").ok(),
+            openai_api_key: env::var("OPENAI_API_KEY").ok(),
+            openai_base_url: env::var("OPENAI_BASE_URL").ok(),
+            ollama_base_url: env::var("OLLAMA_BASE_URL").ok(),
+            default_provider: env::var("DEFAULT_LLM_PROVIDER").ok(),
+            default_model: env::var("DEFAULT_MODEL").ok(),
+            max_tokens: env_parse("MAX_TOKENS"),
+            temperature: env_parse("DEFAULT_TEMPERATURE"),
+            extra_headers: env::var("LLM_EXTRA_HEADERS").ok().map(|v| parse_headers(&v)),
+            backoff: env_parse("LLM_BACKOFF"),
+            routing: [
+                "LLM_PROVIDER_PRIORITY",
+                "LLM_MODEL_ROUTES",
+                "LLM_FAILURE_THRESHOLD",
+                "LLM_UNHEALTHY_COOLDOWN",
+                "LLM_HEALTH_CHECK_INTERVAL",
+            ]
+            .iter()
+            .any(|var| env::var(var).is_ok())
+            .then(RoutingConfig::from_env),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PartialExecutionConfig {
+    pub agent_timeout_seconds: Option<u64>,
+    pub enable_learning: Option<bool>,
+    pub max_llm_calls: Option<u32>,
+    pub max_tokens_per_execution: Option<usize>,
+    pub max_tool_depth: Option<u32>,
+    pub mcp_invoke_timeout_seconds: Option<u64>,
+    pub max_execution_retries: Option<u32>,
+    pub max_cost_usd_per_execution: Option<f64>,
+}
+
+impl PartialExecutionConfig {
+    pub fn from_env() -> Self {
+        Self {
+            agent_timeout_seconds: env_parse("AGENT_TIMEOUT"),
+            enable_learning: env_parse("ENABLE_LEARNING"),
+            max_llm_calls: env_parse("MAX_LLM_CALLS"),
+            max_tokens_per_execution: env_parse("MAX_EXECUTION_TOKENS"),
+            max_tool_depth: env_parse("MAX_TOOL_DEPTH"),
+            mcp_invoke_timeout_seconds: env_parse("MCP_INVOKE_TIMEOUT"),
+            max_execution_retries: env_parse("MAX_EXECUTION_RETRIES"),
+            max_cost_usd_per_execution: env_parse("MAX_EXECUTION_COST_USD"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PartialPerformanceConfig {
+    pub max_concurrent_executions: Option<usize>,
+    pub task_queue_size: Option<usize>,
+    pub rate_limit_per_minute: Option<u32>,
+    pub max_concurrent_llm_calls: Option<usize>,
+    pub provider_rate_limits: Option<HashMap<String, ProviderLimits>>,
+    pub llm_retry: Option<RetryPolicy>,
+}
+
+impl PartialPerformanceConfig {
+    pub fn from_env() -> Self {
+        Self {
+            max_concurrent_executions: env_parse("MAX_CONCURRENT_EXECUTIONS"),
+            task_queue_size: env_parse("TASK_QUEUE_SIZE"),
+            rate_limit_per_minute: env_parse("RATE_LIMIT_PER_MINUTE"),
+            max_concurrent_llm_calls: env_parse("MAX_CONCURRENT_LLM_CALLS"),
+            provider_rate_limits: env::var("LLM_RATE_LIMITS").ok().map(|v| parse_rate_limits(&v)),
+            llm_retry: [
+                "LLM_RETRY_MAX_ATTEMPTS",
+                "LLM_RETRY_BACKOFF_MS",
+                "LLM_RETRY_MAX_BACKOFF_MS",
+                "LLM_RETRY_JITTER",
+                "LLM_RETRY_ON",
+            ]
+            .iter()
+            .any(|var| env::var(var).is_ok())
+            .then(RetryPolicy::from_env),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PartialHttpConfig {
+    pub proxy: Option<String>,
+    pub connect_timeout_seconds: Option<u64>,
+    pub request_timeout_seconds: Option<u64>,
+    pub ca_cert_path: Option<String>,
+}
+
+impl PartialHttpConfig {
+    pub fn from_env() -> Self {
+        Self {
+            proxy: env::var("HTTPS_PROXY").or_else(|_| env::var("HTTP_PROXY")).ok(),
+            connect_timeout_seconds: env_parse("HTTP_CONNECT_TIMEOUT"),
+            request_timeout_seconds: env_parse("HTTP_REQUEST_TIMEOUT"),
+            ca_cert_path: env::var("HTTP_CA_CERT").ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LlmConfig {
     pub anthropic_api_key: Option<String>,
     pub openai_api_key: Option<String>,
+    /// Endpoint of an OpenAI-compatible server (vLLM, LM Studio, ...) used
+    /// instead of api.openai.com (`OPENAI_BASE_URL=http://localhost:8000/v1`)
+    #[serde(default)]
+    pub openai_base_url: Option<String>,
+    /// Local Ollama server, `http://localhost:11434` when unset
+    /// (`OLLAMA_BASE_URL`)
+    #[serde(default)]
+    pub ollama_base_url: Option<String>,
     pub default_provider: String,
     pub default_model: String,
     pub max_tokens: usize,
     pub temperature: f32,
+    /// Headers sent with every provider call, e.g. a gateway's tenant id or
+    /// cost center (`LLM_EXTRA_HEADERS=X-Tenant=acme,X-Cost-Center=42`)
+    #[serde(default)]
+    pub extra_headers: HashMap<String, String>,
+    /// Wait between retries of a failed call (`LLM_BACKOFF=fixed:1000`)
+    #[serde(default)]
+    pub backoff: BackoffConfig,
+    /// Spread calls over several providers with failover
+    /// (`LLM_PROVIDER_PRIORITY`, `LLM_MODEL_ROUTES`); off by default
+    #[serde(default)]
+    pub routing: RoutingConfig,
 }
 
 impl LlmConfig {
     pub fn from_env() -> Self {
-        Self {
-            anthropic_api_key: env::var("ANTHROPIC_API_KEY").ok(),
-            openai_api_key: env::var("OPENAI_API_KEY").ok(),
-            default_provider: env::var("DEFAULT_LLM_PROVIDER")
-                .unwrap_or_else(|_| "mock".to_string()),
-            default_model: env::var("DEFAULT_MODEL")
-                .unwrap_or_else(|_| "claude-3-5-sonnet-20241022".to_string()),
-            max_tokens: env::var("MAX_TOKENS")
-                .unwrap_or_else(|_| "4096".to_string())
-                .parse()
-                .unwrap_or(4096),
-            temperature: env::var("DEFAULT_TEMPERATURE")
-                .unwrap_or_else(|_| "0.7".to_string())
-                .parse()
-                .unwrap_or(0.7),
+        let mut config = Self::default();
+        config.merge(PartialLlmConfig::from_env());
+        config
+    }
+
+    /// Parse the configured default provider
+    pub fn provider(&self) -> agentic_core::Result<LlmProvider> {
+        self.default_provider.parse()
+    }
+
+    fn merge(&mut self, other: PartialLlmConfig) {
+        if other.anthropic_api_key.is_some() {
+            self.anthropic_api_key = other.anthropic_api_key;
+        }
+        if other.openai_api_key.is_some() {
+            self.openai_api_key = other.openai_api_key;
+        }
+        if other.openai_base_url.is_some() {
+            self.openai_base_url = other.openai_base_url;
+        }
+        if other.ollama_base_url.is_some() {
+            self.ollama_base_url = other.ollama_base_url;
+        }
+        if let Some(provider) = other.default_provider {
+            self.default_provider = provider;
+        }
+        if let Some(model) = other.default_model {
+            self.default_model = model;
+        }
+        if let Some(max_tokens) = other.max_tokens {
+            self.max_tokens = max_tokens;
+        }
+        if let Some(temperature) = other.temperature {
+            self.temperature = temperature;
+        }
+        if let Some(headers) = other.extra_headers {
+            self.extra_headers = headers;
+        }
+        if let Some(backoff) = other.backoff {
+            self.backoff = backoff;
+        }
+        if let Some(routing) = other.routing {
+            self.routing = routing;
         }
     }
 }
@@ -66,36 +320,96 @@ impl Default for LlmConfig {
         Self {
             anthropic_api_key: None,
             openai_api_key: None,
-            default_provider: "mock".to_string(),
-            default_model: "claude-3-5-sonnet-20241022".to_string(),
+            openai_base_url: None,
+            ollama_base_url: None,
+            default_provider: LlmProvider::Mock.as_str().to_string(),
+            default_model: LlmProvider::Anthropic.default_model().to_string(),
             max_tokens: 4096,
             temperature: 0.7,
+            extra_headers: HashMap::new(),
+            backoff: BackoffConfig::default(),
+            routing: RoutingConfig::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ExecutionConfig {
+    /// Also the sandbox wall-time limit for a single execution
     pub agent_timeout_seconds: u64,
-    pub max_retries: u32,
     pub enable_learning: bool,
+    /// LLM calls one execution may make
+    #[serde(default = "default_max_llm_calls")]
+    pub max_llm_calls: u32,
+    /// Tokens one execution may consume across all its LLM calls
+    #[serde(default = "default_max_tokens_per_execution")]
+    pub max_tokens_per_execution: usize,
+    /// How deeply tool calls may nest within one execution
+    #[serde(default = "default_max_tool_depth")]
+    pub max_tool_depth: u32,
+    /// How long one MCP tool invocation may run before it is abandoned
+    #[serde(default = "default_mcp_invoke_timeout_seconds")]
+    pub mcp_invoke_timeout_seconds: u64,
+    /// Times a whole execution is re-run after failing with `Error::Retryable`;
+    /// separate from `performance.llm_retry`, which covers individual LLM calls
+    #[serde(default = "default_max_execution_retries")]
+    pub max_execution_retries: u32,
+    /// Estimated USD one execution may spend across all its LLM calls;
+    /// unlimited when unset
+    #[serde(default)]
+    pub max_cost_usd_per_execution: Option<f64>,
+}
+
+fn default_max_llm_calls() -> u32 {
+    10
+}
+
+fn default_max_tokens_per_execution() -> usize {
+    100_000
+}
+
+fn default_max_tool_depth() -> u32 {
+    5
+}
+
+fn default_mcp_invoke_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_max_execution_retries() -> u32 {
+    1
 }
 
 impl ExecutionConfig {
     pub fn from_env() -> Self {
-        Self {
-            agent_timeout_seconds: env::var("AGENT_TIMEOUT")
-                .unwrap_or_else(|_| "120".to_string())
-                .parse()
-                .unwrap_or(120),
-            max_retries: env::var("MAX_RETRIES")
-                .unwrap_or_else(|_| "3".to_string())
-                .parse()
-                .unwrap_or(3),
-            enable_learning: env::var("ENABLE_LEARNING")
-                .unwrap_or_else(|_| "true".to_string())
-                .parse()
-                .unwrap_or(true),
+        let mut config = Self::default();
+        config.merge(PartialExecutionConfig::from_env());
+        config
+    }
+    fn merge(&mut self, other: PartialExecutionConfig) {
+        if let Some(timeout) = other.agent_timeout_seconds {
+            self.agent_timeout_seconds = timeout;
+        }
+        if let Some(enable) = other.enable_learning {
+            self.enable_learning = enable;
+        }
+        if let Some(calls) = other.max_llm_calls {
+            self.max_llm_calls = calls;
+        }
+        if let Some(tokens) = other.max_tokens_per_execution {
+            self.max_tokens_per_execution = tokens;
+        }
+        if let Some(depth) = other.max_tool_depth {
+            self.max_tool_depth = depth;
+        }
+        if let Some(timeout) = other.mcp_invoke_timeout_seconds {
+            self.mcp_invoke_timeout_seconds = timeout;
+        }
+        if let Some(retries) = other.max_execution_retries {
+            self.max_execution_retries = retries;
+        }
+        if let Some(cost) = other.max_cost_usd_per_execution {
+            self.max_cost_usd_per_execution = Some(cost);
         }
     }
 }
@@ -104,34 +418,63 @@ impl Default for ExecutionConfig {
     fn default() -> Self {
         Self {
             agent_timeout_seconds: 120,
-            max_retries: 3,
             enable_learning: true,
+            max_llm_calls: default_max_llm_calls(),
+            max_tokens_per_execution: default_max_tokens_per_execution(),
+            max_tool_depth: default_max_tool_depth(),
+            mcp_invoke_timeout_seconds: default_mcp_invoke_timeout_seconds(),
+            max_execution_retries: default_max_execution_retries(),
+            max_cost_usd_per_execution: None,
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PerformanceConfig {
     pub max_concurrent_executions: usize,
     pub task_queue_size: usize,
+    /// LLM requests a minute per provider without its own entry in
+    /// `provider_rate_limits`
     pub rate_limit_per_minute: u32,
+    /// Upper bound on LLM requests in flight across the whole process
+    #[serde(default = "default_max_concurrent_llm_calls")]
+    pub max_concurrent_llm_calls: usize,
+    /// Request and token budgets by provider name
+    #[serde(default)]
+    pub provider_rate_limits: HashMap<String, ProviderLimits>,
+    /// How failed LLM requests are retried before the error reaches the caller
+    #[serde(default)]
+    pub llm_retry: RetryPolicy,
+}
+
+fn default_max_concurrent_llm_calls() -> usize {
+    20
 }
 
 impl PerformanceConfig {
     pub fn from_env() -> Self {
-        Self {
-            max_concurrent_executions: env::var("MAX_CONCURRENT_EXECUTIONS")
-                .unwrap_or_else(|_| "10".to_string())
-                .parse()
-                .unwrap_or(10),
-            task_queue_size: env::var("TASK_QUEUE_SIZE")
-                .unwrap_or_else(|_| "1000".to_string())
-                .parse()
-                .unwrap_or(1000),
-            rate_limit_per_minute: env::var("RATE_LIMIT_PER_MINUTE")
-                .unwrap_or_else(|_| "100".to_string())
-                .parse()
-                .unwrap_or(100),
+        let mut config = Self::default();
+        config.merge(PartialPerformanceConfig::from_env());
+        config
+    }
+    fn merge(&mut self, other: PartialPerformanceConfig) {
+        if let Some(max) = other.max_concurrent_executions {
+            self.max_concurrent_executions = max;
+        }
+        if let Some(size) = other.task_queue_size {
+            self.task_queue_size = size;
+        }
+        if let Some(rate) = other.rate_limit_per_minute {
+            self.rate_limit_per_minute = rate;
+        }
+        if let Some(max) = other.max_concurrent_llm_calls {
+            self.max_concurrent_llm_calls = max;
+        }
+        if let Some(limits) = other.provider_rate_limits {
+            self.provider_rate_limits = limits;
+        }
+        if let Some(retry) = other.llm_retry {
+            self.llm_retry = retry;
         }
     }
 }
@@ -142,6 +485,81 @@ impl Default for PerformanceConfig {
             max_concurrent_executions: 10,
             task_queue_size: 1000,
             rate_limit_per_minute: 100,
+            max_concurrent_llm_calls: default_max_concurrent_llm_calls(),
+            provider_rate_limits: HashMap::new(),
+            llm_retry: RetryPolicy::default(),
+        }
+    }
+}
+
+/// Outbound HTTP settings shared by LLM providers and discovery sources
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpConfig {
+    pub proxy: Option<String>,
+    pub connect_timeout_seconds: u64,
+    pub request_timeout_seconds: u64,
+    /// PEM file with additional root certificates (e.g. a corporate CA)
+    pub ca_cert_path: Option<String>,
+}
+
+impl HttpConfig {
+    pub fn from_env() -> Self {
+        let mut config = Self::default();
+        config.merge(PartialHttpConfig::from_env());
+        config
+    }
+    fn merge(&mut self, other: PartialHttpConfig) {
+        if other.proxy.is_some() {
+            self.proxy = other.proxy;
         }
+        if let Some(timeout) = other.connect_timeout_seconds {
+            self.connect_timeout_seconds = timeout;
+        }
+        if let Some(timeout) = other.request_timeout_seconds {
+            self.request_timeout_seconds = timeout;
+        }
+        if other.ca_cert_path.is_some() {
+            self.ca_cert_path = other.ca_cert_path;
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            connect_timeout_seconds: 10,
+            request_timeout_seconds: 120,
+            ca_cert_path: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_layer_overrides_file_layer() {
+        let file: PartialRuntimeConfig = serde_json::from_str(
+            r#"{"llm": {"default_model": "gpt-4o", "max_tokens": 2048}, "execution": {"agent_timeout_seconds": 60}}"#,
+        )
+        .unwrap();
+        let env = PartialRuntimeConfig {
+            llm: PartialLlmConfig {
+                default_model: Some("claude-3-opus-20240229".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let config = RuntimeConfig::layered([file, env]);
+        let defaults = RuntimeConfig::default();
+
+        assert_eq!(config.llm.default_model, "claude-3-opus-20240229");
+        assert_eq!(config.llm.max_tokens, 2048);
+        assert_eq!(config.execution.agent_timeout_seconds, 60);
+        assert_eq!(config.llm.default_provider, defaults.llm.default_provider);
+        assert_eq!(config.performance.task_queue_size, defaults.performance.task_queue_size);
     }
 }
@@ -44,6 +44,29 @@ impl Default for ContextData {
     }
 }
 
+/// Per-call model settings that take precedence over the agent's own
+/// configuration without mutating the agent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelOverrides {
+    pub model: Option<String>,
+    pub provider: Option<String>,
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<usize>,
+    /// Added to the request's stop sequences
+    #[serde(default)]
+    pub stop_sequences: Vec<String>,
+}
+
+impl ModelOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.model.is_none()
+            && self.provider.is_none()
+            && self.temperature.is_none()
+            && self.max_tokens.is_none()
+            && self.stop_sequences.is_empty()
+    }
+}
+
 /// Execution context for an agent run
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionContext {
@@ -52,6 +75,11 @@ pub struct ExecutionContext {
     pub parent_agent_id: Option<AgentId>,
     pub data: ContextData,
     pub metadata: HashMap<String, String>,
+    #[serde(default)]
+    pub overrides: ModelOverrides,
+    /// Return the full prompts sent, see [`crate::prompt_trace`]
+    #[serde(default)]
+    pub capture_prompts: bool,
 }
 
 impl ExecutionContext {
@@ -62,6 +90,8 @@ impl ExecutionContext {
             parent_agent_id: None,
             data: ContextData::new(),
             metadata: HashMap::new(),
+            overrides: ModelOverrides::default(),
+            capture_prompts: false,
         }
     }
 
@@ -75,6 +105,17 @@ impl ExecutionContext {
         self
     }
 
+    /// Attach a [`PromptTrace`](crate::PromptTrace) to the execution result
+    pub fn with_prompt_trace(mut self) -> Self {
+        self.capture_prompts = true;
+        self
+    }
+
+    pub fn with_overrides(mut self, overrides: ModelOverrides) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
     pub fn add_metadata(&mut self, key: impl Into<String>, value: impl Into<String>) {
         self.metadata.insert(key.into(), value.into());
     }
@@ -0,0 +1,409 @@
+//! Rolling LLM cost totals and threshold alerts
+//!
+//! [`CostTracker`] is an [`ExecutorMiddleware`] that prices every completed
+//! call with [`TokenUsage::estimated_cost`](crate::llm::TokenUsage::estimated_cost)
+//! and keeps the spend and token usage of the last `window_seconds`, overall
+//! and per agent, workflow and provider. Agents are attributed to a workflow
+//! with [`CostTracker::assign_workflow`]. When a total crosses its threshold
+//! the alert handler is called once; the alert re-arms after the total falls
+//! back below the threshold as old calls leave the window.
+
+use crate::llm::{LlmRequest, LlmResponse, TokenUsage};
+use crate::middleware::ExecutorMiddleware;
+use agentic_core::{Agent, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// Length of the rolling window when none is configured
+pub const DEFAULT_COST_WINDOW_SECONDS: u64 = 3600;
+
+/// Called with each alert as it fires
+pub type CostAlertHandler = Arc<dyn Fn(&CostAlert) + Send + Sync>;
+
+/// Window and thresholds for a [`CostTracker`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostAlertConfig {
+    pub window_seconds: u64,
+    /// Alert when the spend of all agents together exceeds this (USD)
+    pub global_threshold_usd: Option<f64>,
+    /// Alert when any single agent's spend exceeds this (USD)
+    pub agent_threshold_usd: Option<f64>,
+}
+
+impl Default for CostAlertConfig {
+    fn default() -> Self {
+        Self {
+            window_seconds: DEFAULT_COST_WINDOW_SECONDS,
+            global_threshold_usd: None,
+            agent_threshold_usd: None,
+        }
+    }
+}
+
+impl CostAlertConfig {
+    /// Read `COST_WINDOW_SECONDS`, `COST_ALERT_THRESHOLD_USD` and
+    /// `COST_ALERT_AGENT_THRESHOLD_USD`; thresholds are off when unset
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(key: &str) -> Option<T> {
+            std::env::var(key).ok().and_then(|v| v.parse().ok())
+        }
+
+        Self {
+            window_seconds: var("COST_WINDOW_SECONDS").unwrap_or(DEFAULT_COST_WINDOW_SECONDS),
+            global_threshold_usd: var("COST_ALERT_THRESHOLD_USD"),
+            agent_threshold_usd: var("COST_ALERT_AGENT_THRESHOLD_USD"),
+        }
+    }
+}
+
+/// A rolling total went over its threshold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostAlert {
+    /// Agent whose spend crossed the per-agent threshold; `None` for the global one
+    pub agent_id: Option<String>,
+    pub window_cost_usd: f64,
+    pub threshold_usd: f64,
+    pub window_seconds: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Spend and tokens of a group of calls
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub calls: u64,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub cost_usd: f64,
+}
+
+impl UsageTotals {
+    fn add(&mut self, charge: &Charge) {
+        self.calls += 1;
+        self.prompt_tokens += charge.prompt_tokens;
+        self.completion_tokens += charge.completion_tokens;
+        self.cost_usd += charge.cost_usd;
+    }
+}
+
+/// Spend inside the current window, plus everything since start-up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostTotals {
+    pub window_seconds: u64,
+    pub window_cost_usd: f64,
+    pub window_cost_by_agent: HashMap<String, f64>,
+    #[serde(default)]
+    pub window_usage: UsageTotals,
+    #[serde(default)]
+    pub window_usage_by_agent: HashMap<String, UsageTotals>,
+    /// Calls of agents assigned to a workflow, by workflow id
+    #[serde(default)]
+    pub window_usage_by_workflow: HashMap<String, UsageTotals>,
+    /// By provider name; models no provider is known for count as `other`
+    #[serde(default)]
+    pub window_usage_by_provider: HashMap<String, UsageTotals>,
+    pub lifetime_cost_usd: f64,
+    pub global_threshold_usd: Option<f64>,
+    pub agent_threshold_usd: Option<f64>,
+}
+
+/// One recorded call
+#[derive(Debug, Clone, Default)]
+struct Charge {
+    agent_id: String,
+    workflow_id: Option<String>,
+    provider: String,
+    prompt_tokens: usize,
+    completion_tokens: usize,
+    cost_usd: f64,
+}
+
+#[derive(Debug, Default)]
+struct Ledger {
+    /// When and what of every call still inside the window
+    entries: VecDeque<(DateTime<Utc>, Charge)>,
+    lifetime_usd: f64,
+    global_alerted: bool,
+    agents_alerted: HashSet<String>,
+    /// Workflow each agent's calls are attributed to
+    workflows: HashMap<String, String>,
+}
+
+impl Ledger {
+    fn prune(&mut self, cutoff: DateTime<Utc>) {
+        while self.entries.front().is_some_and(|(at, _)| *at < cutoff) {
+            self.entries.pop_front();
+        }
+    }
+
+    fn window_total(&self) -> f64 {
+        self.entries.iter().map(|(_, charge)| charge.cost_usd).sum()
+    }
+
+    fn agent_total(&self, agent_id: &str) -> f64 {
+        self.entries
+            .iter()
+            .filter(|(_, charge)| charge.agent_id == agent_id)
+            .map(|(_, charge)| charge.cost_usd)
+            .sum()
+    }
+}
+
+/// Accumulates estimated LLM cost and raises [`CostAlert`]s; clones share totals
+#[derive(Clone)]
+pub struct CostTracker {
+    ledger: Arc<Mutex<Ledger>>,
+    config: CostAlertConfig,
+    on_alert: Option<CostAlertHandler>,
+}
+
+impl CostTracker {
+    pub fn new(config: CostAlertConfig) -> Self {
+        Self {
+            ledger: Arc::new(Mutex::new(Ledger::default())),
+            config,
+            on_alert: None,
+        }
+    }
+
+    /// Call `handler` for every alert (it runs on the executing task, so keep it short)
+    pub fn with_alert_handler(mut self, handler: impl Fn(&CostAlert) + Send + Sync + 'static) -> Self {
+        self.on_alert = Some(Arc::new(handler));
+        self
+    }
+
+    pub fn config(&self) -> &CostAlertConfig {
+        &self.config
+    }
+
+    /// Attribute `agent_id`'s calls from now on to `workflow_id`
+    pub fn assign_workflow(&self, agent_id: impl Into<String>, workflow_id: impl Into<String>) {
+        self.ledger.lock().unwrap().workflows.insert(agent_id.into(), workflow_id.into());
+    }
+
+    /// Stop attributing `agent_id`'s calls to a workflow, e.g. once it is deleted
+    pub fn unassign_agent(&self, agent_id: &str) {
+        self.ledger.lock().unwrap().workflows.remove(agent_id);
+    }
+
+    /// Add `cost_usd` spent by `agent_id`, returning the alerts it raised
+    pub fn record(&self, agent_id: &str, cost_usd: f64) -> Vec<CostAlert> {
+        self.record_at(agent_id, cost_usd, Utc::now())
+    }
+
+    /// Add a call of `agent_id` to `model` that used `usage`, priced at list
+    /// prices; returns the alerts it raised
+    pub fn record_call(&self, agent_id: &str, model: &str, usage: &TokenUsage) -> Vec<CostAlert> {
+        let charge = Charge {
+            agent_id: agent_id.to_string(),
+            workflow_id: None,
+            provider: agentic_core::model_catalog::provider_for_model(model).unwrap_or("other").to_string(),
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            cost_usd: usage.estimated_cost(model),
+        };
+        self.record_charge(charge, Utc::now())
+    }
+
+    fn record_at(&self, agent_id: &str, cost_usd: f64, now: DateTime<Utc>) -> Vec<CostAlert> {
+        let charge =
+            Charge { agent_id: agent_id.to_string(), provider: "other".to_string(), cost_usd, ..Default::default() };
+        self.record_charge(charge, now)
+    }
+
+    fn record_charge(&self, mut charge: Charge, now: DateTime<Utc>) -> Vec<CostAlert> {
+        let window_seconds = self.config.window_seconds;
+        let agent_id = charge.agent_id.clone();
+        let agent_id = agent_id.as_str();
+        let mut alerts = Vec::new();
+        {
+            let mut ledger = self.ledger.lock().unwrap();
+            ledger.prune(now - Duration::seconds(window_seconds as i64));
+            charge.workflow_id = ledger.workflows.get(agent_id).cloned();
+            ledger.lifetime_usd += charge.cost_usd;
+            ledger.entries.push_back((now, charge));
+
+            let alert = |agent_id: Option<&str>, total: f64, threshold: f64| CostAlert {
+                agent_id: agent_id.map(String::from),
+                window_cost_usd: total,
+                threshold_usd: threshold,
+                window_seconds,
+                timestamp: now,
+            };
+
+            if let Some(threshold) = self.config.global_threshold_usd {
+                let total = ledger.window_total();
+                if total <= threshold {
+                    ledger.global_alerted = false;
+                } else if !ledger.global_alerted {
+                    ledger.global_alerted = true;
+                    alerts.push(alert(None, total, threshold));
+                }
+            }
+
+            if let Some(threshold) = self.config.agent_threshold_usd {
+                let total = ledger.agent_total(agent_id);
+                if total <= threshold {
+                    ledger.agents_alerted.remove(agent_id);
+                } else if ledger.agents_alerted.insert(agent_id.to_string()) {
+                    alerts.push(alert(Some(agent_id), total, threshold));
+                }
+            }
+        }
+
+        for alert in &alerts {
+            warn!(
+                agent_id = alert.agent_id.as_deref().unwrap_or("all"),
+                cost_usd = alert.window_cost_usd,
+                threshold_usd = alert.threshold_usd,
+                "LLM cost threshold crossed"
+            );
+            if let Some(handler) = &self.on_alert {
+                handler(alert);
+            }
+        }
+        alerts
+    }
+
+    /// Rolling totals as of now
+    pub fn totals(&self) -> CostTotals {
+        let mut ledger = self.ledger.lock().unwrap();
+        ledger.prune(Utc::now() - Duration::seconds(self.config.window_seconds as i64));
+
+        let mut usage = UsageTotals::default();
+        let mut by_agent: HashMap<String, UsageTotals> = HashMap::new();
+        let mut by_workflow: HashMap<String, UsageTotals> = HashMap::new();
+        let mut by_provider: HashMap<String, UsageTotals> = HashMap::new();
+        for (_, charge) in &ledger.entries {
+            usage.add(charge);
+            by_agent.entry(charge.agent_id.clone()).or_default().add(charge);
+            by_provider.entry(charge.provider.clone()).or_default().add(charge);
+            if let Some(workflow_id) = &charge.workflow_id {
+                by_workflow.entry(workflow_id.clone()).or_default().add(charge);
+            }
+        }
+
+        CostTotals {
+            window_seconds: self.config.window_seconds,
+            window_cost_usd: ledger.window_total(),
+            window_cost_by_agent: by_agent.iter().map(|(agent, usage)| (agent.clone(), usage.cost_usd)).collect(),
+            window_usage: usage,
+            window_usage_by_agent: by_agent,
+            window_usage_by_workflow: by_workflow,
+            window_usage_by_provider: by_provider,
+            lifetime_cost_usd: ledger.lifetime_usd,
+            global_threshold_usd: self.config.global_threshold_usd,
+            agent_threshold_usd: self.config.agent_threshold_usd,
+        }
+    }
+}
+
+impl Default for CostTracker {
+    fn default() -> Self {
+        Self::new(CostAlertConfig::default())
+    }
+}
+
+#[async_trait]
+impl ExecutorMiddleware for CostTracker {
+    fn name(&self) -> &str {
+        "cost"
+    }
+
+    async fn after(&self, agent: &Agent, _request: &LlmRequest, response: &mut LlmResponse) -> Result<()> {
+        if response.usage.total_tokens > 0 || response.estimated_cost() > 0.0 {
+            self.record_call(&agent.id.to_string(), &response.model, &response.usage);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(global: Option<f64>, agent: Option<f64>) -> (CostTracker, Arc<Mutex<Vec<CostAlert>>>) {
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let sink = fired.clone();
+        let config = CostAlertConfig { window_seconds: 60, global_threshold_usd: global, agent_threshold_usd: agent };
+        let tracker = CostTracker::new(config).with_alert_handler(move |alert| sink.lock().unwrap().push(alert.clone()));
+        (tracker, fired)
+    }
+
+    #[test]
+    fn test_crossing_threshold_fires_one_alert() {
+        let (tracker, fired) = tracker(Some(1.0), None);
+
+        for _ in 0..5 {
+            tracker.record("agent-a", 0.4);
+        }
+
+        let fired = fired.lock().unwrap();
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].agent_id, None);
+        assert!((fired[0].window_cost_usd - 1.2).abs() < 1e-9);
+
+        let totals = tracker.totals();
+        assert!((totals.window_cost_usd - 2.0).abs() < 1e-9);
+        assert!((totals.window_cost_by_agent["agent-a"] - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_usage_is_split_by_agent_workflow_and_provider() {
+        let (tracker, _) = tracker(None, None);
+        let usage = TokenUsage { prompt_tokens: 1_000_000, completion_tokens: 0, total_tokens: 1_000_000 };
+        tracker.assign_workflow("agent-a", "wf-1");
+
+        tracker.record_call("agent-a", "claude-3-5-haiku-20241022", &usage);
+        tracker.record_call("agent-a", "gpt-4o-mini", &usage);
+        tracker.record_call("agent-b", "command-r", &usage);
+
+        let totals = tracker.totals();
+        assert_eq!(totals.window_usage.calls, 3);
+        assert_eq!(totals.window_usage.prompt_tokens, 3_000_000);
+        let workflow = &totals.window_usage_by_workflow["wf-1"];
+        assert_eq!(workflow.calls, 2);
+        assert!((workflow.cost_usd - 0.95).abs() < 1e-9);
+        assert!((totals.window_usage_by_provider["anthropic"].cost_usd - 0.8).abs() < 1e-9);
+        assert!((totals.window_usage_by_provider["openai"].cost_usd - 0.15).abs() < 1e-9);
+        assert_eq!(totals.window_usage_by_provider["other"].cost_usd, 0.0);
+        assert_eq!(totals.window_usage_by_agent["agent-b"].calls, 1);
+        assert!((totals.window_cost_by_agent["agent-a"] - 0.95).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unassigned_agent_is_no_longer_attributed() {
+        let (tracker, _) = tracker(None, None);
+        let usage = TokenUsage { prompt_tokens: 1_000, completion_tokens: 0, total_tokens: 1_000 };
+        tracker.assign_workflow("agent-a", "wf-1");
+
+        tracker.record_call("agent-a", "gpt-4o-mini", &usage);
+        tracker.unassign_agent("agent-a");
+        tracker.record_call("agent-a", "gpt-4o-mini", &usage);
+
+        let totals = tracker.totals();
+        assert_eq!(totals.window_usage_by_workflow["wf-1"].calls, 1);
+        assert_eq!(totals.window_usage.calls, 2);
+    }
+
+    #[test]
+    fn test_alert_rearms_after_window_passes() {
+        let (tracker, fired) = tracker(None, Some(0.5));
+        let start = Utc::now();
+
+        tracker.record_at("agent-a", 0.6, start);
+        tracker.record_at("agent-b", 0.1, start);
+        tracker.record_at("agent-a", 0.6, start + Duration::seconds(30));
+        assert_eq!(fired.lock().unwrap().len(), 1);
+
+        // Both earlier agent-a calls have left the window: it drops below, then crosses again
+        tracker.record_at("agent-a", 0.1, start + Duration::seconds(100));
+        tracker.record_at("agent-a", 0.6, start + Duration::seconds(101));
+        let fired = fired.lock().unwrap();
+        assert_eq!(fired.len(), 2);
+        assert_eq!(fired[1].agent_id.as_deref(), Some("agent-a"));
+    }
+}
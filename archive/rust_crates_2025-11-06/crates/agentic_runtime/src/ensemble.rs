@@ -0,0 +1,311 @@
+//! Polling several models and combining their answers
+//!
+//! An [`EnsembleClient`] is an [`LlmClient`] that sends each request to all
+//! of its members at once and merges the replies with an
+//! [`EnsembleStrategy`]. It suits high-stakes, short answers such as a
+//! validation recommendation, where one model's mistake shouldn't decide
+//! the outcome. Every member's reply is kept in the combined response's
+//! metadata under [`META_ENSEMBLE_MEMBERS`].
+
+use crate::llm::{LlmClient, LlmError, LlmProvider, LlmRequest, LlmResponse, Message, Result, TokenUsage};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tracing::warn;
+
+/// Metadata key holding the members' replies, as a JSON array of [`MemberReply`]
+pub const META_ENSEMBLE_MEMBERS: &str = "ensemble_members";
+
+/// Metadata key naming the strategy that combined the replies
+pub const META_ENSEMBLE_STRATEGY: &str = "ensemble_strategy";
+
+/// How the members' replies become one answer
+#[derive(Clone)]
+pub enum EnsembleStrategy {
+    /// The answer with the largest total member weight, compared ignoring
+    /// case, surrounding whitespace and trailing punctuation; ties go to the
+    /// earliest member. For classifications ("approve" / "reject").
+    Majority,
+    /// Weighted mean of the replies that parse as a number; for scores
+    Average,
+    /// `judge` reads the task and every reply and writes the final answer;
+    /// for free text
+    Judge { client: Arc<dyn LlmClient>, model: String },
+}
+
+impl EnsembleStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EnsembleStrategy::Majority => "majority",
+            EnsembleStrategy::Average => "average",
+            EnsembleStrategy::Judge { .. } => "judge",
+        }
+    }
+}
+
+/// One member's reply, as recorded in the combined response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberReply {
+    pub model: String,
+    pub weight: f64,
+    /// `None` when the member failed
+    pub content: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+struct Member {
+    client: Arc<dyn LlmClient>,
+    /// Sent instead of the request's model, for members of other providers
+    model: Option<String>,
+    weight: f64,
+}
+
+/// Client that polls every member and combines the replies
+pub struct EnsembleClient {
+    members: Vec<Member>,
+    strategy: EnsembleStrategy,
+}
+
+impl EnsembleClient {
+    pub fn new(strategy: EnsembleStrategy) -> Self {
+        Self { members: Vec::new(), strategy }
+    }
+
+    /// Add a member answering with the request's own model
+    pub fn with_member(mut self, client: Arc<dyn LlmClient>, weight: f64) -> Self {
+        self.members.push(Member { client, model: None, weight: weight.max(0.0) });
+        self
+    }
+
+    /// Add a member that always answers with `model`
+    pub fn with_member_model(mut self, client: Arc<dyn LlmClient>, model: impl Into<String>, weight: f64) -> Self {
+        self.members.push(Member { client, model: Some(model.into()), weight: weight.max(0.0) });
+        self
+    }
+
+    /// Send `request` to every member; replies come back in member order
+    async fn poll(&self, request: &LlmRequest) -> Vec<(MemberReply, Option<LlmResponse>)> {
+        // Dropping the set aborts the member calls, so cancelling the
+        // ensemble call cancels them too
+        let mut calls = JoinSet::new();
+        for (i, member) in self.members.iter().enumerate() {
+            let mut request = request.clone();
+            if let Some(model) = &member.model {
+                request.model = model.clone();
+            }
+            let client = member.client.clone();
+            calls.spawn(async move { (i, request.model.clone(), client.complete(request).await) });
+        }
+
+        let mut replies: Vec<Option<(MemberReply, Option<LlmResponse>)>> = vec![None; self.members.len()];
+        while let Some(joined) = calls.join_next().await {
+            let Ok((i, model, result)) = joined else {
+                continue;
+            };
+            let weight = self.members[i].weight;
+            replies[i] = Some(match result {
+                Ok(response) => {
+                    let reply = MemberReply { model, weight, content: Some(response.content.clone()), error: None };
+                    (reply, Some(response))
+                }
+                Err(e) => {
+                    warn!(model = %model, "Ensemble member failed: {}", e);
+                    (MemberReply { model, weight, content: None, error: Some(e.to_string()) }, None)
+                }
+            });
+        }
+        replies.into_iter().flatten().collect()
+    }
+
+    async fn combine(&self, request: &LlmRequest, answers: &[(&str, f64)]) -> Result<(String, Option<LlmResponse>)> {
+        match &self.strategy {
+            EnsembleStrategy::Majority => Ok((majority(answers).to_string(), None)),
+            EnsembleStrategy::Average => {
+                let numbers: Vec<(f64, f64)> = answers
+                    .iter()
+                    .filter_map(|(content, weight)| first_number(content).map(|n| (n, *weight)))
+                    .collect();
+                let total_weight: f64 = numbers.iter().map(|(_, w)| w).sum();
+                if total_weight <= 0.0 {
+                    return Err(LlmError::ApiError("No ensemble member answered with a number".to_string()));
+                }
+                let mean = numbers.iter().map(|(n, w)| n * w).sum::<f64>() / total_weight;
+                Ok((mean.to_string(), None))
+            }
+            EnsembleStrategy::Judge { client, model } => {
+                let mut judge_request = request.clone();
+                judge_request.model = model.clone();
+                judge_request.messages.push(Message::user(judge_prompt(answers)));
+                let verdict = client.complete(judge_request).await?;
+                Ok((verdict.content.clone(), Some(verdict)))
+            }
+        }
+    }
+}
+
+/// Most weighted answer; ties go to the earliest member
+fn majority<'a>(answers: &[(&'a str, f64)]) -> &'a str {
+    let normalize = |s: &str| s.trim().trim_end_matches(['.', '!']).trim().to_lowercase();
+    let mut votes: HashMap<String, f64> = HashMap::new();
+    for (content, weight) in answers {
+        *votes.entry(normalize(content)).or_default() += weight;
+    }
+    let mut best: Option<(&str, f64)> = None;
+    for (content, _) in answers {
+        let score = votes[&normalize(content)];
+        if best.is_none_or(|(_, top)| score > top) {
+            best = Some((content, score));
+        }
+    }
+    best.map(|(content, _)| content.trim()).unwrap_or_default()
+}
+
+/// First number in `text`, e.g. `7.5` in "Score: 7.5/10"
+fn first_number(text: &str) -> Option<f64> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.' || c == '-'))
+        .filter(|token| token.chars().any(|c| c.is_ascii_digit()))
+        .find_map(|token| token.trim_end_matches('.').parse().ok())
+}
+
+fn judge_prompt(answers: &[(&str, f64)]) -> String {
+    let mut prompt = format!("{} assistants answered the request above independently:\n\n", answers.len());
+    for (i, (content, _)) in answers.iter().enumerate() {
+        prompt.push_str(&format!("--- Answer {} ---\n{}\n\n", i + 1, content.trim()));
+    }
+    prompt.push_str(
+        "Judge which answer is most correct and reply with the final answer only, \
+         combining the answers where they complement each other.",
+    );
+    prompt
+}
+
+#[async_trait]
+impl LlmClient for EnsembleClient {
+    fn provider(&self) -> LlmProvider {
+        self.members.first().map_or(LlmProvider::Mock, |m| m.client.provider())
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        if self.members.is_empty() {
+            return Err(LlmError::Unsupported("Ensemble has no members".to_string()));
+        }
+
+        let polled = self.poll(&request).await;
+        let mut usage = TokenUsage::default();
+        let mut answers = Vec::new();
+        for (reply, response) in &polled {
+            if let (Some(content), Some(response)) = (&reply.content, response) {
+                answers.push((content.as_str(), reply.weight));
+                usage.prompt_tokens += response.usage.prompt_tokens;
+                usage.completion_tokens += response.usage.completion_tokens;
+                usage.total_tokens += response.usage.total_tokens;
+            }
+        }
+        if answers.is_empty() {
+            let errors: Vec<String> = polled.into_iter().filter_map(|(reply, _)| reply.error).collect();
+            return Err(LlmError::ApiError(format!("Every ensemble member failed: {}", errors.join("; "))));
+        }
+
+        let (content, judged) = self.combine(&request, &answers).await?;
+        if let Some(verdict) = &judged {
+            usage.prompt_tokens += verdict.usage.prompt_tokens;
+            usage.completion_tokens += verdict.usage.completion_tokens;
+            usage.total_tokens += verdict.usage.total_tokens;
+        }
+
+        let replies: Vec<MemberReply> = polled.into_iter().map(|(reply, _)| reply).collect();
+        let mut metadata = HashMap::new();
+        metadata.insert(META_ENSEMBLE_STRATEGY.to_string(), self.strategy.as_str().to_string());
+        metadata.insert(
+            META_ENSEMBLE_MEMBERS.to_string(),
+            serde_json::to_string(&replies).map_err(|e| LlmError::SerializationError(e.to_string()))?,
+        );
+
+        Ok(LlmResponse {
+            content,
+            model: judged.map_or_else(|| request.model.clone(), |verdict| verdict.model),
+            usage,
+            finish_reason: "ensemble".to_string(),
+            truncated: false,
+            metadata,
+        })
+    }
+
+    fn supports_model(&self, model: &str) -> bool {
+        self.members.iter().any(|m| m.model.is_some() || m.client.supports_model(model))
+    }
+
+    fn available_models(&self) -> Vec<String> {
+        let mut models: Vec<String> = self.members.iter().flat_map(|m| m.client.available_models()).collect();
+        models.sort();
+        models.dedup();
+        models
+    }
+
+    fn is_mock(&self) -> bool {
+        self.members.iter().all(|m| m.client.is_mock())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::MockLlmClient;
+
+    fn member(reply: &str) -> Arc<dyn LlmClient> {
+        Arc::new(MockLlmClient::new(reply))
+    }
+
+    fn request() -> LlmRequest {
+        LlmRequest::builder().model("mock-model").user("Approve this opportunity?").build().unwrap()
+    }
+
+    fn replies(response: &LlmResponse) -> Vec<MemberReply> {
+        serde_json::from_str(&response.metadata[META_ENSEMBLE_MEMBERS]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_majority_picks_plurality_answer() {
+        let ensemble = EnsembleClient::new(EnsembleStrategy::Majority)
+            .with_member(member("Reject"), 1.0)
+            .with_member(member("Approve"), 1.0)
+            .with_member(member("approve."), 1.0);
+
+        let response = ensemble.complete(request()).await.unwrap();
+
+        assert_eq!(response.content, "Approve");
+        assert_eq!(response.metadata[META_ENSEMBLE_STRATEGY], "majority");
+        let contents: Vec<Option<String>> = replies(&response).into_iter().map(|r| r.content).collect();
+        assert_eq!(contents, [Some("Reject".into()), Some("Approve".into()), Some("approve.".into())]);
+        assert_eq!(response.usage.total_tokens, 3 * 30);
+
+        // A heavier member outvotes the other two
+        let weighted = EnsembleClient::new(EnsembleStrategy::Majority)
+            .with_member(member("Reject"), 3.0)
+            .with_member(member("Approve"), 1.0)
+            .with_member(member("Approve"), 1.0);
+        assert_eq!(weighted.complete(request()).await.unwrap().content, "Reject");
+    }
+
+    #[tokio::test]
+    async fn test_average_and_judge() {
+        let average = EnsembleClient::new(EnsembleStrategy::Average)
+            .with_member(member("Score: 6"), 1.0)
+            .with_member(member("9/10"), 2.0)
+            .with_member(member("no idea"), 1.0);
+        assert_eq!(average.complete(request()).await.unwrap().content, "8");
+
+        let judge = EnsembleClient::new(EnsembleStrategy::Judge {
+            client: member("Approve, with caveats"),
+            model: "judge-model".to_string(),
+        })
+        .with_member(member("Approve"), 1.0)
+        .with_member_model(member("Reject"), "other-model", 1.0);
+        let response = judge.complete(request()).await.unwrap();
+        assert_eq!(response.content, "Approve, with caveats");
+        assert_eq!(replies(&response)[1].model, "other-model");
+    }
+}
@@ -1,16 +1,27 @@
 //! Agent executor - runs agents and manages their lifecycle
 
-use crate::context::ExecutionContext;
-use crate::llm::{LlmClient, LlmRequest, LlmResponse, Message};
-use agentic_core::{Agent, AgentStatus, Result, Error};
+use crate::backoff::{BackoffStrategy, Exponential};
+use crate::config::ExecutionConfig;
+use crate::context::{ExecutionContext, ModelOverrides};
+use crate::llm::{LlmClient, LlmError, LlmProvider, LlmRequest, LlmResponse, LlmStream, Message, TokenUsage};
+use crate::middleware::ExecutorMiddleware;
+use crate::prompt_trace::PromptTrace;
+use crate::result_cache::ExecutionCache;
+use crate::sandbox::{ExecutionSandbox, SandboxLimits, TraceStep};
+use agentic_core::{Agent, AgentEnv, AgentStatus, Result, Error};
 use agentic_domain::learning::{LearningEvent, LearningType};
 use agentic_learning::LearningEngine;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use std::time::Instant;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use tracing::{info, warn, error, instrument};
 
+/// Wait before the first execution retry when none is configured
+pub const DEFAULT_RETRY_BACKOFF: Duration = crate::backoff::DEFAULT_BACKOFF_BASE;
+
 /// Result of agent execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecutionResult {
@@ -20,6 +31,24 @@ pub struct ExecutionResult {
     pub tokens_used: usize,
     pub execution_time_ms: u64,
     pub learning_events: Vec<LearningEvent>,
+    /// Model that actually served the request
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Whether the output came from a mock client rather than a real provider
+    #[serde(default)]
+    pub is_mock: bool,
+    /// LLM and tool calls made during the execution
+    #[serde(default)]
+    pub trace: Vec<TraceStep>,
+    /// Prompts sent, when the context asked for them
+    #[serde(default)]
+    pub prompt_trace: Option<PromptTrace>,
+    /// Replayed from the executor's result cache instead of calling the LLM
+    #[serde(default)]
+    pub from_cache: bool,
+    /// Reproducibility details of the final LLM response (see [`LlmResponse::metadata`])
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
 }
 
 impl ExecutionResult {
@@ -31,6 +60,12 @@ impl ExecutionResult {
             tokens_used: tokens,
             execution_time_ms: time_ms,
             learning_events: Vec::new(),
+            model: None,
+            is_mock: false,
+            trace: Vec::new(),
+            prompt_trace: None,
+            from_cache: false,
+            metadata: HashMap::new(),
         }
     }
 
@@ -42,6 +77,12 @@ impl ExecutionResult {
             tokens_used: 0,
             execution_time_ms: time_ms,
             learning_events: Vec::new(),
+            model: None,
+            is_mock: false,
+            trace: Vec::new(),
+            prompt_trace: None,
+            from_cache: false,
+            metadata: HashMap::new(),
         }
     }
 
@@ -49,6 +90,31 @@ impl ExecutionResult {
         self.learning_events.push(event);
         self
     }
+
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    pub fn with_mock(mut self, is_mock: bool) -> Self {
+        self.is_mock = is_mock;
+        self
+    }
+
+    pub fn with_trace(mut self, trace: Vec<TraceStep>) -> Self {
+        self.trace = trace;
+        self
+    }
+
+    pub fn with_prompt_trace(mut self, prompt_trace: Option<PromptTrace>) -> Self {
+        self.prompt_trace = prompt_trace;
+        self
+    }
+
+    pub fn with_metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.metadata = metadata;
+        self
+    }
 }
 
 /// Trait for executing agents
@@ -73,13 +139,234 @@ pub trait AgentExecutor: Send + Sync {
 }
 
 /// Default executor implementation using LLM clients
+///
+/// Every `execute` runs in an [`ExecutionSandbox`]; tripping one of its
+/// limits fails the call with `Error::SandboxLimitExceeded`. The LLM call is
+/// wrapped by the [`ExecutorMiddleware`] chain, and an error from any hook
+/// is returned as is, except `Error::Retryable`: that re-runs the LLM call
+/// and `after` hooks, with backoff, up to `max_execution_retries` times.
+///
+/// With [`DefaultExecutor::with_result_cache`] cacheable requests are first
+/// looked up in an [`ExecutionCache`]; a hit skips the LLM entirely and is
+/// returned with `from_cache` set.
+///
+/// The LLM client can be replaced with [`DefaultExecutor::set_client`] while
+/// the executor is shared; calls already in flight finish on the old client.
 pub struct DefaultExecutor {
-    llm_client: Arc<dyn LlmClient>,
+    llm_client: RwLock<Arc<dyn LlmClient>>,
+    sandbox_limits: SandboxLimits,
+    middleware: Vec<Arc<dyn ExecutorMiddleware>>,
+    max_execution_retries: u32,
+    backoff: Arc<dyn BackoffStrategy>,
+    result_cache: Option<Arc<ExecutionCache>>,
 }
 
 impl DefaultExecutor {
     pub fn new(llm_client: Arc<dyn LlmClient>) -> Self {
-        Self { llm_client }
+        Self {
+            llm_client: RwLock::new(llm_client),
+            sandbox_limits: SandboxLimits::default(),
+            middleware: Vec::new(),
+            max_execution_retries: ExecutionConfig::default().max_execution_retries,
+            backoff: Arc::new(Exponential::new(DEFAULT_RETRY_BACKOFF)),
+            result_cache: None,
+        }
+    }
+
+    /// Append `middleware` to the end of the chain
+    pub fn with_middleware(mut self, middleware: Arc<dyn ExecutorMiddleware>) -> Self {
+        self.middleware.push(middleware);
+        self
+    }
+
+    /// Take sandbox limits and execution retries from `config`
+    pub fn with_execution_config(mut self, config: &ExecutionConfig) -> Self {
+        self.sandbox_limits = SandboxLimits::from_config(config);
+        self.max_execution_retries = config.max_execution_retries;
+        self
+    }
+
+    /// Re-run an execution up to `max` times after an `Error::Retryable`,
+    /// waiting as the backoff strategy says (see [`with_backoff`](Self::with_backoff))
+    pub fn with_execution_retries(mut self, max: u32) -> Self {
+        self.max_execution_retries = max;
+        self
+    }
+
+    /// Wait between execution retries as `backoff` says; retrying also stops
+    /// once it returns `None`
+    pub fn with_backoff(mut self, backoff: Arc<dyn BackoffStrategy>) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Answer repeated cacheable executions from `cache`
+    pub fn with_result_cache(mut self, cache: Arc<ExecutionCache>) -> Self {
+        self.result_cache = Some(cache);
+        self
+    }
+
+    /// Result cache in use, if any
+    pub fn result_cache(&self) -> Option<&Arc<ExecutionCache>> {
+        self.result_cache.as_ref()
+    }
+
+    pub fn with_sandbox_limits(mut self, limits: SandboxLimits) -> Self {
+        self.sandbox_limits = limits;
+        self
+    }
+
+    /// A fresh sandbox with this executor's limits, for work such as tool
+    /// invocations that runs outside `execute`
+    pub fn sandbox(&self) -> ExecutionSandbox {
+        ExecutionSandbox::new(self.sandbox_limits.clone())
+    }
+
+    /// Run a tool call in a fresh sandbox on behalf of `agent`
+    ///
+    /// `call` gets the secrets the tool authenticates with: the agent's
+    /// [`Agent::env`], or none for a call made on no agent's behalf.
+    pub async fn run_tool<T, F, Fut>(&self, agent: Option<&Agent>, tool: &str, call: F) -> Result<T>
+    where
+        F: FnOnce(AgentEnv) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let env = agent.map(|agent| agent.env.clone()).unwrap_or_default();
+        self.sandbox().run_tool(tool, call(env)).await
+    }
+
+    /// Client used by executions started from now on
+    pub fn client(&self) -> Arc<dyn LlmClient> {
+        self.llm_client.read().unwrap().clone()
+    }
+
+    /// Swap the LLM client without rebuilding the executor
+    pub fn set_client(&self, llm_client: Arc<dyn LlmClient>) {
+        info!("Executor LLM client switched to {}", llm_client.provider());
+        *self.llm_client.write().unwrap() = llm_client;
+    }
+
+    /// Provider behind this executor's LLM client
+    pub fn provider(&self) -> LlmProvider {
+        self.client().provider()
+    }
+
+    /// True when executions are answered by a mock client
+    pub fn is_mock(&self) -> bool {
+        self.client().is_mock()
+    }
+
+    /// Stream the agent's reply to `input`, with `history` as earlier turns
+    ///
+    /// Chat bypasses the sandbox: it is one call, bounded by the caller
+    /// dropping the stream. Middleware `before` hooks run first; `after`
+    /// hooks run once the stream ends (or the caller drops it) on the reply
+    /// streamed so far, with token usage estimated from its length, so
+    /// budgets and cost tracking see chat too. An `after` hook's error
+    /// arrives as the stream's last item.
+    pub async fn stream_chat(&self, agent: &Agent, history: Vec<Message>, input: &str) -> Result<LlmStream> {
+        let mut request = LlmRequest::new(&agent.model)
+            .with_system(self.build_system_prompt(agent))
+            .with_caller(agent.id.to_string());
+        for message in history {
+            request = request.add_message(message);
+        }
+        let mut request = request.add_message(Message::user(input));
+        self.run_before(agent, &mut request).await?;
+
+        let mut deltas = self.client().complete_stream(request.clone()).await?;
+        let middleware = self.middleware.clone();
+        let agent = agent.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(16);
+        tokio::spawn(async move {
+            let mut content = String::new();
+            while let Some(delta) = deltas.recv().await {
+                let Ok(text) = delta else {
+                    // Failed midway: there is no reply to account for
+                    let _ = tx.send(delta).await;
+                    return;
+                };
+                content.push_str(&text);
+                // The caller went away: stop the provider, but account for what it sent
+                if tx.send(Ok(text)).await.is_err() {
+                    break;
+                }
+            }
+            drop(deltas);
+
+            let prompt_tokens = request.estimated_prompt_tokens();
+            let completion_tokens = content.chars().count().div_ceil(4);
+            let mut response = LlmResponse {
+                content,
+                model: request.model.clone(),
+                usage: TokenUsage { prompt_tokens, completion_tokens, total_tokens: prompt_tokens + completion_tokens },
+                finish_reason: "stop".to_string(),
+                truncated: false,
+                metadata: HashMap::new(),
+            };
+            if let Err(e) = run_after(&middleware, &agent, &request, &mut response).await {
+                warn!("Agent {} chat reply rejected by middleware: {}", agent.name, e);
+                let _ = tx.send(Err(LlmError::ApiError(e.to_string()))).await;
+            }
+        });
+        Ok(rx)
+    }
+
+    async fn run_before(&self, agent: &Agent, request: &mut LlmRequest) -> Result<()> {
+        for middleware in &self.middleware {
+            middleware.before(agent, request).await?;
+        }
+        Ok(())
+    }
+
+    async fn run_after(&self, agent: &Agent, request: &LlmRequest, response: &mut LlmResponse) -> Result<()> {
+        run_after(&self.middleware, agent, request, response).await
+    }
+
+    fn should_retry(&self, err: &Error, attempt: u32) -> bool {
+        matches!(err, Error::Retryable(_))
+            && attempt < self.max_execution_retries
+            && self.backoff.next_delay(attempt + 1).is_some()
+    }
+
+    async fn wait_before_retry(&self, agent: &Agent, attempt: u32, err: &Error) {
+        let delay = self.backoff.next_delay(attempt).unwrap_or_default();
+        warn!(
+            "Agent {} attempt {} failed ({}), retrying in {:?}",
+            agent.name, attempt, err, delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+
+    /// Reject overrides the configured client cannot serve
+    fn validate_overrides(&self, client: &dyn LlmClient, overrides: &ModelOverrides) -> Result<()> {
+        if let Some(provider) = &overrides.provider {
+            let requested: LlmProvider = provider.parse()?;
+            let available = client.provider();
+            if requested != available {
+                return Err(Error::CapabilityNotSupported(format!(
+                    "Provider {} is not available (executor uses {})",
+                    requested, available
+                )));
+            }
+        }
+
+        if let Some(model) = &overrides.model {
+            if !client.supports_model(model) {
+                return Err(Error::CapabilityNotSupported(format!("Model {} is not supported", model)));
+            }
+        }
+
+        if let Some(temperature) = overrides.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err(Error::PolicyViolation(format!(
+                    "Temperature {} out of range 0.0-2.0",
+                    temperature
+                )));
+            }
+        }
+
+        Ok(())
     }
 
     fn build_system_prompt(&self, agent: &Agent) -> String {
@@ -125,7 +412,10 @@ impl DefaultExecutor {
 
 #[async_trait]
 impl AgentExecutor for DefaultExecutor {
-    #[instrument(skip(self, agent, context), fields(agent_id = %agent.id, agent_name = %agent.name))]
+    #[instrument(
+        skip(self, agent, context),
+        fields(agent_id = %agent.id, agent_name = %agent.name, from_cache = tracing::field::Empty)
+    )]
     async fn execute(
         &self,
         agent: &mut Agent,
@@ -133,47 +423,118 @@ impl AgentExecutor for DefaultExecutor {
         context: &ExecutionContext,
     ) -> Result<ExecutionResult> {
         info!("Executing agent {} with input: {}", agent.name, input);
+        let client = self.client();
+        self.validate_overrides(client.as_ref(), &context.overrides)?;
         let start = Instant::now();
 
         // Update agent status
         agent.set_status(AgentStatus::Busy);
 
-        // Build LLM request
+        // Build LLM request, applying per-call overrides
+        let overrides = &context.overrides;
         let system_prompt = self.build_system_prompt(agent);
-        let request = LlmRequest::new(&agent.model)
+        let mut request = LlmRequest::new(overrides.model.as_deref().unwrap_or(&agent.model))
             .with_system(system_prompt)
+            .with_caller(agent.id.to_string())
             .add_message(Message::user(input));
 
-        // Execute LLM request
-        match self.llm_client.complete(request).await {
-            Ok(response) => {
-                let execution_time = start.elapsed().as_millis() as u64;
-
-                info!(
-                    "Agent {} completed execution in {}ms, used {} tokens",
-                    agent.name,
-                    execution_time,
-                    response.usage.total_tokens
-                );
-
-                // Update agent metrics
-                agent.record_task_success(execution_time as f64);
-                agent.set_status(AgentStatus::Idle);
-
-                Ok(ExecutionResult::success(
-                    response.content,
-                    response.usage.total_tokens,
-                    execution_time,
-                ))
+        if let Some(temperature) = overrides.temperature {
+            request = request.with_temperature(temperature);
+        }
+        if let Some(max_tokens) = overrides.max_tokens {
+            request = request.with_max_tokens(max_tokens);
+        }
+        request.stop_sequences.extend(overrides.stop_sequences.iter().cloned());
+
+        if let Err(e) = self.run_before(agent, &mut request).await {
+            warn!("Agent {} request rejected by middleware: {}", agent.name, e);
+            agent.set_status(AgentStatus::Idle);
+            return Err(e);
+        }
+
+        if let Some(mut cached) = self.result_cache.as_ref().and_then(|cache| cache.get(&agent.id, &request)) {
+            cached.execution_time_ms = start.elapsed().as_millis() as u64;
+            tracing::Span::current().record("from_cache", true);
+            info!("Agent {} answered from the result cache", agent.name);
+            agent.record_task_success(cached.execution_time_ms as f64);
+            agent.set_status(AgentStatus::Idle);
+            return Ok(cached);
+        }
+
+        // Execute LLM request, re-running it while it fails with a retryable
+        // error; the sandbox limits cover all attempts together
+        let mut prompt_trace = context.capture_prompts.then(|| PromptTrace::new(agent.id));
+        let mut sandbox = self.sandbox();
+        let mut attempt = 0;
+        loop {
+            let outcome = sandbox.complete(client.as_ref(), request.clone()).await;
+            if let Some(trace) = &mut prompt_trace {
+                trace.record(&request, outcome.as_ref().ok());
             }
-            Err(e) => {
-                let execution_time = start.elapsed().as_millis() as u64;
-                error!("Agent {} execution failed: {}", agent.name, e);
+            match outcome {
+                Ok(mut response) => {
+                    if let Err(e) = self.run_after(agent, &request, &mut response).await {
+                        if self.should_retry(&e, attempt) {
+                            attempt += 1;
+                            self.wait_before_retry(agent, attempt, &e).await;
+                            continue;
+                        }
+                        warn!("Agent {} response rejected by middleware: {}", agent.name, e);
+                        agent.record_task_failure();
+                        agent.set_status(AgentStatus::Idle);
+                        return Err(e);
+                    }
+
+                    let execution_time = start.elapsed().as_millis() as u64;
+
+                    info!(
+                        "Agent {} completed execution in {}ms, used {} tokens",
+                        agent.name,
+                        execution_time,
+                        response.usage.total_tokens
+                    );
+
+                    // Update agent metrics
+                    agent.record_task_success(execution_time as f64);
+                    agent.set_status(AgentStatus::Idle);
 
-                agent.record_task_failure();
-                agent.set_status(AgentStatus::Error(e.to_string()));
+                    let result = ExecutionResult::success(
+                        response.content,
+                        response.usage.total_tokens,
+                        execution_time,
+                    )
+                    .with_model(response.model)
+                    .with_mock(self.is_mock())
+                    .with_trace(sandbox.trace().to_vec())
+                    .with_prompt_trace(prompt_trace)
+                    .with_metadata(response.metadata);
+                    if let Some(cache) = &self.result_cache {
+                        cache.insert(&agent.id, &request, &result);
+                    }
+                    return Ok(result);
+                }
+                Err(e) if self.should_retry(&e, attempt) => {
+                    attempt += 1;
+                    self.wait_before_retry(agent, attempt, &e).await;
+                }
+                Err(e @ Error::SandboxLimitExceeded { .. }) => {
+                    error!("Agent {} aborted: {}", agent.name, e);
+                    agent.record_task_failure();
+                    agent.set_status(AgentStatus::Idle);
+                    return Err(e);
+                }
+                Err(e) => {
+                    let execution_time = start.elapsed().as_millis() as u64;
+                    error!("Agent {} execution failed: {}", agent.name, e);
 
-                Ok(ExecutionResult::failure(e.to_string(), execution_time))
+                    agent.record_task_failure();
+                    agent.set_status(AgentStatus::Error(e.to_string()));
+
+                    return Ok(ExecutionResult::failure(e.to_string(), execution_time)
+                        .with_mock(self.is_mock())
+                        .with_trace(sandbox.trace().to_vec())
+                        .with_prompt_trace(prompt_trace));
+                }
             }
         }
     }
@@ -206,12 +567,43 @@ impl AgentExecutor for DefaultExecutor {
     }
 }
 
+/// Every `after` hook of `middleware`, last added first; all of them run,
+/// and the first error is returned
+async fn run_after(
+    middleware: &[Arc<dyn ExecutorMiddleware>],
+    agent: &Agent,
+    request: &LlmRequest,
+    response: &mut LlmResponse,
+) -> Result<()> {
+    let mut first_error = None;
+    for middleware in middleware.iter().rev() {
+        if let Err(e) = middleware.after(agent, request, response).await {
+            warn!(middleware = middleware.name(), "Middleware after hook failed: {}", e);
+            first_error.get_or_insert(e);
+        }
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::llm::MockLlmClient;
+    use crate::backoff::Fixed;
+    use crate::llm::{MessageRole, MockLlmClient};
+    use crate::test_support::ScriptedLlmClient;
     use agentic_core::AgentRole;
 
+    #[tokio::test]
+    async fn test_run_tool_gets_the_agents_secrets() {
+        let executor = DefaultExecutor::new(Arc::new(MockLlmClient::default()));
+        let mut agent = Agent::new("Searcher", "Searches", AgentRole::Worker, "mock-model", "mock");
+        agent.set_secret("SEARCH_API_KEY", "sk-search");
+        let key = |env: AgentEnv| async move { Ok(env.get("SEARCH_API_KEY").map(str::to_string)) };
+
+        assert_eq!(executor.run_tool(Some(&agent), "search", key).await.unwrap().as_deref(), Some("sk-search"));
+        assert_eq!(executor.run_tool(None, "search", key).await.unwrap(), None);
+    }
+
     #[tokio::test]
     async fn test_executor_success() {
         let llm_client = Arc::new(MockLlmClient::new("Test response"));
@@ -231,5 +623,324 @@ mod tests {
         assert!(result.success);
         assert_eq!(result.output, "Test response");
         assert_eq!(agent.metrics.tasks_completed, 1);
+        assert!(result.is_mock);
+        assert_eq!(result.metadata[crate::llm::META_SERVED_MODEL], "mock-model");
+    }
+
+    #[tokio::test]
+    async fn test_prompt_trace_captures_system_and_user_messages() {
+        let executor = DefaultExecutor::new(Arc::new(MockLlmClient::new("Traced response")));
+        let mut agent = Agent::new("Tracer", "Traces prompts", AgentRole::Worker, "mock-model", "mock");
+
+        let plain = ExecutionContext::new(agent.id);
+        let untraced = executor.execute(&mut agent, "hello", &plain).await.unwrap();
+        assert!(untraced.prompt_trace.is_none());
+
+        let context = ExecutionContext::new(agent.id).with_prompt_trace();
+        let result = executor.execute(&mut agent, "Summarize the report", &context).await.unwrap();
+        let trace = result.prompt_trace.unwrap();
+
+        assert_eq!(trace.agent_id, agent.id);
+        assert_eq!(trace.calls.len(), 1);
+        let call = &trace.calls[0];
+        assert_eq!(call.request.model, "mock-model");
+        assert_eq!(call.request.messages.len(), 2);
+        assert_eq!(call.request.messages[0].role, MessageRole::System);
+        assert!(call.request.messages[0].content.contains("You are Tracer"));
+        assert_eq!(call.request.messages[1], Message::user("Summarize the report"));
+        assert_eq!(call.response.as_ref().unwrap().usage.total_tokens, result.tokens_used);
+    }
+
+    #[tokio::test]
+    async fn test_set_client_applies_to_later_executions() {
+        let executor = Arc::new(DefaultExecutor::new(Arc::new(MockLlmClient::new("first client"))));
+        let mut agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+        let context = ExecutionContext::new(agent.id);
+
+        let before = executor.execute(&mut agent, "hi", &context).await.unwrap();
+        executor.set_client(Arc::new(MockLlmClient::new("second client")));
+        let after = executor.execute(&mut agent, "hi", &context).await.unwrap();
+
+        assert_eq!(before.output, "first client");
+        assert_eq!(after.output, "second client");
+    }
+
+    #[tokio::test]
+    async fn test_execute_with_model_overrides() {
+        let llm_client = Arc::new(ScriptedLlmClient::default());
+        let executor = DefaultExecutor::new(llm_client.clone());
+        let mut agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+
+        // Default settings
+        let context = ExecutionContext::new(agent.id);
+        let result = executor.execute(&mut agent, "Test input", &context).await.unwrap();
+        assert_eq!(result.model.as_deref(), Some("mock-model"));
+        assert!(!result.is_mock);
+
+        // Overridden settings for a single call
+        let context = ExecutionContext::new(agent.id).with_overrides(ModelOverrides {
+            model: Some("mock-large".to_string()),
+            provider: Some("mock".to_string()),
+            temperature: Some(0.1),
+            max_tokens: Some(256),
+            stop_sequences: vec!["\n\nHuman:".to_string()],
+        });
+        let result = executor.execute(&mut agent, "Test input", &context).await.unwrap();
+        assert_eq!(result.model.as_deref(), Some("mock-large"));
+
+        let request = llm_client.last_request().unwrap();
+        assert_eq!(request.temperature, Some(0.1));
+        assert_eq!(request.max_tokens, Some(256));
+        assert_eq!(request.stop_sequences, ["\n\nHuman:"]);
+
+        // The agent itself is untouched
+        assert_eq!(agent.model, "mock-model");
+    }
+
+    #[tokio::test]
+    async fn test_stream_chat_sends_history() {
+        let llm_client = Arc::new(ScriptedLlmClient::default());
+        let executor = DefaultExecutor::new(llm_client.clone());
+        let agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+        let history = vec![Message::user("Hello"), Message::assistant("Hi there")];
+
+        let mut stream = executor.stream_chat(&agent, history, "How are you?").await.unwrap();
+
+        // Clients without native streaming send the reply as one delta
+        assert_eq!(stream.recv().await.unwrap().unwrap(), "Mock LLM response");
+        assert!(stream.recv().await.is_none());
+
+        let request = llm_client.last_request().unwrap();
+        // System prompt, two turns of history, then the new message
+        assert_eq!(request.messages.len(), 4);
+        assert_eq!(request.messages[3], Message::user("How are you?"));
+    }
+
+    /// Logs every hook call as "<name>:<phase>"
+    struct RecordingMiddleware {
+        name: &'static str,
+        log: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl ExecutorMiddleware for RecordingMiddleware {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn before(&self, _agent: &Agent, request: &mut LlmRequest) -> Result<()> {
+            self.log.lock().unwrap().push(format!("{}:before", self.name));
+            request.max_tokens = Some(128);
+            Ok(())
+        }
+
+        async fn after(&self, _agent: &Agent, _request: &LlmRequest, response: &mut LlmResponse) -> Result<()> {
+            self.log.lock().unwrap().push(format!("{}:after", self.name));
+            response.content.push_str(" (checked)");
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_chain_wraps_each_call() {
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let llm_client = Arc::new(ScriptedLlmClient::default());
+        let executor = DefaultExecutor::new(llm_client.clone())
+            .with_middleware(Arc::new(RecordingMiddleware { name: "outer", log: log.clone() }))
+            .with_middleware(Arc::new(RecordingMiddleware { name: "inner", log: log.clone() }));
+        let mut agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+
+        for _ in 0..2 {
+            let context = ExecutionContext::new(agent.id);
+            let result = executor.execute(&mut agent, "Test input", &context).await.unwrap();
+            assert_eq!(result.output, "Mock LLM response (checked) (checked)");
+        }
+
+        let calls = log.lock().unwrap().clone();
+        assert_eq!(calls.len(), 8);
+        assert_eq!(calls[..4], ["outer:before", "inner:before", "inner:after", "outer:after"]);
+
+        // Rewrites made in `before` reach the client
+        let request = llm_client.last_request().unwrap();
+        assert_eq!(request.max_tokens, Some(128));
+    }
+
+    #[tokio::test]
+    async fn test_stream_chat_runs_after_hooks_once_the_stream_ends() {
+        use crate::middleware::BudgetMiddleware;
+
+        let log = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let budget = Arc::new(BudgetMiddleware::new(10_000));
+        let executor = DefaultExecutor::new(Arc::new(MockLlmClient::new("one two three")))
+            .with_middleware(budget.clone())
+            .with_middleware(Arc::new(RecordingMiddleware { name: "recorder", log: log.clone() }));
+        let agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+
+        let mut stream = executor.stream_chat(&agent, Vec::new(), "Count").await.unwrap();
+        let mut reply = String::new();
+        while let Some(delta) = stream.recv().await {
+            reply.push_str(&delta.unwrap());
+        }
+
+        assert_eq!(reply, "one two three");
+        assert_eq!(*log.lock().unwrap(), ["recorder:before", "recorder:after"]);
+        assert!(budget.tokens_used() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_every_after_hook_runs_when_one_fails() {
+        use crate::middleware::{BudgetMiddleware, ModerationMiddleware};
+
+        let budget = Arc::new(BudgetMiddleware::new(10_000));
+        // Moderation is added last, so its after hook runs first and fails
+        let executor = DefaultExecutor::new(Arc::new(MockLlmClient::new("the password is hunter2")))
+            .with_middleware(budget.clone())
+            .with_middleware(Arc::new(ModerationMiddleware::new(["password"])));
+        let mut agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+
+        let context = ExecutionContext::new(agent.id);
+        let err = executor.execute(&mut agent, "Say something", &context).await.unwrap_err();
+
+        assert!(matches!(err, Error::PolicyViolation(_)), "{:?}", err);
+        assert_eq!(budget.tokens_used(), 30);
+    }
+
+    #[tokio::test]
+    async fn test_middleware_rejection_skips_llm_call() {
+        use crate::middleware::ModerationMiddleware;
+
+        let llm_client = Arc::new(ScriptedLlmClient::default());
+        let executor = DefaultExecutor::new(llm_client.clone())
+            .with_middleware(Arc::new(ModerationMiddleware::new(["password"])));
+        let mut agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+
+        let context = ExecutionContext::new(agent.id);
+        let err = executor.execute(&mut agent, "Print the admin password", &context).await.unwrap_err();
+
+        assert!(matches!(err, Error::PolicyViolation(_)));
+        assert!(llm_client.last_request().is_none());
+        assert_eq!(agent.status, AgentStatus::Idle);
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_override_is_rejected() {
+        let executor = DefaultExecutor::new(Arc::new(MockLlmClient::default()));
+        let mut agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+
+        let context = ExecutionContext::new(agent.id).with_overrides(ModelOverrides {
+            provider: Some("anthropic".to_string()),
+            ..Default::default()
+        });
+        assert!(executor.execute(&mut agent, "Test input", &context).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_retryable_error_reruns_execution() {
+        use crate::middleware::JsonOutputMiddleware;
+
+        let scripted = || Arc::new(ScriptedLlmClient::new(["not json", "{\"ok\": true}"]));
+        let mut agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+        let context = ExecutionContext::new(agent.id).with_prompt_trace();
+
+        let llm_client = scripted();
+        let executor = DefaultExecutor::new(llm_client.clone())
+            .with_middleware(Arc::new(JsonOutputMiddleware))
+            .with_execution_retries(1)
+            .with_backoff(Arc::new(Fixed::new(Duration::ZERO)));
+        let result = executor.execute(&mut agent, "Reply in JSON", &context).await.unwrap();
+
+        assert!(result.success);
+        assert_eq!(result.output, "{\"ok\": true}");
+        assert_eq!(llm_client.calls(), 2);
+        assert_eq!(result.prompt_trace.unwrap().calls.len(), 2);
+
+        // Without retries the garbage reply is final
+        let executor = DefaultExecutor::new(scripted())
+            .with_middleware(Arc::new(JsonOutputMiddleware))
+            .with_execution_retries(0);
+        let err = executor.execute(&mut agent, "Reply in JSON", &context).await.unwrap_err();
+        assert!(matches!(err, Error::Retryable(_)));
+    }
+
+    #[tokio::test]
+    async fn test_backoff_giving_up_ends_retries() {
+        use crate::middleware::JsonOutputMiddleware;
+
+        let llm_client = Arc::new(ScriptedLlmClient::new(["not json", "still not", "{}"]));
+        let executor = DefaultExecutor::new(llm_client.clone())
+            .with_middleware(Arc::new(JsonOutputMiddleware))
+            .with_backoff(Arc::new(Fixed { delay: Duration::ZERO, max_attempts: Some(1) }))
+            .with_execution_retries(5);
+        let mut agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+        let context = ExecutionContext::new(agent.id);
+
+        let err = executor.execute(&mut agent, "Reply in JSON", &context).await.unwrap_err();
+
+        assert!(matches!(err, Error::Retryable(_)));
+        assert_eq!(llm_client.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_deterministic_execution_is_served_from_cache() {
+        let llm_client = Arc::new(ScriptedLlmClient::new(["first", "second"]));
+        let cache = Arc::new(ExecutionCache::default());
+        let executor = DefaultExecutor::new(llm_client.clone()).with_result_cache(cache.clone());
+        let mut agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+        let context = ExecutionContext::new(agent.id).with_overrides(ModelOverrides {
+            temperature: Some(0.0),
+            ..Default::default()
+        });
+
+        let first = executor.execute(&mut agent, "Classify this", &context).await.unwrap();
+        let second = executor.execute(&mut agent, "Classify this", &context).await.unwrap();
+
+        assert!(!first.from_cache);
+        assert!(second.from_cache);
+        assert_eq!(second.output, "first");
+        assert_eq!(llm_client.calls(), 1);
+        assert_eq!(cache.stats().hits, 1);
+        assert_eq!(agent.metrics.tasks_completed, 2);
+
+        // Sampled executions always reach the LLM
+        let sampled = ExecutionContext::new(agent.id);
+        executor.execute(&mut agent, "Classify this", &sampled).await.unwrap();
+        executor.execute(&mut agent, "Classify this", &sampled).await.unwrap();
+        assert_eq!(llm_client.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_limit_aborts_execution() {
+        let executor = DefaultExecutor::new(Arc::new(MockLlmClient::default()))
+            .with_execution_config(&ExecutionConfig {
+                max_tokens_per_execution: 10,
+                ..Default::default()
+            });
+        let mut agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+
+        let context = ExecutionContext::new(agent.id);
+        let err = executor.execute(&mut agent, "Test input", &context).await.unwrap_err();
+
+        assert!(matches!(&err, Error::SandboxLimitExceeded { limit, .. } if limit == "tokens"));
+        assert_eq!(ExecutionSandbox::partial_trace(&err).unwrap().len(), 1);
+        assert_eq!(agent.metrics.tasks_failed, 1);
+    }
+
+    #[tokio::test]
+    async fn test_sandbox_limits_cover_all_retries() {
+        use crate::middleware::JsonOutputMiddleware;
+
+        let llm_client = Arc::new(ScriptedLlmClient::new(["not json", "still not", "{}"]));
+        let executor = DefaultExecutor::new(llm_client.clone())
+            .with_middleware(Arc::new(JsonOutputMiddleware))
+            .with_sandbox_limits(SandboxLimits { max_llm_calls: 2, ..Default::default() })
+            .with_execution_retries(5)
+            .with_backoff(Arc::new(Fixed::new(Duration::ZERO)));
+        let mut agent = Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock");
+        let context = ExecutionContext::new(agent.id);
+
+        let err = executor.execute(&mut agent, "Reply in JSON", &context).await.unwrap_err();
+
+        assert!(matches!(&err, Error::SandboxLimitExceeded { limit, .. } if limit == "llm_calls"));
+        assert_eq!(llm_client.calls(), 2);
     }
 }
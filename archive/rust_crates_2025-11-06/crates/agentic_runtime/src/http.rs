@@ -0,0 +1,108 @@
+//! Shared HTTP client construction for all outbound calls
+//!
+//! LLM providers and discovery sources should share one `reqwest::Client`
+//! built here, so proxy, timeout and certificate settings apply everywhere.
+
+use crate::config::HttpConfig;
+use agentic_core::{Error, Result};
+use std::time::Duration;
+
+/// Builds a `reqwest::Client` from [`HttpConfig`]
+#[derive(Debug, Clone)]
+pub struct HttpClientBuilder {
+    config: HttpConfig,
+    user_agent: String,
+}
+
+impl HttpClientBuilder {
+    pub fn new(config: HttpConfig) -> Self {
+        Self {
+            config,
+            user_agent: "AgenticForge/1.0".to_string(),
+        }
+    }
+
+    /// Build from environment variables (see [`HttpConfig::from_env`])
+    pub fn from_env() -> Self {
+        Self::new(HttpConfig::from_env())
+    }
+
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn config(&self) -> &HttpConfig {
+        &self.config
+    }
+
+    /// Build the configured client
+    pub fn build(&self) -> Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(&self.user_agent)
+            .connect_timeout(Duration::from_secs(self.config.connect_timeout_seconds))
+            .timeout(Duration::from_secs(self.config.request_timeout_seconds));
+
+        if let Some(proxy) = &self.config.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| Error::InitializationFailed(format!("Invalid proxy {}: {}", proxy, e)))?;
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(path) = &self.config.ca_cert_path {
+            let pem = std::fs::read(path)
+                .map_err(|e| Error::InitializationFailed(format!("Cannot read CA cert {}: {}", path, e)))?;
+            let cert = reqwest::Certificate::from_pem(&pem)
+                .map_err(|e| Error::InitializationFailed(format!("Invalid CA cert {}: {}", path, e)))?;
+            builder = builder.add_root_certificate(cert);
+        }
+
+        builder
+            .build()
+            .map_err(|e| Error::InitializationFailed(format!("Failed to create HTTP client: {}", e)))
+    }
+}
+
+impl Default for HttpClientBuilder {
+    fn default() -> Self {
+        Self::new(HttpConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_configured_timeout_is_applied() {
+        // A server that accepts connections but never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        });
+
+        let config = HttpConfig {
+            request_timeout_seconds: 1,
+            ..Default::default()
+        };
+        let client = HttpClientBuilder::new(config).build().unwrap();
+
+        let start = Instant::now();
+        let err = client.get(format!("http://{}/", addr)).send().await.unwrap_err();
+
+        assert!(err.is_timeout());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_invalid_proxy_is_rejected() {
+        let config = HttpConfig {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(HttpClientBuilder::new(config).build().is_err());
+    }
+}
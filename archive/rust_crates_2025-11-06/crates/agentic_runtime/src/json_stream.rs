@@ -0,0 +1,204 @@
+//! Parse JSON output while it is still streaming
+//!
+//! A streamed structured reply is invalid JSON until the last chunk arrives.
+//! [`JsonStreamAccumulator`] buffers the chunks of an
+//! [`LlmStream`](crate::llm::LlmStream) and after each one repairs a copy of
+//! the buffer (closing the open string, arrays and objects, and dropping a
+//! trailing half-written key or value) so consumers can render a partial
+//! value as it grows. Target types should give their fields
+//! `#[serde(default)]` so early partials deserialize.
+
+use agentic_core::{Error, Result};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::marker::PhantomData;
+
+/// Buffers streamed text and yields ever more complete `T`s
+#[derive(Debug)]
+pub struct JsonStreamAccumulator<T> {
+    buffer: String,
+    last: Option<Value>,
+    _target: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> Default for JsonStreamAccumulator<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: DeserializeOwned> JsonStreamAccumulator<T> {
+    pub fn new() -> Self {
+        Self {
+            buffer: String::new(),
+            last: None,
+            _target: PhantomData,
+        }
+    }
+
+    /// Append `chunk`; returns the partial value if it changed
+    ///
+    /// Text before the first `{` or `[` (e.g. a Markdown fence) is ignored.
+    pub fn push(&mut self, chunk: &str) -> Option<T> {
+        self.buffer.push_str(chunk);
+        let value = parse_partial(json_start(&self.buffer)?)?;
+        if self.last.as_ref() == Some(&value) {
+            return None;
+        }
+        let typed = serde_json::from_value(value.clone()).ok()?;
+        self.last = Some(value);
+        Some(typed)
+    }
+
+    /// Everything received so far
+    pub fn buffer(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Parse the complete buffer; fails if the JSON is still unfinished
+    pub fn finish(self) -> Result<T> {
+        let start = json_start(&self.buffer)
+            .ok_or_else(|| Error::InvalidArgument("Stream contained no JSON".to_string()))?;
+        let mut values = serde_json::Deserializer::from_str(start).into_iter::<T>();
+        match values.next() {
+            Some(value) => Ok(value?),
+            None => Err(Error::InvalidArgument("Stream contained no JSON".to_string())),
+        }
+    }
+}
+
+fn json_start(buffer: &str) -> Option<&str> {
+    buffer.find(['{', '[']).map(|i| &buffer[i..])
+}
+
+/// Best-effort parse of a JSON prefix
+fn parse_partial(prefix: &str) -> Option<Value> {
+    let mut text = prefix.to_string();
+    loop {
+        let scan = Scan::of(&text);
+        if let Some(end) = scan.end {
+            // The value is complete; ignore whatever follows it
+            return serde_json::from_str(&text[..end]).ok();
+        }
+        if let Ok(value) = serde_json::from_str(&scan.closed(&text)) {
+            return Some(value);
+        }
+        // Back off to just before the last element that may be incomplete
+        let (pos, ch) = scan.cut_points.last().copied()?;
+        text.truncate(if ch == ',' { pos } else { pos + 1 });
+    }
+}
+
+/// Bracket and string state at the end of a JSON prefix
+struct Scan {
+    open: Vec<char>,
+    in_string: bool,
+    escaped: bool,
+    /// `,`, `{` and `[` outside strings, with their byte offsets
+    cut_points: Vec<(usize, char)>,
+    /// Offset just past the bracket closing the top-level value
+    end: Option<usize>,
+}
+
+impl Scan {
+    fn of(text: &str) -> Self {
+        let mut scan = Scan { open: Vec::new(), in_string: false, escaped: false, cut_points: Vec::new(), end: None };
+        for (i, c) in text.char_indices() {
+            if scan.in_string {
+                match c {
+                    _ if scan.escaped => scan.escaped = false,
+                    '\\' => scan.escaped = true,
+                    '"' => scan.in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                '"' => scan.in_string = true,
+                '{' | '[' => {
+                    scan.open.push(c);
+                    scan.cut_points.push((i, c));
+                }
+                '}' | ']' => {
+                    scan.open.pop();
+                    if scan.open.is_empty() {
+                        scan.end = Some(i + 1);
+                        break;
+                    }
+                }
+                ',' => scan.cut_points.push((i, c)),
+                _ => {}
+            }
+        }
+        scan
+    }
+
+    /// `text` with its open string, arrays and objects closed
+    fn closed(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        if self.in_string {
+            if self.escaped {
+                out.pop();
+            }
+            out.push('"');
+        } else {
+            let trimmed = out.trim_end().trim_end_matches([',', ':']).len();
+            out.truncate(trimmed);
+        }
+        for open in self.open.iter().rev() {
+            out.push(if *open == '{' { '}' } else { ']' });
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, Deserialize, PartialEq)]
+    #[serde(default)]
+    struct Report {
+        title: String,
+        score: f64,
+        risks: Vec<String>,
+    }
+
+    #[test]
+    fn test_partials_grow_until_complete() {
+        let chunks = [
+            "```json\n{\"ti",
+            "tle\": \"Invoice ",
+            "SaaS\", \"score\": 7.",
+            "5, \"risks\": [\"churn\", \"comp",
+            "etition\"]}\n```",
+        ];
+        let mut acc = JsonStreamAccumulator::<Report>::new();
+
+        let partials: Vec<Option<Report>> = chunks.iter().map(|chunk| acc.push(chunk)).collect();
+
+        assert_eq!(partials[0], Some(Report::default()));
+        assert_eq!(partials[1].as_ref().unwrap().title, "Invoice ");
+        let third = partials[2].as_ref().unwrap();
+        assert_eq!((third.title.as_str(), third.score), ("Invoice SaaS", 0.0));
+        let fourth = partials[3].as_ref().unwrap();
+        assert_eq!(fourth.score, 7.5);
+        assert_eq!(fourth.risks, vec!["churn".to_string(), "comp".to_string()]);
+
+        let expected = Report {
+            title: "Invoice SaaS".to_string(),
+            score: 7.5,
+            risks: vec!["churn".to_string(), "competition".to_string()],
+        };
+        assert_eq!(partials[4].as_ref(), Some(&expected));
+        assert_eq!(acc.finish().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_unfinished_stream_fails_to_finish() {
+        let mut acc = JsonStreamAccumulator::<Report>::new();
+        acc.push("{\"title\": \"Half");
+        assert!(acc.finish().is_err());
+    }
+}
@@ -1,7 +1,7 @@
 //! Agent Runtime - Execution engine for autonomous agents
 //!
 //! This crate provides the runtime infrastructure for executing agents:
-//! - LLM API integration (Anthropic, OpenAI)
+//! - LLM API integration (Anthropic, OpenAI, local Ollama)
 //! - Task scheduling and execution
 //! - Message routing between agents
 //! - Resource management and rate limiting
@@ -12,9 +12,50 @@ pub mod executor;
 pub mod scheduler;
 pub mod context;
 pub mod config;
+pub mod http;
+pub mod concurrency;
+pub mod sandbox;
+pub mod temperature;
+pub mod middleware;
+pub mod model_alias;
+pub mod aggregation;
+pub mod prompt_trace;
+pub mod cost;
+pub mod json_stream;
+pub mod result_cache;
+pub mod backoff;
+pub mod redaction;
+pub mod ensemble;
+pub mod routing;
+pub mod retry;
+pub mod rate_limit;
+pub mod ollama;
 
-pub use llm::{LlmClient, LlmProvider, LlmRequest, LlmResponse};
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+
+pub use llm::{LlmClient, LlmProvider, LlmRequest, LlmResponse, LlmStream, ModelInfo, SwappableLlmClient};
 pub use executor::{AgentExecutor, ExecutionResult};
-pub use scheduler::{TaskScheduler, Task, TaskPriority};
-pub use context::{ExecutionContext, ContextData};
-pub use config::{RuntimeConfig, LlmConfig, ExecutionConfig, PerformanceConfig};
+pub use scheduler::{DrainReport, TaskScheduler, Task, TaskPriority, SchedulerStatus};
+pub use context::{ExecutionContext, ContextData, ModelOverrides};
+pub use config::{RuntimeConfig, PartialRuntimeConfig, LlmConfig, ExecutionConfig, PerformanceConfig, HttpConfig};
+pub use http::HttpClientBuilder;
+pub use concurrency::LlmConcurrencyLimiter;
+pub use sandbox::{ExecutionSandbox, SandboxLimit, SandboxLimits, TraceStep};
+pub use temperature::{TaskKind, TemperaturePolicy};
+pub use model_alias::{AliasedLlmClient, ModelAliasMap};
+pub use aggregation::AggregationStrategy;
+pub use prompt_trace::{PromptTrace, PromptTraceStore};
+pub use cost::{CostAlert, CostAlertConfig, CostTotals, CostTracker};
+pub use json_stream::JsonStreamAccumulator;
+pub use result_cache::{ExecutionCache, ExecutionCacheStats};
+pub use backoff::{BackoffConfig, BackoffStrategy};
+pub use redaction::{PiiKind, RedactionPolicy};
+pub use ensemble::{EnsembleClient, EnsembleStrategy};
+pub use routing::{ModelRoute, RouteHealth, RoutingConfig, RoutingLlmClient};
+pub use retry::{RetryOn, RetryPolicy, RetryingLlmClient};
+pub use rate_limit::{CallerLlmClient, ProviderLimits, RateLimitPermit, RateLimitedLlmClient, RateLimiter};
+pub use ollama::OllamaClient;
+pub use middleware::{
+    BudgetMiddleware, CallMetrics, ExecutorMiddleware, JsonOutputMiddleware, MetricsMiddleware, ModerationMiddleware,
+};
@@ -1,9 +1,16 @@
 //! LLM Client abstraction and implementations for multiple providers
 
+use crate::concurrency::LlmConcurrencyLimiter;
+use crate::http::HttpClientBuilder;
+use crate::ollama::OllamaClient;
+use crate::rate_limit::{RateLimitedLlmClient, RateLimiter};
+use crate::temperature::{TaskKind, TemperaturePolicy};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use tracing::{debug, instrument, warn};
 
 #[derive(Debug, Error)]
 pub enum LlmError {
@@ -25,52 +32,226 @@ pub enum LlmError {
     #[error("Network error: {0}")]
     NetworkError(String),
 
+    /// No response arrived within the HTTP client's timeout
+    #[error("Request timed out: {0}")]
+    Timeout(String),
+
     #[error("Token limit exceeded: max {max}, requested {requested}")]
     TokenLimitExceeded { max: usize, requested: usize },
+
+    #[error("Unsupported by provider: {0}")]
+    Unsupported(String),
+
+    /// The provider rejected the request itself (bad parameters, malformed
+    /// messages); sending it again will fail the same way
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    /// The provider failed on its side (HTTP 5xx, including Anthropic's 529
+    /// "overloaded")
+    #[error("Provider error: HTTP {status}: {message}")]
+    ServerError { status: u16, message: String },
+}
+
+impl LlmError {
+    /// The provider is down or unreachable rather than refusing this
+    /// request; another provider may well answer it
+    pub fn is_outage(&self) -> bool {
+        matches!(self, LlmError::ServerError { .. } | LlmError::NetworkError(_) | LlmError::Timeout(_))
+    }
+
+    /// Error for a request that never got a response
+    pub(crate) fn from_send(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            LlmError::Timeout(err.to_string())
+        } else {
+            LlmError::NetworkError(err.to_string())
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, LlmError>;
 
+/// Content deltas of a streamed completion, in order
+///
+/// The channel closes after the last delta. Dropping the receiver tells the
+/// producer to stop, which cancels the underlying call.
+pub type LlmStream = tokio::sync::mpsc::Receiver<Result<String>>;
+
+impl From<LlmError> for agentic_core::Error {
+    fn from(err: LlmError) -> Self {
+        match err {
+            LlmError::InvalidApiKey => agentic_core::Error::AuthorizationFailed(err.to_string()),
+            LlmError::UnsupportedModel(_) | LlmError::Unsupported(_) => {
+                agentic_core::Error::CapabilityNotSupported(err.to_string())
+            }
+            LlmError::InvalidRequest(_) | LlmError::TokenLimitExceeded { .. } => {
+                agentic_core::Error::InvalidArgument(err.to_string())
+            }
+            _ => agentic_core::Error::InternalError(err.to_string()),
+        }
+    }
+}
+
 /// Supported LLM providers
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum LlmProvider {
     Anthropic,
     OpenAI,
+    /// Models served by a local Ollama server
+    Ollama,
     Mock, // For testing
 }
 
+impl LlmProvider {
+    /// Every known provider
+    pub fn all() -> &'static [LlmProvider] {
+        &[LlmProvider::Anthropic, LlmProvider::OpenAI, LlmProvider::Ollama, LlmProvider::Mock]
+    }
+
+    /// Canonical lowercase name, as stored in `Agent.provider` and config
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LlmProvider::Anthropic => "anthropic",
+            LlmProvider::OpenAI => "openai",
+            LlmProvider::Ollama => "ollama",
+            LlmProvider::Mock => "mock",
+        }
+    }
+
+    /// Models this provider serves, from the shared catalog
+    pub fn models(&self) -> &'static [&'static str] {
+        agentic_core::model_catalog::models_for_provider(self.as_str())
+    }
+
+    /// Model used when nothing more specific is configured
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            LlmProvider::Anthropic => "claude-3-5-sonnet-20241022",
+            LlmProvider::OpenAI => "gpt-4o",
+            LlmProvider::Ollama => "llama3.2",
+            LlmProvider::Mock => "mock-model",
+        }
+    }
+}
+
+impl std::fmt::Display for LlmProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for LlmProvider {
+    type Err = agentic_core::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        LlmProvider::all()
+            .iter()
+            .copied()
+            .find(|p| p.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| agentic_core::Error::InvalidArgument(format!("Unknown LLM provider: {}", s)))
+    }
+}
+
 /// Message role in conversation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MessageRole {
     System,
     User,
     Assistant,
+    /// Result of a tool call made in an earlier assistant message
+    Tool,
+}
+
+/// A tool invocation requested by the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// Provider-assigned id that the matching result refers back to
+    pub id: String,
+    pub name: String,
+    pub input: serde_json::Value,
 }
 
 /// A single message in the conversation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Message {
     pub role: MessageRole,
     pub content: String,
+    /// Tools called by this assistant message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<ToolCall>,
+    /// For [`MessageRole::Tool`] messages, the id of the call answered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
 }
 
 impl Message {
+    fn new(role: MessageRole, content: impl Into<String>) -> Self {
+        Self { role, content: content.into(), tool_calls: Vec::new(), tool_call_id: None }
+    }
+
     pub fn system(content: impl Into<String>) -> Self {
-        Self { role: MessageRole::System, content: content.into() }
+        Self::new(MessageRole::System, content)
     }
 
     pub fn user(content: impl Into<String>) -> Self {
-        Self { role: MessageRole::User, content: content.into() }
+        Self::new(MessageRole::User, content)
     }
 
     pub fn assistant(content: impl Into<String>) -> Self {
-        Self { role: MessageRole::Assistant, content: content.into() }
+        Self::new(MessageRole::Assistant, content)
+    }
+
+    /// Assistant turn that calls `calls`, with any text said alongside them
+    pub fn tool_calls(content: impl Into<String>, calls: Vec<ToolCall>) -> Self {
+        Self { tool_calls: calls, ..Self::assistant(content) }
+    }
+
+    /// Output of the tool call `call_id`
+    pub fn tool_result(call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self { tool_call_id: Some(call_id.into()), ..Self::new(MessageRole::Tool, content) }
+    }
+}
+
+/// A model a provider serves, as listed by [`LlmClient::list_models`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub id: String,
+    pub provider: LlmProvider,
+    pub capabilities: agentic_core::model_catalog::ModelCapabilities,
+}
+
+impl ModelInfo {
+    /// `id` served by `provider`, with capabilities from the model catalog
+    pub fn new(id: impl Into<String>, provider: LlmProvider) -> Self {
+        let id = id.into();
+        let capabilities = agentic_core::model_catalog::capabilities_for_model(&id);
+        Self { id, provider, capabilities }
+    }
+}
+
+/// A tool the model may call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolSpec {
+    pub name: String,
+    pub description: String,
+    /// JSON Schema for the tool input
+    pub input_schema: serde_json::Value,
+}
+
+impl From<&agentic_core::Tool> for ToolSpec {
+    fn from(tool: &agentic_core::Tool) -> Self {
+        Self {
+            name: tool.name.clone(),
+            description: tool.description.clone(),
+            input_schema: tool.input_schema.clone(),
+        }
     }
 }
 
 /// Request parameters for LLM completion
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LlmRequest {
     pub model: String,
     pub messages: Vec<Message>,
@@ -78,6 +259,16 @@ pub struct LlmRequest {
     pub temperature: Option<f32>,
     pub top_p: Option<f32>,
     pub stop_sequences: Vec<String>,
+    #[serde(default)]
+    pub tools: Vec<ToolSpec>,
+    /// HTTP headers for this call only; they override a client's configured
+    /// headers of the same name
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra_headers: HashMap<String, String>,
+    /// Who the call is made for, usually an agent id; rate limiting shares
+    /// provider capacity evenly between callers. Never sent to the provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caller: Option<String>,
 }
 
 impl LlmRequest {
@@ -89,9 +280,17 @@ impl LlmRequest {
             temperature: Some(0.7),
             top_p: Some(1.0),
             stop_sequences: Vec::new(),
+            tools: Vec::new(),
+            extra_headers: HashMap::new(),
+            caller: None,
         }
     }
 
+    /// Start a validated request builder
+    pub fn builder() -> LlmRequestBuilder {
+        LlmRequestBuilder::default()
+    }
+
     pub fn with_system(mut self, content: impl Into<String>) -> Self {
         self.messages.insert(0, Message::system(content));
         self
@@ -102,15 +301,166 @@ impl LlmRequest {
         self
     }
 
+    /// Content of a trailing assistant message, which the model should continue
+    pub fn prefill(&self) -> Option<&str> {
+        self.messages
+            .last()
+            .filter(|m| m.role == MessageRole::Assistant && m.tool_calls.is_empty())
+            .map(|m| m.content.as_str())
+    }
+
     pub fn with_temperature(mut self, temp: f32) -> Self {
         self.temperature = Some(temp);
         self
     }
 
+    /// Use the global [`TemperaturePolicy`] temperature for `kind`
+    ///
+    /// A later `with_temperature` overrides it for this call.
+    pub fn with_task(self, kind: TaskKind) -> Self {
+        self.with_temperature(TemperaturePolicy::global().temperature_for(kind))
+    }
+
     pub fn with_max_tokens(mut self, max: usize) -> Self {
         self.max_tokens = Some(max);
         self
     }
+
+    pub fn with_stop_sequences<S: Into<String>>(mut self, stops: impl IntoIterator<Item = S>) -> Self {
+        self.stop_sequences = stops.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Send `name: value` with this call (e.g. a gateway tenant id)
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extra_headers.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn with_caller(mut self, caller: impl Into<String>) -> Self {
+        self.caller = Some(caller.into());
+        self
+    }
+
+    /// Rough prompt size in tokens (four characters each), for budgeting
+    /// before the provider reports the real count
+    pub fn estimated_prompt_tokens(&self) -> usize {
+        self.messages.iter().map(|m| m.content.chars().count()).sum::<usize>().div_ceil(4)
+    }
+}
+
+/// Builder for [`LlmRequest`] that validates on `build()`
+///
+/// Defaults match [`LlmRequest::new`]; only the model and at least one
+/// non-system message are required.
+#[derive(Debug, Clone, Default)]
+pub struct LlmRequestBuilder {
+    model: Option<String>,
+    system: Option<String>,
+    messages: Vec<Message>,
+    temperature: Option<f32>,
+    task: Option<TaskKind>,
+    max_tokens: Option<usize>,
+    stop_sequences: Vec<String>,
+    tools: Vec<ToolSpec>,
+}
+
+impl LlmRequestBuilder {
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Set the system prompt (replaces any previous one)
+    pub fn system(mut self, content: impl Into<String>) -> Self {
+        self.system = Some(content.into());
+        self
+    }
+
+    pub fn user(mut self, content: impl Into<String>) -> Self {
+        self.messages.push(Message::user(content));
+        self
+    }
+
+    pub fn assistant(mut self, content: impl Into<String>) -> Self {
+        self.messages.push(Message::assistant(content));
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f32) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    /// Take the temperature from the global [`TemperaturePolicy`] unless
+    /// `temperature` is also set
+    pub fn task(mut self, kind: TaskKind) -> Self {
+        self.task = Some(kind);
+        self
+    }
+
+    pub fn max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    /// End generation when the model produces `stop`; may be given several times
+    pub fn stop_sequence(mut self, stop: impl Into<String>) -> Self {
+        self.stop_sequences.push(stop.into());
+        self
+    }
+
+    pub fn tool(mut self, tool: impl Into<ToolSpec>) -> Self {
+        self.tools.push(tool.into());
+        self
+    }
+
+    /// Validate and build the request
+    pub fn build(self) -> agentic_core::Result<LlmRequest> {
+        let invalid = |msg: String| agentic_core::Error::InvalidArgument(msg);
+
+        let model = self
+            .model
+            .filter(|m| !m.trim().is_empty())
+            .ok_or_else(|| invalid("LLM request needs a model".to_string()))?;
+
+        if self.messages.is_empty() {
+            return Err(invalid("LLM request needs at least one user or assistant message".to_string()));
+        }
+
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err(invalid(format!("Temperature {} out of range 0.0-2.0", temperature)));
+            }
+        }
+
+        if self.max_tokens == Some(0) {
+            return Err(invalid("max_tokens must be greater than zero".to_string()));
+        }
+
+        if self.stop_sequences.iter().any(|stop| stop.is_empty()) {
+            return Err(invalid("Stop sequences must not be empty".to_string()));
+        }
+
+        let mut request = LlmRequest::new(model);
+        if let Some(system) = self.system {
+            request = request.with_system(system);
+        }
+        request.messages.extend(self.messages);
+        let temperature = self
+            .temperature
+            .or_else(|| self.task.map(|kind| TemperaturePolicy::global().temperature_for(kind)));
+        if let Some(temperature) = temperature {
+            request.temperature = Some(temperature);
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            request.max_tokens = Some(max_tokens);
+        }
+        request.stop_sequences = self.stop_sequences;
+        request.tools = self.tools;
+
+        Ok(request)
+    }
 }
 
 /// Response from LLM
@@ -120,16 +470,262 @@ pub struct LlmResponse {
     pub model: String,
     pub usage: TokenUsage,
     pub finish_reason: String,
+    /// Generation stopped at the token limit, so `content` may be incomplete
+    #[serde(default)]
+    pub truncated: bool,
+    /// Provider details for reproducing the call, under the `META_*` keys
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl LlmResponse {
+    /// Estimated cost of this call in USD, from `usage` and list prices
+    pub fn estimated_cost(&self) -> f64 {
+        self.usage.estimated_cost(&self.model)
+    }
+}
+
+/// Provider-assigned id of the response
+pub const META_RESPONSE_ID: &str = "response_id";
+
+/// Model the provider says served the request, which may be a dated snapshot
+/// of the requested alias
+pub const META_SERVED_MODEL: &str = "served_model";
+
+/// OpenAI's backend configuration id; when it changes between two otherwise
+/// identical requests, so may the output
+pub const META_SYSTEM_FINGERPRINT: &str = "system_fingerprint";
+
+/// Copy the string `fields` of a provider response into metadata, as
+/// (metadata key, response field) pairs; missing fields are skipped
+fn response_metadata(response_json: &serde_json::Value, fields: &[(&str, &str)]) -> HashMap<String, String> {
+    fields
+        .iter()
+        .filter_map(|(key, field)| Some((key.to_string(), response_json[*field].as_str()?.to_string())))
+        .collect()
+}
+
+fn anthropic_metadata(response_json: &serde_json::Value) -> HashMap<String, String> {
+    response_metadata(response_json, &[(META_RESPONSE_ID, "id"), (META_SERVED_MODEL, "model")])
+}
+
+fn openai_metadata(response_json: &serde_json::Value) -> HashMap<String, String> {
+    response_metadata(
+        response_json,
+        &[
+            (META_RESPONSE_ID, "id"),
+            (META_SERVED_MODEL, "model"),
+            (META_SYSTEM_FINGERPRINT, "system_fingerprint"),
+        ],
+    )
+}
+
+/// Header names whose values never appear in logs (matched as substrings,
+/// case-insensitively)
+const SENSITIVE_HEADER_MARKERS: &[&str] = &["auth", "key", "token", "secret", "cookie", "password"];
+
+/// `headers` with the values of credential-like headers replaced, for logging
+pub fn redact_headers(headers: &HashMap<String, String>) -> std::collections::BTreeMap<&str, &str> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let lower = name.to_ascii_lowercase();
+            let sensitive = SENSITIVE_HEADER_MARKERS.iter().any(|marker| lower.contains(marker));
+            (name.as_str(), if sensitive { "[REDACTED]" } else { value.as_str() })
+        })
+        .collect()
+}
+
+/// Attach the client's configured headers, then the per-call ones, to `builder`
+pub(crate) fn with_extra_headers(
+    builder: reqwest::RequestBuilder,
+    configured: &HashMap<String, String>,
+    per_call: &HashMap<String, String>,
+) -> reqwest::RequestBuilder {
+    let mut headers = configured.clone();
+    headers.extend(per_call.clone());
+    if headers.is_empty() {
+        return builder;
+    }
+    debug!(headers = ?redact_headers(&headers), "Adding extra LLM request headers");
+    headers.iter().fold(builder, |builder, (name, value)| builder.header(name.as_str(), value.as_str()))
+}
+
+/// Send a provider `/models` request and collect the `data[].id` entries
+/// that `keep` accepts
+async fn fetch_model_ids(request: reqwest::RequestBuilder, keep: impl Fn(&str) -> bool) -> Result<Vec<String>> {
+    let response = request.send().await.map_err(LlmError::from_send)?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(LlmError::ApiError(format!("HTTP {}: {}", status, error_text)));
+    }
+    let listing: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| LlmError::SerializationError(e.to_string()))?;
+    Ok(listing["data"]
+        .as_array()
+        .map(|models| models.iter().filter_map(|m| m["id"].as_str()).filter(|id| keep(id)).map(String::from).collect())
+        .unwrap_or_default())
+}
+
+/// Content blocks of `message` if it is a user message holding only tool results
+fn tool_result_blocks(message: &mut serde_json::Value) -> Option<&mut Vec<serde_json::Value>> {
+    if message["role"] != "user" {
+        return None;
+    }
+    message["content"]
+        .as_array_mut()
+        .filter(|blocks| blocks.iter().all(|block| block["type"] == "tool_result"))
+}
+
+/// Whether a provider finish reason means the output hit the token limit
+///
+/// OpenAI reports `length`, Anthropic reports `max_tokens`.
+pub fn is_truncation(finish_reason: &str) -> bool {
+    matches!(finish_reason, "length" | "max_tokens")
+}
+
+/// Continuation requests [`LlmClient::complete_with_continuation`] makes by default
+pub const DEFAULT_MAX_CONTINUATIONS: usize = 2;
+
+const CONTINUE_PROMPT: &str = "Your previous response was cut off. Continue the JSON exactly where it stopped, \
+without repeating anything already written and without any commentary.";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TokenUsage {
     pub prompt_tokens: usize,
     pub completion_tokens: usize,
     pub total_tokens: usize,
 }
 
+impl TokenUsage {
+    /// Estimated cost in USD based on list prices (0.0 for unknown models)
+    pub fn estimated_cost(&self, model: &str) -> f64 {
+        let (input, output) = model_pricing(model).unwrap_or((0.0, 0.0));
+        (self.prompt_tokens as f64 * input + self.completion_tokens as f64 * output) / 1_000_000.0
+    }
+}
+
+/// USD per million (input, output) tokens, matched by model prefix
+fn model_pricing(model: &str) -> Option<(f64, f64)> {
+    // More specific prefixes must come first
+    const PRICES: &[(&str, f64, f64)] = &[
+        ("claude-3-opus", 15.0, 75.0),
+        ("claude-3-5-sonnet", 3.0, 15.0),
+        ("claude-3-sonnet", 3.0, 15.0),
+        ("claude-3-5-haiku", 0.8, 4.0),
+        ("claude-3-haiku", 0.25, 1.25),
+        ("gpt-4o-mini", 0.15, 0.6),
+        ("gpt-4o", 2.5, 10.0),
+        ("gpt-4-turbo", 10.0, 30.0),
+        ("gpt-4", 30.0, 60.0),
+        ("gpt-3.5-turbo", 0.5, 1.5),
+        ("o1-mini", 3.0, 12.0),
+        ("o1-preview", 15.0, 60.0),
+    ];
+
+    PRICES
+        .iter()
+        .find(|(prefix, _, _)| model.starts_with(prefix))
+        .map(|&(_, input, output)| (input, output))
+}
+
+/// Record usage, latency and cost on the current `llm.complete` span
+pub(crate) fn record_completion(usage: &TokenUsage, model: &str, started: Instant) {
+    let span = tracing::Span::current();
+    span.record("prompt_tokens", usage.prompt_tokens as u64);
+    span.record("completion_tokens", usage.completion_tokens as u64);
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+    span.record("estimated_cost", usage.estimated_cost(model));
+}
+
+/// What one line of a streamed response body amounts to
+pub(crate) enum StreamLine {
+    /// Content to pass on
+    Delta(String),
+    /// Nothing to pass on: keep-alives, event names, metadata
+    Skip,
+    /// The reply is complete
+    Done,
+    /// The provider failed after the stream opened
+    Failed(LlmError),
+}
+
+/// Read `response` line by line on a spawned task, sending on what `parse`
+/// makes of each line
+///
+/// The task holds `permit` until the body ends, `parse` returns `Done` or
+/// `Failed`, or the receiver is dropped; dropping the response then closes
+/// the connection.
+pub(crate) fn stream_lines<P: Send + 'static>(
+    mut response: reqwest::Response,
+    permit: P,
+    mut parse: impl FnMut(&str) -> StreamLine + Send + 'static,
+) -> LlmStream {
+    let (tx, rx) = tokio::sync::mpsc::channel(16);
+    tokio::spawn(async move {
+        let _permit = permit;
+        let mut buffer = Vec::new();
+        loop {
+            let chunk = tokio::select! {
+                chunk = response.chunk() => chunk,
+                _ = tx.closed() => return,
+            };
+            let finished = match chunk {
+                Ok(Some(bytes)) => {
+                    buffer.extend_from_slice(&bytes);
+                    false
+                }
+                // Whatever follows the last newline is a line too
+                Ok(None) => {
+                    buffer.push(b'\n');
+                    true
+                }
+                Err(e) => {
+                    let _ = tx.send(Err(LlmError::from_send(e))).await;
+                    return;
+                }
+            };
+            while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                let delta = match parse(String::from_utf8_lossy(&line).trim()) {
+                    StreamLine::Delta(text) => Ok(text),
+                    StreamLine::Skip => continue,
+                    StreamLine::Done => return,
+                    StreamLine::Failed(e) => Err(e),
+                };
+                let failed = delta.is_err();
+                if tx.send(delta).await.is_err() || failed {
+                    return;
+                }
+            }
+            if finished {
+                return;
+            }
+        }
+    });
+    rx
+}
+
 /// Trait for LLM client implementations
+///
+/// This is the one definition every crate should use. It is declared with
+/// `#[async_trait]` so it stays object-safe and can be shared as `Arc<dyn LlmClient>`.
+///
+/// # Cancellation
+///
+/// Dropping the future returned by `complete` cancels the call, e.g. when
+/// Axum drops a handler because the client disconnected. The built-in clients
+/// send the request on the calling task, so the in-flight `reqwest` request is
+/// aborted and its connection closed, and the concurrency permit is released
+/// with the future. Implementations must keep it that way: don't hold a
+/// `std::sync::Mutex` guard across an `.await`, and don't hand the HTTP call
+/// to a detached `tokio::spawn`.
+///
+/// `complete_stream` may produce deltas from a spawned task, but that task
+/// must stop, and release its permit, once the [`LlmStream`] is dropped.
 #[async_trait]
 pub trait LlmClient: Send + Sync {
     /// Get the provider this client is for
@@ -143,108 +739,332 @@ pub trait LlmClient: Send + Sync {
 
     /// Get available models
     fn available_models(&self) -> Vec<String>;
+
+    /// Models the provider serves right now
+    ///
+    /// The default lists [`available_models`](Self::available_models);
+    /// clients of real providers ask the provider's `/models` endpoint.
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let provider = self.provider();
+        Ok(self.available_models().into_iter().map(|id| ModelInfo::new(id, provider)).collect())
+    }
+
+    /// True for canned-response clients that never reach a real provider
+    fn is_mock(&self) -> bool {
+        false
+    }
+
+    /// Complete, asking the model to continue while the output is truncated
+    ///
+    /// Meant for prompts that expect structured output such as JSON: the
+    /// partial reply is sent back as an assistant turn and each continuation
+    /// is appended to it. A request that ends in a prefill has the partial
+    /// reply merged into the prefill instead, since two assistant turns in a
+    /// row are rejected; a client that echoes the prefill (as Anthropic's
+    /// does) has the echo dropped from the continuation. Usage is summed
+    /// across calls. After `max_continuations` extra calls the response is
+    /// returned with `truncated` still set.
+    async fn complete_with_continuation(&self, request: LlmRequest, max_continuations: usize) -> Result<LlmResponse> {
+        let prefill = request.prefill().map(str::to_string);
+        let mut response = self.complete(request.clone()).await?;
+
+        let mut continuations = 0;
+        while response.truncated && continuations < max_continuations {
+            continuations += 1;
+            debug!("Response truncated ({}), requesting continuation {}", response.finish_reason, continuations);
+
+            let (continuation_request, written) = match &prefill {
+                Some(prefill) => {
+                    let written = if response.content.starts_with(prefill.trim_end()) {
+                        response.content.clone()
+                    } else {
+                        format!("{}{}", prefill, response.content)
+                    };
+                    let mut continuation_request = request.clone();
+                    if let Some(last) = continuation_request.messages.last_mut() {
+                        last.content = written.clone();
+                    }
+                    (continuation_request, Some(written))
+                }
+                None => {
+                    let continuation_request = request
+                        .clone()
+                        .add_message(Message::assistant(response.content.clone()))
+                        .add_message(Message::user(CONTINUE_PROMPT));
+                    (continuation_request, None)
+                }
+            };
+            let next = self.complete(continuation_request).await?;
+
+            let continued = written
+                .as_deref()
+                .and_then(|written| next.content.strip_prefix(written.trim_end()))
+                .unwrap_or(&next.content);
+            response.content.push_str(continued);
+            response.usage.prompt_tokens += next.usage.prompt_tokens;
+            response.usage.completion_tokens += next.usage.completion_tokens;
+            response.usage.total_tokens += next.usage.total_tokens;
+            response.finish_reason = next.finish_reason;
+            response.truncated = next.truncated;
+            response.metadata.extend(next.metadata);
+        }
+
+        if response.truncated {
+            warn!("Response still truncated after {} continuation(s)", continuations);
+        }
+        Ok(response)
+    }
+
+    /// Stream the completion as content deltas
+    ///
+    /// The default waits for `complete` and sends the whole reply as a
+    /// single delta, so every client can be used where a stream is expected.
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        let response = self.complete(request).await?;
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        // Capacity 1 and a fresh receiver: this send cannot fail
+        let _ = tx.send(Ok(response.content)).await;
+        Ok(rx)
+    }
 }
 
 /// Anthropic Claude client
+///
+/// A trailing assistant message is sent as a prefill: the model continues
+/// from it, and the returned content starts with the prefill (e.g. prefill
+/// `{` to force a JSON object).
 pub struct AnthropicClient {
     api_key: String,
     base_url: String,
     client: reqwest::Client,
+    limiter: LlmConcurrencyLimiter,
+    extra_headers: HashMap<String, String>,
 }
 
 impl AnthropicClient {
-    pub fn new(api_key: impl Into<String>) -> Self {
+    /// Client sending over an HTTP client configured from the environment
+    /// (see `HttpClientBuilder::from_env`)
+    pub fn new(api_key: impl Into<String>) -> agentic_core::Result<Self> {
+        Ok(Self::with_client(api_key, HttpClientBuilder::from_env().build()?))
+    }
+
+    fn with_client(api_key: impl Into<String>, client: reqwest::Client) -> Self {
         Self {
             api_key: api_key.into(),
             base_url: "https://api.anthropic.com/v1".to_string(),
-            client: reqwest::Client::builder()
-                .timeout(Duration::from_secs(120))
-                .build()
-                .expect("Failed to create HTTP client"),
+            client,
+            limiter: LlmConcurrencyLimiter::global().clone(),
+            extra_headers: HashMap::new(),
         }
     }
 
+    /// Client using the API key and extra headers of `config`, sending over
+    /// the shared `http` client
+    ///
+    /// Fails with `InvalidArgument` when `anthropic_api_key`
+    /// (`ANTHROPIC_API_KEY`) is not set.
+    pub fn from_config(config: &crate::config::LlmConfig, http: &reqwest::Client) -> agentic_core::Result<Self> {
+        let api_key = config
+            .anthropic_api_key
+            .clone()
+            .filter(|key| !key.trim().is_empty())
+            .ok_or_else(|| agentic_core::Error::InvalidArgument("ANTHROPIC_API_KEY is not set".to_string()))?;
+        Ok(Self::with_client(api_key, http.clone()).with_extra_headers(config.extra_headers.clone()))
+    }
+
     pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
         self.base_url = url.into();
         self
     }
+
+    /// Use a shared, pre-configured HTTP client (see `HttpClientBuilder`)
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Share a concurrency limiter other than the global one
+    pub fn with_concurrency_limiter(mut self, limiter: LlmConcurrencyLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Send `headers` on every call (see `LlmConfig::extra_headers`)
+    pub fn with_extra_headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.extra_headers = headers;
+        self
+    }
+
+    /// POST `body` to the Messages API, failing with a typed error unless
+    /// the API accepted it
+    async fn post_messages(&self, request: &LlmRequest, body: &serde_json::Value) -> Result<reqwest::Response> {
+        let http_request = self.client
+            .post(format!("{}/messages", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json");
+        let response = with_extra_headers(http_request, &self.extra_headers, &request.extra_headers)
+            .json(body)
+            .send()
+            .await
+            .map_err(LlmError::from_send)?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(anthropic_error(status, &error_text, &request.model));
+        }
+        Ok(response)
+    }
 }
 
-#[async_trait]
-impl LlmClient for AnthropicClient {
-    fn provider(&self) -> LlmProvider {
-        LlmProvider::Anthropic
+/// Typed error for a failed Messages API call
+///
+/// The API answers `{"type": "error", "error": {"type": ..., "message": ...}}`;
+/// bodies that don't parse fall back to the HTTP status.
+fn anthropic_error(status: u16, body: &str, model: &str) -> LlmError {
+    let parsed: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+    let kind = parsed["error"]["type"].as_str().unwrap_or_default();
+    let message = parsed["error"]["message"].as_str().unwrap_or(body).to_string();
+
+    match (status, kind) {
+        (401 | 403, _) | (_, "authentication_error" | "permission_error") => LlmError::InvalidApiKey,
+        (429, _) | (_, "rate_limit_error") => LlmError::RateLimitExceeded(message),
+        (404, _) | (_, "not_found_error") if message.contains("model") => LlmError::UnsupportedModel(model.to_string()),
+        (400 | 413, _) | (_, "invalid_request_error" | "request_too_large") => {
+            prompt_too_long(&message).unwrap_or(LlmError::InvalidRequest(message))
+        }
+        (500.., _) | (_, "api_error" | "overloaded_error") => LlmError::ServerError { status, message },
+        _ => LlmError::ApiError(format!("HTTP {}: {}", status, message)),
     }
+}
 
-    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
-        // Build Anthropic-specific request format
-        let mut anthropic_messages = Vec::new();
-        let mut system_prompt = None;
-
-        for msg in &request.messages {
-            match msg.role {
-                MessageRole::System => {
-                    system_prompt = Some(msg.content.clone());
+/// `prompt is too long: 210000 tokens > 200000 maximum`
+fn prompt_too_long(message: &str) -> Option<LlmError> {
+    let counts = message.strip_prefix("prompt is too long:")?;
+    let (requested, max) = counts.split_once('>')?;
+    let number = |s: &str| s.split_whitespace().next()?.parse().ok();
+    Some(LlmError::TokenLimitExceeded { max: number(max)?, requested: number(requested)? })
+}
+
+/// Messages API body for `request`, and the prefill its reply continues from
+fn anthropic_body(request: &LlmRequest) -> (serde_json::Value, Option<String>) {
+    let mut anthropic_messages = Vec::new();
+    let mut system_prompt: Option<String> = None;
+    // The API rejects a prefill ending in whitespace
+    let prefill = request.prefill().map(|p| p.trim_end().to_string());
+    let prefill_index = prefill.as_ref().map(|_| request.messages.len() - 1);
+
+    for (i, msg) in request.messages.iter().enumerate() {
+        match msg.role {
+            // The API takes one top-level system prompt
+            MessageRole::System => match &mut system_prompt {
+                Some(system) => {
+                    system.push_str("\n\n");
+                    system.push_str(&msg.content);
                 }
-                MessageRole::User | MessageRole::Assistant => {
-                    anthropic_messages.push(serde_json::json!({
-                        "role": match msg.role {
-                            MessageRole::User => "user",
-                            MessageRole::Assistant => "assistant",
-                            _ => unreachable!(),
-                        },
-                        "content": msg.content,
-                    }));
+                None => system_prompt = Some(msg.content.clone()),
+            },
+            MessageRole::Assistant if !msg.tool_calls.is_empty() => {
+                let text = (!msg.content.is_empty())
+                    .then(|| serde_json::json!({"type": "text", "text": msg.content}));
+                let calls = msg.tool_calls.iter().map(|call| {
+                    serde_json::json!({"type": "tool_use", "id": call.id, "name": call.name, "input": call.input})
+                });
+                let blocks: Vec<serde_json::Value> = text.into_iter().chain(calls).collect();
+                anthropic_messages.push(serde_json::json!({"role": "assistant", "content": blocks}));
+            }
+            MessageRole::Tool => {
+                let block = serde_json::json!({
+                    "type": "tool_result",
+                    "tool_use_id": msg.tool_call_id,
+                    "content": msg.content,
+                });
+                // All results of one assistant turn go back in a single user message
+                match anthropic_messages.last_mut().and_then(|last| tool_result_blocks(last)) {
+                    Some(blocks) => blocks.push(block),
+                    None => anthropic_messages.push(serde_json::json!({"role": "user", "content": [block]})),
                 }
             }
+            MessageRole::User | MessageRole::Assistant => {
+                let content = match (&prefill, Some(i) == prefill_index) {
+                    (Some(prefill), true) => prefill.as_str(),
+                    _ => msg.content.as_str(),
+                };
+                anthropic_messages.push(serde_json::json!({
+                    "role": if msg.role == MessageRole::User { "user" } else { "assistant" },
+                    "content": content,
+                }));
+            }
         }
+    }
 
-        let mut body = serde_json::json!({
-            "model": request.model,
-            "messages": anthropic_messages,
-            "max_tokens": request.max_tokens.unwrap_or(4096),
-        });
+    let mut body = serde_json::json!({
+        "model": request.model,
+        "messages": anthropic_messages,
+        "max_tokens": request.max_tokens.unwrap_or(4096),
+    });
 
-        if let Some(system) = system_prompt {
-            body["system"] = serde_json::json!(system);
-        }
+    if let Some(system) = system_prompt {
+        body["system"] = serde_json::json!(system);
+    }
 
-        if let Some(temp) = request.temperature {
-            body["temperature"] = serde_json::json!(temp);
-        }
+    if let Some(temp) = request.temperature {
+        body["temperature"] = serde_json::json!(temp);
+    }
 
-        if let Some(top_p) = request.top_p {
-            body["top_p"] = serde_json::json!(top_p);
-        }
+    if let Some(top_p) = request.top_p {
+        body["top_p"] = serde_json::json!(top_p);
+    }
 
-        if !request.stop_sequences.is_empty() {
-            body["stop_sequences"] = serde_json::json!(request.stop_sequences);
-        }
+    if !request.stop_sequences.is_empty() {
+        body["stop_sequences"] = serde_json::json!(request.stop_sequences);
+    }
 
-        let response = self.client
-            .post(format!("{}/messages", self.base_url))
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| LlmError::NetworkError(e.to_string()))?;
+    if !request.tools.is_empty() {
+        body["tools"] = serde_json::json!(request.tools);
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(LlmError::ApiError(format!("HTTP {}: {}", status, error_text)));
-        }
+    (body, prefill)
+}
+
+#[async_trait]
+impl LlmClient for AnthropicClient {
+    fn provider(&self) -> LlmProvider {
+        LlmProvider::Anthropic
+    }
+
+    #[instrument(
+        name = "llm.complete",
+        skip(self, request),
+        fields(
+            provider = "anthropic",
+            model = %request.model,
+            prompt_tokens = tracing::field::Empty,
+            completion_tokens = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            estimated_cost = tracing::field::Empty,
+        )
+    )]
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        let _permit = self.limiter.acquire().await;
+        let started = Instant::now();
+        let (body, prefill) = anthropic_body(&request);
+        let response = self.post_messages(&request, &body).await?;
 
         let response_json: serde_json::Value = response.json().await
             .map_err(|e| LlmError::SerializationError(e.to_string()))?;
 
-        // Parse Anthropic response
-        let content = response_json["content"][0]["text"]
-            .as_str()
-            .ok_or_else(|| LlmError::ApiError("No content in response".to_string()))?
-            .to_string();
+        // Parse Anthropic response; text may be split across several blocks
+        let blocks = response_json["content"]
+            .as_array()
+            .ok_or_else(|| LlmError::ApiError("No content in response".to_string()))?;
+        let text: String = blocks
+            .iter()
+            .filter(|block| block["type"] == "text")
+            .filter_map(|block| block["text"].as_str())
+            .collect();
+        let content = format!("{}{}", prefill.unwrap_or_default(), text);
 
         let usage = TokenUsage {
             prompt_tokens: response_json["usage"]["input_tokens"].as_u64().unwrap_or(0) as usize,
@@ -252,14 +1072,20 @@ impl LlmClient for AnthropicClient {
             total_tokens: 0,
         };
 
+        let usage = TokenUsage {
+            total_tokens: usage.prompt_tokens + usage.completion_tokens,
+            ..usage
+        };
+        record_completion(&usage, &request.model, started);
+
+        let finish_reason = response_json["stop_reason"].as_str().unwrap_or("unknown").to_string();
         Ok(LlmResponse {
             content,
             model: request.model,
-            usage: TokenUsage {
-                total_tokens: usage.prompt_tokens + usage.completion_tokens,
-                ..usage
-            },
-            finish_reason: response_json["stop_reason"].as_str().unwrap_or("unknown").to_string(),
+            usage,
+            truncated: is_truncation(&finish_reason),
+            finish_reason,
+            metadata: anthropic_metadata(&response_json),
         })
     }
 
@@ -268,89 +1094,276 @@ impl LlmClient for AnthropicClient {
     }
 
     fn available_models(&self) -> Vec<String> {
-        vec![
-            "claude-3-5-sonnet-20241022".to_string(),
-            "claude-3-5-haiku-20241022".to_string(),
-            "claude-3-opus-20240229".to_string(),
-            "claude-3-sonnet-20240229".to_string(),
-            "claude-3-haiku-20240307".to_string(),
-        ]
+        self.provider().models().iter().map(|m| m.to_string()).collect()
     }
-}
 
-/// OpenAI client
-pub struct OpenAIClient {
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let request = self
+            .client
+            .get(format!("{}/models", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01");
+        let request = with_extra_headers(request, &self.extra_headers, &HashMap::new());
+        let ids = fetch_model_ids(request, |id| self.supports_model(id)).await?;
+        Ok(ids.into_iter().map(|id| ModelInfo::new(id, LlmProvider::Anthropic)).collect())
+    }
+
+    /// Streams the text deltas of the Messages API's server-sent events,
+    /// starting with the prefill when there is one
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        let permit = self.limiter.acquire().await;
+        let (mut body, mut prefill) = anthropic_body(&request);
+        body["stream"] = serde_json::json!(true);
+        let response = self.post_messages(&request, &body).await?;
+
+        let model = request.model;
+        Ok(stream_lines(response, permit, move |line| {
+            let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+                return StreamLine::Skip;
+            };
+            let event: serde_json::Value = serde_json::from_str(data).unwrap_or_default();
+            match event["type"].as_str() {
+                Some("content_block_delta") => match event["delta"]["text"].as_str() {
+                    Some(text) => StreamLine::Delta(format!("{}{}", prefill.take().unwrap_or_default(), text)),
+                    None => StreamLine::Skip,
+                },
+                Some("message_stop") => StreamLine::Done,
+                // Failures after the stream opened (e.g. overloaded) arrive as events
+                Some("error") => StreamLine::Failed(anthropic_error(500, data, &model)),
+                _ => StreamLine::Skip,
+            }
+        }))
+    }
+}
+
+/// Endpoint of the hosted OpenAI API
+pub const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// OpenAI client
+///
+/// Speaks the chat completions API, so with [`OpenAIClient::with_base_url`]
+/// it also drives OpenAI-compatible servers such as vLLM or LM Studio. Such
+/// a server is taken to serve whatever model is asked for, and needs no API
+/// key unless it was started with one.
+///
+/// OpenAI has no assistant prefill, so a request ending in an assistant
+/// message fails with `LlmError::Unsupported` unless
+/// [`OpenAIClient::with_drop_prefill`] is set.
+pub struct OpenAIClient {
     api_key: String,
     base_url: String,
     client: reqwest::Client,
+    limiter: LlmConcurrencyLimiter,
+    drop_prefill: bool,
+    extra_headers: HashMap<String, String>,
 }
 
 impl OpenAIClient {
-    pub fn new(api_key: impl Into<String>) -> Self {
+    /// Client sending over an HTTP client configured from the environment
+    /// (see `HttpClientBuilder::from_env`)
+    pub fn new(api_key: impl Into<String>) -> agentic_core::Result<Self> {
+        Ok(Self::with_client(api_key, HttpClientBuilder::from_env().build()?))
+    }
+
+    fn with_client(api_key: impl Into<String>, client: reqwest::Client) -> Self {
         Self {
             api_key: api_key.into(),
-            base_url: "https://api.openai.com/v1".to_string(),
-            client: reqwest::Client::builder()
-                .timeout(Duration::from_secs(120))
-                .build()
-                .expect("Failed to create HTTP client"),
+            base_url: OPENAI_BASE_URL.to_string(),
+            client,
+            limiter: LlmConcurrencyLimiter::global().clone(),
+            drop_prefill: false,
+            extra_headers: HashMap::new(),
         }
     }
-}
 
-#[async_trait]
-impl LlmClient for OpenAIClient {
-    fn provider(&self) -> LlmProvider {
-        LlmProvider::OpenAI
+    /// Client using the API key, base URL and extra headers of `config`,
+    /// sending over the shared `http` client
+    ///
+    /// The key (`OPENAI_API_KEY`) is required only for the hosted API; with
+    /// `openai_base_url` set it may be left out.
+    pub fn from_config(config: &crate::config::LlmConfig, http: &reqwest::Client) -> agentic_core::Result<Self> {
+        let api_key = config.openai_api_key.clone().filter(|key| !key.trim().is_empty());
+        let client = match (&config.openai_base_url, api_key) {
+            (Some(url), key) => Self::with_client(key.unwrap_or_default(), http.clone()).with_base_url(url.as_str()),
+            (None, Some(key)) => Self::with_client(key, http.clone()),
+            (None, None) => {
+                return Err(agentic_core::Error::InvalidArgument("OPENAI_API_KEY is not set".to_string()))
+            }
+        };
+        Ok(client.with_extra_headers(config.extra_headers.clone()))
     }
 
-    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
-        let messages: Vec<serde_json::Value> = request.messages.iter().map(|msg| {
-            serde_json::json!({
-                "role": match msg.role {
-                    MessageRole::System => "system",
-                    MessageRole::User => "user",
-                    MessageRole::Assistant => "assistant",
-                },
-                "content": msg.content,
-            })
-        }).collect();
+    /// Send calls to an OpenAI-compatible server at `url` (e.g.
+    /// `http://localhost:8000/v1`) instead of the hosted API
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
 
-        let mut body = serde_json::json!({
-            "model": request.model,
-            "messages": messages,
-        });
+    /// Talking to a server other than the hosted OpenAI API
+    pub fn is_compatible_server(&self) -> bool {
+        self.base_url != OPENAI_BASE_URL
+    }
 
-        if let Some(max_tokens) = request.max_tokens {
-            body["max_tokens"] = serde_json::json!(max_tokens);
+    /// `builder` with the bearer token, unless the client has no key
+    fn authorized(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if self.api_key.is_empty() {
+            builder
+        } else {
+            builder.header("Authorization", format!("Bearer {}", self.api_key))
         }
+    }
 
-        if let Some(temp) = request.temperature {
-            body["temperature"] = serde_json::json!(temp);
-        }
+    /// Silently drop a trailing assistant message instead of failing
+    pub fn with_drop_prefill(mut self, drop: bool) -> Self {
+        self.drop_prefill = drop;
+        self
+    }
 
-        if let Some(top_p) = request.top_p {
-            body["top_p"] = serde_json::json!(top_p);
-        }
+    /// Use a shared, pre-configured HTTP client (see `HttpClientBuilder`)
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Share a concurrency limiter other than the global one
+    pub fn with_concurrency_limiter(mut self, limiter: LlmConcurrencyLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Send `headers` on every call (see `LlmConfig::extra_headers`)
+    pub fn with_extra_headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.extra_headers = headers;
+        self
+    }
 
-        if !request.stop_sequences.is_empty() {
-            body["stop"] = serde_json::json!(request.stop_sequences);
+    /// Fail on a trailing assistant message, or drop it when so configured
+    fn handle_prefill(&self, request: &mut LlmRequest) -> Result<()> {
+        if request.prefill().is_some() {
+            if !self.drop_prefill {
+                return Err(LlmError::Unsupported("assistant prefill".to_string()));
+            }
+            debug!("Dropping assistant prefill, not supported by OpenAI");
+            request.messages.pop();
         }
+        Ok(())
+    }
 
-        let response = self.client
-            .post(format!("{}/chat/completions", self.base_url))
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("content-type", "application/json")
-            .json(&body)
+    /// POST `body` to the chat completions endpoint, failing with a typed
+    /// error unless the server accepted it
+    async fn post_chat(&self, request: &LlmRequest, body: &serde_json::Value) -> Result<reqwest::Response> {
+        let http_request = self
+            .authorized(self.client.post(format!("{}/chat/completions", self.base_url)))
+            .header("content-type", "application/json");
+        let response = with_extra_headers(http_request, &self.extra_headers, &request.extra_headers)
+            .json(body)
             .send()
             .await
-            .map_err(|e| LlmError::NetworkError(e.to_string()))?;
+            .map_err(LlmError::from_send)?;
 
         if !response.status().is_success() {
-            let status = response.status();
+            let status = response.status().as_u16();
             let error_text = response.text().await.unwrap_or_default();
-            return Err(LlmError::ApiError(format!("HTTP {}: {}", status, error_text)));
+            return Err(openai_error(status, &error_text, &request.model));
+        }
+        Ok(response)
+    }
+}
+
+/// Chat completions body for `request`
+fn openai_body(request: &LlmRequest) -> serde_json::Value {
+    let messages: Vec<serde_json::Value> = request.messages.iter().map(|msg| {
+        let mut message = serde_json::json!({
+            "role": match msg.role {
+                MessageRole::System => "system",
+                MessageRole::User => "user",
+                MessageRole::Assistant => "assistant",
+                MessageRole::Tool => "tool",
+            },
+            "content": msg.content,
+        });
+        if let Some(call_id) = &msg.tool_call_id {
+            message["tool_call_id"] = serde_json::json!(call_id);
         }
+        if !msg.tool_calls.is_empty() {
+            let calls: Vec<serde_json::Value> = msg.tool_calls.iter().map(|call| {
+                serde_json::json!({
+                    "id": call.id,
+                    "type": "function",
+                    "function": {"name": call.name, "arguments": call.input.to_string()},
+                })
+            }).collect();
+            message["tool_calls"] = serde_json::json!(calls);
+            if msg.content.is_empty() {
+                message["content"] = serde_json::Value::Null;
+            }
+        }
+        message
+    }).collect();
+
+    let mut body = serde_json::json!({
+        "model": request.model,
+        "messages": messages,
+    });
+
+    if let Some(max_tokens) = request.max_tokens {
+        body["max_tokens"] = serde_json::json!(max_tokens);
+    }
+
+    if let Some(temp) = request.temperature {
+        body["temperature"] = serde_json::json!(temp);
+    }
+
+    if let Some(top_p) = request.top_p {
+        body["top_p"] = serde_json::json!(top_p);
+    }
+
+    if !request.stop_sequences.is_empty() {
+        body["stop"] = serde_json::json!(request.stop_sequences);
+    }
+
+    if !request.tools.is_empty() {
+        let tools: Vec<serde_json::Value> = request.tools.iter().map(|tool| {
+            serde_json::json!({
+                "type": "function",
+                "function": {
+                    "name": tool.name,
+                    "description": tool.description,
+                    "parameters": tool.input_schema,
+                },
+            })
+        }).collect();
+        body["tools"] = serde_json::json!(tools);
+    }
+
+    body
+}
+
+#[async_trait]
+impl LlmClient for OpenAIClient {
+    fn provider(&self) -> LlmProvider {
+        LlmProvider::OpenAI
+    }
+
+    #[instrument(
+        name = "llm.complete",
+        skip(self, request),
+        fields(
+            provider = "openai",
+            model = %request.model,
+            prompt_tokens = tracing::field::Empty,
+            completion_tokens = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            estimated_cost = tracing::field::Empty,
+        )
+    )]
+    async fn complete(&self, mut request: LlmRequest) -> Result<LlmResponse> {
+        self.handle_prefill(&mut request)?;
+        let _permit = self.limiter.acquire().await;
+        let started = Instant::now();
+        let body = openai_body(&request);
+        let response = self.post_chat(&request, &body).await?;
 
         let response_json: serde_json::Value = response.json().await
             .map_err(|e| LlmError::SerializationError(e.to_string()))?;
@@ -365,46 +1378,176 @@ impl LlmClient for OpenAIClient {
             completion_tokens: response_json["usage"]["completion_tokens"].as_u64().unwrap_or(0) as usize,
             total_tokens: response_json["usage"]["total_tokens"].as_u64().unwrap_or(0) as usize,
         };
+        record_completion(&usage, &request.model, started);
 
+        let finish_reason = response_json["choices"][0]["finish_reason"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string();
         Ok(LlmResponse {
             content,
             model: request.model,
             usage,
-            finish_reason: response_json["choices"][0]["finish_reason"]
-                .as_str()
-                .unwrap_or("unknown")
-                .to_string(),
+            truncated: is_truncation(&finish_reason),
+            finish_reason,
+            metadata: openai_metadata(&response_json),
         })
     }
 
     fn supports_model(&self, model: &str) -> bool {
-        model.starts_with("gpt-") || model.starts_with("o1-")
+        self.is_compatible_server() || model.starts_with("gpt-") || model.starts_with("o1-")
     }
 
     fn available_models(&self) -> Vec<String> {
-        vec![
-            "gpt-4o".to_string(),
-            "gpt-4o-mini".to_string(),
-            "gpt-4-turbo".to_string(),
-            "gpt-4".to_string(),
-            "gpt-3.5-turbo".to_string(),
-            "o1-preview".to_string(),
-            "o1-mini".to_string(),
-        ]
+        self.provider().models().iter().map(|m| m.to_string()).collect()
+    }
+
+    /// Chat models only; embedding, audio and image models are left out
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let request = self.authorized(self.client.get(format!("{}/models", self.base_url)));
+        let request = with_extra_headers(request, &self.extra_headers, &HashMap::new());
+        let ids = fetch_model_ids(request, |id| self.supports_model(id)).await?;
+        Ok(ids.into_iter().map(|id| ModelInfo::new(id, LlmProvider::OpenAI)).collect())
+    }
+
+    /// Streams the content deltas of the server-sent `chat.completion.chunk`
+    /// events until `data: [DONE]`
+    async fn complete_stream(&self, mut request: LlmRequest) -> Result<LlmStream> {
+        self.handle_prefill(&mut request)?;
+        let permit = self.limiter.acquire().await;
+        let mut body = openai_body(&request);
+        body["stream"] = serde_json::json!(true);
+        let response = self.post_chat(&request, &body).await?;
+
+        let model = request.model;
+        Ok(stream_lines(response, permit, move |line| {
+            let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+                return StreamLine::Skip;
+            };
+            if data == "[DONE]" {
+                return StreamLine::Done;
+            }
+            let chunk: serde_json::Value = serde_json::from_str(data).unwrap_or_default();
+            if chunk.get("error").is_some() {
+                return StreamLine::Failed(openai_error(500, data, &model));
+            }
+            match chunk["choices"][0]["delta"]["content"].as_str() {
+                Some(text) if !text.is_empty() => StreamLine::Delta(text.to_string()),
+                _ => StreamLine::Skip,
+            }
+        }))
     }
 }
 
+/// Typed error for a failed chat completions call
+///
+/// OpenAI answers `{"error": {"message": ..., "code": ...}}`; compatible
+/// servers often put `message` at the top level instead.
+fn openai_error(status: u16, body: &str, model: &str) -> LlmError {
+    let parsed: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+    let code = parsed["error"]["code"].as_str().unwrap_or_default();
+    let message = parsed["error"]["message"]
+        .as_str()
+        .or_else(|| parsed["message"].as_str())
+        .unwrap_or(body)
+        .to_string();
+
+    match (status, code) {
+        (401 | 403, _) | (_, "invalid_api_key") => LlmError::InvalidApiKey,
+        // Out of credit: waiting won't help
+        (_, "insufficient_quota") => LlmError::ApiError(format!("HTTP {}: {}", status, message)),
+        (429, _) | (_, "rate_limit_exceeded") => LlmError::RateLimitExceeded(message),
+        (404, _) | (_, "model_not_found") if message.contains("model") => LlmError::UnsupportedModel(model.to_string()),
+        (400 | 413 | 422, _) | (_, "context_length_exceeded") => {
+            context_length_exceeded(&message).unwrap_or(LlmError::InvalidRequest(message))
+        }
+        (500.., _) => LlmError::ServerError { status, message },
+        _ => LlmError::ApiError(format!("HTTP {}: {}", status, message)),
+    }
+}
+
+/// `This model's maximum context length is 8192 tokens. However, your
+/// messages resulted in 9000 tokens.` (OpenAI), or `... you requested 9000
+/// tokens` (vLLM)
+fn context_length_exceeded(message: &str) -> Option<LlmError> {
+    let number_after = |marker: &str| -> Option<usize> {
+        let (_, rest) = message.split_once(marker)?;
+        rest.split_whitespace().next()?.parse().ok()
+    };
+    let max = number_after("maximum context length is ")?;
+    let requested = number_after("resulted in ").or_else(|| number_after("you requested "))?;
+    Some(LlmError::TokenLimitExceeded { max, requested })
+}
+
+/// Client for `provider` built from the keys, endpoints and headers of
+/// `config`, sending over the shared `http` client (see `HttpClientBuilder`)
+///
+/// Calls to real providers wait on the global [`RateLimiter`].
+pub fn client_from_config(
+    provider: LlmProvider,
+    config: &crate::config::LlmConfig,
+    http: &reqwest::Client,
+) -> agentic_core::Result<std::sync::Arc<dyn LlmClient>> {
+    let client: std::sync::Arc<dyn LlmClient> = match provider {
+        LlmProvider::Anthropic => std::sync::Arc::new(AnthropicClient::from_config(config, http)?),
+        LlmProvider::OpenAI => std::sync::Arc::new(OpenAIClient::from_config(config, http)?),
+        // Rate limited per HTTP call, so pulling a missing model holds no permit
+        LlmProvider::Ollama => {
+            let client = OllamaClient::from_config(config, http)?.with_rate_limiter(RateLimiter::global().clone());
+            return Ok(std::sync::Arc::new(client));
+        }
+        LlmProvider::Mock => return Ok(std::sync::Arc::new(MockLlmClient::default())),
+    };
+    Ok(std::sync::Arc::new(RateLimitedLlmClient::new(client, RateLimiter::global().clone())))
+}
+
 /// Mock client for testing
 pub struct MockLlmClient {
     pub response: String,
+    /// Replies handed out one per call before falling back to `response`
+    script: std::sync::Mutex<std::collections::VecDeque<String>>,
+    latency: Duration,
+    chunk_delay: Duration,
+    limiter: LlmConcurrencyLimiter,
 }
 
 impl MockLlmClient {
     pub fn new(response: impl Into<String>) -> Self {
         Self {
             response: response.into(),
+            script: Default::default(),
+            latency: Duration::ZERO,
+            chunk_delay: Duration::ZERO,
+            limiter: LlmConcurrencyLimiter::global().clone(),
         }
     }
+
+    /// Answer the next calls with `responses`, in order, then with `response`
+    pub fn with_script<S: Into<String>>(self, responses: impl IntoIterator<Item = S>) -> Self {
+        *self.script.lock().unwrap() = responses.into_iter().map(Into::into).collect();
+        self
+    }
+
+    fn next_response(&self) -> String {
+        self.script.lock().unwrap().pop_front().unwrap_or_else(|| self.response.clone())
+    }
+
+    /// Simulate a slow provider by sleeping before each response
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    pub fn with_concurrency_limiter(mut self, limiter: LlmConcurrencyLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Pause between the word chunks sent by `complete_stream`
+    pub fn with_chunk_delay(mut self, delay: Duration) -> Self {
+        self.chunk_delay = delay;
+        self
+    }
 }
 
 impl Default for MockLlmClient {
@@ -419,16 +1562,42 @@ impl LlmClient for MockLlmClient {
         LlmProvider::Mock
     }
 
+    #[instrument(
+        name = "llm.complete",
+        skip(self, request),
+        fields(
+            provider = "mock",
+            model = %request.model,
+            prompt_tokens = tracing::field::Empty,
+            completion_tokens = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            estimated_cost = tracing::field::Empty,
+        )
+    )]
     async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        let _permit = self.limiter.acquire().await;
+        let started = Instant::now();
+        let usage = TokenUsage {
+            prompt_tokens: 10,
+            completion_tokens: 20,
+            total_tokens: 30,
+        };
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+        record_completion(&usage, &request.model, started);
+
+        let metadata = HashMap::from([
+            (META_SERVED_MODEL.to_string(), request.model.clone()),
+            (META_SYSTEM_FINGERPRINT.to_string(), "mock".to_string()),
+        ]);
         Ok(LlmResponse {
-            content: self.response.clone(),
+            content: self.next_response(),
             model: request.model,
-            usage: TokenUsage {
-                prompt_tokens: 10,
-                completion_tokens: 20,
-                total_tokens: 30,
-            },
+            usage,
             finish_reason: "stop".to_string(),
+            truncated: false,
+            metadata,
         })
     }
 
@@ -437,6 +1606,769 @@ impl LlmClient for MockLlmClient {
     }
 
     fn available_models(&self) -> Vec<String> {
-        vec!["mock-model".to_string()]
+        self.provider().models().iter().map(|m| m.to_string()).collect()
+    }
+
+    /// Streams the canned response one word (with its trailing space) at a time
+    async fn complete_stream(&self, _request: LlmRequest) -> Result<LlmStream> {
+        let permit = self.limiter.acquire().await;
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+
+        let chunks: Vec<String> = self.next_response().split_inclusive(' ').map(String::from).collect();
+        let delay = self.chunk_delay;
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        tokio::spawn(async move {
+            let _permit = permit;
+            for chunk in chunks {
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+                // The receiver was dropped: stop producing
+                if tx.send(Ok(chunk)).await.is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    fn is_mock(&self) -> bool {
+        true
+    }
+}
+
+/// Client whose inner client can be replaced while it is shared, so
+/// components built around it follow a swap without being rebuilt
+pub struct SwappableLlmClient {
+    inner: std::sync::RwLock<std::sync::Arc<dyn LlmClient>>,
+}
+
+impl SwappableLlmClient {
+    pub fn new(inner: std::sync::Arc<dyn LlmClient>) -> Self {
+        Self { inner: std::sync::RwLock::new(inner) }
+    }
+
+    /// Client calls go to now
+    pub fn get(&self) -> std::sync::Arc<dyn LlmClient> {
+        self.inner.read().unwrap().clone()
+    }
+
+    /// Send calls made from now on to `inner`
+    pub fn set(&self, inner: std::sync::Arc<dyn LlmClient>) {
+        *self.inner.write().unwrap() = inner;
+    }
+}
+
+#[async_trait]
+impl LlmClient for SwappableLlmClient {
+    fn provider(&self) -> LlmProvider {
+        self.get().provider()
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        self.get().complete(request).await
+    }
+
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        self.get().complete_stream(request).await
+    }
+
+    fn supports_model(&self, model: &str) -> bool {
+        self.get().supports_model(model)
+    }
+
+    fn available_models(&self) -> Vec<String> {
+        self.get().available_models()
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.get().list_models().await
+    }
+
+    fn is_mock(&self) -> bool {
+        self.get().is_mock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::ScriptedLlmClient;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing_subscriber::layer::{Context, SubscriberExt};
+    use tracing_subscriber::Layer;
+
+    /// Collects every field recorded on any span, as strings
+    #[derive(Clone, Default)]
+    struct FieldCollector(Arc<Mutex<HashMap<String, String>>>);
+
+    impl Visit for FieldCollector {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.lock().unwrap().insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    impl<S: tracing::Subscriber> Layer<S> for FieldCollector {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            attrs.record(&mut self.clone());
+        }
+
+        fn on_record(&self, _id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+            values.record(&mut self.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_complete_span_records_usage_and_cost() {
+        let collector = FieldCollector::default();
+        let subscriber = tracing_subscriber::registry().with(collector.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let client = MockLlmClient::default();
+        client.complete(LlmRequest::new("gpt-4o").add_message(Message::user("hi"))).await.unwrap();
+
+        let fields = collector.0.lock().unwrap();
+        assert_eq!(fields["provider"], "\"mock\"");
+        assert_eq!(fields["model"], "gpt-4o");
+        assert_eq!(fields["prompt_tokens"], "10");
+        assert_eq!(fields["completion_tokens"], "20");
+        assert!(fields.contains_key("latency_ms"));
+        // 10 * 2.5 + 20 * 10.0 per million tokens
+        assert_eq!(fields["estimated_cost"], "0.000225");
+        assert!(!fields.keys().any(|k| k.contains("key")));
+    }
+
+    #[test]
+    fn test_clients_are_object_safe() {
+        let clients: Vec<Arc<dyn LlmClient>> = vec![
+            Arc::new(AnthropicClient::new("test-key").unwrap()),
+            Arc::new(OpenAIClient::new("test-key").unwrap()),
+            Arc::new(OllamaClient::new().unwrap()),
+            Arc::new(MockLlmClient::default()),
+        ];
+
+        let providers: Vec<LlmProvider> = clients.iter().map(|c| c.provider()).collect();
+        assert_eq!(providers, LlmProvider::all());
+
+        let mocks: Vec<bool> = clients.iter().map(|c| c.is_mock()).collect();
+        assert_eq!(mocks, [false, false, false, true]);
+    }
+
+    #[test]
+    fn test_builder_matches_manual_construction() {
+        let manual = LlmRequest::new("claude-3-5-sonnet-20241022")
+            .with_system("You are terse.")
+            .add_message(Message::user("Summarize this"))
+            .add_message(Message::assistant("Sure"))
+            .with_temperature(0.2)
+            .with_max_tokens(512);
+
+        let built = LlmRequest::builder()
+            .model("claude-3-5-sonnet-20241022")
+            .system("You are terse.")
+            .user("Summarize this")
+            .assistant("Sure")
+            .temperature(0.2)
+            .max_tokens(512)
+            .build()
+            .unwrap();
+        assert_eq!(built, manual);
+
+        // Defaults match LlmRequest::new
+        let minimal = LlmRequest::builder().model("gpt-4o").user("hi").build().unwrap();
+        assert_eq!(minimal, LlmRequest::new("gpt-4o").add_message(Message::user("hi")));
+    }
+
+    #[test]
+    fn test_builder_validates() {
+        assert!(LlmRequest::builder().user("hi").build().is_err());
+        assert!(LlmRequest::builder().model("gpt-4o").system("only system").build().is_err());
+        assert!(LlmRequest::builder().model("gpt-4o").user("hi").temperature(3.0).build().is_err());
+        assert!(LlmRequest::builder().model("gpt-4o").user("hi").max_tokens(0).build().is_err());
+
+        let tool = agentic_core::Tool::new("search", "search", "Web search", "data_access");
+        let request = LlmRequest::builder().model("gpt-4o").user("hi").tool(&tool).build().unwrap();
+        assert_eq!(request.tools[0].name, "search");
+    }
+
+    #[test]
+    fn test_provider_parsing() {
+        assert_eq!("anthropic".parse::<LlmProvider>().unwrap(), LlmProvider::Anthropic);
+        assert_eq!("OpenAI".parse::<LlmProvider>().unwrap(), LlmProvider::OpenAI);
+        assert!(matches!(
+            "cohere".parse::<LlmProvider>(),
+            Err(agentic_core::Error::InvalidArgument(_))
+        ));
+
+        for provider in LlmProvider::all() {
+            assert_eq!(provider.as_str().parse::<LlmProvider>().unwrap(), *provider);
+            assert!(!provider.default_model().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_unknown_model_costs_nothing() {
+        let usage = TokenUsage { prompt_tokens: 1000, completion_tokens: 1000, total_tokens: 2000 };
+        assert_eq!(usage.estimated_cost("local-llama"), 0.0);
+        assert!(usage.estimated_cost("claude-3-opus-20240229") > usage.estimated_cost("claude-3-haiku-20240307"));
+    }
+
+    #[tokio::test]
+    async fn test_truncated_response_is_continued() {
+        let client = ScriptedLlmClient::with_turns([(r#"{"name": "Churn"#, "length"), (r#" Radar", "score": 7}"#, "stop")]);
+        let request = LlmRequest::new("mock-model").add_message(Message::user("Describe it as JSON"));
+
+        let response = client
+            .complete_with_continuation(request, DEFAULT_MAX_CONTINUATIONS)
+            .await
+            .unwrap();
+
+        assert!(!response.truncated);
+        assert_eq!(response.content, r#"{"name": "Churn Radar", "score": 7}"#);
+        assert_eq!(response.usage.total_tokens, 30);
+
+        let requests = client.requests();
+        assert_eq!(requests.len(), 2);
+        let continuation = &requests[1].messages;
+        assert_eq!(continuation[1], Message::assistant(r#"{"name": "Churn"#));
+        assert_eq!(continuation[2].role, MessageRole::User);
+    }
+
+    #[tokio::test]
+    async fn test_truncated_prefill_is_continued_in_place() {
+        let client = ScriptedLlmClient::with_turns([(r#""name": "Churn"#, "length"), (r#" Radar"}"#, "stop")]);
+        let request = LlmRequest::new("mock-model")
+            .add_message(Message::user("Describe it as JSON"))
+            .add_message(Message::assistant("{"));
+
+        let response = client.complete_with_continuation(request, 1).await.unwrap();
+
+        assert_eq!(response.content, r#""name": "Churn Radar"}"#);
+        let continuation = &client.requests()[1].messages;
+        assert_eq!(continuation.len(), 2);
+        assert_eq!(continuation[1], Message::assistant(r#"{"name": "Churn"#));
+    }
+
+    #[tokio::test]
+    async fn test_echoed_prefill_is_not_repeated_by_continuation() {
+        let client = ScriptedLlmClient::with_turns([(r#"{"name": "Churn"#, "max_tokens"), (r#"{"name": "Churn Radar"}"#, "end_turn")]);
+        let request = LlmRequest::new("mock-model")
+            .add_message(Message::user("Describe it as JSON"))
+            .add_message(Message::assistant("{"));
+
+        let response = client.complete_with_continuation(request, 1).await.unwrap();
+
+        assert_eq!(response.content, r#"{"name": "Churn Radar"}"#);
+        assert_eq!(client.requests()[1].prefill(), Some(r#"{"name": "Churn"#));
+    }
+
+    #[tokio::test]
+    async fn test_continuation_gives_up_after_limit() {
+        let client = ScriptedLlmClient::with_turns([("[1,", "max_tokens"), ("2,", "max_tokens")]);
+        let request = LlmRequest::new("mock-model").add_message(Message::user("List numbers"));
+
+        let response = client.complete_with_continuation(request, 1).await.unwrap();
+
+        assert!(response.truncated);
+        assert_eq!(response.content, "[1,2,");
+        assert_eq!(client.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_dropping_complete_releases_permit() {
+        let limiter = LlmConcurrencyLimiter::new(1);
+        let client = MockLlmClient::default()
+            .with_latency(Duration::from_secs(30))
+            .with_concurrency_limiter(limiter.clone());
+        let request = LlmRequest::new("mock-model").add_message(Message::user("slow"));
+
+        let call = tokio::time::timeout(Duration::from_millis(20), client.complete(request));
+        assert!(call.await.is_err());
+
+        assert_eq!(limiter.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_mock_streams_word_chunks() {
+        let client = MockLlmClient::new("streamed reply here");
+        let request = LlmRequest::new("mock-model").add_message(Message::user("hi"));
+
+        let mut stream = client.complete_stream(request).await.unwrap();
+        let mut chunks = Vec::new();
+        while let Some(chunk) = stream.recv().await {
+            chunks.push(chunk.unwrap());
+        }
+
+        assert_eq!(chunks, vec!["streamed ", "reply ", "here"]);
+    }
+
+    #[tokio::test]
+    async fn test_swappable_client_follows_a_swap() {
+        let client = Arc::new(SwappableLlmClient::new(Arc::new(MockLlmClient::new("before"))));
+        let shared: Arc<dyn LlmClient> = client.clone();
+        let request = LlmRequest::new("mock-model").add_message(Message::user("hi"));
+        assert_eq!(shared.complete(request.clone()).await.unwrap().content, "before");
+
+        client.set(Arc::new(MockLlmClient::new("after")));
+        assert_eq!(shared.complete(request).await.unwrap().content, "after");
+    }
+
+    #[tokio::test]
+    async fn test_dropping_stream_releases_permit() {
+        let limiter = LlmConcurrencyLimiter::new(1);
+        let client = MockLlmClient::new("a b c d")
+            .with_chunk_delay(Duration::from_millis(10))
+            .with_concurrency_limiter(limiter.clone());
+        let request = LlmRequest::new("mock-model").add_message(Message::user("hi"));
+
+        let mut stream = client.complete_stream(request).await.unwrap();
+        assert_eq!(stream.recv().await.unwrap().unwrap(), "a ");
+        drop(stream);
+
+        // The producer notices on its next send and exits
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(limiter.in_flight(), 0);
+    }
+
+    /// Answer one HTTP request with `reply`; resolves to the JSON body received
+    async fn serve_json_once(reply: serde_json::Value) -> (String, tokio::sync::oneshot::Receiver<serde_json::Value>) {
+        let (url, received) = serve_request_once(reply).await;
+        let (body_tx, body_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            if let Ok((_, body)) = received.await {
+                let _ = body_tx.send(body);
+            }
+        });
+        (url, body_rx)
+    }
+
+    /// Like [`serve_json_once`], also resolving to the request head (request
+    /// line and headers); a request without a body resolves to `Null`
+    async fn serve_request_once(
+        reply: serde_json::Value,
+    ) -> (String, tokio::sync::oneshot::Receiver<(String, serde_json::Value)>) {
+        serve_body_once("application/json", reply.to_string()).await
+    }
+
+    /// Like [`serve_request_once`], answering with `reply` as `content_type`
+    async fn serve_body_once(
+        content_type: &'static str,
+        reply: String,
+    ) -> (String, tokio::sync::oneshot::Receiver<(String, serde_json::Value)>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (body_tx, body_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut received = Vec::new();
+            let mut buf = [0u8; 4096];
+            let (head, body) = loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                received.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&received).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length: usize = head
+                        .lines()
+                        .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse().unwrap()))
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break (head.to_string(), body.to_string());
+                    }
+                }
+            };
+            let _ = body_tx.send((head, serde_json::from_str(&body).unwrap_or_default()));
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                content_type,
+                reply.len(),
+                reply
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+        (format!("http://{}", addr), body_rx)
+    }
+
+    /// Every delta of `stream` until it closes
+    async fn collect_stream(mut stream: LlmStream) -> Vec<Result<String>> {
+        let mut deltas = Vec::new();
+        while let Some(delta) = stream.recv().await {
+            deltas.push(delta);
+        }
+        deltas
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_stream_sends_text_deltas() {
+        let events = [
+            r#"{"type": "message_start", "message": {"usage": {"input_tokens": 8}}}"#,
+            r#"{"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "\"Ch"}}"#,
+            r#"{"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "urn\"}"}}"#,
+            r#"{"type": "message_stop"}"#,
+        ];
+        let sse: String = events.iter().map(|data| format!("event: x\ndata: {}\n\n", data)).collect();
+        let (url, received) = serve_body_once("text/event-stream", sse).await;
+        let client = AnthropicClient::new("test-key").unwrap()
+            .with_base_url(url)
+            .with_concurrency_limiter(LlmConcurrencyLimiter::new(1));
+        let request = LlmRequest::new("claude-3-5-haiku-20241022")
+            .add_message(Message::user("Name the idea as JSON"))
+            .add_message(Message::assistant("{\"name\": "));
+
+        let deltas = collect_stream(client.complete_stream(request).await.unwrap()).await;
+        let deltas: Vec<String> = deltas.into_iter().map(|d| d.unwrap()).collect();
+        assert_eq!(deltas, vec!["{\"name\":\"Ch", "urn\"}"]);
+        let (_, body) = received.await.unwrap();
+        assert_eq!(body["stream"], true);
+    }
+
+    #[tokio::test]
+    async fn test_openai_stream_ends_at_done_and_reports_errors() {
+        let chunk = |text: &str| serde_json::json!({"choices": [{"delta": {"content": text}}]}).to_string();
+        let sse = format!("data: {}\n\ndata: {}\n\ndata: [DONE]\n\n", chunk("Hel"), chunk("lo"));
+        let (url, received) = serve_body_once("text/event-stream", sse).await;
+        let config = crate::config::LlmConfig { openai_base_url: Some(url), ..Default::default() };
+        let client = OpenAIClient::from_config(&config, &reqwest::Client::new()).unwrap();
+        let request = LlmRequest::new("gpt-4o-mini").add_message(Message::user("hi"));
+
+        let deltas = collect_stream(client.complete_stream(request.clone()).await.unwrap()).await;
+        let deltas: Vec<String> = deltas.into_iter().map(|d| d.unwrap()).collect();
+        assert_eq!(deltas, vec!["Hel", "lo"]);
+        let (_, body) = received.await.unwrap();
+        assert_eq!(body["stream"], true);
+
+        // A failure after the stream opened ends it with a typed error
+        let error = serde_json::json!({"error": {"code": "server_error", "message": "boom"}});
+        let sse = format!("data: {}\n\ndata: {}\n\n", chunk("Hel"), error);
+        let (url, _) = serve_body_once("text/event-stream", sse).await;
+        let config = crate::config::LlmConfig { openai_base_url: Some(url), ..Default::default() };
+        let client = OpenAIClient::from_config(&config, &reqwest::Client::new()).unwrap();
+        let deltas = collect_stream(client.complete_stream(request).await.unwrap()).await;
+        assert_eq!(deltas.len(), 2);
+        assert!(matches!(deltas[1], Err(LlmError::ServerError { .. })), "{:?}", deltas[1]);
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_prefill_is_sent_and_merged() {
+        let (url, body) = serve_json_once(serde_json::json!({
+            "content": [{"type": "text", "text": "\"name\": \"Churn\"}"}],
+            "usage": {"input_tokens": 12, "output_tokens": 6},
+            "stop_reason": "end_turn",
+        }))
+        .await;
+        let client = AnthropicClient::new("test-key").unwrap()
+            .with_base_url(url)
+            .with_concurrency_limiter(LlmConcurrencyLimiter::new(1));
+        let request = LlmRequest::new("claude-3-5-haiku-20241022")
+            .add_message(Message::user("Name the idea as JSON"))
+            .add_message(Message::assistant("{ "));
+
+        let response = client.complete(request).await.unwrap();
+
+        let sent = body.await.unwrap();
+        let last = sent["messages"].as_array().unwrap().last().unwrap().clone();
+        assert_eq!(last, serde_json::json!({"role": "assistant", "content": "{"}));
+        assert_eq!(response.content, r#"{"name": "Churn"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_sends_system_and_sampling_settings() {
+        let (url, body) = serve_json_once(serde_json::json!({
+            "content": [{"type": "text", "text": "Churn "}, {"type": "text", "text": "Radar"}],
+            "usage": {"input_tokens": 20, "output_tokens": 4},
+            "stop_reason": "stop_sequence",
+        }))
+        .await;
+        let client = AnthropicClient::new("test-key").unwrap()
+            .with_base_url(url)
+            .with_concurrency_limiter(LlmConcurrencyLimiter::new(1));
+        let request = LlmRequest::builder()
+            .model("claude-3-5-haiku-20241022")
+            .system("You name products.")
+            .user("Name the idea")
+            .temperature(0.2)
+            .max_tokens(64)
+            .stop_sequence("\n")
+            .build()
+            .unwrap()
+            .add_message(Message::system("Answer in two words."));
+
+        let response = client.complete(request).await.unwrap();
+
+        let sent = body.await.unwrap();
+        assert_eq!(sent["system"], "You name products.\n\nAnswer in two words.");
+        assert_eq!(sent["max_tokens"], 64);
+        assert_eq!(sent["stop_sequences"], serde_json::json!(["\n"]));
+        assert!((sent["temperature"].as_f64().unwrap() - 0.2).abs() < 1e-6);
+        assert_eq!(response.content, "Churn Radar");
+        assert_eq!(response.usage.total_tokens, 24);
+    }
+
+    #[test]
+    fn test_anthropic_errors_map_to_typed_errors() {
+        let body = |kind: &str, message: &str| {
+            serde_json::json!({"type": "error", "error": {"type": kind, "message": message}}).to_string()
+        };
+        let model = "claude-3-5-haiku-20241022";
+
+        let err = anthropic_error(401, &body("authentication_error", "invalid x-api-key"), model);
+        assert!(matches!(err, LlmError::InvalidApiKey));
+        let err = anthropic_error(429, &body("rate_limit_error", "slow down"), model);
+        assert!(matches!(err, LlmError::RateLimitExceeded(_)));
+        let err = anthropic_error(404, &body("not_found_error", "model: claude-9"), model);
+        assert!(matches!(err, LlmError::UnsupportedModel(m) if m == model));
+        let err = anthropic_error(400, &body("invalid_request_error", "prompt is too long: 210000 tokens > 200000 maximum"), model);
+        assert!(matches!(err, LlmError::TokenLimitExceeded { max: 200000, requested: 210000 }));
+        let err = anthropic_error(400, &body("invalid_request_error", "temperature: out of range"), model);
+        assert!(matches!(agentic_core::Error::from(err), agentic_core::Error::InvalidArgument(_)));
+        let err = anthropic_error(529, &body("overloaded_error", "Overloaded"), model);
+        assert_eq!(err.to_string(), "Provider error: HTTP 529: Overloaded");
+        let err = anthropic_error(502, "<html>Bad Gateway</html>", model);
+        assert!(err.is_outage());
+        assert_eq!(err.to_string(), "Provider error: HTTP 502: <html>Bad Gateway</html>");
+        let err = anthropic_error(418, &body("teapot_error", "short and stout"), model);
+        assert_eq!(err.to_string(), "API request failed: HTTP 418: short and stout");
+    }
+
+    #[tokio::test]
+    async fn test_openai_compatible_server_needs_no_key() {
+        let (url, received) = serve_request_once(serde_json::json!({
+            "choices": [{"message": {"role": "assistant", "content": "Hello"}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 1, "total_tokens": 6},
+        }))
+        .await;
+        let config = crate::config::LlmConfig {
+            openai_base_url: Some(format!("{}/v1/", url)),
+            ..Default::default()
+        };
+        let client = OpenAIClient::from_config(&config, &reqwest::Client::new())
+            .unwrap()
+            .with_concurrency_limiter(LlmConcurrencyLimiter::new(1));
+        assert!(client.supports_model("meta-llama/Llama-3.1-8B-Instruct"));
+
+        let request = LlmRequest::new("meta-llama/Llama-3.1-8B-Instruct").add_message(Message::user("hi"));
+        let response = client.complete(request).await.unwrap();
+
+        assert_eq!(response.content, "Hello");
+        let (head, body) = received.await.unwrap();
+        assert!(head.starts_with("POST /v1/chat/completions"));
+        assert!(!head.to_ascii_lowercase().contains("authorization:"));
+        assert_eq!(body["model"], "meta-llama/Llama-3.1-8B-Instruct");
+
+        // The hosted API still needs a key and only serves its own models
+        assert!(OpenAIClient::from_config(&crate::config::LlmConfig::default(), &reqwest::Client::new()).is_err());
+        assert!(!OpenAIClient::new("sk-test").unwrap().supports_model("meta-llama/Llama-3.1-8B-Instruct"));
+    }
+
+    #[test]
+    fn test_openai_errors_map_to_typed_errors() {
+        let body = |code: &str, message: &str| serde_json::json!({"error": {"code": code, "message": message}}).to_string();
+        let model = "gpt-4o";
+
+        assert!(matches!(openai_error(401, &body("invalid_api_key", "Incorrect API key"), model), LlmError::InvalidApiKey));
+        assert!(matches!(openai_error(429, &body("rate_limit_exceeded", "Slow down"), model), LlmError::RateLimitExceeded(_)));
+        assert!(matches!(openai_error(429, &body("insufficient_quota", "No credit"), model), LlmError::ApiError(_)));
+        let err = openai_error(404, &body("model_not_found", "The model `gpt-9` does not exist"), model);
+        assert!(matches!(err, LlmError::UnsupportedModel(m) if m == model));
+        // vLLM style: message at the top level
+        let err = openai_error(400, r#"{"object": "error", "message": "max_tokens too large"}"#, model);
+        assert!(matches!(err, LlmError::InvalidRequest(m) if m == "max_tokens too large"));
+
+        let too_long = "This model's maximum context length is 8192 tokens. However, your messages resulted in 9000 tokens. Please reduce the length of the messages.";
+        let err = openai_error(400, &body("context_length_exceeded", too_long), model);
+        assert!(matches!(err, LlmError::TokenLimitExceeded { max: 8192, requested: 9000 }));
+        let too_long = "This model's maximum context length is 4096 tokens. However, you requested 5000 tokens (4000 in the messages, 1000 in the completion).";
+        let err = openai_error(400, &format!(r#"{{"object": "error", "message": "{}"}}"#, too_long), model);
+        assert!(matches!(err, LlmError::TokenLimitExceeded { max: 4096, requested: 5000 }));
+    }
+
+    #[tokio::test]
+    async fn test_mock_lists_its_fixed_models() {
+        let models = MockLlmClient::default().list_models().await.unwrap();
+
+        let ids: Vec<&str> = models.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, LlmProvider::Mock.models());
+        assert!(models.iter().all(|m| m.provider == LlmProvider::Mock && m.capabilities.tool_use));
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_lists_chat_models_from_provider() {
+        let (url, received) = serve_request_once(serde_json::json!({
+            "data": [{"id": "claude-3-5-haiku-20241022"}, {"id": "voyage-embed"}],
+        }))
+        .await;
+        let client = AnthropicClient::new("test-key").unwrap().with_base_url(url);
+
+        let models = client.list_models().await.unwrap();
+
+        assert_eq!(models, vec![ModelInfo::new("claude-3-5-haiku-20241022", LlmProvider::Anthropic)]);
+        assert!(models[0].capabilities.vision);
+        assert!(received.await.unwrap().0.starts_with("GET /models"));
+    }
+
+    #[tokio::test]
+    async fn test_extra_headers_reach_the_provider() {
+        let (url, received) = serve_request_once(serde_json::json!({
+            "content": [{"type": "text", "text": "ok"}],
+            "usage": {"input_tokens": 3, "output_tokens": 1},
+            "stop_reason": "end_turn",
+        }))
+        .await;
+        let configured = HashMap::from([
+            ("X-Tenant-Id".to_string(), "acme".to_string()),
+            ("X-Cost-Center".to_string(), "default".to_string()),
+        ]);
+        let client = AnthropicClient::new("test-key").unwrap()
+            .with_base_url(url)
+            .with_concurrency_limiter(LlmConcurrencyLimiter::new(1))
+            .with_extra_headers(configured);
+        let request = LlmRequest::new("claude-3-5-haiku-20241022")
+            .add_message(Message::user("hi"))
+            .with_header("X-Cost-Center", "research");
+
+        client.complete(request).await.unwrap();
+
+        let head = received.await.unwrap().0.to_ascii_lowercase();
+        assert!(head.contains("x-tenant-id: acme"));
+        assert!(head.contains("x-cost-center: research"));
+        assert!(!head.contains("x-cost-center: default"));
+
+        let logged = HashMap::from([
+            ("X-Gateway-Token".to_string(), "gw-secret".to_string()),
+            ("X-Tenant-Id".to_string(), "acme".to_string()),
+        ]);
+        let redacted = redact_headers(&logged);
+        assert_eq!((redacted["X-Gateway-Token"], redacted["X-Tenant-Id"]), ("[REDACTED]", "acme"));
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_replays_tool_round_in_order() {
+        let (url, body) = serve_json_once(serde_json::json!({
+            "content": [{"type": "text", "text": "Both are sunny."}],
+            "usage": {"input_tokens": 40, "output_tokens": 5},
+            "stop_reason": "end_turn",
+        }))
+        .await;
+        let client = AnthropicClient::new("test-key").unwrap()
+            .with_base_url(url)
+            .with_concurrency_limiter(LlmConcurrencyLimiter::new(1));
+        let call = |id: &str, city: &str| ToolCall {
+            id: id.to_string(),
+            name: "weather".to_string(),
+            input: serde_json::json!({"city": city}),
+        };
+        let request = LlmRequest::new("claude-3-5-haiku-20241022")
+            .add_message(Message::user("Weather in Oslo and Rome?"))
+            .add_message(Message::tool_calls("Checking.", vec![call("tu_1", "Oslo"), call("tu_2", "Rome")]))
+            .add_message(Message::tool_result("tu_1", "sunny"))
+            .add_message(Message::tool_result("tu_2", "sunny"));
+        assert_eq!(request.prefill(), None);
+
+        client.complete(request).await.unwrap();
+
+        let sent = body.await.unwrap();
+        let messages = sent["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[1]["role"], "assistant");
+        assert_eq!(messages[1]["content"][0], serde_json::json!({"type": "text", "text": "Checking."}));
+        assert_eq!(messages[1]["content"][2]["id"], "tu_2");
+        assert_eq!(messages[1]["content"][2]["input"], serde_json::json!({"city": "Rome"}));
+        assert_eq!(messages[2]["role"], "user");
+        let results: Vec<&str> = messages[2]["content"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|block| block["tool_use_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(results, vec!["tu_1", "tu_2"]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_script_answers_in_order_then_falls_back() {
+        let client = MockLlmClient::new("fallback").with_script(["first", "second"]);
+        let request = LlmRequest::new("mock-model").add_message(Message::user("hi"));
+
+        let mut replies = Vec::new();
+        for _ in 0..3 {
+            replies.push(client.complete(request.clone()).await.unwrap().content);
+        }
+        assert_eq!(replies, vec!["first", "second", "fallback"]);
+    }
+
+    #[tokio::test]
+    async fn test_responses_carry_reproducibility_metadata() {
+        let request = LlmRequest::new("mock-model").add_message(Message::user("hi"));
+        let first = MockLlmClient::default().complete(request.clone()).await.unwrap();
+        let second = MockLlmClient::default().complete(request).await.unwrap();
+        assert_eq!(first.metadata[META_SYSTEM_FINGERPRINT], "mock");
+        assert_eq!(first.metadata, second.metadata);
+
+        let openai = openai_metadata(&serde_json::json!({
+            "id": "chatcmpl-123",
+            "model": "gpt-4o-2024-08-06",
+            "system_fingerprint": "fp_44709d6fcb",
+            "choices": [],
+        }));
+        assert_eq!(openai[META_SYSTEM_FINGERPRINT], "fp_44709d6fcb");
+        assert_eq!(openai[META_SERVED_MODEL], "gpt-4o-2024-08-06");
+
+        // Older models omit the fingerprint
+        let openai = openai_metadata(&serde_json::json!({"id": "chatcmpl-124", "system_fingerprint": null}));
+        assert!(!openai.contains_key(META_SYSTEM_FINGERPRINT));
+        assert_eq!(openai[META_RESPONSE_ID], "chatcmpl-124");
+    }
+
+    #[tokio::test]
+    async fn test_openai_rejects_prefill_by_default() {
+        let request = LlmRequest::new("gpt-4o")
+            .add_message(Message::user("Name the idea as JSON"))
+            .add_message(Message::assistant("{"));
+
+        let err = OpenAIClient::new("test-key").unwrap().complete(request.clone()).await.unwrap_err();
+        assert!(matches!(err, LlmError::Unsupported(_)));
+        assert!(matches!(
+            agentic_core::Error::from(err),
+            agentic_core::Error::CapabilityNotSupported(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dropping_complete_aborts_http_request() {
+        use tokio::io::AsyncReadExt;
+        use tokio::net::TcpListener;
+
+        // A provider that reads the request and never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (closed_tx, closed_rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            // read() returns 0 once the client closes the connection
+            while socket.read(&mut buf).await.map(|n| n > 0).unwrap_or(false) {}
+            let _ = closed_tx.send(());
+        });
+
+        let limiter = LlmConcurrencyLimiter::new(1);
+        let client = AnthropicClient::new("test-key").unwrap()
+            .with_base_url(format!("http://{}", addr))
+            .with_concurrency_limiter(limiter.clone());
+        let request = LlmRequest::new("claude-3-haiku").add_message(Message::user("hi"));
+
+        let call = tokio::time::timeout(Duration::from_millis(200), client.complete(request));
+        assert!(call.await.is_err());
+
+        tokio::time::timeout(Duration::from_secs(5), closed_rx)
+            .await
+            .expect("connection should close when the future is dropped")
+            .unwrap();
+        assert_eq!(limiter.in_flight(), 0);
     }
 }
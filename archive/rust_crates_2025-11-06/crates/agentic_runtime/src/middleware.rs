@@ -0,0 +1,259 @@
+//! Hooks around the LLM call made by an executor
+//!
+//! [`DefaultExecutor`](crate::executor::DefaultExecutor) runs an ordered
+//! chain of [`ExecutorMiddleware`]: every `before` hook in insertion order,
+//! then the call, then every `after` hook in reverse order, so the first
+//! middleware added sees the request first and the response last. A `before`
+//! hook returning an error aborts the execution with that error. Every
+//! `after` hook runs even when an earlier one failed, so accounting hooks see
+//! each response; the first error then aborts the execution, unless it is
+//! `Error::Retryable`, which makes the executor try the call again.
+
+use crate::llm::{LlmRequest, LlmResponse, MessageRole};
+use agentic_core::{Agent, Error, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// A step run before and after each LLM call of an execution
+#[async_trait]
+pub trait ExecutorMiddleware: Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &str;
+
+    /// Inspect or rewrite the request before it is sent
+    async fn before(&self, _agent: &Agent, _request: &mut LlmRequest) -> Result<()> {
+        Ok(())
+    }
+
+    /// Inspect or rewrite the response before the executor uses it
+    async fn after(&self, _agent: &Agent, _request: &LlmRequest, _response: &mut LlmResponse) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Rejects prompts and replies that contain a blocked term
+///
+/// Matching is case-insensitive on substrings; system prompts are not checked.
+#[derive(Debug, Clone, Default)]
+pub struct ModerationMiddleware {
+    blocked_terms: Vec<String>,
+}
+
+impl ModerationMiddleware {
+    pub fn new<I, S>(blocked_terms: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            blocked_terms: blocked_terms.into_iter().map(|t| t.into().to_lowercase()).collect(),
+        }
+    }
+
+    fn check(&self, text: &str, what: &str) -> Result<()> {
+        let text = text.to_lowercase();
+        match self.blocked_terms.iter().find(|term| text.contains(term.as_str())) {
+            Some(term) => Err(Error::PolicyViolation(format!("{} contains blocked term '{}'", what, term))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutorMiddleware for ModerationMiddleware {
+    fn name(&self) -> &str {
+        "moderation"
+    }
+
+    async fn before(&self, _agent: &Agent, request: &mut LlmRequest) -> Result<()> {
+        request
+            .messages
+            .iter()
+            .filter(|m| m.role != MessageRole::System)
+            .try_for_each(|m| self.check(&m.content, "Prompt"))
+    }
+
+    async fn after(&self, _agent: &Agent, _request: &LlmRequest, response: &mut LlmResponse) -> Result<()> {
+        self.check(&response.content, "Response")
+    }
+}
+
+/// Requires the reply to be a JSON document
+///
+/// A Markdown code fence around the JSON is accepted. Anything else fails
+/// with `Error::Retryable`, so the executor asks the model again.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonOutputMiddleware;
+
+#[async_trait]
+impl ExecutorMiddleware for JsonOutputMiddleware {
+    fn name(&self) -> &str {
+        "json_output"
+    }
+
+    async fn after(&self, _agent: &Agent, _request: &LlmRequest, response: &mut LlmResponse) -> Result<()> {
+        let content = response.content.trim();
+        let body = content
+            .strip_prefix("```json")
+            .or_else(|| content.strip_prefix("```"))
+            .and_then(|rest| rest.strip_suffix("```"))
+            .unwrap_or(content);
+        serde_json::from_str::<serde_json::Value>(body)
+            .map(|_| ())
+            .map_err(|e| Error::Retryable(format!("Response is not valid JSON: {}", e)))
+    }
+}
+
+/// Token budget shared by every execution that goes through the middleware
+///
+/// Unlike the per-execution sandbox limit this one is cumulative: once
+/// `max_tokens` have been spent, further calls are refused.
+#[derive(Debug)]
+pub struct BudgetMiddleware {
+    max_tokens: usize,
+    used: AtomicUsize,
+}
+
+impl BudgetMiddleware {
+    pub fn new(max_tokens: usize) -> Self {
+        Self {
+            max_tokens,
+            used: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn tokens_used(&self) -> usize {
+        self.used.load(Ordering::SeqCst)
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.max_tokens.saturating_sub(self.tokens_used())
+    }
+}
+
+#[async_trait]
+impl ExecutorMiddleware for BudgetMiddleware {
+    fn name(&self) -> &str {
+        "budget"
+    }
+
+    async fn before(&self, _agent: &Agent, _request: &mut LlmRequest) -> Result<()> {
+        if self.remaining() == 0 {
+            return Err(Error::PolicyViolation(format!(
+                "Token budget of {} exhausted",
+                self.max_tokens
+            )));
+        }
+        Ok(())
+    }
+
+    async fn after(&self, _agent: &Agent, _request: &LlmRequest, response: &mut LlmResponse) -> Result<()> {
+        self.used.fetch_add(response.usage.total_tokens, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Call and token counts recorded by [`MetricsMiddleware`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CallMetrics {
+    pub calls: u64,
+    pub tokens: usize,
+    /// Calls per model that served them
+    pub calls_by_model: HashMap<String, u64>,
+}
+
+/// Counts completed LLM calls and the tokens they used
+#[derive(Debug, Default)]
+pub struct MetricsMiddleware {
+    metrics: Mutex<CallMetrics>,
+}
+
+impl MetricsMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn snapshot(&self) -> CallMetrics {
+        self.metrics.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl ExecutorMiddleware for MetricsMiddleware {
+    fn name(&self) -> &str {
+        "metrics"
+    }
+
+    async fn after(&self, _agent: &Agent, _request: &LlmRequest, response: &mut LlmResponse) -> Result<()> {
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.calls += 1;
+        metrics.tokens += response.usage.total_tokens;
+        *metrics.calls_by_model.entry(response.model.clone()).or_insert(0) += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{Message, TokenUsage};
+    use agentic_core::AgentRole;
+
+    fn agent() -> Agent {
+        Agent::new("Test Agent", "A test agent", AgentRole::Worker, "mock-model", "mock")
+    }
+
+    fn response(content: &str) -> LlmResponse {
+        LlmResponse {
+            content: content.to_string(),
+            model: "mock-model".to_string(),
+            usage: TokenUsage {
+                prompt_tokens: 10,
+                completion_tokens: 20,
+                total_tokens: 30,
+            },
+            finish_reason: "stop".to_string(),
+            truncated: false,
+            metadata: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_moderation_checks_prompt_and_response() {
+        let moderation = ModerationMiddleware::new(["Forbidden"]);
+        let agent = agent();
+
+        // System prompts are written by us and not checked
+        let mut request = LlmRequest::new("mock-model")
+            .with_system("Never say forbidden things")
+            .add_message(Message::user("hello"));
+        assert!(moderation.before(&agent, &mut request).await.is_ok());
+
+        let mut blocked = LlmRequest::new("mock-model").add_message(Message::user("something FORBIDDEN"));
+        assert!(matches!(
+            moderation.before(&agent, &mut blocked).await,
+            Err(Error::PolicyViolation(_))
+        ));
+
+        let mut reply = response("a forbidden reply");
+        assert!(moderation.after(&agent, &request, &mut reply).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_budget_refuses_calls_once_spent() {
+        let budget = BudgetMiddleware::new(50);
+        let agent = agent();
+        let mut request = LlmRequest::new("mock-model");
+
+        for _ in 0..2 {
+            budget.before(&agent, &mut request).await.unwrap();
+            budget.after(&agent, &request, &mut response("ok")).await.unwrap();
+        }
+
+        assert_eq!(budget.tokens_used(), 60);
+        assert!(budget.before(&agent, &mut request).await.is_err());
+    }
+}
@@ -0,0 +1,205 @@
+//! Model name resolution before a request is sent
+//!
+//! Agents may ask for a friendly name (`claude-latest`) or a model that has
+//! since been retired. [`ModelAliasMap`] rewrites those to a concrete model
+//! id, falling back per provider for models the
+//! [model catalog](agentic_core::model_catalog) doesn't list, and
+//! [`AliasedLlmClient`] applies it to every request so the provider never
+//! sees a name it would reject.
+
+use crate::llm::{LlmClient, LlmProvider, LlmRequest, LlmResponse, LlmStream, ModelInfo, Result};
+use agentic_core::model_catalog::models_for_provider;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Longest alias chain followed before giving up (guards against cycles)
+const MAX_ALIAS_HOPS: usize = 8;
+
+/// Aliases and per-provider fallback models
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModelAliasMap {
+    aliases: HashMap<String, String>,
+    fallbacks: HashMap<LlmProvider, String>,
+}
+
+impl ModelAliasMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send `model` whenever `alias` is requested
+    pub fn with_alias(mut self, alias: impl Into<String>, model: impl Into<String>) -> Self {
+        self.aliases.insert(alias.into(), model.into());
+        self
+    }
+
+    /// Model to use for `provider` when the requested one isn't listed for
+    /// it in the model catalog; without one, unknown models are sent unchanged
+    pub fn with_fallback(mut self, provider: LlmProvider, model: impl Into<String>) -> Self {
+        self.fallbacks.insert(provider, model.into());
+        self
+    }
+
+    /// Aliases from `LLM_MODEL_ALIASES` and fallbacks from `LLM_FALLBACK_MODELS`
+    ///
+    /// Both are comma-separated `name=model` pairs, e.g.
+    /// `claude-latest=claude-3-5-sonnet-20241022` and `openai=gpt-4o`.
+    /// Malformed pairs and unknown providers are skipped.
+    pub fn from_env() -> Self {
+        let pairs = |var: &str| -> Vec<(String, String)> {
+            std::env::var(var)
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+                .filter(|(k, v)| !k.is_empty() && !v.is_empty())
+                .collect()
+        };
+
+        let mut map = Self::new();
+        for (alias, model) in pairs("LLM_MODEL_ALIASES") {
+            map = map.with_alias(alias, model);
+        }
+        for (provider, model) in pairs("LLM_FALLBACK_MODELS") {
+            match provider.parse::<LlmProvider>() {
+                Ok(provider) => map = map.with_fallback(provider, model),
+                Err(_) => warn!("Ignoring fallback model for unknown provider {}", provider),
+            }
+        }
+        map
+    }
+
+    /// Follow aliases from `model`; returns `model` itself if it isn't one
+    pub fn resolve_alias<'a>(&'a self, model: &'a str) -> &'a str {
+        let mut resolved = model;
+        for _ in 0..MAX_ALIAS_HOPS {
+            match self.aliases.get(resolved) {
+                Some(next) => resolved = next,
+                None => break,
+            }
+        }
+        resolved
+    }
+
+    /// Concrete model `provider` should be asked for instead of `model`
+    pub fn resolve(&self, provider: LlmProvider, model: &str) -> String {
+        let resolved = self.resolve_alias(model);
+        if resolved != model {
+            info!("Model {} resolved to {}", model, resolved);
+        }
+
+        if models_for_provider(provider.as_str()).contains(&resolved) {
+            return resolved.to_string();
+        }
+        match self.fallbacks.get(&provider) {
+            Some(fallback) => {
+                warn!("Model {} is not in the catalog for {}, using fallback {}", resolved, provider, fallback);
+                fallback.clone()
+            }
+            None => resolved.to_string(),
+        }
+    }
+}
+
+/// Client that resolves the requested model through a [`ModelAliasMap`]
+pub struct AliasedLlmClient {
+    inner: Arc<dyn LlmClient>,
+    aliases: ModelAliasMap,
+}
+
+impl AliasedLlmClient {
+    pub fn new(inner: Arc<dyn LlmClient>, aliases: ModelAliasMap) -> Self {
+        Self { inner, aliases }
+    }
+
+    fn resolved(&self, mut request: LlmRequest) -> LlmRequest {
+        request.model = self.aliases.resolve(self.inner.provider(), &request.model);
+        request
+    }
+}
+
+#[async_trait]
+impl LlmClient for AliasedLlmClient {
+    fn provider(&self) -> LlmProvider {
+        self.inner.provider()
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        self.inner.complete(self.resolved(request)).await
+    }
+
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        self.inner.complete_stream(self.resolved(request)).await
+    }
+
+    fn supports_model(&self, model: &str) -> bool {
+        self.inner.supports_model(self.aliases.resolve_alias(model))
+            || self.aliases.fallbacks.contains_key(&self.inner.provider())
+    }
+
+    fn available_models(&self) -> Vec<String> {
+        self.inner.available_models()
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.inner.list_models().await
+    }
+
+    fn is_mock(&self) -> bool {
+        self.inner.is_mock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::Message;
+    use crate::test_support::ScriptedLlmClient;
+
+    /// Anthropic-flavoured client; its model list is no help to the fallback
+    fn anthropic() -> Arc<ScriptedLlmClient> {
+        Arc::new(ScriptedLlmClient::default().with_provider(LlmProvider::Anthropic))
+    }
+
+    fn models(client: &ScriptedLlmClient) -> Vec<String> {
+        client.requests().into_iter().map(|request| request.model).collect()
+    }
+
+    fn request(model: &str) -> LlmRequest {
+        LlmRequest::new(model).add_message(Message::user("hi"))
+    }
+
+    #[tokio::test]
+    async fn test_alias_is_resolved_before_sending() {
+        let inner = anthropic();
+        let aliases = ModelAliasMap::new().with_alias("claude-latest", "claude-3-5-sonnet-20241022");
+        let client = AliasedLlmClient::new(inner.clone(), aliases);
+
+        let response = client.complete(request("claude-latest")).await.unwrap();
+
+        assert_eq!(models(&inner), ["claude-3-5-sonnet-20241022"]);
+        assert_eq!(response.model, "claude-3-5-sonnet-20241022");
+        assert!(client.supports_model("claude-latest"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_model_uses_provider_fallback() {
+        let inner = anthropic();
+        let aliases = ModelAliasMap::new().with_fallback(LlmProvider::Anthropic, "claude-3-5-haiku-20241022");
+        let client = AliasedLlmClient::new(inner.clone(), aliases);
+
+        client.complete(request("claude-2.1")).await.unwrap();
+        client.complete(request("claude-3-5-sonnet-20241022")).await.unwrap();
+
+        assert_eq!(models(&inner), ["claude-3-5-haiku-20241022", "claude-3-5-sonnet-20241022"]);
+    }
+
+    #[test]
+    fn test_alias_cycles_terminate() {
+        let aliases = ModelAliasMap::new().with_alias("a", "b").with_alias("b", "a");
+        let resolved = aliases.resolve_alias("a");
+        assert!(resolved == "a" || resolved == "b");
+    }
+}
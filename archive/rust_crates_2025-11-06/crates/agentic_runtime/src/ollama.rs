@@ -0,0 +1,590 @@
+//! Client for a local Ollama server
+//!
+//! Ollama serves open-weight models (Llama, Mistral, Qwen, ...) over HTTP on
+//! the local machine, so agents can run without cloud API keys or network
+//! access. [`OllamaClient`] talks to its native `/api/chat` endpoint, lists
+//! the models pulled so far from `/api/tags`, and by default pulls a missing
+//! model the first time a call asks for it.
+//!
+//! Rate limit and concurrency permits are taken per `/api/chat` call rather
+//! than around the whole completion, so no permit is held while a model
+//! downloads. Streamed replies arrive as one JSON object per line.
+
+use crate::concurrency::LlmConcurrencyLimiter;
+use crate::http::HttpClientBuilder;
+use crate::llm::{
+    is_truncation, record_completion, stream_lines, with_extra_headers, LlmClient, LlmError, LlmProvider, LlmRequest,
+    LlmResponse, LlmStream, MessageRole, ModelInfo, Result, StreamLine, TokenUsage, META_SERVED_MODEL,
+};
+use crate::rate_limit::{RateLimitPermit, RateLimiter};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{info, instrument};
+
+/// Where `ollama serve` listens unless told otherwise
+pub const OLLAMA_BASE_URL: &str = "http://localhost:11434";
+
+/// Local models on modest hardware can take minutes per reply
+const REPLY_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Pulling a model downloads gigabytes, far beyond a completion's timeout
+const PULL_TIMEOUT: Duration = Duration::from_secs(3600);
+
+/// Client for models served by Ollama
+///
+/// Any model name is accepted, since whatever has been pulled locally can be
+/// served. Tool definitions are not sent; a request with tools fails with
+/// `LlmError::Unsupported`.
+pub struct OllamaClient {
+    base_url: String,
+    client: reqwest::Client,
+    limiter: LlmConcurrencyLimiter,
+    rate_limiter: Option<RateLimiter>,
+    extra_headers: HashMap<String, String>,
+    pull_on_demand: bool,
+    /// Held while pulling, so concurrent calls for a missing model download it once
+    pulling: tokio::sync::Mutex<()>,
+}
+
+impl OllamaClient {
+    /// Client for the server at [`OLLAMA_BASE_URL`], sending over an HTTP
+    /// client configured from the environment (see `HttpClientBuilder::from_env`)
+    pub fn new() -> agentic_core::Result<Self> {
+        Ok(Self::with_client(HttpClientBuilder::from_env().build()?))
+    }
+
+    fn with_client(client: reqwest::Client) -> Self {
+        Self {
+            base_url: OLLAMA_BASE_URL.to_string(),
+            client,
+            limiter: LlmConcurrencyLimiter::global().clone(),
+            rate_limiter: None,
+            extra_headers: HashMap::new(),
+            pull_on_demand: true,
+            pulling: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Client using the base URL (`OLLAMA_BASE_URL`) and extra headers of
+    /// `config`, sending over the shared `http` client
+    pub fn from_config(config: &crate::config::LlmConfig, http: &reqwest::Client) -> agentic_core::Result<Self> {
+        let client = match &config.ollama_base_url {
+            Some(url) if !url.trim().is_empty() => Self::with_client(http.clone()).with_base_url(url.as_str()),
+            _ => Self::with_client(http.clone()),
+        };
+        Ok(client.with_extra_headers(config.extra_headers.clone()))
+    }
+
+    /// Talk to the server at `url` (e.g. `http://gpu-box:11434`)
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Use a shared, pre-configured HTTP client (see `HttpClientBuilder`)
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Share a concurrency limiter other than the global one
+    pub fn with_concurrency_limiter(mut self, limiter: LlmConcurrencyLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Hold every `/api/chat` call to the Ollama budgets of `limiter`
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Send `headers` on every call, e.g. for an authenticating proxy
+    pub fn with_extra_headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.extra_headers = headers;
+        self
+    }
+
+    /// Whether a call for a model the server doesn't have pulls it first
+    /// (on by default); when off such calls fail with `UnsupportedModel`
+    pub fn with_pull_on_demand(mut self, pull: bool) -> Self {
+        self.pull_on_demand = pull;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Download `model` to the server, returning once it is ready to serve
+    pub async fn pull_model(&self, model: &str) -> Result<()> {
+        let _pulling = self.pulling.lock().await;
+        // A call that held the lock before this one may have pulled it already
+        if self.has_model(model).await? {
+            return Ok(());
+        }
+        info!(model, server = %self.base_url, "Pulling Ollama model");
+        let request = self
+            .client
+            .post(format!("{}/api/pull", self.base_url))
+            .timeout(PULL_TIMEOUT)
+            .json(&serde_json::json!({ "model": model, "stream": false }));
+        let response = with_extra_headers(request, &self.extra_headers, &HashMap::new())
+            .send()
+            .await
+            .map_err(|e| self.send_error(e))?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ollama_error(status, &error_text, model));
+        }
+        // Failures after the download started still arrive with HTTP 200
+        let status: serde_json::Value = response.json().await.unwrap_or_default();
+        match status["error"].as_str() {
+            Some(error) => Err(LlmError::ApiError(format!("Failed to pull {}: {}", model, error))),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether the server has `model` pulled
+    pub async fn has_model(&self, model: &str) -> Result<bool> {
+        let request = self
+            .client
+            .post(format!("{}/api/show", self.base_url))
+            .json(&serde_json::json!({ "model": model }));
+        let response = with_extra_headers(request, &self.extra_headers, &HashMap::new())
+            .send()
+            .await
+            .map_err(|e| self.send_error(e))?;
+        match response.status().as_u16() {
+            200..=299 => Ok(true),
+            404 => Ok(false),
+            status => {
+                let error_text = response.text().await.unwrap_or_default();
+                Err(ollama_error(status, &error_text, model))
+            }
+        }
+    }
+
+    /// One `/api/chat` call, holding a rate limit and a concurrency permit
+    /// until its response has been read
+    async fn chat(&self, request: &LlmRequest, body: &serde_json::Value) -> Result<LlmResponse> {
+        let (response, permits) = self.send_chat(request, body).await?;
+        let response_json: serde_json::Value =
+            response.json().await.map_err(|e| LlmError::SerializationError(e.to_string()))?;
+        let response = parse_chat_response(&response_json, &request.model)?;
+        permits.settle(response.usage.total_tokens);
+        Ok(response)
+    }
+
+    /// Send `body` to `/api/chat`, returning the accepted response with the
+    /// permits to hold while reading it
+    ///
+    /// A missing model is pulled and the call sent again when
+    /// `pull_on_demand` is set; no permit is held during the pull.
+    async fn send_chat(&self, request: &LlmRequest, body: &serde_json::Value) -> Result<(reqwest::Response, ChatPermits)> {
+        match self.send_chat_once(request, body).await {
+            Err(LlmError::UnsupportedModel(_)) if self.pull_on_demand => {
+                self.pull_model(&request.model).await?;
+                self.send_chat_once(request, body).await
+            }
+            other => other,
+        }
+    }
+
+    async fn send_chat_once(
+        &self,
+        request: &LlmRequest,
+        body: &serde_json::Value,
+    ) -> Result<(reqwest::Response, ChatPermits)> {
+        let rate = match &self.rate_limiter {
+            Some(limiter) => Some(
+                limiter
+                    .acquire(LlmProvider::Ollama, request.caller.as_deref(), request.estimated_prompt_tokens())
+                    .await,
+            ),
+            None => None,
+        };
+        let concurrency = self.limiter.acquire().await;
+
+        let http_request = self.client.post(format!("{}/api/chat", self.base_url)).timeout(REPLY_TIMEOUT).json(body);
+        let response = with_extra_headers(http_request, &self.extra_headers, &request.extra_headers)
+            .send()
+            .await
+            .map_err(|e| self.send_error(e))?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ollama_error(status, &error_text, &request.model));
+        }
+        Ok((response, ChatPermits { rate, concurrency }))
+    }
+
+    /// Connection failures nearly always mean no server is running
+    fn send_error(&self, err: reqwest::Error) -> LlmError {
+        match LlmError::from_send(err) {
+            LlmError::NetworkError(message) => {
+                LlmError::NetworkError(format!("{} (is Ollama running at {}?)", message, self.base_url))
+            }
+            other => other,
+        }
+    }
+}
+
+/// Permits an `/api/chat` call holds until its reply has been read
+struct ChatPermits {
+    rate: Option<RateLimitPermit>,
+    concurrency: tokio::sync::OwnedSemaphorePermit,
+}
+
+impl ChatPermits {
+    fn settle(self, tokens_used: usize) {
+        if let Some(rate) = self.rate {
+            rate.settle(tokens_used);
+        }
+    }
+}
+
+/// `/api/chat` body for `request`
+fn chat_body(request: &LlmRequest, stream: bool) -> serde_json::Value {
+    let messages: Vec<serde_json::Value> = request
+        .messages
+        .iter()
+        .map(|msg| {
+            serde_json::json!({
+                "role": match msg.role {
+                    MessageRole::System => "system",
+                    MessageRole::User => "user",
+                    MessageRole::Assistant => "assistant",
+                    MessageRole::Tool => "tool",
+                },
+                "content": msg.content,
+            })
+        })
+        .collect();
+
+    let mut options = serde_json::Map::new();
+    if let Some(max_tokens) = request.max_tokens {
+        options.insert("num_predict".to_string(), serde_json::json!(max_tokens));
+    }
+    if let Some(temp) = request.temperature {
+        options.insert("temperature".to_string(), serde_json::json!(temp));
+    }
+    if let Some(top_p) = request.top_p {
+        options.insert("top_p".to_string(), serde_json::json!(top_p));
+    }
+    if !request.stop_sequences.is_empty() {
+        options.insert("stop".to_string(), serde_json::json!(request.stop_sequences));
+    }
+
+    let mut body = serde_json::json!({
+        "model": request.model,
+        "messages": messages,
+        "stream": stream,
+    });
+    if !options.is_empty() {
+        body["options"] = serde_json::Value::Object(options);
+    }
+    body
+}
+
+/// Response to a finished `/api/chat` call
+fn parse_chat_response(response_json: &serde_json::Value, model: &str) -> Result<LlmResponse> {
+    let content = response_json["message"]["content"]
+        .as_str()
+        .ok_or_else(|| LlmError::ApiError("No content in response".to_string()))?
+        .to_string();
+
+    let prompt_tokens = response_json["prompt_eval_count"].as_u64().unwrap_or(0) as usize;
+    let completion_tokens = response_json["eval_count"].as_u64().unwrap_or(0) as usize;
+    let usage = TokenUsage { prompt_tokens, completion_tokens, total_tokens: prompt_tokens + completion_tokens };
+
+    let finish_reason = response_json["done_reason"].as_str().unwrap_or("unknown").to_string();
+    let mut metadata = HashMap::new();
+    if let Some(served) = response_json["model"].as_str() {
+        metadata.insert(META_SERVED_MODEL.to_string(), served.to_string());
+    }
+    Ok(LlmResponse {
+        content,
+        model: model.to_string(),
+        usage,
+        truncated: is_truncation(&finish_reason),
+        finish_reason,
+        metadata,
+    })
+}
+
+/// Typed error for a failed Ollama call; the server answers `{"error": ...}`
+fn ollama_error(status: u16, body: &str, model: &str) -> LlmError {
+    let parsed: serde_json::Value = serde_json::from_str(body).unwrap_or_default();
+    let message = parsed["error"].as_str().unwrap_or(body).to_string();
+    match status {
+        404 if message.contains("not found") => LlmError::UnsupportedModel(model.to_string()),
+        400 => LlmError::InvalidRequest(message),
+        500.. => LlmError::ServerError { status, message },
+        _ => LlmError::ApiError(format!("HTTP {}: {}", status, message)),
+    }
+}
+
+#[async_trait]
+impl LlmClient for OllamaClient {
+    fn provider(&self) -> LlmProvider {
+        LlmProvider::Ollama
+    }
+
+    #[instrument(
+        name = "llm.complete",
+        skip(self, request),
+        fields(
+            provider = "ollama",
+            model = %request.model,
+            prompt_tokens = tracing::field::Empty,
+            completion_tokens = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+            estimated_cost = tracing::field::Empty,
+        )
+    )]
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        if !request.tools.is_empty() {
+            return Err(LlmError::Unsupported("tool use".to_string()));
+        }
+
+        let started = Instant::now();
+        let response = self.chat(&request, &chat_body(&request, false)).await?;
+        record_completion(&response.usage, &request.model, started);
+        Ok(response)
+    }
+
+    /// Streams the content of each line of the reply; the last line carries
+    /// the token counts the rate limit is settled with
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        if !request.tools.is_empty() {
+            return Err(LlmError::Unsupported("tool use".to_string()));
+        }
+
+        let (response, ChatPermits { mut rate, concurrency }) =
+            self.send_chat(&request, &chat_body(&request, true)).await?;
+        let model = request.model;
+        Ok(stream_lines(response, concurrency, move |line| {
+            if line.is_empty() {
+                return StreamLine::Skip;
+            }
+            let chunk: serde_json::Value = serde_json::from_str(line).unwrap_or_default();
+            if chunk.get("error").is_some() {
+                return StreamLine::Failed(ollama_error(500, line, &model));
+            }
+            if chunk["done"].as_bool().unwrap_or(false) {
+                let tokens = chunk["prompt_eval_count"].as_u64().unwrap_or(0) + chunk["eval_count"].as_u64().unwrap_or(0);
+                if let Some(rate) = rate.take() {
+                    rate.settle(tokens as usize);
+                }
+                return StreamLine::Done;
+            }
+            match chunk["message"]["content"].as_str() {
+                Some(text) if !text.is_empty() => StreamLine::Delta(text.to_string()),
+                _ => StreamLine::Skip,
+            }
+        }))
+    }
+
+    fn supports_model(&self, _model: &str) -> bool {
+        true
+    }
+
+    fn available_models(&self) -> Vec<String> {
+        self.provider().models().iter().map(|m| m.to_string()).collect()
+    }
+
+    /// Models already pulled to the server
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let request = self.client.get(format!("{}/api/tags", self.base_url));
+        let response = with_extra_headers(request, &self.extra_headers, &HashMap::new())
+            .send()
+            .await
+            .map_err(|e| self.send_error(e))?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(ollama_error(status, &error_text, ""));
+        }
+        let listing: serde_json::Value =
+            response.json().await.map_err(|e| LlmError::SerializationError(e.to_string()))?;
+        Ok(listing["models"]
+            .as_array()
+            .map(|models| {
+                models
+                    .iter()
+                    .filter_map(|m| m["name"].as_str())
+                    .map(|name| ModelInfo::new(name, LlmProvider::Ollama))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::Message;
+
+    #[test]
+    fn test_chat_body_maps_sampling_options() {
+        let request = LlmRequest::new("llama3.2")
+            .add_message(Message::system("Be brief"))
+            .add_message(Message::user("hi"))
+            .with_max_tokens(64)
+            .with_temperature(0.2);
+
+        let body = chat_body(&request, false);
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][1]["content"], "hi");
+        assert_eq!(body["options"]["num_predict"], 64);
+        assert!((body["options"]["temperature"].as_f64().unwrap() - 0.2).abs() < 1e-6);
+        assert!(body["options"].get("stop").is_none());
+    }
+
+    #[test]
+    fn test_parse_chat_response() {
+        let json = serde_json::json!({
+            "model": "llama3.2:latest",
+            "message": {"role": "assistant", "content": "Hello"},
+            "done": true,
+            "done_reason": "length",
+            "prompt_eval_count": 12,
+            "eval_count": 30,
+        });
+
+        let response = parse_chat_response(&json, "llama3.2").unwrap();
+        assert_eq!(response.content, "Hello");
+        assert_eq!(response.usage.total_tokens, 42);
+        assert!(response.truncated);
+        assert_eq!(response.metadata[META_SERVED_MODEL], "llama3.2:latest");
+        assert_eq!(response.estimated_cost(), 0.0);
+    }
+
+    #[test]
+    fn test_missing_model_is_unsupported() {
+        let error = ollama_error(404, r#"{"error":"model \"llama9\" not found, try pulling it first"}"#, "llama9");
+        assert!(matches!(error, LlmError::UnsupportedModel(model) if model == "llama9"));
+        assert!(matches!(ollama_error(500, "{}", "llama9"), LlmError::ServerError { status: 500, .. }));
+    }
+
+    #[test]
+    fn test_base_url_from_config() {
+        let config = crate::config::LlmConfig {
+            ollama_base_url: Some("http://gpu-box:11434/".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(OllamaClient::from_config(&config, &reqwest::Client::new()).unwrap().base_url(), "http://gpu-box:11434");
+        assert_eq!(OllamaClient::from_config(&Default::default(), &reqwest::Client::new()).unwrap().base_url(), OLLAMA_BASE_URL);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_is_an_outage() {
+        let client = OllamaClient::new().unwrap().with_base_url("http://127.0.0.1:9");
+        let error = client.complete(LlmRequest::new("llama3.2").add_message(Message::user("hi"))).await.unwrap_err();
+        assert!(error.is_outage(), "{:?}", error);
+        assert!(error.to_string().contains("is Ollama running"), "{}", error);
+    }
+
+    /// Answer one request per connection with `replies` in order; resolves
+    /// to the request lines received
+    async fn serve_replies<T: ToString + Send + 'static>(
+        replies: Vec<(u16, T)>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut request_lines = Vec::new();
+            for (status, reply) in replies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut received = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    received.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&received).to_string();
+                    if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                        let length: usize = head
+                            .lines()
+                            .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse().unwrap()))
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            request_lines.push(head.lines().next().unwrap_or_default().to_string());
+                            break;
+                        }
+                    }
+                }
+                let reply = reply.to_string();
+                let response = format!(
+                    "HTTP/1.1 {} X\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    reply.len(),
+                    reply
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+            request_lines
+        });
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn test_missing_model_is_pulled_then_served() {
+        let not_found = serde_json::json!({"error": "model \"llama9\" not found, try pulling it first"});
+        let (url, server) = serve_replies(vec![
+            (404, not_found.clone()),
+            (404, not_found),
+            (200, serde_json::json!({"status": "success"})),
+            (200, serde_json::json!({"model": "llama9", "message": {"content": "Hello"}, "done_reason": "stop"})),
+        ])
+        .await;
+        let client = OllamaClient::new().unwrap().with_base_url(url);
+
+        let response = client.complete(LlmRequest::new("llama9").add_message(Message::user("hi"))).await.unwrap();
+
+        assert_eq!(response.content, "Hello");
+        let paths: Vec<String> = server
+            .await
+            .unwrap()
+            .iter()
+            .map(|line| line.split(' ').nth(1).unwrap().to_string())
+            .collect();
+        assert_eq!(paths, ["/api/chat", "/api/show", "/api/pull", "/api/chat"]);
+    }
+
+    #[tokio::test]
+    async fn test_pull_skips_model_already_present() {
+        let (url, server) = serve_replies(vec![(200, serde_json::json!({"details": {}}))]).await;
+        let client = OllamaClient::new().unwrap().with_base_url(url);
+
+        client.pull_model("llama3.2").await.unwrap();
+
+        assert_eq!(server.await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_stream_sends_each_line_and_settles_at_done() {
+        let lines = [
+            serde_json::json!({"model": "llama3.2", "message": {"content": "Hel"}, "done": false}),
+            serde_json::json!({"model": "llama3.2", "message": {"content": "lo"}, "done": false}),
+            serde_json::json!({"model": "llama3.2", "message": {"content": ""}, "done": true, "eval_count": 2}),
+        ];
+        let body: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        let (url, server) = serve_replies(vec![(200, body)]).await;
+        let client = OllamaClient::new().unwrap().with_base_url(url);
+
+        let mut stream = client.complete_stream(LlmRequest::new("llama3.2").add_message(Message::user("hi"))).await.unwrap();
+        let mut deltas = Vec::new();
+        while let Some(delta) = stream.recv().await {
+            deltas.push(delta.unwrap());
+        }
+
+        assert_eq!(deltas, ["Hel", "lo"]);
+        assert_eq!(server.await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,188 @@
+//! Exact prompts sent during an execution
+//!
+//! An [`ExecutionResult`](crate::ExecutionResult) says what the agent
+//! answered, not what it was asked. To reproduce an answer (or bisect a
+//! prompt regression) you need the full request: system prompt, messages and
+//! sampling settings. Executions started with
+//! [`ExecutionContext::with_prompt_trace`](crate::ExecutionContext::with_prompt_trace)
+//! return a [`PromptTrace`] holding every request made, with secrets
+//! redacted, next to the usage reported for it.
+
+use crate::llm::{LlmRequest, LlmResponse, TokenUsage};
+use agentic_core::AgentId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Traces a [`PromptTraceStore`] keeps by default
+pub const DEFAULT_PROMPT_TRACE_CAPACITY: usize = 200;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Environment variables whose values never appear in a trace
+const SECRET_ENV_VARS: &[&str] = &["ANTHROPIC_API_KEY", "OPENAI_API_KEY"];
+
+/// Prefixes of well-known API key formats
+const SECRET_PREFIXES: &[&str] = &["sk-", "ghp_", "github_pat_", "xoxb-", "xoxp-", "AKIA"];
+
+/// Shorter tokens with a secret prefix are treated as ordinary words
+const MIN_SECRET_LEN: usize = 16;
+
+/// What the provider reported for one traced request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TracedResponse {
+    pub model: String,
+    pub usage: TokenUsage,
+    pub finish_reason: String,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+}
+
+/// One LLM call: the request as sent and, if it succeeded, its response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TracedCall {
+    pub request: LlmRequest,
+    pub response: Option<TracedResponse>,
+}
+
+/// Every LLM request made by a single execution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTrace {
+    pub execution_id: Uuid,
+    pub agent_id: AgentId,
+    pub started_at: DateTime<Utc>,
+    pub calls: Vec<TracedCall>,
+}
+
+impl PromptTrace {
+    pub fn new(agent_id: AgentId) -> Self {
+        Self {
+            execution_id: Uuid::new_v4(),
+            agent_id,
+            started_at: Utc::now(),
+            calls: Vec::new(),
+        }
+    }
+
+    /// Record `request`, redacting secrets from its messages
+    pub fn record(&mut self, request: &LlmRequest, response: Option<&LlmResponse>) {
+        let mut request = request.clone();
+        for message in &mut request.messages {
+            message.content = redact_secrets(&message.content);
+        }
+
+        self.calls.push(TracedCall {
+            request,
+            response: response.map(|r| TracedResponse {
+                model: r.model.clone(),
+                usage: r.usage.clone(),
+                finish_reason: r.finish_reason.clone(),
+                metadata: r.metadata.clone(),
+            }),
+        });
+    }
+}
+
+/// Replace API keys in `text` with `[REDACTED]`
+///
+/// Catches the values of the provider key variables and tokens that look
+/// like common key formats (`sk-...`, `ghp_...`, `AKIA...`).
+pub fn redact_secrets(text: &str) -> String {
+    let mut out = text.to_string();
+    for var in SECRET_ENV_VARS {
+        if let Ok(value) = std::env::var(var) {
+            if !value.is_empty() {
+                out = out.replace(&value, REDACTED);
+            }
+        }
+    }
+
+    let tokens: Vec<&str> = out
+        .split(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '`' | ',' | ';' | '='))
+        .filter(|token| {
+            token.len() >= MIN_SECRET_LEN && SECRET_PREFIXES.iter().any(|prefix| token.starts_with(prefix))
+        })
+        .collect();
+    let mut redacted = out.clone();
+    for token in tokens {
+        redacted = redacted.replace(token, REDACTED);
+    }
+    redacted
+}
+
+/// Most recent prompt traces, oldest dropped first
+#[derive(Debug, Clone)]
+pub struct PromptTraceStore {
+    traces: Arc<Mutex<VecDeque<PromptTrace>>>,
+    capacity: usize,
+}
+
+impl Default for PromptTraceStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROMPT_TRACE_CAPACITY)
+    }
+}
+
+impl PromptTraceStore {
+    /// Store keeping at most `capacity` traces (0 is treated as 1)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            traces: Arc::new(Mutex::new(VecDeque::new())),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn insert(&self, trace: PromptTrace) {
+        let mut traces = self.traces.lock().unwrap();
+        if traces.len() == self.capacity {
+            traces.pop_front();
+        }
+        traces.push_back(trace);
+    }
+
+    /// Trace of execution `execution_id` by `agent_id`, if still kept
+    pub fn get(&self, agent_id: &AgentId, execution_id: &Uuid) -> Option<PromptTrace> {
+        self.traces
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|t| &t.agent_id == agent_id && &t.execution_id == execution_id)
+            .cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.traces.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacts_key_like_tokens() {
+        let text = "use key sk-ant-REDACTED, not sk-short";
+        assert_eq!(redact_secrets(text), "use key [REDACTED], not sk-short");
+    }
+
+    #[test]
+    fn test_store_drops_oldest_trace() {
+        let store = PromptTraceStore::new(2);
+        let agent_id = AgentId::generate();
+        let traces: Vec<PromptTrace> = (0..3).map(|_| PromptTrace::new(agent_id)).collect();
+        for trace in &traces {
+            store.insert(trace.clone());
+        }
+
+        assert_eq!(store.len(), 2);
+        assert!(store.get(&agent_id, &traces[0].execution_id).is_none());
+        assert!(store.get(&agent_id, &traces[2].execution_id).is_some());
+        assert!(store.get(&AgentId::generate(), &traces[2].execution_id).is_none());
+    }
+}
@@ -0,0 +1,623 @@
+//! Per-provider request and token budgets
+//!
+//! Parallel managers (validation, revenue) can send bursts that no single
+//! provider account allows. A [`RateLimiter`] keeps a requests-per-minute and
+//! a tokens-per-minute budget for each provider and holds calls back until
+//! both have room. Calls waiting on the same provider are served round-robin
+//! by [`LlmRequest::caller`], so an agent fanning out dozens of calls doesn't
+//! starve the others. Token use is estimated from the prompt up front and
+//! corrected with the provider's reported usage afterwards.
+//!
+//! The cap on calls in flight stays with
+//! [`LlmConcurrencyLimiter`](crate::concurrency::LlmConcurrencyLimiter),
+//! which clients take once past the rate limiter.
+
+use crate::config::PerformanceConfig;
+use crate::llm::{LlmClient, LlmProvider, LlmRequest, LlmResponse, LlmStream, ModelInfo, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use tracing::warn;
+
+static GLOBAL: OnceLock<RateLimiter> = OnceLock::new();
+
+/// Per-minute budgets of one provider; `None` leaves that budget unlimited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderLimits {
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+    #[serde(default)]
+    pub tokens_per_minute: Option<u32>,
+}
+
+impl ProviderLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.requests_per_minute.is_none() && self.tokens_per_minute.is_none()
+    }
+}
+
+impl FromStr for ProviderLimits {
+    type Err = agentic_core::Error;
+
+    /// `<requests>[/<tokens>]` per minute, e.g. `50/40000`; either side may
+    /// be left empty
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let budget = |part: &str| -> std::result::Result<Option<u32>, Self::Err> {
+            match part.trim() {
+                "" => Ok(None),
+                n => n
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| agentic_core::Error::InvalidArgument(format!("Invalid rate limit: {}", s))),
+            }
+        };
+        let (requests, tokens) = s.split_once('/').unwrap_or((s, ""));
+        Ok(Self { requests_per_minute: budget(requests)?, tokens_per_minute: budget(tokens)? })
+    }
+}
+
+/// Budget refilling evenly over a minute, never holding more than a minute's worth
+#[derive(Debug)]
+struct Bucket {
+    per_minute: f64,
+    available: f64,
+    refilled: Instant,
+}
+
+impl Bucket {
+    fn new(per_minute: u32) -> Self {
+        Self { per_minute: per_minute as f64, available: per_minute as f64, refilled: Instant::now() }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let earned = now.duration_since(self.refilled).as_secs_f64() * self.per_minute / 60.0;
+        self.available = (self.available + earned).min(self.per_minute);
+        self.refilled = now;
+    }
+
+    /// How long until `amount` can be taken; an amount over the whole budget
+    /// only waits for a full bucket
+    fn wait_for(&self, amount: f64) -> Duration {
+        let missing = amount.min(self.per_minute) - self.available;
+        if missing <= 0.0 || self.per_minute <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing * 60.0 / self.per_minute)
+        }
+    }
+
+    fn resize(&mut self, per_minute: u32) {
+        self.per_minute = per_minute as f64;
+        self.available = self.available.min(self.per_minute);
+    }
+}
+
+/// Budgets and waiting calls of one provider
+#[derive(Debug, Default)]
+struct Lane {
+    limits: ProviderLimits,
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+    /// Callers with waiting calls, in the order they are served
+    callers: VecDeque<String>,
+    tickets: HashMap<String, VecDeque<u64>>,
+}
+
+impl Lane {
+    fn set_limits(&mut self, limits: ProviderLimits) {
+        let resized = |bucket: &mut Option<Bucket>, per_minute: Option<u32>| match (bucket.as_mut(), per_minute) {
+            (Some(bucket), Some(n)) => bucket.resize(n),
+            (_, n) => *bucket = n.map(Bucket::new),
+        };
+        resized(&mut self.requests, limits.requests_per_minute);
+        resized(&mut self.tokens, limits.tokens_per_minute);
+        self.limits = limits;
+    }
+
+    fn enqueue(&mut self, caller: &str, ticket: u64) {
+        let queue = self.tickets.entry(caller.to_string()).or_default();
+        if queue.is_empty() {
+            self.callers.push_back(caller.to_string());
+        }
+        queue.push_back(ticket);
+    }
+
+    /// The ticket served next
+    fn head(&self) -> Option<u64> {
+        self.callers.front().and_then(|caller| self.tickets[caller].front().copied())
+    }
+
+    /// Remove `ticket`; a caller whose head ticket was served goes to the
+    /// back of the line, one that gave up a later ticket keeps its place
+    fn remove(&mut self, caller: &str, ticket: u64) {
+        let served = self.head() == Some(ticket);
+        let Some(queue) = self.tickets.get_mut(caller) else {
+            return;
+        };
+        queue.retain(|t| *t != ticket);
+        let more = !queue.is_empty();
+        if !more {
+            self.tickets.remove(caller);
+        } else if !served {
+            return;
+        }
+        if let Some(i) = self.callers.iter().position(|c| c == caller) {
+            self.callers.remove(i);
+            if more {
+                self.callers.push_back(caller.to_string());
+            }
+        }
+    }
+
+    /// Wait until both budgets can cover one call of `tokens` tokens
+    fn wait_for(&mut self, tokens: usize, now: Instant) -> Duration {
+        let mut wait = Duration::ZERO;
+        if let Some(bucket) = self.requests.as_mut() {
+            bucket.refill(now);
+            wait = wait.max(bucket.wait_for(1.0));
+        }
+        if let Some(bucket) = self.tokens.as_mut() {
+            bucket.refill(now);
+            wait = wait.max(bucket.wait_for(tokens as f64));
+        }
+        wait
+    }
+
+    fn take(&mut self, tokens: f64) {
+        if let Some(bucket) = self.requests.as_mut() {
+            bucket.available -= 1.0;
+        }
+        self.charge_tokens(tokens);
+    }
+
+    /// Tokens may go negative, which delays later calls until they are earned back
+    fn charge_tokens(&mut self, tokens: f64) {
+        if let Some(bucket) = self.tokens.as_mut() {
+            bucket.available = (bucket.available - tokens).min(bucket.per_minute);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    lanes: HashMap<LlmProvider, Lane>,
+    next_ticket: u64,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: Mutex<State>,
+    /// Woken whenever a call is served or leaves the line, or limits change
+    changed: Notify,
+}
+
+/// Request and token budgets per provider; clones share the same budgets
+#[derive(Debug, Clone, Default)]
+pub struct RateLimiter {
+    shared: Arc<Shared>,
+}
+
+impl RateLimiter {
+    /// Limiter with no budgets
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `limits` to calls to `provider`
+    pub fn with_limits(self, provider: LlmProvider, limits: ProviderLimits) -> Self {
+        self.set_limits(provider, limits);
+        self
+    }
+
+    /// Budgets from `config.provider_rate_limits`; Anthropic and OpenAI
+    /// default to `config.rate_limit_per_minute` requests a minute
+    pub fn from_config(config: &PerformanceConfig) -> Self {
+        let limiter = Self::new();
+        limiter.set_config(config);
+        limiter
+    }
+
+    /// Shared limiter used by [`client_from_config`](crate::llm::client_from_config)
+    ///
+    /// Sized from [`PerformanceConfig::from_env`] on first use, or by an
+    /// earlier call to [`RateLimiter::install_global`].
+    pub fn global() -> &'static RateLimiter {
+        GLOBAL.get_or_init(|| Self::from_config(&PerformanceConfig::from_env()))
+    }
+
+    /// Set the global limiter; returns false if it was already initialised
+    pub fn install_global(limiter: RateLimiter) -> bool {
+        GLOBAL.set(limiter).is_ok()
+    }
+
+    /// Replace every provider's budgets with those of `config`
+    pub fn set_config(&self, config: &PerformanceConfig) {
+        let mut limits: HashMap<LlmProvider, ProviderLimits> = HashMap::new();
+        let default = ProviderLimits { requests_per_minute: Some(config.rate_limit_per_minute), tokens_per_minute: None };
+        for provider in [LlmProvider::Anthropic, LlmProvider::OpenAI] {
+            limits.insert(provider, default);
+        }
+        for (name, provider_limits) in &config.provider_rate_limits {
+            match name.parse() {
+                Ok(provider) => {
+                    limits.insert(provider, *provider_limits);
+                }
+                Err(e) => warn!("Ignoring rate limit: {}", e),
+            }
+        }
+        for &provider in LlmProvider::all() {
+            self.set_limits(provider, limits.get(&provider).copied().unwrap_or_default());
+        }
+    }
+
+    pub fn set_limits(&self, provider: LlmProvider, limits: ProviderLimits) {
+        self.shared.state.lock().unwrap().lanes.entry(provider).or_default().set_limits(limits);
+        self.shared.changed.notify_waiters();
+    }
+
+    pub fn limits(&self, provider: LlmProvider) -> ProviderLimits {
+        self.shared.state.lock().unwrap().lanes.get(&provider).map(|lane| lane.limits).unwrap_or_default()
+    }
+
+    /// Calls waiting for `provider`'s budgets
+    pub fn queued(&self, provider: LlmProvider) -> usize {
+        let state = self.shared.state.lock().unwrap();
+        state.lanes.get(&provider).map_or(0, |lane| lane.tickets.values().map(VecDeque::len).sum())
+    }
+
+    /// Wait until `provider` has room for a call of about `estimated_tokens`
+    /// tokens, taking turns with other callers
+    ///
+    /// Settle the permit with the tokens actually used; dropping it unsettled
+    /// gives the estimate back.
+    pub async fn acquire(&self, provider: LlmProvider, caller: Option<&str>, estimated_tokens: usize) -> RateLimitPermit {
+        let caller = caller.unwrap_or_default();
+        let ticket = {
+            let mut state = self.shared.state.lock().unwrap();
+            state.next_ticket += 1;
+            let ticket = state.next_ticket;
+            let lane = state.lanes.entry(provider).or_default();
+            if lane.limits.is_unlimited() {
+                return RateLimitPermit { limiter: None, provider, reserved: 0 };
+            }
+            lane.enqueue(caller, ticket);
+            ticket
+        };
+        // Leaves the line if this future is dropped while waiting
+        let mut place = Place { limiter: self, provider, caller, ticket, served: false };
+
+        loop {
+            let changed = self.shared.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+
+            let wait = {
+                let mut state = self.shared.state.lock().unwrap();
+                let lane = state.lanes.entry(provider).or_default();
+                if lane.head() == Some(ticket) {
+                    let wait = lane.wait_for(estimated_tokens, Instant::now());
+                    if wait.is_zero() {
+                        lane.take(estimated_tokens as f64);
+                        lane.remove(caller, ticket);
+                        place.served = true;
+                        self.shared.changed.notify_waiters();
+                        return RateLimitPermit { limiter: Some(self.clone()), provider, reserved: estimated_tokens };
+                    }
+                    Some(wait)
+                } else {
+                    None
+                }
+            };
+            match wait {
+                Some(wait) => {
+                    tokio::select! {
+                        _ = tokio::time::sleep(wait) => {}
+                        _ = &mut changed => {}
+                    }
+                }
+                None => changed.await,
+            }
+        }
+    }
+}
+
+/// A waiting call's spot in line
+struct Place<'a> {
+    limiter: &'a RateLimiter,
+    provider: LlmProvider,
+    caller: &'a str,
+    ticket: u64,
+    served: bool,
+}
+
+impl Drop for Place<'_> {
+    fn drop(&mut self) {
+        if !self.served {
+            let mut state = self.limiter.shared.state.lock().unwrap();
+            if let Some(lane) = state.lanes.get_mut(&self.provider) {
+                lane.remove(self.caller, self.ticket);
+            }
+            drop(state);
+            self.limiter.shared.changed.notify_waiters();
+        }
+    }
+}
+
+/// Room for one call, granted by [`RateLimiter::acquire`]
+#[derive(Debug)]
+pub struct RateLimitPermit {
+    limiter: Option<RateLimiter>,
+    provider: LlmProvider,
+    reserved: usize,
+}
+
+impl RateLimitPermit {
+    /// Correct the token budget with the tokens the call actually used
+    pub fn settle(mut self, tokens_used: usize) {
+        if let Some(limiter) = self.limiter.take() {
+            limiter.adjust(self.provider, tokens_used as f64 - self.reserved as f64);
+        }
+    }
+}
+
+impl Drop for RateLimitPermit {
+    fn drop(&mut self) {
+        if let Some(limiter) = self.limiter.take() {
+            limiter.adjust(self.provider, -(self.reserved as f64));
+        }
+    }
+}
+
+impl RateLimiter {
+    fn adjust(&self, provider: LlmProvider, tokens: f64) {
+        if tokens == 0.0 {
+            return;
+        }
+        if let Some(lane) = self.shared.state.lock().unwrap().lanes.get_mut(&provider) {
+            lane.charge_tokens(tokens);
+        }
+        self.shared.changed.notify_waiters();
+    }
+}
+
+/// Client whose calls wait for their provider's budgets first
+pub struct RateLimitedLlmClient {
+    inner: Arc<dyn LlmClient>,
+    limiter: RateLimiter,
+}
+
+impl RateLimitedLlmClient {
+    pub fn new(inner: Arc<dyn LlmClient>, limiter: RateLimiter) -> Self {
+        Self { inner, limiter }
+    }
+
+    async fn admit(&self, request: &LlmRequest) -> RateLimitPermit {
+        self.limiter
+            .acquire(self.inner.provider(), request.caller.as_deref(), request.estimated_prompt_tokens())
+            .await
+    }
+}
+
+#[async_trait]
+impl LlmClient for RateLimitedLlmClient {
+    fn provider(&self) -> LlmProvider {
+        self.inner.provider()
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        let permit = self.admit(&request).await;
+        let response = self.inner.complete(request).await?;
+        permit.settle(response.usage.total_tokens);
+        Ok(response)
+    }
+
+    /// Streamed completions count against the token budget with the prompt
+    /// estimate only, as no usage is reported
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        let permit = self.admit(&request).await;
+        let reserved = permit.reserved;
+        let stream = self.inner.complete_stream(request).await?;
+        permit.settle(reserved);
+        Ok(stream)
+    }
+
+    fn supports_model(&self, model: &str) -> bool {
+        self.inner.supports_model(model)
+    }
+
+    fn available_models(&self) -> Vec<String> {
+        self.inner.available_models()
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.inner.list_models().await
+    }
+
+    fn is_mock(&self) -> bool {
+        self.inner.is_mock()
+    }
+}
+
+/// Client that gives requests without a [`LlmRequest::caller`] a fixed one,
+/// so the rate limiter queues them as a single caller
+pub struct CallerLlmClient {
+    inner: Arc<dyn LlmClient>,
+    caller: String,
+}
+
+impl CallerLlmClient {
+    pub fn wrap(inner: Arc<dyn LlmClient>, caller: impl Into<String>) -> Arc<dyn LlmClient> {
+        Arc::new(Self { inner, caller: caller.into() })
+    }
+
+    /// Client for a manager agent to hand its sub-agents
+    ///
+    /// Their calls go out as `manager`'s, so a pipeline of sub-agent calls
+    /// shares the manager's turn in the rate limiter's line instead of
+    /// queueing once per sub-agent.
+    pub fn for_manager(inner: Arc<dyn LlmClient>, manager: &agentic_core::Agent) -> Arc<dyn LlmClient> {
+        Self::wrap(inner, manager.id.to_string())
+    }
+
+    fn tag(&self, mut request: LlmRequest) -> LlmRequest {
+        request.caller.get_or_insert_with(|| self.caller.clone());
+        request
+    }
+}
+
+#[async_trait]
+impl LlmClient for CallerLlmClient {
+    fn provider(&self) -> LlmProvider {
+        self.inner.provider()
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        self.inner.complete(self.tag(request)).await
+    }
+
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        self.inner.complete_stream(self.tag(request)).await
+    }
+
+    fn supports_model(&self, model: &str) -> bool {
+        self.inner.supports_model(model)
+    }
+
+    fn available_models(&self) -> Vec<String> {
+        self.inner.available_models()
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.inner.list_models().await
+    }
+
+    fn is_mock(&self) -> bool {
+        self.inner.is_mock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{Message, MockLlmClient};
+    use crate::test_support::ScriptedLlmClient;
+
+    fn limited(requests: Option<u32>, tokens: Option<u32>) -> RateLimiter {
+        RateLimiter::new()
+            .with_limits(LlmProvider::Mock, ProviderLimits { requests_per_minute: requests, tokens_per_minute: tokens })
+    }
+
+    /// Use up the whole request budget of `limiter`
+    async fn drain(limiter: &RateLimiter, requests: u32) {
+        for _ in 0..requests {
+            limiter.acquire(LlmProvider::Mock, None, 0).await.settle(0);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_requests_per_minute() {
+        // 600 a minute is one every 100ms once the first minute's worth is spent
+        let limiter = limited(Some(600), None);
+        let started = Instant::now();
+        drain(&limiter, 600).await;
+        assert_eq!(started.elapsed(), Duration::ZERO);
+
+        drain(&limiter, 2).await;
+        let elapsed = started.elapsed();
+        assert!((Duration::from_millis(200)..Duration::from_millis(210)).contains(&elapsed), "{:?}", elapsed);
+    }
+
+    #[test]
+    fn test_giving_up_a_later_ticket_keeps_the_callers_place() {
+        let mut lane = Lane::default();
+        lane.enqueue("busy", 1);
+        lane.enqueue("busy", 2);
+        lane.enqueue("quiet", 3);
+
+        lane.remove("busy", 2);
+        assert_eq!(lane.head(), Some(1));
+
+        // Being served sends it behind the others
+        lane.enqueue("busy", 4);
+        lane.remove("busy", 1);
+        assert_eq!(lane.head(), Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_caller_is_defaulted_not_overridden() {
+        let recorder = Arc::new(ScriptedLlmClient::default());
+        let client = CallerLlmClient::wrap(recorder.clone(), "manager");
+        let request = LlmRequest::new("mock-model").add_message(Message::user("hi"));
+
+        client.complete(request.clone()).await.unwrap();
+        client.complete(request.with_caller("agent-1")).await.unwrap();
+
+        let callers: Vec<_> = recorder.requests().into_iter().map(|request| request.caller).collect();
+        assert_eq!(callers, [Some("manager".to_string()), Some("agent-1".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_tokens_are_settled_with_actual_usage() {
+        let limiter = limited(None, Some(10_000));
+        let client = RateLimitedLlmClient::new(Arc::new(MockLlmClient::default()), limiter.clone());
+        let available = || {
+            let state = limiter.shared.state.lock().unwrap();
+            state.lanes[&LlmProvider::Mock].tokens.as_ref().unwrap().available
+        };
+
+        // The mock reports 30 tokens per call whatever the prompt estimate
+        client.complete(LlmRequest::new("mock-model").add_message(Message::user("hi"))).await.unwrap();
+        assert!((9_970.0..9_972.0).contains(&available()), "{}", available());
+
+        // A call that never completes gives its estimate back
+        let permit = limiter.acquire(LlmProvider::Mock, None, 500).await;
+        assert!(available() < 9_500.0);
+        drop(permit);
+        assert!(available() >= 9_970.0);
+    }
+
+    #[tokio::test]
+    async fn test_waiting_callers_take_turns() {
+        let limiter = limited(Some(600), None);
+        drain(&limiter, 600).await;
+
+        let served = Arc::new(Mutex::new(Vec::new()));
+        let mut calls = tokio::task::JoinSet::new();
+        for caller in ["busy", "busy", "busy", "quiet"] {
+            let (limiter, served) = (limiter.clone(), served.clone());
+            calls.spawn(async move {
+                limiter.acquire(LlmProvider::Mock, Some(caller), 0).await.settle(0);
+                served.lock().unwrap().push(caller);
+            });
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        while calls.join_next().await.is_some() {}
+
+        assert_eq!(*served.lock().unwrap(), ["busy", "quiet", "busy", "busy"]);
+        assert_eq!(limiter.queued(LlmProvider::Mock), 0);
+    }
+
+    #[test]
+    fn test_parse_limits() {
+        let both: ProviderLimits = "50/40000".parse().unwrap();
+        assert_eq!(both, ProviderLimits { requests_per_minute: Some(50), tokens_per_minute: Some(40_000) });
+        let tokens: ProviderLimits = "/1000".parse().unwrap();
+        assert_eq!(tokens.requests_per_minute, None);
+        assert!("fast".parse::<ProviderLimits>().is_err());
+
+        let config = PerformanceConfig {
+            rate_limit_per_minute: 10,
+            provider_rate_limits: HashMap::from([("openai".to_string(), both)]),
+            ..Default::default()
+        };
+        let limiter = RateLimiter::from_config(&config);
+        assert_eq!(limiter.limits(LlmProvider::Anthropic).requests_per_minute, Some(10));
+        assert_eq!(limiter.limits(LlmProvider::OpenAI), both);
+        assert!(limiter.limits(LlmProvider::Mock).is_unlimited());
+    }
+}
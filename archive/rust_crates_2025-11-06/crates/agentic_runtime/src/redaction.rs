@@ -0,0 +1,310 @@
+//! Scrubbing personal data from text before it is kept
+//!
+//! A [`RedactionPolicy`] replaces email addresses, phone numbers, payment
+//! card numbers and configured words with placeholders. It is meant for
+//! copies that outlive a request, such as stored chat history; the text
+//! returned to the caller stays as it was. The default policy redacts
+//! nothing, so deployments opt in (`MESSAGE_REDACTION`).
+
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Replacement for words on the block list
+const REDACTED: &str = "[REDACTED]";
+
+/// Kind of personal data a policy can detect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiKind {
+    Email,
+    /// 10 to 15 digits, optionally grouped with spaces, dashes or
+    /// parentheses and prefixed with `+`
+    Phone,
+    /// 13 to 19 digits passing the Luhn check
+    CreditCard,
+}
+
+impl PiiKind {
+    pub const ALL: [PiiKind; 3] = [PiiKind::Email, PiiKind::Phone, PiiKind::CreditCard];
+
+    fn placeholder(self) -> &'static str {
+        match self {
+            PiiKind::Email => "[REDACTED EMAIL]",
+            PiiKind::Phone => "[REDACTED PHONE]",
+            PiiKind::CreditCard => "[REDACTED CARD]",
+        }
+    }
+}
+
+/// What to scrub from stored text; redacts nothing by default
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RedactionPolicy {
+    #[serde(default)]
+    pub kinds: Vec<PiiKind>,
+    /// Whole words replaced regardless of case, e.g. profanity
+    #[serde(default)]
+    pub blocked_words: Vec<String>,
+}
+
+impl RedactionPolicy {
+    pub fn new(kinds: impl IntoIterator<Item = PiiKind>) -> Self {
+        Self { kinds: kinds.into_iter().collect(), blocked_words: Vec::new() }
+    }
+
+    /// Policy detecting every [`PiiKind`]
+    pub fn all() -> Self {
+        Self::new(PiiKind::ALL)
+    }
+
+    /// Also replace `words` wherever they appear as whole words
+    pub fn with_blocked_words<S: Into<String>>(mut self, words: impl IntoIterator<Item = S>) -> Self {
+        self.blocked_words
+            .extend(words.into_iter().map(|w| w.into().trim().to_lowercase()).filter(|w| !w.is_empty()));
+        self
+    }
+
+    /// Read the kinds from `MESSAGE_REDACTION` (`all`, or a comma-separated
+    /// list of `email`, `phone`, `card`) and the block list from
+    /// `MESSAGE_REDACTION_WORDS`; an invalid setting is logged and ignored
+    pub fn from_env() -> Self {
+        let policy = match std::env::var("MESSAGE_REDACTION") {
+            Ok(value) => value.parse().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring MESSAGE_REDACTION: {}", e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+        match std::env::var("MESSAGE_REDACTION_WORDS") {
+            Ok(words) => policy.with_blocked_words(words.split(',')),
+            Err(_) => policy,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.kinds.is_empty() || !self.blocked_words.is_empty()
+    }
+
+    /// `text` with everything the policy covers replaced by placeholders
+    pub fn redact(&self, text: &str) -> String {
+        if !self.is_enabled() {
+            return text.to_string();
+        }
+
+        let mut spans: Vec<(Range<usize>, &str)> = Vec::new();
+        if self.kinds.contains(&PiiKind::Email) {
+            spans.extend(email_spans(text).map(|range| (range, PiiKind::Email.placeholder())));
+        }
+        let cards = self.kinds.contains(&PiiKind::CreditCard);
+        let phones = self.kinds.contains(&PiiKind::Phone);
+        if cards || phones {
+            for (range, digits) in number_spans(text) {
+                if cards && (13..=19).contains(&digits.len()) && luhn_valid(&digits) {
+                    spans.push((range, PiiKind::CreditCard.placeholder()));
+                } else if phones && (10..=15).contains(&digits.len()) {
+                    spans.push((range, PiiKind::Phone.placeholder()));
+                }
+            }
+        }
+        if !self.blocked_words.is_empty() {
+            spans.extend(
+                word_spans(text)
+                    .filter(|range| self.blocked_words.contains(&text[range.clone()].to_lowercase()))
+                    .map(|range| (range, REDACTED)),
+            );
+        }
+
+        spans.sort_by_key(|(range, _)| range.start);
+        let mut out = String::with_capacity(text.len());
+        let mut pos = 0;
+        for (range, placeholder) in spans {
+            if range.start < pos {
+                continue;
+            }
+            out.push_str(&text[pos..range.start]);
+            out.push_str(placeholder);
+            pos = range.end;
+        }
+        out.push_str(&text[pos..]);
+        out
+    }
+}
+
+impl FromStr for RedactionPolicy {
+    type Err = agentic_core::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut kinds = Vec::new();
+        for name in s.split(',').map(|n| n.trim().to_ascii_lowercase()).filter(|n| !n.is_empty()) {
+            match name.as_str() {
+                "all" => kinds.extend(PiiKind::ALL),
+                "email" => kinds.push(PiiKind::Email),
+                "phone" => kinds.push(PiiKind::Phone),
+                "card" | "credit_card" => kinds.push(PiiKind::CreditCard),
+                "none" | "off" => {}
+                _ => return Err(agentic_core::Error::InvalidArgument(format!("Unknown redaction kind: {}", name))),
+            }
+        }
+        kinds.dedup();
+        Ok(Self::new(kinds))
+    }
+}
+
+/// Byte ranges of whitespace-separated words, without surrounding punctuation
+fn words(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut start = None;
+    text.char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+        .filter_map(move |(i, c)| match (c.is_whitespace(), start) {
+            (false, None) => {
+                start = Some(i);
+                None
+            }
+            (true, Some(s)) => {
+                start = None;
+                Some(s..i)
+            }
+            _ => None,
+        })
+        .map(|range| {
+            let word = &text[range.clone()];
+            let trimmed = word.trim_start_matches(|c| "<([\"'".contains(c));
+            let start = range.start + word.len() - trimmed.len();
+            let trimmed = trimmed.trim_end_matches(|c| ">)]\"',;:.!?".contains(c));
+            start..start + trimmed.len()
+        })
+}
+
+fn email_spans(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    words(text).filter(|range| is_email(&text[range.clone()]))
+}
+
+fn is_email(word: &str) -> bool {
+    let Some((local, domain)) = word.split_once('@') else {
+        return false;
+    };
+    let local_ok = !local.is_empty() && local.chars().all(|c| c.is_alphanumeric() || "._%+-".contains(c));
+    let labels: Vec<&str> = domain.split('.').collect();
+    let domain_ok = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+        && labels.last().is_some_and(|tld| tld.len() >= 2 && tld.chars().all(char::is_alphabetic));
+    local_ok && domain_ok
+}
+
+/// Runs of digits grouped by spaces, dashes, parentheses or a leading `+`,
+/// standing apart from surrounding words, with the digits they contain
+///
+/// Dotted and comma-grouped numbers (amounts, versions) are never runs.
+fn number_spans(text: &str) -> Vec<(Range<usize>, String)> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let opens = |c: char| c.is_whitespace() || "(\"'<[:,;".contains(c);
+    let closes = |c: char| c.is_whitespace() || ".,;:!?)\"'>]".contains(c);
+    let in_run = |c: char| c.is_ascii_digit() || " -()+".contains(c);
+
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        let starts = (c.is_ascii_digit() || c == '+' || c == '(') && (i == 0 || opens(chars[i - 1].1));
+        if !starts {
+            i += 1;
+            continue;
+        }
+
+        let mut j = i;
+        let mut last_digit = None;
+        let mut digits = String::new();
+        while j < chars.len() && in_run(chars[j].1) {
+            if chars[j].1.is_ascii_digit() {
+                last_digit = Some(j);
+                digits.push(chars[j].1);
+            }
+            j += 1;
+        }
+        if let Some(end) = last_digit {
+            if chars.get(end + 1).is_none_or(|&(_, c)| closes(c) || in_run(c)) {
+                spans.push((start..chars[end].0 + 1, digits));
+            }
+        }
+        i = j.max(i + 1);
+    }
+    spans
+}
+
+fn luhn_valid(digits: &str) -> bool {
+    let sum: u32 = digits
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 1 { if d * 2 > 9 { d * 2 - 9 } else { d * 2 } } else { d })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+/// Byte ranges of alphanumeric words
+fn word_spans(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut start = None;
+    text.char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+        .filter_map(move |(i, c)| match (c.is_alphanumeric(), start) {
+            (true, None) => {
+                start = Some(i);
+                None
+            }
+            (false, Some(s)) => {
+                start = None;
+                Some(s..i)
+            }
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacts_email_phone_and_card() {
+        let policy = RedactionPolicy::all();
+        let text = "Mail <jane.doe+work@example.co.uk>, call +1 (555) 123-4567 or pay with 4111 1111 1111 1111.";
+        assert_eq!(
+            policy.redact(text),
+            "Mail <[REDACTED EMAIL]>, call [REDACTED PHONE] or pay with [REDACTED CARD]."
+        );
+    }
+
+    #[test]
+    fn test_leaves_ordinary_numbers_alone() {
+        let policy = RedactionPolicy::all();
+        for text in [
+            "Released 2024-01-15, version 1.2.3",
+            "Revenue of 1,250,000.00 USD in 12 months",
+            "Request 550e8400-e29b-41d4-a716-446655440000 failed",
+            "Ping me @here or at user@localhost",
+            "Order 4111 1111 1111 1112 fails the card checksum",
+        ] {
+            assert_eq!(policy.redact(text), text);
+        }
+    }
+
+    #[test]
+    fn test_default_policy_is_a_no_op_and_kinds_are_selectable() {
+        let text = "jane@example.com 555-123-4567 darn";
+        assert_eq!(RedactionPolicy::default().redact(text), text);
+
+        let emails: RedactionPolicy = "email".parse().unwrap();
+        assert_eq!(emails.redact(text), "[REDACTED EMAIL] 555-123-4567 darn");
+
+        let words = RedactionPolicy::default().with_blocked_words(["Darn"]);
+        assert_eq!(words.redact("Darn it, darned thing"), "[REDACTED] it, darned thing");
+
+        assert_eq!("all".parse::<RedactionPolicy>().unwrap(), RedactionPolicy::all());
+        assert!("email,ssn".parse::<RedactionPolicy>().is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! Reuse of deterministic execution results
+//!
+//! An agent run at temperature 0 with the same model settings, system prompt
+//! and input gives the same answer, so [`ExecutionCache`] lets
+//! [`DefaultExecutor`](crate::executor::DefaultExecutor) return the earlier
+//! [`ExecutionResult`] instead of calling the LLM again. Entries are keyed by
+//! (agent, config hash, input hash) and expire after the cache's TTL. Sampled
+//! requests are not cached unless the cache is told to accept them.
+//!
+//! Every insert purges expired entries; when the cache is still full, the
+//! oldest entry makes room. Hits are counted in [`ExecutionCacheStats`] and
+//! marked `from_cache` on the executor's `execute` span.
+
+use crate::executor::ExecutionResult;
+use crate::llm::{LlmRequest, Message, MessageRole};
+use agentic_core::clock::{system_clock, SharedClock};
+use agentic_core::AgentId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// How long a cached result is reused when no TTL is given
+pub const DEFAULT_EXECUTION_CACHE_TTL: Duration = Duration::from_secs(600);
+
+/// Results a cache holds unless configured otherwise
+pub const DEFAULT_EXECUTION_CACHE_MAX_ENTRIES: usize = 1000;
+
+/// Hit and miss counters for an [`ExecutionCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Entries currently held, expired ones included until the next insert
+    pub entries: usize,
+}
+
+/// (agent, hash of the model settings and system prompt, hash of the conversation)
+type CacheKey = (AgentId, u64, u64);
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<CacheKey, (ExecutionResult, DateTime<Utc>)>,
+    hits: u64,
+    misses: u64,
+}
+
+/// Successful execution results keyed by agent, config and input
+#[derive(Debug)]
+pub struct ExecutionCache {
+    state: Mutex<CacheState>,
+    ttl: chrono::Duration,
+    max_entries: usize,
+    clock: SharedClock,
+    cache_nondeterministic: bool,
+}
+
+impl Default for ExecutionCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_EXECUTION_CACHE_TTL)
+    }
+}
+
+impl ExecutionCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            state: Mutex::new(CacheState::default()),
+            ttl: chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX),
+            max_entries: DEFAULT_EXECUTION_CACHE_MAX_ENTRIES,
+            clock: system_clock(),
+            cache_nondeterministic: false,
+        }
+    }
+
+    /// Hold at most `max` results (0 is treated as 1)
+    pub fn with_max_entries(mut self, max: usize) -> Self {
+        self.max_entries = max.max(1);
+        self
+    }
+
+    /// Use a different time source for expiry (e.g. a `MockClock` in tests)
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Also cache requests sampled above temperature 0
+    pub fn with_nondeterministic(mut self, enabled: bool) -> Self {
+        self.cache_nondeterministic = enabled;
+        self
+    }
+
+    /// Whether results of `request` may be cached
+    pub fn is_cacheable(&self, request: &LlmRequest) -> bool {
+        self.cache_nondeterministic || request.temperature == Some(0.0)
+    }
+
+    /// Fresh result cached for `agent_id` and `request`, marked `from_cache`
+    ///
+    /// Requests that are not cacheable return `None` and are not counted.
+    pub fn get(&self, agent_id: &AgentId, request: &LlmRequest) -> Option<ExecutionResult> {
+        if !self.is_cacheable(request) {
+            return None;
+        }
+
+        let key = cache_key(agent_id, request);
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        let fresh = state
+            .entries
+            .get(&key)
+            .filter(|(_, cached_at)| now - *cached_at < self.ttl)
+            .map(|(result, _)| result.clone());
+        match fresh {
+            Some(mut result) => {
+                state.hits += 1;
+                result.from_cache = true;
+                Some(result)
+            }
+            None => {
+                state.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Remember `result` for `agent_id` and `request`; failures and
+    /// non-cacheable requests are ignored
+    pub fn insert(&self, agent_id: &AgentId, request: &LlmRequest, result: &ExecutionResult) {
+        if !result.success || !self.is_cacheable(request) {
+            return;
+        }
+
+        // A replayed answer made no calls, so it carries no per-call detail
+        let mut cached = result.clone();
+        cached.tokens_used = 0;
+        cached.trace.clear();
+        cached.prompt_trace = None;
+
+        let key = cache_key(agent_id, request);
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        state.entries.retain(|_, (_, cached_at)| now - *cached_at < self.ttl);
+        if state.entries.len() >= self.max_entries && !state.entries.contains_key(&key) {
+            let oldest = state.entries.iter().min_by_key(|(_, (_, cached_at))| *cached_at).map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+        state.entries.insert(key, (cached, now));
+    }
+
+    pub fn stats(&self) -> ExecutionCacheStats {
+        let state = self.state.lock().unwrap();
+        ExecutionCacheStats {
+            hits: state.hits,
+            misses: state.misses,
+            entries: state.entries.len(),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.state.lock().unwrap().entries.clear();
+    }
+}
+
+fn cache_key(agent_id: &AgentId, request: &LlmRequest) -> CacheKey {
+    let (system, conversation): (Vec<&Message>, Vec<&Message>) =
+        request.messages.iter().partition(|m| m.role == MessageRole::System);
+    let config = (
+        &request.model,
+        request.max_tokens,
+        request.temperature,
+        request.top_p,
+        &request.stop_sequences,
+        &request.tools,
+        system,
+    );
+    (*agent_id, hash_json(&config), hash_json(&conversation))
+}
+
+fn hash_json(value: &impl Serialize) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(value).unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agentic_core::MockClock;
+
+    fn request(temperature: f32, input: &str) -> LlmRequest {
+        LlmRequest::new("mock-model")
+            .with_system("You are a tester")
+            .with_temperature(temperature)
+            .add_message(Message::user(input))
+    }
+
+    #[test]
+    fn test_entries_expire_and_sampled_requests_skip_the_cache() {
+        let clock = MockClock::default();
+        let cache = ExecutionCache::new(Duration::from_secs(60)).with_clock(clock.shared());
+        let agent_id = AgentId::generate();
+        let result = ExecutionResult::success("cached".to_string(), 42, 10);
+
+        cache.insert(&agent_id, &request(0.7, "hi"), &result);
+        assert_eq!(cache.stats().entries, 0);
+
+        cache.insert(&agent_id, &request(0.0, "hi"), &result);
+        let hit = cache.get(&agent_id, &request(0.0, "hi")).unwrap();
+        assert!(hit.from_cache);
+        assert_eq!((hit.output.as_str(), hit.tokens_used), ("cached", 0));
+        assert!(cache.get(&agent_id, &request(0.0, "bye")).is_none());
+        assert!(cache.get(&AgentId::generate(), &request(0.0, "hi")).is_none());
+
+        clock.advance(chrono::Duration::seconds(61));
+        assert!(cache.get(&agent_id, &request(0.0, "hi")).is_none());
+        assert_eq!(cache.stats(), ExecutionCacheStats { hits: 1, misses: 3, entries: 1 });
+
+        // The next insert purges what expired
+        cache.insert(&agent_id, &request(0.0, "bye"), &result);
+        assert_eq!(cache.stats().entries, 1);
+    }
+
+    #[test]
+    fn test_full_cache_drops_the_oldest_entry() {
+        let clock = MockClock::default();
+        let cache = ExecutionCache::default().with_max_entries(2).with_clock(clock.shared());
+        let agent_id = AgentId::generate();
+        let result = ExecutionResult::success("cached".to_string(), 42, 10);
+
+        for input in ["first", "second", "third"] {
+            cache.insert(&agent_id, &request(0.0, input), &result);
+            clock.advance(chrono::Duration::seconds(1));
+        }
+
+        assert_eq!(cache.stats().entries, 2);
+        assert!(cache.get(&agent_id, &request(0.0, "first")).is_none());
+        assert!(cache.get(&agent_id, &request(0.0, "second")).is_some());
+        assert!(cache.get(&agent_id, &request(0.0, "third")).is_some());
+    }
+}
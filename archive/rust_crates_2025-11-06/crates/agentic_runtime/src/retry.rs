@@ -0,0 +1,312 @@
+//! Retrying transient LLM failures
+//!
+//! A [`RetryingLlmClient`] sends a request again when the provider fails in
+//! one of the ways listed in [`RetryPolicy::retry_on`] (rate limited, 5xx,
+//! timed out, unreachable), so a brief provider hiccup doesn't surface as a
+//! hard error in the SDLC or business managers. Waits grow exponentially
+//! from [`RetryPolicy::backoff_base_ms`] and are jittered, so agents that
+//! hit the same rate limit don't all come back at once. The policy lives in
+//! `PerformanceConfig::llm_retry`.
+//!
+//! This is separate from the executor's [`BackoffConfig`](crate::BackoffConfig),
+//! which re-runs a whole execution after `Error::Retryable`. LLM errors never
+//! convert to `Error::Retryable`, so the two don't multiply each other's
+//! attempts.
+
+use crate::backoff::{BackoffStrategy, Exponential};
+use crate::llm::{LlmClient, LlmError, LlmProvider, LlmRequest, LlmResponse, LlmStream, ModelInfo, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// Kind of failure worth sending the request again for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryOn {
+    /// HTTP 429
+    RateLimited,
+    /// HTTP 5xx, including 503 and Anthropic's 529 "overloaded"
+    ServerError,
+    /// No response within the HTTP timeout
+    Timeout,
+    /// Connection refused, reset or otherwise failed
+    Network,
+}
+
+impl RetryOn {
+    pub const ALL: [RetryOn; 4] = [RetryOn::RateLimited, RetryOn::ServerError, RetryOn::Timeout, RetryOn::Network];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetryOn::RateLimited => "rate_limited",
+            RetryOn::ServerError => "server_error",
+            RetryOn::Timeout => "timeout",
+            RetryOn::Network => "network",
+        }
+    }
+
+    pub fn matches(&self, error: &LlmError) -> bool {
+        matches!(
+            (self, error),
+            (RetryOn::RateLimited, LlmError::RateLimitExceeded(_))
+                | (RetryOn::ServerError, LlmError::ServerError { .. })
+                | (RetryOn::Timeout, LlmError::Timeout(_))
+                | (RetryOn::Network, LlmError::NetworkError(_))
+        )
+    }
+}
+
+impl FromStr for RetryOn {
+    type Err = agentic_core::Error;
+
+    /// Also accepts the status codes `429` and `5xx`
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "rate_limited" | "429" => Ok(RetryOn::RateLimited),
+            "server_error" | "5xx" => Ok(RetryOn::ServerError),
+            "timeout" => Ok(RetryOn::Timeout),
+            "network" => Ok(RetryOn::Network),
+            other => Err(agentic_core::Error::InvalidArgument(format!("Unknown retry class: {}", other))),
+        }
+    }
+}
+
+/// When and how often a failed LLM request is sent again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first one included; 1 turns retrying off
+    pub max_attempts: u32,
+    /// Longest wait before the first retry; doubles for each later one
+    pub backoff_base_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Wait a random fraction of the backoff rather than all of it
+    pub jitter: bool,
+    pub retry_on: Vec<RetryOn>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff_base_ms: 500,
+            max_backoff_ms: 30_000,
+            jitter: true,
+            retry_on: RetryOn::ALL.to_vec(),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that sends every request once
+    pub fn disabled() -> Self {
+        Self { max_attempts: 1, ..Self::default() }
+    }
+
+    /// Read `LLM_RETRY_MAX_ATTEMPTS`, `LLM_RETRY_BACKOFF_MS`,
+    /// `LLM_RETRY_MAX_BACKOFF_MS`, `LLM_RETRY_JITTER` and `LLM_RETRY_ON`
+    /// (comma-separated classes, e.g. `429,timeout`); unknown classes are
+    /// logged and skipped
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let millis = |var: &str, default: u64| std::env::var(var).ok().and_then(|v| v.parse().ok()).unwrap_or(default);
+        Self {
+            max_attempts: std::env::var("LLM_RETRY_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse::<u32>().ok())
+                .map_or(defaults.max_attempts, |n| n.max(1)),
+            backoff_base_ms: millis("LLM_RETRY_BACKOFF_MS", defaults.backoff_base_ms),
+            max_backoff_ms: millis("LLM_RETRY_MAX_BACKOFF_MS", defaults.max_backoff_ms),
+            jitter: std::env::var("LLM_RETRY_JITTER")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.jitter),
+            retry_on: match std::env::var("LLM_RETRY_ON") {
+                Ok(classes) => classes
+                    .split(',')
+                    .filter(|class| !class.trim().is_empty())
+                    .filter_map(|class| match class.parse() {
+                        Ok(class) => Some(class),
+                        Err(e) => {
+                            warn!("Ignoring LLM_RETRY_ON entry: {}", e);
+                            None
+                        }
+                    })
+                    .collect(),
+                Err(_) => defaults.retry_on,
+            },
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_attempts > 1 && !self.retry_on.is_empty()
+    }
+
+    /// Whether `attempt` (1 for the first) failing with `error` earns another
+    pub fn should_retry(&self, error: &LlmError, attempt: u32) -> bool {
+        attempt < self.max_attempts && self.retry_on.iter().any(|class| class.matches(error))
+    }
+
+    /// Wait before retry number `retry` (1 for the first)
+    pub fn delay(&self, retry: u32) -> Duration {
+        let ceiling = Exponential {
+            max_delay: Some(Duration::from_millis(self.max_backoff_ms)),
+            ..Exponential::new(Duration::from_millis(self.backoff_base_ms))
+        }
+        .next_delay(retry)
+        .unwrap_or_default();
+        if self.jitter {
+            ceiling.mul_f64(random_fraction())
+        } else {
+            ceiling
+        }
+    }
+}
+
+/// Uniform in `[0, 1]`
+fn random_fraction() -> f64 {
+    (uuid::Uuid::new_v4().as_u128() as u64) as f64 / u64::MAX as f64
+}
+
+/// Client that retries its inner client's transient failures
+pub struct RetryingLlmClient {
+    inner: Arc<dyn LlmClient>,
+    policy: RetryPolicy,
+}
+
+impl RetryingLlmClient {
+    pub fn new(inner: Arc<dyn LlmClient>, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    /// `inner` behind `policy`, or `inner` itself when the policy never retries
+    pub fn wrap(inner: Arc<dyn LlmClient>, policy: &RetryPolicy) -> Arc<dyn LlmClient> {
+        if policy.is_enabled() {
+            Arc::new(Self::new(inner, policy.clone()))
+        } else {
+            inner
+        }
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    async fn retry<T, F, Fut>(&self, model: &str, mut call: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match call().await {
+                Err(e) if self.policy.should_retry(&e, attempt) => {
+                    let delay = self.policy.delay(attempt);
+                    warn!(model = %model, attempt, "LLM request failed ({}), retrying in {:?}", e, delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for RetryingLlmClient {
+    fn provider(&self) -> LlmProvider {
+        self.inner.provider()
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        let model = request.model.clone();
+        self.retry(&model, || self.inner.complete(request.clone())).await
+    }
+
+    /// Retries only while opening the stream; a stream that breaks midway
+    /// is not restarted
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        let model = request.model.clone();
+        self.retry(&model, || self.inner.complete_stream(request.clone())).await
+    }
+
+    fn supports_model(&self, model: &str) -> bool {
+        self.inner.supports_model(model)
+    }
+
+    fn available_models(&self) -> Vec<String> {
+        self.inner.available_models()
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.retry("", || self.inner.list_models()).await
+    }
+
+    fn is_mock(&self) -> bool {
+        self.inner.is_mock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::Message;
+    use crate::test_support::FlakyLlmClient;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy { backoff_base_ms: 1, ..RetryPolicy::default() }
+    }
+
+    fn request() -> LlmRequest {
+        LlmRequest::new("mock-model").add_message(Message::user("hi"))
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        let unavailable = || LlmError::ServerError { status: 503, message: "unavailable".to_string() };
+        let flaky = Arc::new(FlakyLlmClient::failing_first(2, unavailable));
+        let client = RetryingLlmClient::new(flaky.clone(), policy());
+
+        assert_eq!(client.complete(request()).await.unwrap().content, "mock");
+        assert_eq!(flaky.calls(), 3);
+
+        // Out of attempts: the last error comes through
+        let flaky = Arc::new(FlakyLlmClient::failing_first(5, || LlmError::RateLimitExceeded("slow down".to_string())));
+        let client = RetryingLlmClient::new(flaky.clone(), policy());
+        assert!(matches!(client.complete(request()).await, Err(LlmError::RateLimitExceeded(_))));
+        assert_eq!(flaky.calls(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_only_listed_classes_are_retried() {
+        let flaky = Arc::new(FlakyLlmClient::failing_first(1, || LlmError::InvalidRequest("bad".to_string())));
+        let client = RetryingLlmClient::new(flaky.clone(), policy());
+        assert!(client.complete(request()).await.is_err());
+        assert_eq!(flaky.calls(), 1);
+
+        let flaky = Arc::new(FlakyLlmClient::failing_first(1, || LlmError::Timeout("30s".to_string())));
+        let client = RetryingLlmClient::new(flaky.clone(), RetryPolicy { retry_on: vec![RetryOn::RateLimited], ..policy() });
+        assert!(matches!(client.complete(request()).await, Err(LlmError::Timeout(_))));
+        assert_eq!(flaky.calls(), 1);
+    }
+
+    #[test]
+    fn test_delays_grow_capped_and_jittered() {
+        let fixed = RetryPolicy { backoff_base_ms: 100, max_backoff_ms: 300, jitter: false, ..RetryPolicy::default() };
+        let delays: Vec<u128> = (1..=4).map(|retry| fixed.delay(retry).as_millis()).collect();
+        assert_eq!(delays, [100, 200, 300, 300]);
+
+        let jittered = RetryPolicy { jitter: true, ..fixed };
+        for retry in 1..=4 {
+            assert!(jittered.delay(retry) <= Duration::from_millis(300));
+        }
+
+        assert_eq!("429".parse::<RetryOn>().unwrap(), RetryOn::RateLimited);
+        assert!("teapot".parse::<RetryOn>().is_err());
+        assert!(!RetryPolicy::disabled().is_enabled());
+    }
+}
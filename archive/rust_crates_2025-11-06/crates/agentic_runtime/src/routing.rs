@@ -0,0 +1,538 @@
+//! Sending each call to the right provider, and to the next one when it's down
+//!
+//! A [`RoutingLlmClient`] holds one client per provider in priority order.
+//! A request goes to the provider a [`ModelRoute`] pins its model to, else
+//! to the highest-priority provider serving the model. When that provider
+//! is down (HTTP 5xx, timeout, connection failure) the call fails over to the
+//! next candidate; other errors, such as a rejected request, are returned
+//! as is. Providers that keep failing sit out a cooldown, and
+//! [`RoutingLlmClient::check_health`] probes them actively.
+//!
+//! Configured through [`RoutingConfig`] (`LlmConfig::routing`).
+
+use crate::config::LlmConfig;
+use crate::llm::{client_from_config, LlmClient, LlmError, LlmProvider, LlmRequest, LlmResponse, LlmStream, ModelInfo, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Consecutive outages after which a provider sits out the cooldown
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// How long an unhealthy provider is skipped when none is configured
+pub const DEFAULT_UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// How often the server probes routed providers when none is configured
+pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Models matching `pattern` go to `provider` first
+///
+/// A pattern ending in `*` matches by prefix (`claude-*`); any other pattern
+/// must equal the model. Written `pattern=provider` in `LLM_MODEL_ROUTES`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelRoute {
+    pub pattern: String,
+    pub provider: LlmProvider,
+}
+
+impl ModelRoute {
+    pub fn new(pattern: impl Into<String>, provider: LlmProvider) -> Self {
+        Self { pattern: pattern.into(), provider }
+    }
+
+    pub fn matches(&self, model: &str) -> bool {
+        match self.pattern.strip_suffix('*') {
+            Some(prefix) => model.starts_with(prefix),
+            None => model == self.pattern,
+        }
+    }
+}
+
+impl FromStr for ModelRoute {
+    type Err = agentic_core::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (pattern, provider) = s
+            .split_once('=')
+            .filter(|(pattern, _)| !pattern.trim().is_empty())
+            .ok_or_else(|| agentic_core::Error::InvalidArgument(format!("Invalid model route: {}", s)))?;
+        Ok(Self::new(pattern.trim(), provider.trim().parse()?))
+    }
+}
+
+/// Which providers a [`RoutingLlmClient`] spans and how it picks one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoutingConfig {
+    /// Providers in failover order; empty turns routing off
+    #[serde(default)]
+    pub providers: Vec<LlmProvider>,
+    /// Checked in order; the first matching route decides the first provider
+    #[serde(default)]
+    pub rules: Vec<ModelRoute>,
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    #[serde(default = "default_unhealthy_cooldown_seconds")]
+    pub unhealthy_cooldown_seconds: u64,
+    /// Seconds between [`RoutingLlmClient::check_health`] probes; 0 turns them off
+    #[serde(default = "default_health_check_interval_seconds")]
+    pub health_check_interval_seconds: u64,
+}
+
+fn default_failure_threshold() -> u32 {
+    DEFAULT_FAILURE_THRESHOLD
+}
+
+fn default_unhealthy_cooldown_seconds() -> u64 {
+    DEFAULT_UNHEALTHY_COOLDOWN.as_secs()
+}
+
+fn default_health_check_interval_seconds() -> u64 {
+    DEFAULT_HEALTH_CHECK_INTERVAL.as_secs()
+}
+
+impl Default for RoutingConfig {
+    fn default() -> Self {
+        Self {
+            providers: Vec::new(),
+            rules: Vec::new(),
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            unhealthy_cooldown_seconds: DEFAULT_UNHEALTHY_COOLDOWN.as_secs(),
+            health_check_interval_seconds: DEFAULT_HEALTH_CHECK_INTERVAL.as_secs(),
+        }
+    }
+}
+
+impl RoutingConfig {
+    /// Read `LLM_PROVIDER_PRIORITY` (`anthropic,openai`), `LLM_MODEL_ROUTES`
+    /// (`claude-*=anthropic,gpt-*=openai`), `LLM_FAILURE_THRESHOLD`,
+    /// `LLM_UNHEALTHY_COOLDOWN` and `LLM_HEALTH_CHECK_INTERVAL`; invalid
+    /// entries are logged and skipped
+    pub fn from_env() -> Self {
+        let list = |var: &str| -> Vec<String> {
+            std::env::var(var)
+                .unwrap_or_default()
+                .split(',')
+                .map(|entry| entry.trim().to_string())
+                .filter(|entry| !entry.is_empty())
+                .collect()
+        };
+        Self {
+            providers: list("LLM_PROVIDER_PRIORITY").iter().filter_map(|p| parse_entry(p)).collect(),
+            rules: list("LLM_MODEL_ROUTES").iter().filter_map(|r| parse_entry(r)).collect(),
+            failure_threshold: std::env::var("LLM_FAILURE_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_FAILURE_THRESHOLD),
+            unhealthy_cooldown_seconds: std::env::var("LLM_UNHEALTHY_COOLDOWN")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_UNHEALTHY_COOLDOWN.as_secs()),
+            health_check_interval_seconds: std::env::var("LLM_HEALTH_CHECK_INTERVAL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_HEALTH_CHECK_INTERVAL.as_secs()),
+        }
+    }
+
+    /// Interval of the server's health checks, `None` when they are off
+    pub fn health_check_interval(&self) -> Option<Duration> {
+        (self.health_check_interval_seconds > 0).then(|| Duration::from_secs(self.health_check_interval_seconds))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.providers.is_empty()
+    }
+}
+
+fn parse_entry<T: FromStr<Err = agentic_core::Error>>(entry: &str) -> Option<T> {
+    match entry.parse() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            warn!("Ignoring routing entry {}: {}", entry, e);
+            None
+        }
+    }
+}
+
+/// Health of one provider, as last observed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RouteHealth {
+    pub provider: LlmProvider,
+    pub healthy: bool,
+    pub consecutive_failures: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(Default)]
+struct RouteState {
+    consecutive_failures: u32,
+    unhealthy_until: Option<Instant>,
+    last_error: Option<String>,
+}
+
+struct Route {
+    client: Arc<dyn LlmClient>,
+    state: Mutex<RouteState>,
+}
+
+/// Client spreading calls over several providers with failover
+pub struct RoutingLlmClient {
+    routes: Vec<Route>,
+    rules: Vec<ModelRoute>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl Default for RoutingLlmClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RoutingLlmClient {
+    pub fn new() -> Self {
+        Self {
+            routes: Vec::new(),
+            rules: Vec::new(),
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            cooldown: DEFAULT_UNHEALTHY_COOLDOWN,
+        }
+    }
+
+    /// One client per provider of `config.routing`, using the keys and
+    /// endpoints of `config` and sending over the shared `http` client
+    ///
+    /// Providers whose client can't be built (e.g. no API key) are left out
+    /// with a warning; it is an error if none is left.
+    pub fn from_config(config: &LlmConfig, http: &reqwest::Client) -> agentic_core::Result<Self> {
+        let routing = &config.routing;
+        let mut client = Self::new()
+            .with_failure_threshold(routing.failure_threshold)
+            .with_cooldown(Duration::from_secs(routing.unhealthy_cooldown_seconds));
+        for &provider in &routing.providers {
+            match client_from_config(provider, config, http) {
+                Ok(provider_client) => client = client.with_provider(provider_client),
+                Err(e) => warn!(provider = %provider, "Leaving provider out of routing: {}", e),
+            }
+        }
+        if client.routes.is_empty() {
+            return Err(agentic_core::Error::InvalidArgument("No routed LLM provider could be set up".to_string()));
+        }
+        for rule in &routing.rules {
+            client = client.with_rule(rule.clone());
+        }
+        Ok(client)
+    }
+
+    /// Add a provider after those already added, i.e. at lower priority
+    pub fn with_provider(mut self, client: Arc<dyn LlmClient>) -> Self {
+        self.routes.push(Route { client, state: Mutex::new(RouteState::default()) });
+        self
+    }
+
+    /// Send models matching `rule.pattern` to `rule.provider` first
+    pub fn with_rule(mut self, rule: ModelRoute) -> Self {
+        if !self.routes.iter().any(|route| route.client.provider() == rule.provider) {
+            warn!(provider = %rule.provider, "Model route {} targets a provider that isn't routed", rule.pattern);
+        }
+        self.rules.push(rule);
+        self
+    }
+
+    /// Consecutive outages after which a provider is skipped for the cooldown
+    pub fn with_failure_threshold(mut self, failures: u32) -> Self {
+        self.failure_threshold = failures.max(1);
+        self
+    }
+
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Current health of every provider, in priority order
+    pub fn health(&self) -> Vec<RouteHealth> {
+        let now = Instant::now();
+        self.routes
+            .iter()
+            .map(|route| {
+                let state = route.state.lock().unwrap();
+                RouteHealth {
+                    provider: route.client.provider(),
+                    healthy: state.unhealthy_until.is_none_or(|until| until <= now),
+                    consecutive_failures: state.consecutive_failures,
+                    last_error: state.last_error.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Probe every provider by listing its models
+    ///
+    /// A provider that fails the probe is skipped for the cooldown; one that
+    /// answers is healthy again right away.
+    pub async fn check_health(&self) -> Vec<RouteHealth> {
+        for route in &self.routes {
+            match route.client.list_models().await {
+                Ok(_) => self.record_success(route),
+                Err(e) => {
+                    let mut state = route.state.lock().unwrap();
+                    state.consecutive_failures = state.consecutive_failures.max(self.failure_threshold);
+                    state.unhealthy_until = Some(Instant::now() + self.cooldown);
+                    state.last_error = Some(e.to_string());
+                    warn!(provider = %route.client.provider(), "LLM provider failed its health check: {}", e);
+                }
+            }
+        }
+        self.health()
+    }
+
+    /// Run [`check_health`](Self::check_health) every `interval` until the
+    /// client is dropped
+    pub fn spawn_health_checks(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let client: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(client) = client.upgrade() else {
+                    break;
+                };
+                client.check_health().await;
+            }
+        })
+    }
+
+    /// Routes to try for `model`, best first
+    ///
+    /// The provider of the first matching rule leads, followed by the other
+    /// providers serving the model in priority order. Providers cooling down
+    /// go last rather than being dropped, so a call is still attempted when
+    /// every provider is marked down.
+    fn candidates(&self, model: &str) -> Vec<&Route> {
+        let pinned = self.rules.iter().find(|rule| rule.matches(model)).map(|rule| rule.provider);
+        let mut candidates: Vec<&Route> = Vec::with_capacity(self.routes.len());
+        candidates.extend(self.routes.iter().filter(|route| Some(route.client.provider()) == pinned));
+        candidates.extend(
+            self.routes
+                .iter()
+                .filter(|route| Some(route.client.provider()) != pinned && route.client.supports_model(model)),
+        );
+        if candidates.is_empty() {
+            candidates.extend(self.routes.iter());
+        }
+
+        let now = Instant::now();
+        candidates.sort_by_key(|route| {
+            route.state.lock().unwrap().unhealthy_until.is_some_and(|until| until > now)
+        });
+        candidates
+    }
+
+    fn record_success(&self, route: &Route) {
+        let mut state = route.state.lock().unwrap();
+        if state.unhealthy_until.take().is_some() {
+            info!(provider = %route.client.provider(), "LLM provider is healthy again");
+        }
+        state.consecutive_failures = 0;
+        state.last_error = None;
+    }
+
+    fn record_outage(&self, route: &Route, err: &LlmError) {
+        let mut state = route.state.lock().unwrap();
+        state.consecutive_failures += 1;
+        state.last_error = Some(err.to_string());
+        if state.consecutive_failures >= self.failure_threshold {
+            warn!(
+                provider = %route.client.provider(),
+                failures = state.consecutive_failures,
+                "LLM provider marked unhealthy for {:?}",
+                self.cooldown
+            );
+            state.unhealthy_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+
+    /// Run `call` against each candidate until one answers or fails with
+    /// something other than an outage
+    async fn route<T, F>(&self, model: &str, call: impl Fn(Arc<dyn LlmClient>) -> F) -> Result<T>
+    where
+        F: std::future::Future<Output = Result<T>>,
+    {
+        let mut last_error = None;
+        for route in self.candidates(model) {
+            match call(route.client.clone()).await {
+                Ok(value) => {
+                    self.record_success(route);
+                    return Ok(value);
+                }
+                Err(e) if e.is_outage() => {
+                    warn!(provider = %route.client.provider(), model, "LLM provider unavailable, failing over: {}", e);
+                    self.record_outage(route, &e);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| LlmError::Unsupported("No LLM provider is routed".to_string())))
+    }
+}
+
+#[async_trait]
+impl LlmClient for RoutingLlmClient {
+    /// Provider of the highest-priority route
+    fn provider(&self) -> LlmProvider {
+        self.routes.first().map_or(LlmProvider::Mock, |route| route.client.provider())
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        let model = request.model.clone();
+        self.route(&model, |client| {
+            let request = request.clone();
+            async move { client.complete(request).await }
+        })
+        .await
+    }
+
+    /// Fails over only while opening the stream; a stream that breaks
+    /// midway is not resumed elsewhere
+    async fn complete_stream(&self, request: LlmRequest) -> Result<LlmStream> {
+        let model = request.model.clone();
+        self.route(&model, |client| {
+            let request = request.clone();
+            async move { client.complete_stream(request).await }
+        })
+        .await
+    }
+
+    fn supports_model(&self, model: &str) -> bool {
+        self.rules.iter().any(|rule| rule.matches(model)) || self.routes.iter().any(|r| r.client.supports_model(model))
+    }
+
+    fn available_models(&self) -> Vec<String> {
+        let mut models: Vec<String> = self.routes.iter().flat_map(|r| r.client.available_models()).collect();
+        models.sort();
+        models.dedup();
+        models
+    }
+
+    /// Models of every provider that answers; failing providers are skipped
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let mut models = Vec::new();
+        let mut last_error = None;
+        for route in &self.routes {
+            match route.client.list_models().await {
+                Ok(listed) => models.extend(listed),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        match last_error {
+            Some(e) if models.is_empty() => Err(e),
+            _ => Ok(models),
+        }
+    }
+
+    fn is_mock(&self) -> bool {
+        self.routes.iter().all(|route| route.client.is_mock())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{Message, MockLlmClient};
+    use crate::test_support::FlakyLlmClient;
+
+    fn request(model: &str) -> LlmRequest {
+        LlmRequest::new(model).add_message(Message::user("hi"))
+    }
+
+    fn outage() -> LlmError {
+        LlmError::ServerError { status: 503, message: "unavailable".to_string() }
+    }
+
+    #[tokio::test]
+    async fn test_rules_pick_provider_and_outages_fail_over() {
+        let anthropic = Arc::new(FlakyLlmClient::new(LlmProvider::Anthropic));
+        let openai = Arc::new(FlakyLlmClient::new(LlmProvider::OpenAI));
+        let mock = Arc::new(FlakyLlmClient::new(LlmProvider::Mock));
+        let client = RoutingLlmClient::new()
+            .with_provider(anthropic.clone())
+            .with_provider(openai.clone())
+            .with_provider(mock.clone())
+            .with_rule(ModelRoute::new("gpt-*", LlmProvider::OpenAI))
+            .with_rule(ModelRoute::new("llama3", LlmProvider::Mock));
+
+        assert_eq!(client.complete(request("gpt-4o")).await.unwrap().content, "openai");
+        assert_eq!(client.complete(request("claude-3-5-haiku-20241022")).await.unwrap().content, "anthropic");
+        assert_eq!(client.complete(request("llama3")).await.unwrap().content, "mock");
+
+        // Anthropic is down: its models fail over to the next provider serving them
+        anthropic.fail_with(Some(outage));
+        let response = client.complete(request("claude-3-5-haiku-20241022")).await.unwrap();
+        assert_eq!(response.content, "mock");
+        assert_eq!(client.health()[0].consecutive_failures, 1);
+
+        // A rejected request is the caller's problem, not a reason to fail over
+        openai.fail_with(Some(|| LlmError::InvalidRequest("bad temperature".to_string())));
+        let err = client.complete(request("gpt-4o")).await.unwrap_err();
+        assert!(matches!(err, LlmError::InvalidRequest(_)));
+        assert_eq!(mock.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_provider_sits_out_until_it_recovers() {
+        let primary = Arc::new(FlakyLlmClient::new(LlmProvider::Anthropic));
+        let backup = Arc::new(FlakyLlmClient::new(LlmProvider::Mock));
+        let client = RoutingLlmClient::new()
+            .with_provider(primary.clone())
+            .with_provider(backup.clone())
+            .with_failure_threshold(2)
+            .with_cooldown(Duration::from_secs(60));
+
+        primary.fail_with(Some(|| LlmError::NetworkError("timed out".to_string())));
+        for _ in 0..3 {
+            assert_eq!(client.complete(request("claude-3-5-haiku-20241022")).await.unwrap().content, "mock");
+        }
+        // Two outages marked it down; the third call went straight to the backup
+        assert_eq!(primary.calls(), 2);
+        let health = client.health();
+        assert!(!health[0].healthy);
+        assert_eq!(health[0].last_error.as_deref(), Some("Network error: timed out"));
+
+        // A passing probe puts it back in front
+        primary.fail_with(None);
+        assert!(client.check_health().await.iter().all(|h| h.healthy));
+        assert_eq!(client.complete(request("claude-3-5-haiku-20241022")).await.unwrap().content, "anthropic");
+
+        // With every provider down the call is still attempted
+        backup.fail_with(Some(outage));
+        primary.fail_with(Some(outage));
+        client.check_health().await;
+        assert!(matches!(client.complete(request("claude-3-5-haiku-20241022")).await, Err(LlmError::ServerError { .. })));
+    }
+
+    #[test]
+    fn test_config_from_strings() {
+        assert_eq!("claude-*=anthropic".parse::<ModelRoute>().unwrap(), ModelRoute::new("claude-*", LlmProvider::Anthropic));
+        assert!("claude-*".parse::<ModelRoute>().is_err());
+        assert!("claude-*=bedrock".parse::<ModelRoute>().is_err());
+
+        let config = LlmConfig {
+            routing: RoutingConfig { providers: vec![LlmProvider::Anthropic, LlmProvider::Mock], ..Default::default() },
+            ..Default::default()
+        };
+        // No Anthropic key: only the mock is routed
+        let client = RoutingLlmClient::from_config(&config, &reqwest::Client::new()).unwrap();
+        assert_eq!(client.provider(), LlmProvider::Mock);
+        assert!(client.is_mock());
+
+        let mock_only = RoutingLlmClient::new().with_provider(Arc::new(MockLlmClient::default()));
+        assert!(mock_only.supports_model("anything"));
+    }
+}
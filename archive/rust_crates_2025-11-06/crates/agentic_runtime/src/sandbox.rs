@@ -0,0 +1,367 @@
+//! Per-execution resource limits
+//!
+//! An [`ExecutionSandbox`] is created for each `execute` call and counts the
+//! LLM calls, tokens, estimated cost, elapsed time and tool-call nesting of
+//! that one run.
+//! When a limit is reached the run is aborted with
+//! `Error::SandboxLimitExceeded`, which carries the steps recorded so far.
+
+use crate::config::ExecutionConfig;
+use crate::llm::{LlmClient, LlmRequest, LlmResponse};
+use agentic_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// The limit that aborted an execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SandboxLimit {
+    LlmCalls,
+    WallTime,
+    Tokens,
+    ToolDepth,
+    /// The execution's estimated spend passed its budget
+    Cost,
+}
+
+impl SandboxLimit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SandboxLimit::LlmCalls => "llm_calls",
+            SandboxLimit::WallTime => "wall_time",
+            SandboxLimit::Tokens => "tokens",
+            SandboxLimit::ToolDepth => "tool_depth",
+            SandboxLimit::Cost => "cost",
+        }
+    }
+}
+
+impl fmt::Display for SandboxLimit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Limits for a single execution
+#[derive(Debug, Clone, PartialEq)]
+pub struct SandboxLimits {
+    pub max_llm_calls: u32,
+    pub max_wall_time: Duration,
+    pub max_tokens: usize,
+    pub max_tool_depth: u32,
+    /// Estimated USD the execution may spend; `None` for no budget
+    pub max_cost_usd: Option<f64>,
+}
+
+impl SandboxLimits {
+    pub fn from_config(config: &ExecutionConfig) -> Self {
+        Self {
+            max_llm_calls: config.max_llm_calls,
+            max_wall_time: Duration::from_secs(config.agent_timeout_seconds),
+            max_tokens: config.max_tokens_per_execution,
+            max_tool_depth: config.max_tool_depth,
+            max_cost_usd: config.max_cost_usd_per_execution,
+        }
+    }
+}
+
+impl Default for SandboxLimits {
+    fn default() -> Self {
+        Self::from_config(&ExecutionConfig::default())
+    }
+}
+
+/// One recorded step of an execution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TraceStep {
+    LlmCall { model: String, tokens: usize, elapsed_ms: u64 },
+    ToolCall { name: String, depth: u32, elapsed_ms: u64 },
+}
+
+/// Budget tracker for one execution
+#[derive(Debug)]
+pub struct ExecutionSandbox {
+    limits: SandboxLimits,
+    started: Instant,
+    llm_calls: u32,
+    tokens: usize,
+    cost_usd: f64,
+    tool_depth: u32,
+    trace: Vec<TraceStep>,
+}
+
+impl ExecutionSandbox {
+    pub fn new(limits: SandboxLimits) -> Self {
+        Self {
+            limits,
+            started: Instant::now(),
+            llm_calls: 0,
+            tokens: 0,
+            cost_usd: 0.0,
+            tool_depth: 0,
+            trace: Vec::new(),
+        }
+    }
+
+    pub fn limits(&self) -> &SandboxLimits {
+        &self.limits
+    }
+
+    /// Steps recorded so far
+    pub fn trace(&self) -> &[TraceStep] {
+        &self.trace
+    }
+
+    pub fn tokens_used(&self) -> usize {
+        self.tokens
+    }
+
+    /// Estimated USD spent so far, at list prices
+    pub fn cost_usd(&self) -> f64 {
+        self.cost_usd
+    }
+
+    /// The trace carried by a `SandboxLimitExceeded` error
+    pub fn partial_trace(error: &Error) -> Option<Vec<TraceStep>> {
+        match error {
+            Error::SandboxLimitExceeded { trace, .. } => serde_json::from_value(trace.clone()).ok(),
+            _ => None,
+        }
+    }
+
+    /// Send `request` through `client`, counting it against the limits
+    ///
+    /// The call itself is cut off when the remaining wall time runs out.
+    /// Errors from the client are passed through unchanged.
+    pub async fn complete(&mut self, client: &dyn LlmClient, request: LlmRequest) -> Result<LlmResponse> {
+        if self.llm_calls >= self.limits.max_llm_calls {
+            return Err(self.exceeded(
+                SandboxLimit::LlmCalls,
+                format!("{} call(s) allowed", self.limits.max_llm_calls),
+            ));
+        }
+        let remaining = self.remaining_time()?;
+
+        self.llm_calls += 1;
+        let model = request.model.clone();
+        let response = match tokio::time::timeout(remaining, client.complete(request)).await {
+            Ok(response) => response?,
+            Err(_) => return Err(self.wall_time_exceeded()),
+        };
+
+        self.tokens += response.usage.total_tokens;
+        self.cost_usd += response.estimated_cost();
+        self.trace.push(TraceStep::LlmCall {
+            model,
+            tokens: response.usage.total_tokens,
+            elapsed_ms: self.elapsed_ms(),
+        });
+        if self.tokens > self.limits.max_tokens {
+            return Err(self.exceeded(
+                SandboxLimit::Tokens,
+                format!("{} of {} tokens used", self.tokens, self.limits.max_tokens),
+            ));
+        }
+        if let Some(budget) = self.limits.max_cost_usd.filter(|budget| self.cost_usd > *budget) {
+            return Err(self.exceeded(
+                SandboxLimit::Cost,
+                format!("${:.6} of ${:.6} budget spent", self.cost_usd, budget),
+            ));
+        }
+
+        Ok(response)
+    }
+
+    /// Record entering a tool call; pair with [`ExecutionSandbox::exit_tool`]
+    pub fn enter_tool(&mut self, name: impl Into<String>) -> Result<()> {
+        self.remaining_time()?;
+        if self.tool_depth >= self.limits.max_tool_depth {
+            return Err(self.exceeded(
+                SandboxLimit::ToolDepth,
+                format!("tool calls nested deeper than {}", self.limits.max_tool_depth),
+            ));
+        }
+
+        self.tool_depth += 1;
+        self.trace.push(TraceStep::ToolCall {
+            name: name.into(),
+            depth: self.tool_depth,
+            elapsed_ms: self.elapsed_ms(),
+        });
+        Ok(())
+    }
+
+    pub fn exit_tool(&mut self) {
+        self.tool_depth = self.tool_depth.saturating_sub(1);
+    }
+
+    /// Run `call` as tool `name`, counting its nesting and cutting it off when
+    /// the remaining wall time runs out
+    pub async fn run_tool<T>(
+        &mut self,
+        name: impl Into<String>,
+        call: impl Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let remaining = self.remaining_time()?;
+        self.enter_tool(name)?;
+        let result = match tokio::time::timeout(remaining, call).await {
+            Ok(result) => result,
+            Err(_) => Err(self.wall_time_exceeded()),
+        };
+        self.exit_tool();
+        result
+    }
+
+    fn remaining_time(&self) -> Result<Duration> {
+        self.limits
+            .max_wall_time
+            .checked_sub(self.started.elapsed())
+            .filter(|remaining| !remaining.is_zero())
+            .ok_or_else(|| self.wall_time_exceeded())
+    }
+
+    fn wall_time_exceeded(&self) -> Error {
+        self.exceeded(
+            SandboxLimit::WallTime,
+            format!("ran longer than {}ms", self.limits.max_wall_time.as_millis()),
+        )
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        self.started.elapsed().as_millis() as u64
+    }
+
+    fn exceeded(&self, limit: SandboxLimit, detail: String) -> Error {
+        Error::SandboxLimitExceeded {
+            limit: limit.to_string(),
+            detail,
+            trace: serde_json::to_value(&self.trace).unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{Message, MockLlmClient};
+
+    fn limits() -> SandboxLimits {
+        SandboxLimits {
+            max_llm_calls: 10,
+            max_wall_time: Duration::from_secs(10),
+            max_tokens: 1_000,
+            max_tool_depth: 3,
+            max_cost_usd: None,
+        }
+    }
+
+    fn request() -> LlmRequest {
+        LlmRequest::new("mock-model").add_message(Message::user("hi"))
+    }
+
+    fn tripped(error: &Error) -> &str {
+        match error {
+            Error::SandboxLimitExceeded { limit, .. } => limit,
+            other => panic!("expected a sandbox error, got {}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_llm_call_limit() {
+        let client = MockLlmClient::default();
+        let mut sandbox = ExecutionSandbox::new(SandboxLimits { max_llm_calls: 2, ..limits() });
+
+        sandbox.complete(&client, request()).await.unwrap();
+        sandbox.complete(&client, request()).await.unwrap();
+        let err = sandbox.complete(&client, request()).await.unwrap_err();
+
+        assert_eq!(tripped(&err), "llm_calls");
+        assert_eq!(ExecutionSandbox::partial_trace(&err).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_token_limit() {
+        // Each mock call reports 30 tokens
+        let client = MockLlmClient::default();
+        let mut sandbox = ExecutionSandbox::new(SandboxLimits { max_tokens: 50, ..limits() });
+
+        sandbox.complete(&client, request()).await.unwrap();
+        let err = sandbox.complete(&client, request()).await.unwrap_err();
+
+        assert_eq!(tripped(&err), "tokens");
+        assert_eq!(sandbox.tokens_used(), 60);
+        assert_eq!(ExecutionSandbox::partial_trace(&err).unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cost_budget() {
+        // 10 prompt and 20 completion tokens of gpt-4o-mini cost $0.0000135 per call
+        let client = MockLlmClient::default();
+        let request = || LlmRequest::new("gpt-4o-mini").add_message(Message::user("hi"));
+        let mut sandbox = ExecutionSandbox::new(SandboxLimits { max_cost_usd: Some(0.000_02), ..limits() });
+
+        sandbox.complete(&client, request()).await.unwrap();
+        let err = sandbox.complete(&client, request()).await.unwrap_err();
+
+        assert_eq!(tripped(&err), "cost");
+        assert!(sandbox.cost_usd() > 0.000_02);
+    }
+
+    #[tokio::test]
+    async fn test_wall_time_limit() {
+        let client = MockLlmClient::default().with_latency(Duration::from_secs(5));
+        let mut sandbox = ExecutionSandbox::new(SandboxLimits {
+            max_wall_time: Duration::from_millis(20),
+            ..limits()
+        });
+
+        let err = sandbox.complete(&client, request()).await.unwrap_err();
+
+        assert_eq!(tripped(&err), "wall_time");
+        assert!(ExecutionSandbox::partial_trace(&err).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_tool_depth_limit() {
+        let mut sandbox = ExecutionSandbox::new(SandboxLimits { max_tool_depth: 2, ..limits() });
+
+        sandbox.enter_tool("search").unwrap();
+        sandbox.enter_tool("fetch").unwrap();
+        let err = sandbox.enter_tool("parse").unwrap_err();
+        assert_eq!(tripped(&err), "tool_depth");
+        assert_eq!(ExecutionSandbox::partial_trace(&err).unwrap().len(), 2);
+
+        // Returning from a tool frees a level
+        sandbox.exit_tool();
+        assert!(sandbox.enter_tool("parse").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_run_tool_is_cut_off_at_wall_time() {
+        let mut sandbox = ExecutionSandbox::new(SandboxLimits {
+            max_wall_time: Duration::from_millis(20),
+            ..limits()
+        });
+
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok("done")
+        };
+        let err = sandbox.run_tool("search", slow).await.unwrap_err();
+
+        assert_eq!(tripped(&err), "wall_time");
+        assert_eq!(ExecutionSandbox::partial_trace(&err).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_run_tool_frees_its_level() {
+        let mut sandbox = ExecutionSandbox::new(SandboxLimits { max_tool_depth: 1, ..limits() });
+
+        assert_eq!(sandbox.run_tool("search", async { Ok(1) }).await.unwrap(), 1);
+        assert_eq!(sandbox.run_tool("fetch", async { Ok(2) }).await.unwrap(), 2);
+        assert_eq!(sandbox.trace().len(), 2);
+    }
+}
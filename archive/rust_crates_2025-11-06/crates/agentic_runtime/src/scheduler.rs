@@ -1,12 +1,16 @@
 //! Task scheduler for managing agent execution queue
 
-use agentic_core::{AgentId, WorkflowId};
+use crate::concurrency::LlmConcurrencyLimiter;
+use agentic_core::clock::{system_clock, SharedClock};
+use agentic_core::{AgentId, Error, Result, WorkflowId};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
 use std::sync::{Arc, Mutex};
-use tokio::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Notify};
 use uuid::Uuid;
 
 /// Task priority levels
@@ -18,12 +22,32 @@ pub enum TaskPriority {
     Critical = 4,
 }
 
+impl TaskPriority {
+    pub fn all() -> &'static [TaskPriority] {
+        &[TaskPriority::Low, TaskPriority::Normal, TaskPriority::High, TaskPriority::Critical]
+    }
+
+    /// Raise by `levels`, never past `High`; `Critical` stays `Critical`
+    fn aged(self, levels: i64) -> Self {
+        if self == TaskPriority::Critical {
+            return self;
+        }
+        match (self as i64 + levels).min(TaskPriority::High as i64) {
+            i64::MIN..=1 => TaskPriority::Low,
+            2 => TaskPriority::Normal,
+            _ => TaskPriority::High,
+        }
+    }
+}
+
 /// Status of a task
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TaskStatus {
     Pending,
     Running,
     Completed,
+    /// Completed, but after its deadline
+    Missed,
     Failed,
     Cancelled,
 }
@@ -44,10 +68,18 @@ pub struct Task {
     pub error: Option<String>,
     pub retry_count: u32,
     pub max_retries: u32,
+    /// Soft deadline; earlier deadlines run first within a priority tier
+    #[serde(default)]
+    pub deadline: Option<DateTime<Utc>>,
 }
 
 impl Task {
     pub fn new(agent_id: AgentId, input: impl Into<String>) -> Self {
+        Self::new_at(agent_id, input, Utc::now())
+    }
+
+    /// Task created at `now` instead of the system time
+    pub fn new_at(agent_id: AgentId, input: impl Into<String>, now: DateTime<Utc>) -> Self {
         Self {
             id: Uuid::new_v4().to_string(),
             agent_id,
@@ -55,13 +87,14 @@ impl Task {
             input: input.into(),
             priority: TaskPriority::Normal,
             status: TaskStatus::Pending,
-            created_at: Utc::now(),
+            created_at: now,
             started_at: None,
             completed_at: None,
             result: None,
             error: None,
             retry_count: 0,
             max_retries: 3,
+            deadline: None,
         }
     }
 
@@ -80,20 +113,56 @@ impl Task {
         self
     }
 
+    pub fn with_deadline(mut self, deadline: DateTime<Utc>) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Time left until the deadline (negative once it has passed)
+    pub fn time_to_deadline(&self) -> Option<chrono::Duration> {
+        self.time_to_deadline_at(Utc::now())
+    }
+
+    pub fn time_to_deadline_at(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        self.deadline.map(|d| d - now)
+    }
+
     pub fn mark_running(&mut self) {
+        self.mark_running_at(Utc::now());
+    }
+
+    pub fn mark_running_at(&mut self, now: DateTime<Utc>) {
         self.status = TaskStatus::Running;
-        self.started_at = Some(Utc::now());
+        self.started_at = Some(now);
     }
 
+    /// Record the result; finishing after the deadline marks the task `Missed`
     pub fn mark_completed(&mut self, result: String) {
-        self.status = TaskStatus::Completed;
-        self.completed_at = Some(Utc::now());
+        self.mark_completed_at(result, Utc::now());
+    }
+
+    pub fn mark_completed_at(&mut self, result: String, now: DateTime<Utc>) {
+        self.status = match self.deadline {
+            Some(deadline) if now > deadline => TaskStatus::Missed,
+            _ => TaskStatus::Completed,
+        };
+        self.completed_at = Some(now);
         self.result = Some(result);
     }
 
+    pub fn mark_cancelled_at(&mut self, reason: String, now: DateTime<Utc>) {
+        self.status = TaskStatus::Cancelled;
+        self.completed_at = Some(now);
+        self.error = Some(reason);
+    }
+
     pub fn mark_failed(&mut self, error: String) {
+        self.mark_failed_at(error, Utc::now());
+    }
+
+    pub fn mark_failed_at(&mut self, error: String, now: DateTime<Utc>) {
         self.status = TaskStatus::Failed;
-        self.completed_at = Some(Utc::now());
+        self.completed_at = Some(now);
         self.error = Some(error);
     }
 
@@ -110,11 +179,22 @@ impl Task {
 #[derive(Clone)]
 struct PrioritizedTask {
     task: Task,
+    /// When the task entered the queue, for aging
+    enqueued_at: DateTime<Utc>,
+    /// `task.priority` raised by aging; used for ordering
+    effective_priority: TaskPriority,
+}
+
+impl PrioritizedTask {
+    fn new(task: Task, enqueued_at: DateTime<Utc>) -> Self {
+        let effective_priority = task.priority;
+        Self { task, enqueued_at, effective_priority }
+    }
 }
 
 impl PartialEq for PrioritizedTask {
     fn eq(&self, other: &Self) -> bool {
-        self.task.priority == other.task.priority
+        self.cmp(other) == Ordering::Equal
     }
 }
 
@@ -128,9 +208,15 @@ impl PartialOrd for PrioritizedTask {
 
 impl Ord for PrioritizedTask {
     fn cmp(&self, other: &Self) -> Ordering {
-        // Higher priority first
-        self.task.priority.cmp(&other.task.priority)
-            .then_with(|| other.task.created_at.cmp(&self.task.created_at)) // Earlier tasks first if same priority
+        // Higher priority first, then earliest deadline (tasks without one last)
+        self.effective_priority.cmp(&other.effective_priority)
+            .then_with(|| match (self.task.deadline, other.task.deadline) {
+                (Some(a), Some(b)) => b.cmp(&a),
+                (Some(_), None) => Ordering::Greater,
+                (None, Some(_)) => Ordering::Less,
+                (None, None) => Ordering::Equal,
+            })
+            .then_with(|| other.enqueued_at.cmp(&self.enqueued_at)) // Earlier tasks first if same priority
     }
 }
 
@@ -140,6 +226,15 @@ pub struct TaskScheduler {
     tasks: Arc<Mutex<HashMap<String, Task>>>,
     task_tx: mpsc::UnboundedSender<Task>,
     task_rx: Arc<Mutex<mpsc::UnboundedReceiver<Task>>>,
+    clock: SharedClock,
+    /// Waiting this long raises a queued task's priority by one level
+    aging_interval: Option<chrono::Duration>,
+    /// Limiter whose capacity is reported by [`TaskScheduler::status`]
+    limiter: LlmConcurrencyLimiter,
+    /// Set by [`TaskScheduler::drain`]; no task is accepted or started after that
+    draining: AtomicBool,
+    /// Signalled whenever a running task finishes
+    task_finished: Notify,
 }
 
 impl TaskScheduler {
@@ -151,11 +246,77 @@ impl TaskScheduler {
             tasks: Arc::new(Mutex::new(HashMap::new())),
             task_tx,
             task_rx: Arc::new(Mutex::new(task_rx)),
+            clock: system_clock(),
+            aging_interval: None,
+            limiter: LlmConcurrencyLimiter::global().clone(),
+            draining: AtomicBool::new(false),
+            task_finished: Notify::new(),
         }
     }
 
+    /// Report `limiter` in [`TaskScheduler::status`] instead of the global one
+    pub fn with_concurrency_limiter(mut self, limiter: LlmConcurrencyLimiter) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Change the LLM concurrency limit reported as `max_concurrency`; the
+    /// limiter is shared, so calls through its clients follow the new limit
+    pub fn set_max_concurrency(&self, limit: usize) {
+        self.limiter.set_limit(limit);
+    }
+
+    /// Take timestamps from `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Raise a queued task one priority level per `interval` it has waited
+    ///
+    /// Aging stops at `High`, so starved work never jumps ahead of
+    /// `Critical` tasks. The task's own `priority` is left unchanged.
+    pub fn with_priority_aging(mut self, interval: chrono::Duration) -> Self {
+        self.aging_interval = Some(interval);
+        self
+    }
+
+    /// Recompute effective priorities of queued tasks
+    fn apply_aging(&self, queue: &mut BinaryHeap<PrioritizedTask>) {
+        let Some(interval) = self.aging_interval.filter(|i| *i > chrono::Duration::zero()) else {
+            return;
+        };
+
+        let now = self.clock.now();
+        let interval_ms = interval.num_milliseconds().max(1);
+        let aged: Vec<PrioritizedTask> = std::mem::take(queue)
+            .into_iter()
+            .map(|mut pt| {
+                let levels = (now - pt.enqueued_at).num_milliseconds() / interval_ms;
+                pt.effective_priority = pt.task.priority.aged(levels);
+                pt
+            })
+            .collect();
+        *queue = aged.into();
+    }
+
+    /// New task for `agent_id`, created at the scheduler's current time
+    pub fn new_task(&self, agent_id: AgentId, input: impl Into<String>) -> Task {
+        Task::new_at(agent_id, input, self.clock.now())
+    }
+
+    /// Time left until `task`'s deadline by the scheduler's clock
+    pub fn time_to_deadline(&self, task: &Task) -> Option<chrono::Duration> {
+        task.time_to_deadline_at(self.clock.now())
+    }
+
     /// Submit a new task to the scheduler
-    pub fn submit(&self, mut task: Task) -> Result<String, String> {
+    ///
+    /// Fails with `Error::ShuttingDown` once [`TaskScheduler::drain`] was called.
+    pub fn submit(&self, mut task: Task) -> Result<String> {
+        if self.is_draining() {
+            return Err(Error::ShuttingDown("Scheduler is not accepting new tasks".to_string()));
+        }
         task.status = TaskStatus::Pending;
         let task_id = task.id.clone();
 
@@ -163,22 +324,26 @@ impl TaskScheduler {
         self.tasks.lock().unwrap().insert(task_id.clone(), task.clone());
 
         // Add to priority queue
-        self.queue.lock().unwrap().push(PrioritizedTask { task: task.clone() });
+        self.queue.lock().unwrap().push(PrioritizedTask::new(task.clone(), self.clock.now()));
 
         // Send notification
         if let Err(e) = self.task_tx.send(task) {
-            return Err(format!("Failed to submit task: {}", e));
+            return Err(Error::InternalError(format!("Failed to submit task: {}", e)));
         }
 
         Ok(task_id)
     }
 
-    /// Get the next task from the queue
+    /// Get the next task from the queue; `None` while draining
     pub fn next_task(&self) -> Option<Task> {
+        if self.is_draining() {
+            return None;
+        }
         let mut queue = self.queue.lock().unwrap();
+        self.apply_aging(&mut queue);
         queue.pop().map(|pt| {
             let mut task = pt.task;
-            task.mark_running();
+            task.mark_running_at(self.clock.now());
 
             // Update task in storage
             self.tasks.lock().unwrap().insert(task.id.clone(), task.clone());
@@ -199,27 +364,98 @@ impl TaskScheduler {
         }
     }
 
-    /// Complete a task
+    /// Complete a task; a task cancelled by [`TaskScheduler::drain`] stays cancelled
     pub fn complete_task(&self, task_id: &str, result: String) {
+        let now = self.clock.now();
         self.update_task(task_id, |task| {
-            task.mark_completed(result);
+            if task.status != TaskStatus::Cancelled {
+                task.mark_completed_at(result, now);
+            }
         });
+        self.task_finished.notify_one();
     }
 
-    /// Fail a task
+    /// Fail a task; a task cancelled by [`TaskScheduler::drain`] stays cancelled
     pub fn fail_task(&self, task_id: &str, error: String) {
+        let now = self.clock.now();
         self.update_task(task_id, |task| {
-            task.mark_failed(error);
+            if task.status != TaskStatus::Cancelled {
+                task.mark_failed_at(error, now);
+            }
         });
+        self.task_finished.notify_one();
+    }
+
+    /// Whether [`TaskScheduler::drain`] has been called
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(AtomicOrdering::SeqCst)
+    }
+
+    /// Stop the scheduler: refuse new tasks, let running ones finish for up
+    /// to `grace`, then cancel whatever is still running
+    ///
+    /// Queued tasks that never started are cancelled straight away. Workers
+    /// should stop work on a task once [`TaskScheduler::get_task`] reports it
+    /// `Cancelled`; a late `complete_task` or `fail_task` is ignored.
+    pub async fn drain(&self, grace: Duration) -> DrainReport {
+        self.draining.store(true, AtomicOrdering::SeqCst);
+        let now = self.clock.now();
+
+        let queued = std::mem::take(&mut *self.queue.lock().unwrap());
+        let cancelled_queued = queued.len();
+        for pt in queued {
+            self.update_task(&pt.task.id, |task| {
+                task.mark_cancelled_at("Scheduler shut down before the task started".to_string(), now);
+            });
+        }
+
+        let running_at_start = self.running_task_ids().len();
+        let deadline = tokio::time::Instant::now() + grace;
+        while !self.running_task_ids().is_empty() {
+            if tokio::time::timeout_at(deadline, self.task_finished.notified()).await.is_err() {
+                break;
+            }
+        }
+
+        let stragglers = self.running_task_ids();
+        let now = self.clock.now();
+        for id in &stragglers {
+            self.update_task(id, |task| {
+                task.mark_cancelled_at(format!("Cancelled after {:?} shutdown grace period", grace), now);
+            });
+        }
+        if !stragglers.is_empty() {
+            tracing::warn!("Scheduler drained: cancelled {} running tasks", stragglers.len());
+        }
+
+        DrainReport {
+            finished: running_at_start.saturating_sub(stragglers.len()),
+            cancelled_running: stragglers.len(),
+            cancelled_queued,
+        }
+    }
+
+    fn running_task_ids(&self) -> Vec<String> {
+        self.tasks.lock().unwrap()
+            .values()
+            .filter(|t| t.status == TaskStatus::Running)
+            .map(|t| t.id.clone())
+            .collect()
     }
 
     /// Retry a task if possible
-    pub fn retry_task(&self, task_id: &str) -> Result<(), String> {
+    ///
+    /// Fails with `Error::ShuttingDown` once [`TaskScheduler::drain`] was
+    /// called, like [`TaskScheduler::submit`].
+    pub fn retry_task(&self, task_id: &str) -> Result<()> {
+        if self.is_draining() {
+            return Err(Error::ShuttingDown("Scheduler is not accepting new tasks".to_string()));
+        }
         let task = self.get_task(task_id)
-            .ok_or_else(|| format!("Task {} not found", task_id))?;
+            .ok_or_else(|| Error::TaskNotFound(task_id.to_string()))?;
 
         if !task.can_retry() {
-            return Err(format!("Task {} has exceeded max retries", task_id));
+            return Err(Error::Conflict(format!("Task {} has exceeded max retries", task_id)));
         }
 
         let mut new_task = task.clone();
@@ -230,7 +466,7 @@ impl TaskScheduler {
         new_task.result = None;
         new_task.error = None;
 
-        self.queue.lock().unwrap().push(PrioritizedTask { task: new_task.clone() });
+        self.queue.lock().unwrap().push(PrioritizedTask::new(new_task.clone(), self.clock.now()));
         self.tasks.lock().unwrap().insert(task_id.to_string(), new_task);
 
         Ok(())
@@ -261,6 +497,7 @@ impl TaskScheduler {
         let running = tasks.values().filter(|t| t.status == TaskStatus::Running).count();
         let completed = tasks.values().filter(|t| t.status == TaskStatus::Completed).count();
         let failed = tasks.values().filter(|t| t.status == TaskStatus::Failed).count();
+        let missed = tasks.values().filter(|t| t.status == TaskStatus::Missed).count();
 
         SchedulerStats {
             total: tasks.len(),
@@ -268,9 +505,39 @@ impl TaskScheduler {
             running,
             completed,
             failed,
+            missed,
             queue_size: self.queue.lock().unwrap().len(),
         }
     }
+
+    /// What the scheduler is doing right now
+    pub fn status(&self) -> SchedulerStatus {
+        let running = self.tasks.lock().unwrap()
+            .values()
+            .filter(|t| t.status == TaskStatus::Running)
+            .count();
+
+        let queue = self.queue.lock().unwrap();
+        let mut queued_by_priority: BTreeMap<TaskPriority, usize> =
+            TaskPriority::all().iter().map(|p| (*p, 0)).collect();
+        for pt in queue.iter() {
+            *queued_by_priority.entry(pt.task.priority).or_insert(0) += 1;
+        }
+        let oldest_queued_age_ms = queue
+            .iter()
+            .map(|pt| pt.enqueued_at)
+            .min()
+            .map(|oldest| (self.clock.now() - oldest).num_milliseconds().max(0));
+
+        SchedulerStatus {
+            running,
+            queued: queue.len(),
+            queued_by_priority,
+            oldest_queued_age_ms,
+            max_concurrency: self.limiter.limit(),
+            llm_calls_in_flight: self.limiter.in_flight(),
+        }
+    }
 }
 
 impl Default for TaskScheduler {
@@ -286,9 +553,35 @@ pub struct SchedulerStats {
     pub running: usize,
     pub completed: usize,
     pub failed: usize,
+    pub missed: usize,
     pub queue_size: usize,
 }
 
+/// Outcome of [`TaskScheduler::drain`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrainReport {
+    /// Tasks that were running and finished within the grace period
+    pub finished: usize,
+    /// Tasks still running when the grace period ended
+    pub cancelled_running: usize,
+    /// Queued tasks that were never started
+    pub cancelled_queued: usize,
+}
+
+/// Snapshot returned by [`TaskScheduler::status`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerStatus {
+    pub running: usize,
+    pub queued: usize,
+    /// Queued tasks by submitted priority (aging is not reflected); every level is present
+    pub queued_by_priority: BTreeMap<TaskPriority, usize>,
+    /// How long the longest-waiting queued task has been queued
+    pub oldest_queued_age_ms: Option<i64>,
+    /// Most LLM calls allowed in flight at once
+    pub max_concurrency: usize,
+    pub llm_calls_in_flight: usize,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -341,4 +634,152 @@ mod tests {
         let task3 = scheduler.next_task().unwrap();
         assert_eq!(task3.priority, TaskPriority::Low);
     }
+
+    #[test]
+    fn test_earliest_deadline_first_within_priority() {
+        let scheduler = TaskScheduler::new();
+        let agent_id = AgentId::generate();
+        let now = Utc::now();
+
+        let later = Task::new(agent_id, "Later").with_deadline(now + chrono::Duration::hours(2));
+        let none = Task::new(agent_id, "No deadline");
+        let sooner = Task::new(agent_id, "Sooner").with_deadline(now + chrono::Duration::minutes(5));
+        let urgent = Task::new(agent_id, "High").with_priority(TaskPriority::High);
+
+        scheduler.submit(later).unwrap();
+        scheduler.submit(none).unwrap();
+        scheduler.submit(sooner).unwrap();
+        scheduler.submit(urgent).unwrap();
+
+        // Priority still wins over deadlines
+        assert_eq!(scheduler.next_task().unwrap().input, "High");
+        assert_eq!(scheduler.next_task().unwrap().input, "Sooner");
+        assert_eq!(scheduler.next_task().unwrap().input, "Later");
+        assert_eq!(scheduler.next_task().unwrap().input, "No deadline");
+    }
+
+    #[test]
+    fn test_late_completion_is_marked_missed() {
+        let scheduler = TaskScheduler::new();
+        let task = Task::new(AgentId::generate(), "Late")
+            .with_deadline(Utc::now() - chrono::Duration::seconds(1));
+        let id = scheduler.submit(task).unwrap();
+
+        assert!(scheduler.get_task(&id).unwrap().time_to_deadline().unwrap() < chrono::Duration::zero());
+
+        scheduler.complete_task(&id, "done".to_string());
+        let task = scheduler.get_task(&id).unwrap();
+        assert_eq!(task.status, TaskStatus::Missed);
+        assert_eq!(task.result.as_deref(), Some("done"));
+        assert_eq!(scheduler.stats().missed, 1);
+    }
+
+    #[test]
+    fn test_task_times_follow_the_scheduler_clock() {
+        use agentic_core::Clock;
+
+        let clock = agentic_core::MockClock::default();
+        let scheduler = TaskScheduler::new().with_clock(clock.shared());
+        let task = scheduler
+            .new_task(AgentId::generate(), "Timed")
+            .with_deadline(clock.now() + chrono::Duration::minutes(30));
+        assert_eq!(task.created_at, clock.now());
+
+        clock.advance(chrono::Duration::minutes(40));
+        assert_eq!(scheduler.time_to_deadline(&task), Some(chrono::Duration::minutes(-10)));
+    }
+
+    #[test]
+    fn test_waiting_task_is_aged_past_newer_work() {
+        use agentic_core::Clock;
+
+        let clock = agentic_core::MockClock::default();
+        let scheduler = TaskScheduler::new()
+            .with_clock(clock.shared())
+            .with_priority_aging(chrono::Duration::minutes(10));
+        let agent_id = AgentId::generate();
+
+        scheduler.submit(Task::new(agent_id, "Old low").with_priority(TaskPriority::Low)).unwrap();
+        clock.advance(chrono::Duration::minutes(5));
+        scheduler.submit(Task::new(agent_id, "Fresh normal")).unwrap();
+
+        // Not aged yet: priority decides
+        clock.advance(chrono::Duration::minutes(4));
+        let first = scheduler.next_task().unwrap();
+        assert_eq!(first.input, "Fresh normal");
+        assert_eq!(first.started_at, Some(clock.now()));
+
+        // 20 minutes in, Low has aged two levels to High and overtakes new Normal work
+        clock.advance(chrono::Duration::minutes(11));
+        scheduler.submit(Task::new(agent_id, "Fresh normal 2")).unwrap();
+        let aged = scheduler.next_task().unwrap();
+        assert_eq!(aged.input, "Old low");
+        assert_eq!(aged.priority, TaskPriority::Low);
+    }
+
+    #[test]
+    fn test_status_reports_queue_depths_and_running() {
+        let clock = agentic_core::MockClock::default();
+        let scheduler = TaskScheduler::new()
+            .with_clock(clock.shared())
+            .with_concurrency_limiter(LlmConcurrencyLimiter::new(3));
+        let agent_id = AgentId::generate();
+
+        scheduler.submit(Task::new(agent_id, "a").with_priority(TaskPriority::High)).unwrap();
+        clock.advance(chrono::Duration::seconds(30));
+        scheduler.submit(Task::new(agent_id, "b")).unwrap();
+        scheduler.submit(Task::new(agent_id, "c")).unwrap();
+        scheduler.submit(Task::new(agent_id, "d").with_priority(TaskPriority::Low)).unwrap();
+        assert_eq!(scheduler.status().oldest_queued_age_ms, Some(30_000));
+
+        // Takes the High task, leaving the Normal and Low ones queued
+        scheduler.next_task().unwrap();
+        let status = scheduler.status();
+
+        assert_eq!(status.running, 1);
+        assert_eq!(status.queued, 3);
+        assert_eq!(status.queued_by_priority[&TaskPriority::Normal], 2);
+        assert_eq!(status.queued_by_priority[&TaskPriority::Low], 1);
+        assert_eq!(status.queued_by_priority[&TaskPriority::High], 0);
+        assert_eq!(status.oldest_queued_age_ms, Some(0));
+        assert_eq!(status.max_concurrency, 3);
+        assert_eq!(status.llm_calls_in_flight, 0);
+    }
+
+    #[test]
+    fn test_aging_never_reaches_critical() {
+        assert_eq!(TaskPriority::Low.aged(10), TaskPriority::High);
+        assert_eq!(TaskPriority::Normal.aged(0), TaskPriority::Normal);
+        assert_eq!(TaskPriority::Critical.aged(3), TaskPriority::Critical);
+    }
+
+    #[tokio::test]
+    async fn test_drain_cancels_tasks_past_grace_period() {
+        let scheduler = Arc::new(TaskScheduler::new());
+        let agent_id = AgentId::generate();
+        scheduler.submit(Task::new(agent_id, "long").with_priority(TaskPriority::High)).unwrap();
+        scheduler.submit(Task::new(agent_id, "quick")).unwrap();
+        let queued_id = scheduler.submit(Task::new(agent_id, "never started")).unwrap();
+
+        let long = scheduler.next_task().unwrap();
+        let quick = scheduler.next_task().unwrap();
+        let worker = scheduler.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            worker.complete_task(&quick.id, "done".to_string());
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            worker.complete_task(&long.id, "too late".to_string());
+        });
+
+        let report = scheduler.drain(Duration::from_millis(100)).await;
+
+        assert_eq!(report, DrainReport { finished: 1, cancelled_running: 1, cancelled_queued: 1 });
+        let long = scheduler.get_agent_tasks(&agent_id).into_iter().find(|t| t.input == "long").unwrap();
+        assert_eq!(long.status, TaskStatus::Cancelled);
+        assert_eq!(scheduler.get_task(&queued_id).unwrap().status, TaskStatus::Cancelled);
+
+        let err = scheduler.submit(Task::new(agent_id, "after shutdown")).unwrap_err();
+        assert!(matches!(err, Error::ShuttingDown(_)));
+        assert!(matches!(scheduler.retry_task(&queued_id), Err(Error::ShuttingDown(_))));
+    }
 }
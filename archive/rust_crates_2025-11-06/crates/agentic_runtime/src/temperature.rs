@@ -0,0 +1,189 @@
+//! Default sampling temperature per kind of task
+//!
+//! Agents tag each request with a [`TaskKind`] instead of hard-coding a
+//! temperature; the process-wide [`TemperaturePolicy`] turns that into a
+//! number. A temperature set explicitly on the request still wins.
+
+use agentic_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+static GLOBAL: OnceLock<TemperaturePolicy> = OnceLock::new();
+
+/// What a request is for, as far as sampling is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskKind {
+    /// Source code and tests; wants consistent output
+    Code,
+    /// Scoring, validation and structured extraction
+    Analysis,
+    /// Technical writing
+    Documentation,
+    /// Searching for new ideas and opportunities
+    Discovery,
+    /// Marketing copy, design and brainstorming
+    Creative,
+}
+
+impl TaskKind {
+    pub fn all() -> &'static [TaskKind] {
+        &[
+            TaskKind::Code,
+            TaskKind::Analysis,
+            TaskKind::Documentation,
+            TaskKind::Discovery,
+            TaskKind::Creative,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskKind::Code => "code",
+            TaskKind::Analysis => "analysis",
+            TaskKind::Documentation => "documentation",
+            TaskKind::Discovery => "discovery",
+            TaskKind::Creative => "creative",
+        }
+    }
+
+    fn default_temperature(&self) -> f32 {
+        match self {
+            TaskKind::Code => 0.2,
+            TaskKind::Analysis => 0.3,
+            TaskKind::Documentation => 0.4,
+            TaskKind::Discovery => 0.7,
+            TaskKind::Creative => 0.8,
+        }
+    }
+}
+
+impl FromStr for TaskKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        TaskKind::all()
+            .iter()
+            .copied()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| Error::InvalidArgument(format!("Unknown task kind: {}", s)))
+    }
+}
+
+/// Temperature to use for each [`TaskKind`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemperaturePolicy {
+    temperatures: HashMap<TaskKind, f32>,
+}
+
+impl Default for TemperaturePolicy {
+    fn default() -> Self {
+        Self {
+            temperatures: TaskKind::all()
+                .iter()
+                .map(|kind| (*kind, kind.default_temperature()))
+                .collect(),
+        }
+    }
+}
+
+impl TemperaturePolicy {
+    /// Override the temperature for one kind of task
+    pub fn with(mut self, kind: TaskKind, temperature: f32) -> Self {
+        self.temperatures.insert(kind, temperature);
+        self
+    }
+
+    pub fn temperature_for(&self, kind: TaskKind) -> f32 {
+        self.temperatures
+            .get(&kind)
+            .copied()
+            .unwrap_or_else(|| kind.default_temperature())
+    }
+
+    /// Defaults overridden by `LLM_TEMPERATURES` (e.g. `code=0.1,creative=0.9`)
+    ///
+    /// A malformed variable is ignored rather than failing startup.
+    pub fn from_env() -> Self {
+        std::env::var("LLM_TEMPERATURES")
+            .ok()
+            .and_then(|spec| spec.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Policy consulted by [`LlmRequest::with_task`](crate::llm::LlmRequest::with_task)
+    ///
+    /// Read from the environment on first use unless
+    /// [`TemperaturePolicy::install_global`] ran earlier.
+    pub fn global() -> &'static TemperaturePolicy {
+        GLOBAL.get_or_init(Self::from_env)
+    }
+
+    /// Set the global policy; returns false if it was already initialised
+    pub fn install_global(policy: TemperaturePolicy) -> bool {
+        GLOBAL.set(policy).is_ok()
+    }
+}
+
+impl FromStr for TemperaturePolicy {
+    type Err = Error;
+
+    /// Parse `kind=temperature` pairs separated by commas, on top of the defaults
+    fn from_str(s: &str) -> Result<Self> {
+        let mut policy = Self::default();
+        for pair in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (kind, temperature) = pair
+                .split_once('=')
+                .ok_or_else(|| Error::InvalidArgument(format!("Expected kind=temperature, got {}", pair)))?;
+            let temperature: f32 = temperature
+                .trim()
+                .parse()
+                .map_err(|_| Error::InvalidArgument(format!("Invalid temperature in {}", pair)))?;
+            if !(0.0..=2.0).contains(&temperature) {
+                return Err(Error::InvalidArgument(format!("Temperature {} out of range 0.0-2.0", temperature)));
+            }
+            policy = policy.with(kind.parse()?, temperature);
+        }
+        Ok(policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::LlmRequest;
+
+    #[test]
+    fn test_code_is_cooler_than_discovery() {
+        let policy = TemperaturePolicy::default();
+        assert!(policy.temperature_for(TaskKind::Code) < policy.temperature_for(TaskKind::Discovery));
+    }
+
+    #[test]
+    fn test_parse_overrides_defaults() {
+        let policy: TemperaturePolicy = "code=0.1, creative=1.0".parse().unwrap();
+
+        assert_eq!(policy.temperature_for(TaskKind::Code), 0.1);
+        assert_eq!(policy.temperature_for(TaskKind::Creative), 1.0);
+        assert_eq!(policy.temperature_for(TaskKind::Analysis), 0.3);
+        assert!("code=3.0".parse::<TemperaturePolicy>().is_err());
+        assert!("poetry=0.5".parse::<TemperaturePolicy>().is_err());
+    }
+
+    #[test]
+    fn test_explicit_temperature_wins_over_task() {
+        let request = LlmRequest::builder()
+            .model("mock-model")
+            .user("hi")
+            .temperature(0.9)
+            .task(TaskKind::Code)
+            .build()
+            .unwrap();
+        assert_eq!(request.temperature, Some(0.9));
+
+        let request = LlmRequest::new("mock-model").with_task(TaskKind::Code).with_temperature(0.9);
+        assert_eq!(request.temperature, Some(0.9));
+    }
+}
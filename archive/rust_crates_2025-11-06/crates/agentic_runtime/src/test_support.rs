@@ -0,0 +1,213 @@
+//! LLM clients for tests
+//!
+//! Built for this crate's unit tests and, with the `test-support` feature,
+//! for other crates' tests.
+
+use crate::llm::{
+    is_truncation, LlmClient, LlmError, LlmProvider, LlmRequest, LlmResponse, ModelInfo, Result, TokenUsage,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+
+/// Answers with scripted (content, finish_reason) turns in order, repeating
+/// the last one, and records every request it gets
+pub struct ScriptedLlmClient {
+    provider: LlmProvider,
+    turns: Vec<(&'static str, &'static str)>,
+    requests: Mutex<Vec<LlmRequest>>,
+}
+
+impl ScriptedLlmClient {
+    /// Replies that all finish with `stop`
+    pub fn new(replies: impl IntoIterator<Item = &'static str>) -> Self {
+        Self::with_turns(replies.into_iter().map(|reply| (reply, "stop")))
+    }
+
+    pub fn with_turns(turns: impl IntoIterator<Item = (&'static str, &'static str)>) -> Self {
+        let turns: Vec<_> = turns.into_iter().collect();
+        assert!(!turns.is_empty(), "a scripted client needs at least one reply");
+        Self { provider: LlmProvider::Mock, turns, requests: Mutex::new(Vec::new()) }
+    }
+
+    /// Report `provider` instead of [`LlmProvider::Mock`]
+    pub fn with_provider(mut self, provider: LlmProvider) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    pub fn calls(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+
+    pub fn requests(&self) -> Vec<LlmRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn last_request(&self) -> Option<LlmRequest> {
+        self.requests.lock().unwrap().last().cloned()
+    }
+}
+
+impl Default for ScriptedLlmClient {
+    /// Answers like [`crate::llm::MockLlmClient::default`]
+    fn default() -> Self {
+        Self::new(["Mock LLM response"])
+    }
+}
+
+#[async_trait]
+impl LlmClient for ScriptedLlmClient {
+    fn provider(&self) -> LlmProvider {
+        self.provider
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        let call = {
+            let mut requests = self.requests.lock().unwrap();
+            requests.push(request.clone());
+            requests.len() - 1
+        };
+        let (content, finish_reason) = self.turns[call.min(self.turns.len() - 1)];
+        Ok(LlmResponse {
+            content: content.to_string(),
+            model: request.model,
+            usage: TokenUsage { prompt_tokens: 10, completion_tokens: 5, total_tokens: 15 },
+            finish_reason: finish_reason.to_string(),
+            truncated: is_truncation(finish_reason),
+            metadata: HashMap::new(),
+        })
+    }
+
+    fn supports_model(&self, _model: &str) -> bool {
+        true
+    }
+
+    fn available_models(&self) -> Vec<String> {
+        vec!["mock-model".to_string()]
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Failure {
+    /// Calls left to fail; `None` fails until cleared
+    remaining: Option<u32>,
+    error: fn() -> LlmError,
+}
+
+/// Answers with its provider's name, failing while an outage is set
+///
+/// It serves the models the catalog lists for its provider, or any model
+/// when it is [`LlmProvider::Mock`].
+pub struct FlakyLlmClient {
+    provider: LlmProvider,
+    failure: Mutex<Option<Failure>>,
+    calls: AtomicU32,
+}
+
+impl FlakyLlmClient {
+    pub fn new(provider: LlmProvider) -> Self {
+        Self { provider, failure: Mutex::new(None), calls: AtomicU32::new(0) }
+    }
+
+    /// Fail the first `failures` calls with `error`, then answer
+    pub fn failing_first(failures: u32, error: fn() -> LlmError) -> Self {
+        let client = Self::new(LlmProvider::Mock);
+        *client.failure.lock().unwrap() = Some(Failure { remaining: Some(failures), error });
+        client
+    }
+
+    /// Fail every call with `error` from now on, or answer again with `None`
+    pub fn fail_with(&self, error: Option<fn() -> LlmError>) {
+        *self.failure.lock().unwrap() = error.map(|error| Failure { remaining: None, error });
+    }
+
+    pub fn calls(&self) -> u32 {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    fn next_failure(&self) -> Option<LlmError> {
+        let mut failure = self.failure.lock().unwrap();
+        let Failure { remaining, error } = (*failure)?;
+        match remaining {
+            Some(0) => {
+                *failure = None;
+                None
+            }
+            Some(n) => {
+                *failure = Some(Failure { remaining: Some(n - 1), error });
+                Some(error())
+            }
+            None => Some(error()),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmClient for FlakyLlmClient {
+    fn provider(&self) -> LlmProvider {
+        self.provider
+    }
+
+    async fn complete(&self, request: LlmRequest) -> Result<LlmResponse> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if let Some(error) = self.next_failure() {
+            return Err(error);
+        }
+        Ok(LlmResponse {
+            content: self.provider.as_str().to_string(),
+            model: request.model,
+            usage: TokenUsage::default(),
+            finish_reason: "stop".to_string(),
+            truncated: false,
+            metadata: HashMap::new(),
+        })
+    }
+
+    fn supports_model(&self, model: &str) -> bool {
+        self.provider == LlmProvider::Mock
+            || agentic_core::model_catalog::provider_for_model(model) == Some(self.provider.as_str())
+    }
+
+    fn available_models(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        match *self.failure.lock().unwrap() {
+            Some(Failure { error, .. }) => Err(error()),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Fails every request with `error`
+pub struct FailingLlmClient {
+    error: fn() -> LlmError,
+}
+
+impl FailingLlmClient {
+    pub fn new(error: fn() -> LlmError) -> Self {
+        Self { error }
+    }
+}
+
+#[async_trait]
+impl LlmClient for FailingLlmClient {
+    fn provider(&self) -> LlmProvider {
+        LlmProvider::Mock
+    }
+
+    async fn complete(&self, _request: LlmRequest) -> Result<LlmResponse> {
+        Err((self.error)())
+    }
+
+    fn supports_model(&self, _model: &str) -> bool {
+        true
+    }
+
+    fn available_models(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
@@ -1,6 +1,6 @@
 //! Standards registry, templates, and a standards agent for compliance checks
 
-use agentic_core::{Agent, Protocol, ProtocolVersion};
+use agentic_core::{Agent, ConfigPatch, Protocol, ProtocolVersion, CAPABILITY_CONFIG_PREFIX};
 use agentic_core::identity::AgentId;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -8,13 +8,16 @@ use std::collections::HashMap;
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct StandardId(pub String);
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ComplianceLevel {
     Draft,
     Recommended,
     Required,
 }
 
+/// `Agent.config` key recording the template an agent was created from
+pub const TEMPLATE_CONFIG_KEY: &str = "template";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct StandardSpec {
     pub id: StandardId,
@@ -35,6 +38,10 @@ pub struct ComplianceReport {
     pub missing_protocols: Vec<Protocol>,
     pub missing_capabilities: Vec<String>,
     pub notes: Vec<String>,
+    /// One patch per missing protocol or capability; applying them all
+    /// (`Agent::apply_patch`) makes the agent compliant
+    #[serde(default)]
+    pub suggested_fixes: Vec<ConfigPatch>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -48,6 +55,88 @@ pub struct StandardizedAgentTemplate {
     /// Default capability flags (by name), set into `Agent.config` under keys `cap:<name>`
     pub default_capabilities: Vec<String>,
     pub default_tags: Vec<String>,
+    /// Leave the registry's baseline standards out of this template
+    #[serde(default)]
+    pub skip_baseline: bool,
+}
+
+impl ComplianceReport {
+    /// Human-readable reasons the agent is not compliant (empty when compliant)
+    pub fn reasons(&self) -> Vec<String> {
+        self.missing_protocols
+            .iter()
+            .map(|p| format!("missing protocol {:?}", p))
+            .chain(self.missing_capabilities.iter().map(|c| format!("missing capability {}", c)))
+            .collect()
+    }
+}
+
+fn protocol_config_key(p: &Protocol) -> &'static str {
+    // MVP: consider protocol present if agent.config has key protocol:<name>
+    match p {
+        Protocol::A2A => "protocol:a2a",
+        Protocol::MCP => "protocol:mcp",
+        Protocol::ANS => "protocol:ans",
+        Protocol::HTTP => "protocol:http",
+        Protocol::WebSocket => "protocol:websocket",
+        Protocol::Internal => "protocol:internal",
+    }
+}
+
+/// Version recorded for a protocol the agent is patched to support; the same
+/// values the factory sets on new agents
+fn protocol_config_version(p: &Protocol) -> &'static str {
+    match p {
+        Protocol::HTTP => "1.1",
+        _ => "1.0",
+    }
+}
+
+/// Version recorded for a capability the agent is patched to advertise
+const CAPABILITY_PATCH_VERSION: &str = "1.0.0";
+
+/// A patch per missing item, skipping items listed twice (by two standards)
+fn suggested_fixes(missing_protocols: &[Protocol], missing_capabilities: &[String]) -> Vec<ConfigPatch> {
+    let protocols = missing_protocols
+        .iter()
+        .map(|p| ConfigPatch::new().with(protocol_config_key(p), protocol_config_version(p)));
+    let capabilities = missing_capabilities.iter().map(|cap| {
+        ConfigPatch::new().with(format!("{}{}", CAPABILITY_CONFIG_PREFIX, cap), CAPABILITY_PATCH_VERSION)
+    });
+    let mut fixes: Vec<ConfigPatch> = Vec::new();
+    for fix in protocols.chain(capabilities) {
+        if !fixes.contains(&fix) {
+            fixes.push(fix);
+        }
+    }
+    fixes
+}
+
+impl StandardSpec {
+    /// Check a single standard against the agent
+    pub fn compliance_for(&self, agent: &Agent) -> ComplianceReport {
+        let missing_protocols: Vec<Protocol> = self
+            .required_protocols
+            .iter()
+            .filter(|p| !agent.config.contains_key(protocol_config_key(p)))
+            .copied()
+            .collect();
+        let missing_caps: Vec<String> = self
+            .required_capabilities
+            .iter()
+            .filter(|cap_name| !agent.config.contains_key(&format!("{}{}", CAPABILITY_CONFIG_PREFIX, cap_name)))
+            .cloned()
+            .collect();
+
+        ComplianceReport {
+            standard: self.id.clone(),
+            compliant: missing_protocols.is_empty() && missing_caps.is_empty(),
+            suggested_fixes: suggested_fixes(&missing_protocols, &missing_caps),
+            missing_protocols,
+            missing_capabilities: missing_caps,
+            notes: vec![],
+        }
+    }
 }
 
 impl StandardizedAgentTemplate {
@@ -56,27 +145,9 @@ impl StandardizedAgentTemplate {
         let mut missing_caps = vec![];
 
         for std in &self.standards {
-            for p in &std.required_protocols {
-                // MVP: consider protocol present if agent.config has key protocol:<name>
-                let key = match p {
-                    Protocol::A2A => "protocol:a2a",
-                    Protocol::MCP => "protocol:mcp",
-                    Protocol::ANS => "protocol:ans",
-                    Protocol::HTTP => "protocol:http",
-                    Protocol::WebSocket => "protocol:websocket",
-                    Protocol::Internal => "protocol:internal",
-                };
-                if !agent.config.contains_key(key) {
-                    missing_protocols.push(*p);
-                }
-            }
-
-            for cap_name in &std.required_capabilities {
-                let key = format!("cap:{}", cap_name);
-                if !agent.config.contains_key(&key) {
-                    missing_caps.push(cap_name.clone());
-                }
-            }
+            let report = std.compliance_for(agent);
+            missing_protocols.extend(report.missing_protocols);
+            missing_caps.extend(report.missing_capabilities);
         }
 
         ComplianceReport {
@@ -86,28 +157,90 @@ impl StandardizedAgentTemplate {
                 .map(|s| s.id.clone())
                 .unwrap_or(StandardId("none".into())),
             compliant: missing_protocols.is_empty() && missing_caps.is_empty(),
+            suggested_fixes: suggested_fixes(&missing_protocols, &missing_caps),
             missing_protocols,
             missing_capabilities: missing_caps,
             notes: vec![],
         }
     }
+
+    /// Add `spec` unless a standard with its id is already listed, along with
+    /// the capabilities it requires, so agents from the template comply
+    pub fn merge_standard(&mut self, spec: &StandardSpec) {
+        if self.standards.iter().any(|s| s.id == spec.id) {
+            return;
+        }
+        self.standards.push(spec.clone());
+        for cap in &spec.required_capabilities {
+            if !self.default_capabilities.contains(cap) {
+                self.default_capabilities.push(cap.clone());
+            }
+        }
+    }
+
+    /// One report per standard, paired with that standard's level
+    pub fn compliance_by_standard(&self, agent: &Agent) -> Vec<(ComplianceLevel, ComplianceReport)> {
+        self.standards
+            .iter()
+            .map(|std| (std.level, std.compliance_for(agent)))
+            .collect()
+    }
 }
 
 #[derive(Default, Clone)]
 pub struct StandardsRegistry {
     templates: HashMap<String, StandardizedAgentTemplate>,
+    /// Org-wide minimum merged into every template that doesn't opt out
+    baseline_standards: Vec<StandardSpec>,
 }
 
 impl StandardsRegistry {
-    pub fn new() -> Self { Self { templates: HashMap::new() } }
+    pub fn new() -> Self { Self { templates: HashMap::new(), baseline_standards: Vec::new() } }
 
-    pub fn register_template(&mut self, tmpl: StandardizedAgentTemplate) {
+    pub fn with_baseline_standards(mut self, specs: Vec<StandardSpec>) -> Self {
+        self.set_baseline_standards(specs);
+        self
+    }
+
+    /// Replace the baseline and merge it into the templates already registered;
+    /// standards of the previous baseline stay on those templates
+    pub fn set_baseline_standards(&mut self, specs: Vec<StandardSpec>) {
+        self.baseline_standards = specs;
+        for tmpl in self.templates.values_mut() {
+            merge_baseline(&self.baseline_standards, tmpl);
+        }
+    }
+
+    pub fn baseline_standards(&self) -> &[StandardSpec] {
+        &self.baseline_standards
+    }
+
+    /// Register `tmpl`, adding the baseline standards it lacks unless it sets
+    /// `skip_baseline`
+    pub fn register_template(&mut self, mut tmpl: StandardizedAgentTemplate) {
+        merge_baseline(&self.baseline_standards, &mut tmpl);
         self.templates.insert(tmpl.template_id.clone(), tmpl);
     }
 
     pub fn get_template(&self, id: &str) -> Option<&StandardizedAgentTemplate> {
         self.templates.get(id)
     }
+
+    /// Every registered template, ordered by id
+    pub fn templates(&self) -> Vec<&StandardizedAgentTemplate> {
+        let mut templates: Vec<_> = self.templates.values().collect();
+        templates.sort_by(|a, b| a.template_id.cmp(&b.template_id));
+        templates
+    }
+}
+
+fn merge_baseline(baseline: &[StandardSpec], tmpl: &mut StandardizedAgentTemplate) {
+    if tmpl.skip_baseline {
+        return;
+    }
+    for spec in baseline {
+        tmpl.merge_standard(spec);
+    }
 }
 
 // Convenience helpers: canned standards
@@ -147,9 +280,96 @@ pub fn template_standard_worker() -> StandardizedAgentTemplate {
         standards: vec![standard_mcp_required(), standard_a2a_recommended()],
         default_capabilities: vec!["mcp.tools".into()],
         default_tags: vec!["standard".into(), "worker".into()],
+        skip_baseline: false,
     }
 }
 
+pub fn template_standard_supervisor() -> StandardizedAgentTemplate {
+    StandardizedAgentTemplate {
+        template_id: "tmpl.standard.supervisor".into(),
+        display_name: "Standard Supervisor".into(),
+        description: "Supervisor that delegates to workers over A2A (required) and MCP".into(),
+        default_model: "claude-3-opus".into(),
+        default_provider: "anthropic".into(),
+        standards: vec![
+            standard_mcp_required(),
+            StandardSpec { level: ComplianceLevel::Required, ..standard_a2a_recommended() },
+        ],
+        default_capabilities: vec!["mcp.tools".into(), "a2a.delegate".into()],
+        default_tags: vec!["standard".into(), "supervisor".into()],
+        skip_baseline: false,
+    }
+}
+
+pub fn template_business_analyst() -> StandardizedAgentTemplate {
+    StandardizedAgentTemplate {
+        template_id: "tmpl.business.analyst".into(),
+        display_name: "Business Analyst".into(),
+        description: "Researches markets and scores business opportunities".into(),
+        default_model: "claude-3-opus".into(),
+        default_provider: "anthropic".into(),
+        standards: vec![standard_mcp_required(), standard_a2a_recommended()],
+        default_capabilities: vec!["mcp.tools".into(), "business.analysis".into()],
+        default_tags: vec!["business".into(), "analyst".into()],
+        skip_baseline: false,
+    }
+}
+
+pub fn template_code_generator() -> StandardizedAgentTemplate {
+    StandardizedAgentTemplate {
+        template_id: "tmpl.code.generator".into(),
+        display_name: "Code Generator".into(),
+        description: "Generates and revises source code from specifications".into(),
+        default_model: "claude-3-opus".into(),
+        default_provider: "anthropic".into(),
+        standards: vec![standard_mcp_required(), standard_a2a_recommended()],
+        default_capabilities: vec!["mcp.tools".into(), "code.generate".into()],
+        default_tags: vec!["code".into(), "generator".into()],
+        skip_baseline: false,
+    }
+}
+
+/// Canned standard with id `id` (e.g. `std.mcp.v1`)
+pub fn standard_by_id(id: &str) -> Option<StandardSpec> {
+    [standard_mcp_required(), standard_a2a_recommended()].into_iter().find(|s| s.id.0 == id)
+}
+
+/// Baseline of a `StandardsAgent` unless configured otherwise: MCP is required
+pub fn default_baseline_standards() -> Vec<StandardSpec> {
+    vec![standard_mcp_required()]
+}
+
+/// Baseline from `BASELINE_STANDARDS`, a comma-separated list of canned
+/// standard ids or `none`; unknown ids are logged and skipped, and the
+/// default applies when the variable is unset
+pub fn baseline_standards_from_env() -> Vec<StandardSpec> {
+    let Ok(value) = std::env::var("BASELINE_STANDARDS") else {
+        return default_baseline_standards();
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && !id.eq_ignore_ascii_case("none"))
+        .filter_map(|id| {
+            let spec = standard_by_id(id);
+            if spec.is_none() {
+                tracing::warn!("Ignoring unknown baseline standard: {}", id);
+            }
+            spec
+        })
+        .collect()
+}
+
+/// Templates every `StandardsAgent` starts with
+pub fn bundled_templates() -> Vec<StandardizedAgentTemplate> {
+    vec![
+        template_standard_worker(),
+        template_standard_supervisor(),
+        template_business_analyst(),
+        template_code_generator(),
+    ]
+}
+
 pub struct StandardsAgent {
     pub id: AgentId,
     pub registry: StandardsRegistry,
@@ -157,11 +377,20 @@ pub struct StandardsAgent {
 
 impl StandardsAgent {
     pub fn new() -> Self {
-        let mut registry = StandardsRegistry::new();
-        registry.register_template(template_standard_worker());
+        let mut registry = StandardsRegistry::new().with_baseline_standards(default_baseline_standards());
+        for tmpl in bundled_templates() {
+            registry.register_template(tmpl);
+        }
         Self { id: AgentId::generate(), registry }
     }
 
+    /// Use `specs` as the baseline for templates registered from now on
+    /// (and merge it into the current ones)
+    pub fn with_baseline_standards(mut self, specs: Vec<StandardSpec>) -> Self {
+        self.registry.set_baseline_standards(specs);
+        self
+    }
+
     pub fn register_template(&mut self, tmpl: StandardizedAgentTemplate) {
         self.registry.register_template(tmpl);
     }
@@ -172,3 +401,78 @@ impl StandardsAgent {
 
     pub fn registry(&self) -> &StandardsRegistry { &self.registry }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_templates_are_registered() {
+        let sa = StandardsAgent::new();
+        let ids: Vec<&str> = sa.registry().templates().iter().map(|t| t.template_id.as_str()).collect();
+
+        assert!(ids.len() >= 3);
+        assert_eq!(
+            ids,
+            vec!["tmpl.business.analyst", "tmpl.code.generator", "tmpl.standard.supervisor", "tmpl.standard.worker"]
+        );
+        for tmpl in sa.registry().templates() {
+            assert!(!tmpl.standards.is_empty(), "{} has no standards", tmpl.template_id);
+            assert!(!tmpl.default_tags.is_empty(), "{} has no tags", tmpl.template_id);
+        }
+    }
+
+    #[test]
+    fn test_applying_suggested_fixes_makes_agent_compliant() {
+        let tmpl = template_standard_supervisor();
+        let mut agent = Agent::new("Lead", "Bare supervisor", agentic_core::AgentRole::Supervisor, "claude-3-opus", "anthropic");
+
+        let report = tmpl.compliance_for(&agent);
+        assert!(!report.compliant);
+        let cap_key = format!("{}mcp.tools", CAPABILITY_CONFIG_PREFIX);
+        assert_eq!(
+            report.suggested_fixes,
+            vec![
+                ConfigPatch::new().with("protocol:mcp", "1.0"),
+                ConfigPatch::new().with("protocol:a2a", "1.0"),
+                ConfigPatch::new().with(cap_key, "1.0.0"),
+            ]
+        );
+
+        for fix in &report.suggested_fixes {
+            agent.apply_patch(fix);
+        }
+        let report = tmpl.compliance_for(&agent);
+        assert!(report.compliant, "{:?}", report.reasons());
+        assert!(report.suggested_fixes.is_empty());
+    }
+
+    #[test]
+    fn test_registered_template_gets_baseline_standards() {
+        let bare = StandardizedAgentTemplate {
+            template_id: "tmpl.custom".into(),
+            display_name: "Custom".into(),
+            description: "Registered without standards".into(),
+            default_model: "claude-3-opus".into(),
+            default_provider: "anthropic".into(),
+            standards: vec![],
+            default_capabilities: vec![],
+            default_tags: vec!["custom".into()],
+            skip_baseline: false,
+        };
+        let mut sa = StandardsAgent::new();
+        sa.register_template(bare.clone());
+        sa.register_template(StandardizedAgentTemplate { template_id: "tmpl.exempt".into(), skip_baseline: true, ..bare });
+
+        let custom = sa.registry().get_template("tmpl.custom").unwrap();
+        let ids: Vec<&str> = custom.standards.iter().map(|s| s.id.0.as_str()).collect();
+        assert_eq!(ids, ["std.mcp.v1"]);
+        assert_eq!(custom.default_capabilities, ["mcp.tools"]);
+        assert!(sa.registry().get_template("tmpl.exempt").unwrap().standards.is_empty());
+
+        // A wider baseline reaches templates already registered, without duplicates
+        let sa = sa.with_baseline_standards(vec![standard_mcp_required(), standard_a2a_recommended()]);
+        let ids: Vec<&str> = sa.registry().get_template("tmpl.custom").unwrap().standards.iter().map(|s| s.id.0.as_str()).collect();
+        assert_eq!(ids, ["std.mcp.v1", "std.a2a.v1"]);
+    }
+}